@@ -0,0 +1,253 @@
+//! Item audit trail.
+//!
+//! Item handlers call [`record`] inside the transaction that makes a change,
+//! so an entry exists if and only if the change commits. Each entry stores
+//! who made the change, a one-line summary and a field-level diff of the item
+//! as the API returns it.
+
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value, json};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Fields left out of diffs: identity and bookkeeping that every change touches
+const IGNORED_FIELDS: &[&str] = &["id", "organization_id", "created_at", "updated_at"];
+
+/// What happened to the item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Created,
+    Updated,
+    /// An update that moved the item to another state
+    StateChanged,
+    /// Another item was folded into this one and deleted
+    Merged,
+    Deleted,
+    /// Several older updates compacted by history retention
+    Summarized,
+}
+
+impl AuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditAction::Created => "created",
+            AuditAction::Updated => "updated",
+            AuditAction::StateChanged => "state_changed",
+            AuditAction::Merged => "merged",
+            AuditAction::Deleted => "deleted",
+            AuditAction::Summarized => "summarized",
+        }
+    }
+}
+
+/// Store an audit entry; call inside the transaction making the change
+pub async fn record(
+    conn: &mut PgConnection,
+    organization_id: Uuid,
+    item_id: Uuid,
+    user_id: Uuid,
+    action: AuditAction,
+    changes: Value,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO audit_log (item_id, organization_id, user_id, action, changes, change_details)
+         VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(item_id)
+    .bind(organization_id)
+    .bind(user_id)
+    .bind(action.as_str())
+    .bind(&changes)
+    .bind(summary(action, &changes))
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Store a summary standing in for compacted entries, dated as the first of
+/// them. `user_id` is None when several users made the changes.
+pub async fn record_summary(
+    conn: &mut PgConnection,
+    organization_id: Uuid,
+    item_id: Uuid,
+    user_id: Option<Uuid>,
+    change_date: DateTime<Utc>,
+    changes: Value,
+) -> Result<(), sqlx::Error> {
+    let action = AuditAction::Summarized;
+    sqlx::query(
+        "INSERT INTO audit_log
+             (item_id, organization_id, user_id, action, changes, change_details, change_date)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(item_id)
+    .bind(organization_id)
+    .bind(user_id)
+    .bind(action.as_str())
+    .bind(&changes)
+    .bind(summary(action, &changes))
+    .bind(change_date)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Field-level diff of two JSON objects: `{"field": {"from": old, "to": new}}`
+/// for every field whose value differs. A missing field counts as null, so
+/// diffing against `{}` gives a creation or deletion snapshot.
+pub fn diff(before: &Value, after: &Value) -> Value {
+    let empty = Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+
+    let mut changes = Map::new();
+    for key in before.keys().chain(after.keys()) {
+        if IGNORED_FIELDS.contains(&key.as_str()) || changes.contains_key(key) {
+            continue;
+        }
+        let from = before.get(key).unwrap_or(&Value::Null);
+        let to = after.get(key).unwrap_or(&Value::Null);
+        if from != to {
+            changes.insert(key.clone(), json!({ "from": from, "to": to }));
+        }
+    }
+    Value::Object(changes)
+}
+
+/// Net effect of successive diffs, oldest first: each field goes from its
+/// first `from` to its last `to`, and fields that ended where they started
+/// are left out.
+pub fn merge<'a>(diffs: impl IntoIterator<Item = &'a Value>) -> Value {
+    let mut merged = Map::new();
+    for changes in diffs {
+        let Some(changes) = changes.as_object() else {
+            continue;
+        };
+        for (key, change) in changes {
+            let to = change.get("to").cloned().unwrap_or(Value::Null);
+            match merged.get_mut(key) {
+                Some(existing) => existing["to"] = to,
+                None => {
+                    let from = change.get("from").cloned().unwrap_or(Value::Null);
+                    merged.insert(key.clone(), json!({ "from": from, "to": to }));
+                }
+            }
+        }
+    }
+    merged.retain(|_, change| change["from"] != change["to"]);
+    Value::Object(merged)
+}
+
+/// One-line description stored as the entry's change_details
+fn summary(action: AuditAction, changes: &Value) -> String {
+    let field = |name: &str, end: &str| {
+        changes
+            .get(name)
+            .and_then(|c| c.get(end))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    match action {
+        AuditAction::Created => format!("Created '{}'", field("name", "to")),
+        AuditAction::Deleted => format!("Deleted '{}'", field("name", "from")),
+        AuditAction::StateChanged => format!(
+            "State changed from {} to {}",
+            field("state", "from"),
+            field("state", "to")
+        ),
+        AuditAction::Merged => format!(
+            "Merged '{}' into this item",
+            changes
+                .pointer("/merged_from/to/name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        ),
+        AuditAction::Updated => {
+            let fields: Vec<&str> = changes
+                .as_object()
+                .map(|c| c.keys().map(String::as_str).collect())
+                .unwrap_or_default();
+            format!("Updated {}", fields.join(", "))
+        }
+        AuditAction::Summarized => {
+            let fields: Vec<&str> = changes
+                .as_object()
+                .map(|c| c.keys().map(String::as_str).collect())
+                .unwrap_or_default();
+            if fields.is_empty() {
+                "Summarized changes with no net effect".to_string()
+            } else {
+                format!("Summarized changes to {}", fields.join(", "))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_reports_changed_fields_only() {
+        let before =
+            json!({"id": 1, "name": "Blue", "notes": null, "state": "current", "updated_at": "a"});
+        let after = json!({"id": 1, "name": "Blue", "notes": "Signed", "state": "loaned", "updated_at": "b"});
+
+        assert_eq!(
+            diff(&before, &after),
+            json!({
+                "notes": {"from": null, "to": "Signed"},
+                "state": {"from": "current", "to": "loaned"}
+            })
+        );
+        assert_eq!(diff(&before, &before), json!({}));
+    }
+
+    #[test]
+    fn test_diff_against_empty_is_a_snapshot() {
+        let item = json!({"id": 1, "name": "Blue", "notes": null});
+
+        assert_eq!(
+            diff(&json!({}), &item),
+            json!({"name": {"from": null, "to": "Blue"}})
+        );
+        assert_eq!(
+            diff(&item, &json!({})),
+            json!({"name": {"from": "Blue", "to": null}})
+        );
+    }
+
+    #[test]
+    fn test_merge_keeps_net_change() {
+        let diffs = [
+            json!({"notes": {"from": null, "to": "Signed"}, "state": {"from": "current", "to": "loaned"}}),
+            json!({"state": {"from": "loaned", "to": "current"}}),
+            json!({"notes": {"from": "Signed", "to": "Signed, first pressing"}}),
+        ];
+
+        assert_eq!(
+            merge(&diffs),
+            json!({"notes": {"from": null, "to": "Signed, first pressing"}})
+        );
+        assert_eq!(merge(&[]), json!({}));
+    }
+
+    #[test]
+    fn test_summary() {
+        let changes = json!({"state": {"from": "current", "to": "loaned"}});
+        assert_eq!(
+            summary(AuditAction::StateChanged, &changes),
+            "State changed from current to loaned"
+        );
+
+        let changes = json!({"name": {"from": "Blue", "to": "Court and Spark"}, "notes": {"from": null, "to": "x"}});
+        assert_eq!(
+            summary(AuditAction::Updated, &changes),
+            "Updated name, notes"
+        );
+        assert_eq!(summary(AuditAction::Deleted, &changes), "Deleted 'Blue'");
+    }
+}
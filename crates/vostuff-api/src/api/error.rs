@@ -0,0 +1,233 @@
+//! The error type returned by handlers and middleware.
+//!
+//! Each variant fixes the HTTP status; the body is always an
+//! [`ErrorResponse`] with a machine-readable [`ErrorCode`]. Database errors
+//! convert with `?`; wrap any other unexpected error with
+//! [`ApiError::internal`].
+
+use std::fmt;
+
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::api::models::{ErrorCode, ErrorResponse, FieldError};
+
+#[derive(Debug)]
+pub enum ApiError {
+    /// 400: the request is malformed or breaks a rule, naming the fields at
+    /// fault when it can
+    Validation {
+        code: ErrorCode,
+        message: String,
+        fields: Vec<FieldError>,
+    },
+    /// 401: no valid credentials
+    Unauthorized(ErrorCode, String),
+    /// 403: the caller may not do this
+    Forbidden(ErrorCode, String),
+    /// 404: the resource does not exist, or not in the caller's organization
+    NotFound(ErrorCode, String),
+    /// 409: the change clashes with existing data
+    Conflict(ErrorCode, String),
+    /// 423: the resource is locked against changes
+    Locked(ErrorCode, String),
+    /// 500: something unexpected failed; the message is the underlying error
+    Internal(String),
+    /// 502: a service the request depends on could not be reached
+    Unavailable(ErrorCode, String),
+}
+
+impl ApiError {
+    pub fn validation(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::Validation {
+            code,
+            message: message.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// A `validation.failed` error not tied to particular fields
+    pub fn validation_failed(message: impl Into<String>) -> Self {
+        Self::validation(ErrorCode::ValidationFailed, message)
+    }
+
+    /// A `validation.failed` error for the given fields; the message joins
+    /// the fields' messages
+    pub fn invalid_fields(fields: Vec<FieldError>) -> Self {
+        let message = fields
+            .iter()
+            .map(|f| f.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+        Self::Validation {
+            code: ErrorCode::ValidationFailed,
+            message,
+            fields,
+        }
+    }
+
+    pub fn unauthorized(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::Unauthorized(code, message.into())
+    }
+
+    pub fn forbidden(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::Forbidden(code, message.into())
+    }
+
+    /// An `auth.forbidden` error, for a caller lacking a permission or role
+    pub fn auth_forbidden(message: impl Into<String>) -> Self {
+        Self::forbidden(ErrorCode::AuthForbidden, message)
+    }
+
+    pub fn not_found(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::NotFound(code, message.into())
+    }
+
+    pub fn conflict(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::Conflict(code, message.into())
+    }
+
+    pub fn locked(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::Locked(code, message.into())
+    }
+
+    pub fn unavailable(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::Unavailable(code, message.into())
+    }
+
+    /// Usable as `.map_err(ApiError::internal)`
+    pub fn internal(err: impl fmt::Display) -> Self {
+        Self::Internal(err.to_string())
+    }
+
+    /// The same error with `prefix` before its message, e.g. to say which
+    /// entry of a batch failed
+    pub fn prefixed(mut self, prefix: &str) -> Self {
+        let message = match &mut self {
+            Self::Validation { message, .. }
+            | Self::Unauthorized(_, message)
+            | Self::Forbidden(_, message)
+            | Self::NotFound(_, message)
+            | Self::Conflict(_, message)
+            | Self::Locked(_, message)
+            | Self::Unavailable(_, message)
+            | Self::Internal(message) => message,
+        };
+        message.insert_str(0, prefix);
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::Validation { .. } => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(..) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(..) => StatusCode::FORBIDDEN,
+            Self::NotFound(..) => StatusCode::NOT_FOUND,
+            Self::Conflict(..) => StatusCode::CONFLICT,
+            Self::Locked(..) => StatusCode::LOCKED,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::Unavailable(..) => StatusCode::BAD_GATEWAY,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Validation { code, .. }
+            | Self::Unauthorized(code, _)
+            | Self::Forbidden(code, _)
+            | Self::NotFound(code, _)
+            | Self::Conflict(code, _)
+            | Self::Locked(code, _)
+            | Self::Unavailable(code, _) => *code,
+            Self::Internal(_) => ErrorCode::InternalError,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Validation { message, .. }
+            | Self::Unauthorized(_, message)
+            | Self::Forbidden(_, message)
+            | Self::NotFound(_, message)
+            | Self::Conflict(_, message)
+            | Self::Locked(_, message)
+            | Self::Unavailable(_, message)
+            | Self::Internal(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code().as_str(), self.message())
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        Self::internal(err)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        if let Self::Internal(message) = &self {
+            tracing::error!(error = %message, "request failed");
+        }
+        let body = match self {
+            Self::Validation {
+                code,
+                message,
+                fields,
+            } => ErrorResponse {
+                error: code,
+                message,
+                fields,
+            },
+            Self::Internal(message) => ErrorResponse::new(ErrorCode::InternalError, message),
+            Self::Unauthorized(code, message)
+            | Self::Forbidden(code, message)
+            | Self::NotFound(code, message)
+            | Self::Conflict(code, message)
+            | Self::Locked(code, message)
+            | Self::Unavailable(code, message) => ErrorResponse::new(code, message),
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_fields_joins_messages() {
+        let err = ApiError::invalid_fields(vec![
+            FieldError {
+                field: "speed".to_string(),
+                message: "Field 'speed' must be a number".to_string(),
+            },
+            FieldError {
+                field: "signed".to_string(),
+                message: "Field 'signed' must be a boolean".to_string(),
+            },
+        ]);
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(err.code(), ErrorCode::ValidationFailed);
+        assert_eq!(
+            err.message(),
+            "Field 'speed' must be a number; Field 'signed' must be a boolean"
+        );
+    }
+
+    #[test]
+    fn test_database_errors_are_internal() {
+        let err = ApiError::from(sqlx::Error::RowNotFound);
+        assert_eq!(err.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(err.code(), ErrorCode::InternalError);
+    }
+}
@@ -0,0 +1,277 @@
+//! API keys: long-lived credentials for scripts and importers, sent in the
+//! `X-Api-Key` header instead of logging in with a password.
+//!
+//! A key acts for the member who created it, in one organization, with a
+//! subset of that member's roles and only the permissions of those roles that
+//! the member holds. Members manage their own keys; `members:manage` allows
+//! seeing and deleting everyone's. Keys are managed from a login session, so a
+//! leaked key cannot mint further keys.
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{
+        ApiKey, CreateApiKeyRequest, CreatedApiKey, ErrorCode, ErrorResponse, Permission,
+        UpdateApiKeyRequest,
+    },
+    state::AppState,
+};
+use crate::auth::{ApiKeys, AuthContext};
+
+const API_KEY_COLUMNS: &str = "id, organization_id, user_id, name, key_prefix, roles, expires_at, last_used_at, created_at, updated_at";
+
+/// List API keys in an organization
+///
+/// Members with `members:manage` see every member's keys; others see their own.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/api-keys",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "List of API keys", body = Vec<ApiKey>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "api-keys"
+)]
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<Vec<ApiKey>>, ApiError> {
+    let keys = sqlx::query_as::<_, ApiKey>(&format!(
+        "SELECT {} FROM api_keys
+         WHERE organization_id = $1 AND ($2 OR user_id = $3)
+         ORDER BY created_at",
+        API_KEY_COLUMNS
+    ))
+    .bind(org_id)
+    .bind(auth.can(Permission::MembersManage))
+    .bind(auth.user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(keys))
+}
+
+/// Create an API key for the current user
+///
+/// The response holds the key itself, which is not shown again.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/api-keys",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "API key created", body = CreatedApiKey),
+        (status = 400, description = "Invalid name, roles or expiry", body = ErrorResponse),
+        (status = 403, description = "Requested a role the caller lacks, or called with an API key", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "api-keys"
+)]
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<CreateApiKeyRequest>,
+) -> Result<(StatusCode, Json<CreatedApiKey>), ApiError> {
+    require_session(&auth)?;
+
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::validation_failed(
+            "API key name must not be empty",
+        ));
+    }
+    let roles = req.roles.unwrap_or_else(|| auth.roles.clone());
+    if roles.is_empty() {
+        return Err(ApiError::validation_failed(
+            "An API key needs at least one role",
+        ));
+    }
+    if let Some(role) = roles.iter().find(|role| !auth.roles.contains(role)) {
+        return Err(ApiError::auth_forbidden(format!(
+            "Cannot grant the {} role, which you do not hold",
+            role
+        )));
+    }
+    if req.expires_at.is_some_and(|at| at <= Utc::now()) {
+        return Err(ApiError::validation_failed(
+            "expires_at must be in the future",
+        ));
+    }
+
+    let key = ApiKeys::generate();
+    let api_key = sqlx::query_as::<_, ApiKey>(&format!(
+        "INSERT INTO api_keys (organization_id, user_id, name, key_prefix, key_hash, roles, expires_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)
+         RETURNING {}",
+        API_KEY_COLUMNS
+    ))
+    .bind(org_id)
+    .bind(auth.user_id)
+    .bind(name)
+    .bind(ApiKeys::display_prefix(&key))
+    .bind(ApiKeys::hash(&key))
+    .bind(&roles)
+    .bind(req.expires_at)
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(CreatedApiKey { key, api_key })))
+}
+
+/// Get an API key
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/api-keys/{key_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("key_id" = Uuid, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 200, description = "API key", body = ApiKey),
+        (status = 404, description = "API key not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "api-keys"
+)]
+pub async fn get_api_key(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, key_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ApiKey>, ApiError> {
+    let api_key = sqlx::query_as::<_, ApiKey>(&format!(
+        "SELECT {} FROM api_keys
+         WHERE id = $1 AND organization_id = $2 AND ($3 OR user_id = $4)",
+        API_KEY_COLUMNS
+    ))
+    .bind(key_id)
+    .bind(org_id)
+    .bind(auth.can(Permission::MembersManage))
+    .bind(auth.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(not_found)?;
+
+    Ok(Json(api_key))
+}
+
+/// Rename one of the current user's API keys
+#[utoipa::path(
+    patch,
+    path = "/api/organizations/{org_id}/api-keys/{key_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("key_id" = Uuid, Path, description = "API key ID")
+    ),
+    request_body = UpdateApiKeyRequest,
+    responses(
+        (status = 200, description = "API key updated", body = ApiKey),
+        (status = 400, description = "Invalid name", body = ErrorResponse),
+        (status = 403, description = "Called with an API key", body = ErrorResponse),
+        (status = 404, description = "API key not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "api-keys"
+)]
+pub async fn update_api_key(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, key_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateApiKeyRequest>,
+) -> Result<Json<ApiKey>, ApiError> {
+    require_session(&auth)?;
+
+    let name = req.name.as_deref().map(str::trim);
+    if name.is_some_and(str::is_empty) {
+        return Err(ApiError::validation_failed(
+            "API key name must not be empty",
+        ));
+    }
+
+    let api_key = sqlx::query_as::<_, ApiKey>(&format!(
+        "UPDATE api_keys SET name = COALESCE($4, name), updated_at = NOW()
+         WHERE id = $1 AND organization_id = $2 AND user_id = $3
+         RETURNING {}",
+        API_KEY_COLUMNS
+    ))
+    .bind(key_id)
+    .bind(org_id)
+    .bind(auth.user_id)
+    .bind(name)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(not_found)?;
+
+    Ok(Json(api_key))
+}
+
+/// Delete an API key, revoking it at once
+///
+/// Members may delete their own keys; `members:manage` allows deleting any key.
+#[utoipa::path(
+    delete,
+    path = "/api/organizations/{org_id}/api-keys/{key_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("key_id" = Uuid, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 204, description = "API key deleted"),
+        (status = 403, description = "Called with an API key", body = ErrorResponse),
+        (status = 404, description = "API key not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "api-keys"
+)]
+pub async fn delete_api_key(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, key_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    require_session(&auth)?;
+
+    let result = sqlx::query(
+        "DELETE FROM api_keys
+         WHERE id = $1 AND organization_id = $2 AND ($3 OR user_id = $4)",
+    )
+    .bind(key_id)
+    .bind(org_id)
+    .bind(auth.can(Permission::MembersManage))
+    .bind(auth.user_id)
+    .execute(&state.pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(not_found());
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Keys are only managed from a login session, never with another key
+fn require_session(auth: &AuthContext) -> Result<(), ApiError> {
+    if auth.api_key_id.is_some() {
+        return Err(ApiError::auth_forbidden(
+            "API keys cannot manage API keys; log in instead",
+        ));
+    }
+    Ok(())
+}
+
+// ── Helpers ────────────────────────────────────────────────────────────────
+
+fn not_found() -> ApiError {
+    ApiError::not_found(ErrorCode::ApiKeyNotFound, "API key not found")
+}
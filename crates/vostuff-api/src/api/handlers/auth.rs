@@ -1,19 +1,36 @@
 use axum::{
-    Json,
+    Extension, Json,
     extract::{Request, State},
     http::StatusCode,
 };
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{PgConnection, PgPool};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+use super::exports;
+
+use crate::api::error::ApiError;
 use crate::{
     api::{
+        middleware::read_only,
         models::{
-            ErrorResponse, LoginRequest, LoginResponse, OrgSelectionResponse, Organization,
-            OrganizationWithRoles, SelectOrgRequest, UserInfo,
+            AcceptTermsRequest, ErrorCode, ErrorResponse, LoginRequest, LoginResponse, MeResponse,
+            OidcAuthorization, OidcCallbackRequest, OidcProviderInfo, OrgSelectionResponse,
+            Organization, OrganizationWithRoles, PasswordChangeRequiredResponse, Permission,
+            RefreshRequest, SelectOrgRequest, TermsAcceptanceRequiredResponse, UserInfo, UserRole,
         },
         state::AppState,
     },
-    auth::{AuthContext, PasswordHasher, TokenManager},
+    auth::{
+        AuthContext, FollowOnClaims, PasswordHasher, REFRESH_TOKEN_REUSE_GRACE_SECONDS,
+        REFRESH_TOKEN_TTL_DAYS, TokenManager,
+        backend::{self, LoginAccount, Verdict},
+        login_lockout,
+        oidc::IdentityClaim,
+    },
+    permissions::Permissions,
 };
 
 /// User login endpoint with optional organization selection
@@ -22,8 +39,10 @@ use crate::{
     path = "/api/auth/login",
     request_body = LoginRequest,
     responses(
-        (status = 200, description = "Login successful or org selection required", body = LoginResponse),
-        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 200, description = "Login successful, org selection required, password change required (PasswordChangeRequiredResponse), or terms acceptance required (TermsAcceptanceRequiredResponse)", body = LoginResponse),
+        (status = 400, description = "New password rejected (validation.failed)", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials, also answered while the account is locked after repeated failures", body = ErrorResponse),
+        (status = 403, description = "User deactivated (user_deactivated) or not a member of any or the requested organization", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "auth"
@@ -31,113 +50,246 @@ use crate::{
 pub async fn login(
     State(state): State<AppState>,
     Json(req): Json<LoginRequest>,
-) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, Json<ErrorResponse>)> {
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
     // Always return same error message to prevent user enumeration
-    let invalid_credentials_error = || {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(ErrorResponse {
-                error: "unauthorized".to_string(),
-                message: "Invalid credentials".to_string(),
-            }),
-        )
-    };
+    let invalid_credentials_error =
+        || ApiError::unauthorized(ErrorCode::AuthInvalidCredentials, "Invalid credentials");
 
     // Get user by identity (no roles in users table anymore)
-    let user_row = sqlx::query_as::<_, (uuid::Uuid, String, String, Option<String>)>(
-        "SELECT id, name, identity, password_hash FROM users WHERE identity = $1",
+    type UserRow = (
+        Uuid,
+        String,
+        String,
+        Option<String>,
+        bool,
+        bool,
+        i32,
+        Option<DateTime<Utc>>,
+    );
+    let user_row = sqlx::query_as::<_, UserRow>(
+        "SELECT id, name, identity, password_hash, must_change_password, is_active,
+                failed_login_attempts, locked_until
+         FROM users WHERE identity = $1",
     )
     .bind(&req.identity)
     .fetch_optional(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
-    let (user_id, user_name, user_identity, password_hash_opt) = match user_row {
+    let (
+        user_id,
+        user_name,
+        user_identity,
+        password_hash,
+        must_change_password,
+        is_active,
+        failed_login_attempts,
+        locked_until,
+    ) = match user_row {
         Some(user) => user,
         None => return Err(invalid_credentials_error()),
     };
 
-    // Check if user has password authentication enabled
-    let password_hash = match password_hash_opt {
-        Some(hash) => hash,
-        None => return Err(invalid_credentials_error()),
+    // A locked account refuses even the right password, with the same answer
+    // as a wrong one, and attempts while locked are not counted
+    if locked_until.is_some_and(|until| until > Utc::now()) {
+        return Err(invalid_credentials_error());
+    }
+
+    // The password stored here, or the deployment's directory; a user neither
+    // holds a password for (such as an OIDC user) cannot log in with one
+    let account = LoginAccount {
+        identity: &user_identity,
+        password_hash: password_hash.as_deref(),
     };
+    match backend::verify(&state.auth_backends, &account, &req.password)
+        .await
+        .map_err(ApiError::internal)?
+    {
+        Verdict::Accepted => {}
+        Verdict::Rejected => {
+            record_failed_login(&state.pool, user_id).await?;
+            return Err(invalid_credentials_error());
+        }
+        Verdict::NotApplicable => return Err(invalid_credentials_error()),
+    }
 
-    // Verify password
-    let is_valid =
-        PasswordHasher::verify_password(&req.password, &password_hash).map_err(internal_error)?;
+    if failed_login_attempts > 0 {
+        sqlx::query(
+            "UPDATE users SET failed_login_attempts = 0, locked_until = NULL WHERE id = $1",
+        )
+        .bind(user_id)
+        .execute(&state.pool)
+        .await?;
+    }
 
-    if !is_valid {
-        return Err(invalid_credentials_error());
+    // Only reveal deactivation to someone who knows the password
+    if !is_active {
+        return Err(user_deactivated());
     }
 
-    // Get user's organizations with roles
-    let org_rows = sqlx::query_as::<_, (Uuid, String, Option<String>, Vec<String>)>(
-        "SELECT o.id, o.name, o.description, uo.roles
-         FROM organizations o
-         INNER JOIN user_organizations uo ON o.id = uo.organization_id
-         WHERE uo.user_id = $1
-         ORDER BY o.name",
+    // A temporary password from an admin reset only lets the user choose a new one
+    if must_change_password {
+        let Some(new_password) = req.new_password.as_deref() else {
+            let response = PasswordChangeRequiredResponse {
+                password_change_required: true,
+                message: "Password was reset; choose a new password to continue".to_string(),
+            };
+            return Ok((
+                StatusCode::OK,
+                Json(serde_json::to_value(response).unwrap()),
+            ));
+        };
+        if new_password.trim().is_empty() || new_password == req.password {
+            return Err(ApiError::validation_failed(
+                "New password must be non-empty and differ from the temporary password".to_string(),
+            ));
+        }
+
+        let new_hash = PasswordHasher::hash_password(new_password).map_err(ApiError::internal)?;
+        sqlx::query(
+            "UPDATE users SET password_hash = $2, must_change_password = FALSE, updated_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(user_id)
+        .bind(&new_hash)
+        .execute(&state.pool)
+        .await?;
+    }
+
+    complete_login(
+        &state,
+        user_id,
+        user_name,
+        user_identity,
+        req.organization_id,
     )
-    .bind(user_id)
-    .fetch_all(&state.pool)
     .await
-    .map_err(internal_error)?;
-
-    if org_rows.is_empty() {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(ErrorResponse {
-                error: "no_organization".to_string(),
-                message: "User is not a member of any organization".to_string(),
-            }),
+}
+
+/// Count a failed password login, locking the account for a while once there
+/// have been too many in a row (see `login_lockout`)
+async fn record_failed_login(pool: &PgPool, user_id: Uuid) -> Result<(), sqlx::Error> {
+    let attempts: i32 = sqlx::query_scalar(
+        "UPDATE users SET failed_login_attempts = failed_login_attempts + 1
+         WHERE id = $1
+         RETURNING failed_login_attempts",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+
+    if let Some(lock) = login_lockout(attempts) {
+        tracing::warn!(%user_id, attempts, "Locking account after repeated failed logins");
+        sqlx::query(
+            "UPDATE users SET locked_until = NOW() + $2 * INTERVAL '1 second' WHERE id = $1",
+        )
+        .bind(user_id)
+        .bind(lock.num_seconds())
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Finish logging in an authenticated user: open a session in the requested
+/// organization, or the only one they belong to, or else have them choose.
+/// Users who have not accepted the current terms of service are asked to first.
+async fn complete_login(
+    state: &AppState,
+    user_id: Uuid,
+    user_name: String,
+    user_identity: String,
+    organization_id: Option<Uuid>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    // Get user's organizations with roles
+    let organizations = user_organizations(&state.pool, user_id).await?;
+
+    if organizations.is_empty() {
+        return Err(ApiError::forbidden(
+            ErrorCode::AuthNoOrganization,
+            "User is not a member of any organization",
         ));
     }
 
     let token_manager = TokenManager::new(&state.jwt_secret);
 
+    if let Some(terms) = &state.terms {
+        let accepted: Option<String> =
+            sqlx::query_scalar("SELECT terms_accepted_version FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_one(&state.pool)
+                .await?;
+        if !accepts_current_terms(state, accepted.as_deref()) {
+            let follow_on_token = token_manager
+                .generate_follow_on_token(user_id, user_identity)
+                .map_err(ApiError::internal)?;
+            let response = TermsAcceptanceRequiredResponse {
+                terms_acceptance_required: true,
+                version: terms.version.clone(),
+                url: terms.url.clone(),
+                follow_on_token,
+            };
+            return Ok((
+                StatusCode::OK,
+                Json(serde_json::to_value(response).unwrap()),
+            ));
+        }
+    }
+
     // If organization_id provided, use it
-    if let Some(org_id) = req.organization_id {
+    if let Some(org_id) = organization_id {
         // Find the requested organization
-        let org_data = org_rows
+        let org = organizations
             .iter()
-            .find(|(id, _, _, _)| *id == org_id)
+            .find(|org| org.id == org_id)
             .ok_or_else(|| {
-                (
-                    StatusCode::FORBIDDEN,
-                    Json(ErrorResponse {
-                        error: "invalid_organization".to_string(),
-                        message: "User is not a member of the specified organization".to_string(),
-                    }),
+                ApiError::forbidden(
+                    ErrorCode::AuthNotMember,
+                    "User is not a member of the specified organization",
                 )
             })?;
 
-        let (org_id, org_name, org_desc, roles) = org_data;
-
-        // Generate JWT token with selected org
-        let token = token_manager
-            .generate_token(user_id, user_identity.clone(), *org_id, roles.clone(), 24)
-            .map_err(internal_error)?;
+        // Generate JWT tokens with selected org
+        let mut conn = state.pool.acquire().await?;
+        let session = issue_session(
+            &mut conn,
+            &token_manager,
+            state.access_token_ttl_minutes,
+            user_id,
+            &user_identity,
+            org.id,
+            org.roles.clone(),
+            org.permissions.clone().into(),
+            Uuid::new_v4(),
+        )
+        .await
+        .map_err(ApiError::internal)?;
 
         // Get full organization details
         let organization = Organization {
-            id: *org_id,
-            name: org_name.clone(),
-            description: org_desc.clone(),
+            id: org.id,
+            name: org.name.clone(),
+            description: org.description.clone(),
             created_at: chrono::Utc::now(), // These will be properly loaded in real scenario
             updated_at: chrono::Utc::now(),
         };
+        let roles = org.roles.clone();
+        let permissions = org.permissions.clone();
 
         let response = LoginResponse {
-            token,
-            expires_in: 24 * 60 * 60,
+            token: session.token,
+            expires_in: session.expires_in,
+            refresh_token: session.refresh_token,
+            refresh_expires_in: REFRESH_TOKEN_TTL_DAYS * 24 * 60 * 60,
             user: UserInfo {
                 id: user_id,
                 name: user_name,
                 identity: user_identity,
                 organization,
-                roles: roles.clone(),
+                roles,
+                permissions,
             },
+            organizations,
         };
 
         return Ok((
@@ -147,32 +299,49 @@ pub async fn login(
     }
 
     // No org_id provided - check how many orgs user belongs to
-    if org_rows.len() == 1 {
+    if organizations.len() == 1 {
         // Auto-select the only organization
-        let (org_id, org_name, org_desc, roles) = &org_rows[0];
+        let org = &organizations[0];
 
-        let token = token_manager
-            .generate_token(user_id, user_identity.clone(), *org_id, roles.clone(), 24)
-            .map_err(internal_error)?;
+        let mut conn = state.pool.acquire().await?;
+        let session = issue_session(
+            &mut conn,
+            &token_manager,
+            state.access_token_ttl_minutes,
+            user_id,
+            &user_identity,
+            org.id,
+            org.roles.clone(),
+            org.permissions.clone().into(),
+            Uuid::new_v4(),
+        )
+        .await
+        .map_err(ApiError::internal)?;
 
         let organization = Organization {
-            id: *org_id,
-            name: org_name.clone(),
-            description: org_desc.clone(),
+            id: org.id,
+            name: org.name.clone(),
+            description: org.description.clone(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
+        let roles = org.roles.clone();
+        let permissions = org.permissions.clone();
 
         let response = LoginResponse {
-            token,
-            expires_in: 24 * 60 * 60,
+            token: session.token,
+            expires_in: session.expires_in,
+            refresh_token: session.refresh_token,
+            refresh_expires_in: REFRESH_TOKEN_TTL_DAYS * 24 * 60 * 60,
             user: UserInfo {
                 id: user_id,
                 name: user_name,
                 identity: user_identity,
                 organization,
-                roles: roles.clone(),
+                roles,
+                permissions,
             },
+            organizations,
         };
 
         return Ok((
@@ -182,19 +351,9 @@ pub async fn login(
     }
 
     // Multiple organizations - return org selection response
-    let organizations: Vec<OrganizationWithRoles> = org_rows
-        .into_iter()
-        .map(|(id, name, description, roles)| OrganizationWithRoles {
-            id,
-            name,
-            description,
-            roles,
-        })
-        .collect();
-
     let follow_on_token = token_manager
         .generate_follow_on_token(user_id, user_identity)
-        .map_err(internal_error)?;
+        .map_err(ApiError::internal)?;
 
     let response = OrgSelectionResponse {
         organizations,
@@ -207,6 +366,237 @@ pub async fn login(
     ))
 }
 
+/// All organizations a user belongs to, with their roles in each
+async fn user_organizations(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<OrganizationWithRoles>, sqlx::Error> {
+    type Row = (Uuid, String, Option<String>, Vec<UserRole>, Vec<Permission>);
+    let rows = sqlx::query_as::<_, Row>(
+        "SELECT o.id, o.name, o.description, uo.roles, uo.permissions
+         FROM organizations o
+         INNER JOIN user_organizations uo ON o.id = uo.organization_id
+         WHERE uo.user_id = $1
+         ORDER BY o.name",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(id, name, description, roles, permissions)| OrganizationWithRoles {
+                id,
+                name,
+                description,
+                roles,
+                permissions,
+            },
+        )
+        .collect())
+}
+
+/// The external identity provider offered for login, if one is configured
+#[utoipa::path(
+    get,
+    path = "/api/auth/oidc",
+    responses(
+        (status = 200, description = "Identity provider available", body = OidcProviderInfo),
+        (status = 404, description = "OIDC login is not configured", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn oidc_provider(
+    State(state): State<AppState>,
+) -> Result<Json<OidcProviderInfo>, ApiError> {
+    let provider = state.oidc.as_ref().ok_or_else(oidc_not_configured)?;
+
+    Ok(Json(OidcProviderInfo {
+        name: provider.display_name().to_string(),
+    }))
+}
+
+/// Start an OIDC login: where to send the user to sign in at the provider
+///
+/// The provider redirects back to the configured redirect URL with `code` and
+/// `state`, which are then passed to the callback endpoint.
+#[utoipa::path(
+    get,
+    path = "/api/auth/oidc/authorize",
+    responses(
+        (status = 200, description = "Provider authorization URL", body = OidcAuthorization),
+        (status = 404, description = "OIDC login is not configured", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn oidc_authorize(
+    State(state): State<AppState>,
+) -> Result<Json<OidcAuthorization>, ApiError> {
+    let provider = state.oidc.as_ref().ok_or_else(oidc_not_configured)?;
+
+    let token_manager = TokenManager::new(&state.jwt_secret);
+    let (oidc_state, nonce) = token_manager
+        .generate_oidc_state()
+        .map_err(ApiError::internal)?;
+    let authorization_url = provider
+        .authorization_url(&oidc_state, &nonce)
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(OidcAuthorization {
+        authorization_url,
+        state: oidc_state,
+    }))
+}
+
+/// Complete an OIDC login with the code and state from the provider's redirect
+///
+/// The identity from the provider must match an existing user's identity; no
+/// accounts are created. Responds as password login does.
+#[utoipa::path(
+    post,
+    path = "/api/auth/oidc/callback",
+    request_body = OidcCallbackRequest,
+    responses(
+        (status = 200, description = "Login successful or org selection required", body = LoginResponse),
+        (status = 401, description = "Provider login failed or no matching user (auth.oidc_failed)", body = ErrorResponse),
+        (status = 403, description = "User deactivated (user_deactivated) or not a member of any or the requested organization", body = ErrorResponse),
+        (status = 404, description = "OIDC login is not configured", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn oidc_callback(
+    State(state): State<AppState>,
+    Json(req): Json<OidcCallbackRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let provider = state.oidc.as_ref().ok_or_else(oidc_not_configured)?;
+
+    let token_manager = TokenManager::new(&state.jwt_secret);
+    let claims = token_manager
+        .validate_oidc_state(&req.state)
+        .map_err(|_| oidc_failed("Login attempt expired or not recognised; please try again"))?;
+
+    let identity = match provider.authenticate(&req.code, &claims.nonce).await {
+        Ok(identity) => identity,
+        Err(err) => {
+            tracing::warn!("OIDC login failed: {:#}", err);
+            return Err(oidc_failed("Sign-in with the identity provider failed"));
+        }
+    };
+
+    // Emails are matched ignoring case, preferring an exact match
+    let query = match provider.identity_claim() {
+        IdentityClaim::Subject => {
+            "SELECT id, name, identity, is_active FROM users WHERE identity = $1"
+        }
+        IdentityClaim::Email => {
+            "SELECT id, name, identity, is_active FROM users
+             WHERE LOWER(identity) = LOWER($1)
+             ORDER BY identity = $1 DESC
+             LIMIT 1"
+        }
+    };
+    let user_row = sqlx::query_as::<_, (Uuid, String, String, bool)>(query)
+        .bind(&identity)
+        .fetch_optional(&state.pool)
+        .await?;
+
+    let Some((user_id, user_name, user_identity, is_active)) = user_row else {
+        return Err(oidc_failed(
+            "No user matches this identity provider account",
+        ));
+    };
+    if !is_active {
+        return Err(user_deactivated());
+    }
+
+    complete_login(
+        &state,
+        user_id,
+        user_name,
+        user_identity,
+        req.organization_id,
+    )
+    .await
+}
+
+/// Accept the instance's terms of service and continue the login that asked for them
+///
+/// Each accepted version is recorded. Responds as password login does.
+#[utoipa::path(
+    post,
+    path = "/api/auth/accept-terms",
+    request_body = AcceptTermsRequest,
+    responses(
+        (status = 200, description = "Login successful or org selection required", body = LoginResponse),
+        (status = 401, description = "Invalid or expired token (invalid_follow_on_token), or token already used (follow_on_token_reused)", body = ErrorResponse),
+        (status = 403, description = "User deactivated (user_deactivated) or not a member of any or the requested organization", body = ErrorResponse),
+        (status = 409, description = "Not the current version of the terms (terms_version_mismatch); log in again to see it", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn accept_terms(
+    State(state): State<AppState>,
+    Json(req): Json<AcceptTermsRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let token_manager = TokenManager::new(&state.jwt_secret);
+    let claims = token_manager
+        .validate_follow_on_token(&req.follow_on_token)
+        .map_err(|_| invalid_follow_on_token())?;
+
+    if state.terms.as_ref().map(|terms| terms.version.as_str()) != Some(req.version.as_str()) {
+        return Err(ApiError::conflict(
+            ErrorCode::AuthTermsVersionMismatch,
+            "These are not the current terms of service; please log in again".to_string(),
+        ));
+    }
+
+    let (user_name, is_active) =
+        sqlx::query_as::<_, (String, bool)>("SELECT name, is_active FROM users WHERE id = $1")
+            .bind(claims.sub)
+            .fetch_optional(&state.pool)
+            .await?
+            .ok_or_else(token_user_not_found)?;
+    if !is_active {
+        return Err(user_deactivated());
+    }
+
+    if !consume_follow_on_token(&state.pool, &claims).await? {
+        return Err(ApiError::unauthorized(
+            ErrorCode::AuthFollowOnTokenReused,
+            "This follow-on token has already been used; please log in again".to_string(),
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    sqlx::query(
+        "INSERT INTO terms_acceptances (user_id, version) VALUES ($1, $2)
+         ON CONFLICT (user_id, version) DO NOTHING",
+    )
+    .bind(claims.sub)
+    .bind(&req.version)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("UPDATE users SET terms_accepted_version = $2, updated_at = NOW() WHERE id = $1")
+        .bind(claims.sub)
+        .bind(&req.version)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    complete_login(
+        &state,
+        claims.sub,
+        user_name,
+        claims.identity,
+        req.organization_id,
+    )
+    .await
+}
+
 /// Select organization endpoint for multi-org users
 #[utoipa::path(
     post,
@@ -214,8 +604,8 @@ pub async fn login(
     request_body = SelectOrgRequest,
     responses(
         (status = 200, description = "Organization selected", body = LoginResponse),
-        (status = 401, description = "Invalid or expired token", body = ErrorResponse),
-        (status = 403, description = "Not a member of organization", body = ErrorResponse),
+        (status = 401, description = "Invalid or expired token (invalid_token), or token already used (follow_on_token_reused)", body = ErrorResponse),
+        (status = 403, description = "Not a member of organization, or user deactivated (user_deactivated)", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "auth"
@@ -223,43 +613,49 @@ pub async fn login(
 pub async fn select_org(
     State(state): State<AppState>,
     Json(req): Json<SelectOrgRequest>,
-) -> Result<Json<LoginResponse>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<LoginResponse>, ApiError> {
     let token_manager = TokenManager::new(&state.jwt_secret);
 
     // Validate follow-on token
     let claims = token_manager
         .validate_follow_on_token(&req.follow_on_token)
-        .map_err(|_| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(ErrorResponse {
-                    error: "invalid_token".to_string(),
-                    message: "Invalid or expired follow-on token".to_string(),
-                }),
-            )
-        })?;
+        .map_err(|_| invalid_follow_on_token())?;
 
     // Get user info
-    let user_row = sqlx::query_as::<_, (String,)>("SELECT name FROM users WHERE id = $1")
-        .bind(claims.sub)
-        .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?
-        .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(ErrorResponse {
-                    error: "user_not_found".to_string(),
-                    message: "User not found".to_string(),
-                }),
-            )
-        })?;
+    let user_row = sqlx::query_as::<_, (String, bool, Option<String>)>(
+        "SELECT name, is_active, terms_accepted_version FROM users WHERE id = $1",
+    )
+    .bind(claims.sub)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(token_user_not_found)?;
 
-    let user_name = user_row.0;
+    let (user_name, is_active, terms_accepted_version) = user_row;
+    if !is_active {
+        return Err(user_deactivated());
+    }
+    // The token may have been issued to accept the terms rather than pick an organization
+    if !accepts_current_terms(&state, terms_accepted_version.as_deref()) {
+        return Err(terms_not_accepted());
+    }
+
+    // Follow-on tokens are single-use: record this one, rejecting a replay
+    if !consume_follow_on_token(&state.pool, &claims).await? {
+        tracing::warn!(
+            "Rejected reused follow-on token {} for user {}",
+            claims.jti,
+            claims.sub
+        );
+        return Err(ApiError::unauthorized(
+            ErrorCode::AuthFollowOnTokenReused,
+            "This follow-on token has already been used; please log in again".to_string(),
+        ));
+    }
 
-    // Verify user is member of selected org and get roles
-    let org_data = sqlx::query_as::<_, (String, Option<String>, Vec<String>)>(
-        "SELECT o.name, o.description, uo.roles
+    // Verify user is member of selected org and get roles and permissions
+    type OrgRow = (String, Option<String>, Vec<UserRole>, Vec<Permission>);
+    let org_data = sqlx::query_as::<_, OrgRow>(
+        "SELECT o.name, o.description, uo.roles, uo.permissions
          FROM organizations o
          INNER JOIN user_organizations uo ON o.id = uo.organization_id
          WHERE uo.user_id = $1 AND o.id = $2",
@@ -267,30 +663,33 @@ pub async fn select_org(
     .bind(claims.sub)
     .bind(req.organization_id)
     .fetch_optional(&state.pool)
-    .await
-    .map_err(internal_error)?
+    .await?
     .ok_or_else(|| {
-        (
-            StatusCode::FORBIDDEN,
-            Json(ErrorResponse {
-                error: "not_member".to_string(),
-                message: "User is not a member of the specified organization".to_string(),
-            }),
+        ApiError::forbidden(
+            ErrorCode::AuthNotMember,
+            "User is not a member of the specified organization",
         )
     })?;
 
-    let (org_name, org_desc, roles) = org_data;
+    let (org_name, org_desc, roles, permissions) = org_data;
 
-    // Generate final JWT token
-    let token = token_manager
-        .generate_token(
-            claims.sub,
-            claims.identity.clone(),
-            req.organization_id,
-            roles.clone(),
-            24,
-        )
-        .map_err(internal_error)?;
+    let organizations = user_organizations(&state.pool, claims.sub).await?;
+
+    // Generate final JWT tokens
+    let mut conn = state.pool.acquire().await?;
+    let session = issue_session(
+        &mut conn,
+        &token_manager,
+        state.access_token_ttl_minutes,
+        claims.sub,
+        &claims.identity,
+        req.organization_id,
+        roles.clone(),
+        permissions.clone().into(),
+        Uuid::new_v4(),
+    )
+    .await
+    .map_err(ApiError::internal)?;
 
     let organization = Organization {
         id: req.organization_id,
@@ -301,26 +700,235 @@ pub async fn select_org(
     };
 
     let response = LoginResponse {
-        token,
-        expires_in: 24 * 60 * 60,
+        token: session.token,
+        expires_in: session.expires_in,
+        refresh_token: session.refresh_token,
+        refresh_expires_in: REFRESH_TOKEN_TTL_DAYS * 24 * 60 * 60,
         user: UserInfo {
             id: claims.sub,
             name: user_name,
             identity: claims.identity,
             organization,
             roles,
+            permissions,
         },
+        organizations,
     };
 
     Ok(Json(response))
 }
 
+/// Exchange a refresh token for a new access and refresh token pair
+///
+/// Refresh tokens are single use: the presented token is revoked and a
+/// replacement returned. Presenting a token that was already exchanged revokes
+/// every token from the same login, so a stolen copy ends the session for both
+/// holders; only concurrent refreshes within a few seconds of each other are
+/// let through. Roles and permissions are read afresh, so changes to them apply
+/// from the next refresh.
+#[utoipa::path(
+    post,
+    path = "/api/auth/refresh",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, description = "New token pair", body = LoginResponse),
+        (status = 401, description = "Invalid, expired or revoked token (invalid_refresh_token), or token already used (refresh_token_reused)", body = ErrorResponse),
+        (status = 403, description = "No longer a member of the organization, or user deactivated (user_deactivated)", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn refresh(
+    State(state): State<AppState>,
+    Json(req): Json<RefreshRequest>,
+) -> Result<Json<LoginResponse>, ApiError> {
+    let token_manager = TokenManager::new(&state.jwt_secret);
+    let claims = token_manager
+        .validate_refresh_token(&req.refresh_token)
+        .map_err(|_| invalid_refresh_token())?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let (family_id, revoked_at, replaced) =
+        sqlx::query_as::<_, (Uuid, Option<DateTime<Utc>>, bool)>(
+            "SELECT family_id, revoked_at, replaced_by IS NOT NULL
+             FROM refresh_tokens WHERE jti = $1
+             FOR UPDATE",
+        )
+        .bind(claims.jti)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(invalid_refresh_token)?;
+
+    if let Some(revoked_at) = revoked_at {
+        if !replaced {
+            return Err(invalid_refresh_token());
+        }
+        // Parallel requests from one client can each present the token it held;
+        // allow that briefly, unless the session has been revoked meanwhile
+        let family_live: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM refresh_tokens WHERE family_id = $1 AND revoked_at IS NULL)",
+        )
+        .bind(family_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        let in_grace =
+            Utc::now() - revoked_at < Duration::seconds(REFRESH_TOKEN_REUSE_GRACE_SECONDS);
+        if !(family_live && in_grace) {
+            // An exchanged token coming back later means it was copied: end the session
+            revoke_refresh_family(&mut tx, family_id).await?;
+            tx.commit().await?;
+            tracing::warn!(
+                "Rejected reused refresh token {} for user {}; revoked its session",
+                claims.jti,
+                claims.sub
+            );
+            return Err(ApiError::unauthorized(
+                ErrorCode::AuthRefreshTokenReused,
+                "This refresh token has already been used; please log in again".to_string(),
+            ));
+        }
+    }
+
+    let (user_name, identity, is_active, terms_accepted_version) =
+        sqlx::query_as::<_, (String, String, bool, Option<String>)>(
+            "SELECT name, identity, is_active, terms_accepted_version FROM users WHERE id = $1",
+        )
+        .bind(claims.sub)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(invalid_refresh_token)?;
+    if !is_active {
+        return Err(user_deactivated());
+    }
+    // New terms end existing sessions at their next refresh
+    if !accepts_current_terms(&state, terms_accepted_version.as_deref()) {
+        return Err(terms_not_accepted());
+    }
+
+    type OrgRow = (String, Option<String>, Vec<UserRole>, Vec<Permission>);
+    let (org_name, org_desc, roles, permissions) = sqlx::query_as::<_, OrgRow>(
+        "SELECT o.name, o.description, uo.roles, uo.permissions
+         FROM organizations o
+         INNER JOIN user_organizations uo ON o.id = uo.organization_id
+         WHERE uo.user_id = $1 AND o.id = $2",
+    )
+    .bind(claims.sub)
+    .bind(claims.organization_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| {
+        ApiError::forbidden(
+            ErrorCode::AuthNotMember,
+            "User is no longer a member of this organization",
+        )
+    })?;
+
+    let session = issue_session(
+        &mut tx,
+        &token_manager,
+        state.access_token_ttl_minutes,
+        claims.sub,
+        &identity,
+        claims.organization_id,
+        roles.clone(),
+        permissions.clone().into(),
+        family_id,
+    )
+    .await
+    .map_err(ApiError::internal)?;
+    sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = COALESCE(revoked_at, NOW()), replaced_by = $2
+         WHERE jti = $1",
+    )
+    .bind(claims.jti)
+    .bind(session.refresh_jti)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let organizations = user_organizations(&state.pool, claims.sub).await?;
+
+    Ok(Json(LoginResponse {
+        token: session.token,
+        expires_in: session.expires_in,
+        refresh_token: session.refresh_token,
+        refresh_expires_in: REFRESH_TOKEN_TTL_DAYS * 24 * 60 * 60,
+        user: UserInfo {
+            id: claims.sub,
+            name: user_name,
+            identity,
+            organization: Organization {
+                id: claims.organization_id,
+                name: org_name,
+                description: org_desc,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            },
+            roles,
+            permissions,
+        },
+        organizations,
+    }))
+}
+
+/// End the current session
+///
+/// The presented access token is rejected from now on, and the refresh tokens
+/// of its login are revoked. Other sessions of the same user are unaffected.
+#[utoipa::path(
+    post,
+    path = "/api/auth/logout",
+    responses(
+        (status = 204, description = "Logged out"),
+        (status = 400, description = "Called with an API key, which has no session", body = ErrorResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<StatusCode, ApiError> {
+    // An API key has no session; it is revoked by deleting it
+    if auth.api_key_id.is_some() {
+        return Err(ApiError::validation_failed(
+            "API keys cannot log out; delete the key to revoke it",
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    // Expired records are purged here; their tokens fail validation anyway
+    sqlx::query("DELETE FROM revoked_access_tokens WHERE expires_at < NOW()")
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        "INSERT INTO revoked_access_tokens (jti, user_id, expires_at)
+         VALUES ($1, $2, to_timestamp($3))
+         ON CONFLICT (jti) DO NOTHING",
+    )
+    .bind(auth.token_id)
+    .bind(auth.user_id)
+    .bind(auth.token_expires_at as f64)
+    .execute(&mut *tx)
+    .await?;
+    revoke_refresh_family(&mut tx, auth.session_id).await?;
+
+    tx.commit().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 /// Get current authenticated user information
 #[utoipa::path(
     get,
     path = "/api/auth/me",
     responses(
-        (status = 200, description = "User information retrieved", body = UserInfo),
+        (status = 200, description = "User information and organization settings", body = MeResponse),
         (status = 401, description = "Not authenticated", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -332,30 +940,21 @@ pub async fn select_org(
 pub async fn get_me(
     State(state): State<AppState>,
     request: Request,
-) -> Result<Json<UserInfo>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<MeResponse>, ApiError> {
     // Extract auth context from request extensions (set by auth middleware)
     let auth_context = request
         .extensions()
         .get::<AuthContext>()
         .cloned()
         .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(ErrorResponse {
-                    error: "unauthorized".to_string(),
-                    message: "Authentication required".to_string(),
-                }),
-            )
+            ApiError::unauthorized(ErrorCode::AuthRequired, "Authentication required")
         })?;
 
     // Check if authenticated
     if !auth_context.is_authenticated() {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(ErrorResponse {
-                error: "unauthorized".to_string(),
-                message: "Authentication required".to_string(),
-            }),
+        return Err(ApiError::unauthorized(
+            ErrorCode::AuthRequired,
+            "Authentication required",
         ));
     }
 
@@ -364,17 +963,8 @@ pub async fn get_me(
         sqlx::query_as::<_, (String, String)>("SELECT name, identity FROM users WHERE id = $1")
             .bind(auth_context.user_id)
             .fetch_optional(&state.pool)
-            .await
-            .map_err(internal_error)?
-            .ok_or_else(|| {
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(ErrorResponse {
-                        error: "user_not_found".to_string(),
-                        message: "User not found".to_string(),
-                    }),
-                )
-            })?;
+            .await?
+            .ok_or_else(|| ApiError::not_found(ErrorCode::UserNotFound, "User not found"))?;
 
     let (user_name, user_identity) = user_row;
 
@@ -384,16 +974,9 @@ pub async fn get_me(
     )
     .bind(auth_context.organization_id)
     .fetch_optional(&state.pool)
-    .await
-    .map_err(internal_error)?
+    .await?
     .ok_or_else(|| {
-        (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "organization_not_found".to_string(),
-                message: "Organization not found".to_string(),
-            }),
-        )
+        ApiError::not_found(ErrorCode::OrganizationNotFound, "Organization not found")
     })?;
 
     let (org_name, org_desc) = org_row;
@@ -412,17 +995,262 @@ pub async fn get_me(
         identity: user_identity,
         organization,
         roles: auth_context.roles.clone(),
+        permissions: auth_context.permissions.clone().into_vec(),
     };
 
-    Ok(Json(user_info))
+    let settings =
+        super::settings::load_current_settings(&state.pool, auth_context.organization_id).await?;
+
+    let logo_url = super::settings::logo_url(&state, auth_context.organization_id)
+        .await
+        .map_err(ApiError::internal)?;
+
+    let broadcasts =
+        super::broadcasts::active_broadcasts(&state.pool, auth_context.organization_id).await?;
+
+    let read_only = read_only(&state.pool, auth_context.organization_id)
+        .await?
+        .is_some();
+
+    Ok(Json(MeResponse {
+        user: user_info,
+        settings,
+        logo_url,
+        broadcasts,
+        read_only,
+    }))
+}
+
+/// Result of a personal data deletion request
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeletionRequestResponse {
+    /// Audit entries no longer attributed to the user
+    pub anonymized_audit_entries: u64,
+}
+
+/// Export all personal data held about the current user
+///
+/// Covers the user's profile, organization memberships and roles, and the
+/// audit entries attributed to them, across every organization.
+#[utoipa::path(
+    get,
+    path = "/api/auth/me/data-export",
+    responses(
+        (status = 200, description = "Personal data report", body = serde_json::Value),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn export_my_data(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let mut conn = state.pool.acquire().await?;
+
+    exports::personal_data(&mut conn, auth.user_id)
+        .await?
+        .map(Json)
+        .ok_or_else(user_not_found)
+}
+
+/// Ask for the current user's history to be anonymized
+///
+/// Detaches the user from every audit entry and logged use they are
+/// attributed to. The entries themselves are kept for the organizations'
+/// records; the account and memberships are untouched.
+#[utoipa::path(
+    post,
+    path = "/api/auth/me/deletion-request",
+    responses(
+        (status = 200, description = "History anonymized", body = DeletionRequestResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn request_data_deletion(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<DeletionRequestResponse>, ApiError> {
+    let mut tx = state.pool.begin().await?;
+    let result = sqlx::query("UPDATE audit_log SET user_id = NULL WHERE user_id = $1")
+        .bind(auth.user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE usage_log SET user_id = NULL WHERE user_id = $1")
+        .bind(auth.user_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(Json(DeletionRequestResponse {
+        anonymized_audit_entries: result.rows_affected(),
+    }))
 }
 
-fn internal_error<E: std::fmt::Display>(err: E) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(ErrorResponse {
-            error: "internal_error".to_string(),
-            message: err.to_string(),
-        }),
+/// Access and refresh tokens for a new or renewed session
+struct SessionTokens {
+    token: String,
+    /// Seconds until the access token expires
+    expires_in: i64,
+    refresh_token: String,
+    refresh_jti: Uuid,
+}
+
+/// Issue an access token and a tracked refresh token for a session.
+///
+/// `family_id` groups the refresh tokens descending from one login and is the
+/// access token's session id; pass a new id when logging in and the current
+/// one when refreshing. Expired refresh
+/// tokens are purged here; they fail validation anyway.
+#[allow(clippy::too_many_arguments)]
+async fn issue_session(
+    conn: &mut PgConnection,
+    token_manager: &TokenManager,
+    access_token_ttl_minutes: i64,
+    user_id: Uuid,
+    identity: &str,
+    organization_id: Uuid,
+    roles: Vec<UserRole>,
+    permissions: Permissions,
+    family_id: Uuid,
+) -> anyhow::Result<SessionTokens> {
+    let token = token_manager.generate_token(
+        user_id,
+        identity.to_string(),
+        organization_id,
+        roles,
+        permissions,
+        family_id,
+        access_token_ttl_minutes,
+    )?;
+    let (refresh_token, refresh_claims) =
+        token_manager.generate_refresh_token(user_id, organization_id)?;
+
+    sqlx::query("DELETE FROM refresh_tokens WHERE expires_at < NOW()")
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query(
+        "INSERT INTO refresh_tokens (jti, family_id, user_id, organization_id, expires_at)
+         VALUES ($1, $2, $3, $4, to_timestamp($5))",
+    )
+    .bind(refresh_claims.jti)
+    .bind(family_id)
+    .bind(user_id)
+    .bind(organization_id)
+    .bind(refresh_claims.exp as f64)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(SessionTokens {
+        token,
+        expires_in: access_token_ttl_minutes * 60,
+        refresh_token,
+        refresh_jti: refresh_claims.jti,
+    })
+}
+
+/// Revoke every live refresh token from one login
+async fn revoke_refresh_family(
+    conn: &mut PgConnection,
+    family_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = NOW()
+         WHERE family_id = $1 AND revoked_at IS NULL",
+    )
+    .bind(family_id)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Record a follow-on token as used. Returns false if it was already used.
+///
+/// Expired records are purged here; their tokens fail validation anyway.
+async fn consume_follow_on_token(
+    pool: &PgPool,
+    claims: &FollowOnClaims,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query("DELETE FROM consumed_follow_on_tokens WHERE expires_at < NOW()")
+        .execute(pool)
+        .await?;
+
+    let result = sqlx::query(
+        "INSERT INTO consumed_follow_on_tokens (jti, user_id, expires_at)
+         VALUES ($1, $2, to_timestamp($3))
+         ON CONFLICT (jti) DO NOTHING",
+    )
+    .bind(claims.jti)
+    .bind(claims.sub)
+    .bind(claims.exp as f64)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// Whether a user who accepted the given version of the terms of service may
+/// log in; always true when the instance has no terms
+fn accepts_current_terms(state: &AppState, accepted_version: Option<&str>) -> bool {
+    state
+        .terms
+        .as_ref()
+        .is_none_or(|terms| accepted_version == Some(terms.version.as_str()))
+}
+
+fn terms_not_accepted() -> ApiError {
+    ApiError::forbidden(
+        ErrorCode::AuthTermsNotAccepted,
+        "The terms of service have changed; log in again to accept them",
+    )
+}
+
+fn invalid_follow_on_token() -> ApiError {
+    ApiError::unauthorized(
+        ErrorCode::AuthInvalidFollowOnToken,
+        "Invalid or expired follow-on token",
+    )
+}
+
+/// The user a follow-on token was issued to no longer exists
+fn token_user_not_found() -> ApiError {
+    ApiError::unauthorized(ErrorCode::UserNotFound, "User not found")
+}
+
+fn user_deactivated() -> ApiError {
+    ApiError::forbidden(
+        ErrorCode::AuthUserDeactivated,
+        "This user has been deactivated",
     )
 }
+
+fn oidc_not_configured() -> ApiError {
+    ApiError::not_found(
+        ErrorCode::AuthOidcNotConfigured,
+        "OIDC login is not configured",
+    )
+}
+
+fn oidc_failed(message: &str) -> ApiError {
+    ApiError::unauthorized(ErrorCode::AuthOidcFailed, message)
+}
+
+fn invalid_refresh_token() -> ApiError {
+    ApiError::unauthorized(
+        ErrorCode::AuthInvalidRefreshToken,
+        "Invalid, expired or revoked refresh token; please log in again",
+    )
+}
+
+fn user_not_found() -> ApiError {
+    ApiError::not_found(ErrorCode::UserNotFound, "User not found")
+}
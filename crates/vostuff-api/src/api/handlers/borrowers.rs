@@ -0,0 +1,338 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::{PgExecutor, PgPool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{ErrorCode, ErrorResponse},
+    state::AppState,
+};
+
+// ── Public types ────────────────────────────────────────────────────────────
+
+/// A borrower with loan counts
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct BorrowerSummary {
+    pub id: Uuid,
+    pub name: String,
+    /// Items currently on loan to this borrower
+    pub outstanding_count: i64,
+    /// All loans ever made to this borrower
+    pub loan_count: i64,
+}
+
+/// One loan of an item to a borrower
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct BorrowerLoan {
+    pub loan_id: Uuid,
+    pub item_id: Uuid,
+    pub item_name: String,
+    pub kind_name: String,
+    pub date_loaned: NaiveDate,
+    pub date_due_back: Option<NaiveDate>,
+    /// None while the item is still out
+    pub date_returned: Option<NaiveDate>,
+}
+
+/// Everything a borrower has had
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BorrowerHistory {
+    pub id: Uuid,
+    pub name: String,
+    /// All loans, newest first
+    pub loans: Vec<BorrowerLoan>,
+    /// Loans not yet returned, oldest first
+    pub outstanding: Vec<BorrowerLoan>,
+    /// Mean days between loan and return, over returned loans
+    pub average_return_days: Option<f64>,
+}
+
+/// One loan of an item, past or current
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct ItemLoan {
+    pub loan_id: Uuid,
+    pub borrower_id: Uuid,
+    pub borrower_name: String,
+    pub date_loaned: NaiveDate,
+    pub date_due_back: Option<NaiveDate>,
+    /// None while the item is still out
+    pub date_returned: Option<NaiveDate>,
+}
+
+// ── Handlers ────────────────────────────────────────────────────────────────
+
+/// List borrowers in an organization
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/borrowers",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "List of borrowers", body = Vec<BorrowerSummary>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "borrowers"
+)]
+pub async fn list_borrowers(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<Vec<BorrowerSummary>>, ApiError> {
+    let borrowers = sqlx::query_as::<_, BorrowerSummary>(
+        "SELECT b.id, b.name,
+                COUNT(l.id) FILTER (WHERE l.date_returned IS NULL) AS outstanding_count,
+                COUNT(l.id) AS loan_count
+         FROM borrowers b
+         LEFT JOIN item_loans l ON l.borrower_id = b.id
+         WHERE b.organization_id = $1
+         GROUP BY b.id, b.name
+         ORDER BY b.name",
+    )
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(borrowers))
+}
+
+/// Loan history for a borrower
+///
+/// Lists every loan to the borrower, the items still outstanding, and the
+/// average number of days taken to return an item.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/borrowers/{borrower_id}/history",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("borrower_id" = Uuid, Path, description = "Borrower ID")
+    ),
+    responses(
+        (status = 200, description = "Borrower loan history", body = BorrowerHistory),
+        (status = 404, description = "Borrower not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "borrowers"
+)]
+pub async fn get_borrower_history(
+    State(state): State<AppState>,
+    Path((org_id, borrower_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<BorrowerHistory>, ApiError> {
+    let name: String =
+        sqlx::query_scalar("SELECT name FROM borrowers WHERE id = $1 AND organization_id = $2")
+            .bind(borrower_id)
+            .bind(org_id)
+            .fetch_optional(&state.pool)
+            .await?
+            .ok_or_else(not_found)?;
+
+    let loans = sqlx::query_as::<_, BorrowerLoan>(
+        "SELECT l.id AS loan_id, l.item_id, i.name AS item_name, k.name AS kind_name,
+                l.date_loaned, l.date_due_back, l.date_returned
+         FROM item_loans l
+         JOIN items i ON i.id = l.item_id
+         JOIN kinds k ON k.id = i.kind_id
+         WHERE l.borrower_id = $1
+         ORDER BY l.date_loaned DESC, l.created_at DESC",
+    )
+    .bind(borrower_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let average_return_days: Option<f64> = sqlx::query_scalar(
+        "SELECT AVG(date_returned - date_loaned)::float8
+         FROM item_loans
+         WHERE borrower_id = $1 AND date_returned IS NOT NULL",
+    )
+    .bind(borrower_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let outstanding = loans
+        .iter()
+        .rev()
+        .filter(|l| l.date_returned.is_none())
+        .cloned()
+        .collect();
+
+    Ok(Json(BorrowerHistory {
+        id: borrower_id,
+        name,
+        loans,
+        outstanding,
+        average_return_days,
+    }))
+}
+
+/// Loan history for an item
+///
+/// Lists every loan of the item, newest first; the current loan, if any, has
+/// no return date.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/items/{item_id}/loans",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("item_id" = Uuid, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 200, description = "Item loan history", body = Vec<ItemLoan>),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "borrowers"
+)]
+pub async fn list_item_loans(
+    State(state): State<AppState>,
+    Path((org_id, item_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<ItemLoan>>, ApiError> {
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM items WHERE id = $1 AND organization_id = $2)",
+    )
+    .bind(item_id)
+    .bind(org_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if !exists {
+        return Err(ApiError::not_found(
+            ErrorCode::ItemNotFound,
+            "Item not found",
+        ));
+    }
+
+    let loans = sqlx::query_as::<_, ItemLoan>(
+        "SELECT l.id AS loan_id, l.borrower_id, b.name AS borrower_name,
+                l.date_loaned, l.date_due_back, l.date_returned
+         FROM item_loans l
+         JOIN borrowers b ON b.id = l.borrower_id
+         WHERE l.item_id = $1 AND l.organization_id = $2
+         ORDER BY l.date_loaned DESC, l.created_at DESC",
+    )
+    .bind(item_id)
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(loans))
+}
+
+// ── Loan history bookkeeping ────────────────────────────────────────────────
+
+/// Record the item's current loan (from item_loan_details) in the loan
+/// history, creating the borrower on first use. An already open loan for the
+/// item is updated in place.
+pub(crate) async fn record_loan<'e>(
+    executor: impl PgExecutor<'e>,
+    org_id: Uuid,
+    item_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "WITH d AS (
+             SELECT item_id, date_loaned, date_due_back, TRIM(loaned_to) AS loaned_to
+             FROM item_loan_details
+             WHERE item_id = $2 AND TRIM(loaned_to) <> ''
+         ), b AS (
+             INSERT INTO borrowers (organization_id, name)
+             SELECT $1, loaned_to FROM d
+             ON CONFLICT (organization_id, (LOWER(name))) DO UPDATE SET name = borrowers.name
+             RETURNING id
+         )
+         INSERT INTO item_loans (organization_id, item_id, borrower_id, date_loaned, date_due_back)
+         SELECT $1, d.item_id, b.id, d.date_loaned, d.date_due_back FROM d, b
+         ON CONFLICT (item_id) WHERE date_returned IS NULL DO UPDATE SET
+           borrower_id   = EXCLUDED.borrower_id,
+           date_loaned   = EXCLUDED.date_loaned,
+           date_due_back = EXCLUDED.date_due_back",
+    )
+    .bind(org_id)
+    .bind(item_id)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Close the item's open loan, if any, as returned today
+pub(crate) async fn close_loan<'e>(
+    executor: impl PgExecutor<'e>,
+    item_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE item_loans SET date_returned = CURRENT_DATE
+         WHERE item_id = $1 AND date_returned IS NULL",
+    )
+    .bind(item_id)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Check a loan against the organization's loan limits, returning a
+/// description of the first limit it breaks
+pub(crate) async fn loan_limit_violation(
+    pool: &PgPool,
+    org_id: Uuid,
+    item_id: Uuid,
+    borrower: Option<&str>,
+    date_loaned: NaiveDate,
+    date_due_back: Option<NaiveDate>,
+) -> Result<Option<String>, sqlx::Error> {
+    let settings = super::settings::load_settings(pool, org_id).await?;
+
+    if let Some(max_days) = settings.max_loan_days {
+        match date_due_back {
+            None => {
+                return Ok(Some(format!(
+                    "Loans need a due back date, at most {} days out",
+                    max_days
+                )));
+            }
+            Some(due) if (due - date_loaned).num_days() > i64::from(max_days) => {
+                return Ok(Some(format!("Loans may last at most {} days", max_days)));
+            }
+            Some(_) => {}
+        }
+    }
+
+    if let Some(max_loans) = settings.max_loans_per_borrower
+        && let Some(name) = borrower
+    {
+        // Other items this borrower still has; re-saving this loan is not a new one
+        let outstanding: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*)
+             FROM item_loans l
+             JOIN borrowers b ON b.id = l.borrower_id
+             WHERE b.organization_id = $1 AND LOWER(b.name) = LOWER(TRIM($2))
+               AND l.date_returned IS NULL AND l.item_id <> $3",
+        )
+        .bind(org_id)
+        .bind(name)
+        .bind(item_id)
+        .fetch_one(pool)
+        .await?;
+
+        if outstanding >= i64::from(max_loans) {
+            return Ok(Some(format!(
+                "{} already has {} items on loan (limit {})",
+                name.trim(),
+                outstanding,
+                max_loans
+            )));
+        }
+    }
+
+    Ok(None)
+}
+
+// ── Helpers ────────────────────────────────────────────────────────────────
+
+fn not_found() -> ApiError {
+    ApiError::not_found(ErrorCode::BorrowerNotFound, "Borrower not found")
+}
@@ -0,0 +1,205 @@
+//! Broadcasts: notices from system administrators, such as scheduled
+//! downtime, shown as a banner to the members of every organization or of
+//! chosen ones until they expire.
+//!
+//! Members receive the current notices with `GET /auth/me`. A broadcast can
+//! also be emailed: publishing it queues an `email.send` background job per
+//! recipient, so a relay failure only retries the messages that failed.
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    mail::{self, Email},
+    models::{
+        Announcement, Broadcast, BroadcastNotice, CreateBroadcastRequest, ErrorCode, ErrorResponse,
+    },
+    state::AppState,
+};
+use crate::auth::{AuthContext, SYSTEM_ORG_ID};
+
+const BROADCAST_COLUMNS: &str =
+    "id, message, organization_ids, expires_at, email, created_by, created_at";
+
+/// List all broadcasts, including expired ones
+#[utoipa::path(
+    get,
+    path = "/api/admin/broadcasts",
+    responses(
+        (status = 200, description = "Broadcasts, newest first", body = Vec<Broadcast>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-broadcasts"
+)]
+pub async fn list_broadcasts(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Broadcast>>, ApiError> {
+    let broadcasts = sqlx::query_as::<_, Broadcast>(&format!(
+        "SELECT {} FROM broadcasts ORDER BY created_at DESC",
+        BROADCAST_COLUMNS
+    ))
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(broadcasts))
+}
+
+/// Publish a notice to every organization, or to those listed
+///
+/// With `email` set, active members of the targeted organizations are also
+/// emailed the message.
+#[utoipa::path(
+    post,
+    path = "/api/admin/broadcasts",
+    request_body = CreateBroadcastRequest,
+    responses(
+        (status = 201, description = "Broadcast published", body = Broadcast),
+        (status = 400, description = "Empty or overlong message, expiry in the past, or unknown organization", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-broadcasts"
+)]
+pub async fn create_broadcast(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Json(req): Json<CreateBroadcastRequest>,
+) -> Result<(StatusCode, Json<Broadcast>), ApiError> {
+    let message = req.message.trim();
+    let len = message.chars().count();
+    if len == 0 || len > Announcement::MAX_LEN {
+        return Err(ApiError::validation_failed(format!(
+            "message must be 1 to {} characters",
+            Announcement::MAX_LEN
+        )));
+    }
+    if req.expires_at <= Utc::now() {
+        return Err(ApiError::validation_failed("expires_at is in the past"));
+    }
+
+    let mut organization_ids = req.organization_ids;
+    if let Some(ids) = &mut organization_ids {
+        ids.sort();
+        ids.dedup();
+        if ids.is_empty() {
+            return Err(ApiError::validation_failed(
+                "organization_ids may not be empty; omit it to reach every organization",
+            ));
+        }
+        let known: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM organizations WHERE id = ANY($1) AND id != $2",
+        )
+        .bind(&*ids)
+        .bind(SYSTEM_ORG_ID)
+        .fetch_one(&state.pool)
+        .await?;
+        if known != ids.len() as i64 {
+            return Err(ApiError::validation_failed(
+                "organization_ids includes an unknown organization",
+            ));
+        }
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let broadcast = sqlx::query_as::<_, Broadcast>(&format!(
+        "INSERT INTO broadcasts (message, organization_ids, expires_at, email, created_by)
+         VALUES ($1, $2, $3, $4, $5)
+         RETURNING {}",
+        BROADCAST_COLUMNS
+    ))
+    .bind(message)
+    .bind(&organization_ids)
+    .bind(req.expires_at)
+    .bind(req.email)
+    .bind(auth.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if broadcast.email {
+        // Each address once, however many targeted organizations it belongs to;
+        // identities from an identity provider need not be addresses
+        let recipients: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT u.identity
+             FROM users u JOIN user_organizations uo ON uo.user_id = u.id
+             WHERE u.is_active AND u.identity LIKE '%@%'
+               AND uo.organization_id != $2
+               AND ($1::uuid[] IS NULL OR uo.organization_id = ANY($1))
+             ORDER BY u.identity",
+        )
+        .bind(&organization_ids)
+        .bind(SYSTEM_ORG_ID)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let body = format!(
+            "{}\n\n\
+             This notice from the VOStuff administrators is also shown in the app until {}.\n",
+            broadcast.message,
+            broadcast.expires_at.format("%Y-%m-%d %H:%M UTC"),
+        );
+        for to in recipients {
+            let email = Email {
+                to,
+                subject: "Notice from VOStuff".to_string(),
+                body: body.clone(),
+            };
+            mail::enqueue_email(&mut tx, None, &email).await?;
+        }
+    }
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(broadcast)))
+}
+
+/// Withdraw a broadcast; its banner disappears at members' next page load
+#[utoipa::path(
+    delete,
+    path = "/api/admin/broadcasts/{broadcast_id}",
+    params(
+        ("broadcast_id" = Uuid, Path, description = "Broadcast ID")
+    ),
+    responses(
+        (status = 204, description = "Broadcast deleted"),
+        (status = 404, description = "Broadcast not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-broadcasts"
+)]
+pub async fn delete_broadcast(
+    State(state): State<AppState>,
+    Path(broadcast_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let result = sqlx::query("DELETE FROM broadcasts WHERE id = $1")
+        .bind(broadcast_id)
+        .execute(&state.pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found(
+            ErrorCode::BroadcastNotFound,
+            "Broadcast not found",
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Unexpired broadcasts reaching an organization, oldest first
+pub(crate) async fn active_broadcasts(
+    pool: &PgPool,
+    org_id: Uuid,
+) -> Result<Vec<BroadcastNotice>, sqlx::Error> {
+    sqlx::query_as::<_, BroadcastNotice>(
+        "SELECT id, message, expires_at FROM broadcasts
+         WHERE expires_at > NOW() AND (organization_ids IS NULL OR $1 = ANY(organization_ids))
+         ORDER BY created_at",
+    )
+    .bind(org_id)
+    .fetch_all(pool)
+    .await
+}
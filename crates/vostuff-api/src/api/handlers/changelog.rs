@@ -0,0 +1,88 @@
+//! The API changelog: what changed in each release, for scripts checking
+//! for new or deprecated endpoints and for the web app's "What's new"
+//! dialog.
+//!
+//! Releases live in `changelog.toml` at the crate root, newest first, and
+//! are compiled into the binary, so the list always matches the running
+//! server. Add an entry there with each release that changes the API.
+
+use std::sync::OnceLock;
+
+use axum::{Json, extract::Query};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::api::models::ChangelogRelease;
+
+const CHANGELOG: &str = include_str!("../../../changelog.toml");
+
+#[derive(Deserialize)]
+struct ChangelogFile {
+    releases: Vec<ChangelogRelease>,
+}
+
+/// Every release, newest first
+fn releases() -> &'static [ChangelogRelease] {
+    static RELEASES: OnceLock<Vec<ChangelogRelease>> = OnceLock::new();
+    RELEASES.get_or_init(|| {
+        toml::from_str::<ChangelogFile>(CHANGELOG)
+            .expect("changelog.toml is valid")
+            .releases
+    })
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ChangelogParams {
+    /// Only releases after this version; an unknown version returns them all
+    pub since: Option<String>,
+}
+
+/// List API changes by release, newest first
+#[utoipa::path(
+    get,
+    path = "/api/changelog",
+    params(ChangelogParams),
+    responses(
+        (status = 200, description = "Releases, newest first", body = Vec<ChangelogRelease>)
+    ),
+    tag = "changelog"
+)]
+pub async fn get_changelog(Query(params): Query<ChangelogParams>) -> Json<Vec<ChangelogRelease>> {
+    Json(releases_since(releases(), params.since.as_deref()).to_vec())
+}
+
+fn releases_since<'a>(
+    releases: &'a [ChangelogRelease],
+    since: Option<&str>,
+) -> &'a [ChangelogRelease] {
+    let newer = since.and_then(|version| releases.iter().position(|r| r.version == version));
+    &releases[..newer.unwrap_or(releases.len())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_changelog_is_newest_first_with_unique_versions() {
+        let releases = releases();
+        assert!(!releases.is_empty());
+        assert!(releases.windows(2).all(|w| w[0].date >= w[1].date));
+        let versions: HashSet<_> = releases.iter().map(|r| &r.version).collect();
+        assert_eq!(versions.len(), releases.len());
+        assert!(releases.iter().all(|r| !r.changes.is_empty()));
+    }
+
+    #[test]
+    fn test_releases_since() {
+        let releases = releases();
+        let latest = &releases[0].version;
+        assert!(releases_since(releases, Some(latest)).is_empty());
+        assert_eq!(releases_since(releases, None).len(), releases.len());
+        assert_eq!(
+            releases_since(releases, Some("0.0.0")).len(),
+            releases.len()
+        );
+    }
+}
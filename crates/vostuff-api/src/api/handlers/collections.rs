@@ -1,17 +1,29 @@
 use axum::{
     Extension, Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
 };
 use uuid::Uuid;
 
+use super::items;
+use crate::api::error::ApiError;
+use crate::api::validation::ValidJson;
 use crate::api::{
-    models::{Collection, CreateCollectionRequest, ErrorResponse},
+    models::{
+        AddCollectionItemRequest, Collection, CollectionDetails, CreateCollectionRequest,
+        ErrorCode, ErrorResponse, Item, ItemFilterParams, PaginatedResponse, PaginationParams,
+        Permission, UpdateCollectionRequest,
+    },
+    outbox,
     state::AppState,
 };
 use crate::auth::AuthContext;
 
+const COLLECTION_COLUMNS: &str = "id, organization_id, name, description, notes, cover_item_id, sort_order, created_at, updated_at";
+
 /// List all collections for an organization
+///
+/// Ordered by `sort_order`, then name.
 #[utoipa::path(
     get,
     path = "/api/organizations/{org_id}/collections",
@@ -27,19 +39,52 @@ use crate::auth::AuthContext;
 pub async fn list_collections(
     State(state): State<AppState>,
     Path(org_id): Path<Uuid>,
-) -> Result<Json<Vec<Collection>>, (StatusCode, Json<ErrorResponse>)> {
-    let collections = sqlx::query_as::<_, Collection>(
-        "SELECT id, organization_id, name, description, notes, created_at, updated_at
-         FROM collections WHERE organization_id = $1 ORDER BY name",
-    )
+) -> Result<Json<Vec<Collection>>, ApiError> {
+    let collections = sqlx::query_as::<_, Collection>(&format!(
+        "SELECT {} FROM collections WHERE organization_id = $1 ORDER BY sort_order, name",
+        COLLECTION_COLUMNS
+    ))
     .bind(org_id)
     .fetch_all(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     Ok(Json(collections))
 }
 
+/// Get a collection and how many items are in it
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/collections/{collection_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("collection_id" = Uuid, Path, description = "Collection ID")
+    ),
+    responses(
+        (status = 200, description = "Collection details", body = CollectionDetails),
+        (status = 404, description = "Collection not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "collections"
+)]
+pub async fn get_collection(
+    State(state): State<AppState>,
+    Path((org_id, collection_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<CollectionDetails>, ApiError> {
+    let collection = sqlx::query_as::<_, CollectionDetails>(&format!(
+        "SELECT {}, (SELECT COUNT(*) FROM item_collections ic WHERE ic.collection_id = c.id)
+                    AS item_count
+         FROM collections c WHERE id = $1 AND organization_id = $2",
+        COLLECTION_COLUMNS
+    ))
+    .bind(collection_id)
+    .bind(org_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| ApiError::not_found(ErrorCode::CollectionNotFound, "Collection not found"))?;
+
+    Ok(Json(collection))
+}
+
 /// Create a new collection
 #[utoipa::path(
     post,
@@ -59,29 +104,128 @@ pub async fn create_collection(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
     Path(org_id): Path<Uuid>,
-    Json(req): Json<CreateCollectionRequest>,
-) -> Result<(StatusCode, Json<Collection>), (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden(
-            "Administrator access required to manage collections",
+    ValidJson(req): ValidJson<CreateCollectionRequest>,
+) -> Result<(StatusCode, Json<Collection>), ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage collections",
         ));
     }
-    let collection = sqlx::query_as::<_, Collection>(
-        "INSERT INTO collections (organization_id, name, description, notes)
-         VALUES ($1, $2, $3, $4)
-         RETURNING id, organization_id, name, description, notes, created_at, updated_at",
-    )
+    let mut tx = state.pool.begin().await?;
+    let collection = sqlx::query_as::<_, Collection>(&format!(
+        "INSERT INTO collections (organization_id, name, description, notes, sort_order)
+         VALUES ($1, $2, $3, $4, COALESCE($5, 0))
+         RETURNING {}",
+        COLLECTION_COLUMNS
+    ))
     .bind(org_id)
     .bind(&req.name)
     .bind(&req.description)
     .bind(&req.notes)
-    .fetch_one(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .bind(req.sort_order)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    outbox::enqueue(
+        &mut tx,
+        org_id,
+        outbox::COLLECTION_CREATED,
+        serde_json::to_value(&collection).unwrap_or_default(),
+    )
+    .await?;
+    tx.commit().await?;
 
     Ok((StatusCode::CREATED, Json(collection)))
 }
 
+/// Update a collection's name, description, notes, cover or place in the list
+///
+/// Items refer to their collections by id, so item lists, exports and saved
+/// searches filtering by `collection_id` follow a rename. The cover must be
+/// one of the collection's items. Sends a `collection.updated` event so open
+/// clients can refresh.
+#[utoipa::path(
+    patch,
+    path = "/api/organizations/{org_id}/collections/{collection_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("collection_id" = Uuid, Path, description = "Collection ID")
+    ),
+    request_body = UpdateCollectionRequest,
+    responses(
+        (status = 200, description = "Collection updated successfully", body = Collection),
+        (status = 400, description = "Invalid input, or a cover not in the collection", body = ErrorResponse),
+        (status = 404, description = "Collection not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "collections"
+)]
+pub async fn update_collection(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, collection_id)): Path<(Uuid, Uuid)>,
+    ValidJson(req): ValidJson<UpdateCollectionRequest>,
+) -> Result<Json<Collection>, ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage collections",
+        ));
+    }
+    let name = req.name.as_deref().map(str::trim);
+
+    let mut tx = state.pool.begin().await?;
+    let collection = sqlx::query_as::<_, Collection>(&format!(
+        "UPDATE collections
+         SET name = COALESCE($3, name),
+             description = COALESCE($4, description),
+             notes = COALESCE($5, notes),
+             cover_item_id = CASE WHEN $6::uuid IS NULL THEN cover_item_id
+                                  ELSE NULLIF($6, '00000000-0000-0000-0000-000000000000') END,
+             sort_order = COALESCE($7, sort_order),
+             updated_at = NOW()
+         WHERE id = $1 AND organization_id = $2
+         RETURNING {}",
+        COLLECTION_COLUMNS
+    ))
+    .bind(collection_id)
+    .bind(org_id)
+    .bind(name)
+    .bind(&req.description)
+    .bind(&req.notes)
+    .bind(req.cover_item_id)
+    .bind(req.sort_order)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ApiError::not_found(ErrorCode::CollectionNotFound, "Collection not found"))?;
+
+    if let Some(cover_item_id) = req.cover_item_id.filter(|id| !id.is_nil()) {
+        let in_collection: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM item_collections WHERE collection_id = $1 AND item_id = $2)",
+        )
+        .bind(collection_id)
+        .bind(cover_item_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if !in_collection {
+            return Err(ApiError::validation(
+                ErrorCode::CollectionCoverNotInCollection,
+                "The cover must be an item in the collection",
+            ));
+        }
+    }
+
+    outbox::enqueue(
+        &mut tx,
+        org_id,
+        outbox::COLLECTION_UPDATED,
+        serde_json::to_value(&collection).unwrap_or_default(),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(collection))
+}
+
 /// Delete a collection
 #[utoipa::path(
     delete,
@@ -101,48 +245,227 @@ pub async fn delete_collection(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
     Path((org_id, collection_id)): Path<(Uuid, Uuid)>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden(
-            "Administrator access required to manage collections",
+) -> Result<StatusCode, ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage collections",
         ));
     }
+    let mut tx = state.pool.begin().await?;
     let result = sqlx::query("DELETE FROM collections WHERE id = $1 AND organization_id = $2")
         .bind(collection_id)
         .bind(org_id)
-        .execute(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .execute(&mut *tx)
+        .await?;
 
     if result.rows_affected() == 0 {
-        Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "Collection not found".to_string(),
-            }),
-        ))
-    } else {
-        Ok(StatusCode::NO_CONTENT)
+        return Err(ApiError::not_found(
+            ErrorCode::CollectionNotFound,
+            "Collection not found",
+        ));
     }
+
+    outbox::enqueue(
+        &mut tx,
+        org_id,
+        outbox::COLLECTION_DELETED,
+        serde_json::json!({ "id": collection_id }),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
-fn internal_error<E: std::fmt::Display>(err: E) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(ErrorResponse {
-            error: "internal_error".to_string(),
-            message: err.to_string(),
-        }),
+/// List the items in a collection
+///
+/// Same as listing items with `collection_id={collection_id}`.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/collections/{collection_id}/items",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("collection_id" = Uuid, Path, description = "Collection ID"),
+        PaginationParams
+    ),
+    responses(
+        (status = 200, description = "Items in the collection", body = PaginatedResponse<Item>),
+        (status = 404, description = "Collection not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "collections"
+)]
+pub async fn list_collection_items(
+    State(state): State<AppState>,
+    Path((org_id, collection_id)): Path<(Uuid, Uuid)>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Json<PaginatedResponse<serde_json::Value>>, ApiError> {
+    ensure_collection_exists(&state, org_id, collection_id).await?;
+
+    let filters = ItemFilterParams {
+        page: pagination.page,
+        per_page: pagination.per_page,
+        collection_id: Some(collection_id.to_string()),
+        ..Default::default()
+    };
+    items::list_items(State(state), Path(org_id), Query(filters)).await
+}
+
+/// Add an item to a collection
+///
+/// Adding an item that is already in the collection is not an error.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/collections/{collection_id}/items",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("collection_id" = Uuid, Path, description = "Collection ID")
+    ),
+    request_body = AddCollectionItemRequest,
+    responses(
+        (status = 204, description = "Item is in the collection"),
+        (status = 404, description = "Collection or item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "collections"
+)]
+pub async fn add_collection_item(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, collection_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<AddCollectionItemRequest>,
+) -> Result<StatusCode, ApiError> {
+    if !auth.can(Permission::ItemsWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The items:write permission is required to file items in collections",
+        ));
+    }
+    ensure_collection_exists(&state, org_id, collection_id).await?;
+
+    let item_exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM items WHERE id = $1 AND organization_id = $2)",
+    )
+    .bind(req.item_id)
+    .bind(org_id)
+    .fetch_one(&state.pool)
+    .await?;
+    if !item_exists {
+        return Err(ApiError::not_found(
+            ErrorCode::ItemNotFound,
+            "Item not found",
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let result = sqlx::query(
+        "INSERT INTO item_collections (item_id, collection_id) VALUES ($1, $2)
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(req.item_id)
+    .bind(collection_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Nothing changed when the item was already in the collection
+    if result.rows_affected() > 0 {
+        outbox::enqueue(
+            &mut tx,
+            org_id,
+            outbox::COLLECTION_ITEM_ADDED,
+            serde_json::json!({ "collection_id": collection_id, "item_id": req.item_id }),
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove an item from a collection
+///
+/// The collection loses its cover if it was this item.
+#[utoipa::path(
+    delete,
+    path = "/api/organizations/{org_id}/collections/{collection_id}/items/{item_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("collection_id" = Uuid, Path, description = "Collection ID"),
+        ("item_id" = Uuid, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 204, description = "Item removed from the collection"),
+        (status = 404, description = "Collection not found, or item not in it", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "collections"
+)]
+pub async fn remove_collection_item(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, collection_id, item_id)): Path<(Uuid, Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    if !auth.can(Permission::ItemsWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The items:write permission is required to file items in collections",
+        ));
+    }
+    ensure_collection_exists(&state, org_id, collection_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+    let result =
+        sqlx::query("DELETE FROM item_collections WHERE collection_id = $1 AND item_id = $2")
+            .bind(collection_id)
+            .bind(item_id)
+            .execute(&mut *tx)
+            .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found(
+            ErrorCode::ItemNotFound,
+            "Item is not in the collection",
+        ));
+    }
+    sqlx::query(
+        "UPDATE collections SET cover_item_id = NULL, updated_at = NOW()
+         WHERE id = $1 AND cover_item_id = $2",
+    )
+    .bind(collection_id)
+    .bind(item_id)
+    .execute(&mut *tx)
+    .await?;
+
+    outbox::enqueue(
+        &mut tx,
+        org_id,
+        outbox::COLLECTION_ITEM_REMOVED,
+        serde_json::json!({ "collection_id": collection_id, "item_id": item_id }),
     )
+    .await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
-fn forbidden(msg: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::FORBIDDEN,
-        Json(ErrorResponse {
-            error: "forbidden".to_string(),
-            message: msg.to_string(),
-        }),
+/// 404 unless the collection belongs to the organization
+async fn ensure_collection_exists(
+    state: &AppState,
+    org_id: Uuid,
+    collection_id: Uuid,
+) -> Result<(), ApiError> {
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM collections WHERE id = $1 AND organization_id = $2)",
     )
+    .bind(collection_id)
+    .bind(org_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if exists {
+        Ok(())
+    } else {
+        Err(ApiError::not_found(
+            ErrorCode::CollectionNotFound,
+            "Collection not found",
+        ))
+    }
 }
@@ -0,0 +1,138 @@
+//! Streaming CSV/JSON downloads shared by the export endpoints.
+//!
+//! Rows are encoded on a spawned task and sent through a bounded channel that
+//! feeds the response body, so an export never holds the whole result set in
+//! memory and a slow client applies back-pressure to the database query.
+
+use std::future::Future;
+
+use axum::{
+    body::{Body, Bytes},
+    http::header,
+    response::IntoResponse,
+};
+use chrono::Utc;
+use futures::{SinkExt, Stream, StreamExt, channel::mpsc};
+use serde::Serialize;
+
+use crate::api::error::ApiError;
+
+/// Encoded rows buffered ahead of a slow client
+const EXPORT_BUFFER: usize = 64;
+
+/// Sending half of a download body
+pub(super) type ChunkSender = mpsc::Sender<Result<Bytes, std::io::Error>>;
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// Parse a `format` query parameter; CSV when absent
+    pub(super) fn parse(format: Option<&str>) -> Result<Self, ApiError> {
+        match format {
+            None | Some("csv") => Ok(ExportFormat::Csv),
+            Some("json") => Ok(ExportFormat::Json),
+            Some(other) => Err(ApiError::validation_failed(format!(
+                "Unknown export format '{}'; use csv or json",
+                other
+            ))),
+        }
+    }
+}
+
+/// Respond with an attachment named `vostuff-<name>-<date>` whose body is
+/// written by `produce`. A failure part way through ends the body with an
+/// error so the client sees a broken download rather than a truncated file.
+pub(super) fn streamed_download<F, Fut>(
+    format: ExportFormat,
+    name: &str,
+    produce: F,
+) -> impl IntoResponse
+where
+    F: FnOnce(ChunkSender) -> Fut,
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+{
+    let (mut tx, rx) = mpsc::channel(EXPORT_BUFFER);
+    let task = produce(tx.clone());
+    let label = name.to_string();
+    tokio::spawn(async move {
+        if let Err(e) = task.await {
+            tracing::error!("{} export failed: {}", label, e);
+            let _ = tx.send(Err(std::io::Error::other(e.to_string()))).await;
+        }
+    });
+
+    let (content_type, extension) = match format {
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "csv"),
+        ExportFormat::Json => ("application/json", "json"),
+    };
+    let disposition = format!(
+        "attachment; filename=\"vostuff-{}-{}.{}\"",
+        name,
+        Utc::now().format("%Y-%m-%d"),
+        extension
+    );
+    (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        Body::from_stream(rx),
+    )
+}
+
+/// Encode each row as it arrives: a CSV line built by `record` under a
+/// `columns` header, or an element of a JSON array
+pub(super) async fn send_rows<T, S, R>(
+    mut rows: S,
+    format: ExportFormat,
+    columns: Vec<String>,
+    record: R,
+    tx: &mut ChunkSender,
+) -> anyhow::Result<()>
+where
+    T: Serialize,
+    S: Stream<Item = Result<T, sqlx::Error>> + Unpin,
+    R: Fn(&T) -> Vec<String>,
+{
+    let header = match format {
+        ExportFormat::Csv => csv_line(columns)?,
+        ExportFormat::Json => b"[".to_vec(),
+    };
+    tx.send(Ok(header.into())).await?;
+
+    let mut first = true;
+    while let Some(row) = rows.next().await {
+        let row = row?;
+        let chunk = match format {
+            ExportFormat::Csv => csv_line(record(&row))?,
+            ExportFormat::Json => {
+                let mut chunk = if first { Vec::new() } else { b",".to_vec() };
+                serde_json::to_writer(&mut chunk, &row)?;
+                chunk
+            }
+        };
+        first = false;
+        tx.send(Ok(chunk.into())).await?;
+    }
+
+    if let ExportFormat::Json = format {
+        tx.send(Ok(Bytes::from_static(b"]"))).await?;
+    }
+    Ok(())
+}
+
+/// Format an optional value for a CSV cell, empty when absent
+pub(super) fn opt<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(T::to_string).unwrap_or_default()
+}
+
+/// Encode one CSV line, quoting as needed
+fn csv_line(fields: impl IntoIterator<Item = String>) -> anyhow::Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(fields)?;
+    Ok(writer.into_inner()?)
+}
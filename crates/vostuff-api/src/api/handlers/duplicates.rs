@@ -0,0 +1,350 @@
+//! Duplicate items: finding items of one kind whose names look alike, as
+//! imports tend to leave behind, and merging a duplicate into the item kept.
+
+use std::collections::HashMap;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use super::items;
+use crate::api::error::ApiError;
+use crate::api::{
+    audit::{self, AuditAction},
+    models::{
+        DuplicateGroup, DuplicateParams, ErrorCode, ErrorResponse, Item, ItemState,
+        MergeItemsRequest, Permission,
+    },
+    outbox,
+    state::AppState,
+};
+use crate::auth::AuthContext;
+
+/// Most alike pairs considered; beyond this the weakest matches are left out
+const MAX_PAIRS: i64 = 2000;
+
+/// List groups of items that may be duplicates
+///
+/// Items are paired when they are of the same kind and their names are at
+/// least `threshold` alike by trigram similarity, which ignores case and
+/// punctuation; pairs sharing an item form one group. Groups come most alike
+/// first.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/items/duplicates",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        DuplicateParams
+    ),
+    responses(
+        (status = 200, description = "Groups of alike items", body = Vec<DuplicateGroup>),
+        (status = 400, description = "Threshold out of range", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn find_duplicates(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Query(params): Query<DuplicateParams>,
+) -> Result<Json<Vec<DuplicateGroup>>, ApiError> {
+    if !(0.1..=1.0).contains(&params.threshold) {
+        return Err(ApiError::validation_failed(
+            "threshold must be from 0.1 to 1",
+        ));
+    }
+    let kinds: Vec<String> = params
+        .kind
+        .as_deref()
+        .map(|list| items::split_list(list).map(str::to_string).collect())
+        .unwrap_or_default();
+
+    // The % operator uses the trigram index, at the threshold set for this
+    // transaction only
+    let mut tx = state.pool.begin().await?;
+    sqlx::query("SELECT set_config('pg_trgm.similarity_threshold', $1, true)")
+        .bind(params.threshold.to_string())
+        .execute(&mut *tx)
+        .await?;
+    let pairs: Vec<(Uuid, Uuid, f32)> = sqlx::query_as(
+        "SELECT a.id, b.id, similarity(a.name, b.name)
+         FROM items a
+         JOIN kinds k ON k.id = a.kind_id
+         JOIN items b ON b.organization_id = a.organization_id
+                     AND b.kind_id = a.kind_id
+                     AND b.id > a.id
+                     AND b.name % a.name
+         WHERE a.organization_id = $1
+           AND (cardinality($2::text[]) = 0 OR k.name = ANY($2))
+         ORDER BY 3 DESC, a.id, b.id
+         LIMIT $3",
+    )
+    .bind(org_id)
+    .bind(&kinds)
+    .bind(MAX_PAIRS)
+    .fetch_all(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let groups = group_pairs(&pairs);
+    let ids: Vec<Uuid> = groups.iter().flat_map(|(ids, _)| ids.clone()).collect();
+    let mut found: HashMap<Uuid, Item> = items::fetch_items(&state.pool, org_id, &ids)
+        .await?
+        .into_iter()
+        .map(|item| (item.id, item))
+        .collect();
+
+    let groups = groups
+        .into_iter()
+        .filter_map(|(ids, similarity)| {
+            let mut members: Vec<Item> = ids.iter().filter_map(|id| found.remove(id)).collect();
+            members.sort_by_key(|item| (item.created_at, item.id));
+            let first = members.first()?;
+            Some(DuplicateGroup {
+                kind_id: first.kind_id,
+                kind_name: first.kind_name.clone(),
+                similarity,
+                items: members,
+            })
+        })
+        .collect();
+
+    Ok(Json(groups))
+}
+
+/// Join pairs sharing an item into groups, each with its best pair's
+/// similarity; groups keep the order of their best pairs
+fn group_pairs(pairs: &[(Uuid, Uuid, f32)]) -> Vec<(Vec<Uuid>, f32)> {
+    let mut group_of: HashMap<Uuid, usize> = HashMap::new();
+    let mut groups: Vec<(Vec<Uuid>, f32)> = Vec::new();
+    for &(a, b, similarity) in pairs {
+        match (group_of.get(&a).copied(), group_of.get(&b).copied()) {
+            (Some(ga), Some(gb)) if ga == gb => {}
+            (Some(ga), Some(gb)) => {
+                // Fold the later group into the earlier, which has the better pair
+                let (keep, gone) = (ga.min(gb), ga.max(gb));
+                let moved = std::mem::take(&mut groups[gone].0);
+                for id in &moved {
+                    group_of.insert(*id, keep);
+                }
+                groups[keep].0.extend(moved);
+            }
+            (Some(g), None) | (None, Some(g)) => {
+                let new = if group_of.contains_key(&a) { b } else { a };
+                group_of.insert(new, g);
+                groups[g].0.push(new);
+            }
+            (None, None) => {
+                group_of.insert(a, groups.len());
+                group_of.insert(b, groups.len());
+                groups.push((vec![a, b], similarity));
+            }
+        }
+    }
+    groups.retain(|(ids, _)| !ids.is_empty());
+    groups
+}
+
+/// Merge a duplicate into an item
+///
+/// The item keeps its own values and takes the duplicate's where it has none:
+/// text fields, location, barcode and catalog code, acquisition date,
+/// measurements and soft fields. The duplicate's tags, collections, loan
+/// history, uses and photos move to the item, and the duplicate is deleted.
+/// Both items must be of the same kind, and the duplicate not on loan.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/items/{item_id}/merge",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("item_id" = Uuid, Path, description = "Item to keep")
+    ),
+    request_body = MergeItemsRequest,
+    responses(
+        (status = 200, description = "The merged item", body = Item),
+        (status = 400, description = "An item cannot be merged into itself", body = ErrorResponse),
+        (status = 403, description = "Missing the items:write permission", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 409, description = "Different kinds, or the duplicate is on loan", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn merge_items(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, item_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<MergeItemsRequest>,
+) -> Result<Json<Item>, ApiError> {
+    if !auth.can(Permission::ItemsWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The items:write permission is required to change items",
+        ));
+    }
+    let duplicate_id = req.duplicate_id;
+    if duplicate_id == item_id {
+        return Err(ApiError::validation_failed(
+            "An item cannot be merged into itself",
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    // Lock in id order, so two merges of the same pair cannot deadlock
+    let mut locked = HashMap::new();
+    for id in [item_id.min(duplicate_id), item_id.max(duplicate_id)] {
+        let item = items::lock_item(&mut tx, org_id, id)
+            .await?
+            .ok_or_else(items::not_found)?;
+        locked.insert(id, item);
+    }
+    let before = locked.remove(&item_id).ok_or_else(items::not_found)?;
+    let duplicate = locked.remove(&duplicate_id).ok_or_else(items::not_found)?;
+
+    if duplicate.kind_id != before.kind_id {
+        return Err(ApiError::conflict(
+            ErrorCode::ItemMergeConflict,
+            "Only items of the same kind can be merged",
+        ));
+    }
+    if duplicate.state == ItemState::Loaned {
+        return Err(ApiError::conflict(
+            ErrorCode::ItemMergeConflict,
+            "The duplicate is on loan; record its return before merging it",
+        ));
+    }
+
+    for statement in [
+        "INSERT INTO item_tags (item_id, organization_id, tag_name)
+         SELECT $1, organization_id, tag_name FROM item_tags WHERE item_id = $2
+         ON CONFLICT DO NOTHING",
+        "INSERT INTO item_collections (item_id, collection_id)
+         SELECT $1, collection_id FROM item_collections WHERE item_id = $2
+         ON CONFLICT DO NOTHING",
+        "UPDATE item_loans SET item_id = $1 WHERE item_id = $2",
+        "UPDATE usage_log SET item_id = $1 WHERE item_id = $2",
+        "UPDATE item_photos SET item_id = $1 WHERE item_id = $2",
+    ] {
+        sqlx::query(statement)
+            .bind(item_id)
+            .bind(duplicate_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    // Deleted before the fill below, which may take its barcode
+    sqlx::query("DELETE FROM items WHERE id = $1 AND organization_id = $2")
+        .bind(duplicate_id)
+        .bind(org_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "UPDATE items SET
+           description   = COALESCE(NULLIF(description, ''), $3),
+           notes         = COALESCE(NULLIF(notes, ''), $4),
+           location_id   = COALESCE(location_id, $5),
+           date_acquired = COALESCE(date_acquired, $6),
+           barcode       = COALESCE(barcode, $7),
+           catalog_code  = COALESCE(catalog_code, $8),
+           width_mm      = COALESCE(width_mm, $9),
+           height_mm     = COALESCE(height_mm, $10),
+           depth_mm      = COALESCE(depth_mm, $11),
+           weight_g      = COALESCE(weight_g, $12),
+           last_used_at  = GREATEST(last_used_at, $13),
+           date_entered  = LEAST(date_entered, $14),
+           soft_fields   = $15 || soft_fields
+         WHERE id = $1 AND organization_id = $2",
+    )
+    .bind(item_id)
+    .bind(org_id)
+    .bind(&duplicate.description)
+    .bind(&duplicate.notes)
+    .bind(duplicate.location_id)
+    .bind(duplicate.date_acquired)
+    .bind(&duplicate.barcode)
+    .bind(&duplicate.catalog_code)
+    .bind(duplicate.width_mm)
+    .bind(duplicate.height_mm)
+    .bind(duplicate.depth_mm)
+    .bind(duplicate.weight_g)
+    .bind(duplicate.last_used_at)
+    .bind(duplicate.date_entered)
+    .bind(&duplicate.soft_fields)
+    .execute(&mut *tx)
+    .await?;
+
+    let item = items::lock_item(&mut tx, org_id, item_id)
+        .await?
+        .ok_or_else(items::not_found)?;
+    let payload = items::item_payload(&item);
+
+    let mut changes = audit::diff(&items::item_payload(&before), &payload);
+    changes["merged_from"] = json!({ "to": { "id": duplicate_id, "name": duplicate.name } });
+    audit::record(
+        &mut tx,
+        org_id,
+        item_id,
+        auth.user_id,
+        AuditAction::Merged,
+        changes,
+    )
+    .await?;
+    let mut changes = audit::diff(&items::item_payload(&duplicate), &json!({}));
+    changes["merged_into"] = json!({ "to": { "id": item_id, "name": item.name } });
+    audit::record(
+        &mut tx,
+        org_id,
+        duplicate_id,
+        auth.user_id,
+        AuditAction::Deleted,
+        changes,
+    )
+    .await?;
+
+    outbox::enqueue(
+        &mut tx,
+        org_id,
+        outbox::ITEM_DELETED,
+        json!({ "id": duplicate_id }),
+    )
+    .await?;
+    outbox::enqueue(&mut tx, org_id, outbox::ITEM_UPDATED, payload).await?;
+    tx.commit().await?;
+
+    Ok(Json(item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pairs_sharing_an_item_form_one_group() {
+        let ids: Vec<Uuid> = (0..6).map(|_| Uuid::new_v4()).collect();
+        let pairs = [
+            (ids[0], ids[1], 0.9),
+            (ids[2], ids[3], 0.8),
+            (ids[1], ids[4], 0.7),
+            // Joins the two groups already found
+            (ids[3], ids[4], 0.65),
+            (ids[0], ids[4], 0.6),
+        ];
+        let groups = group_pairs(&pairs);
+        assert_eq!(groups.len(), 1);
+        let (mut members, similarity) = groups[0].clone();
+        members.sort();
+        let mut expected = ids[..5].to_vec();
+        expected.sort();
+        assert_eq!(members, expected);
+        assert_eq!(similarity, 0.9);
+
+        let groups = group_pairs(&[(ids[0], ids[1], 0.9), (ids[2], ids[5], 0.7)]);
+        assert_eq!(
+            groups,
+            vec![(vec![ids[0], ids[1]], 0.9), (vec![ids[2], ids[5]], 0.7)]
+        );
+    }
+}
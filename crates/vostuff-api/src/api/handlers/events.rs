@@ -0,0 +1,56 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::{Path, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::Stream;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+use crate::api::{live::RESYNC, models::ErrorResponse, state::AppState};
+
+/// Stream changes to the organization's items, collections, tags and locations
+///
+/// A server-sent event stream. Each event's type is the change, such as
+/// `item.updated`, `collection.item_added` or `tag.deleted`; its data is the
+/// changed item, collection, tag or location as JSON, or the ids of what was
+/// removed; and its id orders events and identifies repeats, as an event may
+/// be sent more than once. A client that falls behind is sent `resync` and
+/// should reload what it shows.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/events",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Server-sent event stream of changes", content_type = "text/event-stream", body = String),
+        (status = 403, description = "Not a member of the organization", body = ErrorResponse)
+    ),
+    tag = "events"
+)]
+pub async fn stream_events(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let receiver = state.live.subscribe();
+    let stream = futures::stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            let event = match receiver.recv().await {
+                Ok(event) if event.organization_id == org_id => Event::default()
+                    .id(event.id.to_string())
+                    .event(&event.event_type)
+                    .data(event.payload.to_string()),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(missed)) => {
+                    Event::default().event(RESYNC).data(missed.to_string())
+                }
+                Err(RecvError::Closed) => return None,
+            };
+            return Some((Ok(event), receiver));
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
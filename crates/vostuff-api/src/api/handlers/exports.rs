@@ -0,0 +1,372 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgConnection;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{ErrorCode, ErrorResponse},
+    state::AppState,
+};
+
+/// A final data export kept after its user or organization was deleted
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct DataExportSummary {
+    pub id: Uuid,
+    /// "user" or "organization"
+    pub subject_type: String,
+    pub subject_id: Uuid,
+    pub subject_name: String,
+    pub created_at: DateTime<Utc>,
+    /// The export is purged after this time
+    pub expires_at: DateTime<Utc>,
+    /// Admin download link for the archive
+    #[sqlx(skip)]
+    pub download_url: String,
+}
+
+/// List final data exports that have not yet expired
+#[utoipa::path(
+    get,
+    path = "/api/admin/exports",
+    responses(
+        (status = 200, description = "Retained data exports", body = Vec<DataExportSummary>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-exports"
+)]
+pub async fn list_exports(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<DataExportSummary>>, ApiError> {
+    let mut conn = state.pool.acquire().await?;
+    purge_expired(&mut conn, &state).await?;
+
+    let mut exports = sqlx::query_as::<_, DataExportSummary>(
+        "SELECT id, subject_type, subject_id, subject_name, created_at, expires_at
+         FROM data_exports
+         ORDER BY created_at DESC",
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for export in &mut exports {
+        export.download_url = download_url(export.id);
+    }
+
+    Ok(Json(exports))
+}
+
+/// Download a final data export archive as JSON
+#[utoipa::path(
+    get,
+    path = "/api/admin/exports/{export_id}",
+    params(
+        ("export_id" = Uuid, Path, description = "Export ID")
+    ),
+    responses(
+        (status = 200, description = "Export archive", body = serde_json::Value),
+        (status = 404, description = "Export not found or expired", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-exports"
+)]
+pub async fn download_export(
+    State(state): State<AppState>,
+    Path(export_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let row: Option<(Option<String>, Option<serde_json::Value>)> = sqlx::query_as(
+        "SELECT storage_key, archive FROM data_exports WHERE id = $1 AND expires_at > NOW()",
+    )
+    .bind(export_id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    // Older exports hold the archive inline rather than in storage
+    let archive = match row {
+        Some((Some(key), _)) => state.storage.get(&key).await.map_err(ApiError::internal)?,
+        Some((None, Some(archive))) => serde_json::to_vec(&archive).map_err(ApiError::internal)?,
+        _ => {
+            return Err(ApiError::not_found(
+                ErrorCode::ExportNotFound,
+                "Export not found or expired",
+            ));
+        }
+    };
+
+    let disposition = format!("attachment; filename=\"vostuff-export-{}.json\"", export_id);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        archive,
+    ))
+}
+
+// ── Archiving ──────────────────────────────────────────────────────────────
+
+/// Per-organization archive sections; each query selects one JSON row per record
+const ORGANIZATION_SECTIONS: &[(&str, &str)] = &[
+    (
+        "organization",
+        "SELECT to_jsonb(o) FROM organizations o WHERE o.id = $1",
+    ),
+    (
+        "members",
+        "SELECT jsonb_build_object('user_id', u.id, 'name', u.name, 'identity', u.identity, 'roles', uo.roles, 'permissions', uo.permissions)
+         FROM user_organizations uo JOIN users u ON u.id = uo.user_id
+         WHERE uo.organization_id = $1 ORDER BY u.name",
+    ),
+    (
+        "locations",
+        "SELECT to_jsonb(l) FROM locations l WHERE l.organization_id = $1 ORDER BY l.name",
+    ),
+    (
+        "collections",
+        "SELECT to_jsonb(c) FROM collections c WHERE c.organization_id = $1 ORDER BY c.name",
+    ),
+    (
+        "tags",
+        "SELECT to_jsonb(t) FROM tags t WHERE t.organization_id = $1 ORDER BY t.name",
+    ),
+    (
+        "kinds",
+        "SELECT to_jsonb(k) || jsonb_build_object('field_ids',
+                    COALESCE((SELECT jsonb_agg(kf.field_id ORDER BY kf.display_order)
+                              FROM kind_fields kf WHERE kf.kind_id = k.id), '[]'))
+         FROM kinds k WHERE k.org_id = $1 ORDER BY k.name",
+    ),
+    (
+        "fields",
+        "SELECT to_jsonb(f) || jsonb_build_object('enum_values',
+                    COALESCE((SELECT jsonb_agg(to_jsonb(ev) ORDER BY ev.sort_order)
+                              FROM enum_values ev WHERE ev.field_id = f.id), '[]'))
+         FROM fields f WHERE f.org_id = $1 ORDER BY f.name",
+    ),
+    (
+        "items",
+        "SELECT to_jsonb(i) || jsonb_build_object(
+                    'kind_name', k.name,
+                    'loan', (SELECT to_jsonb(d) FROM item_loan_details d WHERE d.item_id = i.id),
+                    'missing', (SELECT to_jsonb(d) FROM item_missing_details d WHERE d.item_id = i.id),
+                    'disposed', (SELECT to_jsonb(d) FROM item_disposed_details d WHERE d.item_id = i.id),
+                    'tags', COALESCE((SELECT jsonb_agg(it.tag_name ORDER BY it.tag_name)
+                                      FROM item_tags it WHERE it.item_id = i.id), '[]'),
+                    'collection_ids', COALESCE((SELECT jsonb_agg(ic.collection_id)
+                                                FROM item_collections ic WHERE ic.item_id = i.id), '[]'))
+         FROM items i JOIN kinds k ON k.id = i.kind_id
+         WHERE i.organization_id = $1 ORDER BY i.name",
+    ),
+    (
+        "borrowers",
+        "SELECT to_jsonb(b) FROM borrowers b WHERE b.organization_id = $1 ORDER BY b.name",
+    ),
+    (
+        "loans",
+        "SELECT to_jsonb(l) FROM item_loans l WHERE l.organization_id = $1 ORDER BY l.date_loaned",
+    ),
+    (
+        "audit_log",
+        "SELECT to_jsonb(a) FROM audit_log a WHERE a.organization_id = $1 ORDER BY a.change_date",
+    ),
+    (
+        "usage_log",
+        "SELECT to_jsonb(l) FROM usage_log l WHERE l.organization_id = $1 ORDER BY l.used_at",
+    ),
+    (
+        "pages",
+        "SELECT to_jsonb(p) || jsonb_build_object('versions',
+                    (SELECT jsonb_agg(to_jsonb(v) - 'page_id' ORDER BY v.version)
+                     FROM org_page_versions v WHERE v.page_id = p.id))
+         FROM org_pages p WHERE p.organization_id = $1 ORDER BY p.title",
+    ),
+];
+
+/// Per-user archive sections; password hashes are never exported
+const USER_SECTIONS: &[(&str, &str)] = &[
+    (
+        "user",
+        "SELECT to_jsonb(u) - 'password_hash' FROM users u WHERE u.id = $1",
+    ),
+    (
+        "memberships",
+        "SELECT jsonb_build_object('organization_id', o.id, 'organization_name', o.name, 'roles', uo.roles, 'permissions', uo.permissions, 'joined_at', uo.created_at)
+         FROM user_organizations uo JOIN organizations o ON o.id = uo.organization_id
+         WHERE uo.user_id = $1 ORDER BY o.name",
+    ),
+    (
+        "audit_entries",
+        "SELECT to_jsonb(a) - 'user_id' FROM audit_log a WHERE a.user_id = $1 ORDER BY a.change_date",
+    ),
+    (
+        "usage_entries",
+        "SELECT to_jsonb(l) - 'user_id' FROM usage_log l WHERE l.user_id = $1 ORDER BY l.used_at",
+    ),
+    (
+        "preferences",
+        "SELECT jsonb_build_object('organization_id', p.organization_id, 'preferences', p.preferences, 'updated_at', p.updated_at)
+         FROM user_preferences p WHERE p.user_id = $1 ORDER BY p.organization_id",
+    ),
+    (
+        "api_keys",
+        "SELECT to_jsonb(k) - 'key_hash' FROM api_keys k WHERE k.user_id = $1 ORDER BY k.created_at",
+    ),
+    (
+        "terms_acceptances",
+        "SELECT to_jsonb(t) - 'user_id' FROM terms_acceptances t WHERE t.user_id = $1 ORDER BY t.accepted_at",
+    ),
+];
+
+/// Snapshot an organization's data into `data_exports` before it is deleted.
+///
+/// Run inside the deleting transaction. Returns the export ID, or None if the
+/// organization does not exist.
+pub(crate) async fn archive_organization(
+    conn: &mut PgConnection,
+    state: &AppState,
+    org_id: Uuid,
+) -> anyhow::Result<Option<Uuid>> {
+    archive(conn, state, "organization", org_id).await
+}
+
+/// Snapshot a user's data into `data_exports` before the user is deleted.
+///
+/// Run inside the deleting transaction. Returns the export ID, or None if the
+/// user does not exist.
+pub(crate) async fn archive_user(
+    conn: &mut PgConnection,
+    state: &AppState,
+    user_id: Uuid,
+) -> anyhow::Result<Option<Uuid>> {
+    archive(conn, state, "user", user_id).await
+}
+
+async fn archive(
+    conn: &mut PgConnection,
+    state: &AppState,
+    subject_type: &str,
+    subject_id: Uuid,
+) -> anyhow::Result<Option<Uuid>> {
+    let (sections, name_query) = match subject_type {
+        "organization" => (
+            ORGANIZATION_SECTIONS,
+            "SELECT name FROM organizations WHERE id = $1 FOR UPDATE",
+        ),
+        _ => (
+            USER_SECTIONS,
+            "SELECT name FROM users WHERE id = $1 FOR UPDATE",
+        ),
+    };
+
+    let Some(subject_name) = sqlx::query_scalar::<_, String>(name_query)
+        .bind(subject_id)
+        .fetch_optional(&mut *conn)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let archive = build_archive(conn, subject_type, sections, subject_id).await?;
+
+    purge_expired(conn, state).await?;
+
+    // Written before the deleting transaction commits; if it rolls back the
+    // object is orphaned, which is harmless.
+    let export_id = Uuid::new_v4();
+    let storage_key = format!("exports/{}.json", export_id);
+    state
+        .storage
+        .put(&storage_key, serde_json::to_vec(&archive)?)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO data_exports (id, subject_type, subject_id, subject_name, storage_key, expires_at)
+         VALUES ($1, $2, $3, $4, $5, NOW() + make_interval(days => $6))",
+    )
+    .bind(export_id)
+    .bind(subject_type)
+    .bind(subject_id)
+    .bind(&subject_name)
+    .bind(&storage_key)
+    .bind(state.export_retention_days)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(Some(export_id))
+}
+
+/// All personal data held about a user, in the same shape as a deleted user's archive.
+///
+/// Returns None if the user does not exist.
+pub(crate) async fn personal_data(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> Result<Option<serde_json::Value>, sqlx::Error> {
+    let exists: Option<Uuid> = sqlx::query_scalar("SELECT id FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if exists.is_none() {
+        return Ok(None);
+    }
+
+    build_archive(conn, "user", USER_SECTIONS, user_id)
+        .await
+        .map(Some)
+}
+
+async fn build_archive(
+    conn: &mut PgConnection,
+    subject_type: &str,
+    sections: &[(&str, &str)],
+    subject_id: Uuid,
+) -> Result<serde_json::Value, sqlx::Error> {
+    let mut archive = serde_json::Map::new();
+    archive.insert("format_version".to_string(), 1.into());
+    archive.insert("subject_type".to_string(), subject_type.into());
+    archive.insert("exported_at".to_string(), Utc::now().to_rfc3339().into());
+    for (section, query) in sections {
+        let rows: Vec<serde_json::Value> = sqlx::query_scalar(query)
+            .bind(subject_id)
+            .fetch_all(&mut *conn)
+            .await?;
+        // Single-record sections ("organization", "user") are stored as an object
+        let value = if matches!(*section, "organization" | "user") {
+            rows.into_iter().next().unwrap_or_default()
+        } else {
+            serde_json::Value::Array(rows)
+        };
+        archive.insert(section.to_string(), value);
+    }
+
+    Ok(serde_json::Value::Object(archive))
+}
+
+/// Drop exports past their retention window, along with their stored archives
+async fn purge_expired(conn: &mut PgConnection, state: &AppState) -> Result<(), sqlx::Error> {
+    let keys: Vec<Option<String>> = sqlx::query_scalar(
+        "DELETE FROM data_exports WHERE expires_at <= NOW() RETURNING storage_key",
+    )
+    .fetch_all(conn)
+    .await?;
+
+    for key in keys.into_iter().flatten() {
+        if let Err(e) = state.storage.delete(&key).await {
+            tracing::warn!("Failed to delete expired export {}: {}", key, e);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn download_url(export_id: Uuid) -> String {
+    format!("/api/admin/exports/{}", export_id)
+}
+
+// ── Helpers ────────────────────────────────────────────────────────────────
@@ -7,41 +7,16 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::api::{models::ErrorResponse, state::AppState};
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{ErrorCode, ErrorResponse, Permission},
+    state::AppState,
+};
 use crate::auth::AuthContext;
 
 // ── Public types ────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum FieldType {
-    String,
-    Text,
-    Date,
-    Datetime,
-    Number,
-    Enum,
-    Boolean,
-}
-
-impl FieldType {
-    pub fn from_str(s: &str) -> Self {
-        match s {
-            "string" => FieldType::String,
-            "text" => FieldType::Text,
-            "date" => FieldType::Date,
-            "datetime" => FieldType::Datetime,
-            "number" => FieldType::Number,
-            "enum" => FieldType::Enum,
-            "boolean" => FieldType::Boolean,
-            _ => FieldType::String,
-        }
-    }
-
-    fn is_enum(&self) -> bool {
-        matches!(self, FieldType::Enum)
-    }
-}
+pub use crate::api::models::FieldType;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct EnumValue {
@@ -125,7 +100,7 @@ impl TryFrom<FieldRow> for Field {
             org_id: row.org_id,
             name: row.name,
             display_name: row.display_name,
-            field_type: FieldType::from_str(&row.field_type),
+            field_type: row.field_type.parse().unwrap_or(FieldType::String),
             is_shared: row.is_shared,
             enum_values,
         })
@@ -169,7 +144,7 @@ const FIELD_SELECT: &str = "
 pub async fn list_fields(
     State(state): State<AppState>,
     Path(org_id): Path<Uuid>,
-) -> Result<Json<Vec<Field>>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Vec<Field>>, ApiError> {
     let query = format!(
         "{} GROUP BY f.id ORDER BY f.display_name NULLS LAST, f.name",
         FIELD_SELECT
@@ -177,14 +152,13 @@ pub async fn list_fields(
     let rows = sqlx::query_as::<_, FieldRow>(&query)
         .bind(org_id)
         .fetch_all(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
     let fields: Vec<Field> = rows
         .into_iter()
         .map(Field::try_from)
         .collect::<Result<Vec<_>, _>>()
-        .map_err(internal_error)?;
+        .map_err(ApiError::internal)?;
 
     Ok(Json(fields))
 }
@@ -207,17 +181,16 @@ pub async fn list_fields(
 pub async fn get_field(
     State(state): State<AppState>,
     Path((org_id, field_id)): Path<(Uuid, Uuid)>,
-) -> Result<Json<Field>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Field>, ApiError> {
     let query = format!("{} AND f.id = $2 GROUP BY f.id", FIELD_SELECT);
     let row = sqlx::query_as::<_, FieldRow>(&query)
         .bind(org_id)
         .bind(field_id)
         .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?
+        .await?
         .ok_or_else(not_found)?;
 
-    let field = Field::try_from(row).map_err(internal_error)?;
+    let field = Field::try_from(row).map_err(ApiError::internal)?;
     Ok(Json(field))
 }
 
@@ -240,9 +213,11 @@ pub async fn create_field(
     Extension(auth): Extension<AuthContext>,
     Path(org_id): Path<Uuid>,
     Json(req): Json<CreateFieldRequest>,
-) -> Result<(StatusCode, Json<Field>), (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden("Administrator access required to manage fields"));
+) -> Result<(StatusCode, Json<Field>), ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage fields",
+        ));
     }
     // Check shared name conflict
     let shared_conflict: bool = sqlx::query_scalar(
@@ -250,12 +225,11 @@ pub async fn create_field(
     )
     .bind(&req.name)
     .fetch_one(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     if shared_conflict {
-        return Err(conflict(
-            "name_conflict",
+        return Err(ApiError::conflict(
+            ErrorCode::FieldNameConflict,
             "A shared field with this name already exists",
         ));
     }
@@ -266,30 +240,24 @@ pub async fn create_field(
             .bind(&req.name)
             .bind(org_id)
             .fetch_one(&state.pool)
-            .await
-            .map_err(internal_error)?;
+            .await?;
 
     if org_conflict {
-        return Err(conflict(
-            "name_conflict",
+        return Err(ApiError::conflict(
+            ErrorCode::FieldNameConflict,
             "A field with this name already exists in this organization",
         ));
     }
 
     // Validate enum values only allowed for enum fields
     if !req.field_type.is_enum() && !req.enum_values.is_empty() {
-        return Err(bad_request(
-            "invalid_enum_values",
+        return Err(ApiError::validation(
+            ErrorCode::FieldInvalidEnumValues,
             "enum_values can only be provided for enum fields",
         ));
     }
 
-    let mut tx = state.pool.begin().await.map_err(internal_error)?;
-
-    let field_type_str = serde_json::to_string(&req.field_type)
-        .map_err(internal_error)?
-        .trim_matches('"')
-        .to_string();
+    let mut tx = state.pool.begin().await?;
 
     let new_id: Uuid = sqlx::query_scalar(
         "INSERT INTO fields (org_id, name, display_name, field_type) VALUES ($1, $2, $3, $4::field_type) RETURNING id",
@@ -297,10 +265,9 @@ pub async fn create_field(
     .bind(org_id)
     .bind(&req.name)
     .bind(&req.display_name)
-    .bind(&field_type_str)
+    .bind(req.field_type.as_str())
     .fetch_one(&mut *tx)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     for ev in &req.enum_values {
         sqlx::query(
@@ -311,21 +278,19 @@ pub async fn create_field(
         .bind(&ev.display_value)
         .bind(ev.sort_order)
         .execute(&mut *tx)
-        .await
-        .map_err(internal_error)?;
+        .await?;
     }
 
-    tx.commit().await.map_err(internal_error)?;
+    tx.commit().await?;
 
     let query = format!("{} AND f.id = $2 GROUP BY f.id", FIELD_SELECT);
     let row = sqlx::query_as::<_, FieldRow>(&query)
         .bind(org_id)
         .bind(new_id)
         .fetch_one(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
-    let field = Field::try_from(row).map_err(internal_error)?;
+    let field = Field::try_from(row).map_err(ApiError::internal)?;
     Ok((StatusCode::CREATED, Json(field)))
 }
 
@@ -353,9 +318,11 @@ pub async fn update_field(
     Extension(auth): Extension<AuthContext>,
     Path((org_id, field_id)): Path<(Uuid, Uuid)>,
     Json(req): Json<UpdateFieldRequest>,
-) -> Result<Json<Field>, (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden("Administrator access required to manage fields"));
+) -> Result<Json<Field>, ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage fields",
+        ));
     }
     // Fetch the field and verify ownership
     let row = sqlx::query(
@@ -363,8 +330,7 @@ pub async fn update_field(
     )
     .bind(field_id)
     .fetch_optional(&state.pool)
-    .await
-    .map_err(internal_error)?
+    .await?
     .ok_or_else(not_found)?;
 
     use sqlx::Row;
@@ -373,29 +339,30 @@ pub async fn update_field(
     let field_type_str: String = row.get("field_type");
 
     if field_org_id.is_none() {
-        return Err(forbidden("Cannot modify a shared field"));
+        return Err(ApiError::auth_forbidden("Cannot modify a shared field"));
     }
     if field_org_id != Some(org_id) {
-        return Err(forbidden("Field does not belong to this organization"));
+        return Err(ApiError::auth_forbidden(
+            "Field does not belong to this organization",
+        ));
     }
 
-    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+    let mut tx = state.pool.begin().await?;
 
     if let Some(ref display_name) = req.display_name {
         sqlx::query("UPDATE fields SET display_name = $1, updated_at = NOW() WHERE id = $2")
             .bind(display_name)
             .bind(field_id)
             .execute(&mut *tx)
-            .await
-            .map_err(internal_error)?;
+            .await?;
     }
 
     if let Some(ref new_values) = req.enum_values {
-        let ft = FieldType::from_str(&field_type_str);
+        let ft: FieldType = field_type_str.parse().map_err(ApiError::internal)?;
         if !ft.is_enum() {
-            tx.rollback().await.map_err(internal_error)?;
-            return Err(bad_request(
-                "not_enum_field",
+            tx.rollback().await?;
+            return Err(ApiError::validation(
+                ErrorCode::FieldNotEnum,
                 "enum_values can only be set on enum fields",
             ));
         }
@@ -405,8 +372,7 @@ pub async fn update_field(
             sqlx::query_scalar("SELECT value FROM enum_values WHERE field_id = $1")
                 .bind(field_id)
                 .fetch_all(&mut *tx)
-                .await
-                .map_err(internal_error)?;
+                .await?;
 
         let new_value_set: std::collections::HashSet<&str> =
             new_values.iter().map(|v| v.value.as_str()).collect();
@@ -434,28 +400,24 @@ pub async fn update_field(
             .bind(field_id)
             .bind(&removed)
             .fetch_all(&mut *tx)
-            .await
-            .map_err(internal_error)?
+            .await?
             .into_iter()
             .map(|r| (r.get::<String, _>("value"), r.get::<i64, _>("item_count")))
             .collect();
 
             if !blocked.is_empty() {
-                tx.rollback().await.map_err(internal_error)?;
+                tx.rollback().await?;
                 let detail = blocked
                     .iter()
                     .map(|(v, c)| format!("{} ({} items)", v, c))
                     .collect::<Vec<_>>()
                     .join(", ");
-                return Err((
-                    StatusCode::CONFLICT,
-                    Json(ErrorResponse {
-                        error: "enum_value_in_use".to_string(),
-                        message: format!(
-                            "Cannot remove enum values that are assigned to items: {}",
-                            detail
-                        ),
-                    }),
+                return Err(ApiError::conflict(
+                    ErrorCode::FieldEnumValueInUse,
+                    format!(
+                        "Cannot remove enum values that are assigned to items: {}",
+                        detail
+                    ),
                 ));
             }
         }
@@ -464,8 +426,7 @@ pub async fn update_field(
         sqlx::query("DELETE FROM enum_values WHERE field_id = $1")
             .bind(field_id)
             .execute(&mut *tx)
-            .await
-            .map_err(internal_error)?;
+            .await?;
 
         for ev in new_values {
             sqlx::query(
@@ -476,22 +437,20 @@ pub async fn update_field(
             .bind(&ev.display_value)
             .bind(ev.sort_order)
             .execute(&mut *tx)
-            .await
-            .map_err(internal_error)?;
+            .await?;
         }
     }
 
-    tx.commit().await.map_err(internal_error)?;
+    tx.commit().await?;
 
     let query = format!("{} AND f.id = $2 GROUP BY f.id", FIELD_SELECT);
     let row = sqlx::query_as::<_, FieldRow>(&query)
         .bind(org_id)
         .bind(field_id)
         .fetch_one(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
-    let field = Field::try_from(row).map_err(internal_error)?;
+    let field = Field::try_from(row).map_err(ApiError::internal)?;
     Ok(Json(field))
 }
 
@@ -516,38 +475,40 @@ pub async fn delete_field(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
     Path((org_id, field_id)): Path<(Uuid, Uuid)>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden("Administrator access required to manage fields"));
+) -> Result<StatusCode, ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage fields",
+        ));
     }
     use sqlx::Row;
 
     let row = sqlx::query("SELECT id, org_id FROM fields WHERE id = $1")
         .bind(field_id)
         .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?
+        .await?
         .ok_or_else(not_found)?;
 
     let field_org_id: Option<Uuid> = row.get("org_id");
     if field_org_id.is_none() {
-        return Err(forbidden("Cannot delete a shared field"));
+        return Err(ApiError::auth_forbidden("Cannot delete a shared field"));
     }
     if field_org_id != Some(org_id) {
-        return Err(forbidden("Field does not belong to this organization"));
+        return Err(ApiError::auth_forbidden(
+            "Field does not belong to this organization",
+        ));
     }
 
     let kind_count: i64 =
         sqlx::query_scalar("SELECT COUNT(*) FROM kind_fields WHERE field_id = $1")
             .bind(field_id)
             .fetch_one(&state.pool)
-            .await
-            .map_err(internal_error)?;
+            .await?;
 
     if kind_count > 0 {
-        return Err(conflict(
-            "field_in_use",
-            &format!("{} kind(s) reference this field", kind_count),
+        return Err(ApiError::conflict(
+            ErrorCode::FieldInUse,
+            format!("{} kind(s) reference this field", kind_count),
         ));
     }
 
@@ -555,60 +516,13 @@ pub async fn delete_field(
         .bind(field_id)
         .bind(org_id)
         .execute(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
 // ── Error helpers ────────────────────────────────────────────────────────────
 
-fn internal_error<E: std::fmt::Display>(err: E) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(ErrorResponse {
-            error: "internal_error".to_string(),
-            message: err.to_string(),
-        }),
-    )
-}
-
-fn not_found() -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::NOT_FOUND,
-        Json(ErrorResponse {
-            error: "not_found".to_string(),
-            message: "Field not found".to_string(),
-        }),
-    )
-}
-
-fn bad_request(code: &str, msg: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::BAD_REQUEST,
-        Json(ErrorResponse {
-            error: code.to_string(),
-            message: msg.to_string(),
-        }),
-    )
-}
-
-fn conflict(code: &str, msg: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::CONFLICT,
-        Json(ErrorResponse {
-            error: code.to_string(),
-            message: msg.to_string(),
-        }),
-    )
-}
-
-fn forbidden(msg: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::FORBIDDEN,
-        Json(ErrorResponse {
-            error: "forbidden".to_string(),
-            message: msg.to_string(),
-        }),
-    )
+fn not_found() -> ApiError {
+    ApiError::not_found(ErrorCode::FieldNotFound, "Field not found")
 }
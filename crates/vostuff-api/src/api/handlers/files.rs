@@ -0,0 +1,63 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use vostuff_core::storage::StorageError;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{ErrorCode, ErrorResponse},
+    state::AppState,
+};
+
+/// Signature of a presigned storage URL
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PresignedParams {
+    /// Expiry as unix seconds
+    pub expires: i64,
+    pub signature: String,
+}
+
+/// Download a file through a presigned URL issued by local storage
+///
+/// No authentication: the signature and expiry in the URL grant access.
+#[utoipa::path(
+    get,
+    path = "/api/files/{key}",
+    params(
+        ("key" = String, Path, description = "Storage key"),
+        PresignedParams
+    ),
+    responses(
+        (status = 200, description = "File contents"),
+        (status = 403, description = "Signature invalid or expired", body = ErrorResponse),
+        (status = 404, description = "File not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "files"
+)]
+pub async fn get_presigned_file(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Query(params): Query<PresignedParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    if !state
+        .storage
+        .verify_presigned(&key, params.expires, &params.signature)
+    {
+        return Err(ApiError::forbidden(
+            ErrorCode::FileLinkInvalid,
+            "Link is invalid or has expired",
+        ));
+    }
+
+    let data = state.storage.get(&key).await.map_err(|e| match e {
+        StorageError::NotFound(_) => ApiError::not_found(ErrorCode::FileNotFound, "File not found"),
+        other => ApiError::internal(other),
+    })?;
+
+    Ok(([(header::CONTENT_TYPE, "application/octet-stream")], data))
+}
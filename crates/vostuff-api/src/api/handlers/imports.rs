@@ -0,0 +1,236 @@
+//! Importing items from a CSV file in the background; the import itself runs
+//! as a job, see [`crate::api::imports`].
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    imports::{self, MAX_ROWS},
+    models::{CreateImportRequest, ErrorCode, ErrorResponse, ItemImport, Permission},
+    state::AppState,
+    validation::NAME_MAX_LEN,
+};
+use crate::auth::AuthContext;
+use crate::import_mapping::{ImportMapping, MappingTarget, suggest_mapping};
+
+/// Kind imported when neither the request nor its mapping names one
+const DEFAULT_KIND: &str = "misc";
+
+const IMPORT_SELECT: &str = "
+    SELECT im.id, im.filename, im.kind_id, k.name AS kind_name, im.mapping, im.status,
+           im.total_rows, im.processed_rows, im.imported_rows, im.skipped_rows, im.failed_rows,
+           im.errors, im.last_error, im.created_by, im.created_at, im.started_at, im.finished_at
+    FROM item_imports im JOIN kinds k ON k.id = im.kind_id";
+
+/// Import items from a CSV file
+///
+/// The CSV's first row holds the headers. Columns go where the `mapping`
+/// says, in the format `csv-importer` uses; without one, a mapping is guessed
+/// from the headers for `kind` (default `misc`) and returned with the import.
+/// The file is checked and stored, and its items are created by a background
+/// job: follow its progress with `GET .../imports/{import_id}`. Rows without
+/// a name, or whose barcode is already in use, are skipped.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/imports",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = CreateImportRequest,
+    responses(
+        (status = 202, description = "Import queued", body = ItemImport),
+        (status = 400, description = "Not a CSV file, no rows or too many, or an unusable mapping", body = ErrorResponse),
+        (status = 403, description = "Missing the items:write permission", body = ErrorResponse),
+        (status = 413, description = "The file is too large"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn create_import(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<CreateImportRequest>,
+) -> Result<(StatusCode, Json<ItemImport>), ApiError> {
+    if !auth.can(Permission::ItemsWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The items:write permission is required to change items",
+        ));
+    }
+    let filename = req
+        .filename
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty());
+    if filename
+        .as_ref()
+        .is_some_and(|f| f.chars().count() > NAME_MAX_LEN)
+    {
+        return Err(ApiError::validation_failed(format!(
+            "filename must be at most {} characters",
+            NAME_MAX_LEN
+        )));
+    }
+
+    let (headers, rows) = imports::read_csv(req.csv.as_bytes())
+        .map_err(|e| ApiError::validation_failed(format!("Could not read the CSV file: {}", e)))?;
+    if rows.is_empty() || rows.len() > MAX_ROWS {
+        return Err(ApiError::validation_failed(format!(
+            "The CSV file must have 1 to {} rows after its headers",
+            MAX_ROWS
+        )));
+    }
+
+    let mapping = req
+        .mapping
+        .map(serde_json::from_value::<ImportMapping>)
+        .transpose()
+        .map_err(|e| ApiError::validation_failed(format!("Invalid mapping: {}", e)))?;
+    let kind_name = match &mapping {
+        Some(mapping) => mapping.kind.clone(),
+        None => req
+            .kind
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty())
+            .unwrap_or_else(|| DEFAULT_KIND.to_string()),
+    };
+
+    // The organization's own copy of a shared kind comes first
+    let kind_id: Uuid = sqlx::query_scalar(
+        "SELECT id FROM kinds WHERE name = $1 AND (org_id = $2 OR org_id IS NULL)
+         ORDER BY org_id NULLS LAST LIMIT 1",
+    )
+    .bind(&kind_name)
+    .bind(org_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| ApiError::validation_failed(format!("Kind '{}' not found", kind_name)))?;
+    let fields = imports::soft_field_specs(&state.pool, kind_id).await?;
+
+    let mapping = match mapping {
+        Some(mapping) => {
+            mapping
+                .validate(&headers)
+                .map_err(|e| ApiError::validation_failed(format!("Invalid mapping: {}", e)))?;
+            for col in &mapping.columns {
+                if let MappingTarget::SoftField(name) = &col.target
+                    && !fields.iter().any(|f| &f.name == name)
+                {
+                    return Err(ApiError::validation_failed(format!(
+                        "Invalid mapping: {} has no field '{}'",
+                        kind_name, name
+                    )));
+                }
+            }
+            mapping
+        }
+        None => {
+            let soft_fields: Vec<(String, Option<String>)> = sqlx::query_as(
+                "SELECT f.name, f.display_name FROM kind_fields kf JOIN fields f ON f.id = kf.field_id
+                 WHERE kf.kind_id = $1 ORDER BY kf.display_order",
+            )
+            .bind(kind_id)
+            .fetch_all(&state.pool)
+            .await?;
+            let suggested = suggest_mapping(&kind_name, &headers, &soft_fields);
+            suggested.validate(&headers).map_err(|_| {
+                ApiError::validation_failed(
+                    "No column looks like a name; give a mapping saying which is",
+                )
+            })?;
+            suggested
+        }
+    };
+
+    let mapping = serde_json::to_value(&mapping).map_err(ApiError::internal)?;
+    let import_id = Uuid::new_v4();
+    let key = imports::storage_key(org_id, import_id);
+    state
+        .storage
+        .put(&key, req.csv.into_bytes())
+        .await
+        .map_err(ApiError::internal)?;
+
+    let recorded: Result<(), sqlx::Error> = async {
+        let mut tx = state.pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO item_imports
+               (id, organization_id, created_by, filename, kind_id, mapping, storage_key,
+                total_rows)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(import_id)
+        .bind(org_id)
+        .bind(auth.user_id)
+        .bind(&filename)
+        .bind(kind_id)
+        .bind(&mapping)
+        .bind(&key)
+        .bind(rows.len() as i32)
+        .execute(&mut *tx)
+        .await?;
+        imports::enqueue_import(
+            &mut tx,
+            org_id,
+            import_id,
+            auth.can(Permission::CatalogWrite),
+        )
+        .await?;
+        tx.commit().await
+    }
+    .await;
+    if let Err(e) = recorded {
+        if let Err(e) = state.storage.delete(&key).await {
+            tracing::warn!("Failed to delete import file {}: {}", key, e);
+        }
+        return Err(e.into());
+    }
+
+    let import = fetch_import(&state, org_id, import_id).await?;
+    Ok((StatusCode::ACCEPTED, Json(import)))
+}
+
+/// Get an import's progress
+///
+/// `errors` describes the first rows that were not imported, by data row
+/// number (the row after the headers is 1).
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/imports/{import_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("import_id" = Uuid, Path, description = "Import ID")
+    ),
+    responses(
+        (status = 200, description = "The import and its progress", body = ItemImport),
+        (status = 404, description = "Import not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn get_import(
+    State(state): State<AppState>,
+    Path((org_id, import_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ItemImport>, ApiError> {
+    Ok(Json(fetch_import(&state, org_id, import_id).await?))
+}
+
+async fn fetch_import(
+    state: &AppState,
+    org_id: Uuid,
+    import_id: Uuid,
+) -> Result<ItemImport, ApiError> {
+    sqlx::query_as::<_, ItemImport>(&format!(
+        "{} WHERE im.id = $1 AND im.organization_id = $2",
+        IMPORT_SELECT
+    ))
+    .bind(import_id)
+    .bind(org_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| ApiError::not_found(ErrorCode::ImportNotFound, "Import not found"))
+}
@@ -0,0 +1,357 @@
+//! Organization invites: members with `members:manage` invite someone by
+//! email, and the invitee joins through the emailed link.
+//!
+//! Creating an invite enqueues an `invite.created` outbox event, which
+//! `mail::InviteMailSink` turns into the email. Accepting the link creates the
+//! account if none exists for the address, then the membership, with the roles
+//! and permissions chosen when inviting. Inviting an address again replaces
+//! its pending invite, so an old link stops working.
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use super::{settings, users};
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{
+        AcceptInviteRequest, CreateInviteRequest, ErrorCode, ErrorResponse, Invite, Permission,
+        UserOrganization, UserRole,
+    },
+    outbox,
+    state::AppState,
+};
+use crate::auth::{AuthContext, INVITE_TOKEN_TTL_DAYS, PasswordHasher, TokenManager};
+
+const INVITE_COLUMNS: &str =
+    "id, organization_id, email, roles, permissions, invited_by, expires_at, created_at";
+
+/// List an organization's pending invites
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/invites",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Pending invites, newest first", body = Vec<Invite>),
+        (status = 403, description = "Lacks the members:manage permission", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "invites"
+)]
+pub async fn list_invites(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<Vec<Invite>>, ApiError> {
+    require_members_manage(&auth)?;
+
+    let invites = sqlx::query_as::<_, Invite>(&format!(
+        "SELECT {} FROM organization_invites
+         WHERE organization_id = $1 AND accepted_at IS NULL AND expires_at > NOW()
+         ORDER BY created_at DESC",
+        INVITE_COLUMNS
+    ))
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(invites))
+}
+
+/// Invite someone to the organization by email
+///
+/// The invite link is emailed to the address and is not returned.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/invites",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = CreateInviteRequest,
+    responses(
+        (status = 201, description = "Invite created and queued for sending", body = Invite),
+        (status = 400, description = "Invalid email, roles or permissions", body = ErrorResponse),
+        (status = 403, description = "Lacks members:manage, or granted a permission the caller lacks", body = ErrorResponse),
+        (status = 409, description = "The address already belongs to a member", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "invites"
+)]
+pub async fn create_invite(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<CreateInviteRequest>,
+) -> Result<(StatusCode, Json<Invite>), ApiError> {
+    require_members_manage(&auth)?;
+
+    let email = req.email.trim();
+    if email.is_empty() || !email.contains('@') {
+        return Err(ApiError::validation_failed("A valid email is required"));
+    }
+
+    // Roles default to the organization's default member roles, else USER
+    let roles = match req.roles {
+        Some(roles) => roles,
+        None => settings::load_settings(&state.pool, org_id)
+            .await?
+            .default_member_roles
+            .unwrap_or_else(|| vec![UserRole::User]),
+    };
+    if roles.is_empty() {
+        return Err(users::no_roles());
+    }
+    let permissions = users::member_permissions(&roles, req.permissions)?;
+    if let Some(permission) = permissions.iter().find(|p| !auth.can((*p).clone())) {
+        return Err(ApiError::auth_forbidden(format!(
+            "Cannot grant the {} permission, which you do not hold",
+            permission
+        )));
+    }
+
+    let already_member: bool = sqlx::query_scalar(
+        "SELECT EXISTS(
+             SELECT 1 FROM user_organizations uo JOIN users u ON u.id = uo.user_id
+             WHERE uo.organization_id = $1 AND LOWER(u.identity) = LOWER($2))",
+    )
+    .bind(org_id)
+    .bind(email)
+    .fetch_one(&state.pool)
+    .await?;
+    if already_member {
+        return Err(ApiError::conflict(
+            ErrorCode::UserAlreadyMember,
+            "This address already belongs to a member",
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    sqlx::query(
+        "DELETE FROM organization_invites
+         WHERE organization_id = $1 AND LOWER(email) = LOWER($2) AND accepted_at IS NULL",
+    )
+    .bind(org_id)
+    .bind(email)
+    .execute(&mut *tx)
+    .await?;
+
+    let invite = sqlx::query_as::<_, Invite>(&format!(
+        "INSERT INTO organization_invites
+             (organization_id, email, roles, permissions, invited_by, expires_at)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING {}",
+        INVITE_COLUMNS
+    ))
+    .bind(org_id)
+    .bind(email)
+    .bind(&roles)
+    .bind(permissions.into_vec())
+    .bind(auth.user_id)
+    .bind(Utc::now() + Duration::days(INVITE_TOKEN_TTL_DAYS))
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let (organization_name, invited_by_name) = sqlx::query_as::<_, (String, Option<String>)>(
+        "SELECT o.name, (SELECT name FROM users WHERE id = $2)
+         FROM organizations o WHERE o.id = $1",
+    )
+    .bind(org_id)
+    .bind(auth.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    outbox::enqueue(
+        &mut tx,
+        org_id,
+        outbox::INVITE_CREATED,
+        serde_json::json!({
+            "id": invite.id,
+            "organization_id": org_id,
+            "organization_name": organization_name,
+            "email": invite.email,
+            "roles": invite.roles,
+            "invited_by_name": invited_by_name,
+            "expires_at": invite.expires_at,
+        }),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(invite)))
+}
+
+/// Revoke a pending invite; its link stops working
+#[utoipa::path(
+    delete,
+    path = "/api/organizations/{org_id}/invites/{invite_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("invite_id" = Uuid, Path, description = "Invite ID")
+    ),
+    responses(
+        (status = 204, description = "Invite revoked"),
+        (status = 403, description = "Lacks the members:manage permission", body = ErrorResponse),
+        (status = 404, description = "No pending invite with this ID", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "invites"
+)]
+pub async fn delete_invite(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, invite_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    require_members_manage(&auth)?;
+
+    let result = sqlx::query(
+        "DELETE FROM organization_invites
+         WHERE id = $1 AND organization_id = $2 AND accepted_at IS NULL",
+    )
+    .bind(invite_id)
+    .bind(org_id)
+    .execute(&state.pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found(
+            ErrorCode::InviteNotFound,
+            "Invite not found",
+        ));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Accept an invite from its emailed link
+///
+/// Joins the existing account for the invited address, or creates one with
+/// the given name and password. The new member then logs in as usual.
+#[utoipa::path(
+    post,
+    path = "/api/auth/accept-invite",
+    request_body = AcceptInviteRequest,
+    responses(
+        (status = 201, description = "Membership created", body = UserOrganization),
+        (status = 400, description = "Invalid, expired or used invite, or a new account without a name and password", body = ErrorResponse),
+        (status = 403, description = "The invited account has been deactivated", body = ErrorResponse),
+        (status = 409, description = "Already a member of the organization", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "auth"
+)]
+pub async fn accept_invite(
+    State(state): State<AppState>,
+    Json(req): Json<AcceptInviteRequest>,
+) -> Result<(StatusCode, Json<UserOrganization>), ApiError> {
+    let claims = TokenManager::new(&state.jwt_secret)
+        .validate_invite_token(&req.token)
+        .map_err(|_| invalid_invite())?;
+
+    let mut tx = state.pool.begin().await?;
+    let invite = sqlx::query_as::<_, Invite>(&format!(
+        "SELECT {} FROM organization_invites
+         WHERE id = $1 AND organization_id = $2 AND accepted_at IS NULL AND expires_at > NOW()
+         FOR UPDATE",
+        INVITE_COLUMNS
+    ))
+    .bind(claims.sub)
+    .bind(claims.organization_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(invalid_invite)?;
+
+    // Emails are matched ignoring case, preferring an exact match
+    let existing = sqlx::query_as::<_, (Uuid, bool)>(
+        "SELECT id, is_active FROM users
+         WHERE LOWER(identity) = LOWER($1)
+         ORDER BY identity = $1 DESC
+         LIMIT 1",
+    )
+    .bind(&invite.email)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let user_id = match existing {
+        Some((_, false)) => {
+            return Err(ApiError::forbidden(
+                ErrorCode::AuthUserDeactivated,
+                "This account has been deactivated",
+            ));
+        }
+        Some((user_id, true)) => user_id,
+        None => {
+            let name = req.name.as_deref().map(str::trim).unwrap_or_default();
+            let password = req.password.as_deref().unwrap_or_default();
+            if name.is_empty() || password.trim().is_empty() {
+                return Err(ApiError::validation_failed(
+                    "A name and password are required to create your account",
+                ));
+            }
+            let password_hash =
+                PasswordHasher::hash_password(password).map_err(ApiError::internal)?;
+            sqlx::query_scalar(
+                "INSERT INTO users (name, identity, password_hash) VALUES ($1, $2, $3)
+                 RETURNING id",
+            )
+            .bind(name)
+            .bind(&invite.email)
+            .bind(password_hash)
+            .fetch_one(&mut *tx)
+            .await?
+        }
+    };
+
+    let membership = sqlx::query_as::<_, UserOrganization>(
+        "INSERT INTO user_organizations (user_id, organization_id, roles, permissions)
+         VALUES ($1, $2, $3, $4)
+         RETURNING user_id, organization_id, roles, permissions, created_at",
+    )
+    .bind(user_id)
+    .bind(invite.organization_id)
+    .bind(&invite.roles)
+    .bind(&invite.permissions)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::conflict(
+            ErrorCode::UserAlreadyMember,
+            "You are already a member of this organization",
+        ),
+        err => ApiError::internal(err),
+    })?;
+
+    sqlx::query(
+        "UPDATE organization_invites SET accepted_at = NOW(), accepted_by = $2 WHERE id = $1",
+    )
+    .bind(invite.id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(membership)))
+}
+
+// ── Helpers ────────────────────────────────────────────────────────────────
+
+fn require_members_manage(auth: &AuthContext) -> Result<(), ApiError> {
+    if !auth.can(Permission::MembersManage) {
+        return Err(ApiError::auth_forbidden(
+            "The members:manage permission is required to manage invites",
+        ));
+    }
+    Ok(())
+}
+
+fn invalid_invite() -> ApiError {
+    ApiError::validation(
+        ErrorCode::InviteInvalid,
+        "This invite is invalid, has expired or has already been used",
+    )
+}
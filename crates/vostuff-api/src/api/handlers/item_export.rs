@@ -0,0 +1,226 @@
+//! Item export as CSV or JSON.
+//!
+//! The export runs the item list filters without paging and streams rows to
+//! the client as the database returns them, so large collections are never
+//! held in memory. CSV has one column per soft field used by the exported
+//! items; JSON keeps `soft_fields` and `tags` as an object and array.
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::QueryBuilder;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use super::download::{ExportFormat, opt, send_rows, streamed_download};
+use super::items::{ItemFilter, ItemSort};
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{ErrorResponse, ItemFilterParams},
+    state::AppState,
+};
+
+/// Query parameters for the item export, alongside the item list filters
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ItemExportParams {
+    /// `csv` (default) or `json`
+    pub format: Option<String>,
+}
+
+// Exported columns, before the filter conditions
+const EXPORT_SELECT: &str = "
+    SELECT i.id, i.name, k.name AS kind, i.state::text AS state,
+           i.description, i.notes, l.name AS location, i.date_acquired,
+           i.barcode, i.catalog_code, i.width_mm, i.height_mm, i.depth_mm, i.weight_g,
+           ld.loaned_to, ld.date_loaned, ld.date_due_back,
+           md.date_missing, dd.date_disposed,
+           COALESCE(
+               (SELECT array_agg(t.tag_name ORDER BY t.tag_name)
+                FROM item_tags t WHERE t.item_id = i.id),
+               '{}'
+           ) AS tags,
+           i.soft_fields, i.created_at, i.updated_at
+    FROM items i
+    JOIN kinds k ON k.id = i.kind_id
+    LEFT JOIN locations l ON l.id = i.location_id
+    LEFT JOIN item_loan_details ld ON ld.item_id = i.id AND i.state = 'loaned'
+    LEFT JOIN item_missing_details md ON md.item_id = i.id AND i.state = 'missing'
+    LEFT JOIN item_disposed_details dd ON dd.item_id = i.id AND i.state = 'disposed'";
+
+/// One exported item: the item with its location, current state details and tags
+#[derive(Debug, Serialize, sqlx::FromRow)]
+struct ExportRow {
+    id: Uuid,
+    name: String,
+    kind: String,
+    state: String,
+    description: Option<String>,
+    notes: Option<String>,
+    location: Option<String>,
+    date_acquired: Option<NaiveDate>,
+    barcode: Option<String>,
+    catalog_code: Option<String>,
+    width_mm: Option<i32>,
+    height_mm: Option<i32>,
+    depth_mm: Option<i32>,
+    weight_g: Option<i32>,
+    loaned_to: Option<String>,
+    date_loaned: Option<NaiveDate>,
+    date_due_back: Option<NaiveDate>,
+    date_missing: Option<NaiveDate>,
+    date_disposed: Option<NaiveDate>,
+    tags: Vec<String>,
+    soft_fields: Value,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// CSV columns before the soft field columns, in ExportRow order
+const CSV_COLUMNS: &[&str] = &[
+    "id",
+    "name",
+    "kind",
+    "state",
+    "description",
+    "notes",
+    "location",
+    "date_acquired",
+    "barcode",
+    "catalog_code",
+    "width_mm",
+    "height_mm",
+    "depth_mm",
+    "weight_g",
+    "loaned_to",
+    "date_loaned",
+    "date_due_back",
+    "date_missing",
+    "date_disposed",
+    "tags",
+    "created_at",
+    "updated_at",
+];
+
+/// Export the filtered item list as CSV or JSON
+///
+/// Takes the same filters and sort as the item list; every matching item is
+/// exported, in the order the list shows them. Loan, missing and disposed details are filled in for items
+/// currently in that state. In CSV, tags are joined with `;` and each soft
+/// field gets its own column.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/items/export",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ItemExportParams,
+        ItemFilterParams
+    ),
+    responses(
+        (status = 200, description = "Item export file", content(
+            (String = "text/csv"),
+            (Vec<serde_json::Value> = "application/json")
+        )),
+        (status = 400, description = "Unknown format", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn export_items(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Query(params): Query<ItemExportParams>,
+    Query(filters): Query<ItemFilterParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let format = ExportFormat::parse(params.format.as_deref())?;
+    let filter = ItemFilter::new(&filters);
+    let sort = ItemSort::new(&filters, &filter);
+
+    // CSV needs its soft field columns before the first row
+    let soft_field_names: Vec<String> = match format {
+        ExportFormat::Csv => {
+            let mut query = QueryBuilder::new(
+                "SELECT DISTINCT jsonb_object_keys(i.soft_fields) AS key
+                 FROM items i JOIN kinds k ON k.id = i.kind_id
+                 WHERE ",
+            );
+            filter.push_conditions(&mut query, org_id);
+            query.push(" ORDER BY key");
+            query.build_query_scalar().fetch_all(&state.pool).await?
+        }
+        ExportFormat::Json => Vec::new(),
+    };
+
+    let pool = state.pool.clone();
+    let columns = CSV_COLUMNS
+        .iter()
+        .map(|c| c.to_string())
+        .chain(
+            soft_field_names
+                .iter()
+                .map(|name| format!("field:{}", name)),
+        )
+        .collect();
+    Ok(streamed_download(
+        format,
+        "items",
+        move |mut tx| async move {
+            let mut query = QueryBuilder::new(EXPORT_SELECT);
+            query.push(" WHERE ");
+            filter.push_conditions(&mut query, org_id);
+            query.push(" ORDER BY ");
+            sort.push_order_by(&mut query);
+            let rows = query.build_query_as::<ExportRow>().fetch(&pool);
+            send_rows(
+                rows,
+                format,
+                columns,
+                |row| csv_record(row, &soft_field_names),
+                &mut tx,
+            )
+            .await
+        },
+    ))
+}
+
+/// CSV values for a row, in CSV_COLUMNS order followed by the soft fields
+fn csv_record(row: &ExportRow, soft_field_names: &[String]) -> Vec<String> {
+    let mut record = vec![
+        row.id.to_string(),
+        row.name.clone(),
+        row.kind.clone(),
+        row.state.clone(),
+        opt(&row.description),
+        opt(&row.notes),
+        opt(&row.location),
+        opt(&row.date_acquired),
+        opt(&row.barcode),
+        opt(&row.catalog_code),
+        opt(&row.width_mm),
+        opt(&row.height_mm),
+        opt(&row.depth_mm),
+        opt(&row.weight_g),
+        opt(&row.loaned_to),
+        opt(&row.date_loaned),
+        opt(&row.date_due_back),
+        opt(&row.date_missing),
+        opt(&row.date_disposed),
+        row.tags.join(";"),
+        row.created_at.to_rfc3339(),
+        row.updated_at.to_rfc3339(),
+    ];
+    // Text soft fields are written as-is, anything else as JSON
+    record.extend(
+        soft_field_names
+            .iter()
+            .map(|name| match row.soft_fields.get(name) {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(s)) => s.clone(),
+                Some(other) => other.to_string(),
+            }),
+    );
+    record
+}
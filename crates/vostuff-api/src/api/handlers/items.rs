@@ -1,30 +1,58 @@
 use std::collections::HashMap;
 
 use axum::{
-    Json,
+    Extension, Json,
     extract::{Path, Query, State},
     http::StatusCode,
 };
-use sqlx::{PgPool, Row};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, QueryBuilder, Row};
 use uuid::Uuid;
 
+use super::{borrowers, photos};
+use crate::api::error::ApiError;
+use crate::api::validation::ValidJson;
 use crate::api::{
+    audit::{self, AuditAction},
     models::{
-        CreateItemRequest, DisposedDetails, ErrorResponse, Item, ItemFilterParams, ItemFullDetails,
-        ItemState, LoanDetails, MissingDetails, PaginatedResponse, UpdateItemRequest,
+        BulkCreateItemsRequest, BulkUpdateItemsRequest, Collection, CreateItemRequest,
+        DisposedDetails, ErrorCode, ErrorResponse, FacetCount, FieldError, Item, ItemAggregate,
+        ItemDetailsRequest, ItemFacets, ItemFilterParams, ItemFullDetails, ItemHistoryEntry,
+        ItemState, ItemWithWarnings, LoanDetails, LogUsageRequest, MissingDetails,
+        PaginatedResponse, PaginationParams, Permission, RotationParams, Tag, UpdateItemRequest,
+        UsageEntry,
     },
+    outbox,
     state::AppState,
+    warnings,
 };
+use crate::auth::AuthContext;
 
 // Base SELECT shared by list, get, and details handlers
 const ITEM_SELECT: &str = "
     SELECT i.id, i.organization_id, i.kind_id, k.name AS kind_name,
            i.state::text, i.name, i.description, i.notes,
-           i.location_id, i.date_entered, i.date_acquired,
+           i.location_id, i.date_entered, i.date_acquired, i.barcode, i.catalog_code,
+           i.width_mm, i.height_mm, i.depth_mm, i.weight_g, i.last_used_at,
            i.created_at, i.updated_at, i.soft_fields
     FROM items i
     JOIN kinds k ON k.id = i.kind_id";
 
+// Logged uses with the name of the user who logged each
+const USAGE_SELECT: &str = "
+    SELECT l.id, l.item_id, l.used_at, l.user_id, u.name AS user_name, l.note
+    FROM usage_log l
+    LEFT JOIN users u ON u.id = l.user_id";
+
+// Audit entries with the name of the user who made each change
+const HISTORY_SELECT: &str = "
+    SELECT a.id, a.change_date, a.change_details, a.action, a.user_id,
+           u.name AS user_name, a.changes
+    FROM audit_log a
+    LEFT JOIN users u ON u.id = a.user_id";
+
 /// List all items for an organization with optional filters
 #[utoipa::path(
     get,
@@ -34,7 +62,8 @@ const ITEM_SELECT: &str = "
         ItemFilterParams
     ),
     responses(
-        (status = 200, description = "List of items", body = PaginatedResponse<Item>),
+        (status = 200, description = "List of items, reduced to `fields` and extended by `include` when given", body = PaginatedResponse<Item>),
+        (status = 400, description = "Unknown field or include", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "items"
@@ -43,156 +72,77 @@ pub async fn list_items(
     State(state): State<AppState>,
     Path(org_id): Path<Uuid>,
     Query(filters): Query<ItemFilterParams>,
-) -> Result<Json<PaginatedResponse<Item>>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<PaginatedResponse<serde_json::Value>>, ApiError> {
     tracing::debug!(
-        "list_items called with filters: kind={:?}, state={:?}, location_id={:?}, search={:?}",
+        "list_items called with filters: kind={:?}, state={:?}, location_id={:?}, collection_id={:?}, search={:?}, barcode={:?}, catalog_code={:?}, disposed_since={:?}, tags={:?}, tag_match={:?}",
         filters.kind,
         filters.state,
         filters.location_id,
-        filters.search
+        filters.collection_id,
+        filters.search,
+        filters.barcode,
+        filters.catalog_code,
+        filters.disposed_since,
+        filters.tags,
+        filters.tag_match
     );
 
-    let offset = (filters.page - 1) * filters.per_page;
-
-    // Parse filter values
-    let kinds: Vec<String> = filters
-        .kind
-        .as_ref()
-        .map(|s| s.split(',').map(|t| t.trim().to_string()).collect())
-        .unwrap_or_default();
-
-    let states: Vec<String> = filters
-        .state
-        .as_ref()
-        .map(|s| s.split(',').map(|t| t.trim().to_string()).collect())
-        .unwrap_or_default();
-
-    let location_ids: Vec<Uuid> = filters
-        .location_id
-        .as_ref()
-        .map(|s| {
-            s.split(',')
-                .filter_map(|t| Uuid::parse_str(t.trim()).ok())
-                .collect()
-        })
-        .unwrap_or_default();
-
-    // Build dynamic WHERE clause (table-prefixed for the JOIN)
-    let mut where_clauses = vec!["i.organization_id = $1".to_string()];
-    let mut param_idx = 2;
-
-    if !kinds.is_empty() {
-        let placeholders: Vec<String> = kinds
-            .iter()
-            .enumerate()
-            .map(|(i, _)| format!("${}", param_idx + i))
-            .collect();
-        where_clauses.push(format!("k.name IN ({})", placeholders.join(", ")));
-        param_idx += kinds.len();
-    }
-
-    if !states.is_empty() {
-        let placeholders: Vec<String> = states
-            .iter()
-            .enumerate()
-            .map(|(i, _)| format!("${}", param_idx + i))
-            .collect();
-        where_clauses.push(format!("i.state::text IN ({})", placeholders.join(", ")));
-        param_idx += states.len();
-    }
-
-    if !location_ids.is_empty() {
-        let placeholders: Vec<String> = location_ids
-            .iter()
-            .enumerate()
-            .map(|(i, _)| format!("${}", param_idx + i))
-            .collect();
-        where_clauses.push(format!("i.location_id IN ({})", placeholders.join(", ")));
-        param_idx += location_ids.len();
-    }
-
-    let search_pattern = filters.search.as_ref().map(|s| format!("%{}%", s));
-    if search_pattern.is_some() {
-        where_clauses.push(format!(
-            "(i.name ILIKE ${p} OR i.description ILIKE ${p} OR i.notes ILIKE ${p})",
-            p = param_idx
-        ));
-        param_idx += 1;
-    }
-
-    let where_clause = where_clauses.join(" AND ");
+    let selection = Selection::parse(&filters)?;
+    let filter = ItemFilter::new(&filters);
+    let sort = ItemSort::new(&filters, &filter);
+    let cursor = filters
+        .after
+        .as_deref()
+        .map(|after| sort.parse_cursor(after))
+        .transpose()?;
 
     // Count query
-    let count_query = format!(
-        "SELECT COUNT(*) as count FROM items i JOIN kinds k ON k.id = i.kind_id WHERE {}",
-        where_clause
-    );
-    let mut count_builder = sqlx::query(&count_query).bind(org_id);
-    for k in &kinds {
-        count_builder = count_builder.bind(k);
-    }
-    for s in &states {
-        count_builder = count_builder.bind(s);
-    }
-    for loc in &location_ids {
-        count_builder = count_builder.bind(loc);
-    }
-    if let Some(ref pattern) = search_pattern {
-        count_builder = count_builder.bind(pattern);
-    }
-
-    let total: i64 = count_builder
+    let mut count_query =
+        QueryBuilder::new("SELECT COUNT(*) FROM items i JOIN kinds k ON k.id = i.kind_id WHERE ");
+    filter.push_conditions(&mut count_query, org_id);
+    let total: i64 = count_query
+        .build_query_scalar()
         .fetch_one(&state.pool)
-        .await
-        .map_err(internal_error)?
-        .get("count");
-
-    // ORDER BY — whitelist to prevent injection
-    let order_column = match filters.sort_by.as_deref() {
-        Some("name") => "i.name",
-        Some("kind") => "k.name",
-        Some("state") => "i.state",
-        Some("location_id") => "i.location_id",
-        Some("created_at") => "i.created_at",
-        _ => "i.name",
-    };
-    let order_direction = match filters.sort_order.as_deref() {
-        Some("desc") => "DESC",
-        _ => "ASC",
+        .await?;
+
+    let mut items_query = QueryBuilder::new(ITEM_SELECT);
+    items_query.push(" WHERE ");
+    filter.push_conditions(&mut items_query, org_id);
+
+    // A cursor replaces the offset with a condition on the sort key, so a deep
+    // page is found through the index instead of by skipping every row before it
+    let offset = match &cursor {
+        Some(cursor) => {
+            items_query.push(" AND ");
+            sort.push_after(&mut items_query, cursor);
+            0
+        }
+        None => (filters.page - 1) * filters.per_page,
     };
 
-    let items_query = format!(
-        "{} WHERE {} ORDER BY {} {} LIMIT ${} OFFSET ${}",
-        ITEM_SELECT,
-        where_clause,
-        order_column,
-        order_direction,
-        param_idx,
-        param_idx + 1
-    );
-
-    let mut items_builder = sqlx::query_as::<_, ItemRow>(&items_query).bind(org_id);
-    for k in &kinds {
-        items_builder = items_builder.bind(k);
-    }
-    for s in &states {
-        items_builder = items_builder.bind(s);
-    }
-    for loc in &location_ids {
-        items_builder = items_builder.bind(loc);
-    }
-    if let Some(ref pattern) = search_pattern {
-        items_builder = items_builder.bind(pattern);
-    }
-    items_builder = items_builder.bind(filters.per_page).bind(offset);
-
-    let items: Vec<Item> = items_builder
+    // One row beyond the page shows whether another page follows
+    items_query.push(" ORDER BY ");
+    sort.push_order_by(&mut items_query);
+    items_query
+        .push(" LIMIT ")
+        .push_bind(filters.per_page + 1)
+        .push(" OFFSET ")
+        .push_bind(offset);
+
+    let mut items: Vec<Item> = items_query
+        .build_query_as::<ItemRow>()
         .fetch_all(&state.pool)
-        .await
-        .map_err(internal_error)?
+        .await?
         .into_iter()
         .map(Into::into)
         .collect();
+    let has_more = items.len() as i64 > filters.per_page;
+    items.truncate(filters.per_page.max(0) as usize);
+    let next_cursor = match items.last() {
+        Some(last) if has_more => sort.cursor_after(last),
+        _ => None,
+    };
+    let items = selection.apply(&state.pool, items).await?;
 
     let total_pages = if total == 0 {
         1
@@ -206,9 +156,149 @@ pub async fn list_items(
         page: filters.page,
         per_page: filters.per_page,
         total_pages,
+        next_cursor,
     }))
 }
 
+/// Item counts per kind, state, location and tag under the given filters
+///
+/// Takes the same filters as the item list (paging, sorting, `fields` and
+/// `include` are ignored), so each count is the number of items the list would
+/// show with that value's filter added. Counts within a facet are largest first.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/items/facets",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ItemFilterParams
+    ),
+    responses(
+        (status = 200, description = "Item counts per filter value", body = ItemFacets),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn item_facets(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Query(filters): Query<ItemFilterParams>,
+) -> Result<Json<ItemFacets>, ApiError> {
+    let filter = ItemFilter::new(&filters);
+
+    // Every facet in one pass over the filtered items
+    let mut query = QueryBuilder::new(
+        "WITH filtered AS (
+             SELECT i.id, k.name AS kind_name, i.state::text AS state, i.location_id
+             FROM items i JOIN kinds k ON k.id = i.kind_id
+             WHERE ",
+    );
+    filter.push_conditions(&mut query, org_id);
+    query.push(
+        "
+         )
+         SELECT 'kind', f.kind_name, f.kind_name, COUNT(*)
+         FROM filtered f GROUP BY f.kind_name
+         UNION ALL
+         SELECT 'state', f.state, f.state, COUNT(*)
+         FROM filtered f GROUP BY f.state
+         UNION ALL
+         SELECT 'location', f.location_id::text, l.name, COUNT(*)
+         FROM filtered f LEFT JOIN locations l ON l.id = f.location_id
+         GROUP BY f.location_id, l.name
+         UNION ALL
+         SELECT 'tag', t.tag_name, t.tag_name, COUNT(*)
+         FROM filtered f JOIN item_tags t ON t.item_id = f.id GROUP BY t.tag_name
+         ORDER BY 4 DESC, 3 NULLS LAST",
+    );
+    let rows = query
+        .build_query_as::<(String, Option<String>, Option<String>, i64)>()
+        .fetch_all(&state.pool)
+        .await?;
+
+    let mut facets = ItemFacets {
+        total: 0,
+        kinds: Vec::new(),
+        states: Vec::new(),
+        locations: Vec::new(),
+        tags: Vec::new(),
+    };
+    for (facet, value, label, count) in rows {
+        let entry = FacetCount {
+            value,
+            label,
+            count,
+        };
+        match facet.as_str() {
+            "kind" => facets.kinds.push(entry),
+            "state" => {
+                // Every item has exactly one state
+                facets.total += entry.count;
+                facets.states.push(entry);
+            }
+            "location" => facets.locations.push(entry),
+            _ => facets.tags.push(entry),
+        }
+    }
+
+    Ok(Json(facets))
+}
+
+/// Suggest current items to play or use next
+///
+/// A weighted random pick, so repeated calls vary: the longer since an item was
+/// last used (or entered, if never used), the likelier it is to be suggested.
+/// Items used within `min_days` are skipped.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/items/rotation",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        RotationParams
+    ),
+    responses(
+        (status = 200, description = "Suggested items", body = Vec<Item>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn item_rotation(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Query(params): Query<RotationParams>,
+) -> Result<Json<Vec<Item>>, ApiError> {
+    let kinds: Vec<String> = params
+        .kind
+        .as_deref()
+        .map(|list| split_list(list).map(str::to_string).collect())
+        .unwrap_or_default();
+
+    // Ordering by the exponential key -ln(u)/w picks each item with
+    // probability proportional to its weight w, here days unused plus one
+    let query = format!(
+        "{} WHERE i.organization_id = $1
+           AND i.state = 'current'::item_state
+           AND (cardinality($2::text[]) = 0 OR k.name = ANY($2))
+           AND (i.last_used_at IS NULL
+                OR i.last_used_at < NOW() - make_interval(days => $3::int))
+         ORDER BY -ln(1 - random())
+           / (EXTRACT(EPOCH FROM NOW() - COALESCE(i.last_used_at, i.date_entered)) / 86400 + 1)
+         LIMIT $4",
+        ITEM_SELECT
+    );
+    let items = sqlx::query_as::<_, ItemRow>(&query)
+        .bind(org_id)
+        .bind(&kinds)
+        .bind(params.min_days.clamp(0, 36500) as i32)
+        .bind(params.count.clamp(1, 50))
+        .fetch_all(&state.pool)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(Json(items))
+}
+
 /// Get a single item by ID
 #[utoipa::path(
     get,
@@ -227,19 +317,44 @@ pub async fn list_items(
 pub async fn get_item(
     State(state): State<AppState>,
     Path((org_id, item_id)): Path<(Uuid, Uuid)>,
-) -> Result<Json<Item>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Item>, ApiError> {
+    match fetch_item(&state.pool, org_id, item_id).await? {
+        Some(item) => Ok(Json(item)),
+        None => Err(not_found()),
+    }
+}
+
+/// Load one item of the organization
+pub(super) async fn fetch_item(
+    pool: &PgPool,
+    org_id: Uuid,
+    item_id: Uuid,
+) -> Result<Option<Item>, sqlx::Error> {
     let query = format!("{} WHERE i.id = $1 AND i.organization_id = $2", ITEM_SELECT);
-    let item = sqlx::query_as::<_, ItemRow>(&query)
+    let row = sqlx::query_as::<_, ItemRow>(&query)
         .bind(item_id)
         .bind(org_id)
-        .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.map(Into::into))
+}
 
-    match item {
-        Some(row) => Ok(Json(row.into())),
-        None => Err(not_found()),
-    }
+/// Load several items of the organization, in no particular order
+pub(super) async fn fetch_items(
+    pool: &PgPool,
+    org_id: Uuid,
+    item_ids: &[Uuid],
+) -> Result<Vec<Item>, sqlx::Error> {
+    let query = format!(
+        "{} WHERE i.id = ANY($1) AND i.organization_id = $2",
+        ITEM_SELECT
+    );
+    let rows = sqlx::query_as::<_, ItemRow>(&query)
+        .bind(item_ids)
+        .bind(org_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.into_iter().map(Into::into).collect())
 }
 
 /// Create a new item
@@ -251,61 +366,417 @@ pub async fn get_item(
     ),
     request_body = CreateItemRequest,
     responses(
-        (status = 201, description = "Item created successfully", body = Item),
+        (status = 201, description = "Item created successfully, with any warnings", body = ItemWithWarnings),
         (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 403, description = "Creating tags needs the catalog:write permission", body = ErrorResponse),
+        (status = 409, description = "Barcode already used in this organization", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "items"
 )]
 pub async fn create_item(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+    ValidJson(req): ValidJson<CreateItemRequest>,
+) -> Result<(StatusCode, Json<ItemWithWarnings>), ApiError> {
+    if !auth.can(Permission::ItemsWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The items:write permission is required to change items",
+        ));
+    }
+
+    let can_create_tags = auth.can(Permission::CatalogWrite);
+    let mut tx = state.pool.begin().await?;
+    let item = insert_item(
+        &state.pool,
+        &mut tx,
+        org_id,
+        auth.user_id,
+        can_create_tags,
+        req,
+    )
+    .await?;
+    tx.commit().await?;
+
+    let warnings = warnings::item_warnings(&state.pool, &item).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ItemWithWarnings { item, warnings }),
+    ))
+}
+
+/// Create several items in one transaction
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/items/bulk",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = BulkCreateItemsRequest,
+    responses(
+        (status = 201, description = "Items created successfully, in request order, with any warnings", body = Vec<ItemWithWarnings>),
+        (status = 400, description = "Invalid input; the message names the first failing item", body = ErrorResponse),
+        (status = 403, description = "Creating tags needs the catalog:write permission", body = ErrorResponse),
+        (status = 409, description = "Barcode already used in this organization", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn bulk_create_items(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+    ValidJson(req): ValidJson<BulkCreateItemsRequest>,
+) -> Result<(StatusCode, Json<Vec<ItemWithWarnings>>), ApiError> {
+    if !auth.can(Permission::ItemsWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The items:write permission is required to change items",
+        ));
+    }
+    if req.items.is_empty() {
+        return Err(ApiError::validation_failed("No items given"));
+    }
+    if req.items.len() > BulkCreateItemsRequest::MAX_ITEMS {
+        return Err(ApiError::validation_failed(format!(
+            "At most {} items may be created at once",
+            BulkCreateItemsRequest::MAX_ITEMS
+        )));
+    }
+
+    let can_create_tags = auth.can(Permission::CatalogWrite);
+    let mut tx = state.pool.begin().await?;
+    let mut items = Vec::with_capacity(req.items.len());
+    for (index, item_req) in req.items.into_iter().enumerate() {
+        let item = insert_item(
+            &state.pool,
+            &mut tx,
+            org_id,
+            auth.user_id,
+            can_create_tags,
+            item_req,
+        )
+        .await
+        .map_err(|e| e.prefixed(&format!("Item {}: ", index + 1)))?;
+        items.push(item);
+    }
+    tx.commit().await?;
+
+    let mut created = Vec::with_capacity(items.len());
+    for item in items {
+        let warnings = warnings::item_warnings(&state.pool, &item).await?;
+        created.push(ItemWithWarnings { item, warnings });
+    }
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Change several items in one transaction
+///
+/// Every item gets the same change: a new location, a new state, tags added
+/// or removed. Each records its own history entry and `item.updated` event.
+/// If any item is not found, nothing changes.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/items/bulk-update",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = BulkUpdateItemsRequest,
+    responses(
+        (status = 200, description = "Items updated, in request order", body = Vec<Item>),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 403, description = "Creating tags needs the catalog:write permission", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn bulk_update_items(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
     Path(org_id): Path<Uuid>,
-    Json(req): Json<CreateItemRequest>,
-) -> Result<(StatusCode, Json<Item>), (StatusCode, Json<ErrorResponse>)> {
+    ValidJson(req): ValidJson<BulkUpdateItemsRequest>,
+) -> Result<Json<Vec<Item>>, ApiError> {
+    if !auth.can(Permission::ItemsWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The items:write permission is required to change items",
+        ));
+    }
+    if req.item_ids.is_empty() {
+        return Err(ApiError::validation_failed("No items given"));
+    }
+    if req.item_ids.len() > BulkUpdateItemsRequest::MAX_ITEMS {
+        return Err(ApiError::validation_failed(format!(
+            "At most {} items may be changed at once",
+            BulkUpdateItemsRequest::MAX_ITEMS
+        )));
+    }
+    if req.location_id.is_none()
+        && req.state.is_none()
+        && req.add_tags.is_empty()
+        && req.remove_tags.is_empty()
+    {
+        return Err(ApiError::validation_failed("No changes given"));
+    }
+    if req.state == Some(ItemState::Loaned) {
+        return Err(ApiError::validation_failed(
+            "Items are lent one at a time, each with its borrower",
+        ));
+    }
+    if let Some(location_id) = req.location_id {
+        let location_exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM locations WHERE id = $1 AND organization_id = $2)",
+        )
+        .bind(location_id)
+        .bind(org_id)
+        .fetch_one(&state.pool)
+        .await?;
+        if !location_exists {
+            return Err(ApiError::validation_failed("Location not found"));
+        }
+    }
+
+    let mut item_ids = Vec::with_capacity(req.item_ids.len());
+    for item_id in &req.item_ids {
+        if !item_ids.contains(item_id) {
+            item_ids.push(*item_id);
+        }
+    }
+    let add_tags: Vec<String> = req.add_tags.iter().map(|t| t.trim().to_string()).collect();
+    let remove_tags: Vec<String> = req
+        .remove_tags
+        .iter()
+        .map(|t| t.trim().to_string())
+        .collect();
+    let update = UpdateItemRequest {
+        location_id: req.location_id,
+        state: req.state,
+        ..Default::default()
+    };
+    let can_create_tags = auth.can(Permission::CatalogWrite);
+
+    let mut tx = state.pool.begin().await?;
+    // Lock in id order, so two bulk updates sharing items cannot deadlock
+    let locked: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM items WHERE id = ANY($1) AND organization_id = $2
+         ORDER BY id FOR UPDATE",
+    )
+    .bind(&item_ids)
+    .bind(org_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    if locked.len() != item_ids.len() {
+        return Err(not_found());
+    }
+
+    let mut items = Vec::with_capacity(item_ids.len());
+    for item_id in item_ids {
+        let before = lock_item(&mut tx, org_id, item_id)
+            .await?
+            .ok_or_else(not_found)?;
+        let tags_before = item_tags(&mut tx, item_id).await?;
+        let item: Item = item_update_query(item_id, org_id, &update)
+            .build_query_as::<ItemRow>()
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(write_error)?
+            .ok_or_else(not_found)?
+            .into();
+
+        if before.state == ItemState::Loaned && item.state != ItemState::Loaned {
+            borrowers::close_loan(&mut *tx, item_id).await?;
+        }
+        if !remove_tags.is_empty() {
+            sqlx::query("DELETE FROM item_tags WHERE item_id = $1 AND tag_name = ANY($2)")
+                .bind(item_id)
+                .bind(&remove_tags)
+                .execute(&mut *tx)
+                .await?;
+        }
+        if !add_tags.is_empty() {
+            tag_item(&mut tx, org_id, item_id, &add_tags, can_create_tags).await?;
+        }
+
+        let payload = item_payload(&item);
+        let mut changes = audit::diff(&item_payload(&before), &payload);
+        // Tags are not part of the item; record them as the item's own field
+        let tags_after = item_tags(&mut tx, item_id).await?;
+        if tags_after != tags_before {
+            changes["tags"] = serde_json::json!({ "from": tags_before, "to": tags_after });
+        }
+        if changes.as_object().is_some_and(|c| !c.is_empty()) {
+            let action = if before.state != item.state {
+                AuditAction::StateChanged
+            } else {
+                AuditAction::Updated
+            };
+            audit::record(&mut tx, org_id, item_id, auth.user_id, action, changes).await?;
+        }
+        outbox::enqueue(&mut tx, org_id, outbox::ITEM_UPDATED, payload).await?;
+        items.push(item);
+    }
+    tx.commit().await?;
+
+    Ok(Json(items))
+}
+
+/// An item's tag names, in order
+async fn item_tags(
+    conn: &mut sqlx::PgConnection,
+    item_id: Uuid,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT tag_name FROM item_tags WHERE item_id = $1 ORDER BY tag_name")
+        .bind(item_id)
+        .fetch_all(conn)
+        .await
+}
+
+/// Validate and insert one item with its tags, recording its audit entry and
+/// outbox event
+pub(crate) async fn insert_item(
+    pool: &PgPool,
+    conn: &mut sqlx::PgConnection,
+    org_id: Uuid,
+    user_id: Uuid,
+    can_create_tags: bool,
+    req: CreateItemRequest,
+) -> Result<Item, ApiError> {
     // Validate kind exists (shared kinds have NULL org_id, org kinds must match)
     let kind_exists: bool = sqlx::query_scalar(
         "SELECT EXISTS(SELECT 1 FROM kinds WHERE id = $1 AND (org_id IS NULL OR org_id = $2))",
     )
     .bind(req.kind_id)
     .bind(org_id)
-    .fetch_one(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .fetch_one(pool)
+    .await?;
 
     if !kind_exists {
-        return Err(bad_request("invalid_kind", "Kind not found"));
+        return Err(ApiError::validation_failed("Kind not found"));
     }
 
+    validate_measurements([req.width_mm, req.height_mm, req.depth_mm, req.weight_g])?;
+
     let soft_fields = req.soft_fields.unwrap_or(serde_json::json!({}));
 
-    validate_soft_fields(&state.pool, req.kind_id, &soft_fields)
-        .await
-        .map_err(|e| bad_request("invalid_soft_fields", &e.to_string()))?;
+    validate_soft_fields(pool, req.kind_id, &soft_fields).await?;
 
-    let query = format!(
-        "INSERT INTO items
-         (organization_id, kind_id, state, name, description, notes, location_id, date_acquired, soft_fields)
-         VALUES ($1, $2, 'current'::item_state, $3, $4, $5, $6, $7, $8)
+    let query = "INSERT INTO items
+         (organization_id, kind_id, state, name, description, notes, location_id, date_acquired,
+          barcode, catalog_code, width_mm, height_mm, depth_mm, weight_g, soft_fields)
+         VALUES ($1, $2, 'current'::item_state, $3, $4, $5, $6, $7,
+                 NULLIF(TRIM($8), ''), NULLIF(TRIM($9), ''),
+                 NULLIF($10, 0), NULLIF($11, 0), NULLIF($12, 0), NULLIF($13, 0), $14)
          RETURNING id, organization_id, kind_id,
            (SELECT name FROM kinds WHERE id = kind_id) AS kind_name,
            state::text, name, description, notes,
-           location_id, date_entered, date_acquired, created_at, updated_at, soft_fields"
-    );
+           location_id, date_entered, date_acquired, barcode, catalog_code,
+           width_mm, height_mm, depth_mm, weight_g, last_used_at, created_at, updated_at, soft_fields";
 
-    let row = sqlx::query_as::<_, ItemRow>(&query)
+    let row = sqlx::query_as::<_, ItemRow>(query)
         .bind(org_id)
         .bind(req.kind_id)
         .bind(&req.name)
         .bind(&req.description)
         .bind(&req.notes)
-        .bind(&req.location_id)
-        .bind(&req.date_acquired)
+        .bind(req.location_id)
+        .bind(req.date_acquired)
+        .bind(&req.barcode)
+        .bind(&req.catalog_code)
+        .bind(req.width_mm)
+        .bind(req.height_mm)
+        .bind(req.depth_mm)
+        .bind(req.weight_g)
         .bind(&soft_fields)
-        .fetch_one(&state.pool)
+        .fetch_one(&mut *conn)
         .await
-        .map_err(internal_error)?;
+        .map_err(write_error)?;
+
+    let item: Item = row.into();
+    let payload = item_payload(&item);
+    audit::record(
+        conn,
+        org_id,
+        item.id,
+        user_id,
+        AuditAction::Created,
+        audit::diff(&serde_json::json!({}), &payload),
+    )
+    .await?;
+    outbox::enqueue(conn, org_id, outbox::ITEM_CREATED, payload).await?;
+
+    if let Some(tags) = &req.tags {
+        tag_item(conn, org_id, item.id, tags, can_create_tags).await?;
+    }
+
+    Ok(item)
+}
+
+/// Tag an item, first creating the tags the organization lacks; tags it
+/// already has are kept
+async fn tag_item(
+    conn: &mut sqlx::PgConnection,
+    org_id: Uuid,
+    item_id: Uuid,
+    tags: &[String],
+    can_create_tags: bool,
+) -> Result<(), ApiError> {
+    let mut names: Vec<String> = tags.iter().map(|t| t.trim().to_string()).collect();
+    names.sort();
+    names.dedup();
+
+    let existing: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM tags WHERE organization_id = $1 AND name = ANY($2)")
+            .bind(org_id)
+            .bind(&names)
+            .fetch_all(&mut *conn)
+            .await?;
+    let missing: Vec<&String> = names.iter().filter(|n| !existing.contains(n)).collect();
+    if !missing.is_empty() && !can_create_tags {
+        return Err(ApiError::auth_forbidden(format!(
+            "The catalog:write permission is required to create tags: {}",
+            missing
+                .iter()
+                .map(|n| n.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+    for name in missing {
+        // Another request may have created it meanwhile
+        let Some(tag) = sqlx::query_as::<_, Tag>(
+            "INSERT INTO tags (organization_id, name) VALUES ($1, $2)
+             ON CONFLICT DO NOTHING
+             RETURNING organization_id, name, created_at",
+        )
+        .bind(org_id)
+        .bind(name)
+        .fetch_optional(&mut *conn)
+        .await?
+        else {
+            continue;
+        };
+        outbox::enqueue(
+            conn,
+            org_id,
+            outbox::TAG_CREATED,
+            serde_json::to_value(&tag).unwrap_or_default(),
+        )
+        .await?;
+    }
 
-    Ok((StatusCode::CREATED, Json(row.into())))
+    sqlx::query(
+        "INSERT INTO item_tags (item_id, organization_id, tag_name)
+         SELECT $1, $2, UNNEST($3::text[])
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(item_id)
+    .bind(org_id)
+    .bind(&names)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
 }
 
 /// Update an existing item
@@ -318,17 +789,26 @@ pub async fn create_item(
     ),
     request_body = UpdateItemRequest,
     responses(
-        (status = 200, description = "Item updated successfully", body = Item),
+        (status = 200, description = "Item updated successfully, with any warnings", body = ItemWithWarnings),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 403, description = "Loan limit exceeded, or override not permitted", body = ErrorResponse),
         (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 409, description = "Barcode already used in this organization", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "items"
 )]
 pub async fn update_item(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
     Path((org_id, item_id)): Path<(Uuid, Uuid)>,
-    Json(req): Json<UpdateItemRequest>,
-) -> Result<Json<Item>, (StatusCode, Json<ErrorResponse>)> {
+    ValidJson(req): ValidJson<UpdateItemRequest>,
+) -> Result<Json<ItemWithWarnings>, ApiError> {
+    if !auth.can(Permission::ItemsWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The items:write permission is required to change items",
+        ));
+    }
     // Fetch current item to get kind_id and state for validation
     let current = sqlx::query(
         "SELECT kind_id, state::text FROM items WHERE id = $1 AND organization_id = $2",
@@ -336,147 +816,173 @@ pub async fn update_item(
     .bind(item_id)
     .bind(org_id)
     .fetch_optional(&state.pool)
-    .await
-    .map_err(internal_error)?
+    .await?
     .ok_or_else(not_found)?;
 
     let kind_id: Uuid = current.get("kind_id");
     let state_str: String = current.get("state");
+    // State-specific details apply to the state the item ends up in
+    let new_state = req
+        .state
+        .as_ref()
+        .map_or(state_str.as_str(), |s| s.as_str());
+
+    validate_measurements([req.width_mm, req.height_mm, req.depth_mm, req.weight_g])?;
 
     // Validate soft_fields if provided
     if let Some(ref sf) = req.soft_fields {
-        validate_soft_fields(&state.pool, kind_id, sf)
-            .await
-            .map_err(|e| bad_request("invalid_soft_fields", &e.to_string()))?;
+        validate_soft_fields(&state.pool, kind_id, sf).await?;
     }
 
-    // Build dynamic UPDATE
-    let mut query = String::from("UPDATE items SET updated_at = NOW()");
-    let mut param_num = 3; // $1 = item_id, $2 = org_id
-
-    if req.name.is_some() {
-        query.push_str(&format!(", name = ${}", param_num));
-        param_num += 1;
-    }
-    if req.description.is_some() {
-        query.push_str(&format!(", description = ${}", param_num));
-        param_num += 1;
-    }
-    if req.notes.is_some() {
-        query.push_str(&format!(", notes = ${}", param_num));
-        param_num += 1;
-    }
-    if req.location_id.is_some() {
-        query.push_str(&format!(", location_id = ${}", param_num));
-        param_num += 1;
-    }
-    if req.date_acquired.is_some() {
-        query.push_str(&format!(", date_acquired = ${}", param_num));
-        param_num += 1;
-    }
-    if req.state.is_some() {
-        query.push_str(&format!(", state = ${}::item_state", param_num));
-        param_num += 1;
-    }
-    if req.soft_fields.is_some() {
-        // Merge: existing || new (new keys overwrite, absent keys preserved)
-        query.push_str(&format!(", soft_fields = soft_fields || ${}", param_num));
-        let _ = param_num; // last use of param_num
+    // Enforce the org's loan limits when lending the item or changing its loan
+    let has_loan = req.loan_date_loaned.is_some()
+        || req.loan_date_due_back.is_some()
+        || req.loan_loaned_to.is_some();
+    if new_state == "loaned" && (has_loan || state_str != "loaned") {
+        if req.override_loan_limits {
+            if !auth.can(Permission::LoansOverride) {
+                return Err(ApiError::auth_forbidden(
+                    "The loans:override permission is required to override loan limits",
+                ));
+            }
+        } else {
+            // Fields not in the request keep their stored values (see the upsert below)
+            let stored = sqlx::query_as::<_, LoanDetailsRow>(
+                "SELECT item_id, date_loaned, date_due_back, loaned_to, NULL::uuid AS borrower_id
+                 FROM item_loan_details WHERE item_id = $1",
+            )
+            .bind(item_id)
+            .fetch_optional(&state.pool)
+            .await?;
+
+            let borrower = req
+                .loan_loaned_to
+                .as_deref()
+                .or(stored.as_ref().map(|s| s.loaned_to.as_str()));
+            let date_loaned = req
+                .loan_date_loaned
+                .or(stored.as_ref().map(|s| s.date_loaned))
+                .unwrap_or_else(|| chrono::Local::now().date_naive());
+            let date_due_back = req
+                .loan_date_due_back
+                .or(stored.as_ref().and_then(|s| s.date_due_back));
+
+            if let Some(violation) = borrowers::loan_limit_violation(
+                &state.pool,
+                org_id,
+                item_id,
+                borrower,
+                date_loaned,
+                date_due_back,
+            )
+            .await?
+            {
+                return Err(ApiError::forbidden(
+                    ErrorCode::LoanLimitExceeded,
+                    &violation,
+                ));
+            }
+        }
     }
 
-    query.push_str(&format!(
-        " WHERE id = $1 AND organization_id = $2
-          RETURNING id, organization_id, kind_id,
-            (SELECT name FROM kinds WHERE id = kind_id) AS kind_name,
-            state::text, name, description, notes,
-            location_id, date_entered, date_acquired, created_at, updated_at, soft_fields"
-    ));
-
-    let mut qb = sqlx::query_as::<_, ItemRow>(&query)
-        .bind(item_id)
-        .bind(org_id);
+    let mut query = item_update_query(item_id, org_id, &req);
 
-    if let Some(ref v) = req.name {
-        qb = qb.bind(v);
-    }
-    if let Some(ref v) = req.description {
-        qb = qb.bind(v);
-    }
-    if let Some(ref v) = req.notes {
-        qb = qb.bind(v);
-    }
-    if let Some(ref v) = req.location_id {
-        qb = qb.bind(v);
-    }
-    if let Some(ref v) = req.date_acquired {
-        qb = qb.bind(v);
-    }
-    if let Some(ref v) = req.state {
-        qb = qb.bind(item_state_to_db(v));
-    }
-    if let Some(ref v) = req.soft_fields {
-        qb = qb.bind(v);
-    }
-
-    let row = qb
-        .fetch_optional(&state.pool)
+    // The item and its state details change together with the audit entry and outbox event
+    let mut tx = state.pool.begin().await?;
+    let before = lock_item(&mut tx, org_id, item_id)
+        .await?
+        .ok_or_else(not_found)?;
+    let row = query
+        .build_query_as::<ItemRow>()
+        .fetch_optional(&mut *tx)
         .await
-        .map_err(internal_error)?
+        .map_err(write_error)?
         .ok_or_else(not_found)?;
 
     let item: Item = row.into();
 
     // Upsert loan details
-    let has_loan = req.loan_date_loaned.is_some()
-        || req.loan_date_due_back.is_some()
-        || req.loan_loaned_to.is_some();
-    if has_loan && state_str == "loaned" {
+    if has_loan && new_state == "loaned" {
         sqlx::query(
             "INSERT INTO item_loan_details (item_id, date_loaned, date_due_back, loaned_to)
-             VALUES ($1, $2, $3, $4)
+             VALUES ($1, COALESCE($2, CURRENT_DATE), $3, $4)
              ON CONFLICT (item_id) DO UPDATE SET
                date_loaned  = COALESCE($2, item_loan_details.date_loaned),
                date_due_back = COALESCE($3, item_loan_details.date_due_back),
                loaned_to    = COALESCE($4, item_loan_details.loaned_to)",
         )
         .bind(item_id)
-        .bind(&req.loan_date_loaned)
-        .bind(&req.loan_date_due_back)
+        .bind(req.loan_date_loaned)
+        .bind(req.loan_date_due_back)
         .bind(&req.loan_loaned_to)
-        .execute(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .execute(&mut *tx)
+        .await?;
+
+        borrowers::record_loan(&mut *tx, org_id, item_id).await?;
+    } else if state_str == "loaned" && new_state != "loaned" {
+        borrowers::close_loan(&mut *tx, item_id).await?;
     }
 
     // Upsert missing details
-    if req.missing_date_missing.is_some() && state_str == "missing" {
+    if req.missing_date_missing.is_some() && new_state == "missing" {
         sqlx::query(
             "INSERT INTO item_missing_details (item_id, date_missing) VALUES ($1, $2)
              ON CONFLICT (item_id) DO UPDATE SET
                date_missing = COALESCE($2, item_missing_details.date_missing)",
         )
         .bind(item_id)
-        .bind(&req.missing_date_missing)
-        .execute(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .bind(req.missing_date_missing)
+        .execute(&mut *tx)
+        .await?;
     }
 
     // Upsert disposed details
-    if req.disposed_date_disposed.is_some() && state_str == "disposed" {
+    if req.disposed_date_disposed.is_some() && new_state == "disposed" {
         sqlx::query(
             "INSERT INTO item_disposed_details (item_id, date_disposed) VALUES ($1, $2)
              ON CONFLICT (item_id) DO UPDATE SET
                date_disposed = COALESCE($2, item_disposed_details.date_disposed)",
         )
         .bind(item_id)
-        .bind(&req.disposed_date_disposed)
-        .execute(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .bind(req.disposed_date_disposed)
+        .execute(&mut *tx)
+        .await?;
     }
 
-    Ok(Json(item))
+    let payload = item_payload(&item);
+    let mut changes = audit::diff(&item_payload(&before), &payload);
+    // State details live in their own tables; record the values the request set
+    for (field, value) in [
+        ("loan_date_loaned", json_or_null(req.loan_date_loaned)),
+        ("loan_date_due_back", json_or_null(req.loan_date_due_back)),
+        ("loan_loaned_to", json_or_null(req.loan_loaned_to.as_ref())),
+        (
+            "missing_date_missing",
+            json_or_null(req.missing_date_missing),
+        ),
+        (
+            "disposed_date_disposed",
+            json_or_null(req.disposed_date_disposed),
+        ),
+    ] {
+        if !value.is_null() {
+            changes[field] = serde_json::json!({ "to": value });
+        }
+    }
+    if changes.as_object().is_some_and(|c| !c.is_empty()) {
+        let action = if before.state != item.state {
+            AuditAction::StateChanged
+        } else {
+            AuditAction::Updated
+        };
+        audit::record(&mut tx, org_id, item_id, auth.user_id, action, changes).await?;
+    }
+    outbox::enqueue(&mut tx, org_id, outbox::ITEM_UPDATED, payload).await?;
+    tx.commit().await?;
+
+    let warnings = warnings::item_warnings(&state.pool, &item).await?;
+
+    Ok(Json(ItemWithWarnings { item, warnings }))
 }
 
 /// Delete an item
@@ -496,20 +1002,191 @@ pub async fn update_item(
 )]
 pub async fn delete_item(
     State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
     Path((org_id, item_id)): Path<(Uuid, Uuid)>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    let result = sqlx::query("DELETE FROM items WHERE id = $1 AND organization_id = $2")
+) -> Result<StatusCode, ApiError> {
+    if !auth.can(Permission::ItemsWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The items:write permission is required to change items",
+        ));
+    }
+    let mut tx = state.pool.begin().await?;
+    let item = lock_item(&mut tx, org_id, item_id)
+        .await?
+        .ok_or_else(not_found)?;
+
+    let photo_keys: Vec<String> =
+        sqlx::query_scalar("SELECT storage_key FROM item_photos WHERE item_id = $1")
+            .bind(item_id)
+            .fetch_all(&mut *tx)
+            .await?;
+
+    sqlx::query("DELETE FROM items WHERE id = $1 AND organization_id = $2")
         .bind(item_id)
         .bind(org_id)
-        .execute(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .execute(&mut *tx)
+        .await?;
+
+    audit::record(
+        &mut tx,
+        org_id,
+        item_id,
+        auth.user_id,
+        AuditAction::Deleted,
+        audit::diff(&item_payload(&item), &serde_json::json!({})),
+    )
+    .await?;
 
-    if result.rows_affected() == 0 {
-        Err(not_found())
-    } else {
-        Ok(StatusCode::NO_CONTENT)
+    outbox::enqueue(
+        &mut tx,
+        org_id,
+        outbox::ITEM_DELETED,
+        serde_json::json!({ "id": item_id }),
+    )
+    .await?;
+    tx.commit().await?;
+
+    photos::remove_blobs(&state, &photo_keys).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Log a use of an item: it was played, read or otherwise used
+///
+/// Adds an entry to the item's usage log and moves `last_used_at` forward.
+/// Uses are not recorded in the item's history.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/items/{item_id}/used",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("item_id" = Uuid, Path, description = "Item ID")
+    ),
+    request_body = LogUsageRequest,
+    responses(
+        (status = 200, description = "Use logged; the updated item", body = Item),
+        (status = 400, description = "used_at is in the future", body = ErrorResponse),
+        (status = 403, description = "Missing the items:write permission", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn mark_item_used(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, item_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<LogUsageRequest>,
+) -> Result<Json<Item>, ApiError> {
+    if !auth.can(Permission::ItemsWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The items:write permission is required to change items",
+        ));
     }
+    let used_at = req.used_at.unwrap_or_else(chrono::Utc::now);
+    if used_at > chrono::Utc::now() {
+        return Err(ApiError::validation_failed(
+            "used_at must not be in the future",
+        ));
+    }
+    let note = req
+        .note
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty());
+
+    let mut tx = state.pool.begin().await?;
+    let row = sqlx::query_as::<_, ItemRow>(
+        "UPDATE items SET last_used_at = GREATEST(last_used_at, $3)
+         WHERE id = $1 AND organization_id = $2
+         RETURNING id, organization_id, kind_id,
+           (SELECT name FROM kinds WHERE id = kind_id) AS kind_name,
+           state::text, name, description, notes,
+           location_id, date_entered, date_acquired, barcode, catalog_code,
+           width_mm, height_mm, depth_mm, weight_g, last_used_at, created_at, updated_at, soft_fields",
+    )
+    .bind(item_id)
+    .bind(org_id)
+    .bind(used_at)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(not_found)?;
+
+    sqlx::query(
+        "INSERT INTO usage_log (organization_id, item_id, user_id, used_at, note)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(org_id)
+    .bind(item_id)
+    .bind(auth.user_id)
+    .bind(used_at)
+    .bind(&note)
+    .execute(&mut *tx)
+    .await?;
+
+    let item: Item = row.into();
+    outbox::enqueue(&mut tx, org_id, outbox::ITEM_UPDATED, item_payload(&item)).await?;
+    tx.commit().await?;
+
+    Ok(Json(item))
+}
+
+/// Usage log for an item, newest first (paginated)
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/items/{item_id}/usage",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("item_id" = Uuid, Path, description = "Item ID"),
+        PaginationParams
+    ),
+    responses(
+        (status = 200, description = "Logged uses", body = PaginatedResponse<UsageEntry>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn get_item_usage(
+    State(state): State<AppState>,
+    Path((org_id, item_id)): Path<(Uuid, Uuid)>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Json<PaginatedResponse<UsageEntry>>, ApiError> {
+    let offset = (pagination.page - 1) * pagination.per_page;
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM usage_log WHERE item_id = $1 AND organization_id = $2",
+    )
+    .bind(item_id)
+    .bind(org_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let items = sqlx::query_as::<_, UsageEntry>(&format!(
+        "{} WHERE l.item_id = $1 AND l.organization_id = $2
+         ORDER BY l.used_at DESC, l.id
+         LIMIT $3 OFFSET $4",
+        USAGE_SELECT
+    ))
+    .bind(item_id)
+    .bind(org_id)
+    .bind(pagination.per_page)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let total_pages = if total == 0 {
+        1
+    } else {
+        (total + pagination.per_page - 1) / pagination.per_page
+    };
+
+    Ok(Json(PaginatedResponse {
+        items,
+        total,
+        page: pagination.page,
+        per_page: pagination.per_page,
+        total_pages,
+        next_cursor: None,
+    }))
 }
 
 /// Get full details for a single item (including state-specific details)
@@ -530,85 +1207,813 @@ pub async fn delete_item(
 pub async fn get_item_details(
     State(state): State<AppState>,
     Path((org_id, item_id)): Path<(Uuid, Uuid)>,
-) -> Result<Json<ItemFullDetails>, (StatusCode, Json<ErrorResponse>)> {
-    let query = format!("{} WHERE i.id = $1 AND i.organization_id = $2", ITEM_SELECT);
-    let item_row = sqlx::query_as::<_, ItemRow>(&query)
-        .bind(item_id)
-        .bind(org_id)
-        .fetch_optional(&state.pool)
+) -> Result<Json<ItemFullDetails>, ApiError> {
+    fetch_item_details(&state.pool, org_id, item_id)
         .await
-        .map_err(internal_error)?
-        .ok_or_else(not_found)?;
+        .map(Json)
+}
 
-    let state_str = item_row.state.clone();
-    let item: Item = item_row.into();
+/// Get full details for several items at once
+///
+/// Returns the details in the order the ids were given. Ids of items that do
+/// not exist in the organization are left out.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/items/details",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = ItemDetailsRequest,
+    responses(
+        (status = 200, description = "Item full details, in request order", body = Vec<ItemFullDetails>),
+        (status = 400, description = "Too many item ids", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn get_items_details(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<ItemDetailsRequest>,
+) -> Result<Json<Vec<ItemFullDetails>>, ApiError> {
+    if req.item_ids.len() > ItemDetailsRequest::MAX_ITEMS {
+        return Err(ApiError::validation_failed(format!(
+            "At most {} items may be fetched at once",
+            ItemDetailsRequest::MAX_ITEMS
+        )));
+    }
 
-    let loan_details = if state_str == "loaned" {
-        sqlx::query_as::<_, LoanDetailsRow>(
-            "SELECT item_id, date_loaned, date_due_back, loaned_to
-             FROM item_loan_details WHERE item_id = $1",
-        )
-        .bind(item_id)
-        .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?
-        .map(|r| LoanDetails {
-            item_id: r.item_id,
-            date_loaned: r.date_loaned,
-            date_due_back: r.date_due_back,
-            loaned_to: r.loaned_to,
-        })
-    } else {
-        None
-    };
+    let mut found: HashMap<Uuid, ItemFullDetails> = sqlx::query_as::<_, ItemDetailsRow>(
+        &item_details_query("i.id = ANY($1) AND i.organization_id = $2"),
+    )
+    .bind(&req.item_ids)
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?
+    .into_iter()
+    .map(|row| {
+        let details = ItemFullDetails::from(row);
+        (details.item.id, details)
+    })
+    .collect();
+
+    // Request order; a repeated id is returned once
+    Ok(Json(
+        req.item_ids
+            .iter()
+            .filter_map(|id| found.remove(id))
+            .collect(),
+    ))
+}
 
-    let missing_details = if state_str == "missing" {
-        sqlx::query_as::<_, MissingDetailsRow>(
-            "SELECT item_id, date_missing FROM item_missing_details WHERE item_id = $1",
-        )
-        .bind(item_id)
-        .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?
-        .map(|r| MissingDetails {
-            item_id: r.item_id,
-            date_missing: r.date_missing,
-        })
-    } else {
-        None
-    };
+/// Number of audit entries and logged uses returned by get_item_full
+const ITEM_HISTORY_LIMIT: i64 = 20;
 
-    let disposed_details = if state_str == "disposed" {
-        sqlx::query_as::<_, DisposedDetailsRow>(
-            "SELECT item_id, date_disposed FROM item_disposed_details WHERE item_id = $1",
-        )
-        .bind(item_id)
-        .fetch_optional(&state.pool)
+/// Get everything the expanded item view shows in one call: the item, its
+/// state-specific details, tags, collections, recent history and recent uses
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/items/{item_id}/full",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("item_id" = Uuid, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 200, description = "Item with related data", body = ItemAggregate),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn get_item_full(
+    State(state): State<AppState>,
+    Path((org_id, item_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ItemAggregate>, ApiError> {
+    let details = fetch_item_details(&state.pool, org_id, item_id).await?;
+
+    let tags: Vec<String> =
+        sqlx::query_scalar("SELECT tag_name FROM item_tags WHERE item_id = $1 ORDER BY tag_name")
+            .bind(item_id)
+            .fetch_all(&state.pool)
+            .await?;
+
+    let collections = sqlx::query_as::<_, Collection>(
+        "SELECT c.id, c.organization_id, c.name, c.description, c.notes, c.cover_item_id,
+                c.sort_order, c.created_at, c.updated_at
+         FROM collections c
+         JOIN item_collections ic ON ic.collection_id = c.id
+         WHERE ic.item_id = $1
+         ORDER BY c.sort_order, c.name",
+    )
+    .bind(item_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let history = sqlx::query_as::<_, ItemHistoryEntry>(&format!(
+        "{} WHERE a.item_id = $1 AND a.organization_id = $2
+         ORDER BY a.change_date DESC
+         LIMIT $3",
+        HISTORY_SELECT
+    ))
+    .bind(item_id)
+    .bind(org_id)
+    .bind(ITEM_HISTORY_LIMIT)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let usage = sqlx::query_as::<_, UsageEntry>(&format!(
+        "{} WHERE l.item_id = $1 AND l.organization_id = $2
+         ORDER BY l.used_at DESC
+         LIMIT $3",
+        USAGE_SELECT
+    ))
+    .bind(item_id)
+    .bind(org_id)
+    .bind(ITEM_HISTORY_LIMIT)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let photos = photos::item_photos(&state, org_id, item_id)
         .await
-        .map_err(internal_error)?
-        .map(|r| DisposedDetails {
-            item_id: r.item_id,
-            date_disposed: r.date_disposed,
-        })
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(ItemAggregate {
+        details,
+        tags,
+        collections,
+        history,
+        usage,
+        photos,
+    }))
+}
+
+/// Audit history for an item, newest first
+///
+/// Entries outlive the item, so a deleted item's history is still listed.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/items/{item_id}/history",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("item_id" = Uuid, Path, description = "Item ID"),
+        PaginationParams
+    ),
+    responses(
+        (status = 200, description = "Item audit history", body = PaginatedResponse<ItemHistoryEntry>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn get_item_history(
+    State(state): State<AppState>,
+    Path((org_id, item_id)): Path<(Uuid, Uuid)>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Json<PaginatedResponse<ItemHistoryEntry>>, ApiError> {
+    let offset = (pagination.page - 1) * pagination.per_page;
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM audit_log WHERE item_id = $1 AND organization_id = $2",
+    )
+    .bind(item_id)
+    .bind(org_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let items = sqlx::query_as::<_, ItemHistoryEntry>(&format!(
+        "{} WHERE a.item_id = $1 AND a.organization_id = $2
+         ORDER BY a.change_date DESC, a.id
+         LIMIT $3 OFFSET $4",
+        HISTORY_SELECT
+    ))
+    .bind(item_id)
+    .bind(org_id)
+    .bind(pagination.per_page)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let total_pages = if total == 0 {
+        1
     } else {
-        None
+        (total + pagination.per_page - 1) / pagination.per_page
     };
 
-    Ok(Json(ItemFullDetails {
-        item,
-        loan_details,
-        missing_details,
-        disposed_details,
+    Ok(Json(PaginatedResponse {
+        items,
+        total,
+        page: pagination.page,
+        per_page: pagination.per_page,
+        total_pages,
+        next_cursor: None,
     }))
 }
 
+/// Items matching `condition` (on `items i JOIN kinds k`) with their state
+/// details, as ItemDetailsRow, in one round trip: only the details table
+/// matching each item's state joins
+fn item_details_query(condition: &str) -> String {
+    format!(
+        "SELECT item.*,
+                ld.date_loaned AS loan_date_loaned, ld.date_due_back AS loan_date_due_back,
+                ld.loaned_to AS loan_loaned_to, l.borrower_id AS loan_borrower_id,
+                md.date_missing, dd.date_disposed
+         FROM ({} WHERE {}) item
+         LEFT JOIN item_loan_details ld ON ld.item_id = item.id AND item.state = 'loaned'
+         LEFT JOIN item_loans l ON l.item_id = ld.item_id AND l.date_returned IS NULL
+         LEFT JOIN item_missing_details md ON md.item_id = item.id AND item.state = 'missing'
+         LEFT JOIN item_disposed_details dd ON dd.item_id = item.id AND item.state = 'disposed'",
+        ITEM_SELECT, condition
+    )
+}
+
+async fn fetch_item_details(
+    pool: &PgPool,
+    org_id: Uuid,
+    item_id: Uuid,
+) -> Result<ItemFullDetails, ApiError> {
+    sqlx::query_as::<_, ItemDetailsRow>(&item_details_query("i.id = $1 AND i.organization_id = $2"))
+        .bind(item_id)
+        .bind(org_id)
+        .fetch_optional(pool)
+        .await?
+        .map(Into::into)
+        .ok_or_else(not_found)
+}
+
+// ── Item list filters ──────────────────────────────────────────────────────
+
+/// Conditions for the item list filters, shared by list_items, item_facets
+/// and the item export
+pub(super) struct ItemFilter {
+    kinds: Vec<String>,
+    states: Vec<String>,
+    location_ids: Vec<Uuid>,
+    collection_ids: Vec<Uuid>,
+    /// The search text as a tsquery, when searching
+    search_query: Option<String>,
+    barcode: Option<String>,
+    catalog_code: Option<String>,
+    disposed_since: Option<chrono::NaiveDate>,
+    tags: Vec<String>,
+    /// Items must have every tag rather than any
+    all_tags: bool,
+}
+
+impl ItemFilter {
+    pub(super) fn new(filters: &ItemFilterParams) -> Self {
+        let kinds: Vec<String> = filters
+            .kind
+            .as_ref()
+            .map(|s| s.split(',').map(|t| t.trim().to_string()).collect())
+            .unwrap_or_default();
+
+        let states: Vec<String> = filters
+            .state
+            .as_ref()
+            .map(|s| s.split(',').map(|t| t.trim().to_string()).collect())
+            .unwrap_or_default();
+
+        let location_ids: Vec<Uuid> = filters
+            .location_id
+            .as_ref()
+            .map(|s| {
+                s.split(',')
+                    .filter_map(|t| Uuid::parse_str(t.trim()).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let collection_ids: Vec<Uuid> = filters
+            .collection_id
+            .as_ref()
+            .map(|s| {
+                s.split(',')
+                    .filter_map(|t| Uuid::parse_str(t.trim()).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut tags: Vec<String> = filters
+            .tags
+            .as_ref()
+            .map(|s| {
+                s.split(',')
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        // Matching all tags counts the distinct ones an item has, so a name
+        // given twice must only be counted once
+        tags.sort();
+        tags.dedup();
+
+        let barcode = filters
+            .barcode
+            .as_deref()
+            .map(str::trim)
+            .filter(|b| !b.is_empty())
+            .map(str::to_string);
+
+        let catalog_code = filters
+            .catalog_code
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(str::to_string);
+
+        ItemFilter {
+            kinds,
+            states,
+            location_ids,
+            collection_ids,
+            search_query: filters.search.as_deref().and_then(prefix_tsquery),
+            barcode,
+            catalog_code,
+            disposed_since: filters.disposed_since,
+            tags,
+            all_tags: filters.tag_match.as_deref() == Some("all"),
+        }
+    }
+
+    /// Push the conditions on `items i JOIN kinds k`, starting with the
+    /// organization, binding each value as it goes
+    pub(super) fn push_conditions<'a>(
+        &'a self,
+        query: &mut QueryBuilder<'a, Postgres>,
+        org_id: Uuid,
+    ) {
+        query.push("i.organization_id = ").push_bind(org_id);
+
+        if !self.kinds.is_empty() {
+            query
+                .push(" AND k.name = ANY(")
+                .push_bind(&self.kinds)
+                .push(")");
+        }
+
+        if !self.states.is_empty() {
+            query
+                .push(" AND i.state::text = ANY(")
+                .push_bind(&self.states)
+                .push(")");
+        }
+
+        if !self.location_ids.is_empty() {
+            query
+                .push(" AND i.location_id = ANY(")
+                .push_bind(&self.location_ids)
+                .push(")");
+        }
+
+        if !self.collection_ids.is_empty() {
+            query
+                .push(
+                    " AND EXISTS (SELECT 1 FROM item_collections ic
+                                  WHERE ic.item_id = i.id AND ic.collection_id = ANY(",
+                )
+                .push_bind(&self.collection_ids)
+                .push("))");
+        }
+
+        if let Some(search_query) = &self.search_query {
+            query
+                .push(" AND i.search_vector @@ to_tsquery('simple', ")
+                .push_bind(search_query)
+                .push(")");
+        }
+
+        if let Some(barcode) = &self.barcode {
+            query.push(" AND i.barcode = ").push_bind(barcode);
+        }
+
+        if let Some(catalog_code) = &self.catalog_code {
+            query
+                .push(" AND LOWER(i.catalog_code) = LOWER(")
+                .push_bind(catalog_code)
+                .push(")");
+        }
+
+        if let Some(disposed_since) = self.disposed_since {
+            query
+                .push(
+                    " AND EXISTS (SELECT 1 FROM item_disposed_details d
+                                  WHERE d.item_id = i.id AND d.date_disposed >= ",
+                )
+                .push_bind(disposed_since)
+                .push(")");
+        }
+
+        if !self.tags.is_empty() {
+            let tag_filter = "FROM item_tags t WHERE t.item_id = i.id AND t.tag_name = ANY(";
+            if self.all_tags {
+                query
+                    .push(" AND (SELECT COUNT(DISTINCT t.tag_name) ")
+                    .push(tag_filter)
+                    .push_bind(&self.tags)
+                    .push(format!(")) = {}", self.tags.len()));
+            } else {
+                query
+                    .push(" AND EXISTS (SELECT 1 ")
+                    .push(tag_filter)
+                    .push_bind(&self.tags)
+                    .push("))");
+            }
+        }
+    }
+}
+
+// ── Sorting and cursors for list_items ────────────────────────────────────
+
+/// Columns the item list can be sorted by: the `sort_by` name, the column,
+/// and the SQL type a cursor's value is cast to
+const SORT_COLUMNS: &[(&str, &str, &str)] = &[
+    ("name", "i.name", "text"),
+    ("kind", "k.name", "text"),
+    ("state", "i.state", "item_state"),
+    ("location_id", "i.location_id", "uuid"),
+    ("created_at", "i.created_at", "timestamptz"),
+];
+
+/// The item list's order, also used by the item export. Ties are broken by
+/// id, so the order is total and a cursor names an exact position in it.
+pub(super) struct ItemSort {
+    /// Entry in `SORT_COLUMNS`; None when ranking search results by relevance
+    column: Option<(&'static str, &'static str, &'static str)>,
+    descending: bool,
+    /// The search tsquery, for relevance ranking
+    search_query: Option<String>,
+}
+
+/// Position after the last item of a page, as handed out in `next_cursor`
+#[derive(Serialize, Deserialize)]
+struct ItemCursor {
+    /// `sort_by` and direction the cursor was made for
+    sort: String,
+    desc: bool,
+    /// The item's sort value as text; None when it has none (no location)
+    value: Option<String>,
+    id: Uuid,
+}
+
+impl ItemSort {
+    pub(super) fn new(filters: &ItemFilterParams, filter: &ItemFilter) -> Self {
+        // A search without an explicit sort lists the best matches first;
+        // unknown sort columns fall back to the name
+        let search_query = filter.search_query.clone();
+        let column = match (&search_query, filters.sort_by.as_deref()) {
+            (Some(_), None) => None,
+            (_, sort_by) => Some(
+                SORT_COLUMNS
+                    .iter()
+                    .find(|(name, _, _)| Some(*name) == sort_by)
+                    .copied()
+                    .unwrap_or(SORT_COLUMNS[0]),
+            ),
+        };
+        ItemSort {
+            column,
+            descending: filters.sort_order.as_deref() == Some("desc"),
+            search_query,
+        }
+    }
+
+    pub(super) fn push_order_by<'a>(&'a self, query: &mut QueryBuilder<'a, Postgres>) {
+        match (self.column, &self.search_query) {
+            (Some((_, column, _)), _) => {
+                let direction = if self.descending { "DESC" } else { "ASC" };
+                query.push(format!("{} {}, i.id {}", column, direction, direction));
+            }
+            (None, Some(search_query)) => {
+                query
+                    .push("ts_rank(i.search_vector, to_tsquery('simple', ")
+                    .push_bind(search_query)
+                    .push(")) DESC, i.name ASC, i.id ASC");
+            }
+            (None, None) => unreachable!("relevance order is only used when searching"),
+        }
+    }
+
+    /// Cursor for the items after `item`; None in relevance order, which
+    /// only pages by offset
+    fn cursor_after(&self, item: &Item) -> Option<String> {
+        let (name, _, _) = self.column?;
+        let value = match name {
+            "kind" => Some(item.kind_name.clone()),
+            "state" => Some(item.state.as_str().to_string()),
+            "location_id" => item.location_id.map(|id| id.to_string()),
+            "created_at" => Some(item.created_at.to_rfc3339_opts(SecondsFormat::Micros, true)),
+            _ => Some(item.name.clone()),
+        };
+        let cursor = ItemCursor {
+            sort: name.to_string(),
+            desc: self.descending,
+            value,
+            id: item.id,
+        };
+        Some(URL_SAFE_NO_PAD.encode(serde_json::to_vec(&cursor).ok()?))
+    }
+
+    /// Decode an `after` cursor, which must have been made for this order and
+    /// hold a value of the sort column's type
+    fn parse_cursor(&self, after: &str) -> Result<ItemCursor, ApiError> {
+        let Some((name, _, sql_type)) = self.column else {
+            return Err(ApiError::validation_failed(
+                "Cursors cannot page search results ordered by relevance; set sort_by",
+            ));
+        };
+        let cursor: ItemCursor = URL_SAFE_NO_PAD
+            .decode(after)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or_else(|| ApiError::validation_failed("Invalid cursor"))?;
+        if cursor.sort != name || cursor.desc != self.descending {
+            return Err(ApiError::validation_failed(
+                "The cursor is for a different sort order",
+            ));
+        }
+        // The value is cast to the column's type in SQL; check it here so a
+        // bad one is the client's error rather than a failed query
+        let valid = match (sql_type, cursor.value.as_deref()) {
+            (_, None) | ("text", _) => true,
+            ("item_state", Some(value)) => value.parse::<ItemState>().is_ok(),
+            ("uuid", Some(value)) => Uuid::parse_str(value).is_ok(),
+            ("timestamptz", Some(value)) => DateTime::parse_from_rfc3339(value).is_ok(),
+            (sql_type, _) => unreachable!("no cursor check for {}", sql_type),
+        };
+        if !valid {
+            return Err(ApiError::validation_failed("Invalid cursor"));
+        }
+        Ok(cursor)
+    }
+
+    /// Push the condition selecting the items after the cursor. Items without
+    /// a value sort last ascending and first descending, as Postgres orders
+    /// NULLs.
+    fn push_after<'a>(&self, query: &mut QueryBuilder<'a, Postgres>, cursor: &'a ItemCursor) {
+        let (_, column, sql_type) = self.column.expect("cursors are parsed for a column sort");
+        let op = if self.descending { "<" } else { ">" };
+        match &cursor.value {
+            Some(value) => {
+                query
+                    .push(format!("({} {} ", column, op))
+                    .push_bind(value)
+                    .push(format!("::{} OR ({} = ", sql_type, column))
+                    .push_bind(value)
+                    .push(format!("::{} AND i.id {} ", sql_type, op))
+                    .push_bind(cursor.id)
+                    .push(")");
+                if !self.descending {
+                    query.push(format!(" OR {} IS NULL", column));
+                }
+                query.push(")");
+            }
+            None => {
+                if self.descending {
+                    query.push(format!("({} IS NOT NULL OR i.id < ", column));
+                } else {
+                    query.push(format!("({} IS NULL AND i.id > ", column));
+                }
+                query.push_bind(cursor.id).push(")");
+            }
+        }
+    }
+}
+
+// ── Field selection for list_items ─────────────────────────────────────────
+
+/// Item fields that may be named in `?fields=`
+const ITEM_FIELDS: &[&str] = &[
+    "id",
+    "organization_id",
+    "kind_id",
+    "kind_name",
+    "state",
+    "name",
+    "description",
+    "notes",
+    "location_id",
+    "date_entered",
+    "date_acquired",
+    "barcode",
+    "catalog_code",
+    "width_mm",
+    "height_mm",
+    "depth_mm",
+    "weight_g",
+    "last_used_at",
+    "soft_fields",
+    "created_at",
+    "updated_at",
+];
+
+/// The `fields` and `include` parameters of a list request
+struct Selection {
+    /// None returns every field
+    fields: Option<Vec<String>>,
+    details: bool,
+    tags: bool,
+    collections: bool,
+}
+
+impl Selection {
+    fn parse(filters: &ItemFilterParams) -> Result<Self, ApiError> {
+        let fields = match filters.fields.as_deref() {
+            Some(list) => {
+                let mut fields = vec!["id".to_string()];
+                for f in split_list(list) {
+                    if !ITEM_FIELDS.contains(&f) {
+                        return Err(ApiError::validation_failed(format!(
+                            "Unknown item field '{}'",
+                            f
+                        )));
+                    }
+                    if !fields.iter().any(|existing| existing == f) {
+                        fields.push(f.to_string());
+                    }
+                }
+                Some(fields)
+            }
+            None => None,
+        };
+
+        let mut selection = Selection {
+            fields,
+            details: false,
+            tags: false,
+            collections: false,
+        };
+        for inc in split_list(filters.include.as_deref().unwrap_or_default()) {
+            match inc {
+                "details" => selection.details = true,
+                "tags" => selection.tags = true,
+                "collections" => selection.collections = true,
+                other => {
+                    return Err(ApiError::validation_failed(format!(
+                        "Unknown include '{}'; expected details, tags or collections",
+                        other
+                    )));
+                }
+            }
+        }
+        Ok(selection)
+    }
+
+    /// Serialize the page of items, keeping only the selected fields and
+    /// embedding any included data
+    async fn apply(&self, pool: &PgPool, items: Vec<Item>) -> sqlx::Result<Vec<serde_json::Value>> {
+        let ids: Vec<Uuid> = items.iter().map(|i| i.id).collect();
+        let loaned = items_in_state(&items, "loaned");
+        let missing = items_in_state(&items, "missing");
+        let disposed = items_in_state(&items, "disposed");
+
+        let mut loan_details: HashMap<Uuid, LoanDetails> = HashMap::new();
+        let mut missing_details: HashMap<Uuid, MissingDetails> = HashMap::new();
+        let mut disposed_details: HashMap<Uuid, DisposedDetails> = HashMap::new();
+        if self.details {
+            loan_details = sqlx::query_as::<_, LoanDetailsRow>(
+                "SELECT d.item_id, d.date_loaned, d.date_due_back, d.loaned_to, l.borrower_id
+                 FROM item_loan_details d
+                 LEFT JOIN item_loans l ON l.item_id = d.item_id AND l.date_returned IS NULL
+                 WHERE d.item_id = ANY($1)",
+            )
+            .bind(&loaned)
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(|r| (r.item_id, r.into()))
+            .collect();
+            missing_details = sqlx::query_as::<_, MissingDetailsRow>(
+                "SELECT item_id, date_missing FROM item_missing_details WHERE item_id = ANY($1)",
+            )
+            .bind(&missing)
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(|r| (r.item_id, r.into()))
+            .collect();
+            disposed_details = sqlx::query_as::<_, DisposedDetailsRow>(
+                "SELECT item_id, date_disposed FROM item_disposed_details WHERE item_id = ANY($1)",
+            )
+            .bind(&disposed)
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(|r| (r.item_id, r.into()))
+            .collect();
+        }
+
+        let mut tags: HashMap<Uuid, Vec<String>> = HashMap::new();
+        if self.tags {
+            let rows = sqlx::query(
+                "SELECT item_id, tag_name FROM item_tags WHERE item_id = ANY($1) ORDER BY tag_name",
+            )
+            .bind(&ids)
+            .fetch_all(pool)
+            .await?;
+            for row in rows {
+                tags.entry(row.get("item_id"))
+                    .or_default()
+                    .push(row.get("tag_name"));
+            }
+        }
+
+        let mut collections: HashMap<Uuid, Vec<Collection>> = HashMap::new();
+        if self.collections {
+            let rows = sqlx::query_as::<_, ItemCollectionRow>(
+                "SELECT ic.item_id, c.id, c.organization_id, c.name, c.description, c.notes,
+                        c.cover_item_id, c.sort_order, c.created_at, c.updated_at
+                 FROM item_collections ic
+                 JOIN collections c ON c.id = ic.collection_id
+                 WHERE ic.item_id = ANY($1)
+                 ORDER BY c.sort_order, c.name",
+            )
+            .bind(&ids)
+            .fetch_all(pool)
+            .await?;
+            for row in rows {
+                collections
+                    .entry(row.item_id)
+                    .or_default()
+                    .push(row.collection);
+            }
+        }
+
+        Ok(items
+            .into_iter()
+            .map(|item| {
+                let id = item.id;
+                let mut value = serde_json::to_value(item).unwrap_or_default();
+                let Some(obj) = value.as_object_mut() else {
+                    return value;
+                };
+                if let Some(ref fields) = self.fields {
+                    obj.retain(|key, _| fields.contains(key));
+                }
+                if self.details {
+                    obj.insert(
+                        "loan_details".into(),
+                        json_or_null(loan_details.remove(&id)),
+                    );
+                    obj.insert(
+                        "missing_details".into(),
+                        json_or_null(missing_details.remove(&id)),
+                    );
+                    obj.insert(
+                        "disposed_details".into(),
+                        json_or_null(disposed_details.remove(&id)),
+                    );
+                }
+                if self.tags {
+                    obj.insert(
+                        "tags".into(),
+                        serde_json::json!(tags.remove(&id).unwrap_or_default()),
+                    );
+                }
+                if self.collections {
+                    obj.insert(
+                        "collections".into(),
+                        serde_json::json!(collections.remove(&id).unwrap_or_default()),
+                    );
+                }
+                value
+            })
+            .collect())
+    }
+}
+
+pub(super) fn split_list(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// Details are only reported for the state an item is in, as in get_item_details
+fn items_in_state(items: &[Item], state: &str) -> Vec<Uuid> {
+    items
+        .iter()
+        .filter(|i| i.state.as_str() == state)
+        .map(|i| i.id)
+        .collect()
+}
+
+fn json_or_null<T: serde::Serialize>(value: Option<T>) -> serde_json::Value {
+    value
+        .and_then(|v| serde_json::to_value(v).ok())
+        .unwrap_or(serde_json::Value::Null)
+}
+
 // ── Soft field validation ──────────────────────────────────────────────────
 
+/// Number fields that count parts of an item. Negative or fractional counts
+/// are refused; 0 is saved with a warning (see `warnings::item_warnings`).
+const COUNT_FIELDS: [&str; 2] = ["disks", "cassettes"];
+
+/// Check soft field values against the kind's field types, reporting every
+/// field at fault. Fields the kind does not define are passed through.
 async fn validate_soft_fields(
     pool: &PgPool,
     kind_id: Uuid,
     soft_fields: &serde_json::Value,
-) -> anyhow::Result<()> {
+) -> Result<(), ApiError> {
     let obj = match soft_fields.as_object() {
         Some(o) if !o.is_empty() => o,
         _ => return Ok(()),
@@ -630,72 +2035,71 @@ async fn validate_soft_fields(
         .map(|r| (r.get::<String, _>("name"), r.get::<String, _>("field_type")))
         .collect();
 
+    let mut errors = Vec::new();
     for (key, value) in obj {
         let field_type = match field_types.get(key) {
             Some(t) => t.as_str(),
             None => continue, // unknown fields are passed through without validation
         };
 
-        match field_type {
-            "number" => {
-                if !value.is_number() {
-                    anyhow::bail!("Field '{}' must be a number", key);
-                }
+        let message = match field_type {
+            "number" if !value.is_number() => format!("Field '{}' must be a number", key),
+            "number"
+                if COUNT_FIELDS.contains(&key.as_str()) && value.as_i64().is_none_or(|n| n < 0) =>
+            {
+                format!("Field '{}' must be a whole number, not negative", key)
             }
-            "boolean" => {
-                if !value.is_boolean() {
-                    anyhow::bail!("Field '{}' must be a boolean", key);
-                }
-            }
-            "string" | "text" | "date" | "datetime" => {
-                if !value.is_string() {
-                    anyhow::bail!("Field '{}' must be a string", key);
-                }
+            "boolean" if !value.is_boolean() => format!("Field '{}' must be a boolean", key),
+            "string" | "text" | "date" | "datetime" | "enum" if !value.is_string() => {
+                format!("Field '{}' must be a string", key)
             }
             "enum" => {
-                let v = value
-                    .as_str()
-                    .ok_or_else(|| anyhow::anyhow!("Field '{}' must be a string", key))?;
-
-                let valid: bool = sqlx::query_scalar(
-                    "SELECT EXISTS(
-                         SELECT 1 FROM enum_values ev
-                         JOIN fields f ON f.id = ev.field_id
-                         JOIN kind_fields kf ON kf.field_id = f.id
-                         WHERE kf.kind_id = $1 AND f.name = $2 AND ev.value = $3
-                     )",
+                let v = value.as_str().unwrap_or_default();
+                let allowed: Vec<String> = sqlx::query_scalar(
+                    "SELECT ev.value FROM enum_values ev
+                     JOIN fields f ON f.id = ev.field_id
+                     JOIN kind_fields kf ON kf.field_id = f.id
+                     WHERE kf.kind_id = $1 AND f.name = $2
+                     ORDER BY ev.sort_order",
                 )
                 .bind(kind_id)
                 .bind(key)
-                .bind(v)
-                .fetch_one(pool)
+                .fetch_all(pool)
                 .await?;
 
-                if !valid {
-                    let allowed: Vec<String> = sqlx::query_scalar(
-                        "SELECT ev.value FROM enum_values ev
-                         JOIN fields f ON f.id = ev.field_id
-                         JOIN kind_fields kf ON kf.field_id = f.id
-                         WHERE kf.kind_id = $1 AND f.name = $2
-                         ORDER BY ev.sort_order",
-                    )
-                    .bind(kind_id)
-                    .bind(key)
-                    .fetch_all(pool)
-                    .await?;
-
-                    anyhow::bail!(
-                        "Field '{}' value '{}' is not valid. Allowed values: {}",
-                        key,
-                        v,
-                        allowed.join(", ")
-                    );
+                if allowed.iter().any(|a| a == v) {
+                    continue;
                 }
+                format!(
+                    "Field '{}' value '{}' is not valid. Allowed values: {}",
+                    key,
+                    v,
+                    allowed.join(", ")
+                )
             }
-            _ => {}
-        }
+            _ => continue,
+        };
+        errors.push(FieldError {
+            field: key.clone(),
+            message,
+        });
     }
 
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::invalid_fields(errors))
+    }
+}
+
+/// Dimensions and weight must be positive; 0 is accepted and means "unset"
+fn validate_measurements(values: [Option<i32>; 4]) -> Result<(), ApiError> {
+    if values.iter().flatten().any(|v| *v < 0) {
+        return Err(ApiError::validation(
+            ErrorCode::ItemInvalidMeasurement,
+            "Dimensions and weight must not be negative",
+        ));
+    }
     Ok(())
 }
 
@@ -714,6 +2118,13 @@ struct ItemRow {
     location_id: Option<Uuid>,
     date_entered: chrono::DateTime<chrono::Utc>,
     date_acquired: Option<chrono::NaiveDate>,
+    barcode: Option<String>,
+    catalog_code: Option<String>,
+    width_mm: Option<i32>,
+    height_mm: Option<i32>,
+    depth_mm: Option<i32>,
+    weight_g: Option<i32>,
+    last_used_at: Option<chrono::DateTime<chrono::Utc>>,
     created_at: chrono::DateTime<chrono::Utc>,
     updated_at: chrono::DateTime<chrono::Utc>,
     soft_fields: serde_json::Value,
@@ -726,13 +2137,20 @@ impl From<ItemRow> for Item {
             organization_id: row.organization_id,
             kind_id: row.kind_id,
             kind_name: row.kind_name,
-            state: db_to_item_state(&row.state),
+            state: row.state.parse().unwrap_or(ItemState::Current),
             name: row.name,
             description: row.description,
             notes: row.notes,
             location_id: row.location_id,
             date_entered: row.date_entered,
             date_acquired: row.date_acquired,
+            barcode: row.barcode,
+            catalog_code: row.catalog_code,
+            width_mm: row.width_mm,
+            height_mm: row.height_mm,
+            depth_mm: row.depth_mm,
+            weight_g: row.weight_g,
+            last_used_at: row.last_used_at,
             soft_fields: row.soft_fields,
             created_at: row.created_at,
             updated_at: row.updated_at,
@@ -740,12 +2158,65 @@ impl From<ItemRow> for Item {
     }
 }
 
+/// An item with the details of its state, each None unless the item is in
+/// that state
+#[derive(sqlx::FromRow)]
+struct ItemDetailsRow {
+    #[sqlx(flatten)]
+    item: ItemRow,
+    loan_date_loaned: Option<chrono::NaiveDate>,
+    loan_date_due_back: Option<chrono::NaiveDate>,
+    loan_loaned_to: Option<String>,
+    loan_borrower_id: Option<Uuid>,
+    date_missing: Option<chrono::NaiveDate>,
+    date_disposed: Option<chrono::NaiveDate>,
+}
+
+impl From<ItemDetailsRow> for ItemFullDetails {
+    fn from(r: ItemDetailsRow) -> Self {
+        let item_id = r.item.id;
+        ItemFullDetails {
+            item: r.item.into(),
+            loan_details: r.loan_date_loaned.zip(r.loan_loaned_to).map(
+                |(date_loaned, loaned_to)| LoanDetails {
+                    item_id,
+                    date_loaned,
+                    date_due_back: r.loan_date_due_back,
+                    loaned_to,
+                    borrower_id: r.loan_borrower_id,
+                },
+            ),
+            missing_details: r.date_missing.map(|date_missing| MissingDetails {
+                item_id,
+                date_missing,
+            }),
+            disposed_details: r.date_disposed.map(|date_disposed| DisposedDetails {
+                item_id,
+                date_disposed,
+            }),
+        }
+    }
+}
+
 #[derive(sqlx::FromRow)]
 struct LoanDetailsRow {
     item_id: Uuid,
     date_loaned: chrono::NaiveDate,
     date_due_back: Option<chrono::NaiveDate>,
     loaned_to: String,
+    borrower_id: Option<Uuid>,
+}
+
+impl From<LoanDetailsRow> for LoanDetails {
+    fn from(r: LoanDetailsRow) -> Self {
+        LoanDetails {
+            item_id: r.item_id,
+            date_loaned: r.date_loaned,
+            date_due_back: r.date_due_back,
+            loaned_to: r.loaned_to,
+            borrower_id: r.borrower_id,
+        }
+    }
 }
 
 #[derive(sqlx::FromRow)]
@@ -754,59 +2225,347 @@ struct MissingDetailsRow {
     date_missing: chrono::NaiveDate,
 }
 
+impl From<MissingDetailsRow> for MissingDetails {
+    fn from(r: MissingDetailsRow) -> Self {
+        MissingDetails {
+            item_id: r.item_id,
+            date_missing: r.date_missing,
+        }
+    }
+}
+
 #[derive(sqlx::FromRow)]
 struct DisposedDetailsRow {
     item_id: Uuid,
     date_disposed: chrono::NaiveDate,
 }
 
-// ── Helpers ────────────────────────────────────────────────────────────────
+impl From<DisposedDetailsRow> for DisposedDetails {
+    fn from(r: DisposedDetailsRow) -> Self {
+        DisposedDetails {
+            item_id: r.item_id,
+            date_disposed: r.date_disposed,
+        }
+    }
+}
 
-fn db_to_item_state(s: &str) -> ItemState {
-    match s {
-        "current" => ItemState::Current,
-        "loaned" => ItemState::Loaned,
-        "missing" => ItemState::Missing,
-        "disposed" => ItemState::Disposed,
-        _ => ItemState::Current,
+#[derive(sqlx::FromRow)]
+struct ItemCollectionRow {
+    item_id: Uuid,
+    #[sqlx(flatten)]
+    collection: Collection,
+}
+
+/// UPDATE setting the fields given in the request, returning the item row
+fn item_update_query(
+    item_id: Uuid,
+    org_id: Uuid,
+    req: &UpdateItemRequest,
+) -> QueryBuilder<'_, Postgres> {
+    let mut query = QueryBuilder::new("UPDATE items SET updated_at = NOW()");
+
+    if let Some(name) = &req.name {
+        query.push(", name = ").push_bind(name);
+    }
+    if let Some(description) = &req.description {
+        query.push(", description = ").push_bind(description);
+    }
+    if let Some(notes) = &req.notes {
+        query.push(", notes = ").push_bind(notes);
+    }
+    if let Some(location_id) = req.location_id {
+        query.push(", location_id = ").push_bind(location_id);
+    }
+    if let Some(date_acquired) = req.date_acquired {
+        query.push(", date_acquired = ").push_bind(date_acquired);
+    }
+    if let Some(state) = &req.state {
+        query
+            .push(", state = ")
+            .push_bind(state.as_str())
+            .push("::item_state");
     }
+    if let Some(barcode) = &req.barcode {
+        query
+            .push(", barcode = NULLIF(TRIM(")
+            .push_bind(barcode)
+            .push("), '')");
+    }
+    if let Some(catalog_code) = &req.catalog_code {
+        query
+            .push(", catalog_code = NULLIF(TRIM(")
+            .push_bind(catalog_code)
+            .push("), '')");
+    }
+    for (column, value) in [
+        ("width_mm", req.width_mm),
+        ("height_mm", req.height_mm),
+        ("depth_mm", req.depth_mm),
+        ("weight_g", req.weight_g),
+    ] {
+        if let Some(value) = value {
+            query
+                .push(format!(", {} = NULLIF(", column))
+                .push_bind(value)
+                .push(", 0)");
+        }
+    }
+    if let Some(soft_fields) = &req.soft_fields {
+        // Merge: existing || new (new keys overwrite, absent keys preserved)
+        query
+            .push(", soft_fields = soft_fields || ")
+            .push_bind(soft_fields);
+    }
+
+    query
+        .push(" WHERE id = ")
+        .push_bind(item_id)
+        .push(" AND organization_id = ")
+        .push_bind(org_id)
+        .push(
+            " RETURNING id, organization_id, kind_id,
+                (SELECT name FROM kinds WHERE id = kind_id) AS kind_name,
+                state::text, name, description, notes,
+                location_id, date_entered, date_acquired, barcode, catalog_code,
+                width_mm, height_mm, depth_mm, weight_g, last_used_at, created_at, updated_at,
+                soft_fields",
+        );
+    query
 }
 
-fn item_state_to_db(s: &ItemState) -> &'static str {
-    match s {
-        ItemState::Current => "current",
-        ItemState::Loaned => "loaned",
-        ItemState::Missing => "missing",
-        ItemState::Disposed => "disposed",
+// ── Helpers ────────────────────────────────────────────────────────────────
+
+/// Turn free search text into a tsquery matching items that contain every
+/// word, each as a word prefix (`dark side` -> `dark:* & side:*`)
+///
+/// Only letters and digits are kept, so tsquery syntax in the input is inert.
+/// None when nothing searchable remains.
+fn prefix_tsquery(search: &str) -> Option<String> {
+    let terms: Vec<String> = search
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| format!("{}:*", w.to_lowercase()))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" & "))
+}
+
+/// Apply an update that sets plain item fields only, with its audit entry and
+/// outbox event; used where the change comes from somewhere other than the
+/// edit form, such as an accepted lookup suggestion
+pub(super) async fn update_item_fields(
+    conn: &mut sqlx::PgConnection,
+    org_id: Uuid,
+    item_id: Uuid,
+    user_id: Uuid,
+    req: &UpdateItemRequest,
+) -> Result<Item, ApiError> {
+    let before = lock_item(conn, org_id, item_id)
+        .await?
+        .ok_or_else(not_found)?;
+    let item: Item = item_update_query(item_id, org_id, req)
+        .build_query_as::<ItemRow>()
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(write_error)?
+        .ok_or_else(not_found)?
+        .into();
+
+    let payload = item_payload(&item);
+    let changes = audit::diff(&item_payload(&before), &payload);
+    if changes.as_object().is_some_and(|c| !c.is_empty()) {
+        audit::record(
+            conn,
+            org_id,
+            item_id,
+            user_id,
+            AuditAction::Updated,
+            changes,
+        )
+        .await?;
     }
+    outbox::enqueue(conn, org_id, outbox::ITEM_UPDATED, payload).await?;
+    Ok(item)
 }
 
-fn internal_error<E: std::fmt::Display>(err: E) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(ErrorResponse {
-            error: "internal_error".to_string(),
-            message: err.to_string(),
-        }),
-    )
+/// Load an item and lock its row for the rest of the transaction, so the
+/// audit diff is taken against the version being changed
+pub(super) async fn lock_item(
+    conn: &mut sqlx::PgConnection,
+    org_id: Uuid,
+    item_id: Uuid,
+) -> Result<Option<Item>, sqlx::Error> {
+    let query = format!(
+        "{} WHERE i.id = $1 AND i.organization_id = $2 FOR UPDATE OF i",
+        ITEM_SELECT
+    );
+    let row = sqlx::query_as::<_, ItemRow>(&query)
+        .bind(item_id)
+        .bind(org_id)
+        .fetch_optional(conn)
+        .await?;
+    Ok(row.map(Into::into))
 }
 
-fn not_found() -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::NOT_FOUND,
-        Json(ErrorResponse {
-            error: "not_found".to_string(),
-            message: "Item not found".to_string(),
-        }),
-    )
+/// Outbox payload for item events: the item as the API returns it
+pub(super) fn item_payload(item: &Item) -> serde_json::Value {
+    serde_json::to_value(item).unwrap_or_default()
 }
 
-fn bad_request(error: &str, message: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::BAD_REQUEST,
-        Json(ErrorResponse {
-            error: error.to_string(),
-            message: message.to_string(),
-        }),
-    )
+/// Map an item INSERT/UPDATE failure, reporting barcode clashes as 409
+fn write_error(err: sqlx::Error) -> ApiError {
+    match err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::conflict(
+            ErrorCode::ItemDuplicateBarcode,
+            "An item with this barcode already exists",
+        ),
+        err => ApiError::internal(err),
+    }
+}
+
+pub(super) fn not_found() -> ApiError {
+    ApiError::not_found(ErrorCode::ItemNotFound, "Item not found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The query's SQL with runs of whitespace collapsed
+    fn sql(query: &QueryBuilder<'_, Postgres>) -> String {
+        query.sql().split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn conditions(filters: &ItemFilterParams) -> String {
+        let filter = ItemFilter::new(filters);
+        let mut query = QueryBuilder::new("");
+        filter.push_conditions(&mut query, Uuid::nil());
+        sql(&query)
+    }
+
+    #[test]
+    fn test_filter_without_params_only_checks_organization() {
+        assert_eq!(
+            conditions(&ItemFilterParams::default()),
+            "i.organization_id = $1"
+        );
+    }
+
+    #[test]
+    fn test_filter_numbers_placeholders_in_order() {
+        let filters = ItemFilterParams {
+            kind: Some("vinyl,cd".into()),
+            state: Some("current".into()),
+            collection_id: Some(Uuid::nil().to_string()),
+            search: Some("blue".into()),
+            barcode: Some(" 123 ".into()),
+            disposed_since: chrono::NaiveDate::from_ymd_opt(2026, 1, 1),
+            tags: Some("jazz,signed,jazz".into()),
+            tag_match: Some("all".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            conditions(&filters),
+            "i.organization_id = $1 \
+             AND k.name = ANY($2) \
+             AND i.state::text = ANY($3) \
+             AND EXISTS (SELECT 1 FROM item_collections ic \
+             WHERE ic.item_id = i.id AND ic.collection_id = ANY($4)) \
+             AND i.search_vector @@ to_tsquery('simple', $5) \
+             AND i.barcode = $6 \
+             AND EXISTS (SELECT 1 FROM item_disposed_details d \
+             WHERE d.item_id = i.id AND d.date_disposed >= $7) \
+             AND (SELECT COUNT(DISTINCT t.tag_name) FROM item_tags t \
+             WHERE t.item_id = i.id AND t.tag_name = ANY($8)) = 2"
+        );
+    }
+
+    #[test]
+    fn test_blank_filters_are_ignored() {
+        let filters = ItemFilterParams {
+            barcode: Some("  ".into()),
+            catalog_code: Some("".into()),
+            tags: Some(" , ".into()),
+            search: Some("  ".into()),
+            ..Default::default()
+        };
+        assert_eq!(conditions(&filters), "i.organization_id = $1");
+    }
+
+    #[test]
+    fn test_cursor_and_paging_follow_filter_placeholders() {
+        let filters = ItemFilterParams {
+            state: Some("loaned".into()),
+            sort_by: Some("created_at".into()),
+            sort_order: Some("desc".into()),
+            ..Default::default()
+        };
+        let filter = ItemFilter::new(&filters);
+        let sort = ItemSort::new(&filters, &filter);
+        let cursor = ItemCursor {
+            sort: "created_at".into(),
+            desc: true,
+            value: Some("2026-01-01T00:00:00Z".into()),
+            id: Uuid::nil(),
+        };
+
+        let mut query = QueryBuilder::new("");
+        filter.push_conditions(&mut query, Uuid::nil());
+        query.push(" AND ");
+        sort.push_after(&mut query, &cursor);
+        query.push(" ORDER BY ");
+        sort.push_order_by(&mut query);
+        query.push(" LIMIT ").push_bind(51_i64);
+        assert_eq!(
+            sql(&query),
+            "i.organization_id = $1 AND i.state::text = ANY($2) \
+             AND (i.created_at < $3::timestamptz \
+             OR (i.created_at = $4::timestamptz AND i.id < $5)) \
+             ORDER BY i.created_at DESC, i.id DESC LIMIT $6"
+        );
+    }
+
+    #[test]
+    fn test_relevance_order_binds_search_again() {
+        let filters = ItemFilterParams {
+            search: Some("kind of blue".into()),
+            ..Default::default()
+        };
+        let filter = ItemFilter::new(&filters);
+        let sort = ItemSort::new(&filters, &filter);
+
+        let mut query = QueryBuilder::new("");
+        filter.push_conditions(&mut query, Uuid::nil());
+        query.push(" ORDER BY ");
+        sort.push_order_by(&mut query);
+        assert_eq!(
+            sql(&query),
+            "i.organization_id = $1 AND i.search_vector @@ to_tsquery('simple', $2) \
+             ORDER BY ts_rank(i.search_vector, to_tsquery('simple', $3)) DESC, \
+             i.name ASC, i.id ASC"
+        );
+    }
+
+    #[test]
+    fn test_item_update_sets_only_given_fields() {
+        let req: UpdateItemRequest = serde_json::from_value(serde_json::json!({
+            "name": "Blue",
+            "state": "loaned",
+            "catalog_code": "",
+            "depth_mm": 0,
+            "soft_fields": {"disks": 2}
+        }))
+        .unwrap();
+        let query = item_update_query(Uuid::nil(), Uuid::nil(), &req);
+        let sql = sql(&query);
+        assert!(
+            sql.starts_with(
+                "UPDATE items SET updated_at = NOW(), name = $1, state = $2::item_state, \
+                 catalog_code = NULLIF(TRIM($3), ''), depth_mm = NULLIF($4, 0), \
+                 soft_fields = soft_fields || $5 \
+                 WHERE id = $6 AND organization_id = $7 RETURNING "
+            ),
+            "{}",
+            sql
+        );
+    }
 }
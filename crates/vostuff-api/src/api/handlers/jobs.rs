@@ -0,0 +1,104 @@
+//! Background job status, for system administrators watching the queue or
+//! looking into work that failed.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    jobs::{JOB_COLUMNS, Job, STATUSES},
+    models::{ErrorCode, ErrorResponse},
+    state::AppState,
+};
+
+/// Query parameters for the job list
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct JobListParams {
+    /// Only jobs in this status: pending, running, succeeded or failed
+    pub status: Option<String>,
+    /// Only jobs of this kind, such as `email.send`
+    pub kind: Option<String>,
+    /// Jobs returned (1-500, default 50)
+    #[serde(default = "default_job_limit")]
+    pub limit: i64,
+}
+
+fn default_job_limit() -> i64 {
+    50
+}
+
+/// List background jobs, newest first
+#[utoipa::path(
+    get,
+    path = "/api/admin/jobs",
+    params(JobListParams),
+    responses(
+        (status = 200, description = "Jobs, newest first", body = Vec<Job>),
+        (status = 400, description = "Unknown status", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-jobs"
+)]
+pub async fn list_jobs(
+    State(state): State<AppState>,
+    Query(params): Query<JobListParams>,
+) -> Result<Json<Vec<Job>>, ApiError> {
+    if let Some(status) = params
+        .status
+        .as_deref()
+        .filter(|status| !STATUSES.contains(status))
+    {
+        return Err(ApiError::validation_failed(format!(
+            "Unknown status {}; expected one of {}",
+            status,
+            STATUSES.join(", ")
+        )));
+    }
+
+    let jobs = sqlx::query_as::<_, Job>(&format!(
+        "SELECT {} FROM jobs
+         WHERE ($1::text IS NULL OR status = $1)
+           AND ($2::text IS NULL OR kind = $2)
+         ORDER BY created_at DESC
+         LIMIT $3",
+        JOB_COLUMNS
+    ))
+    .bind(&params.status)
+    .bind(&params.kind)
+    .bind(params.limit.clamp(1, 500))
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(jobs))
+}
+
+/// Get a background job, including why its latest run failed
+#[utoipa::path(
+    get,
+    path = "/api/admin/jobs/{job_id}",
+    params(
+        ("job_id" = Uuid, Path, description = "Job ID")
+    ),
+    responses(
+        (status = 200, description = "The job", body = Job),
+        (status = 404, description = "Job not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-jobs"
+)]
+pub async fn get_job(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<Job>, ApiError> {
+    sqlx::query_as::<_, Job>(&format!("SELECT {} FROM jobs WHERE id = $1", JOB_COLUMNS))
+        .bind(job_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(ErrorCode::JobNotFound, "Job not found"))
+}
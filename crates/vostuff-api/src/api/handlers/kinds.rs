@@ -8,7 +8,11 @@ use sqlx::Row;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::api::{models::ErrorResponse, state::AppState};
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{ErrorCode, ErrorResponse, Permission},
+    state::AppState,
+};
 use crate::auth::AuthContext;
 
 pub use super::fields::{EnumValue, FieldType};
@@ -71,7 +75,7 @@ pub struct RevertResponse {
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct DataLossError {
-    pub error: String,
+    pub error: ErrorCode,
     pub message: String,
     pub fields_with_data: Vec<String>,
 }
@@ -117,7 +121,7 @@ impl TryFrom<KindRow> for Kind {
                 id: f.id,
                 name: f.name,
                 display_name: f.display_name,
-                field_type: FieldType::from_str(&f.field_type),
+                field_type: f.field_type.parse().unwrap_or(FieldType::String),
                 display_order: f.display_order,
                 enum_values: f
                     .enum_values
@@ -192,7 +196,7 @@ const KIND_SELECT: &str = "
 pub async fn list_kinds(
     State(state): State<AppState>,
     Path(org_id): Path<Uuid>,
-) -> Result<Json<Vec<Kind>>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Vec<Kind>>, ApiError> {
     let query = format!(
         "{} GROUP BY k.id ORDER BY k.display_name NULLS LAST, k.name",
         KIND_SELECT
@@ -200,14 +204,13 @@ pub async fn list_kinds(
     let rows = sqlx::query_as::<_, KindRow>(&query)
         .bind(org_id)
         .fetch_all(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
     let kinds: Vec<Kind> = rows
         .into_iter()
         .map(Kind::try_from)
         .collect::<Result<Vec<_>, _>>()
-        .map_err(internal_error)?;
+        .map_err(ApiError::internal)?;
 
     Ok(Json(kinds))
 }
@@ -230,17 +233,16 @@ pub async fn list_kinds(
 pub async fn get_kind(
     State(state): State<AppState>,
     Path((org_id, kind_id)): Path<(Uuid, Uuid)>,
-) -> Result<Json<Kind>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Kind>, ApiError> {
     let query = format!("{} AND k.id = $2 GROUP BY k.id", KIND_SELECT);
     let row = sqlx::query_as::<_, KindRow>(&query)
         .bind(org_id)
         .bind(kind_id)
         .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?
+        .await?
         .ok_or_else(not_found)?;
 
-    let kind = Kind::try_from(row).map_err(internal_error)?;
+    let kind = Kind::try_from(row).map_err(ApiError::internal)?;
     Ok(Json(kind))
 }
 
@@ -263,21 +265,22 @@ pub async fn create_kind(
     Extension(auth): Extension<AuthContext>,
     Path(org_id): Path<Uuid>,
     Json(req): Json<CreateKindRequest>,
-) -> Result<(StatusCode, Json<Kind>), (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden("Administrator access required to manage kinds"));
+) -> Result<(StatusCode, Json<Kind>), ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage kinds",
+        ));
     }
     // Check name is not taken by a shared kind
     let shared_conflict: bool =
         sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM kinds WHERE name = $1 AND org_id IS NULL)")
             .bind(&req.name)
             .fetch_one(&state.pool)
-            .await
-            .map_err(internal_error)?;
+            .await?;
 
     if shared_conflict {
-        return Err(conflict(
-            "name_conflict",
+        return Err(ApiError::conflict(
+            ErrorCode::KindNameConflict,
             "A shared kind with this name already exists",
         ));
     }
@@ -288,12 +291,11 @@ pub async fn create_kind(
             .bind(&req.name)
             .bind(org_id)
             .fetch_one(&state.pool)
-            .await
-            .map_err(internal_error)?;
+            .await?;
 
     if org_conflict {
-        return Err(conflict(
-            "name_conflict",
+        return Err(ApiError::conflict(
+            ErrorCode::KindNameConflict,
             "A kind with this name already exists in this organization",
         ));
     }
@@ -306,18 +308,16 @@ pub async fn create_kind(
         .bind(&req.field_ids)
         .bind(org_id)
         .fetch_one(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
         if valid_count != req.field_ids.len() as i64 {
-            return Err(bad_request(
-                "invalid_fields",
+            return Err(ApiError::validation_failed(
                 "One or more field IDs are invalid or not accessible",
             ));
         }
     }
 
-    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+    let mut tx = state.pool.begin().await?;
 
     let new_id: Uuid = sqlx::query_scalar(
         "INSERT INTO kinds (org_id, name, display_name) VALUES ($1, $2, $3) RETURNING id",
@@ -326,8 +326,7 @@ pub async fn create_kind(
     .bind(&req.name)
     .bind(&req.display_name)
     .fetch_one(&mut *tx)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     for (idx, field_id) in req.field_ids.iter().enumerate() {
         sqlx::query(
@@ -337,21 +336,19 @@ pub async fn create_kind(
         .bind(field_id)
         .bind(idx as i32)
         .execute(&mut *tx)
-        .await
-        .map_err(internal_error)?;
+        .await?;
     }
 
-    tx.commit().await.map_err(internal_error)?;
+    tx.commit().await?;
 
     let query = format!("{} AND k.id = $2 GROUP BY k.id", KIND_SELECT);
     let row = sqlx::query_as::<_, KindRow>(&query)
         .bind(org_id)
         .bind(new_id)
         .fetch_one(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
-    let kind = Kind::try_from(row).map_err(internal_error)?;
+    let kind = Kind::try_from(row).map_err(ApiError::internal)?;
     Ok((StatusCode::CREATED, Json(kind)))
 }
 
@@ -381,35 +378,37 @@ pub async fn update_kind(
     Path((org_id, kind_id)): Path<(Uuid, Uuid)>,
     Query(q): Query<UpdateKindQuery>,
     Json(req): Json<UpdateKindRequest>,
-) -> Result<Json<Kind>, (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden("Administrator access required to manage kinds"));
+) -> Result<Json<Kind>, ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage kinds",
+        ));
     }
     // Fetch the kind and verify it belongs to this org
     let row = sqlx::query("SELECT id, org_id FROM kinds WHERE id = $1")
         .bind(kind_id)
         .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?
+        .await?
         .ok_or_else(not_found)?;
 
     let kind_org_id: Option<Uuid> = row.get("org_id");
     if kind_org_id.is_none() {
-        return Err(forbidden("Cannot modify a shared kind"));
+        return Err(ApiError::auth_forbidden("Cannot modify a shared kind"));
     }
     if kind_org_id != Some(org_id) {
-        return Err(forbidden("Kind does not belong to this organization"));
+        return Err(ApiError::auth_forbidden(
+            "Kind does not belong to this organization",
+        ));
     }
 
-    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+    let mut tx = state.pool.begin().await?;
 
     if let Some(ref display_name) = req.display_name {
         sqlx::query("UPDATE kinds SET display_name = $1, updated_at = NOW() WHERE id = $2")
             .bind(display_name)
             .bind(kind_id)
             .execute(&mut *tx)
-            .await
-            .map_err(internal_error)?;
+            .await?;
     }
 
     if let Some(ref new_field_ids) = req.field_ids {
@@ -419,8 +418,7 @@ pub async fn update_kind(
         )
         .bind(kind_id)
         .fetch_all(&mut *tx)
-        .await
-        .map_err(internal_error)?
+        .await?
         .into_iter()
         .map(|r| (r.get::<Uuid, _>("id"), r.get::<String, _>("name")))
         .collect();
@@ -451,23 +449,19 @@ pub async fn update_kind(
             .bind(&removed_ids)
             .bind(kind_id)
             .fetch_all(&mut *tx)
-            .await
-            .map_err(internal_error)?
+            .await?
             .into_iter()
             .map(|r| r.get::<String, _>("name"))
             .collect();
 
             if !fields_with_data.is_empty() && !q.force {
-                tx.rollback().await.map_err(internal_error)?;
-                return Err((
-                    StatusCode::CONFLICT,
-                    Json(ErrorResponse {
-                        error: "data_loss_required".to_string(),
-                        message: format!(
-                            "Removing fields [{}] would delete data from existing items. Pass force=true to confirm.",
-                            fields_with_data.join(", ")
-                        ),
-                    }),
+                tx.rollback().await?;
+                return Err(ApiError::conflict(
+                    ErrorCode::KindDataLossRequired,
+                    format!(
+                        "Removing fields [{}] would delete data from existing items. Pass force=true to confirm.",
+                        fields_with_data.join(", ")
+                    ),
                 ));
             }
 
@@ -479,8 +473,7 @@ pub async fn update_kind(
                 .bind(field_name)
                 .bind(kind_id)
                 .execute(&mut *tx)
-                .await
-                .map_err(internal_error)?;
+                .await?;
             }
         }
 
@@ -488,8 +481,7 @@ pub async fn update_kind(
         sqlx::query("DELETE FROM kind_fields WHERE kind_id = $1")
             .bind(kind_id)
             .execute(&mut *tx)
-            .await
-            .map_err(internal_error)?;
+            .await?;
 
         for (idx, field_id) in new_field_ids.iter().enumerate() {
             sqlx::query(
@@ -499,22 +491,20 @@ pub async fn update_kind(
             .bind(field_id)
             .bind(idx as i32)
             .execute(&mut *tx)
-            .await
-            .map_err(internal_error)?;
+            .await?;
         }
     }
 
-    tx.commit().await.map_err(internal_error)?;
+    tx.commit().await?;
 
     let query = format!("{} AND k.id = $2 GROUP BY k.id", KIND_SELECT);
     let row = sqlx::query_as::<_, KindRow>(&query)
         .bind(org_id)
         .bind(kind_id)
         .fetch_one(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
-    let kind = Kind::try_from(row).map_err(internal_error)?;
+    let kind = Kind::try_from(row).map_err(ApiError::internal)?;
     Ok(Json(kind))
 }
 
@@ -539,35 +529,37 @@ pub async fn delete_kind(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
     Path((org_id, kind_id)): Path<(Uuid, Uuid)>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden("Administrator access required to manage kinds"));
+) -> Result<StatusCode, ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage kinds",
+        ));
     }
     let row = sqlx::query("SELECT id, org_id FROM kinds WHERE id = $1")
         .bind(kind_id)
         .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?
+        .await?
         .ok_or_else(not_found)?;
 
     let kind_org_id: Option<Uuid> = row.get("org_id");
     if kind_org_id.is_none() {
-        return Err(forbidden("Cannot delete a shared kind"));
+        return Err(ApiError::auth_forbidden("Cannot delete a shared kind"));
     }
     if kind_org_id != Some(org_id) {
-        return Err(forbidden("Kind does not belong to this organization"));
+        return Err(ApiError::auth_forbidden(
+            "Kind does not belong to this organization",
+        ));
     }
 
     let item_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items WHERE kind_id = $1")
         .bind(kind_id)
         .fetch_one(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
     if item_count > 0 {
-        return Err(conflict(
-            "kind_in_use",
-            &format!("{} item(s) use this kind", item_count),
+        return Err(ApiError::conflict(
+            ErrorCode::KindInUse,
+            format!("{} item(s) use this kind", item_count),
         ));
     }
 
@@ -575,8 +567,7 @@ pub async fn delete_kind(
         .bind(kind_id)
         .bind(org_id)
         .execute(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -601,18 +592,24 @@ pub async fn override_kind(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
     Path((org_id, kind_id)): Path<(Uuid, Uuid)>,
-) -> Result<(StatusCode, Json<Kind>), (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden("Administrator access required to manage kinds"));
+) -> Result<(StatusCode, Json<Kind>), ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage kinds",
+        ));
     }
     // Fetch and verify it is a shared kind
     let shared_row =
         sqlx::query("SELECT id, name, display_name FROM kinds WHERE id = $1 AND org_id IS NULL")
             .bind(kind_id)
             .fetch_optional(&state.pool)
-            .await
-            .map_err(internal_error)?
-            .ok_or_else(|| bad_request("not_shared", "The specified kind is not a shared kind"))?;
+            .await?
+            .ok_or_else(|| {
+                ApiError::validation(
+                    ErrorCode::KindNotShared,
+                    "The specified kind is not a shared kind",
+                )
+            })?;
 
     let shared_name: String = shared_row.get("name");
     let shared_display_name: Option<String> = shared_row.get("display_name");
@@ -623,17 +620,16 @@ pub async fn override_kind(
             .bind(&shared_name)
             .bind(org_id)
             .fetch_one(&state.pool)
-            .await
-            .map_err(internal_error)?;
+            .await?;
 
     if already_exists {
-        return Err(conflict(
-            "override_exists",
+        return Err(ApiError::conflict(
+            ErrorCode::KindOverrideExists,
             "This organization already has a kind with this name",
         ));
     }
 
-    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+    let mut tx = state.pool.begin().await?;
 
     let new_id: Uuid = sqlx::query_scalar(
         "INSERT INTO kinds (org_id, name, display_name) VALUES ($1, $2, $3) RETURNING id",
@@ -642,8 +638,7 @@ pub async fn override_kind(
     .bind(&shared_name)
     .bind(&shared_display_name)
     .fetch_one(&mut *tx)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     sqlx::query(
         "INSERT INTO kind_fields (kind_id, field_id, display_order)
@@ -652,20 +647,18 @@ pub async fn override_kind(
     .bind(new_id)
     .bind(kind_id)
     .execute(&mut *tx)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
-    tx.commit().await.map_err(internal_error)?;
+    tx.commit().await?;
 
     let query = format!("{} AND k.id = $2 GROUP BY k.id", KIND_SELECT);
     let row = sqlx::query_as::<_, KindRow>(&query)
         .bind(org_id)
         .bind(new_id)
         .fetch_one(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
-    let kind = Kind::try_from(row).map_err(internal_error)?;
+    let kind = Kind::try_from(row).map_err(ApiError::internal)?;
     Ok((StatusCode::CREATED, Json(kind)))
 }
 
@@ -689,26 +682,32 @@ pub async fn revert_kind(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
     Path((org_id, kind_id)): Path<(Uuid, Uuid)>,
-) -> Result<Json<RevertResponse>, (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden("Administrator access required to manage kinds"));
+) -> Result<Json<RevertResponse>, ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage kinds",
+        ));
     }
     // Fetch org kind
     let org_row = sqlx::query("SELECT id, org_id, name FROM kinds WHERE id = $1")
         .bind(kind_id)
         .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?
+        .await?
         .ok_or_else(not_found)?;
 
     let kind_org_id: Option<Uuid> = org_row.get("org_id");
     let kind_name: String = org_row.get("name");
 
     if kind_org_id.is_none() {
-        return Err(bad_request("already_shared", "Cannot revert a shared kind"));
+        return Err(ApiError::validation(
+            ErrorCode::KindAlreadyShared,
+            "Cannot revert a shared kind",
+        ));
     }
     if kind_org_id != Some(org_id) {
-        return Err(forbidden("Kind does not belong to this organization"));
+        return Err(ApiError::auth_forbidden(
+            "Kind does not belong to this organization",
+        ));
     }
 
     // Find the matching shared kind
@@ -716,15 +715,11 @@ pub async fn revert_kind(
         sqlx::query_scalar("SELECT id FROM kinds WHERE name = $1 AND org_id IS NULL")
             .bind(&kind_name)
             .fetch_optional(&state.pool)
-            .await
-            .map_err(internal_error)?
+            .await?
             .ok_or_else(|| {
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(ErrorResponse {
-                        error: "no_shared_kind".to_string(),
-                        message: "No shared kind found with this name to revert to".to_string(),
-                    }),
+                ApiError::not_found(
+                    ErrorCode::KindNoSharedKind,
+                    "No shared kind found with this name to revert to",
                 )
             })?;
 
@@ -743,29 +738,26 @@ pub async fn revert_kind(
     .bind(kind_id)
     .bind(shared_id)
     .fetch_all(&state.pool)
-    .await
-    .map_err(internal_error)?
+    .await?
     .into_iter()
     .map(|r| r.get::<String, _>("name"))
     .collect();
 
-    let mut tx = state.pool.begin().await.map_err(internal_error)?;
+    let mut tx = state.pool.begin().await?;
 
     let items_reassigned = sqlx::query("UPDATE items SET kind_id = $1 WHERE kind_id = $2")
         .bind(shared_id)
         .bind(kind_id)
         .execute(&mut *tx)
-        .await
-        .map_err(internal_error)?
+        .await?
         .rows_affected() as i64;
 
     sqlx::query("DELETE FROM kinds WHERE id = $1")
         .bind(kind_id)
         .execute(&mut *tx)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
-    tx.commit().await.map_err(internal_error)?;
+    tx.commit().await?;
 
     Ok(Json(RevertResponse {
         items_reassigned,
@@ -799,7 +791,7 @@ pub struct FieldImpact {
 pub async fn get_field_impact(
     State(state): State<AppState>,
     Path((org_id, kind_id, field_id)): Path<(Uuid, Uuid, Uuid)>,
-) -> Result<Json<FieldImpact>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<FieldImpact>, ApiError> {
     // Verify field is part of this kind
     let exists: bool = sqlx::query_scalar(
         "SELECT EXISTS(SELECT 1 FROM kind_fields WHERE kind_id = $1 AND field_id = $2)",
@@ -807,8 +799,7 @@ pub async fn get_field_impact(
     .bind(kind_id)
     .bind(field_id)
     .fetch_one(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     if !exists {
         return Err(not_found());
@@ -818,8 +809,7 @@ pub async fn get_field_impact(
     let field_name: String = sqlx::query_scalar("SELECT name FROM fields WHERE id = $1")
         .bind(field_id)
         .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?
+        .await?
         .ok_or_else(not_found)?;
 
     // Count impacted items
@@ -830,60 +820,13 @@ pub async fn get_field_impact(
     .bind(kind_id)
     .bind(&field_name)
     .fetch_one(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     Ok(Json(FieldImpact { item_count }))
 }
 
 // ── Error helpers ────────────────────────────────────────────────────────────
 
-fn internal_error<E: std::fmt::Display>(err: E) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(ErrorResponse {
-            error: "internal_error".to_string(),
-            message: err.to_string(),
-        }),
-    )
-}
-
-fn not_found() -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::NOT_FOUND,
-        Json(ErrorResponse {
-            error: "not_found".to_string(),
-            message: "Kind not found".to_string(),
-        }),
-    )
-}
-
-fn bad_request(code: &str, msg: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::BAD_REQUEST,
-        Json(ErrorResponse {
-            error: code.to_string(),
-            message: msg.to_string(),
-        }),
-    )
-}
-
-fn conflict(code: &str, msg: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::CONFLICT,
-        Json(ErrorResponse {
-            error: code.to_string(),
-            message: msg.to_string(),
-        }),
-    )
-}
-
-fn forbidden(msg: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::FORBIDDEN,
-        Json(ErrorResponse {
-            error: "forbidden".to_string(),
-            message: msg.to_string(),
-        }),
-    )
+fn not_found() -> ApiError {
+    ApiError::not_found(ErrorCode::KindNotFound, "Kind not found")
 }
@@ -0,0 +1,155 @@
+//! Printing item labels with the organization's label templates; the PDF is
+//! drawn by [`crate::api::labels`].
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+};
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use super::settings::load_settings;
+use crate::api::error::ApiError;
+use crate::api::labels::{self, Label};
+use crate::api::{
+    models::{ErrorCode, ErrorResponse, LabelTemplate, PrintLabelsRequest},
+    state::AppState,
+    validation::ValidJson,
+};
+
+/// The values a label template can print, by field name
+#[derive(sqlx::FromRow)]
+struct LabelRow {
+    id: Uuid,
+    name: String,
+    kind_name: String,
+    state: String,
+    location: Option<String>,
+    barcode: Option<String>,
+    catalog_code: Option<String>,
+    description: Option<String>,
+    date_acquired: Option<NaiveDate>,
+}
+
+impl LabelRow {
+    fn field(&self, field: &str) -> Option<String> {
+        match field {
+            "name" => Some(self.name.clone()),
+            "kind_name" => Some(self.kind_name.clone()),
+            "state" => Some(self.state.clone()),
+            "location" => self.location.clone(),
+            "barcode" => self.barcode.clone(),
+            "catalog_code" => self.catalog_code.clone(),
+            // The first line of a description
+            "description" => self
+                .description
+                .as_deref()
+                .and_then(|d| d.lines().next())
+                .map(str::to_string),
+            "date_acquired" => self.date_acquired.map(|d| d.to_string()),
+            _ => None,
+        }
+        .filter(|value| !value.trim().is_empty())
+    }
+
+    fn label(&self, template: &LabelTemplate, item_url: &str) -> Label {
+        Label {
+            lines: template
+                .fields
+                .iter()
+                .filter_map(|field| self.field(field))
+                .collect(),
+            link: format!("{}?item={}", item_url, self.id),
+            barcode: self.barcode.clone().filter(|b| !b.trim().is_empty()),
+        }
+    }
+}
+
+/// Print labels for items
+///
+/// Lays out one label per item with the named template from the
+/// organization's settings, as a PDF with a page per label.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/items/labels",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = PrintLabelsRequest,
+    responses(
+        (status = 200, description = "PDF with one page per label", content_type = "application/pdf"),
+        (status = 400, description = "Invalid input, or no template of that name", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn print_labels(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    ValidJson(req): ValidJson<PrintLabelsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    if req.item_ids.is_empty() {
+        return Err(ApiError::validation_failed("No items given"));
+    }
+    if req.item_ids.len() > PrintLabelsRequest::MAX_ITEMS {
+        return Err(ApiError::validation_failed(format!(
+            "At most {} labels may be printed at once",
+            PrintLabelsRequest::MAX_ITEMS
+        )));
+    }
+
+    let settings = load_settings(&state.pool, org_id).await?;
+    let template = settings
+        .label_templates
+        .into_iter()
+        .find(|t| t.name == req.template.trim())
+        .ok_or_else(|| {
+            ApiError::validation_failed(format!("No label template named '{}'", req.template))
+        })?;
+
+    let rows: HashMap<Uuid, LabelRow> = sqlx::query_as::<_, LabelRow>(
+        "SELECT i.id, i.name, COALESCE(k.display_name, k.name) AS kind_name,
+                i.state::text AS state, l.name AS location, i.barcode, i.catalog_code,
+                i.description, i.date_acquired
+         FROM items i
+         JOIN kinds k ON k.id = i.kind_id
+         LEFT JOIN locations l ON l.id = i.location_id
+         WHERE i.id = ANY($1) AND i.organization_id = $2",
+    )
+    .bind(&req.item_ids)
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?
+    .into_iter()
+    .map(|row| (row.id, row))
+    .collect();
+
+    // Request order; an item listed twice gets two labels
+    let labels = req
+        .item_ids
+        .iter()
+        .map(|id| {
+            rows.get(id)
+                .map(|row| row.label(&template, &state.item_url))
+                .ok_or_else(|| {
+                    ApiError::not_found(ErrorCode::ItemNotFound, format!("Item {} not found", id))
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let pdf = labels::render(&template, &labels).map_err(ApiError::internal)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"vostuff-labels.pdf\"".to_string(),
+            ),
+        ],
+        pdf,
+    ))
+}
@@ -5,8 +5,15 @@ use axum::{
 };
 use uuid::Uuid;
 
+use super::items;
+use crate::api::error::ApiError;
+use crate::api::validation::ValidJson;
 use crate::api::{
-    models::{CreateLocationRequest, ErrorResponse, Location},
+    models::{
+        CreateLocationRequest, ErrorCode, ErrorResponse, Location, MergeLocationsRequest,
+        MergeLocationsResponse, Permission, UpdateItemRequest, UpdateLocationRequest,
+    },
+    outbox,
     state::AppState,
 };
 use crate::auth::AuthContext;
@@ -27,15 +34,14 @@ use crate::auth::AuthContext;
 pub async fn list_locations(
     State(state): State<AppState>,
     Path(org_id): Path<Uuid>,
-) -> Result<Json<Vec<Location>>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Vec<Location>>, ApiError> {
     let locations = sqlx::query_as::<_, Location>(
         "SELECT id, organization_id, name, created_at, updated_at
          FROM locations WHERE organization_id = $1 ORDER BY name",
     )
     .bind(org_id)
     .fetch_all(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     Ok(Json(locations))
 }
@@ -59,11 +65,11 @@ pub async fn create_location(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
     Path(org_id): Path<Uuid>,
-    Json(req): Json<CreateLocationRequest>,
-) -> Result<(StatusCode, Json<Location>), (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden(
-            "Administrator access required to manage locations",
+    ValidJson(req): ValidJson<CreateLocationRequest>,
+) -> Result<(StatusCode, Json<Location>), ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage locations",
         ));
     }
     let location = sqlx::query_as::<_, Location>(
@@ -73,12 +79,72 @@ pub async fn create_location(
     .bind(org_id)
     .bind(&req.name)
     .fetch_one(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     Ok((StatusCode::CREATED, Json(location)))
 }
 
+/// Rename a location
+///
+/// Items refer to their location by id, so they, item lists and exports show
+/// the new name straight away. Sends a `location.updated` event so open
+/// clients can refresh their location lists.
+#[utoipa::path(
+    patch,
+    path = "/api/organizations/{org_id}/locations/{location_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("location_id" = Uuid, Path, description = "Location ID")
+    ),
+    request_body = UpdateLocationRequest,
+    responses(
+        (status = 200, description = "Location renamed successfully", body = Location),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 404, description = "Location not found", body = ErrorResponse),
+        (status = 409, description = "Another location has this name", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "locations"
+)]
+pub async fn update_location(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, location_id)): Path<(Uuid, Uuid)>,
+    ValidJson(req): ValidJson<UpdateLocationRequest>,
+) -> Result<Json<Location>, ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage locations",
+        ));
+    }
+    let name = req.name.trim();
+
+    let mut tx = state.pool.begin().await?;
+    let location = sqlx::query_as::<_, Location>(
+        "UPDATE locations SET name = $3, updated_at = NOW()
+         WHERE id = $1 AND organization_id = $2
+         RETURNING id, organization_id, name, created_at, updated_at",
+    )
+    .bind(location_id)
+    .bind(org_id)
+    .bind(name)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(write_error)?
+    .ok_or_else(not_found)?;
+
+    outbox::enqueue(
+        &mut tx,
+        org_id,
+        outbox::LOCATION_UPDATED,
+        serde_json::to_value(&location).unwrap_or_default(),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(location))
+}
+
 /// Delete a location
 #[utoipa::path(
     delete,
@@ -98,48 +164,145 @@ pub async fn delete_location(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
     Path((org_id, location_id)): Path<(Uuid, Uuid)>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden(
-            "Administrator access required to manage locations",
+) -> Result<StatusCode, ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage locations",
         ));
     }
     let result = sqlx::query("DELETE FROM locations WHERE id = $1 AND organization_id = $2")
         .bind(location_id)
         .bind(org_id)
         .execute(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
     if result.rows_affected() == 0 {
-        Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "Location not found".to_string(),
-            }),
-        ))
+        Err(not_found())
     } else {
         Ok(StatusCode::NO_CONTENT)
     }
 }
 
-fn internal_error<E: std::fmt::Display>(err: E) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(ErrorResponse {
-            error: "internal_error".to_string(),
-            message: err.to_string(),
-        }),
+/// Merge another location into this one
+///
+/// Every item at the source location moves here, each with a history entry
+/// and an `item.updated` event, and the source is deleted, all in one
+/// transaction. Saved searches filtering by the source location are pointed
+/// at this one. Sends `location.deleted` for the source.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/locations/{location_id}/merge",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("location_id" = Uuid, Path, description = "Location to keep")
+    ),
+    request_body = MergeLocationsRequest,
+    responses(
+        (status = 200, description = "Locations merged", body = MergeLocationsResponse),
+        (status = 400, description = "A location cannot be merged into itself", body = ErrorResponse),
+        (status = 404, description = "Location not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "locations"
+)]
+pub async fn merge_locations(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, location_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<MergeLocationsRequest>,
+) -> Result<Json<MergeLocationsResponse>, ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage locations",
+        ));
+    }
+    let source_id = req.source_location_id;
+    if source_id == location_id {
+        return Err(ApiError::validation_failed(
+            "A location cannot be merged into itself",
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    // Lock in id order, so two merges of the same pair cannot deadlock
+    let locked = sqlx::query_as::<_, Location>(
+        "SELECT id, organization_id, name, created_at, updated_at
+         FROM locations WHERE id = ANY($1) AND organization_id = $2
+         ORDER BY id FOR UPDATE",
     )
-}
+    .bind([location_id, source_id])
+    .bind(org_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    if locked.len() != 2 {
+        return Err(not_found());
+    }
+    let location = locked
+        .into_iter()
+        .find(|l| l.id == location_id)
+        .ok_or_else(not_found)?;
 
-fn forbidden(msg: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::FORBIDDEN,
-        Json(ErrorResponse {
-            error: "forbidden".to_string(),
-            message: msg.to_string(),
-        }),
+    let item_ids: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM items WHERE location_id = $1 AND organization_id = $2 ORDER BY id",
     )
+    .bind(source_id)
+    .bind(org_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    let update = UpdateItemRequest {
+        location_id: Some(location_id),
+        ..Default::default()
+    };
+    for item_id in &item_ids {
+        items::update_item_fields(&mut tx, org_id, *item_id, auth.user_id, &update).await?;
+    }
+
+    // Saved searches hold item list query strings, in which a location
+    // appears only by id
+    sqlx::query(
+        "UPDATE user_preferences
+         SET preferences = replace(preferences::text, $2::text, $3::text)::jsonb,
+             updated_at = NOW()
+         WHERE organization_id = $1 AND preferences::text LIKE '%' || $2::text || '%'",
+    )
+    .bind(org_id)
+    .bind(source_id)
+    .bind(location_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM locations WHERE id = $1 AND organization_id = $2")
+        .bind(source_id)
+        .bind(org_id)
+        .execute(&mut *tx)
+        .await?;
+
+    outbox::enqueue(
+        &mut tx,
+        org_id,
+        outbox::LOCATION_DELETED,
+        serde_json::json!({ "id": source_id, "merged_into": location_id }),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(MergeLocationsResponse {
+        location,
+        items_moved: item_ids.len() as i64,
+    }))
+}
+
+/// Map a location UPDATE failure, reporting name clashes as 409
+fn write_error(err: sqlx::Error) -> ApiError {
+    match err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::conflict(
+            ErrorCode::LocationNameConflict,
+            "A location with this name already exists",
+        ),
+        err => ApiError::internal(err),
+    }
+}
+
+fn not_found() -> ApiError {
+    ApiError::not_found(ErrorCode::LocationNotFound, "Location not found")
 }
@@ -0,0 +1,223 @@
+//! Prefilling a new item from its ISBN or barcode as it is entered, by asking
+//! the metadata services of [`crate::api::lookup`].
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    lookup::{self as metadata, Metadata},
+    models::{
+        BarcodeLookupParams, CreateItemRequest, ErrorCode, ErrorResponse, ItemLookup, Permission,
+    },
+    state::AppState,
+    validation::CODE_MAX_LEN,
+};
+use crate::auth::AuthContext;
+
+/// Kind of a match whose service does not say what it is
+const FALLBACK_KIND: &str = "misc";
+
+fn require_items_write(auth: &AuthContext) -> Result<(), ApiError> {
+    if auth.can(Permission::ItemsWrite) {
+        Ok(())
+    } else {
+        Err(ApiError::auth_forbidden(
+            "The items:write permission is required to change items",
+        ))
+    }
+}
+
+/// Prefill a book from its ISBN
+///
+/// The ISBN-10 or ISBN-13 may contain hyphens and spaces. Books are looked up
+/// on OpenLibrary; nothing is saved.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/lookup/isbn/{isbn}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("isbn" = String, Path, description = "ISBN-10 or ISBN-13")
+    ),
+    responses(
+        (status = 200, description = "The book, ready to create", body = ItemLookup),
+        (status = 400, description = "Not a valid ISBN", body = ErrorResponse),
+        (status = 403, description = "Missing the items:write permission", body = ErrorResponse),
+        (status = 404, description = "No service knows the ISBN", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 502, description = "Every service asked failed", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn lookup_isbn(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, isbn)): Path<(Uuid, String)>,
+) -> Result<Json<ItemLookup>, ApiError> {
+    require_items_write(&auth)?;
+    let isbn = metadata::normalize_isbn(&isbn)
+        .ok_or_else(|| ApiError::validation_failed("Not a valid ISBN-10 or ISBN-13"))?;
+    lookup_code(&state, org_id, &isbn, Some("book")).await
+}
+
+/// Prefill an item from its barcode
+///
+/// Books' barcodes (EAN-13 starting 978 or 979) are looked up as ISBNs.
+/// Other barcodes are asked of each service in turn, or only those covering
+/// `kind` when given; nothing is saved.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/lookup/barcode/{code}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("code" = String, Path, description = "Barcode: 8 to 14 digits"),
+        BarcodeLookupParams
+    ),
+    responses(
+        (status = 200, description = "The item, ready to create", body = ItemLookup),
+        (status = 400, description = "Not a barcode, or no service covers the kind", body = ErrorResponse),
+        (status = 403, description = "Missing the items:write permission", body = ErrorResponse),
+        (status = 404, description = "No service knows the barcode", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 502, description = "Every service asked failed", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn lookup_barcode(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, code)): Path<(Uuid, String)>,
+    Query(params): Query<BarcodeLookupParams>,
+) -> Result<Json<ItemLookup>, ApiError> {
+    require_items_write(&auth)?;
+    let code = code.trim();
+    if !(8..=14).contains(&code.len()) || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ApiError::validation_failed("A barcode is 8 to 14 digits"));
+    }
+    let kind = params
+        .kind
+        .as_deref()
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .or(metadata::is_book_barcode(code).then_some("book"));
+    lookup_code(&state, org_id, code, kind).await
+}
+
+/// Ask each service covering `kind` (every service when None) in turn and
+/// prefill an item from the first match. Unavailable only when every service
+/// asked failed.
+async fn lookup_code(
+    state: &AppState,
+    org_id: Uuid,
+    code: &str,
+    kind: Option<&str>,
+) -> Result<Json<ItemLookup>, ApiError> {
+    let providers: Vec<_> = state
+        .metadata_providers
+        .iter()
+        .filter(|p| kind.is_none_or(|k| p.supports(k)))
+        .collect();
+    if let (Some(kind), true) = (kind, providers.is_empty()) {
+        return Err(ApiError::validation_failed(format!(
+            "No metadata service covers {} items",
+            kind
+        )));
+    }
+
+    // A failing service is passed over; the code may still be found elsewhere
+    let mut failed = Vec::new();
+    for provider in &providers {
+        let found = match provider.lookup(code).await {
+            Ok(found) => found,
+            Err(e) => {
+                tracing::warn!("{} lookup of {} failed: {:#}", provider.name(), code, e);
+                failed.push(provider.name());
+                continue;
+            }
+        };
+        if let Some(found) = found {
+            return Ok(Json(ItemLookup {
+                provider: provider.name().to_string(),
+                source_url: found.source_url.clone(),
+                item: prefill(state, org_id, code, kind, found).await?,
+            }));
+        }
+    }
+    if !providers.is_empty() && failed.len() == providers.len() {
+        return Err(ApiError::unavailable(
+            ErrorCode::ItemLookupUnavailable,
+            format!(
+                "No metadata service could be reached: {}",
+                failed.join(", ")
+            ),
+        ));
+    }
+    Err(ApiError::not_found(
+        ErrorCode::ItemLookupNotFound,
+        format!("No metadata service knows {}", code),
+    ))
+}
+
+/// A create request for what the service found, of the requested kind or
+/// else the one the service reports
+async fn prefill(
+    state: &AppState,
+    org_id: Uuid,
+    code: &str,
+    kind: Option<&str>,
+    found: Metadata,
+) -> Result<CreateItemRequest, ApiError> {
+    let kind_name = kind.or(found.kind.as_deref()).unwrap_or(FALLBACK_KIND);
+    // The organization's own copy of a shared kind comes first
+    let kind_id: Uuid = sqlx::query_scalar(
+        "SELECT id FROM kinds WHERE name = $1 AND (org_id = $2 OR org_id IS NULL)
+         ORDER BY org_id NULLS LAST LIMIT 1",
+    )
+    .bind(kind_name)
+    .bind(org_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| {
+        ApiError::not_found(
+            ErrorCode::KindNotFound,
+            format!("Kind '{}' not found", kind_name),
+        )
+    })?;
+
+    // Only the fields the kind has; an organization's copy may lack some
+    let field_names: Vec<String> = sqlx::query_scalar(
+        "SELECT f.name FROM kind_fields kf JOIN fields f ON f.id = kf.field_id
+         WHERE kf.kind_id = $1",
+    )
+    .bind(kind_id)
+    .fetch_all(&state.pool)
+    .await?;
+    let soft_fields: Map<String, Value> = found
+        .soft_fields
+        .into_iter()
+        .filter(|(name, _)| field_names.contains(name))
+        .collect();
+
+    Ok(CreateItemRequest {
+        kind_id,
+        name: found.title.unwrap_or_else(|| code.to_string()),
+        description: found.description,
+        notes: None,
+        location_id: None,
+        date_acquired: None,
+        barcode: Some(code.to_string()),
+        catalog_code: found
+            .catalog_code
+            .filter(|c| c.chars().count() <= CODE_MAX_LEN),
+        width_mm: None,
+        height_mm: None,
+        depth_mm: None,
+        weight_g: None,
+        soft_fields: (!soft_fields.is_empty()).then_some(Value::Object(soft_fields)),
+        tags: None,
+    })
+}
@@ -1,22 +1,51 @@
+pub mod api_keys;
 pub mod auth;
+pub mod borrowers;
+pub mod broadcasts;
+pub mod changelog;
 pub mod collections;
+mod download;
+pub mod duplicates;
+pub mod events;
+pub mod exports;
 pub mod fields;
+pub mod files;
+pub mod history;
+pub mod imports;
+pub mod invites;
+pub mod item_export;
 pub mod items;
+pub mod jobs;
 pub mod kinds;
+pub mod labels;
 pub mod locations;
+pub mod lookup;
+pub mod org_pages;
+pub mod org_snapshots;
 pub mod organizations;
+pub mod photos;
+pub mod preferences;
+pub mod reports;
+pub mod settings;
+pub mod share_links;
+pub mod smart_collections;
+pub mod suggestions;
 pub mod tags;
 pub mod users;
 
 use crate::api::{
+    metrics,
     middleware::{
-        auth_middleware, org_access_middleware, require_auth_middleware, system_admin_middleware,
+        auth_middleware, org_access_middleware, org_read_only_middleware, org_write_middleware,
+        require_auth_middleware, system_admin_middleware,
     },
     state::AppState,
 };
 use axum::{
-    Router, middleware,
-    routing::{delete, get, patch, post},
+    Router,
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{delete, get, patch, post, put},
 };
 
 /// Build the API router with all routes configured
@@ -27,11 +56,51 @@ use axum::{
 /// via `route_layer`. The global `auth_middleware` (outermost `.layer`) runs first and
 /// populates the `AuthContext` that the gates then read.
 pub fn build_router(state: AppState) -> Router {
-    // Org-scoped routes: require authentication and membership of the path org.
+    // Org-scoped routes: require authentication and membership of the path org. Any
+    // member may read, and manage their own preferences and API keys.
     let org_routes = Router::new()
         // Items
         .route("/organizations/:org_id/items", get(items::list_items))
-        .route("/organizations/:org_id/items", post(items::create_item))
+        .route(
+            "/organizations/:org_id/items/facets",
+            get(items::item_facets),
+        )
+        .route(
+            "/organizations/:org_id/items/rotation",
+            get(items::item_rotation),
+        )
+        .route(
+            "/organizations/:org_id/items/export",
+            get(item_export::export_items),
+        )
+        .route(
+            "/organizations/:org_id/items/details",
+            post(items::get_items_details),
+        )
+        .route(
+            "/organizations/:org_id/items/labels",
+            post(labels::print_labels),
+        )
+        .route(
+            "/organizations/:org_id/items/suggestions",
+            get(suggestions::list_item_suggestions),
+        )
+        .route(
+            "/organizations/:org_id/imports/:import_id",
+            get(imports::get_import),
+        )
+        .route(
+            "/organizations/:org_id/items/duplicates",
+            get(duplicates::find_duplicates),
+        )
+        .route(
+            "/organizations/:org_id/lookup/isbn/:isbn",
+            get(lookup::lookup_isbn),
+        )
+        .route(
+            "/organizations/:org_id/lookup/barcode/:code",
+            get(lookup::lookup_barcode),
+        )
         .route(
             "/organizations/:org_id/items/:item_id",
             get(items::get_item),
@@ -40,6 +109,201 @@ pub fn build_router(state: AppState) -> Router {
             "/organizations/:org_id/items/:item_id/details",
             get(items::get_item_details),
         )
+        .route(
+            "/organizations/:org_id/items/:item_id/full",
+            get(items::get_item_full),
+        )
+        .route(
+            "/organizations/:org_id/items/:item_id/history",
+            get(items::get_item_history),
+        )
+        .route(
+            "/organizations/:org_id/items/:item_id/usage",
+            get(items::get_item_usage),
+        )
+        .route(
+            "/organizations/:org_id/items/:item_id/photos",
+            get(photos::list_item_photos),
+        )
+        .route(
+            "/organizations/:org_id/items/:item_id/share-link",
+            post(share_links::create_share_link),
+        )
+        .route(
+            "/organizations/:org_id/history/export",
+            get(history::export_history),
+        )
+        // Borrowers
+        .route(
+            "/organizations/:org_id/borrowers",
+            get(borrowers::list_borrowers),
+        )
+        .route(
+            "/organizations/:org_id/borrowers/:borrower_id/history",
+            get(borrowers::get_borrower_history),
+        )
+        .route(
+            "/organizations/:org_id/items/:item_id/loans",
+            get(borrowers::list_item_loans),
+        )
+        // Settings
+        .route(
+            "/organizations/:org_id/settings",
+            get(settings::get_settings),
+        )
+        // Preferences
+        .route(
+            "/organizations/:org_id/preferences",
+            get(preferences::get_preferences),
+        )
+        .route(
+            "/organizations/:org_id/preferences",
+            put(preferences::update_preferences),
+        )
+        .route(
+            "/organizations/:org_id/preferences/export",
+            get(preferences::export_preferences),
+        )
+        .route(
+            "/organizations/:org_id/preferences/import",
+            post(preferences::import_preferences),
+        )
+        // Invites
+        .route("/organizations/:org_id/invites", get(invites::list_invites))
+        // API keys
+        .route(
+            "/organizations/:org_id/api-keys",
+            get(api_keys::list_api_keys),
+        )
+        .route(
+            "/organizations/:org_id/api-keys",
+            post(api_keys::create_api_key),
+        )
+        .route(
+            "/organizations/:org_id/api-keys/:key_id",
+            get(api_keys::get_api_key),
+        )
+        .route(
+            "/organizations/:org_id/api-keys/:key_id",
+            patch(api_keys::update_api_key),
+        )
+        .route(
+            "/organizations/:org_id/api-keys/:key_id",
+            delete(api_keys::delete_api_key),
+        )
+        // Pages
+        .route("/organizations/:org_id/pages", get(org_pages::list_pages))
+        .route(
+            "/organizations/:org_id/pages/:page_id",
+            get(org_pages::get_page),
+        )
+        .route(
+            "/organizations/:org_id/pages/:page_id/versions",
+            get(org_pages::list_page_versions),
+        )
+        // Locations
+        .route(
+            "/organizations/:org_id/locations",
+            get(locations::list_locations),
+        )
+        // Kinds
+        .route("/organizations/:org_id/kinds", get(kinds::list_kinds))
+        .route(
+            "/organizations/:org_id/kinds/:kind_id",
+            get(kinds::get_kind),
+        )
+        .route(
+            "/organizations/:org_id/kinds/:kind_id/fields/:field_id/impact",
+            get(kinds::get_field_impact),
+        )
+        // Fields
+        .route("/organizations/:org_id/fields", get(fields::list_fields))
+        .route(
+            "/organizations/:org_id/fields/:field_id",
+            get(fields::get_field),
+        )
+        // Collections
+        .route(
+            "/organizations/:org_id/collections",
+            get(collections::list_collections),
+        )
+        .route(
+            "/organizations/:org_id/collections/:collection_id",
+            get(collections::get_collection),
+        )
+        .route(
+            "/organizations/:org_id/collections/:collection_id/items",
+            get(collections::list_collection_items),
+        )
+        // Smart collections
+        .route(
+            "/organizations/:org_id/smart-collections",
+            get(smart_collections::list_smart_collections),
+        )
+        .route(
+            "/organizations/:org_id/smart-collections/:collection_id",
+            get(smart_collections::get_smart_collection),
+        )
+        .route(
+            "/organizations/:org_id/smart-collections/:collection_id/items",
+            get(smart_collections::list_smart_collection_items),
+        )
+        // Tags
+        .route("/organizations/:org_id/tags", get(tags::list_tags))
+        .route(
+            "/organizations/:org_id/tags/suggest",
+            get(tags::suggest_tags),
+        )
+        // Reports
+        .route(
+            "/organizations/:org_id/reports/storage",
+            get(reports::storage_report),
+        )
+        .route(
+            "/organizations/:org_id/reports/item-states",
+            get(reports::item_state_summary),
+        )
+        .route(
+            "/organizations/:org_id/reports/overdue-loans",
+            get(reports::overdue_loans_report),
+        )
+        .route(
+            "/organizations/:org_id/reports/usage",
+            get(reports::usage_report),
+        )
+        .route(
+            "/organizations/:org_id/stats",
+            get(reports::organization_stats),
+        )
+        // Live changes
+        .route("/organizations/:org_id/events", get(events::stream_events))
+        .route_layer(middleware::from_fn(org_access_middleware));
+
+    // Org-scoped changes: additionally require a role that may write (not VIEWER), and
+    // refused while the organization is read-only.
+    let org_write_routes = Router::new()
+        // Items
+        .route("/organizations/:org_id/items", post(items::create_item))
+        .route(
+            "/organizations/:org_id/items/bulk",
+            post(items::bulk_create_items),
+        )
+        .route(
+            "/organizations/:org_id/items/bulk-update",
+            post(items::bulk_update_items),
+        )
+        .route(
+            "/organizations/:org_id/items/enrich",
+            post(suggestions::enrich_items),
+        )
+        .route(
+            "/organizations/:org_id/items/suggestions/:suggestion_id/apply",
+            post(suggestions::apply_item_suggestion),
+        )
+        .route(
+            "/organizations/:org_id/items/suggestions/:suggestion_id/dismiss",
+            post(suggestions::dismiss_item_suggestion),
+        )
         .route(
             "/organizations/:org_id/items/:item_id",
             patch(items::update_item),
@@ -48,26 +312,57 @@ pub fn build_router(state: AppState) -> Router {
             "/organizations/:org_id/items/:item_id",
             delete(items::delete_item),
         )
-        // Locations
         .route(
-            "/organizations/:org_id/locations",
-            get(locations::list_locations),
+            "/organizations/:org_id/items/:item_id/used",
+            post(items::mark_item_used),
         )
+        .route(
+            "/organizations/:org_id/items/:item_id/merge",
+            post(duplicates::merge_items),
+        )
+        .route(
+            "/organizations/:org_id/items/:item_id/photos/:photo_id",
+            delete(photos::delete_item_photo),
+        )
+        .route(
+            "/organizations/:org_id/items/photos/import",
+            post(photos::import_photos).layer(DefaultBodyLimit::max(photos::MAX_ARCHIVE_BYTES)),
+        )
+        .route(
+            "/organizations/:org_id/imports",
+            post(imports::create_import)
+                .layer(DefaultBodyLimit::max(crate::api::imports::MAX_BODY_BYTES)),
+        )
+        // Settings
+        .route(
+            "/organizations/:org_id/settings/logo",
+            put(settings::upload_logo).delete(settings::delete_logo),
+        )
+        // Pages
+        .route("/organizations/:org_id/pages", post(org_pages::create_page))
+        .route(
+            "/organizations/:org_id/pages/:page_id",
+            patch(org_pages::update_page),
+        )
+        .route(
+            "/organizations/:org_id/pages/:page_id",
+            delete(org_pages::delete_page),
+        )
+        // Locations
         .route(
             "/organizations/:org_id/locations",
             post(locations::create_location),
         )
         .route(
             "/organizations/:org_id/locations/:location_id",
-            delete(locations::delete_location),
+            patch(locations::update_location).delete(locations::delete_location),
         )
-        // Kinds
-        .route("/organizations/:org_id/kinds", get(kinds::list_kinds))
-        .route("/organizations/:org_id/kinds", post(kinds::create_kind))
         .route(
-            "/organizations/:org_id/kinds/:kind_id",
-            get(kinds::get_kind),
+            "/organizations/:org_id/locations/:location_id/merge",
+            post(locations::merge_locations),
         )
+        // Kinds
+        .route("/organizations/:org_id/kinds", post(kinds::create_kind))
         .route(
             "/organizations/:org_id/kinds/:kind_id",
             patch(kinds::update_kind),
@@ -84,17 +379,8 @@ pub fn build_router(state: AppState) -> Router {
             "/organizations/:org_id/kinds/:kind_id/revert",
             post(kinds::revert_kind),
         )
-        .route(
-            "/organizations/:org_id/kinds/:kind_id/fields/:field_id/impact",
-            get(kinds::get_field_impact),
-        )
         // Fields
-        .route("/organizations/:org_id/fields", get(fields::list_fields))
         .route("/organizations/:org_id/fields", post(fields::create_field))
-        .route(
-            "/organizations/:org_id/fields/:field_id",
-            get(fields::get_field),
-        )
         .route(
             "/organizations/:org_id/fields/:field_id",
             patch(fields::update_field),
@@ -104,26 +390,61 @@ pub fn build_router(state: AppState) -> Router {
             delete(fields::delete_field),
         )
         // Collections
-        .route(
-            "/organizations/:org_id/collections",
-            get(collections::list_collections),
-        )
         .route(
             "/organizations/:org_id/collections",
             post(collections::create_collection),
         )
         .route(
             "/organizations/:org_id/collections/:collection_id",
-            delete(collections::delete_collection),
+            patch(collections::update_collection).delete(collections::delete_collection),
+        )
+        .route(
+            "/organizations/:org_id/collections/:collection_id/items",
+            post(collections::add_collection_item),
+        )
+        .route(
+            "/organizations/:org_id/collections/:collection_id/items/:item_id",
+            delete(collections::remove_collection_item),
+        )
+        // Smart collections
+        .route(
+            "/organizations/:org_id/smart-collections",
+            post(smart_collections::create_smart_collection),
+        )
+        .route(
+            "/organizations/:org_id/smart-collections/:collection_id",
+            patch(smart_collections::update_smart_collection)
+                .delete(smart_collections::delete_smart_collection),
+        )
+        // Invites
+        .route(
+            "/organizations/:org_id/invites",
+            post(invites::create_invite),
+        )
+        .route(
+            "/organizations/:org_id/invites/:invite_id",
+            delete(invites::delete_invite),
         )
         // Tags
-        .route("/organizations/:org_id/tags", get(tags::list_tags))
         .route("/organizations/:org_id/tags", post(tags::create_tag))
         .route(
             "/organizations/:org_id/tags/:tag_name",
             delete(tags::delete_tag),
         )
-        .route_layer(middleware::from_fn(org_access_middleware));
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            org_read_only_middleware,
+        ))
+        .route_layer(middleware::from_fn(org_write_middleware));
+
+    // Org settings: as changes, but allowed while the organization is read-only so its
+    // admins can turn read-only mode off.
+    let org_settings_routes = Router::new()
+        .route(
+            "/organizations/:org_id/settings",
+            put(settings::update_settings),
+        )
+        .route_layer(middleware::from_fn(org_write_middleware));
 
     // System administration routes: require a SYSTEM-org super-admin.
     let system_routes = Router::new()
@@ -148,16 +469,40 @@ pub fn build_router(state: AppState) -> Router {
             "/admin/organizations/:org_id",
             delete(organizations::delete_organization),
         )
+        .route(
+            "/admin/organizations/:org_id/read-only",
+            get(organizations::get_organization_read_only)
+                .put(organizations::set_organization_read_only),
+        )
         .route(
             "/admin/organizations/:org_id/users",
             get(organizations::list_organization_users),
         )
+        .route(
+            "/admin/organizations/:org_id/export",
+            get(org_snapshots::export_organization),
+        )
+        .route(
+            "/admin/organizations/import",
+            post(org_snapshots::import_organization)
+                .layer(DefaultBodyLimit::max(org_snapshots::MAX_SNAPSHOT_BYTES)),
+        )
         // Admin - Users
         .route("/admin/users", get(users::list_users))
         .route("/admin/users", post(users::create_user))
         .route("/admin/users/:user_id", get(users::get_user))
         .route("/admin/users/:user_id", patch(users::update_user))
         .route("/admin/users/:user_id", delete(users::delete_user))
+        .route(
+            "/admin/users/:user_id/reset-password",
+            post(users::reset_user_password),
+        )
+        .route(
+            "/admin/users/:user_id/deactivate",
+            post(users::deactivate_user),
+        )
+        .route("/admin/users/:user_id/activate", post(users::activate_user))
+        .route("/admin/users/:user_id/unlock", post(users::unlock_user))
         // Admin - User Organizations
         .route(
             "/admin/users/:user_id/organizations",
@@ -175,23 +520,60 @@ pub fn build_router(state: AppState) -> Router {
             "/admin/users/:user_id/organizations/:org_id",
             delete(users::remove_user_from_organization),
         )
+        // Admin - Broadcasts
+        .route(
+            "/admin/broadcasts",
+            get(broadcasts::list_broadcasts).post(broadcasts::create_broadcast),
+        )
+        .route(
+            "/admin/broadcasts/:broadcast_id",
+            delete(broadcasts::delete_broadcast),
+        )
+        // Admin - Final data exports
+        .route("/admin/exports", get(exports::list_exports))
+        .route("/admin/exports/:export_id", get(exports::download_export))
+        .route("/admin/jobs", get(jobs::list_jobs))
+        .route("/admin/jobs/:job_id", get(jobs::get_job))
         .route_layer(middleware::from_fn(system_admin_middleware));
 
     // Authenticated (but not org/role gated) routes.
     let authed_routes = Router::new()
         .route("/auth/me", get(auth::get_me))
+        .route("/auth/logout", post(auth::logout))
+        .route("/auth/me/data-export", get(auth::export_my_data))
+        .route(
+            "/auth/me/deletion-request",
+            post(auth::request_data_deletion),
+        )
         .route_layer(middleware::from_fn(require_auth_middleware));
 
     // Public routes: no authentication required.
     let public_routes = Router::new()
         .route("/auth/login", post(auth::login))
-        .route("/auth/select-org", post(auth::select_org));
+        .route("/auth/select-org", post(auth::select_org))
+        .route("/auth/accept-terms", post(auth::accept_terms))
+        .route("/auth/refresh", post(auth::refresh))
+        .route("/auth/accept-invite", post(invites::accept_invite))
+        .route("/auth/oidc", get(auth::oidc_provider))
+        .route("/auth/oidc/authorize", get(auth::oidc_authorize))
+        .route("/auth/oidc/callback", post(auth::oidc_callback))
+        .route("/changelog", get(changelog::get_changelog))
+        // Presigned storage links and share links carry their own signature
+        .route("/files/*key", get(files::get_presigned_file))
+        .route("/shared-items/:token", get(share_links::get_shared_item));
 
-    Router::new()
+    let mut router = Router::new()
         .merge(org_routes)
+        .merge(org_write_routes)
+        .merge(org_settings_routes)
         .merge(system_routes)
         .merge(authed_routes)
-        .merge(public_routes)
+        .merge(public_routes);
+    if state.metrics.is_some() {
+        router = router.route_layer(middleware::from_fn(metrics::track_requests));
+    }
+
+    router
         .with_state(state.clone())
         // Add auth middleware to extract tokens from headers (runs before the gates above)
         .layer(middleware::from_fn_with_state(state, auth_middleware))
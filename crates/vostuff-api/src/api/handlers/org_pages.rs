@@ -0,0 +1,378 @@
+//! Organization documentation pages: Markdown notes such as "How we grade
+//! vinyl" or a shelving map, kept next to the collection they describe.
+//!
+//! Any member may write and edit pages; admins may delete them. Every edit
+//! makes a new version and the old ones are kept. An edit names the version it
+//! was made to, so two members editing at once cannot overwrite each other.
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{
+        CreateOrgPageRequest, ErrorCode, ErrorResponse, OrgPage, OrgPageSummary, OrgPageVersion,
+        Permission, UpdateOrgPageRequest,
+    },
+    state::AppState,
+};
+use crate::auth::AuthContext;
+
+/// Longest page title accepted
+const MAX_TITLE_LEN: usize = 255;
+
+/// Current page with the author of its latest version
+const PAGE_SELECT: &str = "SELECT p.id, p.organization_id, p.title, p.body, p.version,
+        v.edited_by AS updated_by, u.name AS updated_by_name, p.created_at, p.updated_at
+     FROM org_pages p
+     LEFT JOIN org_page_versions v ON v.page_id = p.id AND v.version = p.version
+     LEFT JOIN users u ON u.id = v.edited_by";
+
+/// List the organization's pages, by title
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/pages",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "List of pages", body = Vec<OrgPageSummary>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "pages"
+)]
+pub async fn list_pages(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<Vec<OrgPageSummary>>, ApiError> {
+    let pages = sqlx::query_as::<_, OrgPageSummary>(
+        "SELECT p.id, p.title, p.version, u.name AS updated_by_name, p.updated_at
+         FROM org_pages p
+         LEFT JOIN org_page_versions v ON v.page_id = p.id AND v.version = p.version
+         LEFT JOIN users u ON u.id = v.edited_by
+         WHERE p.organization_id = $1
+         ORDER BY LOWER(p.title)",
+    )
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(pages))
+}
+
+/// Create a page
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/pages",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = CreateOrgPageRequest,
+    responses(
+        (status = 201, description = "Page created", body = OrgPage),
+        (status = 400, description = "Invalid title", body = ErrorResponse),
+        (status = 409, description = "Another page has the same title", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "pages"
+)]
+pub async fn create_page(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<CreateOrgPageRequest>,
+) -> Result<(StatusCode, Json<OrgPage>), ApiError> {
+    if !auth.can(Permission::PagesWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The pages:write permission is required to edit pages",
+        ));
+    }
+    let title = valid_title(&req.title)?;
+
+    let mut tx = state.pool.begin().await?;
+    let page_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO org_pages (organization_id, title, body) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(org_id)
+    .bind(title)
+    .bind(&req.body)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(write_error)?;
+    record_version(&mut tx, page_id, 1, title, &req.body, auth.user_id).await?;
+    let page = fetch_page(&mut tx, org_id, page_id)
+        .await?
+        .ok_or_else(not_found)?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(page)))
+}
+
+/// Get a page
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/pages/{page_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("page_id" = Uuid, Path, description = "Page ID")
+    ),
+    responses(
+        (status = 200, description = "Page", body = OrgPage),
+        (status = 404, description = "Page not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "pages"
+)]
+pub async fn get_page(
+    State(state): State<AppState>,
+    Path((org_id, page_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<OrgPage>, ApiError> {
+    let mut conn = state.pool.acquire().await?;
+    let page = fetch_page(&mut conn, org_id, page_id)
+        .await?
+        .ok_or_else(not_found)?;
+
+    Ok(Json(page))
+}
+
+/// Edit a page, making a new version
+///
+/// `version` is the version the edit was made to. If the page has been edited
+/// since, the edit is refused with 409 so it can be redone on the latest text.
+/// An edit that changes nothing makes no new version.
+#[utoipa::path(
+    patch,
+    path = "/api/organizations/{org_id}/pages/{page_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("page_id" = Uuid, Path, description = "Page ID")
+    ),
+    request_body = UpdateOrgPageRequest,
+    responses(
+        (status = 200, description = "Page updated", body = OrgPage),
+        (status = 400, description = "Invalid title", body = ErrorResponse),
+        (status = 404, description = "Page not found", body = ErrorResponse),
+        (status = 409, description = "Page edited since `version`, or another page has the same title", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "pages"
+)]
+pub async fn update_page(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, page_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateOrgPageRequest>,
+) -> Result<Json<OrgPage>, ApiError> {
+    if !auth.can(Permission::PagesWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The pages:write permission is required to edit pages",
+        ));
+    }
+    let new_title = req.title.as_deref().map(valid_title).transpose()?;
+
+    let mut tx = state.pool.begin().await?;
+    let (version, old_title, old_body): (i32, String, String) = sqlx::query_as(
+        "SELECT version, title, body FROM org_pages
+         WHERE id = $1 AND organization_id = $2 FOR UPDATE",
+    )
+    .bind(page_id)
+    .bind(org_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(not_found)?;
+
+    if req.version != version {
+        return Err(version_conflict(version));
+    }
+
+    let title = new_title.unwrap_or(&old_title);
+    let body = req.body.as_deref().unwrap_or(&old_body);
+    if title != old_title || body != old_body {
+        let version = version + 1;
+        sqlx::query(
+            "UPDATE org_pages SET title = $2, body = $3, version = $4, updated_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(page_id)
+        .bind(title)
+        .bind(body)
+        .bind(version)
+        .execute(&mut *tx)
+        .await
+        .map_err(write_error)?;
+        record_version(&mut tx, page_id, version, title, body, auth.user_id).await?;
+    }
+    let page = fetch_page(&mut tx, org_id, page_id)
+        .await?
+        .ok_or_else(not_found)?;
+    tx.commit().await?;
+
+    Ok(Json(page))
+}
+
+/// Delete a page and all its versions (requires `pages:delete`)
+#[utoipa::path(
+    delete,
+    path = "/api/organizations/{org_id}/pages/{page_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("page_id" = Uuid, Path, description = "Page ID")
+    ),
+    responses(
+        (status = 204, description = "Page deleted"),
+        (status = 403, description = "Requires the pages:delete permission", body = ErrorResponse),
+        (status = 404, description = "Page not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "pages"
+)]
+pub async fn delete_page(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, page_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    if !auth.can(Permission::PagesDelete) {
+        return Err(ApiError::auth_forbidden(
+            "The pages:delete permission is required to delete pages",
+        ));
+    }
+    let result = sqlx::query("DELETE FROM org_pages WHERE id = $1 AND organization_id = $2")
+        .bind(page_id)
+        .bind(org_id)
+        .execute(&state.pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(not_found());
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List a page's versions, newest first
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/pages/{page_id}/versions",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("page_id" = Uuid, Path, description = "Page ID")
+    ),
+    responses(
+        (status = 200, description = "Page versions", body = Vec<OrgPageVersion>),
+        (status = 404, description = "Page not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "pages"
+)]
+pub async fn list_page_versions(
+    State(state): State<AppState>,
+    Path((org_id, page_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<OrgPageVersion>>, ApiError> {
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM org_pages WHERE id = $1 AND organization_id = $2)",
+    )
+    .bind(page_id)
+    .bind(org_id)
+    .fetch_one(&state.pool)
+    .await?;
+    if !exists {
+        return Err(not_found());
+    }
+
+    let versions = sqlx::query_as::<_, OrgPageVersion>(
+        "SELECT v.version, v.title, v.body, v.edited_by, u.name AS edited_by_name, v.created_at
+         FROM org_page_versions v
+         LEFT JOIN users u ON u.id = v.edited_by
+         WHERE v.page_id = $1
+         ORDER BY v.version DESC",
+    )
+    .bind(page_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(versions))
+}
+
+/// Trimmed title, if it is acceptable
+fn valid_title(title: &str) -> Result<&str, ApiError> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(ApiError::validation_failed("Page title must not be empty"));
+    }
+    if title.chars().count() > MAX_TITLE_LEN {
+        return Err(ApiError::validation_failed(format!(
+            "Page title must be at most {} characters",
+            MAX_TITLE_LEN
+        )));
+    }
+    Ok(title)
+}
+
+async fn fetch_page(
+    conn: &mut PgConnection,
+    org_id: Uuid,
+    page_id: Uuid,
+) -> Result<Option<OrgPage>, ApiError> {
+    sqlx::query_as::<_, OrgPage>(&format!(
+        "{} WHERE p.id = $1 AND p.organization_id = $2",
+        PAGE_SELECT
+    ))
+    .bind(page_id)
+    .bind(org_id)
+    .fetch_optional(conn)
+    .await
+    .map_err(ApiError::internal)
+}
+
+async fn record_version(
+    conn: &mut PgConnection,
+    page_id: Uuid,
+    version: i32,
+    title: &str,
+    body: &str,
+    edited_by: Uuid,
+) -> Result<(), ApiError> {
+    sqlx::query(
+        "INSERT INTO org_page_versions (page_id, version, title, body, edited_by)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(page_id)
+    .bind(version)
+    .bind(title)
+    .bind(body)
+    .bind(edited_by)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+// ── Helpers ────────────────────────────────────────────────────────────────
+
+/// Map a page INSERT/UPDATE failure, reporting title clashes as 409
+fn write_error(err: sqlx::Error) -> ApiError {
+    match err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::conflict(
+            ErrorCode::PageTitleConflict,
+            "A page with this title already exists",
+        ),
+        err => ApiError::internal(err),
+    }
+}
+
+fn version_conflict(current: i32) -> ApiError {
+    ApiError::conflict(
+        ErrorCode::PageVersionConflict,
+        format!(
+            "The page has been edited since; it is now at version {}",
+            current
+        ),
+    )
+}
+
+fn not_found() -> ApiError {
+    ApiError::not_found(ErrorCode::PageNotFound, "Page not found")
+}
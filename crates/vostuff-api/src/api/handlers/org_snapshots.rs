@@ -0,0 +1,456 @@
+//! Moving an organization between instances.
+//!
+//! `GET /admin/organizations/{org_id}/export` writes an organization's data
+//! as a snapshot: its settings, then one section of records per table, each
+//! record the table's row as JSON. `POST /admin/organizations/import` creates
+//! a new organization from a snapshot. Every record is given a new ID and
+//! references between records follow, so a snapshot can be imported into the
+//! instance it came from as well as another; references to the shared kinds
+//! and fields, whose IDs are the same everywhere, are kept. Any other
+//! reference to a record outside the snapshot rejects the import, so a
+//! snapshot cannot reach into another organization's records.
+//!
+//! Users are not part of a snapshot: members, API keys and invites stay
+//! behind, and the history's users are cleared. Photos and the logo, held in
+//! file storage, are not included either.
+
+use std::collections::{HashMap, HashSet};
+
+use axum::{Json, extract::Path, extract::State, http::StatusCode};
+use chrono::Utc;
+use serde_json::{Map, Value};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{ErrorCode, ErrorResponse, Organization},
+    state::AppState,
+};
+
+/// `snapshot_type` of every organization snapshot
+const SNAPSHOT_TYPE: &str = "organization";
+
+/// Version of the snapshot layout, raised when it changes incompatibly
+const FORMAT_VERSION: i64 = 1;
+
+/// Largest snapshot accepted, as the import's request body limit
+pub const MAX_SNAPSHOT_BYTES: usize = 256 * 1024 * 1024;
+
+/// A snapshot section: the records of one table, in the order they are
+/// imported so each record's references are already in place
+struct Section {
+    name: &'static str,
+    /// Columns exported and imported; generated columns are left out
+    columns: &'static str,
+    /// Selects the organization's records; `$1` is its ID
+    filter: &'static str,
+}
+
+impl Section {
+    /// The column naming the organization a record belongs to, if any
+    fn organization_column(&self) -> Option<&'static str> {
+        self.columns
+            .split(',')
+            .map(str::trim)
+            .find(|column| ORGANIZATION_COLUMNS.contains(column))
+    }
+}
+
+const SECTIONS: &[Section] = &[
+    Section {
+        name: "locations",
+        columns: "id, organization_id, name, created_at, updated_at",
+        filter: "organization_id = $1",
+    },
+    Section {
+        name: "tags",
+        columns: "organization_id, name, created_at",
+        filter: "organization_id = $1",
+    },
+    Section {
+        name: "fields",
+        columns: "id, org_id, name, display_name, field_type, created_at, updated_at",
+        filter: "org_id = $1",
+    },
+    Section {
+        name: "enum_values",
+        columns: "id, field_id, value, display_value, sort_order",
+        filter: "field_id IN (SELECT id FROM fields WHERE org_id = $1)",
+    },
+    Section {
+        name: "kinds",
+        columns: "id, org_id, name, display_name, created_at, updated_at",
+        filter: "org_id = $1",
+    },
+    Section {
+        name: "kind_fields",
+        columns: "kind_id, field_id, display_order",
+        filter: "kind_id IN (SELECT id FROM kinds WHERE org_id = $1)",
+    },
+    Section {
+        name: "items",
+        columns: "id, organization_id, kind_id, state, name, description, notes, location_id,
+                  date_entered, date_acquired, barcode, catalog_code, width_mm, height_mm,
+                  depth_mm, weight_g, last_used_at, soft_fields, created_at, updated_at",
+        filter: "organization_id = $1",
+    },
+    Section {
+        name: "collections",
+        columns: "id, organization_id, name, description, notes, cover_item_id, sort_order,
+                  created_at, updated_at",
+        filter: "organization_id = $1",
+    },
+    Section {
+        name: "smart_collections",
+        columns: "id, organization_id, name, description, filter, created_at, updated_at",
+        filter: "organization_id = $1",
+    },
+    Section {
+        name: "item_tags",
+        columns: "item_id, organization_id, tag_name, created_at",
+        filter: "organization_id = $1",
+    },
+    Section {
+        name: "item_collections",
+        columns: "item_id, collection_id, created_at",
+        filter: "item_id IN (SELECT id FROM items WHERE organization_id = $1)",
+    },
+    Section {
+        name: "item_loan_details",
+        columns: "item_id, date_loaned, date_due_back, loaned_to, created_at, updated_at",
+        filter: "item_id IN (SELECT id FROM items WHERE organization_id = $1)",
+    },
+    Section {
+        name: "item_missing_details",
+        columns: "item_id, date_missing, created_at, updated_at",
+        filter: "item_id IN (SELECT id FROM items WHERE organization_id = $1)",
+    },
+    Section {
+        name: "item_disposed_details",
+        columns: "item_id, date_disposed, created_at, updated_at",
+        filter: "item_id IN (SELECT id FROM items WHERE organization_id = $1)",
+    },
+    Section {
+        name: "borrowers",
+        columns: "id, organization_id, name, created_at, updated_at",
+        filter: "organization_id = $1",
+    },
+    Section {
+        name: "item_loans",
+        columns: "id, organization_id, item_id, borrower_id, date_loaned, date_due_back,
+                  date_returned, created_at, updated_at",
+        filter: "organization_id = $1",
+    },
+    Section {
+        name: "audit_log",
+        columns: "id, organization_id, item_id, user_id, action, change_date, change_details, changes",
+        filter: "organization_id = $1",
+    },
+    Section {
+        name: "usage_log",
+        columns: "id, organization_id, item_id, user_id, used_at, note",
+        filter: "organization_id = $1",
+    },
+    Section {
+        name: "org_pages",
+        columns: "id, organization_id, title, body, version, created_at, updated_at",
+        filter: "organization_id = $1",
+    },
+    Section {
+        name: "org_page_versions",
+        columns: "page_id, version, title, body, edited_by, created_at",
+        filter: "page_id IN (SELECT id FROM org_pages WHERE organization_id = $1)",
+    },
+];
+
+/// Columns naming the organization a record belongs to
+const ORGANIZATION_COLUMNS: &[&str] = &["organization_id", "org_id"];
+
+/// Columns referring to another record of the snapshot, or to a shared kind
+/// or field
+const REFERENCE_COLUMNS: &[&str] = &[
+    "location_id",
+    "kind_id",
+    "field_id",
+    "item_id",
+    "collection_id",
+    "cover_item_id",
+    "borrower_id",
+    "page_id",
+];
+
+/// The only references that may be to a shared kind or field rather than a
+/// record of the snapshot: section, column and the shared table. Export never
+/// writes shared records' own rows, such as a shared field's enum values, so
+/// nothing else may name them.
+const SHARED_REFERENCES: &[(&str, &str, &str)] = &[
+    ("items", "kind_id", "kinds"),
+    ("kind_fields", "field_id", "fields"),
+];
+
+/// Columns naming a user, cleared on import
+const USER_COLUMNS: &[&str] = &["user_id", "edited_by"];
+
+/// Export an organization for moving to another instance
+///
+/// The snapshot holds the organization's settings, locations, collections,
+/// tags, own kinds and fields, items with their tags and states, borrowers
+/// and loans, history and pages. Users, photos and the logo are not included.
+#[utoipa::path(
+    get,
+    path = "/api/admin/organizations/{org_id}/export",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Organization snapshot", body = serde_json::Value),
+        (status = 404, description = "Organization not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-organizations"
+)]
+pub async fn export_organization(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<Value>, ApiError> {
+    // One transaction, so the sections agree with each other
+    let mut tx = state.pool.begin().await?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+        .execute(&mut *tx)
+        .await?;
+
+    let organization: Option<Value> = sqlx::query_scalar(
+        "SELECT jsonb_build_object('name', name, 'description', description, 'settings', settings)
+         FROM organizations WHERE id = $1",
+    )
+    .bind(org_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(organization) = organization else {
+        return Err(ApiError::not_found(
+            ErrorCode::OrganizationNotFound,
+            "Organization not found",
+        ));
+    };
+
+    let mut snapshot = Map::new();
+    snapshot.insert("snapshot_type".to_string(), SNAPSHOT_TYPE.into());
+    snapshot.insert("format_version".to_string(), FORMAT_VERSION.into());
+    snapshot.insert("exported_at".to_string(), Utc::now().to_rfc3339().into());
+    snapshot.insert("organization_id".to_string(), org_id.to_string().into());
+    snapshot.insert("organization".to_string(), organization);
+    for section in SECTIONS {
+        let records: Vec<Value> = sqlx::query_scalar(&format!(
+            "SELECT to_jsonb(r) FROM (SELECT {} FROM {} WHERE {}) r",
+            section.columns, section.name, section.filter
+        ))
+        .bind(org_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        snapshot.insert(section.name.to_string(), Value::Array(records));
+    }
+    tx.commit().await?;
+
+    Ok(Json(Value::Object(snapshot)))
+}
+
+/// Import an organization exported from this or another instance
+///
+/// Creates a new organization from a snapshot written by
+/// `GET /api/admin/organizations/{org_id}/export`, with new IDs for all its
+/// records. The organization has no members yet; add them with
+/// `POST /api/admin/users/{user_id}/organizations/{org_id}`.
+#[utoipa::path(
+    post,
+    path = "/api/admin/organizations/import",
+    request_body(content = serde_json::Value, description = "Organization snapshot"),
+    responses(
+        (status = 201, description = "Organization imported", body = Organization),
+        (status = 400, description = "Not an organization snapshot, or its records do not fit this instance", body = ErrorResponse),
+        (status = 413, description = "The snapshot is too large"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-organizations"
+)]
+pub async fn import_organization(
+    State(state): State<AppState>,
+    Json(snapshot): Json<Value>,
+) -> Result<(StatusCode, Json<Organization>), ApiError> {
+    if snapshot["snapshot_type"] != SNAPSHOT_TYPE {
+        return Err(ApiError::validation_failed("Not an organization snapshot"));
+    }
+    if snapshot["format_version"] != FORMAT_VERSION {
+        return Err(ApiError::validation_failed(format!(
+            "Unsupported snapshot format_version {}; expected {}",
+            snapshot["format_version"], FORMAT_VERSION
+        )));
+    }
+    let organization = &snapshot["organization"];
+    let Some(name) = organization["name"].as_str() else {
+        return Err(ApiError::validation_failed(
+            "The snapshot has no organization name",
+        ));
+    };
+
+    let mut sections = Vec::new();
+    for section in SECTIONS {
+        let records = match &snapshot[section.name] {
+            Value::Null => Vec::new(),
+            Value::Array(records) if records.iter().all(Value::is_object) => records.clone(),
+            _ => {
+                return Err(ApiError::validation_failed(format!(
+                    "Snapshot section '{}' must be a list of records",
+                    section.name
+                )));
+            }
+        };
+        sections.push((section, records));
+    }
+
+    let mut shared_ids: HashMap<&str, HashSet<String>> = HashMap::new();
+    for (_, _, table) in SHARED_REFERENCES {
+        let ids: Vec<String> = sqlx::query_scalar(&format!(
+            "SELECT id::text FROM {} WHERE org_id IS NULL",
+            table
+        ))
+        .fetch_all(&state.pool)
+        .await?;
+        shared_ids.insert(table, ids.into_iter().collect());
+    }
+
+    let org_id = Uuid::new_v4();
+    remap_ids(org_id, &shared_ids, &mut sections).map_err(ApiError::validation_failed)?;
+
+    let mut tx = state.pool.begin().await?;
+    let imported = async {
+        let organization = sqlx::query_as::<_, Organization>(
+            "INSERT INTO organizations (id, name, description, settings)
+             VALUES ($1, $2, $3, COALESCE($4, '{}'::jsonb))
+             RETURNING id, name, description, created_at, updated_at",
+        )
+        .bind(org_id)
+        .bind(name)
+        .bind(organization["description"].as_str())
+        .bind(organization.get("settings").filter(|s| s.is_object()))
+        .fetch_one(&mut *tx)
+        .await?;
+        for (section, records) in sections {
+            insert_section(&mut tx, section, records).await?;
+        }
+        Ok::<_, sqlx::Error>(organization)
+    }
+    .await;
+
+    let organization = match imported {
+        Ok(organization) => organization,
+        // A record that does not fit, e.g. of a kind this instance lacks
+        Err(sqlx::Error::Database(e)) => {
+            tracing::warn!("Organization snapshot rejected: {}", e);
+            return Err(ApiError::validation_failed(
+                "The snapshot could not be imported: its records do not fit this instance",
+            ));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(organization)))
+}
+
+/// Give every record a new ID and point references at the new IDs. The only
+/// references kept as they are are those in [`SHARED_REFERENCES`] to shared
+/// kinds and fields; history of items deleted before the export is given new
+/// item IDs. Any other reference outside the snapshot is an error. Every
+/// record of a section with an organization column is given the new
+/// organization, whether or not the snapshot names one.
+fn remap_ids(
+    org_id: Uuid,
+    shared_ids: &HashMap<&str, HashSet<String>>,
+    sections: &mut [(&Section, Vec<Value>)],
+) -> Result<(), String> {
+    let mut ids: HashMap<String, Value> = HashMap::new();
+    for (_, records) in sections.iter() {
+        for record in records {
+            if let Some(Value::String(id)) = record.get("id") {
+                ids.insert(id.clone(), Uuid::new_v4().to_string().into());
+            }
+        }
+    }
+
+    for (section, records) in sections.iter_mut() {
+        for record in records.iter_mut() {
+            let Value::Object(record) = record else {
+                continue;
+            };
+            if let Some(column) = section.organization_column() {
+                record.insert(column.to_string(), org_id.to_string().into());
+            }
+            for (column, value) in record.iter_mut() {
+                let column = column.as_str();
+                if ORGANIZATION_COLUMNS.contains(&column) {
+                    continue;
+                } else if USER_COLUMNS.contains(&column) {
+                    *value = Value::Null;
+                } else if column == "id" {
+                    if let Some(new_id) = value.as_str().and_then(|id| ids.get(id)) {
+                        *value = new_id.clone();
+                    }
+                } else if REFERENCE_COLUMNS.contains(&column) {
+                    let id = match value {
+                        Value::Null => continue,
+                        Value::String(id) => id.clone(),
+                        _ => {
+                            return Err(format!(
+                                "Snapshot section '{}' has a {} that is not an ID",
+                                section.name, column
+                            ));
+                        }
+                    };
+                    if let Some(new_id) = ids.get(&id) {
+                        *value = new_id.clone();
+                    } else if SHARED_REFERENCES
+                        .iter()
+                        .any(|(name, shared_column, table)| {
+                            *name == section.name
+                                && *shared_column == column
+                                && shared_ids.get(table).is_some_and(|ids| ids.contains(&id))
+                        })
+                    {
+                        // Shared kinds and fields have the same ID everywhere
+                    } else if section.name == "audit_log" && column == "item_id" {
+                        // The audit log outlives the items it records
+                        let new_id = Uuid::new_v4().to_string();
+                        *value = ids.entry(id).or_insert_with(|| new_id.into()).clone();
+                    } else {
+                        return Err(format!(
+                            "Snapshot section '{}' refers to {} {}, which is not in the snapshot",
+                            section.name, column, id
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn insert_section(
+    conn: &mut PgConnection,
+    section: &Section,
+    records: Vec<Value>,
+) -> Result<(), sqlx::Error> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    sqlx::query(&format!(
+        "INSERT INTO {table} ({columns})
+         SELECT {columns} FROM jsonb_populate_recordset(NULL::{table}, $1)",
+        table = section.name,
+        columns = section.columns
+    ))
+    .bind(Value::Array(records))
+    .execute(conn)
+    .await?;
+    Ok(())
+}
@@ -1,12 +1,20 @@
 use axum::{
     Json,
     extract::{Path, State},
-    http::StatusCode,
+    http::{StatusCode, header},
+    response::IntoResponse,
 };
 use uuid::Uuid;
 
+use super::exports;
+use super::users::UserRow;
+use crate::api::error::ApiError;
+use crate::api::validation::ValidJson;
 use crate::api::{
-    models::{CreateOrganizationRequest, ErrorResponse, Organization, UpdateOrganizationRequest},
+    models::{
+        CreateOrganizationRequest, ErrorCode, ErrorResponse, Organization, OrganizationReadOnly,
+        UpdateOrganizationRequest, UserResponse,
+    },
     state::AppState,
 };
 
@@ -22,13 +30,12 @@ use crate::api::{
 )]
 pub async fn list_organizations(
     State(state): State<AppState>,
-) -> Result<Json<Vec<Organization>>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Vec<Organization>>, ApiError> {
     let organizations = sqlx::query_as::<_, Organization>(
         "SELECT id, name, description, created_at, updated_at FROM organizations ORDER BY name",
     )
     .fetch_all(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     Ok(Json(organizations))
 }
@@ -50,23 +57,19 @@ pub async fn list_organizations(
 pub async fn get_organization(
     State(state): State<AppState>,
     Path(org_id): Path<Uuid>,
-) -> Result<Json<Organization>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Organization>, ApiError> {
     let organization = sqlx::query_as::<_, Organization>(
         "SELECT id, name, description, created_at, updated_at FROM organizations WHERE id = $1",
     )
     .bind(org_id)
     .fetch_optional(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     match organization {
         Some(org) => Ok(Json(org)),
-        None => Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "Organization not found".to_string(),
-            }),
+        None => Err(ApiError::not_found(
+            ErrorCode::OrganizationNotFound,
+            "Organization not found",
         )),
     }
 }
@@ -85,8 +88,8 @@ pub async fn get_organization(
 )]
 pub async fn create_organization(
     State(state): State<AppState>,
-    Json(req): Json<CreateOrganizationRequest>,
-) -> Result<(StatusCode, Json<Organization>), (StatusCode, Json<ErrorResponse>)> {
+    ValidJson(req): ValidJson<CreateOrganizationRequest>,
+) -> Result<(StatusCode, Json<Organization>), ApiError> {
     let organization = sqlx::query_as::<_, Organization>(
         "INSERT INTO organizations (name, description) VALUES ($1, $2)
          RETURNING id, name, description, created_at, updated_at",
@@ -94,8 +97,7 @@ pub async fn create_organization(
     .bind(&req.name)
     .bind(&req.description)
     .fetch_one(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     Ok((StatusCode::CREATED, Json(organization)))
 }
@@ -118,8 +120,8 @@ pub async fn create_organization(
 pub async fn update_organization(
     State(state): State<AppState>,
     Path(org_id): Path<Uuid>,
-    Json(req): Json<UpdateOrganizationRequest>,
-) -> Result<Json<Organization>, (StatusCode, Json<ErrorResponse>)> {
+    ValidJson(req): ValidJson<UpdateOrganizationRequest>,
+) -> Result<Json<Organization>, ApiError> {
     // Build dynamic update query
     let mut query = String::from("UPDATE organizations SET updated_at = NOW()");
     let mut param_num = 2;
@@ -143,24 +145,18 @@ pub async fn update_organization(
         query_builder = query_builder.bind(description);
     }
 
-    let organization = query_builder
-        .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?;
+    let organization = query_builder.fetch_optional(&state.pool).await?;
 
     match organization {
         Some(org) => Ok(Json(org)),
-        None => Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "Organization not found".to_string(),
-            }),
+        None => Err(ApiError::not_found(
+            ErrorCode::OrganizationNotFound,
+            "Organization not found",
         )),
     }
 }
 
-/// Delete an organization
+/// Delete an organization, keeping a final data export
 #[utoipa::path(
     delete,
     path = "/api/admin/organizations/{org_id}",
@@ -168,7 +164,7 @@ pub async fn update_organization(
         ("org_id" = Uuid, Path, description = "Organization ID")
     ),
     responses(
-        (status = 204, description = "Organization deleted successfully"),
+        (status = 204, description = "Organization deleted; the Location header links to its final data export"),
         (status = 404, description = "Organization not found", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -177,24 +173,90 @@ pub async fn update_organization(
 pub async fn delete_organization(
     State(state): State<AppState>,
     Path(org_id): Path<Uuid>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    let result = sqlx::query("DELETE FROM organizations WHERE id = $1")
-        .bind(org_id)
-        .execute(&state.pool)
+) -> Result<impl IntoResponse, ApiError> {
+    let mut tx = state.pool.begin().await?;
+
+    // Keep a final export of the organization's data for the retention window
+    let export_id = exports::archive_organization(&mut tx, &state, org_id)
         .await
-        .map_err(internal_error)?;
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| {
+            ApiError::not_found(ErrorCode::OrganizationNotFound, "Organization not found")
+        })?;
 
-    if result.rows_affected() == 0 {
-        Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "Organization not found".to_string(),
-            }),
-        ))
-    } else {
-        Ok(StatusCode::NO_CONTENT)
-    }
+    sqlx::query("DELETE FROM organizations WHERE id = $1")
+        .bind(org_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::NO_CONTENT,
+        [(header::LOCATION, exports::download_url(export_id))],
+    ))
+}
+
+/// Get whether a system administrator has made an organization read-only
+#[utoipa::path(
+    get,
+    path = "/api/admin/organizations/{org_id}/read-only",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Read-only state", body = OrganizationReadOnly),
+        (status = 404, description = "Organization not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-organizations"
+)]
+pub async fn get_organization_read_only(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<OrganizationReadOnly>, ApiError> {
+    sqlx::query_scalar("SELECT read_only FROM organizations WHERE id = $1")
+        .bind(org_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .map(|read_only| Json(OrganizationReadOnly { read_only }))
+        .ok_or_else(not_found)
+}
+
+/// Make an organization read-only, or lift it
+///
+/// While read-only, the organization's members can read its data but every
+/// change is refused with 423, including changes to its settings. Unlike the
+/// organization's own `read_only` setting, its admins cannot lift this.
+#[utoipa::path(
+    put,
+    path = "/api/admin/organizations/{org_id}/read-only",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = OrganizationReadOnly,
+    responses(
+        (status = 200, description = "Read-only state updated", body = OrganizationReadOnly),
+        (status = 404, description = "Organization not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-organizations"
+)]
+pub async fn set_organization_read_only(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<OrganizationReadOnly>,
+) -> Result<Json<OrganizationReadOnly>, ApiError> {
+    sqlx::query_scalar(
+        "UPDATE organizations SET read_only = $2, updated_at = NOW() WHERE id = $1
+         RETURNING read_only",
+    )
+    .bind(org_id)
+    .bind(req.read_only)
+    .fetch_optional(&state.pool)
+    .await?
+    .map(|read_only| Json(OrganizationReadOnly { read_only }))
+    .ok_or_else(not_found)
 }
 
 /// List users in an organization
@@ -205,7 +267,7 @@ pub async fn delete_organization(
         ("org_id" = Uuid, Path, description = "Organization ID")
     ),
     responses(
-        (status = 200, description = "List of users in organization", body = Vec<crate::api::models::User>),
+        (status = 200, description = "List of users in organization", body = Vec<UserResponse>),
         (status = 404, description = "Organization not found", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -214,26 +276,23 @@ pub async fn delete_organization(
 pub async fn list_organization_users(
     State(state): State<AppState>,
     Path(org_id): Path<Uuid>,
-) -> Result<Json<Vec<crate::api::models::User>>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Vec<UserResponse>>, ApiError> {
     // First check if organization exists
     let org_exists = sqlx::query("SELECT id FROM organizations WHERE id = $1")
         .bind(org_id)
         .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
     if org_exists.is_none() {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "Organization not found".to_string(),
-            }),
+        return Err(ApiError::not_found(
+            ErrorCode::OrganizationNotFound,
+            "Organization not found",
         ));
     }
 
-    let users = sqlx::query_as::<_, crate::api::models::User>(
-        "SELECT u.id, u.name, u.identity, u.password_hash, u.created_at, u.updated_at
+    let users = sqlx::query_as::<_, UserRow>(
+        "SELECT u.id, u.name, u.identity, u.password_hash, u.is_active,
+                u.failed_login_attempts, u.locked_until, u.created_at, u.updated_at
          FROM users u
          INNER JOIN user_organizations uo ON u.id = uo.user_id
          WHERE uo.organization_id = $1
@@ -241,18 +300,11 @@ pub async fn list_organization_users(
     )
     .bind(org_id)
     .fetch_all(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
-    Ok(Json(users))
+    Ok(Json(users.into_iter().map(Into::into).collect()))
 }
 
-fn internal_error<E: std::fmt::Display>(err: E) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(ErrorResponse {
-            error: "internal_error".to_string(),
-            message: err.to_string(),
-        }),
-    )
+fn not_found() -> ApiError {
+    ApiError::not_found(ErrorCode::OrganizationNotFound, "Organization not found")
 }
@@ -0,0 +1,514 @@
+//! Item photos.
+//!
+//! Images are kept in blob storage under `photos/<org_id>/<photo_id>.<ext>`;
+//! `item_photos` records which item each belongs to. Photos are listed with
+//! short-lived presigned links rather than served by the API.
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::time::Duration;
+
+use axum::{
+    Extension, Json,
+    body::Bytes,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{
+        AttachedPhoto, ErrorCode, ErrorResponse, ItemPhoto, Permission, PhotoImportResult,
+        UnmatchedPhoto,
+    },
+    state::AppState,
+};
+use crate::auth::AuthContext;
+
+/// Largest zip a batch upload may send
+pub const MAX_ARCHIVE_BYTES: usize = 256 * 1024 * 1024;
+
+/// Largest single image, uncompressed
+const MAX_PHOTO_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Most entries, folders included, one archive may hold
+const MAX_ARCHIVE_ENTRIES: usize = 1000;
+
+/// Most image bytes, uncompressed, read from one archive
+const MAX_ARCHIVE_IMAGE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// How long the links in a photo listing stay valid
+const PHOTO_LINK_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Image types accepted, by file extension
+const IMAGE_TYPES: &[(&str, &str)] = &[
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("png", "image/png"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("heic", "image/heic"),
+];
+
+/// Photos of an item, oldest first
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/items/{item_id}/photos",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("item_id" = Uuid, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 200, description = "The item's photos", body = Vec<ItemPhoto>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn list_item_photos(
+    State(state): State<AppState>,
+    Path((org_id, item_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Vec<ItemPhoto>>, ApiError> {
+    item_photos(&state, org_id, item_id)
+        .await
+        .map(Json)
+        .map_err(ApiError::internal)
+}
+
+/// Remove a photo from an item
+#[utoipa::path(
+    delete,
+    path = "/api/organizations/{org_id}/items/{item_id}/photos/{photo_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("item_id" = Uuid, Path, description = "Item ID"),
+        ("photo_id" = Uuid, Path, description = "Photo ID")
+    ),
+    responses(
+        (status = 204, description = "Photo removed"),
+        (status = 403, description = "Missing the items:write permission", body = ErrorResponse),
+        (status = 404, description = "Photo not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn delete_item_photo(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, item_id, photo_id)): Path<(Uuid, Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    if !auth.can(Permission::ItemsWrite) {
+        return Err(forbidden());
+    }
+    let key: String = sqlx::query_scalar(
+        "DELETE FROM item_photos
+         WHERE id = $1 AND item_id = $2 AND organization_id = $3
+         RETURNING storage_key",
+    )
+    .bind(photo_id)
+    .bind(item_id)
+    .bind(org_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| ApiError::not_found(ErrorCode::PhotoNotFound, "Photo not found"))?;
+
+    remove_blobs(&state, &[key]).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Attach a batch of photos to items, matched by file name
+///
+/// The body is a zip archive of images. Each image is attached to the item
+/// whose barcode or catalogue number matches its file name, ignoring case,
+/// spaces and punctuation: `PCS 7088.jpg` and `pcs7088.JPG` both match the
+/// catalogue number "PCS 7088". A suffix after the last `_`, `-` or space
+/// lets one item have several photos, e.g. `PCS7088_back.jpg`. Barcodes are
+/// tried before catalogue numbers.
+///
+/// Files that match no item, match several items, are not images or are too
+/// large are reported as unmatched; the rest are attached. Folders in the
+/// archive are ignored. An archive with more than 1000 entries or more than
+/// 512 MB of images once unpacked is rejected.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/items/photos/import",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body(content = Vec<u8>, content_type = "application/zip", description = "Zip of images named by barcode or catalogue number"),
+    responses(
+        (status = 200, description = "Which files were attached and which were not", body = PhotoImportResult),
+        (status = 400, description = "The body is not a zip archive, or unpacks to too many files or bytes", body = ErrorResponse),
+        (status = 403, description = "Missing the items:write permission", body = ErrorResponse),
+        (status = 413, description = "The archive is too large"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn import_photos(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+    body: Bytes,
+) -> Result<Json<PhotoImportResult>, ApiError> {
+    if !auth.can(Permission::ItemsWrite) {
+        return Err(forbidden());
+    }
+
+    let files = tokio::task::spawn_blocking(move || read_archive(body))
+        .await
+        .map_err(ApiError::internal)?
+        .map_err(|e| ApiError::validation(ErrorCode::PhotoArchiveInvalid, e.to_string()))?;
+
+    let matcher = ItemMatcher::load(&state.pool, org_id).await?;
+
+    // Store every image before recording any, so a storage failure leaves no
+    // rows pointing at missing blobs
+    let mut unmatched = Vec::new();
+    let mut stored: Vec<StoredPhoto> = Vec::new();
+    for file in files {
+        let matched = file
+            .data
+            .and_then(|data| Ok((matcher.find(&file.stem)?, data)));
+        let ((item_id, item_name), data) = match matched {
+            Ok(matched) => matched,
+            Err(reason) => {
+                unmatched.push(UnmatchedPhoto {
+                    filename: file.filename,
+                    reason,
+                });
+                continue;
+            }
+        };
+        let id = Uuid::new_v4();
+        let photo = StoredPhoto {
+            id,
+            key: format!("photos/{}/{}.{}", org_id, id, file.extension),
+            size_bytes: data.len() as i64,
+            filename: file.filename,
+            content_type: file.content_type,
+            item_id,
+            item_name,
+        };
+        if let Err(e) = state.storage.put(&photo.key, data).await {
+            remove_blobs(&state, &stored_keys(&stored)).await;
+            return Err(ApiError::internal(e));
+        }
+        stored.push(photo);
+    }
+
+    let recorded: Result<(), sqlx::Error> = async {
+        let mut tx = state.pool.begin().await?;
+        for photo in &stored {
+            sqlx::query(
+                "INSERT INTO item_photos
+                   (id, organization_id, item_id, storage_key, filename, content_type,
+                    size_bytes, uploaded_by)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            )
+            .bind(photo.id)
+            .bind(org_id)
+            .bind(photo.item_id)
+            .bind(&photo.key)
+            .bind(&photo.filename)
+            .bind(photo.content_type)
+            .bind(photo.size_bytes)
+            .bind(auth.user_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+    .await;
+    if let Err(e) = recorded {
+        remove_blobs(&state, &stored_keys(&stored)).await;
+        return Err(ApiError::internal(e));
+    }
+
+    tracing::info!(
+        organization_id = %org_id,
+        attached = stored.len(),
+        unmatched = unmatched.len(),
+        "batch photo upload"
+    );
+
+    let attached = stored
+        .into_iter()
+        .map(|photo| AttachedPhoto {
+            filename: photo.filename,
+            photo_id: photo.id,
+            item_id: photo.item_id,
+            item_name: photo.item_name,
+        })
+        .collect();
+    Ok(Json(PhotoImportResult {
+        attached,
+        unmatched,
+    }))
+}
+
+/// An item's photos, oldest first, with links to each
+pub(super) async fn item_photos(
+    state: &AppState,
+    org_id: Uuid,
+    item_id: Uuid,
+) -> anyhow::Result<Vec<ItemPhoto>> {
+    let rows = sqlx::query_as::<_, PhotoRow>(
+        "SELECT id, item_id, storage_key, filename, content_type, size_bytes, created_at
+         FROM item_photos
+         WHERE item_id = $1 AND organization_id = $2
+         ORDER BY created_at, id",
+    )
+    .bind(item_id)
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let mut photos = Vec::with_capacity(rows.len());
+    for row in rows {
+        let url = state
+            .storage
+            .presign(&row.storage_key, PHOTO_LINK_TTL)
+            .await?;
+        photos.push(ItemPhoto {
+            id: row.id,
+            item_id: row.item_id,
+            filename: row.filename,
+            content_type: row.content_type,
+            size_bytes: row.size_bytes,
+            created_at: row.created_at,
+            url,
+        });
+    }
+    Ok(photos)
+}
+
+/// Delete photo blobs, logging failures; the rows are already gone, so a
+/// leftover blob is only wasted space
+pub(super) async fn remove_blobs(state: &AppState, keys: &[String]) {
+    for key in keys {
+        if let Err(e) = state.storage.delete(key).await {
+            tracing::warn!("Failed to delete photo {}: {}", key, e);
+        }
+    }
+}
+
+fn stored_keys(stored: &[StoredPhoto]) -> Vec<String> {
+    stored.iter().map(|photo| photo.key.clone()).collect()
+}
+
+/// An uploaded image saved to storage for an item
+struct StoredPhoto {
+    id: Uuid,
+    key: String,
+    size_bytes: i64,
+    filename: String,
+    content_type: &'static str,
+    item_id: Uuid,
+    item_name: String,
+}
+
+/// One file from an uploaded archive
+struct ArchiveFile {
+    filename: String,
+    /// File name without its extension
+    stem: String,
+    extension: String,
+    content_type: &'static str,
+    /// The image, or why it was not read
+    data: Result<Vec<u8>, String>,
+}
+
+/// Why an uploaded archive was rejected as a whole
+enum ArchiveError {
+    Zip(zip::result::ZipError),
+    TooManyEntries,
+    TooLarge,
+}
+
+impl From<zip::result::ZipError> for ArchiveError {
+    fn from(e: zip::result::ZipError) -> Self {
+        Self::Zip(e)
+    }
+}
+
+impl From<std::io::Error> for ArchiveError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Zip(e.into())
+    }
+}
+
+impl std::fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Zip(e) => write!(f, "Could not read the zip archive: {}", e),
+            Self::TooManyEntries => write!(
+                f,
+                "The archive holds more than {} files",
+                MAX_ARCHIVE_ENTRIES
+            ),
+            Self::TooLarge => write!(
+                f,
+                "The archive unpacks to more than {} MB of images",
+                MAX_ARCHIVE_IMAGE_BYTES / (1024 * 1024)
+            ),
+        }
+    }
+}
+
+/// Read the files in a zip archive, skipping folders and the hidden files
+/// some archivers add. The archive is rejected once it holds too many
+/// entries or its images add up to too many bytes, since every image is
+/// held in memory until all are stored.
+fn read_archive(body: Bytes) -> Result<Vec<ArchiveFile>, ArchiveError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(body))?;
+    if archive.len() > MAX_ARCHIVE_ENTRIES {
+        return Err(ArchiveError::TooManyEntries);
+    }
+    let mut files = Vec::new();
+    let mut total_bytes: u64 = 0;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if entry.is_dir() || entry.name().starts_with("__MACOSX/") {
+            continue;
+        }
+        let filename = entry
+            .name()
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        if filename.is_empty() || filename.starts_with('.') {
+            continue;
+        }
+        let (stem, extension) = match filename.rsplit_once('.') {
+            Some((stem, extension)) => (stem.to_string(), extension.to_ascii_lowercase()),
+            None => (filename.clone(), String::new()),
+        };
+        let content_type = IMAGE_TYPES
+            .iter()
+            .find(|(ext, _)| *ext == extension)
+            .map(|(_, content_type)| *content_type);
+
+        let data = match content_type {
+            None => Err("not an image (expected .jpg, .png, .gif, .webp or .heic)".to_string()),
+            Some(_) if entry.size() > MAX_PHOTO_BYTES => Err(format!(
+                "larger than {} MB",
+                MAX_PHOTO_BYTES / (1024 * 1024)
+            )),
+            Some(_) => {
+                // The declared size can lie, so cap what is read
+                let mut data = Vec::with_capacity(entry.size() as usize);
+                (&mut entry)
+                    .take(MAX_PHOTO_BYTES + 1)
+                    .read_to_end(&mut data)?;
+                total_bytes += data.len() as u64;
+                if total_bytes > MAX_ARCHIVE_IMAGE_BYTES {
+                    return Err(ArchiveError::TooLarge);
+                }
+                if data.len() as u64 > MAX_PHOTO_BYTES {
+                    Err(format!(
+                        "larger than {} MB",
+                        MAX_PHOTO_BYTES / (1024 * 1024)
+                    ))
+                } else {
+                    Ok(data)
+                }
+            }
+        };
+        files.push(ArchiveFile {
+            filename,
+            stem,
+            extension,
+            content_type: content_type.unwrap_or_default(),
+            data,
+        });
+    }
+    Ok(files)
+}
+
+/// The organization's items keyed by normalized barcode and catalogue number
+struct ItemMatcher {
+    by_barcode: HashMap<String, (Uuid, String)>,
+    by_catalog_code: HashMap<String, Vec<(Uuid, String)>>,
+}
+
+impl ItemMatcher {
+    async fn load(pool: &sqlx::PgPool, org_id: Uuid) -> Result<Self, sqlx::Error> {
+        let rows: Vec<(Uuid, String, Option<String>, Option<String>)> = sqlx::query_as(
+            "SELECT id, name, barcode, catalog_code FROM items
+             WHERE organization_id = $1
+               AND (barcode IS NOT NULL OR catalog_code IS NOT NULL)",
+        )
+        .bind(org_id)
+        .fetch_all(pool)
+        .await?;
+
+        let mut by_barcode = HashMap::new();
+        let mut by_catalog_code: HashMap<String, Vec<(Uuid, String)>> = HashMap::new();
+        for (id, name, barcode, catalog_code) in rows {
+            if let Some(key) = barcode.as_deref().map(match_key).filter(|k| !k.is_empty()) {
+                by_barcode.insert(key, (id, name.clone()));
+            }
+            if let Some(key) = catalog_code
+                .as_deref()
+                .map(match_key)
+                .filter(|k| !k.is_empty())
+            {
+                by_catalog_code.entry(key).or_default().push((id, name));
+            }
+        }
+        Ok(Self {
+            by_barcode,
+            by_catalog_code,
+        })
+    }
+
+    /// The item a file name stem refers to: the whole stem, then the stem
+    /// without a suffix after its last `_`, `-` or space
+    fn find(&self, stem: &str) -> Result<(Uuid, String), String> {
+        let without_suffix = stem
+            .rsplit_once(['_', '-', ' '])
+            .map(|(base, _)| base)
+            .filter(|base| !base.trim().is_empty());
+        for candidate in std::iter::once(stem).chain(without_suffix) {
+            let key = match_key(candidate);
+            if let Some(item) = self.by_barcode.get(&key) {
+                return Ok(item.clone());
+            }
+            match self.by_catalog_code.get(&key).map(Vec::as_slice) {
+                Some([item]) => return Ok(item.clone()),
+                Some(items) if items.len() > 1 => {
+                    return Err(format!("{} items have this catalogue number", items.len()));
+                }
+                _ => {}
+            }
+        }
+        Err("no item has this barcode or catalogue number".to_string())
+    }
+}
+
+/// Letters and digits only, upper-cased, so "PCS 7088" and "pcs-7088" match
+fn match_key(value: &str) -> String {
+    value
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+#[derive(sqlx::FromRow)]
+struct PhotoRow {
+    id: Uuid,
+    item_id: Uuid,
+    storage_key: String,
+    filename: String,
+    content_type: String,
+    size_bytes: i64,
+    created_at: DateTime<Utc>,
+}
+
+fn forbidden() -> ApiError {
+    ApiError::auth_forbidden("The items:write permission is required to change items")
+}
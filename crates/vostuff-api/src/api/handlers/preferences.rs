@@ -0,0 +1,242 @@
+//! Per-member preferences: item table layout, saved searches and list defaults.
+//!
+//! Preferences belong to a member within one organization. They can be
+//! exported as a JSON file and imported elsewhere, to move them to another
+//! instance or share them with another member.
+
+use std::collections::HashSet;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{ErrorCode, ErrorResponse, PreferencesExport, UserPreferences},
+    state::AppState,
+};
+use crate::auth::AuthContext;
+
+/// Item list columns that may be sorted on, as accepted by `sort_by`
+const SORT_COLUMNS: &[&str] = &["name", "kind", "state", "location_id", "created_at"];
+
+/// Get the current user's preferences in the organization
+///
+/// Defaults if none have been saved.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/preferences",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "User preferences", body = UserPreferences),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "preferences"
+)]
+pub async fn get_preferences(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<UserPreferences>, ApiError> {
+    let preferences = load_preferences(&state.pool, auth.user_id, org_id).await?;
+
+    Ok(Json(preferences))
+}
+
+/// Replace the current user's preferences in the organization
+#[utoipa::path(
+    put,
+    path = "/api/organizations/{org_id}/preferences",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = UserPreferences,
+    responses(
+        (status = 200, description = "Preferences updated", body = UserPreferences),
+        (status = 400, description = "Invalid preference value", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "preferences"
+)]
+pub async fn update_preferences(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<UserPreferences>,
+) -> Result<Json<UserPreferences>, ApiError> {
+    validate(&req).map_err(|msg| bad_request(&msg))?;
+    save_preferences(&state.pool, auth.user_id, org_id, &req).await?;
+
+    Ok(Json(req))
+}
+
+/// Export the current user's preferences as a JSON file
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/preferences/export",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Preferences file", body = PreferencesExport),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "preferences"
+)]
+pub async fn export_preferences(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let preferences = load_preferences(&state.pool, auth.user_id, org_id).await?;
+
+    let now = Utc::now();
+    let disposition = format!(
+        "attachment; filename=\"vostuff-preferences-{}.json\"",
+        now.format("%Y-%m-%d")
+    );
+    Ok((
+        [(header::CONTENT_DISPOSITION, disposition)],
+        Json(PreferencesExport {
+            format_version: PreferencesExport::FORMAT_VERSION,
+            exported_at: Some(now),
+            preferences,
+        }),
+    ))
+}
+
+/// Import a preferences file, replacing the current user's preferences
+///
+/// Accepts a file from the export endpoint, from this or another instance
+/// and from any member.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/preferences/import",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = PreferencesExport,
+    responses(
+        (status = 200, description = "Preferences imported", body = UserPreferences),
+        (status = 400, description = "Unsupported file version or invalid preference value", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "preferences"
+)]
+pub async fn import_preferences(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<PreferencesExport>,
+) -> Result<Json<UserPreferences>, ApiError> {
+    if req.format_version != PreferencesExport::FORMAT_VERSION {
+        return Err(bad_request(&format!(
+            "Unsupported preferences format_version {}; expected {}",
+            req.format_version,
+            PreferencesExport::FORMAT_VERSION
+        )));
+    }
+    validate(&req.preferences).map_err(|msg| bad_request(&msg))?;
+    save_preferences(&state.pool, auth.user_id, org_id, &req.preferences).await?;
+
+    Ok(Json(req.preferences))
+}
+
+/// Check preferences before they are stored
+fn validate(prefs: &UserPreferences) -> Result<(), String> {
+    if let Some(columns) = &prefs.item_columns {
+        let mut seen = HashSet::new();
+        if columns
+            .iter()
+            .any(|c| c.trim().is_empty() || !seen.insert(c))
+        {
+            return Err("item_columns must be distinct, non-empty names".to_string());
+        }
+    }
+
+    if prefs.saved_searches.len() > UserPreferences::MAX_SAVED_SEARCHES {
+        return Err(format!(
+            "at most {} saved searches are allowed",
+            UserPreferences::MAX_SAVED_SEARCHES
+        ));
+    }
+    let mut names = HashSet::new();
+    for search in &prefs.saved_searches {
+        let name = search.name.trim();
+        if name.is_empty() {
+            return Err("saved search names must not be empty".to_string());
+        }
+        if !names.insert(name.to_lowercase()) {
+            return Err(format!("saved search '{}' appears more than once", name));
+        }
+    }
+
+    if prefs.per_page.is_some_and(|n| n <= 0) {
+        return Err("per_page must be positive".to_string());
+    }
+    if let Some(sort_by) = &prefs.sort_by
+        && !SORT_COLUMNS.contains(&sort_by.as_str())
+    {
+        return Err(format!(
+            "sort_by must be one of {}",
+            SORT_COLUMNS.join(", ")
+        ));
+    }
+    if let Some(sort_order) = &prefs.sort_order
+        && !matches!(sort_order.as_str(), "asc" | "desc")
+    {
+        return Err("sort_order must be asc or desc".to_string());
+    }
+    Ok(())
+}
+
+/// Load a member's preferences; defaults if none are saved
+async fn load_preferences(
+    pool: &PgPool,
+    user_id: Uuid,
+    org_id: Uuid,
+) -> Result<UserPreferences, sqlx::Error> {
+    let preferences: Option<sqlx::types::Json<UserPreferences>> = sqlx::query_scalar(
+        "SELECT preferences FROM user_preferences WHERE user_id = $1 AND organization_id = $2",
+    )
+    .bind(user_id)
+    .bind(org_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(preferences.map(|p| p.0).unwrap_or_default())
+}
+
+async fn save_preferences(
+    pool: &PgPool,
+    user_id: Uuid,
+    org_id: Uuid,
+    preferences: &UserPreferences,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO user_preferences (user_id, organization_id, preferences)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (user_id, organization_id)
+         DO UPDATE SET preferences = EXCLUDED.preferences, updated_at = NOW()",
+    )
+    .bind(user_id)
+    .bind(org_id)
+    .bind(sqlx::types::Json(preferences))
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// ── Helpers ────────────────────────────────────────────────────────────────
+
+fn bad_request(message: &str) -> ApiError {
+    ApiError::validation(ErrorCode::PreferenceInvalid, message)
+}
@@ -0,0 +1,483 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{ErrorResponse, ItemState, PaginatedResponse},
+    state::AppState,
+};
+
+/// Storage totals for one location (or for items with no location)
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct LocationStorage {
+    /// None for items without a location
+    pub location_id: Option<Uuid>,
+    pub location_name: Option<String>,
+    pub item_count: i64,
+    /// Items with a recorded depth, i.e. counted in shelf_length_m
+    pub measured_count: i64,
+    /// Total shelf length taken by items filed spine-out, in metres
+    pub shelf_length_m: f64,
+    /// Items with a recorded weight, i.e. counted in total_weight_kg
+    pub weighed_count: i64,
+    pub total_weight_kg: f64,
+}
+
+/// Storage report: shelf length and weight per location
+///
+/// Disposed items are excluded since they no longer occupy space.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/reports/storage",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Storage totals per location", body = Vec<LocationStorage>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "reports"
+)]
+pub async fn storage_report(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<Vec<LocationStorage>>, ApiError> {
+    let rows = sqlx::query_as::<_, LocationStorage>(
+        "SELECT i.location_id,
+                l.name AS location_name,
+                COUNT(*) AS item_count,
+                COUNT(i.depth_mm) AS measured_count,
+                COALESCE(SUM(i.depth_mm), 0)::float8 / 1000.0 AS shelf_length_m,
+                COUNT(i.weight_g) AS weighed_count,
+                COALESCE(SUM(i.weight_g), 0)::float8 / 1000.0 AS total_weight_kg
+         FROM items i
+         LEFT JOIN locations l ON l.id = i.location_id
+         WHERE i.organization_id = $1 AND i.state <> 'disposed'
+         GROUP BY i.location_id, l.name
+         ORDER BY l.name NULLS LAST",
+    )
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(rows))
+}
+
+/// Counts behind the dashboard's item state widgets
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct ItemStateSummary {
+    pub loaned: i64,
+    pub missing: i64,
+    /// Disposed items with a disposal date on or after `year_start`
+    pub disposed_this_year: i64,
+    /// First day of the current year; pass as `disposed_since` to list these items
+    pub year_start: NaiveDate,
+}
+
+/// Item state summary: loaned, missing and disposed-this-year counts
+///
+/// Each count matches the item list filtered the same way (`state=loaned`,
+/// `state=missing`, `state=disposed&disposed_since={year_start}`), so widgets can link to it.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/reports/item-states",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Item state counts", body = ItemStateSummary),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "reports"
+)]
+pub async fn item_state_summary(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<ItemStateSummary>, ApiError> {
+    let summary = sqlx::query_as::<_, ItemStateSummary>(
+        "WITH y AS (SELECT date_trunc('year', CURRENT_DATE)::date AS year_start)
+         SELECT COUNT(*) FILTER (WHERE i.state = 'loaned') AS loaned,
+                COUNT(*) FILTER (WHERE i.state = 'missing') AS missing,
+                COUNT(*) FILTER (
+                    WHERE i.state = 'disposed' AND d.date_disposed >= y.year_start
+                ) AS disposed_this_year,
+                y.year_start
+         FROM y
+         LEFT JOIN items i ON i.organization_id = $1
+         LEFT JOIN item_disposed_details d ON d.item_id = i.id
+         GROUP BY y.year_start",
+    )
+    .bind(org_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(Json(summary))
+}
+
+/// Query parameters for the overdue loans report
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct OverdueLoansParams {
+    #[serde(default = "default_page")]
+    pub page: i64,
+    #[serde(default = "default_per_page")]
+    pub per_page: i64,
+    /// Sort by column (date_due_back, date_loaned, name, loaned_to); default date_due_back
+    pub sort_by: Option<String>,
+    /// Sort direction (asc, desc)
+    pub sort_order: Option<String>,
+}
+
+fn default_page() -> i64 {
+    1
+}
+
+fn default_per_page() -> i64 {
+    50
+}
+
+/// A loaned item that is past its due-back date
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct OverdueLoan {
+    pub item_id: Uuid,
+    pub item_name: String,
+    pub kind_name: String,
+    pub loaned_to: String,
+    /// Borrower record for the open loan, if one is linked
+    pub borrower_id: Option<Uuid>,
+    pub date_loaned: NaiveDate,
+    pub date_due_back: NaiveDate,
+    pub days_overdue: i32,
+}
+
+/// Overdue loans report: loaned items whose due-back date is before today
+///
+/// Loans without a due-back date are never overdue.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/reports/overdue-loans",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        OverdueLoansParams
+    ),
+    responses(
+        (status = 200, description = "Overdue loans", body = PaginatedResponse<OverdueLoan>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "reports"
+)]
+pub async fn overdue_loans_report(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Query(params): Query<OverdueLoansParams>,
+) -> Result<Json<PaginatedResponse<OverdueLoan>>, ApiError> {
+    let offset = (params.page - 1) * params.per_page;
+
+    const OVERDUE_WHERE: &str = "i.organization_id = $1
+           AND i.state = 'loaned'
+           AND d.date_due_back < CURRENT_DATE";
+
+    let total: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*)
+         FROM items i
+         JOIN item_loan_details d ON d.item_id = i.id
+         WHERE {}",
+        OVERDUE_WHERE
+    ))
+    .bind(org_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    // ORDER BY — whitelist to prevent injection
+    let order_column = match params.sort_by.as_deref() {
+        Some("date_loaned") => "d.date_loaned",
+        Some("name") => "i.name",
+        Some("loaned_to") => "d.loaned_to",
+        _ => "d.date_due_back",
+    };
+    let order_direction = match params.sort_order.as_deref() {
+        Some("desc") => "DESC",
+        _ => "ASC",
+    };
+
+    let items = sqlx::query_as::<_, OverdueLoan>(&format!(
+        "SELECT i.id AS item_id,
+                i.name AS item_name,
+                k.name AS kind_name,
+                d.loaned_to,
+                l.borrower_id,
+                d.date_loaned,
+                d.date_due_back,
+                (CURRENT_DATE - d.date_due_back) AS days_overdue
+         FROM items i
+         JOIN kinds k ON k.id = i.kind_id
+         JOIN item_loan_details d ON d.item_id = i.id
+         LEFT JOIN item_loans l ON l.item_id = i.id AND l.date_returned IS NULL
+         WHERE {}
+         ORDER BY {} {}, i.name, i.id
+         LIMIT $2 OFFSET $3",
+        OVERDUE_WHERE, order_column, order_direction
+    ))
+    .bind(org_id)
+    .bind(params.per_page)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let total_pages = if total == 0 {
+        1
+    } else {
+        (total + params.per_page - 1) / params.per_page
+    };
+
+    Ok(Json(PaginatedResponse {
+        items,
+        total,
+        page: params.page,
+        per_page: params.per_page,
+        total_pages,
+        next_cursor: None,
+    }))
+}
+
+/// Item count for one kind, location or collection
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct GroupCount {
+    /// None for items without a location
+    pub id: Option<Uuid>,
+    pub name: Option<String>,
+    pub count: i64,
+}
+
+/// Item count for one state
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StateCount {
+    pub state: ItemState,
+    pub count: i64,
+}
+
+/// Items added during one calendar month
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct MonthCount {
+    /// First day of the month
+    pub month: NaiveDate,
+    pub count: i64,
+}
+
+/// Aggregate item counts for the dashboard
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrganizationStats {
+    pub total_items: i64,
+    /// Kinds with at least one item, largest first
+    pub by_kind: Vec<GroupCount>,
+    /// Every state, including those with no items
+    pub by_state: Vec<StateCount>,
+    /// Locations with at least one item, largest first; items without a location have a null id
+    pub by_location: Vec<GroupCount>,
+    /// Every collection, largest first; an item in several collections counts in each
+    pub by_collection: Vec<GroupCount>,
+    /// Items created in each of the last 12 months, oldest first, ending with the current month
+    pub added_per_month: Vec<MonthCount>,
+}
+
+/// Organization statistics: item counts by kind, state, location and collection
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/stats",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Organization statistics", body = OrganizationStats),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "reports"
+)]
+pub async fn organization_stats(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<OrganizationStats>, ApiError> {
+    let by_kind = sqlx::query_as::<_, GroupCount>(
+        "SELECT k.id, k.name, COUNT(*) AS count
+         FROM items i
+         JOIN kinds k ON k.id = i.kind_id
+         WHERE i.organization_id = $1
+         GROUP BY k.id, k.name
+         ORDER BY count DESC, k.name",
+    )
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let state_rows = sqlx::query_as::<_, (String, i64)>(
+        "SELECT state::text, COUNT(*) FROM items WHERE organization_id = $1 GROUP BY state",
+    )
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+    let mut by_state: Vec<StateCount> = ItemState::ALL
+        .iter()
+        .map(|item_state| StateCount {
+            state: item_state.clone(),
+            count: 0,
+        })
+        .collect();
+    for (name, count) in state_rows {
+        let item_state: ItemState = name.parse().map_err(ApiError::internal)?;
+        if let Some(entry) = by_state.iter_mut().find(|s| s.state == item_state) {
+            entry.count = count;
+        }
+    }
+    let total_items = by_state.iter().map(|s| s.count).sum();
+
+    let by_location = sqlx::query_as::<_, GroupCount>(
+        "SELECT l.id, l.name, COUNT(*) AS count
+         FROM items i
+         LEFT JOIN locations l ON l.id = i.location_id
+         WHERE i.organization_id = $1
+         GROUP BY l.id, l.name
+         ORDER BY count DESC, l.name NULLS LAST",
+    )
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let by_collection = sqlx::query_as::<_, GroupCount>(
+        "SELECT c.id, c.name, COUNT(ic.item_id) AS count
+         FROM collections c
+         LEFT JOIN item_collections ic ON ic.collection_id = c.id
+         WHERE c.organization_id = $1
+         GROUP BY c.id, c.name
+         ORDER BY count DESC, c.name",
+    )
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let added_per_month = sqlx::query_as::<_, MonthCount>(
+        "SELECT m.month::date AS month, COUNT(i.id) AS count
+         FROM generate_series(
+                  date_trunc('month', CURRENT_DATE) - INTERVAL '11 months',
+                  date_trunc('month', CURRENT_DATE),
+                  INTERVAL '1 month'
+              ) AS m(month)
+         LEFT JOIN items i
+                ON i.organization_id = $1 AND date_trunc('month', i.created_at) = m.month
+         GROUP BY m.month
+         ORDER BY m.month",
+    )
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(OrganizationStats {
+        total_items,
+        by_kind,
+        by_state,
+        by_location,
+        by_collection,
+        added_per_month,
+    }))
+}
+
+/// Query parameters for the usage report
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UsageReportParams {
+    /// Only count uses on or after this date (YYYY-MM-DD); default all time
+    pub since: Option<NaiveDate>,
+    /// Items in each list (1-50, default 5)
+    #[serde(default = "default_usage_limit")]
+    pub limit: i64,
+}
+
+fn default_usage_limit() -> i64 {
+    5
+}
+
+/// How often one item has been used
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct ItemUsage {
+    pub item_id: Uuid,
+    pub item_name: String,
+    pub kind_name: String,
+    /// Logged uses in the report period
+    pub use_count: i64,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Most and least used items, for the dashboard
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UsageReport {
+    /// Items used at least once, most used first
+    pub most_used: Vec<ItemUsage>,
+    /// Least used first, including items never used; among equal counts the
+    /// longest unused come first
+    pub least_used: Vec<ItemUsage>,
+}
+
+/// Usage report: the current items used most and least, from the usage log
+///
+/// Helps decide what to keep in reach and what to sell. Only items in the
+/// `current` state are counted.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/reports/usage",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        UsageReportParams
+    ),
+    responses(
+        (status = 200, description = "Most and least used items", body = UsageReport),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "reports"
+)]
+pub async fn usage_report(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Query(params): Query<UsageReportParams>,
+) -> Result<Json<UsageReport>, ApiError> {
+    const USAGE_COUNTS: &str = "
+        SELECT i.id AS item_id, i.name AS item_name, k.name AS kind_name,
+               COUNT(l.id) AS use_count, i.last_used_at
+        FROM items i
+        JOIN kinds k ON k.id = i.kind_id
+        LEFT JOIN usage_log l
+               ON l.item_id = i.id AND ($2::date IS NULL OR l.used_at >= $2::date)
+        WHERE i.organization_id = $1 AND i.state = 'current'
+        GROUP BY i.id, k.name";
+    let limit = params.limit.clamp(1, 50);
+
+    let most_used = sqlx::query_as::<_, ItemUsage>(&format!(
+        "{} HAVING COUNT(l.id) > 0
+         ORDER BY use_count DESC, i.last_used_at DESC, i.name
+         LIMIT $3",
+        USAGE_COUNTS
+    ))
+    .bind(org_id)
+    .bind(params.since)
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let least_used = sqlx::query_as::<_, ItemUsage>(&format!(
+        "{} ORDER BY use_count, i.last_used_at NULLS FIRST, i.date_entered, i.name
+         LIMIT $3",
+        USAGE_COUNTS
+    ))
+    .bind(org_id)
+    .bind(params.since)
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(UsageReport {
+        most_used,
+        least_used,
+    }))
+}
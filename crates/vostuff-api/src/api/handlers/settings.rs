@@ -0,0 +1,385 @@
+use std::{collections::HashSet, time::Duration};
+
+use axum::{
+    Extension, Json,
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header},
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::{
+    middleware::{ReadOnly, locked, read_only},
+    models::{
+        Announcement, ErrorCode, ErrorResponse, LabelTemplate, OrganizationLogo,
+        OrganizationSettings, Permission,
+    },
+    state::AppState,
+};
+use crate::auth::AuthContext;
+
+/// Largest logo accepted
+const MAX_LOGO_BYTES: usize = 512 * 1024;
+
+/// How long logo links stay valid
+const LOGO_LINK_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Logo image types accepted, by Content-Type, with the extension stored
+const LOGO_TYPES: &[(&str, &str)] = &[
+    ("image/png", "png"),
+    ("image/jpeg", "jpg"),
+    ("image/webp", "webp"),
+    ("image/gif", "gif"),
+];
+
+/// Get the organization's settings
+///
+/// An expired announcement is left out.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/settings",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Organization settings", body = OrganizationSettings),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "settings"
+)]
+pub async fn get_settings(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<OrganizationSettings>, ApiError> {
+    let settings = load_current_settings(&state.pool, org_id).await?;
+
+    Ok(Json(settings))
+}
+
+/// Replace the organization's settings
+#[utoipa::path(
+    put,
+    path = "/api/organizations/{org_id}/settings",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = OrganizationSettings,
+    responses(
+        (status = 200, description = "Settings updated", body = OrganizationSettings),
+        (status = 400, description = "Invalid setting value", body = ErrorResponse),
+        (status = 403, description = "Requires the settings:write permission", body = ErrorResponse),
+        (status = 423, description = "A system administrator made the organization read-only", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "settings"
+)]
+pub async fn update_settings(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<OrganizationSettings>,
+) -> Result<Json<OrganizationSettings>, ApiError> {
+    if !auth.can(Permission::SettingsWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The settings:write permission is required to change settings",
+        ));
+    }
+
+    // The read_only setting is lifted here, but not a system administrator's freeze
+    if read_only(&state.pool, org_id).await? == Some(ReadOnly::System) {
+        return Err(locked(ReadOnly::System));
+    }
+
+    for (name, value) in [
+        ("max_loans_per_borrower", req.max_loans_per_borrower),
+        ("max_loan_days", req.max_loan_days),
+        ("history_retention_months", req.history_retention_months),
+    ] {
+        if value.is_some_and(|v| v <= 0) {
+            return Err(ApiError::validation(
+                ErrorCode::SettingInvalid,
+                format!("{} must be positive", name),
+            ));
+        }
+    }
+
+    if req
+        .default_member_roles
+        .as_ref()
+        .is_some_and(|roles| roles.is_empty())
+    {
+        return Err(ApiError::validation(
+            ErrorCode::SettingInvalid,
+            "default_member_roles must name at least one role",
+        ));
+    }
+
+    if let Some(announcement) = &req.announcement {
+        let len = announcement.message.trim().chars().count();
+        if len == 0 || len > Announcement::MAX_LEN {
+            return Err(ApiError::validation(
+                ErrorCode::SettingInvalid,
+                format!(
+                    "announcement message must be 1 to {} characters",
+                    Announcement::MAX_LEN
+                ),
+            ));
+        }
+        if !announcement.is_active(chrono::Utc::now().date_naive()) {
+            return Err(ApiError::validation(
+                ErrorCode::SettingInvalid,
+                "announcement expiry date is in the past",
+            ));
+        }
+    }
+
+    if req
+        .accent_color
+        .as_deref()
+        .is_some_and(|c| !OrganizationSettings::is_valid_color(c))
+    {
+        return Err(ApiError::validation(
+            ErrorCode::SettingInvalid,
+            "accent_color must be a hex colour such as #2a7ae2",
+        ));
+    }
+
+    validate_label_templates(&req.label_templates)
+        .map_err(|msg| ApiError::validation(ErrorCode::SettingInvalid, &msg))?;
+
+    sqlx::query("UPDATE organizations SET settings = $2 WHERE id = $1")
+        .bind(org_id)
+        .bind(sqlx::types::Json(&req))
+        .execute(&state.pool)
+        .await?;
+
+    Ok(Json(req))
+}
+
+/// Set the organization's logo, shown in the web app header
+///
+/// The body is the image, with a `Content-Type` of `image/png`, `image/jpeg`,
+/// `image/webp` or `image/gif`. Replaces any existing logo.
+#[utoipa::path(
+    put,
+    path = "/api/organizations/{org_id}/settings/logo",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body(content = Vec<u8>, content_type = "image/png", description = "The logo image"),
+    responses(
+        (status = 200, description = "Logo set", body = OrganizationLogo),
+        (status = 400, description = "Not a supported image, empty or too large", body = ErrorResponse),
+        (status = 403, description = "Requires the settings:write permission", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "settings"
+)]
+pub async fn upload_logo(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<OrganizationLogo>, ApiError> {
+    if !auth.can(Permission::SettingsWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The settings:write permission is required to change the logo",
+        ));
+    }
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let extension = LOGO_TYPES
+        .iter()
+        .find(|(mime, _)| *mime == content_type)
+        .map(|(_, extension)| *extension)
+        .ok_or_else(|| {
+            ApiError::validation(
+                ErrorCode::SettingInvalid,
+                "The logo must be a PNG, JPEG, WebP or GIF image",
+            )
+        })?;
+    if body.is_empty() || body.len() > MAX_LOGO_BYTES {
+        return Err(ApiError::validation(
+            ErrorCode::SettingInvalid,
+            format!("The logo must be at most {} KB", MAX_LOGO_BYTES / 1024),
+        ));
+    }
+
+    // A new key each time, so a cached link never shows a replaced logo
+    let key = format!("branding/{}/logo-{}.{}", org_id, Uuid::new_v4(), extension);
+    state
+        .storage
+        .put(&key, body.to_vec())
+        .await
+        .map_err(ApiError::internal)?;
+
+    let previous: Option<String> = sqlx::query_scalar(
+        "UPDATE organizations o SET logo_key = $2
+         FROM (SELECT logo_key FROM organizations WHERE id = $1 FOR UPDATE) previous
+         WHERE o.id = $1
+         RETURNING previous.logo_key",
+    )
+    .bind(org_id)
+    .bind(&key)
+    .fetch_one(&state.pool)
+    .await?;
+    if let Some(previous) = previous {
+        remove_logo_blob(&state, &previous).await;
+    }
+
+    let url = state
+        .storage
+        .presign(&key, LOGO_LINK_TTL)
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Json(OrganizationLogo { url }))
+}
+
+/// Remove the organization's logo
+#[utoipa::path(
+    delete,
+    path = "/api/organizations/{org_id}/settings/logo",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 204, description = "Logo removed, or there was none"),
+        (status = 403, description = "Requires the settings:write permission", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "settings"
+)]
+pub async fn delete_logo(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    if !auth.can(Permission::SettingsWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The settings:write permission is required to change the logo",
+        ));
+    }
+
+    let previous: Option<String> = sqlx::query_scalar(
+        "UPDATE organizations o SET logo_key = NULL
+         FROM (SELECT logo_key FROM organizations WHERE id = $1 FOR UPDATE) previous
+         WHERE o.id = $1
+         RETURNING previous.logo_key",
+    )
+    .bind(org_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .flatten();
+    if let Some(previous) = previous {
+        remove_logo_blob(&state, &previous).await;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Check label templates before they are stored
+fn validate_label_templates(templates: &[LabelTemplate]) -> Result<(), String> {
+    if templates.len() > LabelTemplate::MAX_TEMPLATES {
+        return Err(format!(
+            "at most {} label templates are allowed",
+            LabelTemplate::MAX_TEMPLATES
+        ));
+    }
+    let mut names = HashSet::new();
+    for template in templates {
+        let name = template.name.trim();
+        if name.is_empty() {
+            return Err("label template names must not be empty".to_string());
+        }
+        if !names.insert(name.to_lowercase()) {
+            return Err(format!("label template '{}' appears more than once", name));
+        }
+        if template.fields.is_empty() && !template.include_qr && !template.include_barcode {
+            return Err(format!("label template '{}' prints nothing", name));
+        }
+        let mut fields = HashSet::new();
+        for field in &template.fields {
+            if !LabelTemplate::FIELDS.contains(&field.as_str()) {
+                return Err(format!(
+                    "label template '{}' has unknown field '{}'; expected one of {}",
+                    name,
+                    field,
+                    LabelTemplate::FIELDS.join(", ")
+                ));
+            }
+            if !fields.insert(field) {
+                return Err(format!(
+                    "label template '{}' shows '{}' more than once",
+                    name, field
+                ));
+            }
+        }
+        if ![template.width_mm, template.height_mm]
+            .iter()
+            .all(|side| LabelTemplate::SIZE_RANGE_MM.contains(side))
+        {
+            return Err(format!(
+                "label template '{}' must be {} to {} mm on each side",
+                name,
+                LabelTemplate::SIZE_RANGE_MM.start(),
+                LabelTemplate::SIZE_RANGE_MM.end()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A link to an organization's logo, if it has one
+pub(crate) async fn logo_url(state: &AppState, org_id: Uuid) -> anyhow::Result<Option<String>> {
+    let key: Option<String> =
+        sqlx::query_scalar("SELECT logo_key FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .fetch_optional(&state.pool)
+            .await?
+            .flatten();
+    match key {
+        Some(key) => Ok(Some(state.storage.presign(&key, LOGO_LINK_TTL).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Delete a replaced logo, logging failures; nothing refers to it any more
+async fn remove_logo_blob(state: &AppState, key: &str) {
+    if let Err(e) = state.storage.delete(key).await {
+        tracing::warn!("Failed to delete logo {}: {}", key, e);
+    }
+}
+
+/// Load an organization's settings as members see them, without an expired
+/// announcement
+pub(crate) async fn load_current_settings(
+    pool: &PgPool,
+    org_id: Uuid,
+) -> Result<OrganizationSettings, sqlx::Error> {
+    let mut settings = load_settings(pool, org_id).await?;
+    let today = chrono::Utc::now().date_naive();
+    settings.announcement.take_if(|a| !a.is_active(today));
+    Ok(settings)
+}
+
+/// Load an organization's settings; defaults if it has none
+pub(crate) async fn load_settings(
+    pool: &PgPool,
+    org_id: Uuid,
+) -> Result<OrganizationSettings, sqlx::Error> {
+    let settings: Option<sqlx::types::Json<OrganizationSettings>> =
+        sqlx::query_scalar("SELECT settings FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(settings.map(|s| s.0).unwrap_or_default())
+}
+
+// ── Helpers ────────────────────────────────────────────────────────────────
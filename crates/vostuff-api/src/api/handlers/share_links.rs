@@ -0,0 +1,193 @@
+//! Share links: a signed, time-limited URL to a read-only public page showing
+//! one item, so a member can send someone a record without giving them access
+//! to the organization.
+//!
+//! The link's token carries the item and the fields chosen when sharing, so
+//! nothing is stored. The page shows the item as it is when opened, and the
+//! link cannot be revoked before it expires; deleting the item stops it working.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use uuid::Uuid;
+
+use super::items;
+use crate::api::error::ApiError;
+use crate::api::{
+    models::{CreateShareLinkRequest, ErrorCode, ErrorResponse, ShareLink, SharedItem},
+    state::AppState,
+};
+use crate::auth::{SHARE_LINK_DEFAULT_TTL_HOURS, SHARE_LINK_MAX_TTL_HOURS, TokenManager};
+
+/// Item fields that may be shared. Where an item is kept, its loans and its
+/// history stay private.
+const SHAREABLE_FIELDS: &[&str] = &[
+    "kind_name",
+    "state",
+    "description",
+    "notes",
+    "date_acquired",
+    "barcode",
+    "catalog_code",
+    "width_mm",
+    "height_mm",
+    "depth_mm",
+    "weight_g",
+    "soft_fields",
+];
+
+/// Fields shared when the request names none
+const DEFAULT_SHARED_FIELDS: &[&str] = &["kind_name", "description", "soft_fields"];
+
+/// Create a link to a public page showing one item
+///
+/// Anyone with the link can see the item's name and the chosen fields until
+/// the link expires.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/items/{item_id}/share-link",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("item_id" = Uuid, Path, description = "Item ID")
+    ),
+    request_body = CreateShareLinkRequest,
+    responses(
+        (status = 200, description = "The share link", body = ShareLink),
+        (status = 400, description = "Unknown field or expiry out of range", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn create_share_link(
+    State(state): State<AppState>,
+    Path((org_id, item_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<CreateShareLinkRequest>,
+) -> Result<Json<ShareLink>, ApiError> {
+    let hours = req.expires_in_hours.unwrap_or(SHARE_LINK_DEFAULT_TTL_HOURS);
+    if !(1..=SHARE_LINK_MAX_TTL_HOURS).contains(&hours) {
+        return Err(ApiError::validation_failed(format!(
+            "expires_in_hours must be 1 to {}",
+            SHARE_LINK_MAX_TTL_HOURS
+        )));
+    }
+    let fields = shared_fields(req.fields)?;
+
+    if items::fetch_item(&state.pool, org_id, item_id)
+        .await?
+        .is_none()
+    {
+        return Err(items::not_found());
+    }
+
+    // Whole seconds, as the token records them
+    let expires_at = (Utc::now() + Duration::hours(hours)).trunc_subsecs(0);
+    let token = TokenManager::new(&state.jwt_secret)
+        .generate_share_token(item_id, org_id, fields.clone(), expires_at)
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(ShareLink {
+        url: format!("{}?token={}", state.share_url, token),
+        token,
+        fields,
+        expires_at,
+    }))
+}
+
+/// Show the item a share link points to
+///
+/// No authentication: the link's signature and expiry grant access.
+#[utoipa::path(
+    get,
+    path = "/api/shared-items/{token}",
+    params(
+        ("token" = String, Path, description = "Token from the share link")
+    ),
+    responses(
+        (status = 200, description = "The item's name and shared fields", body = SharedItem),
+        (status = 403, description = "Link invalid or expired", body = ErrorResponse),
+        (status = 404, description = "The item has been deleted", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn get_shared_item(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Json<SharedItem>, ApiError> {
+    let claims = TokenManager::new(&state.jwt_secret)
+        .validate_share_token(&token)
+        .map_err(|_| {
+            ApiError::forbidden(
+                ErrorCode::ItemShareLinkInvalid,
+                "This link is invalid or has expired",
+            )
+        })?;
+
+    let item = items::fetch_item(&state.pool, claims.organization_id, claims.sub)
+        .await?
+        .ok_or_else(items::not_found)?;
+
+    let name = item.name.clone();
+    let mut fields = serde_json::to_value(item).map_err(ApiError::internal)?;
+    if let Some(obj) = fields.as_object_mut() {
+        // Only fields that are still shareable, should the list ever shrink
+        obj.retain(|key, _| {
+            claims.fields.contains(key) && SHAREABLE_FIELDS.contains(&key.as_str())
+        });
+    }
+
+    Ok(Json(SharedItem {
+        name,
+        fields,
+        expires_at: DateTime::from_timestamp(claims.exp, 0).unwrap_or_default(),
+    }))
+}
+
+/// The requested fields, checked and without duplicates, or the defaults
+fn shared_fields(requested: Option<Vec<String>>) -> Result<Vec<String>, ApiError> {
+    let Some(requested) = requested else {
+        return Ok(DEFAULT_SHARED_FIELDS
+            .iter()
+            .map(|f| f.to_string())
+            .collect());
+    };
+    let mut fields: Vec<String> = Vec::new();
+    for field in requested {
+        if !SHAREABLE_FIELDS.contains(&field.as_str()) {
+            return Err(ApiError::validation_failed(format!(
+                "Field '{}' cannot be shared",
+                field
+            )));
+        }
+        if !fields.contains(&field) {
+            fields.push(field);
+        }
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_fields() {
+        assert_eq!(shared_fields(None).unwrap(), DEFAULT_SHARED_FIELDS);
+        assert_eq!(
+            shared_fields(Some(vec![
+                "notes".to_string(),
+                "barcode".to_string(),
+                "notes".to_string()
+            ]))
+            .unwrap(),
+            ["notes", "barcode"]
+        );
+        assert!(shared_fields(Some(Vec::new())).unwrap().is_empty());
+
+        let err = shared_fields(Some(vec!["location_id".to_string()])).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::ValidationFailed);
+    }
+}
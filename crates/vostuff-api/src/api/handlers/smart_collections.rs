@@ -0,0 +1,372 @@
+//! Smart collections: named, stored item filters. Unlike a collection, items
+//! are never added to one; listing it lists whichever items match its filter
+//! at the time, so "All loaned vinyl" stays current as records are loaned and
+//! returned.
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+use super::items;
+use crate::api::error::ApiError;
+use crate::api::validation::{FieldErrors, ValidJson};
+use crate::api::{
+    models::{
+        CreateSmartCollectionRequest, ErrorCode, ErrorResponse, Item, ItemFilterParams,
+        PaginatedResponse, PaginationParams, Permission, SmartCollection, SmartCollectionFilter,
+        UpdateSmartCollectionRequest,
+    },
+    state::AppState,
+};
+use crate::auth::AuthContext;
+
+const SMART_COLLECTION_COLUMNS: &str =
+    "id, organization_id, name, description, filter, created_at, updated_at";
+
+/// List all smart collections for an organization
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/smart-collections",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "List of smart collections", body = Vec<SmartCollection>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "collections"
+)]
+pub async fn list_smart_collections(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<Vec<SmartCollection>>, ApiError> {
+    let collections = sqlx::query_as::<_, SmartCollection>(&format!(
+        "SELECT {} FROM smart_collections WHERE organization_id = $1 ORDER BY name",
+        SMART_COLLECTION_COLUMNS
+    ))
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(collections))
+}
+
+/// Get a smart collection
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/smart-collections/{collection_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("collection_id" = Uuid, Path, description = "Smart collection ID")
+    ),
+    responses(
+        (status = 200, description = "Smart collection", body = SmartCollection),
+        (status = 404, description = "Smart collection not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "collections"
+)]
+pub async fn get_smart_collection(
+    State(state): State<AppState>,
+    Path((org_id, collection_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<SmartCollection>, ApiError> {
+    fetch(&state, org_id, collection_id).await.map(Json)
+}
+
+/// Create a smart collection
+///
+/// Every kind named in the filter must be one the organization can use.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/smart-collections",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = CreateSmartCollectionRequest,
+    responses(
+        (status = 201, description = "Smart collection created successfully", body = SmartCollection),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 409, description = "A smart collection with this name already exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "collections"
+)]
+pub async fn create_smart_collection(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+    ValidJson(req): ValidJson<CreateSmartCollectionRequest>,
+) -> Result<(StatusCode, Json<SmartCollection>), ApiError> {
+    require_catalog_write(&auth)?;
+    check_kinds(&state, org_id, &req.filter).await?;
+
+    let collection = sqlx::query_as::<_, SmartCollection>(&format!(
+        "INSERT INTO smart_collections (organization_id, name, description, filter)
+         VALUES ($1, $2, $3, $4)
+         RETURNING {}",
+        SMART_COLLECTION_COLUMNS
+    ))
+    .bind(org_id)
+    .bind(req.name.trim())
+    .bind(&req.description)
+    .bind(sqlx::types::Json(&req.filter))
+    .fetch_one(&state.pool)
+    .await
+    .map_err(write_error)?;
+
+    Ok((StatusCode::CREATED, Json(collection)))
+}
+
+/// Update a smart collection
+#[utoipa::path(
+    patch,
+    path = "/api/organizations/{org_id}/smart-collections/{collection_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("collection_id" = Uuid, Path, description = "Smart collection ID")
+    ),
+    request_body = UpdateSmartCollectionRequest,
+    responses(
+        (status = 200, description = "Smart collection updated successfully", body = SmartCollection),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 404, description = "Smart collection not found", body = ErrorResponse),
+        (status = 409, description = "A smart collection with this name already exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "collections"
+)]
+pub async fn update_smart_collection(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, collection_id)): Path<(Uuid, Uuid)>,
+    ValidJson(req): ValidJson<UpdateSmartCollectionRequest>,
+) -> Result<Json<SmartCollection>, ApiError> {
+    require_catalog_write(&auth)?;
+    if let Some(filter) = &req.filter {
+        check_kinds(&state, org_id, filter).await?;
+    }
+
+    let collection = sqlx::query_as::<_, SmartCollection>(&format!(
+        "UPDATE smart_collections
+         SET name = COALESCE($3, name),
+             description = COALESCE($4, description),
+             filter = COALESCE($5, filter)
+         WHERE id = $1 AND organization_id = $2
+         RETURNING {}",
+        SMART_COLLECTION_COLUMNS
+    ))
+    .bind(collection_id)
+    .bind(org_id)
+    .bind(req.name.as_deref().map(str::trim))
+    .bind(&req.description)
+    .bind(req.filter.as_ref().map(sqlx::types::Json))
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(write_error)?
+    .ok_or_else(not_found)?;
+
+    Ok(Json(collection))
+}
+
+/// Delete a smart collection
+///
+/// Its items are untouched.
+#[utoipa::path(
+    delete,
+    path = "/api/organizations/{org_id}/smart-collections/{collection_id}",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("collection_id" = Uuid, Path, description = "Smart collection ID")
+    ),
+    responses(
+        (status = 204, description = "Smart collection deleted successfully"),
+        (status = 404, description = "Smart collection not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "collections"
+)]
+pub async fn delete_smart_collection(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, collection_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    require_catalog_write(&auth)?;
+    let result =
+        sqlx::query("DELETE FROM smart_collections WHERE id = $1 AND organization_id = $2")
+            .bind(collection_id)
+            .bind(org_id)
+            .execute(&state.pool)
+            .await?;
+
+    if result.rows_affected() == 0 {
+        Err(not_found())
+    } else {
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
+
+/// List the items in a smart collection
+///
+/// The items matching its filter now, as the item list would return them.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/smart-collections/{collection_id}/items",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("collection_id" = Uuid, Path, description = "Smart collection ID"),
+        PaginationParams
+    ),
+    responses(
+        (status = 200, description = "Items matching the smart collection", body = PaginatedResponse<Item>),
+        (status = 404, description = "Smart collection not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "collections"
+)]
+pub async fn list_smart_collection_items(
+    State(state): State<AppState>,
+    Path((org_id, collection_id)): Path<(Uuid, Uuid)>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Json<PaginatedResponse<serde_json::Value>>, ApiError> {
+    let collection = fetch(&state, org_id, collection_id).await?;
+    let filters = item_filter(&collection.filter, pagination);
+    items::list_items(State(state), Path(org_id), Query(filters)).await
+}
+
+/// The item list query a smart collection's filter stands for
+fn item_filter(filter: &SmartCollectionFilter, pagination: PaginationParams) -> ItemFilterParams {
+    // The item list reads an empty string as a single empty name, matching
+    // nothing, so an empty criterion is left out altogether
+    fn joined<'a>(values: impl Iterator<Item = &'a str>) -> Option<String> {
+        Some(values.collect::<Vec<_>>().join(",")).filter(|s| !s.is_empty())
+    }
+
+    ItemFilterParams {
+        page: pagination.page,
+        per_page: pagination.per_page,
+        kind: joined(filter.kinds.iter().map(String::as_str)),
+        state: joined(filter.states.iter().map(|s| s.as_str())),
+        tags: joined(filter.tags.iter().map(String::as_str)),
+        tag_match: filter.all_tags.then(|| "all".to_string()),
+        search: filter.search.clone(),
+        ..Default::default()
+    }
+}
+
+async fn fetch(
+    state: &AppState,
+    org_id: Uuid,
+    collection_id: Uuid,
+) -> Result<SmartCollection, ApiError> {
+    sqlx::query_as::<_, SmartCollection>(&format!(
+        "SELECT {} FROM smart_collections WHERE id = $1 AND organization_id = $2",
+        SMART_COLLECTION_COLUMNS
+    ))
+    .bind(collection_id)
+    .bind(org_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(not_found)
+}
+
+/// Reject a filter naming kinds the organization cannot use, which would
+/// otherwise quietly match nothing
+async fn check_kinds(
+    state: &AppState,
+    org_id: Uuid,
+    filter: &SmartCollectionFilter,
+) -> Result<(), ApiError> {
+    if filter.kinds.is_empty() {
+        return Ok(());
+    }
+    let known: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM kinds WHERE name = ANY($1) AND (org_id IS NULL OR org_id = $2)",
+    )
+    .bind(&filter.kinds)
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let mut errors = FieldErrors::default();
+    for (index, kind) in filter.kinds.iter().enumerate() {
+        if !known.contains(kind) {
+            errors.add(
+                &format!("filter.kinds.{}", index),
+                format!("There is no kind named '{}'", kind),
+            );
+        }
+    }
+    errors.into_result()
+}
+
+fn require_catalog_write(auth: &AuthContext) -> Result<(), ApiError> {
+    if auth.can(Permission::CatalogWrite) {
+        Ok(())
+    } else {
+        Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage collections",
+        ))
+    }
+}
+
+/// Map a smart collection write failure, reporting name clashes as 409
+fn write_error(err: sqlx::Error) -> ApiError {
+    match err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::conflict(
+            ErrorCode::SmartCollectionNameConflict,
+            "A smart collection with this name already exists",
+        ),
+        err => ApiError::internal(err),
+    }
+}
+
+fn not_found() -> ApiError {
+    ApiError::not_found(
+        ErrorCode::SmartCollectionNotFound,
+        "Smart collection not found",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::ItemState;
+
+    fn pagination() -> PaginationParams {
+        PaginationParams {
+            page: 2,
+            per_page: 10,
+        }
+    }
+
+    #[test]
+    fn test_filter_becomes_item_list_query() {
+        let filter = SmartCollectionFilter {
+            kinds: vec!["vinyl".into(), "cd".into()],
+            states: vec![ItemState::Loaned],
+            tags: vec!["jazz".into(), "signed".into()],
+            all_tags: true,
+            search: Some("blue".into()),
+        };
+        let params = item_filter(&filter, pagination());
+        assert_eq!(params.page, 2);
+        assert_eq!(params.kind.as_deref(), Some("vinyl,cd"));
+        assert_eq!(params.state.as_deref(), Some("loaned"));
+        assert_eq!(params.tags.as_deref(), Some("jazz,signed"));
+        assert_eq!(params.tag_match.as_deref(), Some("all"));
+        assert_eq!(params.search.as_deref(), Some("blue"));
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let params = item_filter(&SmartCollectionFilter::default(), pagination());
+        assert!(params.kind.is_none());
+        assert!(params.state.is_none());
+        assert!(params.tags.is_none());
+        assert!(params.tag_match.is_none());
+        assert!(params.search.is_none());
+    }
+}
@@ -0,0 +1,246 @@
+//! Item suggestions: details found for items by a background metadata lookup
+//! (see [`crate::api::lookup`]), kept for a member to apply or dismiss.
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::items;
+use crate::api::error::ApiError;
+use crate::api::{
+    lookup,
+    models::{
+        EnrichItemsRequest, EnrichItemsResponse, ErrorCode, ErrorResponse, Item, ItemSuggestion,
+        Permission, UpdateItemRequest,
+    },
+    state::AppState,
+};
+use crate::auth::AuthContext;
+
+fn require_items_write(auth: &AuthContext) -> Result<(), ApiError> {
+    if auth.can(Permission::ItemsWrite) {
+        Ok(())
+    } else {
+        Err(ApiError::auth_forbidden(
+            "The items:write permission is required to change items",
+        ))
+    }
+}
+
+fn not_found() -> ApiError {
+    ApiError::not_found(
+        ErrorCode::ItemSuggestionNotFound,
+        "Suggestion not found or already decided",
+    )
+}
+
+/// Look up details for items in the background
+///
+/// Items with a barcode and a missing description or catalog code are queued
+/// for one lookup job; what it finds appears in `GET .../items/suggestions`.
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/items/enrich",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = EnrichItemsRequest,
+    responses(
+        (status = 202, description = "Lookup queued", body = EnrichItemsResponse),
+        (status = 200, description = "No item needed a lookup", body = EnrichItemsResponse),
+        (status = 400, description = "No items or too many", body = ErrorResponse),
+        (status = 403, description = "Missing the items:write permission", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn enrich_items(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<EnrichItemsRequest>,
+) -> Result<(StatusCode, Json<EnrichItemsResponse>), ApiError> {
+    require_items_write(&auth)?;
+    if req.item_ids.is_empty() || req.item_ids.len() > EnrichItemsRequest::MAX_ITEMS {
+        return Err(ApiError::validation_failed(format!(
+            "item_ids must name 1 to {} items",
+            EnrichItemsRequest::MAX_ITEMS
+        )));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let queued: Vec<Uuid> = sqlx::query_scalar(&format!(
+        "SELECT i.id FROM items i
+         WHERE i.organization_id = $1 AND i.id = ANY($2) AND {}",
+        lookup::NEEDS_LOOKUP
+    ))
+    .bind(org_id)
+    .bind(&req.item_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+    // Keep the request's order
+    let (queued, mut skipped): (Vec<Uuid>, Vec<Uuid>) =
+        req.item_ids.iter().partition(|id| queued.contains(id));
+    skipped.dedup();
+
+    if queued.is_empty() {
+        return Ok((
+            StatusCode::OK,
+            Json(EnrichItemsResponse {
+                job_id: None,
+                queued,
+                skipped,
+            }),
+        ));
+    }
+    let job_id = lookup::enqueue_lookup(&mut tx, org_id, queued.clone()).await?;
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(EnrichItemsResponse {
+            job_id: Some(job_id),
+            queued,
+            skipped,
+        }),
+    ))
+}
+
+/// List suggestions awaiting review
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/items/suggestions",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Pending suggestions, oldest first", body = Vec<ItemSuggestion>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn list_item_suggestions(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<Vec<ItemSuggestion>>, ApiError> {
+    let suggestions = sqlx::query_as::<_, ItemSuggestion>(
+        "SELECT s.id, s.item_id, i.name AS item_name, s.provider, s.source_url, s.changes,
+                jsonb_build_object(
+                    'description', i.description, 'catalog_code', i.catalog_code
+                ) AS current,
+                s.created_at
+         FROM item_suggestions s
+         JOIN items i ON i.id = s.item_id
+         WHERE s.organization_id = $1 AND s.status = 'pending'
+         ORDER BY s.created_at, i.name",
+    )
+    .bind(org_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(suggestions))
+}
+
+/// Apply a suggestion's values to its item
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/items/suggestions/{suggestion_id}/apply",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("suggestion_id" = Uuid, Path, description = "Suggestion ID")
+    ),
+    responses(
+        (status = 200, description = "The updated item", body = Item),
+        (status = 403, description = "Missing the items:write permission", body = ErrorResponse),
+        (status = 404, description = "Suggestion not found or already decided", body = ErrorResponse),
+        (status = 409, description = "Item barcode conflict", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn apply_item_suggestion(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, suggestion_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Item>, ApiError> {
+    require_items_write(&auth)?;
+    let mut tx = state.pool.begin().await?;
+    let (item_id, changes): (Uuid, Value) = sqlx::query_as(
+        "SELECT item_id, changes FROM item_suggestions
+         WHERE id = $1 AND organization_id = $2 AND status = 'pending'
+         FOR UPDATE",
+    )
+    .bind(suggestion_id)
+    .bind(org_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(not_found)?;
+
+    let update: UpdateItemRequest = serde_json::from_value(changes).map_err(ApiError::internal)?;
+    let item = items::update_item_fields(&mut tx, org_id, item_id, auth.user_id, &update).await?;
+    decide(&mut tx, suggestion_id, "applied", auth.user_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(item))
+}
+
+/// Dismiss a suggestion, leaving its item unchanged
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{org_id}/items/suggestions/{suggestion_id}/dismiss",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        ("suggestion_id" = Uuid, Path, description = "Suggestion ID")
+    ),
+    responses(
+        (status = 204, description = "Suggestion dismissed"),
+        (status = 403, description = "Missing the items:write permission", body = ErrorResponse),
+        (status = 404, description = "Suggestion not found or already decided", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "items"
+)]
+pub async fn dismiss_item_suggestion(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path((org_id, suggestion_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    require_items_write(&auth)?;
+    let mut tx = state.pool.begin().await?;
+    let pending: Option<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM item_suggestions
+         WHERE id = $1 AND organization_id = $2 AND status = 'pending'
+         FOR UPDATE",
+    )
+    .bind(suggestion_id)
+    .bind(org_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    pending.ok_or_else(not_found)?;
+    decide(&mut tx, suggestion_id, "dismissed", auth.user_id).await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn decide(
+    conn: &mut sqlx::PgConnection,
+    suggestion_id: Uuid,
+    status: &str,
+    user_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE item_suggestions SET status = $2, decided_by = $3, decided_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(suggestion_id)
+    .bind(status)
+    .bind(user_id)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
@@ -1,12 +1,18 @@
 use axum::{
     Extension, Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
 };
 use uuid::Uuid;
 
+use crate::api::error::ApiError;
+use crate::api::validation::ValidJson;
 use crate::api::{
-    models::{CreateTagRequest, ErrorResponse, Tag},
+    models::{
+        CreateTagRequest, ErrorCode, ErrorResponse, Permission, Tag, TagSuggestParams,
+        TagSuggestion,
+    },
+    outbox,
     state::AppState,
 };
 use crate::auth::AuthContext;
@@ -27,19 +33,70 @@ use crate::auth::AuthContext;
 pub async fn list_tags(
     State(state): State<AppState>,
     Path(org_id): Path<Uuid>,
-) -> Result<Json<Vec<Tag>>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Vec<Tag>>, ApiError> {
     let tags = sqlx::query_as::<_, Tag>(
         "SELECT organization_id, name, created_at
          FROM tags WHERE organization_id = $1 ORDER BY name",
     )
     .bind(org_id)
     .fetch_all(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     Ok(Json(tags))
 }
 
+/// Suggest tags for autocomplete
+///
+/// Tags starting with `q` come first, then tags merely similar to it (trigram
+/// match, so typos still find a tag). Within each group the most used tags lead.
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{org_id}/tags/suggest",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization ID"),
+        TagSuggestParams
+    ),
+    responses(
+        (status = 200, description = "Matching tags, best first", body = Vec<TagSuggestion>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "tags"
+)]
+pub async fn suggest_tags(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Query(params): Query<TagSuggestParams>,
+) -> Result<Json<Vec<TagSuggestion>>, ApiError> {
+    let query = params.q.trim();
+    // Escape LIKE wildcards so the text is matched literally
+    let prefix = format!(
+        "{}%",
+        query
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    let limit = params.limit.clamp(1, 50);
+
+    let suggestions = sqlx::query_as::<_, TagSuggestion>(
+        "SELECT t.name, COUNT(it.item_id) AS usage_count
+         FROM tags t
+         LEFT JOIN item_tags it ON it.organization_id = t.organization_id AND it.tag_name = t.name
+         WHERE t.organization_id = $1 AND (t.name ILIKE $2 OR t.name % $3)
+         GROUP BY t.name
+         ORDER BY t.name ILIKE $2 DESC, usage_count DESC, similarity(t.name, $3) DESC, t.name
+         LIMIT $4",
+    )
+    .bind(org_id)
+    .bind(&prefix)
+    .bind(query)
+    .bind(limit)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(suggestions))
+}
+
 /// Create a new tag
 #[utoipa::path(
     post,
@@ -51,6 +108,7 @@ pub async fn list_tags(
     responses(
         (status = 201, description = "Tag created successfully", body = Tag),
         (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 409, description = "The tag already exists", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "tags"
@@ -59,20 +117,38 @@ pub async fn create_tag(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
     Path(org_id): Path<Uuid>,
-    Json(req): Json<CreateTagRequest>,
-) -> Result<(StatusCode, Json<Tag>), (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden("Administrator access required to manage tags"));
+    ValidJson(req): ValidJson<CreateTagRequest>,
+) -> Result<(StatusCode, Json<Tag>), ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage tags",
+        ));
     }
+    let mut tx = state.pool.begin().await?;
     let tag = sqlx::query_as::<_, Tag>(
         "INSERT INTO tags (organization_id, name) VALUES ($1, $2)
          RETURNING organization_id, name, created_at",
     )
     .bind(org_id)
     .bind(&req.name)
-    .fetch_one(&state.pool)
+    .fetch_one(&mut *tx)
     .await
-    .map_err(internal_error)?;
+    .map_err(|err| match err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::conflict(
+            ErrorCode::TagNameConflict,
+            "A tag with this name already exists",
+        ),
+        err => ApiError::internal(err),
+    })?;
+
+    outbox::enqueue(
+        &mut tx,
+        org_id,
+        outbox::TAG_CREATED,
+        serde_json::to_value(&tag).unwrap_or_default(),
+    )
+    .await?;
+    tx.commit().await?;
 
     Ok((StatusCode::CREATED, Json(tag)))
 }
@@ -96,46 +172,31 @@ pub async fn delete_tag(
     State(state): State<AppState>,
     Extension(auth): Extension<AuthContext>,
     Path((org_id, tag_name)): Path<(Uuid, String)>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    if !auth.is_admin() {
-        return Err(forbidden("Administrator access required to manage tags"));
+) -> Result<StatusCode, ApiError> {
+    if !auth.can(Permission::CatalogWrite) {
+        return Err(ApiError::auth_forbidden(
+            "The catalog:write permission is required to manage tags",
+        ));
     }
+    let mut tx = state.pool.begin().await?;
     let result = sqlx::query("DELETE FROM tags WHERE organization_id = $1 AND name = $2")
         .bind(org_id)
         .bind(&tag_name)
-        .execute(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .execute(&mut *tx)
+        .await?;
 
     if result.rows_affected() == 0 {
-        Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "Tag not found".to_string(),
-            }),
-        ))
-    } else {
-        Ok(StatusCode::NO_CONTENT)
+        return Err(ApiError::not_found(ErrorCode::TagNotFound, "Tag not found"));
     }
-}
 
-fn forbidden(msg: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::FORBIDDEN,
-        Json(ErrorResponse {
-            error: "forbidden".to_string(),
-            message: msg.to_string(),
-        }),
+    outbox::enqueue(
+        &mut tx,
+        org_id,
+        outbox::TAG_DELETED,
+        serde_json::json!({ "name": tag_name }),
     )
-}
+    .await?;
+    tx.commit().await?;
 
-fn internal_error<E: std::fmt::Display>(err: E) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(ErrorResponse {
-            error: "internal_error".to_string(),
-            message: err.to_string(),
-        }),
-    )
+    Ok(StatusCode::NO_CONTENT)
 }
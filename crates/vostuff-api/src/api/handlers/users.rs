@@ -1,40 +1,48 @@
 use axum::{
     Json,
     extract::{Path, State},
-    http::StatusCode,
+    http::{StatusCode, header},
+    response::IntoResponse,
 };
+use sqlx::{Postgres, QueryBuilder};
 use uuid::Uuid;
 
+use super::{exports, settings};
+use crate::api::error::ApiError;
+use crate::api::validation::ValidJson;
 use crate::api::{
     models::{
-        AddUserToOrgRequest, CreateUserRequest, ErrorResponse, Organization,
-        UpdateUserOrgRolesRequest, UpdateUserRequest, User, UserOrganization,
+        AddUserToOrgRequest, CreateUserRequest, ErrorCode, ErrorResponse, Organization, Permission,
+        ResetPasswordResponse, UpdateUserOrgRolesRequest, UpdateUserRequest, UserOrganization,
+        UserResponse, UserRole,
     },
     state::AppState,
 };
 use crate::auth::PasswordHasher;
+use crate::permissions::Permissions;
 
 /// List all users
 #[utoipa::path(
     get,
     path = "/api/admin/users",
     responses(
-        (status = 200, description = "List of users", body = Vec<User>),
+        (status = 200, description = "List of users", body = Vec<UserResponse>),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tag = "admin-users"
 )]
 pub async fn list_users(
     State(state): State<AppState>,
-) -> Result<Json<Vec<User>>, (StatusCode, Json<ErrorResponse>)> {
-    let users = sqlx::query_as::<_, User>(
-        "SELECT id, name, identity, password_hash, created_at, updated_at FROM users ORDER BY name",
+) -> Result<Json<Vec<UserResponse>>, ApiError> {
+    let users = sqlx::query_as::<_, UserRow>(
+        "SELECT id, name, identity, password_hash, is_active,
+                failed_login_attempts, locked_until, created_at, updated_at
+         FROM users ORDER BY name",
     )
     .fetch_all(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
-    Ok(Json(users))
+    Ok(Json(users.into_iter().map(Into::into).collect()))
 }
 
 /// Get a single user by ID
@@ -45,7 +53,7 @@ pub async fn list_users(
         ("user_id" = Uuid, Path, description = "User ID")
     ),
     responses(
-        (status = 200, description = "User details", body = User),
+        (status = 200, description = "User details", body = UserResponse),
         (status = 404, description = "User not found", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -54,23 +62,21 @@ pub async fn list_users(
 pub async fn get_user(
     State(state): State<AppState>,
     Path(user_id): Path<Uuid>,
-) -> Result<Json<User>, (StatusCode, Json<ErrorResponse>)> {
-    let user = sqlx::query_as::<_, User>(
-        "SELECT id, name, identity, password_hash, created_at, updated_at FROM users WHERE id = $1",
+) -> Result<Json<UserResponse>, ApiError> {
+    let user = sqlx::query_as::<_, UserRow>(
+        "SELECT id, name, identity, password_hash, is_active,
+                failed_login_attempts, locked_until, created_at, updated_at
+         FROM users WHERE id = $1",
     )
     .bind(user_id)
     .fetch_optional(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     match user {
-        Some(user) => Ok(Json(user)),
-        None => Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "User not found".to_string(),
-            }),
+        Some(user) => Ok(Json(user.into())),
+        None => Err(ApiError::not_found(
+            ErrorCode::UserNotFound,
+            "User not found",
         )),
     }
 }
@@ -81,7 +87,7 @@ pub async fn get_user(
     path = "/api/admin/users",
     request_body = CreateUserRequest,
     responses(
-        (status = 201, description = "User created successfully", body = User),
+        (status = 201, description = "User created successfully", body = UserResponse),
         (status = 400, description = "Invalid input", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -89,27 +95,27 @@ pub async fn get_user(
 )]
 pub async fn create_user(
     State(state): State<AppState>,
-    Json(req): Json<CreateUserRequest>,
-) -> Result<(StatusCode, Json<User>), (StatusCode, Json<ErrorResponse>)> {
+    ValidJson(req): ValidJson<CreateUserRequest>,
+) -> Result<(StatusCode, Json<UserResponse>), ApiError> {
     // Hash password if provided
     let password_hash = if let Some(password) = &req.password {
-        Some(PasswordHasher::hash_password(password).map_err(internal_error)?)
+        Some(PasswordHasher::hash_password(password).map_err(ApiError::internal)?)
     } else {
         None
     };
 
-    let user = sqlx::query_as::<_, User>(
+    let user = sqlx::query_as::<_, UserRow>(
         "INSERT INTO users (name, identity, password_hash) VALUES ($1, $2, $3)
-         RETURNING id, name, identity, password_hash, created_at, updated_at",
+         RETURNING id, name, identity, password_hash, is_active,
+                failed_login_attempts, locked_until, created_at, updated_at",
     )
     .bind(&req.name)
     .bind(&req.identity)
     .bind(&password_hash)
     .fetch_one(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
-    Ok((StatusCode::CREATED, Json(user)))
+    Ok((StatusCode::CREATED, Json(user.into())))
 }
 
 /// Update an existing user
@@ -121,7 +127,8 @@ pub async fn create_user(
     ),
     request_body = UpdateUserRequest,
     responses(
-        (status = 200, description = "User updated successfully", body = User),
+        (status = 200, description = "User updated successfully", body = UserResponse),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
         (status = 404, description = "User not found", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -130,65 +137,194 @@ pub async fn create_user(
 pub async fn update_user(
     State(state): State<AppState>,
     Path(user_id): Path<Uuid>,
-    Json(req): Json<UpdateUserRequest>,
-) -> Result<Json<User>, (StatusCode, Json<ErrorResponse>)> {
+    ValidJson(req): ValidJson<UpdateUserRequest>,
+) -> Result<Json<UserResponse>, ApiError> {
     // Hash password if provided
     let password_hash = if let Some(password) = &req.password {
-        Some(PasswordHasher::hash_password(password).map_err(internal_error)?)
+        Some(PasswordHasher::hash_password(password).map_err(ApiError::internal)?)
     } else {
         None
     };
 
-    // Build dynamic update query
-    let mut query = String::from("UPDATE users SET updated_at = NOW()");
-    let mut param_num = 2;
+    let user = user_update_query(user_id, &req, password_hash.as_deref())
+        .build_query_as::<UserRow>()
+        .fetch_optional(&state.pool)
+        .await?;
 
-    if req.name.is_some() {
-        query.push_str(&format!(", name = ${}", param_num));
-        param_num += 1;
-    }
-    if req.identity.is_some() {
-        query.push_str(&format!(", identity = ${}", param_num));
-        param_num += 1;
+    match user {
+        Some(user) => Ok(Json(user.into())),
+        None => Err(ApiError::not_found(
+            ErrorCode::UserNotFound,
+            "User not found",
+        )),
     }
-    if req.password.is_some() {
-        query.push_str(&format!(", password_hash = ${}", param_num));
+}
+
+/// Reset a user's password to a generated temporary one
+///
+/// The user must choose a new password at their next login; until then the
+/// login answers `password_change_required` instead of issuing a token. Their
+/// refresh tokens are revoked, so existing sessions end when their access
+/// tokens expire. Any login lockout is cleared.
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{user_id}/reset-password",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Password reset; the temporary password is returned once", body = ResetPasswordResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-users"
+)]
+pub async fn reset_user_password(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<ResetPasswordResponse>, ApiError> {
+    let temporary_password = PasswordHasher::generate_temporary_password();
+    let password_hash =
+        PasswordHasher::hash_password(&temporary_password).map_err(ApiError::internal)?;
+
+    let result = sqlx::query(
+        "UPDATE users
+         SET password_hash = $2, must_change_password = TRUE,
+             failed_login_attempts = 0, locked_until = NULL, updated_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(user_id)
+    .bind(&password_hash)
+    .execute(&state.pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found(
+            ErrorCode::UserNotFound,
+            "User not found",
+        ));
     }
 
-    query.push_str(
-        " WHERE id = $1 RETURNING id, name, identity, password_hash, created_at, updated_at",
-    );
+    sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = NOW()
+         WHERE user_id = $1 AND revoked_at IS NULL",
+    )
+    .bind(user_id)
+    .execute(&state.pool)
+    .await?;
 
-    let mut query_builder = sqlx::query_as::<_, User>(&query).bind(user_id);
+    Ok(Json(ResetPasswordResponse { temporary_password }))
+}
 
-    if let Some(name) = &req.name {
-        query_builder = query_builder.bind(name);
-    }
-    if let Some(identity) = &req.identity {
-        query_builder = query_builder.bind(identity);
-    }
-    if req.password.is_some() {
-        query_builder = query_builder.bind(&password_hash);
+/// Deactivate a user
+///
+/// The user can no longer log in and their existing tokens are rejected, but
+/// their memberships and history attribution are kept. Reverse with `activate`.
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{user_id}/deactivate",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User deactivated", body = UserResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-users"
+)]
+pub async fn deactivate_user(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<UserResponse>, ApiError> {
+    set_user_active(&state, user_id, false).await
+}
+
+/// Reactivate a deactivated user
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{user_id}/activate",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User activated", body = UserResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-users"
+)]
+pub async fn activate_user(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<UserResponse>, ApiError> {
+    set_user_active(&state, user_id, true).await
+}
+
+/// Unlock a user locked out by repeated failed logins
+///
+/// Clears the lock and the failed login count, so the user can log in at once.
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{user_id}/unlock",
+    params(
+        ("user_id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User unlocked", body = UserResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tag = "admin-users"
+)]
+pub async fn unlock_user(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<UserResponse>, ApiError> {
+    let user = sqlx::query_as::<_, UserRow>(
+        "UPDATE users SET failed_login_attempts = 0, locked_until = NULL, updated_at = NOW()
+         WHERE id = $1
+         RETURNING id, name, identity, password_hash, is_active,
+                failed_login_attempts, locked_until, created_at, updated_at",
+    )
+    .bind(user_id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    match user {
+        Some(user) => Ok(Json(user.into())),
+        None => Err(ApiError::not_found(
+            ErrorCode::UserNotFound,
+            "User not found",
+        )),
     }
+}
 
-    let user = query_builder
-        .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?;
+async fn set_user_active(
+    state: &AppState,
+    user_id: Uuid,
+    is_active: bool,
+) -> Result<Json<UserResponse>, ApiError> {
+    let user = sqlx::query_as::<_, UserRow>(
+        "UPDATE users SET is_active = $2, updated_at = NOW() WHERE id = $1
+         RETURNING id, name, identity, password_hash, is_active,
+                failed_login_attempts, locked_until, created_at, updated_at",
+    )
+    .bind(user_id)
+    .bind(is_active)
+    .fetch_optional(&state.pool)
+    .await?;
 
     match user {
-        Some(user) => Ok(Json(user)),
-        None => Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "User not found".to_string(),
-            }),
+        Some(user) => Ok(Json(user.into())),
+        None => Err(ApiError::not_found(
+            ErrorCode::UserNotFound,
+            "User not found",
         )),
     }
 }
 
-/// Delete a user
+/// Delete a user, keeping a final data export
 #[utoipa::path(
     delete,
     path = "/api/admin/users/{user_id}",
@@ -196,7 +332,7 @@ pub async fn update_user(
         ("user_id" = Uuid, Path, description = "User ID")
     ),
     responses(
-        (status = 204, description = "User deleted successfully"),
+        (status = 204, description = "User deleted; the Location header links to its final data export"),
         (status = 404, description = "User not found", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -205,24 +341,26 @@ pub async fn update_user(
 pub async fn delete_user(
     State(state): State<AppState>,
     Path(user_id): Path<Uuid>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    let result = sqlx::query("DELETE FROM users WHERE id = $1")
-        .bind(user_id)
-        .execute(&state.pool)
+) -> Result<impl IntoResponse, ApiError> {
+    let mut tx = state.pool.begin().await?;
+
+    // Keep a final export of the user's data for the retention window
+    let export_id = exports::archive_user(&mut tx, &state, user_id)
         .await
-        .map_err(internal_error)?;
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| ApiError::not_found(ErrorCode::UserNotFound, "User not found"))?;
 
-    if result.rows_affected() == 0 {
-        Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "User not found".to_string(),
-            }),
-        ))
-    } else {
-        Ok(StatusCode::NO_CONTENT)
-    }
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok((
+        StatusCode::NO_CONTENT,
+        [(header::LOCATION, exports::download_url(export_id))],
+    ))
 }
 
 /// List organizations for a user
@@ -242,21 +380,17 @@ pub async fn delete_user(
 pub async fn list_user_organizations(
     State(state): State<AppState>,
     Path(user_id): Path<Uuid>,
-) -> Result<Json<Vec<Organization>>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Vec<Organization>>, ApiError> {
     // First check if user exists
     let user_exists = sqlx::query("SELECT id FROM users WHERE id = $1")
         .bind(user_id)
         .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
     if user_exists.is_none() {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "User not found".to_string(),
-            }),
+        return Err(ApiError::not_found(
+            ErrorCode::UserNotFound,
+            "User not found",
         ));
     }
 
@@ -269,8 +403,7 @@ pub async fn list_user_organizations(
     )
     .bind(user_id)
     .fetch_all(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     Ok(Json(organizations))
 }
@@ -286,6 +419,7 @@ pub async fn list_user_organizations(
     request_body = AddUserToOrgRequest,
     responses(
         (status = 201, description = "User added to organization successfully", body = UserOrganization),
+        (status = 400, description = "Empty role or permission list", body = ErrorResponse),
         (status = 404, description = "User or organization not found", body = ErrorResponse),
         (status = 409, description = "User already in organization", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
@@ -296,71 +430,68 @@ pub async fn add_user_to_organization(
     State(state): State<AppState>,
     Path((user_id, org_id)): Path<(Uuid, Uuid)>,
     Json(req): Json<AddUserToOrgRequest>,
-) -> Result<(StatusCode, Json<UserOrganization>), (StatusCode, Json<ErrorResponse>)> {
+) -> Result<(StatusCode, Json<UserOrganization>), ApiError> {
     // Verify user and organization exist
     let user_exists = sqlx::query("SELECT id FROM users WHERE id = $1")
         .bind(user_id)
         .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
     if user_exists.is_none() {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "User not found".to_string(),
-            }),
+        return Err(ApiError::not_found(
+            ErrorCode::UserNotFound,
+            "User not found",
         ));
     }
 
     let org_exists = sqlx::query("SELECT id FROM organizations WHERE id = $1")
         .bind(org_id)
         .fetch_optional(&state.pool)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
     if org_exists.is_none() {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "Organization not found".to_string(),
-            }),
+        return Err(ApiError::not_found(
+            ErrorCode::OrganizationNotFound,
+            "Organization not found",
         ));
     }
 
-    // Prepare roles - default to USER if not provided
-    let roles: Vec<String> = req
-        .roles
-        .map(|r| r.iter().map(|role| role.as_str().to_string()).collect())
-        .unwrap_or_else(|| vec!["USER".to_string()]);
+    // Roles default to the organization's default member roles, else USER
+    let roles = match req.roles {
+        Some(roles) => roles,
+        None => settings::load_settings(&state.pool, org_id)
+            .await?
+            .default_member_roles
+            .unwrap_or_else(|| vec![UserRole::User]),
+    };
+    if roles.is_empty() {
+        return Err(no_roles());
+    }
+    let permissions = member_permissions(&roles, req.permissions)?;
 
     // Add user to organization
     let result = sqlx::query_as::<_, UserOrganization>(
-        "INSERT INTO user_organizations (user_id, organization_id, roles) VALUES ($1, $2, $3)
-         RETURNING user_id, organization_id, roles, created_at",
+        "INSERT INTO user_organizations (user_id, organization_id, roles, permissions)
+         VALUES ($1, $2, $3, $4)
+         RETURNING user_id, organization_id, roles, permissions, created_at",
     )
     .bind(user_id)
     .bind(org_id)
     .bind(&roles)
+    .bind(permissions.into_vec())
     .fetch_one(&state.pool)
     .await;
 
     match result {
         Ok(user_org) => Ok((StatusCode::CREATED, Json(user_org))),
-        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => Err((
-            StatusCode::CONFLICT,
-            Json(ErrorResponse {
-                error: "conflict".to_string(),
-                message: "User already in organization".to_string(),
-            }),
-        )),
-        Err(err) => Err(internal_error(err)),
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => Err(
+            ApiError::conflict(ErrorCode::UserAlreadyMember, "User already in organization"),
+        ),
+        Err(err) => Err(ApiError::internal(err)),
     }
 }
 
-/// Update user roles in organization
+/// Update user roles and permissions in organization
 #[utoipa::path(
     patch,
     path = "/api/admin/users/{user_id}/organizations/{org_id}",
@@ -371,6 +502,7 @@ pub async fn add_user_to_organization(
     request_body = UpdateUserOrgRolesRequest,
     responses(
         (status = 200, description = "User roles updated successfully", body = UserOrganization),
+        (status = 400, description = "Empty role or permission list", body = ErrorResponse),
         (status = 404, description = "User not in organization", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -380,36 +512,31 @@ pub async fn update_user_org_roles(
     State(state): State<AppState>,
     Path((user_id, org_id)): Path<(Uuid, Uuid)>,
     Json(req): Json<UpdateUserOrgRolesRequest>,
-) -> Result<Json<UserOrganization>, (StatusCode, Json<ErrorResponse>)> {
-    // Convert UserRole to strings
-    let roles: Vec<String> = req
-        .roles
-        .iter()
-        .map(|role| role.as_str().to_string())
-        .collect();
-
-    // Update user roles in organization
+) -> Result<Json<UserOrganization>, ApiError> {
+    if req.roles.is_empty() {
+        return Err(no_roles());
+    }
+    let permissions = member_permissions(&req.roles, req.permissions)?;
+
+    // Update user roles and permissions in organization
     let result = sqlx::query_as::<_, UserOrganization>(
         "UPDATE user_organizations
-         SET roles = $3
+         SET roles = $3, permissions = $4
          WHERE user_id = $1 AND organization_id = $2
-         RETURNING user_id, organization_id, roles, created_at",
+         RETURNING user_id, organization_id, roles, permissions, created_at",
     )
     .bind(user_id)
     .bind(org_id)
-    .bind(&roles)
+    .bind(&req.roles)
+    .bind(permissions.into_vec())
     .fetch_optional(&state.pool)
-    .await
-    .map_err(internal_error)?;
+    .await?;
 
     match result {
         Some(user_org) => Ok(Json(user_org)),
-        None => Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "User not in organization".to_string(),
-            }),
+        None => Err(ApiError::not_found(
+            ErrorCode::UserNotMember,
+            "User not in organization",
         )),
     }
 }
@@ -432,34 +559,128 @@ pub async fn update_user_org_roles(
 pub async fn remove_user_from_organization(
     State(state): State<AppState>,
     Path((user_id, org_id)): Path<(Uuid, Uuid)>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<StatusCode, ApiError> {
     let result =
         sqlx::query("DELETE FROM user_organizations WHERE user_id = $1 AND organization_id = $2")
             .bind(user_id)
             .bind(org_id)
             .execute(&state.pool)
-            .await
-            .map_err(internal_error)?;
+            .await?;
 
     if result.rows_affected() == 0 {
-        Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: "not_found".to_string(),
-                message: "User not in organization".to_string(),
-            }),
+        Err(ApiError::not_found(
+            ErrorCode::UserNotMember,
+            "User not in organization",
         ))
     } else {
         Ok(StatusCode::NO_CONTENT)
     }
 }
 
-fn internal_error<E: std::fmt::Display>(err: E) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(ErrorResponse {
-            error: "internal_error".to_string(),
-            message: err.to_string(),
-        }),
-    )
+/// The permissions to store for a member: those requested, else those the roles grant
+pub(super) fn member_permissions(
+    roles: &[UserRole],
+    requested: Option<Vec<Permission>>,
+) -> Result<Permissions, ApiError> {
+    let permissions = match requested {
+        Some(permissions) => Permissions::from(permissions),
+        None => Permissions::for_roles(roles),
+    };
+    if permissions.is_empty() {
+        return Err(ApiError::validation_failed(
+            "permissions must name at least one permission",
+        ));
+    }
+    Ok(permissions)
+}
+
+/// UPDATE setting the fields given in the request, returning the user
+fn user_update_query<'a>(
+    user_id: Uuid,
+    req: &'a UpdateUserRequest,
+    password_hash: Option<&'a str>,
+) -> QueryBuilder<'a, Postgres> {
+    let mut query = QueryBuilder::new("UPDATE users SET updated_at = NOW()");
+    if let Some(name) = &req.name {
+        query.push(", name = ").push_bind(name);
+    }
+    if let Some(identity) = &req.identity {
+        query.push(", identity = ").push_bind(identity);
+    }
+    if let Some(password_hash) = password_hash {
+        query.push(", password_hash = ").push_bind(password_hash);
+    }
+    query.push(" WHERE id = ").push_bind(user_id).push(
+        " RETURNING id, name, identity, password_hash, is_active,
+                failed_login_attempts, locked_until, created_at, updated_at",
+    );
+    query
+}
+
+/// A users row; only the API reads the password hash, and `UserResponse`
+/// leaves it out
+#[derive(sqlx::FromRow)]
+pub(super) struct UserRow {
+    id: Uuid,
+    name: String,
+    identity: String,
+    password_hash: Option<String>,
+    is_active: bool,
+    failed_login_attempts: i32,
+    locked_until: Option<chrono::DateTime<chrono::Utc>>,
+    created_at: chrono::DateTime<chrono::Utc>,
+    updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<UserRow> for UserResponse {
+    fn from(row: UserRow) -> Self {
+        UserResponse {
+            id: row.id,
+            name: row.name,
+            identity: row.identity,
+            has_password: row.password_hash.is_some(),
+            is_active: row.is_active,
+            failed_login_attempts: row.failed_login_attempts,
+            locked_until: row.locked_until,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// A member needs at least one role
+pub(super) fn no_roles() -> ApiError {
+    ApiError::validation_failed("roles must name at least one role")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(req: serde_json::Value, password_hash: Option<&str>) -> String {
+        let req: UpdateUserRequest = serde_json::from_value(req).unwrap();
+        let query = user_update_query(Uuid::nil(), &req, password_hash);
+        let sql: Vec<_> = query.sql().split_whitespace().collect();
+        let sql = sql.join(" ");
+        sql[..sql.find(" RETURNING").unwrap()].to_string()
+    }
+
+    #[test]
+    fn test_user_update_sets_only_given_fields() {
+        assert_eq!(
+            update(serde_json::json!({}), None),
+            "UPDATE users SET updated_at = NOW() WHERE id = $1"
+        );
+        assert_eq!(
+            update(serde_json::json!({"identity": "dee@example.com"}), None),
+            "UPDATE users SET updated_at = NOW(), identity = $1 WHERE id = $2"
+        );
+        assert_eq!(
+            update(
+                serde_json::json!({"name": "Dee", "password": "secret123"}),
+                Some("hash")
+            ),
+            "UPDATE users SET updated_at = NOW(), name = $1, password_hash = $2 WHERE id = $3"
+        );
+    }
 }
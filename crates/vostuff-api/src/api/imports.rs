@@ -0,0 +1,317 @@
+//! CSV imports run on the server.
+//!
+//! `POST .../imports` stores the uploaded CSV, records an `item_imports` row
+//! and queues one [`ITEMS_IMPORT`](jobs::ITEMS_IMPORT) job for it with
+//! [`enqueue_import`]. [`ImportItemsJob`] routes each row through the import's
+//! column mapping (see [`crate::import_mapping`]), as `csv-importer` does, and
+//! creates the item. Each row is committed with the import's counts, so
+//! `GET .../imports/{id}` shows progress as it goes and a retried job carries
+//! on after the last row done rather than creating items twice. The stored
+//! CSV is deleted once the import has succeeded or failed for good.
+
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+use vostuff_core::storage::Storage;
+
+use crate::api::{
+    error::ApiError,
+    handlers::items,
+    jobs::{self, Job, JobHandler},
+    models::{CreateItemRequest, ErrorCode},
+    validation::Validate,
+};
+use crate::import_mapping::{ImportMapping, SoftFieldSpec, parse_import_date, typed_soft_fields};
+
+/// Most rows one import may have
+pub const MAX_ROWS: usize = 10_000;
+
+/// Largest CSV accepted, as the request body's limit
+pub const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Rows not imported beyond this many are counted but not described
+pub const MAX_ROW_ERRORS: i32 = 500;
+
+/// Where an import's CSV is stored
+pub fn storage_key(org_id: Uuid, import_id: Uuid) -> String {
+    format!("imports/{}/{}.csv", org_id, import_id)
+}
+
+/// A CSV file's trimmed headers and its rows; rows may be shorter or longer
+/// than the headers
+pub fn read_csv(data: &[u8]) -> Result<(Vec<String>, Vec<Vec<String>>), csv::Error> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(data);
+    let headers = reader
+        .headers()?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+    let rows = reader
+        .records()
+        .map(|record| Ok(record?.iter().map(str::to_string).collect()))
+        .collect::<Result<_, csv::Error>>()?;
+    Ok((headers, rows))
+}
+
+#[derive(sqlx::FromRow)]
+struct SoftFieldRow {
+    name: String,
+    field_type: String,
+    enum_values: sqlx::types::Json<Vec<(String, Option<String>)>>,
+}
+
+/// A kind's soft fields, in display order, to type the values mapped to them
+pub async fn soft_field_specs(
+    pool: &PgPool,
+    kind_id: Uuid,
+) -> Result<Vec<SoftFieldSpec>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, SoftFieldRow>(
+        "SELECT f.name, f.field_type::text AS field_type, COALESCE(
+             (SELECT json_agg(json_build_array(ev.value, ev.display_value)
+                              ORDER BY ev.sort_order)
+              FROM enum_values ev WHERE ev.field_id = f.id),
+             '[]'::json) AS enum_values
+         FROM kind_fields kf JOIN fields f ON f.id = kf.field_id
+         WHERE kf.kind_id = $1
+         ORDER BY kf.display_order",
+    )
+    .bind(kind_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| SoftFieldSpec {
+            name: row.name,
+            field_type: row.field_type,
+            enum_values: row.enum_values.0,
+        })
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ImportPayload {
+    import_id: Uuid,
+    /// Whether the user who started the import may create missing tags
+    can_create_tags: bool,
+}
+
+/// Queue the import's job; call inside the transaction recording the import
+pub async fn enqueue_import(
+    conn: &mut PgConnection,
+    organization_id: Uuid,
+    import_id: Uuid,
+    can_create_tags: bool,
+) -> Result<Uuid, sqlx::Error> {
+    jobs::enqueue(
+        conn,
+        jobs::ITEMS_IMPORT,
+        Some(organization_id),
+        serde_json::to_value(ImportPayload {
+            import_id,
+            can_create_tags,
+        })
+        .expect("a payload serializes"),
+    )
+    .await
+}
+
+/// What became of one CSV row
+enum RowOutcome {
+    Imported,
+    Skipped(String),
+    Failed(String),
+}
+
+/// Runs the imports queued by [`enqueue_import`]. Rows are taken from the
+/// first not yet processed; an import whose job fails for good is marked
+/// failed with the reason.
+pub struct ImportItemsJob {
+    pool: PgPool,
+    storage: Arc<dyn Storage>,
+}
+
+impl ImportItemsJob {
+    pub fn new(pool: PgPool, storage: Arc<dyn Storage>) -> Self {
+        Self { pool, storage }
+    }
+
+    async fn import(&self, org_id: Uuid, payload: &ImportPayload) -> anyhow::Result<()> {
+        let import: Option<(Uuid, Value, String, i32, Option<Uuid>)> = sqlx::query_as(
+            "UPDATE item_imports
+             SET status = 'running', started_at = COALESCE(started_at, NOW())
+             WHERE id = $1 AND organization_id = $2 AND status IN ('pending', 'running')
+             RETURNING kind_id, mapping, storage_key, processed_rows, created_by",
+        )
+        .bind(payload.import_id)
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        // Deleted with its organization, or already finished
+        let Some((kind_id, mapping, key, processed, created_by)) = import else {
+            return Ok(());
+        };
+        let user_id = created_by.context("the user who started the import was deleted")?;
+        let mapping: ImportMapping = serde_json::from_value(mapping)?;
+
+        let data = self.storage.get(&key).await?;
+        let (headers, rows) = read_csv(&data).context("reading the CSV")?;
+        let fields = soft_field_specs(&self.pool, kind_id).await?;
+
+        for (index, values) in rows.iter().enumerate().skip(processed as usize) {
+            let row = mapping.apply(&headers, values);
+            let name = row.name.clone();
+
+            let mut tx = self.pool.begin().await?;
+            let outcome = match (&row.name, typed_soft_fields(&row.soft_fields, &fields)) {
+                (None, _) => RowOutcome::Skipped("No name".to_string()),
+                (_, Err(e)) => RowOutcome::Failed(e),
+                (Some(name), Ok(soft_fields)) => {
+                    let req = CreateItemRequest {
+                        kind_id,
+                        name: name.clone(),
+                        description: row.description,
+                        notes: row.notes,
+                        location_id: None,
+                        date_acquired: row.date_acquired.as_deref().and_then(parse_import_date),
+                        barcode: row.barcode,
+                        catalog_code: row.catalog_code,
+                        width_mm: None,
+                        height_mm: None,
+                        depth_mm: None,
+                        weight_g: None,
+                        soft_fields: (!soft_fields.is_empty()).then(|| soft_fields.into()),
+                        tags: (!row.tags.is_empty()).then_some(row.tags),
+                    };
+                    let created = match req.check() {
+                        Ok(()) => {
+                            items::insert_item(
+                                &self.pool,
+                                &mut tx,
+                                org_id,
+                                user_id,
+                                payload.can_create_tags,
+                                req,
+                            )
+                            .await
+                        }
+                        Err(e) => Err(e),
+                    };
+                    match created {
+                        Ok(_) => RowOutcome::Imported,
+                        // Likely passing, e.g. the database going away: retry
+                        // the job from this row
+                        Err(ApiError::Internal(e)) => {
+                            anyhow::bail!("row {}: {}", index + 1, e)
+                        }
+                        Err(e) if e.code() == ErrorCode::ItemDuplicateBarcode => {
+                            RowOutcome::Skipped(e.message().to_string())
+                        }
+                        Err(e) => RowOutcome::Failed(e.message().to_string()),
+                    }
+                }
+            };
+            if !matches!(outcome, RowOutcome::Imported) {
+                // Leave nothing of the row behind
+                tx.rollback().await?;
+                tx = self.pool.begin().await?;
+            }
+            record_row(&mut tx, payload.import_id, index, name, outcome).await?;
+            tx.commit().await?;
+        }
+
+        sqlx::query(
+            "UPDATE item_imports SET status = 'succeeded', finished_at = NOW() WHERE id = $1",
+        )
+        .bind(payload.import_id)
+        .execute(&self.pool)
+        .await?;
+        self.remove_file(&key).await;
+        Ok(())
+    }
+
+    /// Delete a finished import's CSV, logging failures; the import's rows
+    /// are done with, so a leftover file is only wasted space
+    async fn remove_file(&self, key: &str) {
+        if let Err(e) = self.storage.delete(key).await {
+            tracing::warn!("Failed to delete import file {}: {}", key, e);
+        }
+    }
+}
+
+/// Count a processed row, describing it when it was not imported
+async fn record_row(
+    conn: &mut PgConnection,
+    import_id: Uuid,
+    index: usize,
+    name: Option<String>,
+    outcome: RowOutcome,
+) -> Result<(), sqlx::Error> {
+    let (imported, skipped, failed, error) = match outcome {
+        RowOutcome::Imported => (1, 0, 0, None),
+        RowOutcome::Skipped(message) => (0, 1, 0, Some((true, message))),
+        RowOutcome::Failed(message) => (0, 0, 1, Some((false, message))),
+    };
+    let error = error.map(|(skipped, message)| {
+        json!({"row": index + 1, "name": name, "skipped": skipped, "message": message})
+    });
+    sqlx::query(
+        "UPDATE item_imports SET
+           processed_rows = processed_rows + 1,
+           imported_rows = imported_rows + $2,
+           skipped_rows = skipped_rows + $3,
+           failed_rows = failed_rows + $4,
+           errors = CASE
+             WHEN $5::jsonb IS NULL OR jsonb_array_length(errors) >= $6 THEN errors
+             ELSE errors || jsonb_build_array($5::jsonb)
+           END
+         WHERE id = $1",
+    )
+    .bind(import_id)
+    .bind(imported)
+    .bind(skipped)
+    .bind(failed)
+    .bind(error)
+    .bind(MAX_ROW_ERRORS)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+#[async_trait]
+impl JobHandler for ImportItemsJob {
+    fn kind(&self) -> &str {
+        jobs::ITEMS_IMPORT
+    }
+
+    async fn run(&self, job: &Job) -> anyhow::Result<()> {
+        let org_id = job
+            .organization_id
+            .context("an import job needs an organization")?;
+        let payload: ImportPayload = serde_json::from_value(job.payload.clone())?;
+
+        let result = self.import(org_id, &payload).await;
+        if let Err(e) = &result
+            && job.attempts >= job.max_attempts
+        {
+            let key: Option<String> = sqlx::query_scalar(
+                "UPDATE item_imports
+                 SET status = 'failed', last_error = $2, finished_at = NOW()
+                 WHERE id = $1
+                 RETURNING storage_key",
+            )
+            .bind(payload.import_id)
+            .bind(format!("{:#}", e))
+            .fetch_optional(&self.pool)
+            .await?;
+            if let Some(key) = key {
+                self.remove_file(&key).await;
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,260 @@
+//! Background jobs.
+//!
+//! Work too slow or unreliable for the request path, such as sending email, is
+//! stored as a row in `jobs` by [`enqueue`], usually inside the transaction
+//! making the change that needs it. A worker in the API server
+//! ([`spawn_worker`]) claims due jobs one at a time and runs the
+//! [`JobHandler`] registered for their kind. A failed job is retried with
+//! backoff until it succeeds or reaches its `max_attempts`, when it is marked
+//! failed and kept for inspection through `GET /admin/jobs`.
+//!
+//! Like the outbox, execution is at-least-once: a job whose worker dies while
+//! running it is picked up again once it is presumed abandoned, so handlers
+//! should tolerate running twice.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{PgConnection, PgPool};
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Longest wait between retries of a failing job
+const MAX_BACKOFF_SECS: i32 = 3600;
+
+/// A running job not finished after this long is presumed abandoned by a
+/// worker that died, and is run again
+const ABANDONED_AFTER_MINUTES: i32 = 15;
+
+/// Succeeded jobs are kept this long, then deleted
+const SUCCEEDED_RETENTION_DAYS: i32 = 7;
+
+/// Failed jobs are kept this long for investigation, then deleted
+const FAILED_RETENTION_DAYS: i32 = 30;
+
+/// How often the worker requeues abandoned jobs and purges finished ones
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Job statuses, in lifecycle order
+pub const STATUSES: [&str; 4] = ["pending", "running", "succeeded", "failed"];
+
+/// Send one [`Email`](crate::api::mail::Email)
+pub const EMAIL_SEND: &str = "email.send";
+
+/// Look up details for an organization's items; see [`crate::api::lookup`]
+pub const ITEMS_LOOKUP: &str = "items.lookup";
+
+/// Create items from an uploaded CSV; see [`crate::api::imports`]
+pub const ITEMS_IMPORT: &str = "items.import";
+
+/// A queued, running or finished job
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct Job {
+    pub id: Uuid,
+    /// What the job does, such as `email.send`
+    pub kind: String,
+    /// The organization the work is for; None for instance-wide work
+    pub organization_id: Option<Uuid>,
+    /// Input for the job's handler
+    pub payload: Value,
+    /// pending, running, succeeded or failed
+    pub status: String,
+    /// Runs started so far
+    pub attempts: i32,
+    pub max_attempts: i32,
+    /// Why the latest run failed
+    pub last_error: Option<String>,
+    /// When a pending job is next due
+    pub run_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub(crate) const JOB_COLUMNS: &str = "id, kind, organization_id, payload, status, attempts, \
+     max_attempts, last_error, run_at, started_at, finished_at, created_at";
+
+/// Runs the jobs of one kind
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    /// The job kind handled, such as [`EMAIL_SEND`]
+    fn kind(&self) -> &str;
+
+    async fn run(&self, job: &Job) -> anyhow::Result<()>;
+}
+
+/// Store a job to run as soon as a worker is free; call inside the transaction
+/// making the change that needs it
+pub async fn enqueue(
+    conn: &mut PgConnection,
+    kind: &str,
+    organization_id: Option<Uuid>,
+    payload: Value,
+) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar(
+        "INSERT INTO jobs (kind, organization_id, payload) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(kind)
+    .bind(organization_id)
+    .bind(payload)
+    .fetch_one(conn)
+    .await
+}
+
+/// Claim and run the oldest due job; returns whether there was one.
+///
+/// The job is claimed with `FOR UPDATE SKIP LOCKED` and marked running before
+/// its handler starts, so several API instances can run workers against the
+/// same database. A job with no handler for its kind fails like any other.
+pub async fn run_next(
+    pool: &PgPool,
+    handlers: &[Arc<dyn JobHandler>],
+) -> Result<bool, sqlx::Error> {
+    let job = sqlx::query_as::<_, Job>(&format!(
+        "UPDATE jobs SET status = 'running', attempts = attempts + 1, started_at = NOW()
+         WHERE id = (
+           SELECT id FROM jobs
+           WHERE status = 'pending' AND run_at <= NOW()
+           ORDER BY run_at
+           LIMIT 1
+           FOR UPDATE SKIP LOCKED
+         )
+         RETURNING {}",
+        JOB_COLUMNS
+    ))
+    .fetch_optional(pool)
+    .await?;
+    let Some(job) = job else {
+        return Ok(false);
+    };
+
+    let result = match handlers.iter().find(|h| h.kind() == job.kind) {
+        Some(handler) => {
+            // Run on its own task so a panicking handler fails the job rather
+            // than the worker
+            let handler = handler.clone();
+            let job = job.clone();
+            match tokio::spawn(async move { handler.run(&job).await }).await {
+                Ok(result) => result,
+                Err(e) => Err(anyhow::anyhow!("handler panicked: {}", e)),
+            }
+        }
+        None => Err(anyhow::anyhow!("no handler for job kind {}", job.kind)),
+    };
+
+    match result {
+        Ok(()) => {
+            sqlx::query(
+                "UPDATE jobs SET status = 'succeeded', last_error = NULL, finished_at = NOW()
+                 WHERE id = $1",
+            )
+            .bind(job.id)
+            .execute(pool)
+            .await?;
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Job {} ({}) failed on attempt {}: {:#}",
+                job.id,
+                job.kind,
+                job.attempts,
+                e
+            );
+            // Exponential backoff: 2, 4, 8, ... seconds, capped
+            sqlx::query(
+                "UPDATE jobs SET
+                   status = CASE WHEN attempts >= max_attempts THEN 'failed' ELSE 'pending' END,
+                   finished_at = CASE WHEN attempts >= max_attempts THEN NOW() END,
+                   last_error = $2,
+                   run_at = NOW() + make_interval(secs => LEAST(POWER(2, attempts), $3))
+                 WHERE id = $1",
+            )
+            .bind(job.id)
+            .bind(format!("{:#}", e))
+            .bind(MAX_BACKOFF_SECS)
+            .execute(pool)
+            .await?;
+        }
+    }
+
+    Ok(true)
+}
+
+/// Return jobs left running by a worker that died to the queue, or fail them
+/// if they have no attempts left; returns how many were affected
+pub async fn requeue_abandoned(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE jobs SET
+           status = CASE WHEN attempts >= max_attempts THEN 'failed' ELSE 'pending' END,
+           finished_at = CASE WHEN attempts >= max_attempts THEN NOW() END,
+           last_error = 'abandoned while running',
+           run_at = NOW()
+         WHERE status = 'running' AND started_at < NOW() - make_interval(mins => $1)",
+    )
+    .bind(ABANDONED_AFTER_MINUTES)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Delete finished jobs past their retention window
+pub async fn purge_finished(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "DELETE FROM jobs
+         WHERE (status = 'succeeded' AND finished_at < NOW() - make_interval(days => $1))
+            OR (status = 'failed' AND finished_at < NOW() - make_interval(days => $2))",
+    )
+    .bind(SUCCEEDED_RETENTION_DAYS)
+    .bind(FAILED_RETENTION_DAYS)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Run the worker in the background, polling for due jobs every `interval`
+pub fn spawn_worker(
+    pool: PgPool,
+    handlers: Vec<Arc<dyn JobHandler>>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut last_maintenance: Option<Instant> = None;
+        loop {
+            ticker.tick().await;
+
+            // Keep going while there are due jobs
+            loop {
+                match run_next(&pool, &handlers).await {
+                    Ok(true) => continue,
+                    Ok(false) => break,
+                    Err(e) => {
+                        tracing::error!("Job worker failed: {}", e);
+                        break;
+                    }
+                }
+            }
+
+            if last_maintenance.is_none_or(|t| t.elapsed() >= MAINTENANCE_INTERVAL) {
+                last_maintenance = Some(Instant::now());
+                match requeue_abandoned(&pool).await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::warn!("Requeued {} abandoned jobs", n),
+                    Err(e) => tracing::error!("Requeuing abandoned jobs failed: {}", e),
+                }
+                if let Err(e) = purge_finished(&pool).await {
+                    tracing::error!("Job purge failed: {}", e);
+                }
+            }
+        }
+    })
+}
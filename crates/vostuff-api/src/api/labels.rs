@@ -0,0 +1,316 @@
+//! Item labels as a PDF, laid out by one of the organization's label
+//! templates.
+//!
+//! Each label is a page the size of the template, as label printers expect:
+//! the template's fields top to bottom, a QR code linking to the item on the
+//! left, and the item's barcode along the bottom. EAN-13, UPC-A and EAN-8
+//! codes print as bars; other codes print as text. Labels use the standard
+//! Helvetica font, so characters outside Latin-1 print as `?`.
+
+use anyhow::Result;
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str};
+use qrcode::{Color, EcLevel, QrCode};
+use vostuff_core::models::LabelTemplate;
+
+/// Points per millimetre
+const PT_PER_MM: f32 = 72.0 / 25.4;
+/// Blank border around each label and between its parts
+const MARGIN_MM: f32 = 2.0;
+/// Text size range, in points; text shrinks to fit the label
+const MAX_FONT_PT: f32 = 10.0;
+const MIN_FONT_PT: f32 = 4.0;
+/// Line height as a multiple of the text size
+const LINE_SPACING: f32 = 1.2;
+/// Helvetica's average character width as a share of its size, used to cut
+/// lines too long for the label
+const AVG_CHAR_WIDTH: f32 = 0.55;
+/// Tallest barcode bars; shorter on small labels
+const MAX_BARCODE_MM: f32 = 10.0;
+/// Text size of the digits under barcode bars
+const BARCODE_DIGITS_PT: f32 = 5.0;
+
+const FONT: Name<'static> = Name(b"F1");
+
+/// What one label shows
+#[derive(Debug, Clone)]
+pub struct Label {
+    /// The template's fields, in its order; empty values are left out
+    pub lines: Vec<String>,
+    /// Encoded in the QR code, when the template asks for one
+    pub link: String,
+    /// Printed when the template asks for it and the item has one
+    pub barcode: Option<String>,
+}
+
+/// A PDF with one page per label
+pub fn render(template: &LabelTemplate, labels: &[Label]) -> Result<Vec<u8>> {
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let font_id = Ref::new(3);
+    let mut next_id = 4;
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.type1_font(font_id)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    let width = template.width_mm as f32 * PT_PER_MM;
+    let height = template.height_mm as f32 * PT_PER_MM;
+    let mut page_ids = Vec::with_capacity(labels.len());
+    for label in labels {
+        let page_id = Ref::new(next_id);
+        let content_id = Ref::new(next_id + 1);
+        next_id += 2;
+        page_ids.push(page_id);
+
+        let mut page = pdf.page(page_id);
+        page.media_box(Rect::new(0.0, 0.0, width, height))
+            .parent(page_tree_id)
+            .contents(content_id);
+        page.resources().fonts().pair(FONT, font_id);
+        page.finish();
+
+        let content = draw_label(template, label, width, height)?;
+        pdf.stream(content_id, &content);
+    }
+    pdf.pages(page_tree_id)
+        .count(page_ids.len() as i32)
+        .kids(page_ids);
+
+    Ok(pdf.finish())
+}
+
+/// Content stream of one label
+fn draw_label(template: &LabelTemplate, label: &Label, width: f32, height: f32) -> Result<Vec<u8>> {
+    let margin = MARGIN_MM * PT_PER_MM;
+    let mut content = Content::new();
+    let mut lines = label.lines.clone();
+    // The area left for the QR code and text, above any barcode
+    let mut bottom = margin;
+
+    if let Some(code) = label
+        .barcode
+        .as_deref()
+        .filter(|_| template.include_barcode)
+    {
+        match ean_modules(code) {
+            Some(modules) => {
+                let bar_height = (MAX_BARCODE_MM * PT_PER_MM).min((height - 2.0 * margin) / 3.0);
+                let module = (width - 2.0 * margin) / modules.len() as f32;
+                let bars_bottom = margin + BARCODE_DIGITS_PT * LINE_SPACING;
+                for (i, dark) in modules.iter().enumerate() {
+                    if *dark {
+                        content.rect(margin + i as f32 * module, bars_bottom, module, bar_height);
+                    }
+                }
+                content.fill_nonzero();
+                show_text(&mut content, BARCODE_DIGITS_PT, margin, margin, code);
+                bottom = bars_bottom + bar_height + margin;
+            }
+            None => lines.push(code.to_string()),
+        }
+    }
+
+    let mut text_left = margin;
+    if template.include_qr {
+        let qr = QrCode::with_error_correction_level(label.link.as_bytes(), EcLevel::M)?;
+        let side = (height - margin - bottom).min((width - 2.0 * margin) / 2.0);
+        if side > 0.0 {
+            let module = side / qr.width() as f32;
+            let top = height - margin;
+            for (i, color) in qr.to_colors().iter().enumerate() {
+                if *color == Color::Dark {
+                    let (col, row) = (i % qr.width(), i / qr.width());
+                    content.rect(
+                        margin + col as f32 * module,
+                        top - (row + 1) as f32 * module,
+                        module,
+                        module,
+                    );
+                }
+            }
+            content.fill_nonzero();
+            text_left = margin + side + margin;
+        }
+    }
+
+    let text_width = width - margin - text_left;
+    let text_height = height - margin - bottom;
+    if lines.is_empty() || text_width <= 0.0 || text_height <= 0.0 {
+        return Ok(content.finish());
+    }
+    let size = (text_height / (lines.len() as f32 * LINE_SPACING)).clamp(MIN_FONT_PT, MAX_FONT_PT);
+    let max_chars = (text_width / (size * AVG_CHAR_WIDTH)).floor() as usize;
+    let mut baseline = height - margin - size;
+    for line in &lines {
+        if baseline < bottom {
+            break;
+        }
+        show_text(
+            &mut content,
+            size,
+            text_left,
+            baseline,
+            &truncate(line, max_chars),
+        );
+        baseline -= size * LINE_SPACING;
+    }
+    Ok(content.finish())
+}
+
+fn show_text(content: &mut Content, size: f32, x: f32, y: f32, text: &str) {
+    content
+        .begin_text()
+        .set_font(FONT, size)
+        .next_line(x, y)
+        .show(Str(&win_ansi(text)))
+        .end_text();
+}
+
+/// `text` cut to `max_chars`, ending in `...` when cut
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+    format!("{}...", kept.trim_end())
+}
+
+/// Text in the standard fonts' encoding; Latin-1 maps directly
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c as u32 {
+            code @ (0x20..=0x7E | 0xA0..=0xFF) => code as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Left-hand odd parity patterns of the digits; the right-hand patterns are
+/// their complements, and the even parity ones those reversed
+const EAN_L: [u8; 10] = [
+    0b0001101, 0b0011001, 0b0010011, 0b0111101, 0b0100011, 0b0110001, 0b0101111, 0b0111011,
+    0b0110111, 0b0001011,
+];
+
+/// Which of an EAN-13's left digits use even parity, by its first digit;
+/// the most significant of six bits is the second digit
+const EAN_PARITY: [u8; 10] = [
+    0b000000, 0b001011, 0b001101, 0b001110, 0b010011, 0b011001, 0b011100, 0b010101, 0b010110,
+    0b011010,
+];
+
+/// The dark and light modules of an EAN-13, UPC-A or EAN-8 code; None for
+/// any other code
+fn ean_modules(code: &str) -> Option<Vec<bool>> {
+    if !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let mut digits: Vec<u8> = code.bytes().map(|b| b - b'0').collect();
+    // UPC-A is EAN-13 with a leading zero
+    if digits.len() == 12 {
+        digits.insert(0, 0);
+    }
+    let (left, right, parity) = match digits.len() {
+        13 => (&digits[1..7], &digits[7..], EAN_PARITY[digits[0] as usize]),
+        8 => (&digits[..4], &digits[4..], 0),
+        _ => return None,
+    };
+
+    let mut modules = Vec::with_capacity(95);
+    let mut push = |pattern: u8, len: u32| {
+        for bit in (0..len).rev() {
+            modules.push(pattern & (1 << bit) != 0);
+        }
+    };
+    push(0b101, 3);
+    for (i, &digit) in left.iter().enumerate() {
+        let odd = EAN_L[digit as usize];
+        let even = (!odd & 0x7F).reverse_bits() >> 1;
+        let is_even = parity & (1 << (left.len() - 1 - i)) != 0;
+        push(if is_even { even } else { odd }, 7);
+    }
+    push(0b01010, 5);
+    for &digit in right {
+        push(!EAN_L[digit as usize] & 0x7F, 7);
+    }
+    push(0b101, 3);
+    Some(modules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(include_qr: bool, include_barcode: bool) -> LabelTemplate {
+        LabelTemplate {
+            name: "Shelf".to_string(),
+            fields: vec!["name".to_string(), "location".to_string()],
+            width_mm: 62,
+            height_mm: 29,
+            include_qr,
+            include_barcode,
+        }
+    }
+
+    fn modules_string(code: &str) -> String {
+        ean_modules(code)
+            .unwrap()
+            .iter()
+            .map(|dark| if *dark { '1' } else { '0' })
+            .collect()
+    }
+
+    #[test]
+    fn test_ean13_modules() {
+        let modules = modules_string("4006381333931");
+        assert_eq!(modules.len(), 95);
+        // A leading 4 gives the left digits odd, even, odd, odd, even, even parity
+        assert!(modules.starts_with("101"));
+        assert_eq!(&modules[3..10], "0001101");
+        assert_eq!(&modules[10..17], "0100111");
+        assert_eq!(&modules[45..50], "01010");
+        assert_eq!(&modules[50..57], "1000010");
+        assert!(modules.ends_with("101"));
+    }
+
+    #[test]
+    fn test_upc_a_and_ean8_are_encoded_and_others_are_not() {
+        assert_eq!(ean_modules("036000291452").unwrap().len(), 95);
+        assert_eq!(ean_modules("96385074").unwrap().len(), 67);
+        assert!(ean_modules("12345").is_none());
+        assert!(ean_modules("ABC-123").is_none());
+    }
+
+    #[test]
+    fn test_text_is_cut_and_encoded() {
+        assert_eq!(truncate("Kind of Blue", 20), "Kind of Blue");
+        assert_eq!(truncate("Kind of Blue", 10), "Kind of...");
+        assert_eq!(win_ansi("Café €"), b"Caf\xe9 ?");
+    }
+
+    #[test]
+    fn test_render_has_a_page_per_label() {
+        let labels = vec![
+            Label {
+                lines: vec!["Blue Train".to_string(), "Shelf A".to_string()],
+                link: "http://localhost:3001/?item=1".to_string(),
+                barcode: Some("4006381333931".to_string()),
+            },
+            Label {
+                lines: vec!["Dune".to_string()],
+                link: "http://localhost:3001/?item=2".to_string(),
+                barcode: Some("LIB-0042".to_string()),
+            },
+        ];
+        let pdf = render(&template(true, true), &labels).unwrap();
+        assert!(pdf.starts_with(b"%PDF-"));
+        let text = String::from_utf8_lossy(&pdf);
+        assert_eq!(text.matches("/Type /Page\n").count(), 2);
+        assert!(text.contains("/Count 2"));
+
+        let pdf = render(&template(false, false), &labels[..1]).unwrap();
+        assert!(String::from_utf8_lossy(&pdf).contains("/Count 1"));
+    }
+}
@@ -0,0 +1,85 @@
+//! Live change notifications for clients of an organization.
+//!
+//! [`LiveSink`] is an outbox [`EventSink`] that republishes item, collection,
+//! tag and location events on an in-process broadcast channel
+//! ([`LiveEvents`], held in `AppState`). Each open
+//! `GET /organizations/{org_id}/events` stream subscribes to it and passes on
+//! its organization's events, so a client notices when someone else changes
+//! what it is showing.
+//!
+//! Events only reach streams on the instance whose dispatcher delivered them.
+//! A subscriber that falls too far behind is sent [`RESYNC`] and should reload.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+use crate::api::outbox::{EventSink, OutboxEvent};
+
+/// Events buffered for each subscriber before it starts missing them
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Sent instead of the events a slow subscriber missed
+pub const RESYNC: &str = "resync";
+
+/// Event type prefixes passed on to live streams
+const LIVE_EVENT_PREFIXES: [&str; 4] = ["item.", "collection.", "tag.", "location."];
+
+/// Broadcast channel of changes, shared by the outbox dispatcher and every
+/// open event stream
+#[derive(Clone)]
+pub struct LiveEvents {
+    sender: broadcast::Sender<Arc<OutboxEvent>>,
+}
+
+impl LiveEvents {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Receive events published from now on, for every organization
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<OutboxEvent>> {
+        self.sender.subscribe()
+    }
+
+    /// Pass an event to current subscribers; it is dropped when there are none
+    pub fn publish(&self, event: OutboxEvent) {
+        let _ = self.sender.send(Arc::new(event));
+    }
+}
+
+impl Default for LiveEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sink publishing item, collection, tag and location events to live streams
+pub struct LiveSink {
+    events: LiveEvents,
+}
+
+impl LiveSink {
+    pub fn new(events: LiveEvents) -> Self {
+        Self { events }
+    }
+}
+
+#[async_trait]
+impl EventSink for LiveSink {
+    fn name(&self) -> &str {
+        "live"
+    }
+
+    async fn deliver(&self, event: &OutboxEvent) -> anyhow::Result<()> {
+        if LIVE_EVENT_PREFIXES
+            .iter()
+            .any(|prefix| event.event_type.starts_with(prefix))
+        {
+            self.events.publish(event.clone());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,590 @@
+//! Item details from public metadata services.
+//!
+//! A member picks items lacking details and the API queues one
+//! [`ITEMS_LOOKUP`](jobs::ITEMS_LOOKUP) job for them with [`enqueue_lookup`].
+//! [`LookupItemsJob`] asks the first [`MetadataProvider`] covering each item's
+//! kind about its barcode, and stores what it finds for the item's empty
+//! fields as a pending row in `item_suggestions`. Nothing changes an item
+//! until a member applies the suggestion.
+//!
+//! The same providers back the `lookup/isbn` and `lookup/barcode` endpoints,
+//! which prefill a new item from a code as it is entered.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use sqlx::{PgConnection, PgPool};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::api::{
+    jobs::{self, Job, JobHandler},
+    validation::CODE_MAX_LEN,
+};
+
+/// Items worth looking up: with a barcode to look up by, a missing
+/// description or catalog code, and no suggestion already awaiting review
+pub const NEEDS_LOOKUP: &str = "i.barcode IS NOT NULL
+    AND (COALESCE(i.description, '') = '' OR i.catalog_code IS NULL)
+    AND NOT EXISTS (
+        SELECT 1 FROM item_suggestions s WHERE s.item_id = i.id AND s.status = 'pending'
+    )";
+
+/// What a provider knows about an item
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    /// Such as "Kind of Blue"
+    pub title: Option<String>,
+    /// Such as "Miles Davis - Kind of Blue (1959)"
+    pub description: Option<String>,
+    pub catalog_code: Option<String>,
+    /// Name of the shared kind the match is, such as `vinyl`, when known
+    pub kind: Option<String>,
+    /// Values for that kind's soft fields, such as `author` or `disks`
+    pub soft_fields: Map<String, Value>,
+    /// The matching record at the provider
+    pub source_url: Option<String>,
+}
+
+impl Metadata {
+    /// The found values for fields the item lacks, keyed as in an item update
+    pub fn changes(&self, has_description: bool, has_catalog_code: bool) -> Map<String, Value> {
+        let mut changes = Map::new();
+        if let Some(description) = &self.description
+            && !has_description
+        {
+            changes.insert("description".into(), json!(description));
+        }
+        if let Some(code) = &self.catalog_code
+            && !has_catalog_code
+            && code.chars().count() <= CODE_MAX_LEN
+        {
+            changes.insert("catalog_code".into(), json!(code));
+        }
+        changes
+    }
+}
+
+/// A metadata service that can be asked about a barcode
+#[async_trait]
+pub trait MetadataProvider: Send + Sync {
+    /// Short name stored with suggestions, such as `musicbrainz`
+    fn name(&self) -> &str;
+
+    /// Whether the service catalogs items of this kind
+    fn supports(&self, kind: &str) -> bool;
+
+    /// Details for a barcode or ISBN; None when the service has no match
+    async fn lookup(&self, code: &str) -> anyhow::Result<Option<Metadata>>;
+}
+
+/// The public services: MusicBrainz for records, CDs and tapes, OpenLibrary
+/// for books
+pub fn default_providers() -> Vec<Arc<dyn MetadataProvider>> {
+    vec![Arc::new(MusicBrainz::new()), Arc::new(OpenLibrary::new())]
+}
+
+fn client() -> reqwest::Client {
+    // MusicBrainz refuses requests without an identifying user agent
+    reqwest::Client::builder()
+        .user_agent(concat!(
+            "VOStuff/",
+            env!("CARGO_PKG_VERSION"),
+            " ( https://github.com/FrankTaylorLieder/vostuff )"
+        ))
+        .timeout(Duration::from_secs(20))
+        .build()
+        .expect("a default HTTP client builds")
+}
+
+/// Releases on MusicBrainz, found by barcode. Requests are spaced a second
+/// apart, as the service asks.
+pub struct MusicBrainz {
+    client: reqwest::Client,
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl MusicBrainz {
+    const BASE_URL: &str = "https://musicbrainz.org";
+    const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn new() -> Self {
+        Self {
+            client: client(),
+            last_request: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for MusicBrainz {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for MusicBrainz {
+    fn name(&self) -> &str {
+        "musicbrainz"
+    }
+
+    fn supports(&self, kind: &str) -> bool {
+        matches!(kind, "vinyl" | "cd" | "cassette")
+    }
+
+    async fn lookup(&self, code: &str) -> anyhow::Result<Option<Metadata>> {
+        {
+            let mut last = self.last_request.lock().await;
+            if let Some(wait) = last.and_then(|at| Self::REQUEST_INTERVAL.checked_sub(at.elapsed()))
+            {
+                tokio::time::sleep(wait).await;
+            }
+            *last = Some(Instant::now());
+        }
+        let body: Value = self
+            .client
+            .get(format!("{}/ws/2/release/", Self::BASE_URL))
+            .query(&[
+                ("query", format!("barcode:{}", code)),
+                ("fmt", "json".to_string()),
+                ("limit", "1".to_string()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(body["releases"].get(0).and_then(parse_release))
+    }
+}
+
+/// The kind and soft fields of a release, from the format of its media such
+/// as `12" Vinyl` or `CD`
+fn release_format(release: &Value) -> (Option<String>, Map<String, Value>) {
+    let media = release["media"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    let format = media
+        .first()
+        .and_then(|m| m["format"].as_str())
+        .unwrap_or_default();
+    // The soft field counting the media differs by kind
+    let (kind, count_field) = if format.contains("Vinyl") {
+        ("vinyl", "disks")
+    } else if format.contains("CD") {
+        ("cd", "disks")
+    } else if format.contains("Cassette") {
+        ("cassette", "cassettes")
+    } else {
+        return (None, Map::new());
+    };
+    let mut soft_fields = Map::new();
+    soft_fields.insert(count_field.into(), json!(media.len()));
+    if format.starts_with("12\"") {
+        soft_fields.insert("size".into(), json!("12_inch"));
+    }
+    (Some(kind.to_string()), soft_fields)
+}
+
+/// Metadata from a MusicBrainz release search result
+fn parse_release(release: &Value) -> Option<Metadata> {
+    let title = release["title"].as_str()?;
+    let artist = release["artist-credit"]
+        .as_array()
+        .map(|credits| {
+            credits
+                .iter()
+                .map(|c| {
+                    format!(
+                        "{}{}",
+                        c["name"].as_str().unwrap_or_default(),
+                        c["joinphrase"].as_str().unwrap_or_default()
+                    )
+                })
+                .collect::<String>()
+        })
+        .filter(|a| !a.is_empty());
+    let year = release["date"].as_str().and_then(|d| d.get(..4));
+    let catalog_code = release["label-info"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find_map(|info| info["catalog-number"].as_str())
+        .filter(|c| !c.is_empty() && *c != "[none]");
+    let (kind, soft_fields) = release_format(release);
+    Some(Metadata {
+        title: Some(title.to_string()),
+        description: Some(describe(artist.as_deref(), title, year)),
+        catalog_code: catalog_code.map(str::to_string),
+        kind,
+        soft_fields,
+        source_url: release["id"]
+            .as_str()
+            .map(|id| format!("{}/release/{}", MusicBrainz::BASE_URL, id)),
+    })
+}
+
+/// Books on OpenLibrary, found by ISBN
+pub struct OpenLibrary {
+    client: reqwest::Client,
+}
+
+impl OpenLibrary {
+    const BASE_URL: &str = "https://openlibrary.org";
+
+    pub fn new() -> Self {
+        Self { client: client() }
+    }
+}
+
+impl Default for OpenLibrary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for OpenLibrary {
+    fn name(&self) -> &str {
+        "openlibrary"
+    }
+
+    fn supports(&self, kind: &str) -> bool {
+        kind == "book"
+    }
+
+    async fn lookup(&self, code: &str) -> anyhow::Result<Option<Metadata>> {
+        let isbn: String = code.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+        let body: Value = self
+            .client
+            .get(format!("{}/search.json", Self::BASE_URL))
+            .query(&[
+                ("isbn", isbn.as_str()),
+                (
+                    "fields",
+                    "key,title,author_name,first_publish_year,publisher",
+                ),
+                ("limit", "1"),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(body["docs"].get(0).and_then(parse_book).map(|mut found| {
+            found.soft_fields.insert("isbn".into(), json!(isbn));
+            found
+        }))
+    }
+}
+
+/// Metadata from an OpenLibrary search result
+fn parse_book(doc: &Value) -> Option<Metadata> {
+    let title = doc["title"].as_str()?;
+    let authors = doc["author_name"]
+        .as_array()
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .filter(|a| !a.is_empty());
+    let year = doc["first_publish_year"].as_i64();
+    let mut soft_fields = Map::new();
+    if let Some(authors) = &authors {
+        soft_fields.insert("author".into(), json!(authors));
+    }
+    if let Some(year) = year {
+        soft_fields.insert("year".into(), json!(year));
+    }
+    if let Some(publisher) = doc["publisher"].get(0).and_then(Value::as_str) {
+        soft_fields.insert("publisher".into(), json!(publisher));
+    }
+    Some(Metadata {
+        title: Some(title.to_string()),
+        description: Some(describe(
+            authors.as_deref(),
+            title,
+            year.map(|y| y.to_string()).as_deref(),
+        )),
+        catalog_code: None,
+        kind: Some("book".into()),
+        soft_fields,
+        source_url: doc["key"]
+            .as_str()
+            .map(|key| format!("{}{}", OpenLibrary::BASE_URL, key)),
+    })
+}
+
+/// An ISBN-10 or ISBN-13 without its hyphens and spaces, or None when the
+/// check digit does not match
+pub fn normalize_isbn(isbn: &str) -> Option<String> {
+    let isbn: String = isbn
+        .chars()
+        .filter(|c| !matches!(c, '-' | ' '))
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let valid = match isbn.len() {
+        // Digits weighted 10 down to 1, the last may be X for 10
+        10 => isbn
+            .chars()
+            .enumerate()
+            .try_fold(0, |sum, (i, c)| {
+                let value = match c {
+                    'X' if i == 9 => 10,
+                    c => c.to_digit(10)?,
+                };
+                Some(sum + value * (10 - i as u32))
+            })
+            .is_some_and(|sum| sum % 11 == 0),
+        // Digits weighted 1, 3, 1, 3...
+        13 => isbn
+            .chars()
+            .enumerate()
+            .try_fold(0, |sum, (i, c)| {
+                Some(sum + c.to_digit(10)? * if i % 2 == 0 { 1 } else { 3 })
+            })
+            .is_some_and(|sum| sum % 10 == 0),
+        _ => false,
+    };
+    valid.then_some(isbn)
+}
+
+/// Whether a barcode is a book's: an EAN-13 in the 978 or 979 "Bookland" range
+pub fn is_book_barcode(code: &str) -> bool {
+    code.len() == 13 && (code.starts_with("978") || code.starts_with("979"))
+}
+
+/// "Creator - Title (Year)", leaving out what is unknown
+fn describe(creator: Option<&str>, title: &str, year: Option<&str>) -> String {
+    let mut description = match creator {
+        Some(creator) => format!("{} - {}", creator, title),
+        None => title.to_string(),
+    };
+    if let Some(year) = year {
+        description.push_str(&format!(" ({})", year));
+    }
+    description
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LookupPayload {
+    item_ids: Vec<Uuid>,
+}
+
+/// Queue a lookup of the items; call inside the transaction that chose them
+pub async fn enqueue_lookup(
+    conn: &mut PgConnection,
+    organization_id: Uuid,
+    item_ids: Vec<Uuid>,
+) -> Result<Uuid, sqlx::Error> {
+    jobs::enqueue(
+        conn,
+        jobs::ITEMS_LOOKUP,
+        Some(organization_id),
+        serde_json::to_value(LookupPayload { item_ids }).expect("a payload serializes"),
+    )
+    .await
+}
+
+/// Looks up the items queued by [`enqueue_lookup`]. Items that were completed
+/// or given a suggestion since they were queued are passed over, so a retried
+/// job only asks about the rest.
+pub struct LookupItemsJob {
+    pool: PgPool,
+    providers: Vec<Arc<dyn MetadataProvider>>,
+}
+
+impl LookupItemsJob {
+    pub fn new(pool: PgPool, providers: Vec<Arc<dyn MetadataProvider>>) -> Self {
+        Self { pool, providers }
+    }
+}
+
+#[async_trait]
+impl JobHandler for LookupItemsJob {
+    fn kind(&self) -> &str {
+        jobs::ITEMS_LOOKUP
+    }
+
+    async fn run(&self, job: &Job) -> anyhow::Result<()> {
+        let org_id = job
+            .organization_id
+            .context("a lookup job needs an organization")?;
+        let payload: LookupPayload = serde_json::from_value(job.payload.clone())?;
+
+        let items: Vec<(Uuid, String, String, bool, bool)> = sqlx::query_as(&format!(
+            "SELECT i.id, k.name, i.barcode,
+                    COALESCE(i.description, '') <> '', i.catalog_code IS NOT NULL
+             FROM items i JOIN kinds k ON k.id = i.kind_id
+             WHERE i.organization_id = $1 AND i.id = ANY($2) AND {}
+             ORDER BY i.name",
+            NEEDS_LOOKUP
+        ))
+        .bind(org_id)
+        .bind(&payload.item_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for (item_id, kind, barcode, has_description, has_catalog_code) in items {
+            let Some(provider) = self.providers.iter().find(|p| p.supports(&kind)) else {
+                continue;
+            };
+            let found = provider
+                .lookup(barcode.trim())
+                .await
+                .with_context(|| format!("{} lookup of {}", provider.name(), barcode))?;
+            let Some(found) = found else {
+                continue;
+            };
+            let changes = found.changes(has_description, has_catalog_code);
+            if changes.is_empty() {
+                continue;
+            }
+            sqlx::query(
+                "INSERT INTO item_suggestions
+                   (organization_id, item_id, provider, source_url, changes)
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT (item_id) WHERE status = 'pending' DO NOTHING",
+            )
+            .bind(org_id)
+            .bind(item_id)
+            .bind(provider.name())
+            .bind(&found.source_url)
+            .bind(Value::Object(changes))
+            .execute(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_is_described_with_artist_year_and_catalog_number() {
+        let release = json!({
+            "id": "b84ee12a-09ef-421b-82de-0441a926375b",
+            "title": "Kind of Blue",
+            "date": "1959-08-17",
+            "artist-credit": [{"name": "Miles Davis", "joinphrase": ""}],
+            "label-info": [{"catalog-number": "CL 1355", "label": {"name": "Columbia"}}],
+            "media": [{"format": "12\" Vinyl", "track-count": 5}]
+        });
+        assert_eq!(
+            parse_release(&release),
+            Some(Metadata {
+                title: Some("Kind of Blue".into()),
+                description: Some("Miles Davis - Kind of Blue (1959)".into()),
+                catalog_code: Some("CL 1355".into()),
+                kind: Some("vinyl".into()),
+                soft_fields: json!({"disks": 1, "size": "12_inch"})
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+                source_url: Some(
+                    "https://musicbrainz.org/release/b84ee12a-09ef-421b-82de-0441a926375b".into()
+                ),
+            })
+        );
+
+        let bare = json!({"title": "Untitled", "label-info": [{"catalog-number": "[none]"}]});
+        let found = parse_release(&bare).unwrap();
+        assert_eq!(found.description.as_deref(), Some("Untitled"));
+        assert_eq!(found.catalog_code, None);
+        assert_eq!(found.kind, None);
+    }
+
+    #[test]
+    fn test_release_kind_comes_from_its_media() {
+        let release = json!({
+            "title": "Tapestry",
+            "media": [{"format": "Cassette"}, {"format": "Cassette"}]
+        });
+        let found = parse_release(&release).unwrap();
+        assert_eq!(found.kind.as_deref(), Some("cassette"));
+        assert_eq!(Value::Object(found.soft_fields), json!({"cassettes": 2}));
+
+        let release = json!({"title": "Tapestry", "media": [{"format": "Enhanced CD"}]});
+        assert_eq!(parse_release(&release).unwrap().kind.as_deref(), Some("cd"));
+    }
+
+    #[test]
+    fn test_book_is_described_with_authors_and_year() {
+        let doc = json!({
+            "key": "/works/OL45804W",
+            "title": "Good Omens",
+            "author_name": ["Terry Pratchett", "Neil Gaiman"],
+            "first_publish_year": 1990,
+            "publisher": ["Gollancz", "Workman"]
+        });
+        let found = parse_book(&doc).unwrap();
+        assert_eq!(found.title.as_deref(), Some("Good Omens"));
+        assert_eq!(found.kind.as_deref(), Some("book"));
+        assert_eq!(
+            Value::Object(found.soft_fields.clone()),
+            json!({"author": "Terry Pratchett, Neil Gaiman", "year": 1990, "publisher": "Gollancz"})
+        );
+        assert_eq!(
+            found.description.as_deref(),
+            Some("Terry Pratchett, Neil Gaiman - Good Omens (1990)")
+        );
+        assert_eq!(
+            found.source_url.as_deref(),
+            Some("https://openlibrary.org/works/OL45804W")
+        );
+        assert_eq!(parse_book(&json!({"key": "/works/OL1W"})), None);
+    }
+
+    #[test]
+    fn test_changes_only_fill_empty_fields() {
+        let found = Metadata {
+            description: Some("Miles Davis - Kind of Blue (1959)".into()),
+            catalog_code: Some("CL 1355".into()),
+            ..Default::default()
+        };
+        assert_eq!(found.changes(false, false).len(), 2);
+        assert_eq!(
+            Value::Object(found.changes(true, false)),
+            json!({"catalog_code": "CL 1355"})
+        );
+        assert!(found.changes(true, true).is_empty());
+
+        let long_code = Metadata {
+            catalog_code: Some("X".repeat(CODE_MAX_LEN + 1)),
+            ..Default::default()
+        };
+        assert!(long_code.changes(false, false).is_empty());
+    }
+
+    #[test]
+    fn test_isbn_check_digits() {
+        assert_eq!(
+            normalize_isbn("978-0-441-17271-9").as_deref(),
+            Some("9780441172719")
+        );
+        assert_eq!(
+            normalize_isbn("0 441 17271 7").as_deref(),
+            Some("0441172717")
+        );
+        assert_eq!(normalize_isbn("080442957x").as_deref(), Some("080442957X"));
+        assert_eq!(normalize_isbn("9780441172710"), None);
+        assert_eq!(normalize_isbn("0441172718"), None);
+        assert_eq!(normalize_isbn("X441172717"), None);
+        assert_eq!(normalize_isbn("12345"), None);
+
+        assert!(is_book_barcode("9780441172719"));
+        assert!(!is_book_barcode("5099706424420"));
+    }
+}
@@ -0,0 +1,227 @@
+//! Outgoing email.
+//!
+//! Mail is never sent by handlers directly. Either a handler enqueues an outbox
+//! event in the transaction making the change and a sink such as
+//! [`InviteMailSink`] turns it into a message, or it queues the message itself
+//! as a background job with [`enqueue_email`], run by [`SendEmailJob`]. Both
+//! go to the configured [`Mailer`]. A mail relay outage therefore delays
+//! messages instead of losing them, and nothing is sent for a change that
+//! rolls back.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use uuid::Uuid;
+use vostuff_core::config::Config;
+
+use crate::api::{
+    jobs::{self, Job, JobHandler},
+    outbox::{self, EventSink, OutboxEvent},
+};
+use crate::auth::TokenManager;
+
+/// A plain-text email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Sends email
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: &Email) -> anyhow::Result<()>;
+}
+
+/// Writes each message to the log instead of sending it; the default when no
+/// relay is configured, for development
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, email: &Email) -> anyhow::Result<()> {
+        tracing::info!(to = %email.to, subject = %email.subject, "email\n{}", email.body);
+        Ok(())
+    }
+}
+
+/// Posts each message as JSON (`to`, `subject`, `text`) to a mail relay's HTTP
+/// endpoint, with an optional bearer token
+pub struct HttpMailer {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+impl HttpMailer {
+    pub fn new(url: String, token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            token,
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for HttpMailer {
+    async fn send(&self, email: &Email) -> anyhow::Result<()> {
+        let mut request = self.client.post(&self.url).json(&serde_json::json!({
+            "to": email.to,
+            "subject": email.subject,
+            "text": email.body,
+        }));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Where mail goes, from the `[mail]` settings
+#[derive(Debug, Clone)]
+pub struct MailConfig {
+    /// Messages are only logged when unset
+    pub relay_url: Option<String>,
+    /// Sent as a bearer token to the relay
+    pub relay_token: Option<String>,
+    /// The web page that accepts invites; the token is appended as `?token=`
+    pub invite_url: String,
+}
+
+impl MailConfig {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            relay_url: config.mail.relay_url.clone(),
+            relay_token: config.mail.relay_token.clone(),
+            invite_url: config.invite_url.clone(),
+        }
+    }
+
+    pub fn build_mailer(&self) -> Arc<dyn Mailer> {
+        match &self.relay_url {
+            Some(url) => Arc::new(HttpMailer::new(url.clone(), self.relay_token.clone())),
+            None => Arc::new(LogMailer),
+        }
+    }
+}
+
+/// Payload of an `invite.created` event
+#[derive(Debug, Deserialize)]
+struct InvitePayload {
+    id: Uuid,
+    organization_id: Uuid,
+    organization_name: String,
+    email: String,
+    invited_by_name: Option<String>,
+    expires_at: DateTime<Utc>,
+}
+
+/// Emails the link for each new invite.
+///
+/// The link's token is signed here, at delivery, so it is never stored in the
+/// outbox or seen by other sinks.
+pub struct InviteMailSink {
+    mailer: Arc<dyn Mailer>,
+    tokens: TokenManager,
+    invite_url: String,
+}
+
+impl InviteMailSink {
+    /// `jwt_secret` must be the API's, so `POST /auth/accept-invite` accepts the tokens
+    pub fn new(mailer: Arc<dyn Mailer>, jwt_secret: &str, invite_url: String) -> Self {
+        Self {
+            mailer,
+            tokens: TokenManager::new(jwt_secret),
+            invite_url,
+        }
+    }
+}
+
+#[async_trait]
+impl EventSink for InviteMailSink {
+    fn name(&self) -> &str {
+        "invite-mail"
+    }
+
+    async fn deliver(&self, event: &OutboxEvent) -> anyhow::Result<()> {
+        if event.event_type != outbox::INVITE_CREATED {
+            return Ok(());
+        }
+        let invite: InvitePayload = serde_json::from_value(event.payload.clone())?;
+        let token = self.tokens.generate_invite_token(
+            invite.id,
+            invite.organization_id,
+            invite.email.clone(),
+            invite.expires_at,
+        )?;
+
+        let opening = match invite.invited_by_name {
+            Some(name) => format!(
+                "{} has invited you to join {}",
+                name, invite.organization_name
+            ),
+            None => format!("You have been invited to join {}", invite.organization_name),
+        };
+        let body = format!(
+            "{} on VOStuff.\n\n\
+             Accept the invitation here:\n{}?token={}\n\n\
+             The link works once and expires on {}.\n",
+            opening,
+            self.invite_url,
+            token,
+            invite.expires_at.format("%Y-%m-%d %H:%M UTC"),
+        );
+        self.mailer
+            .send(&Email {
+                to: invite.email,
+                subject: format!("Join {} on VOStuff", invite.organization_name),
+                body,
+            })
+            .await
+    }
+}
+
+/// Queue an email to send in the background; call inside the transaction
+/// making the change it reports
+pub async fn enqueue_email(
+    conn: &mut PgConnection,
+    organization_id: Option<Uuid>,
+    email: &Email,
+) -> Result<Uuid, sqlx::Error> {
+    jobs::enqueue(
+        conn,
+        jobs::EMAIL_SEND,
+        organization_id,
+        serde_json::to_value(email).expect("an email serializes"),
+    )
+    .await
+}
+
+/// Sends the email queued by [`enqueue_email`]
+pub struct SendEmailJob {
+    mailer: Arc<dyn Mailer>,
+}
+
+impl SendEmailJob {
+    pub fn new(mailer: Arc<dyn Mailer>) -> Self {
+        Self { mailer }
+    }
+}
+
+#[async_trait]
+impl JobHandler for SendEmailJob {
+    fn kind(&self) -> &str {
+        jobs::EMAIL_SEND
+    }
+
+    async fn run(&self, job: &Job) -> anyhow::Result<()> {
+        let email: Email = serde_json::from_value(job.payload.clone())?;
+        self.mailer.send(&email).await
+    }
+}
@@ -0,0 +1,98 @@
+//! Prometheus metrics.
+//!
+//! When [`AppState::metrics`] is set, every API request is counted and timed
+//! by [`track_requests`], labelled with its route pattern rather than the raw
+//! path so that ids do not multiply series. [`router`] serves the text
+//! exposition at `/metrics`, refreshing the database pool gauges on each
+//! scrape.
+
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use axum::{
+    Router,
+    extract::{MatchedPath, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+
+use crate::api::state::AppState;
+
+/// Requests handled, labelled by method, route and status
+pub const HTTP_REQUESTS_TOTAL: &str = "http_requests_total";
+/// Request latency in seconds, labelled by method, route and status
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "http_request_duration_seconds";
+/// Open connections in the database pool
+pub const DB_POOL_CONNECTIONS: &str = "db_pool_connections";
+/// Idle connections in the database pool
+pub const DB_POOL_IDLE_CONNECTIONS: &str = "db_pool_idle_connections";
+
+/// Latency buckets in seconds, from a quick lookup up to a large export
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Install the process-wide Prometheus recorder, or return the one already
+/// installed; the `metrics` macros can only report to a single recorder
+pub fn install_recorder() -> PrometheusHandle {
+    static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+    HANDLE
+        .get_or_init(|| {
+            PrometheusBuilder::new()
+                .set_buckets_for_metric(
+                    Matcher::Full(HTTP_REQUEST_DURATION_SECONDS.to_string()),
+                    LATENCY_BUCKETS,
+                )
+                .expect("latency buckets are not empty")
+                .install_recorder()
+                .expect("no other metrics recorder is installed")
+        })
+        .clone()
+}
+
+/// Count and time each request. Applied with `route_layer`, so only requests
+/// that match a route are recorded.
+pub async fn track_requests(matched_path: MatchedPath, request: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let method = request.method().to_string();
+    let route = matched_path.as_str().to_string();
+
+    let response = next.run(request).await;
+
+    let labels = [
+        ("method", method),
+        ("route", route),
+        ("status", response.status().as_u16().to_string()),
+    ];
+    metrics::counter!(HTTP_REQUESTS_TOTAL, &labels).increment(1);
+    metrics::histogram!(HTTP_REQUEST_DURATION_SECONDS, &labels)
+        .record(start.elapsed().as_secs_f64());
+    response
+}
+
+/// The `/metrics` route, or no routes when metrics are disabled. Served
+/// outside `/api` and without authentication, as Prometheus expects; keep it
+/// off the public network.
+pub fn router(state: AppState) -> Router {
+    if state.metrics.is_none() {
+        return Router::new();
+    }
+    Router::new()
+        .route("/metrics", get(render_metrics))
+        .with_state(state)
+}
+
+async fn render_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    metrics::gauge!(DB_POOL_CONNECTIONS).set(state.pool.size() as f64);
+    metrics::gauge!(DB_POOL_IDLE_CONNECTIONS).set(state.pool.num_idle() as f64);
+
+    let body = state
+        .metrics
+        .as_ref()
+        .map(PrometheusHandle::render)
+        .unwrap_or_default();
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
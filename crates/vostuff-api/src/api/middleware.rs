@@ -1,32 +1,50 @@
 use std::collections::HashMap;
 
 use axum::{
-    Json,
     extract::{Path, Request, State},
-    http::{HeaderMap, StatusCode, header},
+    http::{HeaderMap, header},
     middleware::Next,
     response::Response,
 };
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::api::error::ApiError;
 use crate::{
-    api::{models::ErrorResponse, state::AppState},
-    auth::{AuthContext, TokenManager},
+    api::{
+        models::{ErrorCode, Permission, UserRole},
+        state::AppState,
+    },
+    auth::{API_KEY_HEADER, ApiKeys, AuthContext, TokenManager},
+    permissions::Permissions,
 };
 
+/// How stale `api_keys.last_used_at` may get before a request updates it, so
+/// busy scripts do not write on every request
+const API_KEY_LAST_USED_RESOLUTION_SECONDS: i64 = 60;
+
 /// Authentication middleware that extracts JWT token from Authorization header
-/// and validates it, adding AuthContext to request extensions
+/// (or an API key from X-Api-Key) and validates it, adding AuthContext to
+/// request extensions
 pub async fn auth_middleware(
     State(state): State<AppState>,
     mut request: Request,
     next: Next,
-) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Response, ApiError> {
     let headers = request.headers();
 
     // Extract token from Authorization header
     let token = match extract_token_from_headers(headers) {
         Some(token) => token,
         None => {
+            if let Some(key) = headers.get(API_KEY_HEADER) {
+                let key = key.to_str().map_err(|_| invalid_api_key())?.to_string();
+                let auth_context = authenticate_api_key(&state, &key).await?;
+                request.extensions_mut().insert(auth_context);
+                return Ok(next.run(request).await);
+            }
+
             // No token provided - set unauthenticated context
             request
                 .extensions_mut()
@@ -39,24 +57,123 @@ pub async fn auth_middleware(
     let token_manager = TokenManager::new(&state.jwt_secret);
     match token_manager.validate_token(&token) {
         Ok(claims) => {
+            // Tokens outlive deactivation and logout, so check both on every request
+            let status: Option<(bool, bool)> = sqlx::query_as(
+                "SELECT u.is_active,
+                        EXISTS(SELECT 1 FROM revoked_access_tokens r WHERE r.jti = $2)
+                 FROM users u WHERE u.id = $1",
+            )
+            .bind(claims.sub)
+            .bind(claims.jti)
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(ApiError::internal)?;
+            match status {
+                Some((false, _)) => {
+                    return Err(ApiError::unauthorized(
+                        ErrorCode::AuthUserDeactivated,
+                        "This user has been deactivated",
+                    ));
+                }
+                Some((true, true)) => {
+                    return Err(ApiError::unauthorized(
+                        ErrorCode::AuthInvalidToken,
+                        "This token has been revoked; please log in again",
+                    ));
+                }
+                _ => {}
+            }
+
             // Token valid - set authenticated context
             let auth_context = AuthContext::from_claims(claims);
             request.extensions_mut().insert(auth_context);
             Ok(next.run(request).await)
         }
-        Err(_) => {
-            // Token invalid - return unauthorized error
-            Err((
-                StatusCode::UNAUTHORIZED,
-                Json(ErrorResponse {
-                    error: "unauthorized".to_string(),
-                    message: "Invalid or expired token".to_string(),
-                }),
+        Err(e) => {
+            // Token invalid, expired or not an access token - return unauthorized error
+            tracing::debug!("Rejected bearer token: {}", e);
+            Err(ApiError::unauthorized(
+                ErrorCode::AuthInvalidToken,
+                "Invalid or expired token",
             ))
         }
     }
 }
 
+/// Look up an API key and build the context it acts in: its owner, in its
+/// organization, with the key's roles that the owner still holds there, and
+/// the owner's permissions there that those roles grant
+async fn authenticate_api_key(state: &AppState, key: &str) -> Result<AuthContext, ApiError> {
+    type ApiKeyRow = (
+        Uuid,
+        Uuid,
+        String,
+        bool,
+        Uuid,
+        Vec<UserRole>,
+        Vec<Permission>,
+        Option<DateTime<Utc>>,
+        Option<DateTime<Utc>>,
+    );
+    let row: Option<ApiKeyRow> = sqlx::query_as(
+        "SELECT k.id, k.user_id, u.identity, u.is_active, k.organization_id,
+                ARRAY(SELECT unnest(k.roles) INTERSECT SELECT unnest(uo.roles)),
+                uo.permissions, k.expires_at, k.last_used_at
+         FROM api_keys k
+         JOIN users u ON u.id = k.user_id
+         JOIN user_organizations uo
+           ON uo.user_id = k.user_id AND uo.organization_id = k.organization_id
+         WHERE k.key_hash = $1",
+    )
+    .bind(ApiKeys::hash(key))
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let Some((
+        key_id,
+        user_id,
+        identity,
+        is_active,
+        org_id,
+        roles,
+        member_permissions,
+        expires_at,
+        last_used_at,
+    )) = row
+    else {
+        return Err(invalid_api_key());
+    };
+    let now = Utc::now();
+    if expires_at.is_some_and(|at| at <= now) || roles.is_empty() {
+        return Err(invalid_api_key());
+    }
+    if !is_active {
+        return Err(ApiError::unauthorized(
+            ErrorCode::AuthUserDeactivated,
+            "This user has been deactivated",
+        ));
+    }
+
+    if last_used_at
+        .is_none_or(|at| now - at > Duration::seconds(API_KEY_LAST_USED_RESOLUTION_SECONDS))
+    {
+        sqlx::query("UPDATE api_keys SET last_used_at = NOW() WHERE id = $1")
+            .bind(key_id)
+            .execute(&state.pool)
+            .await?;
+    }
+
+    let permissions = Permissions::for_roles(&roles).intersection(&member_permissions.into());
+    Ok(AuthContext::from_api_key(
+        key_id,
+        user_id,
+        identity,
+        org_id,
+        roles,
+        permissions,
+    ))
+}
+
 /// Extract JWT token from Authorization header
 /// Supports both "Bearer <token>" and just "<token>" formats
 fn extract_token_from_headers(headers: &HeaderMap) -> Option<String> {
@@ -72,10 +189,7 @@ fn extract_token_from_headers(headers: &HeaderMap) -> Option<String> {
 }
 
 /// Middleware that requires authentication - returns 401 if not authenticated
-pub async fn require_auth_middleware(
-    request: Request,
-    next: Next,
-) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+pub async fn require_auth_middleware(request: Request, next: Next) -> Result<Response, ApiError> {
     // Check if user is authenticated
     let auth_context = request
         .extensions()
@@ -84,53 +198,138 @@ pub async fn require_auth_middleware(
         .unwrap_or_else(AuthContext::unauthenticated);
 
     if !auth_context.is_authenticated() {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(ErrorResponse {
-                error: "unauthorized".to_string(),
-                message: "Authentication required".to_string(),
-            }),
+        return Err(ApiError::unauthorized(
+            ErrorCode::AuthRequired,
+            "Authentication required",
         ));
     }
 
     Ok(next.run(request).await)
 }
 
-/// Helpers for building error responses
-fn unauthorized() -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::UNAUTHORIZED,
-        Json(ErrorResponse {
-            error: "unauthorized".to_string(),
-            message: "Authentication required".to_string(),
-        }),
-    )
+fn invalid_api_key() -> ApiError {
+    ApiError::unauthorized(ErrorCode::AuthInvalidApiKey, "Invalid or expired API key")
 }
 
-fn forbidden(message: &str) -> (StatusCode, Json<ErrorResponse>) {
-    (
-        StatusCode::FORBIDDEN,
-        Json(ErrorResponse {
-            error: "forbidden".to_string(),
-            message: message.to_string(),
-        }),
-    )
+fn unauthorized() -> ApiError {
+    ApiError::unauthorized(ErrorCode::AuthRequired, "Authentication required")
 }
 
 /// Middleware for org-scoped routes (`/organizations/:org_id/*`). Requires the caller to
 /// be authenticated and to have selected the same org as the one in the path. Returns 401
-/// if unauthenticated, 403 if authenticated but not a member of the path org.
+/// if unauthenticated, 403 if authenticated but not a member of the path org or without
+/// `items:read` there.
 pub async fn org_access_middleware(
     Path(params): Path<HashMap<String, String>>,
     request: Request,
     next: Next,
-) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Response, ApiError> {
     let auth_context = request
         .extensions()
         .get::<AuthContext>()
         .cloned()
         .unwrap_or_else(AuthContext::unauthenticated);
 
+    check_org_access(&auth_context, &params)?;
+
+    if !auth_context.can(Permission::ItemsRead) {
+        return Err(ApiError::auth_forbidden(
+            "Your permissions in this organization do not allow reading its data",
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Middleware for org-scoped routes that change data. As `org_access_middleware`, and
+/// also returns 403 if the caller's permissions in the org only allow reading. Handlers
+/// check the specific permission they need.
+pub async fn org_write_middleware(
+    Path(params): Path<HashMap<String, String>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let auth_context = request
+        .extensions()
+        .get::<AuthContext>()
+        .cloned()
+        .unwrap_or_else(AuthContext::unauthenticated);
+
+    check_org_access(&auth_context, &params)?;
+
+    if !auth_context.can_write() {
+        return Err(ApiError::auth_forbidden(
+            "Your permissions in this organization do not allow changes",
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Middleware for org-scoped routes that change data, run after `org_write_middleware`.
+/// Returns 423 while the organization is read-only.
+pub async fn org_read_only_middleware(
+    State(state): State<AppState>,
+    Path(params): Path<HashMap<String, String>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let org_id = params
+        .get("org_id")
+        .and_then(|s| Uuid::parse_str(s).ok())
+        .ok_or_else(|| ApiError::auth_forbidden("Invalid organization id"))?;
+
+    if let Some(reason) = read_only(&state.pool, org_id).await? {
+        return Err(locked(reason));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Why an organization refuses changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReadOnly {
+    /// A system administrator made it read-only
+    System,
+    /// Its own `read_only` setting
+    Setting,
+}
+
+/// Whether an organization refuses changes, and why; None when it accepts them
+pub(crate) async fn read_only(
+    pool: &PgPool,
+    org_id: Uuid,
+) -> Result<Option<ReadOnly>, sqlx::Error> {
+    let row: Option<(bool, bool)> = sqlx::query_as(
+        "SELECT read_only, COALESCE((settings->>'read_only')::boolean, FALSE)
+         FROM organizations WHERE id = $1",
+    )
+    .bind(org_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(match row {
+        Some((true, _)) => Some(ReadOnly::System),
+        Some((false, true)) => Some(ReadOnly::Setting),
+        _ => None,
+    })
+}
+
+/// The 423 answer to a change in a read-only organization
+pub(crate) fn locked(reason: ReadOnly) -> ApiError {
+    let message = match reason {
+        ReadOnly::System => "This organization has been made read-only by a system administrator",
+        ReadOnly::Setting => {
+            "This organization is read-only; an administrator can turn read-only mode off in its settings"
+        }
+    };
+    ApiError::locked(ErrorCode::OrganizationReadOnly, message)
+}
+
+fn check_org_access(
+    auth_context: &AuthContext,
+    params: &HashMap<String, String>,
+) -> Result<(), ApiError> {
     if !auth_context.is_authenticated() {
         return Err(unauthorized());
     }
@@ -138,21 +337,20 @@ pub async fn org_access_middleware(
     let org_id = params
         .get("org_id")
         .and_then(|s| Uuid::parse_str(s).ok())
-        .ok_or_else(|| forbidden("Invalid organization id"))?;
+        .ok_or_else(|| ApiError::auth_forbidden("Invalid organization id"))?;
 
     if !auth_context.has_org_access(org_id) {
-        return Err(forbidden("You do not have access to this organization"));
+        return Err(ApiError::auth_forbidden(
+            "You do not have access to this organization",
+        ));
     }
 
-    Ok(next.run(request).await)
+    Ok(())
 }
 
 /// Middleware for system administration routes (`/admin/*`). Requires the caller to be a
 /// system super-admin: authenticated with the SYSTEM org selected and holding ADMIN there.
-pub async fn system_admin_middleware(
-    request: Request,
-    next: Next,
-) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+pub async fn system_admin_middleware(request: Request, next: Next) -> Result<Response, ApiError> {
     let auth_context = request
         .extensions()
         .get::<AuthContext>()
@@ -164,7 +362,9 @@ pub async fn system_admin_middleware(
     }
 
     if !auth_context.is_system_admin() {
-        return Err(forbidden("System administrator access required"));
+        return Err(ApiError::auth_forbidden(
+            "System administrator access required",
+        ));
     }
 
     Ok(next.run(request).await)
@@ -1,6 +1,20 @@
+pub mod audit;
+pub mod docs;
+pub mod error;
 pub mod handlers;
+pub mod imports;
+pub mod jobs;
+pub mod labels;
+pub mod live;
+pub mod lookup;
+pub mod mail;
+pub mod metrics;
 pub mod middleware;
+pub mod outbox;
+pub mod retention;
 pub mod state;
+pub mod validation;
+pub mod warnings;
 
 // Re-export models from core
 pub use vostuff_core::models;
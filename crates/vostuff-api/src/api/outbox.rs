@@ -0,0 +1,218 @@
+//! Transactional outbox for domain events.
+//!
+//! Handlers call [`enqueue`] inside the transaction that makes a change, so the
+//! event is stored if and only if the change commits. A background dispatcher
+//! ([`spawn_dispatcher`]) then delivers pending events to every registered
+//! [`EventSink`] and marks them dispatched. Delivery is at-least-once: an event
+//! is retried with backoff until all sinks accept it, and a crash after
+//! delivery but before marking it dispatched delivers it again. Sinks should
+//! use the event id to ignore duplicates.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{PgConnection, PgPool};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Events claimed per dispatcher pass
+const BATCH_SIZE: i64 = 100;
+
+/// Longest wait between retries of a failing event
+const MAX_BACKOFF_SECS: i32 = 3600;
+
+/// Dispatched events are kept this long for troubleshooting, then deleted
+const DISPATCHED_RETENTION_DAYS: i32 = 7;
+
+/// How often the dispatcher purges old dispatched events
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+pub const ITEM_CREATED: &str = "item.created";
+pub const ITEM_UPDATED: &str = "item.updated";
+pub const ITEM_DELETED: &str = "item.deleted";
+pub const COLLECTION_CREATED: &str = "collection.created";
+pub const COLLECTION_UPDATED: &str = "collection.updated";
+pub const COLLECTION_DELETED: &str = "collection.deleted";
+pub const COLLECTION_ITEM_ADDED: &str = "collection.item_added";
+pub const COLLECTION_ITEM_REMOVED: &str = "collection.item_removed";
+pub const LOCATION_UPDATED: &str = "location.updated";
+pub const LOCATION_DELETED: &str = "location.deleted";
+pub const TAG_CREATED: &str = "tag.created";
+pub const TAG_DELETED: &str = "tag.deleted";
+pub const INVITE_CREATED: &str = "invite.created";
+
+/// An event waiting in (or delivered from) the outbox
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct OutboxEvent {
+    pub id: i64,
+    pub organization_id: Uuid,
+    pub event_type: String,
+    pub payload: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A destination for outbox events, such as webhooks or a live event stream
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    /// Short name used in logs and stored delivery errors
+    fn name(&self) -> &str;
+
+    async fn deliver(&self, event: &OutboxEvent) -> anyhow::Result<()>;
+}
+
+/// Sink that writes each event to the log; the default when nothing else is configured
+pub struct LogSink;
+
+#[async_trait]
+impl EventSink for LogSink {
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    async fn deliver(&self, event: &OutboxEvent) -> anyhow::Result<()> {
+        tracing::info!(
+            event_id = event.id,
+            organization_id = %event.organization_id,
+            event_type = %event.event_type,
+            "outbox event"
+        );
+        Ok(())
+    }
+}
+
+/// Store an event; call inside the transaction making the change it describes
+pub async fn enqueue(
+    conn: &mut PgConnection,
+    organization_id: Uuid,
+    event_type: &str,
+    payload: Value,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO outbox_events (organization_id, event_type, payload) VALUES ($1, $2, $3)",
+    )
+    .bind(organization_id)
+    .bind(event_type)
+    .bind(payload)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Deliver one batch of due events to all sinks; returns how many were dispatched.
+///
+/// Events are claimed with `FOR UPDATE SKIP LOCKED` so several API instances can
+/// run dispatchers against the same database without delivering an event twice
+/// concurrently.
+pub async fn dispatch_pending(
+    pool: &PgPool,
+    sinks: &[Arc<dyn EventSink>],
+) -> Result<usize, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let events = sqlx::query_as::<_, OutboxEvent>(
+        "SELECT id, organization_id, event_type, payload, created_at
+         FROM outbox_events
+         WHERE dispatched_at IS NULL AND next_attempt_at <= NOW()
+         ORDER BY id
+         LIMIT $1
+         FOR UPDATE SKIP LOCKED",
+    )
+    .bind(BATCH_SIZE)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut dispatched = 0;
+    for event in &events {
+        let mut errors = Vec::new();
+        for sink in sinks {
+            if let Err(e) = sink.deliver(event).await {
+                tracing::warn!(
+                    "Outbox event {} failed in sink {}: {}",
+                    event.id,
+                    sink.name(),
+                    e
+                );
+                errors.push(format!("{}: {}", sink.name(), e));
+            }
+        }
+
+        if errors.is_empty() {
+            sqlx::query("UPDATE outbox_events SET dispatched_at = NOW() WHERE id = $1")
+                .bind(event.id)
+                .execute(&mut *tx)
+                .await?;
+            dispatched += 1;
+        } else {
+            // Exponential backoff: 2, 4, 8, ... seconds, capped
+            sqlx::query(
+                "UPDATE outbox_events SET
+                   attempts = attempts + 1,
+                   last_error = $2,
+                   next_attempt_at = NOW() + make_interval(secs => LEAST(POWER(2, attempts + 1), $3))
+                 WHERE id = $1",
+            )
+            .bind(event.id)
+            .bind(errors.join("; "))
+            .bind(MAX_BACKOFF_SECS)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(dispatched)
+}
+
+/// Delete dispatched events past their retention window
+pub async fn purge_dispatched(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "DELETE FROM outbox_events
+         WHERE dispatched_at < NOW() - make_interval(days => $1)",
+    )
+    .bind(DISPATCHED_RETENTION_DAYS)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Run the dispatcher in the background, polling for due events every `interval`
+pub fn spawn_dispatcher(
+    pool: PgPool,
+    sinks: Vec<Arc<dyn EventSink>>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut last_purge: Option<Instant> = None;
+        loop {
+            ticker.tick().await;
+
+            // Keep draining while full batches come back
+            loop {
+                match dispatch_pending(&pool, &sinks).await {
+                    Ok(n) if n as i64 == BATCH_SIZE => continue,
+                    Ok(_) => break,
+                    Err(e) => {
+                        tracing::error!("Outbox dispatch failed: {}", e);
+                        break;
+                    }
+                }
+            }
+
+            if last_purge.is_none_or(|t| t.elapsed() >= PURGE_INTERVAL) {
+                last_purge = Some(Instant::now());
+                if let Err(e) = purge_dispatched(&pool).await {
+                    tracing::error!("Outbox purge failed: {}", e);
+                }
+            }
+        }
+    })
+}
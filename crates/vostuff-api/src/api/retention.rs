@@ -0,0 +1,132 @@
+//! Item history retention.
+//!
+//! Organizations that set `history_retention_months` have update entries older
+//! than that compacted by a background job ([`spawn_history_compaction`]):
+//! each item's updates within a calendar month become one `summarized` entry
+//! holding the net change over the month. Creation and deletion entries are
+//! kept as they are, so an item's lifecycle and name stay on record.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::api::audit;
+
+/// How often the compaction job runs
+pub const HISTORY_COMPACTION_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+/// An entry eligible for compaction
+#[derive(sqlx::FromRow)]
+struct OldEntry {
+    id: Uuid,
+    item_id: Uuid,
+    user_id: Option<Uuid>,
+    change_date: DateTime<Utc>,
+    changes: Value,
+}
+
+/// Compact old history in every organization with a retention period.
+/// Returns the number of entries replaced by summaries.
+pub async fn compact_history(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let orgs = sqlx::query_as::<_, (Uuid, i32)>(
+        "SELECT id, (settings->>'history_retention_months')::int
+         FROM organizations
+         WHERE (settings->>'history_retention_months')::int > 0",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut compacted = 0;
+    for (org_id, months) in orgs {
+        compacted += compact_organization(pool, org_id, months).await?;
+    }
+    Ok(compacted)
+}
+
+/// Compact one organization's update entries from before the start of the
+/// month `months` months ago, a calendar month per transaction
+pub async fn compact_organization(
+    pool: &PgPool,
+    org_id: Uuid,
+    months: i32,
+) -> Result<u64, sqlx::Error> {
+    let month_starts: Vec<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT DISTINCT date_trunc('month', change_date) AS month
+         FROM audit_log
+         WHERE organization_id = $1
+           AND action IN ('updated', 'state_changed')
+           AND change_date < date_trunc('month', NOW()) - make_interval(months => $2)
+         ORDER BY month",
+    )
+    .bind(org_id)
+    .bind(months)
+    .fetch_all(pool)
+    .await?;
+
+    let mut compacted = 0;
+    for month_start in month_starts {
+        let mut tx = pool.begin().await?;
+        let entries = sqlx::query_as::<_, OldEntry>(
+            "SELECT id, item_id, user_id, change_date, changes
+             FROM audit_log
+             WHERE organization_id = $1
+               AND action IN ('updated', 'state_changed')
+               AND change_date >= $2 AND change_date < $2 + INTERVAL '1 month'
+             ORDER BY item_id, change_date, id
+             FOR UPDATE",
+        )
+        .bind(org_id)
+        .bind(month_start)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for group in entries.chunk_by(|a, b| a.item_id == b.item_id) {
+            // A lone entry is already as compact as it gets
+            if group.len() < 2 {
+                continue;
+            }
+            let first = &group[0];
+            let user_id = first
+                .user_id
+                .filter(|user| group.iter().all(|e| e.user_id == Some(*user)));
+            let changes = audit::merge(group.iter().map(|e| &e.changes));
+            audit::record_summary(
+                &mut tx,
+                org_id,
+                first.item_id,
+                user_id,
+                first.change_date,
+                changes,
+            )
+            .await?;
+
+            let ids: Vec<Uuid> = group.iter().map(|e| e.id).collect();
+            sqlx::query("DELETE FROM audit_log WHERE id = ANY($1)")
+                .bind(&ids)
+                .execute(&mut *tx)
+                .await?;
+            compacted += ids.len() as u64;
+        }
+        tx.commit().await?;
+    }
+    Ok(compacted)
+}
+
+/// Run history compaction in the background every `interval`, starting now
+pub fn spawn_history_compaction(pool: PgPool, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match compact_history(&pool).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("History retention compacted {} entries", n),
+                Err(e) => tracing::error!("History compaction failed: {}", e),
+            }
+        }
+    })
+}
@@ -1,13 +1,131 @@
+use std::sync::Arc;
+
+use metrics_exporter_prometheus::PrometheusHandle;
 use sqlx::PgPool;
+use vostuff_core::auth::{
+    ACCESS_TOKEN_TTL_MINUTES,
+    backend::{AuthBackend, PasswordBackend},
+    oidc::OidcProvider,
+};
+use vostuff_core::storage::{LocalStorage, Storage};
+
+use crate::api::live::LiveEvents;
+use crate::api::lookup::{self, MetadataProvider};
+
+pub use vostuff_core::config::DEFAULT_EXPORT_RETENTION_DAYS;
+
+/// Terms of service users must accept before logging in, as a hosted
+/// instance requires
+#[derive(Clone, Debug)]
+pub struct TermsOfService {
+    /// Current version; users who accepted an earlier one must accept again
+    pub version: String,
+    /// Where the terms can be read
+    pub url: Option<String>,
+}
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
     pub jwt_secret: String,
+    /// Minutes an issued access token is valid
+    pub access_token_ttl_minutes: i64,
+    pub export_retention_days: i32,
+    /// Blob storage for exports; see `StorageConfig`
+    pub storage: Arc<dyn Storage>,
+    /// Backends checking password logins, in the order they are asked; the
+    /// stored password first, then any directory added with `with_auth_backend`
+    pub auth_backends: Arc<Vec<Arc<dyn AuthBackend>>>,
+    /// External identity provider for OIDC login; None when it is not configured
+    pub oidc: Option<Arc<OidcProvider>>,
+    /// Prometheus recorder; requests are tracked and `/metrics` served only when set
+    pub metrics: Option<PrometheusHandle>,
+    /// Terms to accept at login; None when the instance requires none
+    pub terms: Option<TermsOfService>,
+    /// Changes streamed to clients; the outbox dispatcher's `LiveSink` feeds it
+    pub live: LiveEvents,
+    /// Web page that shows a shared item; see `Config::share_url`
+    pub share_url: String,
+    /// Web page that opens an item; see `Config::item_url`
+    pub item_url: String,
+    /// Services asked about barcodes and ISBNs, in order. The lookup job is
+    /// given the same ones so requests to each service share its rate limit.
+    pub metadata_providers: Arc<Vec<Arc<dyn MetadataProvider>>>,
 }
 
 impl AppState {
+    /// State with local storage in the system temp directory, as used by tests
+    /// and tools. The API server configures storage with `with_storage`.
     pub fn new(pool: PgPool, jwt_secret: String) -> Self {
-        Self { pool, jwt_secret }
+        let storage = LocalStorage::new(
+            std::env::temp_dir().join("vostuff-storage"),
+            "http://localhost:8080/api/files",
+            &jwt_secret,
+        );
+        Self {
+            pool,
+            jwt_secret,
+            access_token_ttl_minutes: ACCESS_TOKEN_TTL_MINUTES,
+            export_retention_days: DEFAULT_EXPORT_RETENTION_DAYS,
+            storage: Arc::new(storage),
+            auth_backends: Arc::new(vec![Arc::new(PasswordBackend)]),
+            oidc: None,
+            metrics: None,
+            terms: None,
+            live: LiveEvents::new(),
+            share_url: "http://localhost:3001/shared".to_string(),
+            item_url: "http://localhost:3001/".to_string(),
+            metadata_providers: Arc::new(lookup::default_providers()),
+        }
+    }
+
+    pub fn with_access_token_ttl_minutes(mut self, minutes: i64) -> Self {
+        self.access_token_ttl_minutes = minutes;
+        self
+    }
+
+    pub fn with_export_retention_days(mut self, days: i32) -> Self {
+        self.export_retention_days = days;
+        self
+    }
+
+    pub fn with_storage(mut self, storage: Arc<dyn Storage>) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    pub fn with_auth_backend(mut self, backend: Arc<dyn AuthBackend>) -> Self {
+        Arc::make_mut(&mut self.auth_backends).push(backend);
+        self
+    }
+
+    pub fn with_oidc(mut self, provider: Arc<OidcProvider>) -> Self {
+        self.oidc = Some(provider);
+        self
+    }
+
+    pub fn with_metrics(mut self, handle: PrometheusHandle) -> Self {
+        self.metrics = Some(handle);
+        self
+    }
+
+    pub fn with_terms(mut self, terms: TermsOfService) -> Self {
+        self.terms = Some(terms);
+        self
+    }
+
+    pub fn with_share_url(mut self, url: String) -> Self {
+        self.share_url = url;
+        self
+    }
+
+    pub fn with_item_url(mut self, url: String) -> Self {
+        self.item_url = url;
+        self
+    }
+
+    pub fn with_metadata_providers(mut self, providers: Vec<Arc<dyn MetadataProvider>>) -> Self {
+        self.metadata_providers = Arc::new(providers);
+        self
     }
 }
@@ -0,0 +1,339 @@
+//! Checks on request bodies, made before a handler runs so a bad payload is
+//! answered with the fields at fault rather than a database error.
+
+use async_trait::async_trait;
+use axum::{
+    Json,
+    extract::{FromRequest, Request},
+    response::{IntoResponse, Response},
+};
+use serde::de::DeserializeOwned;
+
+use crate::api::error::ApiError;
+use crate::api::models::{
+    BulkCreateItemsRequest, BulkUpdateItemsRequest, CreateCollectionRequest, CreateItemRequest,
+    CreateLocationRequest, CreateOrganizationRequest, CreateSmartCollectionRequest,
+    CreateTagRequest, CreateUserRequest, FieldError, PrintLabelsRequest, SmartCollectionFilter,
+    UpdateCollectionRequest, UpdateItemRequest, UpdateLocationRequest, UpdateOrganizationRequest,
+    UpdateSmartCollectionRequest, UpdateUserRequest,
+};
+
+/// Longest name, identity or borrower the database stores
+pub const NAME_MAX_LEN: usize = 255;
+/// Longest tag name the database stores
+pub const TAG_MAX_LEN: usize = 100;
+/// Longest barcode or catalog code the database stores
+pub const CODE_MAX_LEN: usize = 64;
+
+/// A request body that can check its own fields
+pub trait Validate {
+    /// Add an error to `errors` for each field at fault
+    fn validate(&self, errors: &mut FieldErrors);
+
+    /// Every field error, as one `validation.failed` error
+    fn check(&self) -> Result<(), ApiError> {
+        let mut errors = FieldErrors::default();
+        self.validate(&mut errors);
+        errors.into_result()
+    }
+}
+
+/// Field errors collected while validating a request
+#[derive(Debug, Default)]
+pub struct FieldErrors {
+    prefix: String,
+    errors: Vec<FieldError>,
+}
+
+impl FieldErrors {
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: format!("{}{}", self.prefix, field),
+            message: message.into(),
+        });
+    }
+
+    /// A name that must be given, must not be blank and must fit the column
+    pub fn required(&mut self, field: &str, value: &str, max_len: usize) {
+        if value.trim().is_empty() {
+            self.add(field, format!("{} must not be empty", field));
+        } else {
+            self.max_len(field, Some(value), max_len);
+        }
+    }
+
+    /// A name that may be left out, but must not be blank when given
+    pub fn optional(&mut self, field: &str, value: Option<&str>, max_len: usize) {
+        if let Some(value) = value {
+            self.required(field, value, max_len);
+        }
+    }
+
+    pub fn max_len(&mut self, field: &str, value: Option<&str>, max_len: usize) {
+        if value.is_some_and(|v| v.trim().chars().count() > max_len) {
+            self.add(
+                field,
+                format!("{} must be at most {} characters", field, max_len),
+            );
+        }
+    }
+
+    /// Validate `value` with its field names under `prefix`, as for the
+    /// elements of a list
+    pub fn nested(&mut self, prefix: &str, value: &impl Validate) {
+        let inner = format!("{}{}.", self.prefix, prefix);
+        let outer = std::mem::replace(&mut self.prefix, inner);
+        value.validate(self);
+        self.prefix = outer;
+    }
+
+    pub fn into_result(self) -> Result<(), ApiError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::invalid_fields(self.errors))
+        }
+    }
+}
+
+/// JSON body extractor that runs the body's `Validate` checks
+pub struct ValidJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        value.check().map_err(IntoResponse::into_response)?;
+        Ok(Self(value))
+    }
+}
+
+impl Validate for CreateItemRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.required("name", &self.name, NAME_MAX_LEN);
+        errors.max_len("barcode", self.barcode.as_deref(), CODE_MAX_LEN);
+        errors.max_len("catalog_code", self.catalog_code.as_deref(), CODE_MAX_LEN);
+        for (index, tag) in self.tags.iter().flatten().enumerate() {
+            errors.required(&format!("tags.{}", index), tag, TAG_MAX_LEN);
+        }
+    }
+}
+
+impl Validate for BulkCreateItemsRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        for (index, item) in self.items.iter().enumerate() {
+            errors.nested(&format!("items.{}", index), item);
+        }
+    }
+}
+
+impl Validate for BulkUpdateItemsRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        for (index, tag) in self.add_tags.iter().enumerate() {
+            errors.required(&format!("add_tags.{}", index), tag, TAG_MAX_LEN);
+            if self.remove_tags.iter().any(|t| t.trim() == tag.trim()) {
+                errors.add(
+                    &format!("add_tags.{}", index),
+                    "A tag cannot be both added and removed",
+                );
+            }
+        }
+        for (index, tag) in self.remove_tags.iter().enumerate() {
+            errors.required(&format!("remove_tags.{}", index), tag, TAG_MAX_LEN);
+        }
+    }
+}
+
+impl Validate for PrintLabelsRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.required("template", &self.template, NAME_MAX_LEN);
+    }
+}
+
+impl Validate for UpdateItemRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.optional("name", self.name.as_deref(), NAME_MAX_LEN);
+        errors.max_len("barcode", self.barcode.as_deref(), CODE_MAX_LEN);
+        errors.max_len("catalog_code", self.catalog_code.as_deref(), CODE_MAX_LEN);
+        errors.optional(
+            "loan_loaned_to",
+            self.loan_loaned_to.as_deref(),
+            NAME_MAX_LEN,
+        );
+        if let (Some(loaned), Some(due_back)) = (self.loan_date_loaned, self.loan_date_due_back)
+            && due_back < loaned
+        {
+            errors.add(
+                "loan_date_due_back",
+                "loan_date_due_back must not be before loan_date_loaned",
+            );
+        }
+    }
+}
+
+impl Validate for CreateUserRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.required("name", &self.name, NAME_MAX_LEN);
+        errors.required("identity", &self.identity, NAME_MAX_LEN);
+        if self
+            .password
+            .as_deref()
+            .is_some_and(|p| p.trim().is_empty())
+        {
+            errors.add("password", "password must not be empty");
+        }
+    }
+}
+
+impl Validate for UpdateUserRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.optional("name", self.name.as_deref(), NAME_MAX_LEN);
+        errors.optional("identity", self.identity.as_deref(), NAME_MAX_LEN);
+        if self
+            .password
+            .as_deref()
+            .is_some_and(|p| p.trim().is_empty())
+        {
+            errors.add("password", "password must not be empty");
+        }
+    }
+}
+
+impl Validate for CreateLocationRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.required("name", &self.name, NAME_MAX_LEN);
+    }
+}
+
+impl Validate for UpdateLocationRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.required("name", &self.name, NAME_MAX_LEN);
+    }
+}
+
+impl Validate for CreateCollectionRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.required("name", &self.name, NAME_MAX_LEN);
+    }
+}
+
+impl Validate for UpdateCollectionRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.optional("name", self.name.as_deref(), NAME_MAX_LEN);
+    }
+}
+
+impl Validate for SmartCollectionFilter {
+    fn validate(&self, errors: &mut FieldErrors) {
+        for (index, kind) in self.kinds.iter().enumerate() {
+            errors.required(&format!("kinds.{}", index), kind, NAME_MAX_LEN);
+        }
+        for (index, tag) in self.tags.iter().enumerate() {
+            errors.required(&format!("tags.{}", index), tag, TAG_MAX_LEN);
+        }
+        errors.optional("search", self.search.as_deref(), NAME_MAX_LEN);
+    }
+}
+
+impl Validate for CreateSmartCollectionRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.required("name", &self.name, NAME_MAX_LEN);
+        errors.nested("filter", &self.filter);
+    }
+}
+
+impl Validate for UpdateSmartCollectionRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.optional("name", self.name.as_deref(), NAME_MAX_LEN);
+        if let Some(filter) = &self.filter {
+            errors.nested("filter", filter);
+        }
+    }
+}
+
+impl Validate for CreateTagRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.required("name", &self.name, TAG_MAX_LEN);
+    }
+}
+
+impl Validate for CreateOrganizationRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.required("name", &self.name, NAME_MAX_LEN);
+    }
+}
+
+impl Validate for UpdateOrganizationRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.optional("name", self.name.as_deref(), NAME_MAX_LEN);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    fn fields(result: Result<(), ApiError>) -> Vec<String> {
+        match result {
+            Ok(()) => Vec::new(),
+            Err(ApiError::Validation { fields, .. }) => {
+                fields.into_iter().map(|f| f.field).collect()
+            }
+            Err(other) => panic!("unexpected error {:?}", other.code()),
+        }
+    }
+
+    fn item(value: serde_json::Value) -> CreateItemRequest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_item_names_and_codes_are_checked() {
+        let kind = "00000000-0000-0000-0000-000000000001";
+        assert!(fields(item(json!({"kind_id": kind, "name": "Kind of Blue"})).check()).is_empty());
+
+        let bad = item(json!({
+            "kind_id": kind,
+            "name": "   ",
+            "barcode": "0".repeat(CODE_MAX_LEN + 1),
+            "catalog_code": "CL 1355",
+            "tags": ["jazz", " "]
+        }));
+        assert_eq!(fields(bad.check()), vec!["name", "barcode", "tags.1"]);
+
+        let long = item(json!({"kind_id": kind, "name": "x".repeat(NAME_MAX_LEN + 1)}));
+        assert_eq!(fields(long.check()), vec!["name"]);
+    }
+
+    #[test]
+    fn test_bulk_items_are_reported_by_index() {
+        let kind = "00000000-0000-0000-0000-000000000001";
+        let bulk: BulkCreateItemsRequest = serde_json::from_value(json!({"items": [
+            {"kind_id": kind, "name": "Giant Steps"},
+            {"kind_id": kind, "name": ""}
+        ]}))
+        .unwrap();
+        assert_eq!(fields(bulk.check()), vec!["items.1.name"]);
+    }
+
+    #[test]
+    fn test_loan_due_back_is_not_before_loaned() {
+        let mut update: UpdateItemRequest = serde_json::from_value(json!({})).unwrap();
+        update.loan_date_loaned = NaiveDate::from_ymd_opt(2026, 3, 10);
+        update.loan_date_due_back = NaiveDate::from_ymd_opt(2026, 3, 1);
+        assert_eq!(fields(update.check()), vec!["loan_date_due_back"]);
+
+        update.loan_date_due_back = update.loan_date_loaned;
+        assert!(fields(update.check()).is_empty());
+    }
+}
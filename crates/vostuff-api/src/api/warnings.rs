@@ -0,0 +1,104 @@
+//! Non-fatal validation warnings for items.
+//!
+//! Warnings flag values that are allowed but probably mistakes. They are
+//! returned alongside a successful create or update and never block the save.
+
+use chrono::NaiveDate;
+use sqlx::PgPool;
+
+use crate::api::models::Item;
+
+/// Warnings for an item as saved
+pub async fn item_warnings(pool: &PgPool, item: &Item) -> Result<Vec<String>, sqlx::Error> {
+    // Number fields of the item's kind hold counts (disks, cassettes, ...)
+    let number_fields: Vec<String> = sqlx::query_scalar(
+        "SELECT f.name
+         FROM kind_fields kf
+         JOIN fields f ON f.id = kf.field_id
+         WHERE kf.kind_id = $1 AND f.field_type = 'number'",
+    )
+    .bind(item.kind_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(check_item(
+        item,
+        &number_fields,
+        chrono::Local::now().date_naive(),
+    ))
+}
+
+fn check_item(item: &Item, number_fields: &[String], today: NaiveDate) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if item.date_acquired.is_some_and(|d| d > today) {
+        warnings.push("date_acquired is in the future".to_string());
+    }
+
+    for field in number_fields {
+        if let Some(value) = item.soft_fields.get(field).and_then(|v| v.as_f64())
+            && value <= 0.0
+        {
+            warnings.push(format!("{}={} looks wrong", field, item.soft_fields[field]));
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::ItemState;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn item(date_acquired: Option<NaiveDate>, soft_fields: serde_json::Value) -> Item {
+        let now = chrono::Utc::now();
+        Item {
+            id: Uuid::new_v4(),
+            organization_id: Uuid::new_v4(),
+            kind_id: Uuid::new_v4(),
+            kind_name: "vinyl".to_string(),
+            state: ItemState::Current,
+            name: "Blue Train".to_string(),
+            description: None,
+            notes: None,
+            location_id: None,
+            date_entered: now,
+            date_acquired,
+            barcode: None,
+            catalog_code: None,
+            width_mm: None,
+            height_mm: None,
+            depth_mm: None,
+            weight_g: None,
+            last_used_at: None,
+            soft_fields,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_plausible_item_has_no_warnings() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let item = item(Some(today), json!({"disks": 2, "size": "12"}));
+        assert!(check_item(&item, &["disks".to_string()], today).is_empty());
+    }
+
+    #[test]
+    fn test_suspicious_values_warn() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let item = item(today.succ_opt(), json!({"disks": 0, "cassettes": 1}));
+        let warnings = check_item(
+            &item,
+            &["disks".to_string(), "cassettes".to_string()],
+            today,
+        );
+        assert_eq!(
+            warnings,
+            ["date_acquired is in the future", "disks=0 looks wrong"]
+        );
+    }
+}
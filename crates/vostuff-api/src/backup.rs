@@ -0,0 +1,530 @@
+//! The organization backup written by `vostuff-export`.
+//!
+//! A [`Backup`] holds everything an organization has: its items with their
+//! tags and loan, missing or disposed details, and its kinds, locations,
+//! collections (with their items), tags, borrowers and loans. It is written as
+//! one JSON file, or by [`Backup::write_zip`] as a zip of CSV files.
+//!
+//! The zip's items are split by kind, `items/<kind>.csv`, each beside a
+//! column mapping `items/<kind>.mapping.toml` (see [`crate::import_mapping`]),
+//! so the items can be brought back into any organization with
+//! `csv-importer --mapping items/book.mapping.toml items/book.csv` or the
+//! imports API. Only the JSON restores everything as it was: items imported
+//! from the CSVs are new items, their notes headed `notes:` as any mapped
+//! notes column, and without their state, location or collections.
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::{Seek, Write};
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+use vostuff_core::models::{
+    Collection, DisposedDetails, Item, LoanDetails, Location, MissingDetails, Tag,
+};
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+use crate::import_client::KindDetail;
+use crate::import_mapping::{ColumnMapping, ImportMapping, MappingTarget};
+
+/// [`Backup::format`] of every backup
+pub const FORMAT: &str = "vostuff-backup";
+
+/// Version of the backup layout, raised when it changes incompatibly
+pub const VERSION: u32 = 1;
+
+/// Separator of the tags and collections in an item CSV row
+const LIST_SEPARATOR: &str = "; ";
+
+/// Everything an organization has, as of `exported_at`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Backup {
+    /// Always [`FORMAT`]
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub organization_id: Uuid,
+    /// The kinds the organization can use, shared ones included
+    pub kinds: Vec<KindDetail>,
+    pub locations: Vec<Location>,
+    pub collections: Vec<BackupCollection>,
+    pub tags: Vec<Tag>,
+    pub borrowers: Vec<BackupBorrower>,
+    pub items: Vec<BackupItem>,
+}
+
+/// A collection and the items in it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupCollection {
+    #[serde(flatten)]
+    pub collection: Collection,
+    pub item_ids: Vec<Uuid>,
+}
+
+/// A borrower and every loan made to them
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupBorrower {
+    pub id: Uuid,
+    pub name: String,
+    pub loans: Vec<BackupLoan>,
+}
+
+/// One loan, past or current
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupLoan {
+    pub item_id: Uuid,
+    pub date_loaned: NaiveDate,
+    pub date_due_back: Option<NaiveDate>,
+    /// None while the item is still out
+    pub date_returned: Option<NaiveDate>,
+}
+
+/// An item with its tags and the details of its state, as the item list
+/// returns it with `include=details,tags`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupItem {
+    #[serde(flatten)]
+    pub item: Item,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loan_details: Option<LoanDetails>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub missing_details: Option<MissingDetails>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disposed_details: Option<DisposedDetails>,
+}
+
+/// Item columns before the soft fields, with where `csv-importer` puts them
+const ITEM_COLUMNS: &[(&str, MappingTarget)] = &[
+    ("id", MappingTarget::Ignore),
+    ("name", MappingTarget::Name),
+    ("state", MappingTarget::Ignore),
+    ("description", MappingTarget::Description),
+    ("notes", MappingTarget::Notes),
+    ("location", MappingTarget::Ignore),
+    ("date_acquired", MappingTarget::DateAcquired),
+    ("barcode", MappingTarget::Barcode),
+    ("catalog_code", MappingTarget::CatalogCode),
+    ("width_mm", MappingTarget::Ignore),
+    ("height_mm", MappingTarget::Ignore),
+    ("depth_mm", MappingTarget::Ignore),
+    ("weight_g", MappingTarget::Ignore),
+    ("tags", MappingTarget::Tags),
+    ("collections", MappingTarget::Ignore),
+];
+
+impl Backup {
+    /// Write the backup as a zip of CSV files: the items of each kind with
+    /// their column mapping, then `locations.csv`, `collections.csv`,
+    /// `tags.csv` and `loans.csv`
+    pub fn write_zip<W: Write + Seek>(&self, out: W) -> Result<()> {
+        let mut zip = ZipWriter::new(out);
+        let options = SimpleFileOptions::default();
+
+        let mut used_names = BTreeSet::new();
+        for (kind, rows) in self.item_csvs()? {
+            let mut stem = file_stem(&kind.name);
+            // An organization's copy of a shared kind has the same name
+            let mut n = 1;
+            while !used_names.insert(stem.clone()) {
+                n += 1;
+                stem = format!("{}-{}", file_stem(&kind.name), n);
+            }
+            zip.start_file(format!("items/{}.csv", stem), options)?;
+            zip.write_all(&rows)?;
+            zip.start_file(format!("items/{}.mapping.toml", stem), options)?;
+            zip.write_all(toml::to_string(&item_mapping(kind))?.as_bytes())?;
+        }
+
+        zip.start_file("locations.csv", options)?;
+        zip.write_all(&csv_bytes(
+            &["id", "name"],
+            self.locations
+                .iter()
+                .map(|l| vec![l.id.to_string(), l.name.clone()]),
+        )?)?;
+
+        let items: HashMap<Uuid, &Item> = self.items.iter().map(|i| (i.item.id, &i.item)).collect();
+        zip.start_file("collections.csv", options)?;
+        zip.write_all(&csv_bytes(
+            &["id", "name", "description", "notes", "items"],
+            self.collections.iter().map(|c| {
+                vec![
+                    c.collection.id.to_string(),
+                    c.collection.name.clone(),
+                    c.collection.description.clone().unwrap_or_default(),
+                    c.collection.notes.clone().unwrap_or_default(),
+                    c.item_ids
+                        .iter()
+                        .filter_map(|id| items.get(id).map(|i| i.name.as_str()))
+                        .collect::<Vec<_>>()
+                        .join(LIST_SEPARATOR),
+                ]
+            }),
+        )?)?;
+
+        zip.start_file("tags.csv", options)?;
+        zip.write_all(&csv_bytes(
+            &["name"],
+            self.tags.iter().map(|t| vec![t.name.clone()]),
+        )?)?;
+
+        zip.start_file("loans.csv", options)?;
+        zip.write_all(&csv_bytes(
+            &[
+                "borrower",
+                "item_id",
+                "item",
+                "date_loaned",
+                "date_due_back",
+                "date_returned",
+            ],
+            self.borrowers.iter().flat_map(|b| {
+                b.loans.iter().map(|l| {
+                    vec![
+                        b.name.clone(),
+                        l.item_id.to_string(),
+                        items
+                            .get(&l.item_id)
+                            .map(|i| i.name.clone())
+                            .unwrap_or_default(),
+                        l.date_loaned.to_string(),
+                        opt(l.date_due_back),
+                        opt(l.date_returned),
+                    ]
+                })
+            }),
+        )?)?;
+
+        zip.finish()?;
+        Ok(())
+    }
+
+    /// Each kind with items, and the CSV of its items
+    fn item_csvs(&self) -> Result<Vec<(&KindDetail, Vec<u8>)>> {
+        let locations: HashMap<Uuid, &str> = self
+            .locations
+            .iter()
+            .map(|l| (l.id, l.name.as_str()))
+            .collect();
+        let mut collections: HashMap<Uuid, Vec<&str>> = HashMap::new();
+        for c in &self.collections {
+            for id in &c.item_ids {
+                collections
+                    .entry(*id)
+                    .or_default()
+                    .push(c.collection.name.as_str());
+            }
+        }
+
+        let mut csvs = Vec::new();
+        for kind in &self.kinds {
+            let items: Vec<&BackupItem> = self
+                .items
+                .iter()
+                .filter(|i| i.item.kind_id == kind.id)
+                .collect();
+            if items.is_empty() {
+                continue;
+            }
+            let mut headers: Vec<&str> = ITEM_COLUMNS.iter().map(|(name, _)| *name).collect();
+            headers.extend(kind.fields.iter().map(|f| f.name.as_str()));
+
+            let rows = items.iter().map(|i| {
+                let item = &i.item;
+                let mut row = vec![
+                    item.id.to_string(),
+                    item.name.clone(),
+                    item.state.to_string(),
+                    item.description.clone().unwrap_or_default(),
+                    item.notes.clone().unwrap_or_default(),
+                    item.location_id
+                        .and_then(|id| locations.get(&id))
+                        .map(|l| l.to_string())
+                        .unwrap_or_default(),
+                    opt(item.date_acquired),
+                    item.barcode.clone().unwrap_or_default(),
+                    item.catalog_code.clone().unwrap_or_default(),
+                    opt(item.width_mm),
+                    opt(item.height_mm),
+                    opt(item.depth_mm),
+                    opt(item.weight_g),
+                    i.tags.join(LIST_SEPARATOR),
+                    collections
+                        .get(&item.id)
+                        .map(|c| c.join(LIST_SEPARATOR))
+                        .unwrap_or_default(),
+                ];
+                row.extend(
+                    kind.fields
+                        .iter()
+                        .map(|f| soft_text(item.soft_fields.get(&f.name))),
+                );
+                row
+            });
+            csvs.push((kind, csv_bytes(&headers, rows)?));
+        }
+        Ok(csvs)
+    }
+}
+
+/// The mapping that imports a kind's items CSV back as that kind
+fn item_mapping(kind: &KindDetail) -> ImportMapping {
+    let mut columns: Vec<ColumnMapping> = ITEM_COLUMNS
+        .iter()
+        .map(|(name, target)| ColumnMapping {
+            column: name.to_string(),
+            target: target.clone(),
+            confidence: None,
+        })
+        .collect();
+    columns.extend(kind.fields.iter().map(|f| ColumnMapping {
+        column: f.name.clone(),
+        target: MappingTarget::SoftField(f.name.clone()),
+        confidence: None,
+    }));
+    ImportMapping {
+        kind: kind.name.clone(),
+        tags: Vec::new(),
+        columns,
+    }
+}
+
+fn csv_bytes(headers: &[&str], rows: impl Iterator<Item = Vec<String>>) -> Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(headers)?;
+    for row in rows {
+        writer.write_record(&row)?;
+    }
+    Ok(writer.into_inner()?)
+}
+
+/// A file name for the kind: its name with anything unusual replaced
+fn file_stem(kind_name: &str) -> String {
+    kind_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn opt(value: Option<impl ToString>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// A soft field value as CSV text, which `csv-importer` reads back to the
+/// same value
+fn soft_text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::import_client::{EnumValue, KindField};
+    use crate::import_mapping::{SoftFieldSpec, typed_soft_fields};
+
+    fn backup() -> Backup {
+        let org = Uuid::new_v4();
+        let book = Uuid::new_v4();
+        let shelf = Uuid::new_v4();
+        let now = Utc::now();
+        let item = |name: &str, soft_fields: Value| -> Item {
+            serde_json::from_value(json!({
+                "id": Uuid::new_v4(), "organization_id": org, "kind_id": book,
+                "kind_name": "book", "state": "current", "name": name,
+                "description": null, "notes": "Signed, first page", "location_id": shelf,
+                "date_entered": now, "date_acquired": "2020-05-01", "barcode": null,
+                "catalog_code": null, "width_mm": null, "height_mm": null, "depth_mm": null,
+                "weight_g": null, "last_used_at": null, "soft_fields": soft_fields,
+                "created_at": now, "updated_at": now
+            }))
+            .unwrap()
+        };
+        let dune = item("Dune", json!({"author": "Frank Herbert", "year": 1965}));
+        let dune_id = dune.id;
+        Backup {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            exported_at: now,
+            organization_id: org,
+            kinds: vec![KindDetail {
+                id: book,
+                name: "book".to_string(),
+                display_name: Some("Book".to_string()),
+                is_shared: true,
+                fields: vec![
+                    KindField {
+                        name: "author".to_string(),
+                        display_name: Some("Author".to_string()),
+                        field_type: "string".to_string(),
+                        enum_values: Vec::new(),
+                    },
+                    KindField {
+                        name: "year".to_string(),
+                        display_name: Some("Year".to_string()),
+                        field_type: "number".to_string(),
+                        enum_values: Vec::new(),
+                    },
+                    KindField {
+                        name: "book_format".to_string(),
+                        display_name: Some("Format".to_string()),
+                        field_type: "enum".to_string(),
+                        enum_values: vec![EnumValue {
+                            value: "hardback".to_string(),
+                            display_value: Some("Hardback".to_string()),
+                        }],
+                    },
+                ],
+            }],
+            locations: vec![Location {
+                id: shelf,
+                organization_id: org,
+                name: "Study".to_string(),
+                created_at: now,
+                updated_at: now,
+            }],
+            collections: vec![BackupCollection {
+                collection: Collection {
+                    id: Uuid::new_v4(),
+                    organization_id: org,
+                    name: "Classics".to_string(),
+                    description: None,
+                    notes: None,
+                    cover_item_id: None,
+                    sort_order: 0,
+                    created_at: now,
+                    updated_at: now,
+                },
+                item_ids: vec![dune_id],
+            }],
+            tags: Vec::new(),
+            borrowers: vec![BackupBorrower {
+                id: Uuid::new_v4(),
+                name: "Ann".to_string(),
+                loans: vec![BackupLoan {
+                    item_id: dune_id,
+                    date_loaned: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+                    date_due_back: None,
+                    date_returned: NaiveDate::from_ymd_opt(2024, 2, 3),
+                }],
+            }],
+            items: vec![
+                BackupItem {
+                    item: dune,
+                    tags: vec!["sci-fi".to_string(), "classics".to_string()],
+                    loan_details: None,
+                    missing_details: None,
+                    disposed_details: None,
+                },
+                BackupItem {
+                    item: item("Emma", json!({"book_format": "hardback"})),
+                    tags: Vec::new(),
+                    loan_details: None,
+                    missing_details: None,
+                    disposed_details: None,
+                },
+            ],
+        }
+    }
+
+    fn read_file(zip: &mut zip::ZipArchive<Cursor<Vec<u8>>>, name: &str) -> String {
+        let mut text = String::new();
+        zip.by_name(name)
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    }
+
+    #[test]
+    fn test_zip_items_import_back_through_their_mapping() {
+        let backup = backup();
+        let mut out = Cursor::new(Vec::new());
+        backup.write_zip(&mut out).unwrap();
+        let mut zip = zip::ZipArchive::new(Cursor::new(out.into_inner())).unwrap();
+
+        let mapping: ImportMapping =
+            toml::from_str(&read_file(&mut zip, "items/book.mapping.toml")).unwrap();
+        assert_eq!(mapping.kind, "book");
+
+        let text = read_file(&mut zip, "items/book.csv");
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+        let headers: Vec<String> = reader.headers().unwrap().iter().map(String::from).collect();
+        mapping.validate(&headers).unwrap();
+        let rows: Vec<Vec<String>> = reader
+            .records()
+            .map(|r| r.unwrap().iter().map(String::from).collect())
+            .collect();
+        assert_eq!(rows.len(), 2);
+
+        let fields: Vec<SoftFieldSpec> = backup.kinds[0]
+            .fields
+            .iter()
+            .map(|f| SoftFieldSpec {
+                name: f.name.clone(),
+                field_type: f.field_type.clone(),
+                enum_values: f
+                    .enum_values
+                    .iter()
+                    .map(|ev| (ev.value.clone(), ev.display_value.clone()))
+                    .collect(),
+            })
+            .collect();
+        let dune = mapping.apply(&headers, &rows[0]);
+        assert_eq!(dune.name.as_deref(), Some("Dune"));
+        assert_eq!(
+            dune.notes.as_deref(),
+            Some("- **notes:** Signed, first page")
+        );
+        assert_eq!(dune.date_acquired.as_deref(), Some("2020-05-01"));
+        assert_eq!(dune.tags, ["sci-fi", "classics"]);
+        assert_eq!(
+            Value::Object(typed_soft_fields(&dune.soft_fields, &fields).unwrap()),
+            backup.items[0].item.soft_fields
+        );
+        let emma = mapping.apply(&headers, &rows[1]);
+        assert_eq!(
+            Value::Object(typed_soft_fields(&emma.soft_fields, &fields).unwrap()),
+            json!({"book_format": "hardback"})
+        );
+
+        assert_eq!(
+            read_file(&mut zip, "collections.csv"),
+            format!(
+                "id,name,description,notes,items\n{},Classics,,,Dune\n",
+                backup.collections[0].collection.id
+            )
+        );
+        assert!(read_file(&mut zip, "loans.csv").contains(",Dune,2024-01-02,,2024-02-03\n"));
+        assert!(read_file(&mut zip, "items/book.csv").contains(",Study,2020-05-01,"));
+    }
+
+    #[test]
+    fn test_json_round_trips() {
+        let backup = backup();
+        let text = serde_json::to_string(&backup).unwrap();
+        let read: Backup = serde_json::from_str(&text).unwrap();
+        assert_eq!(read.format, FORMAT);
+        assert_eq!(read.items.len(), 2);
+        assert_eq!(read.items[0].tags, ["sci-fi", "classics"]);
+        assert_eq!(read.collections[0].item_ids, [backup.items[0].item.id]);
+        assert_eq!(serde_json::to_string(&read).unwrap(), text);
+    }
+}
@@ -0,0 +1,218 @@
+//! Generate a large synthetic collection and time the item queries against it.
+//!
+//! Requests go through the real router in-process, so timings include
+//! authentication, filtering and serialization but not the network.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use axum::body::Body;
+use axum::http::Request;
+use clap::Parser;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+use vostuff_api::api::{handlers::build_router, models::UserRole, state::AppState};
+use vostuff_api::auth::TokenManager;
+use vostuff_api::generator::ItemGenerator;
+use vostuff_api::permissions::Permissions;
+use vostuff_api::test_utils::DEFAULT_SEED;
+
+/// Queries timed, as paths below the organization
+const QUERIES: &[&str] = &[
+    "items",
+    "items?page=50",
+    "items?state=loaned",
+    "items?kind=vinyl",
+    "items?tags=jazz",
+    "items?tags=jazz,rare&tag_match=all",
+    "items?search=harbour",
+    "items?sort_by=name&sort_order=desc",
+    "items/facets",
+    "items/rotation",
+    "stats",
+];
+
+#[derive(Parser)]
+#[command(name = "bench")]
+#[command(about = "Generate synthetic items and time the item queries against them")]
+struct Cli {
+    #[arg(
+        long,
+        env = "DATABASE_URL",
+        default_value = "postgresql://localhost/vostuff_dev"
+    )]
+    database_url: String,
+    /// Items to generate first; 0 times the organization as it is
+    #[arg(long, default_value_t = 100_000)]
+    items: usize,
+    /// RNG seed; the same seed generates the same items
+    #[arg(long, default_value_t = DEFAULT_SEED)]
+    seed: u64,
+    /// Organization to fill and query; a new one is created if omitted
+    #[arg(long)]
+    org_id: Option<Uuid>,
+    /// Times each query is run
+    #[arg(long, default_value_t = 20)]
+    iterations: usize,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let pool = PgPool::connect(&cli.database_url).await?;
+
+    let org_id = match cli.org_id {
+        Some(org_id) => org_id,
+        None => create_bench_org(&pool).await?,
+    };
+
+    if cli.items > 0 {
+        println!("Generating {} items in {}...", cli.items, org_id);
+        let report = ItemGenerator::new(&pool, cli.seed)
+            .with_progress(true)
+            .generate(org_id, cli.items)
+            .await?;
+        println!(
+            "✓ Generated {} items with {} tags in {:.1}s ({:.0} items/s)",
+            report.items,
+            report.tags,
+            report.elapsed.as_secs_f64(),
+            report.items as f64 / report.elapsed.as_secs_f64()
+        );
+        for (state, count) in &report.by_state {
+            println!("    {:<12} {}", state, count);
+        }
+        for (kind, count) in &report.by_kind {
+            println!("    {:<12} {}", kind, count);
+        }
+        // Fresh statistics, so plans reflect the new rows
+        sqlx::query("ANALYZE").execute(&pool).await?;
+    }
+
+    let secret = Uuid::new_v4().to_string();
+    let token = member_token(&pool, org_id, &secret).await?;
+    let app = build_router(AppState::new(pool.clone(), secret));
+
+    println!(
+        "\n{:<40} {:>9} {:>9} {:>9} {:>9}",
+        "query", "min ms", "median", "p95", "max"
+    );
+    for query in QUERIES {
+        let uri = format!("/organizations/{}/{}", org_id, query);
+        let mut timings = Vec::with_capacity(cli.iterations);
+        // The first run warms caches and is not counted
+        for run in 0..=cli.iterations {
+            let request = Request::get(&uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())?;
+            let started = Instant::now();
+            let response = app.clone().oneshot(request).await?;
+            let status = response.status();
+            axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            if !status.is_success() {
+                bail!("GET {} returned {}", uri, status);
+            }
+            if run > 0 {
+                timings.push(started.elapsed());
+            }
+        }
+        print_timings(query, &mut timings);
+    }
+    Ok(())
+}
+
+async fn create_bench_org(pool: &PgPool) -> Result<Uuid> {
+    let org_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO organizations (name, description) VALUES ($1, $2) RETURNING id",
+    )
+    .bind(format!(
+        "Bench {}",
+        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S")
+    ))
+    .bind("Synthetic items generated by the bench tool")
+    .fetch_one(pool)
+    .await?;
+
+    let user_id: Uuid =
+        sqlx::query_scalar("INSERT INTO users (name, identity) VALUES ($1, $2) RETURNING id")
+            .bind("Bench")
+            .bind(format!("bench-{}@bench.example", org_id))
+            .fetch_one(pool)
+            .await?;
+
+    let roles = vec![UserRole::User, UserRole::Admin];
+    sqlx::query(
+        "INSERT INTO user_organizations (user_id, organization_id, roles, permissions)
+         VALUES ($1, $2, $3, $4)",
+    )
+    .bind(user_id)
+    .bind(org_id)
+    .bind(&roles)
+    .bind(Permissions::for_roles(&roles).into_vec())
+    .execute(pool)
+    .await?;
+
+    for name in ["Living Room", "Bedroom", "Storage Unit", "Office", "Garage"] {
+        sqlx::query("INSERT INTO locations (organization_id, name) VALUES ($1, $2)")
+            .bind(org_id)
+            .bind(name)
+            .execute(pool)
+            .await?;
+    }
+    for name in [
+        "Jazz Collection",
+        "Rock Classics",
+        "Rare Items",
+        "To Digitise",
+    ] {
+        sqlx::query("INSERT INTO collections (organization_id, name) VALUES ($1, $2)")
+            .bind(org_id)
+            .bind(name)
+            .execute(pool)
+            .await?;
+    }
+    Ok(org_id)
+}
+
+/// An access token for the organization's first member
+async fn member_token(pool: &PgPool, org_id: Uuid, secret: &str) -> Result<String> {
+    let (user_id, identity, roles): (Uuid, String, Vec<UserRole>) = sqlx::query_as(
+        "SELECT u.id, u.identity, uo.roles
+         FROM user_organizations uo JOIN users u ON u.id = uo.user_id
+         WHERE uo.organization_id = $1
+         ORDER BY uo.created_at LIMIT 1",
+    )
+    .bind(org_id)
+    .fetch_optional(pool)
+    .await?
+    .context("The organization has no members to query as")?;
+
+    let permissions = Permissions::for_roles(&roles);
+    TokenManager::new(secret).generate_token(
+        user_id,
+        identity,
+        org_id,
+        roles,
+        permissions,
+        Uuid::new_v4(),
+        60,
+    )
+}
+
+fn print_timings(query: &str, timings: &mut [Duration]) {
+    if timings.is_empty() {
+        return;
+    }
+    timings.sort();
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    let p95 = (timings.len() * 95).div_ceil(100) - 1;
+    println!(
+        "{:<40} {:>9.2} {:>9.2} {:>9.2} {:>9.2}",
+        query,
+        ms(timings[0]),
+        ms(timings[timings.len() / 2]),
+        ms(timings[p95]),
+        ms(timings[timings.len() - 1])
+    );
+}
@@ -1,17 +1,27 @@
-//! CLZ CSV Importer - imports movies/DVDs from CLZ export files into vostuff
+//! CLZ CSV Importer - imports movies (DVDs, Blu-rays and 4K discs) from CLZ
+//! export files into vostuff
 //!
 //! This tool reads CSV files exported from CLZ applications and creates items
-//! in vostuff via the REST API.
+//! in vostuff via the REST API. Other CSV layouts are imported with
+//! `csv-importer` and a column mapping.
+//!
+//! Records already in vostuff, with the same barcode or the same title and
+//! kind, are skipped (or updated with `--on-duplicate update`), so an import
+//! can safely be run again. Records that fail are appended to a journal file,
+//! and `--resume-from` starts an interrupted import part way through.
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result};
 use chrono::NaiveDate;
-use clap::Parser;
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::env;
-use std::io::{self, Write};
-use std::path::PathBuf;
+use clap::{Parser, ValueEnum};
+use serde::Deserialize;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
+use vostuff_api::import_client::{
+    ApiClient, CreateItemRequest, ExistingItems, ImportStats, Outcome, UpdateItemRequest,
+    read_password,
+};
 
 /// CLZ CSV Importer - Import movies/DVDs from CLZ export files into vostuff
 #[derive(Parser, Debug)]
@@ -38,10 +48,83 @@ struct Args {
     #[arg(long)]
     dry_run: bool,
 
+    /// What to do with records already in vostuff
+    #[arg(long, value_enum, default_value = "skip")]
+    on_duplicate: OnDuplicate,
+
+    /// Record number (1-based) to start from, e.g. to carry on after an
+    /// interrupted import
+    #[arg(long, default_value_t = 1)]
+    resume_from: usize,
+
+    /// File that records which fail to import are appended to, as JSON lines
+    /// [default: the CSV file with a .failed.jsonl extension]
+    #[arg(long)]
+    journal: Option<PathBuf>,
+
     /// CSV file to import
     csv_file: PathBuf,
 }
 
+/// What to do with a record already in vostuff: one with the same barcode,
+/// or of the same kind with the same title
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OnDuplicate {
+    /// Leave the existing item alone
+    Skip,
+    /// Set the existing item's notes, acquired date, barcode and disc format
+    /// from the record
+    Update,
+}
+
+/// Failed records, appended as they happen so they survive a crash. The file
+/// is only created once a record fails.
+struct Journal {
+    path: PathBuf,
+    file: Option<File>,
+    entries: usize,
+}
+
+impl Journal {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            file: None,
+            entries: 0,
+        }
+    }
+
+    fn failed(&mut self, record: usize, title: &str, message: &str) -> Result<()> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => self.file.insert(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)
+                    .with_context(|| {
+                        format!("Failed to open journal file: {}", self.path.display())
+                    })?,
+            ),
+        };
+        let entry = serde_json::json!({
+            "record": record,
+            "title": title,
+            "error": message,
+        });
+        writeln!(file, "{}", entry)
+            .and_then(|_| file.flush())
+            .with_context(|| format!("Failed to write journal file: {}", self.path.display()))?;
+        self.entries += 1;
+        Ok(())
+    }
+}
+
+/// The journal's default path, beside the CSV file
+fn default_journal(csv_file: &Path) -> PathBuf {
+    csv_file.with_extension("failed.jsonl")
+}
+
 /// CSV record from CLZ export
 #[derive(Debug, Deserialize)]
 struct ClzRecord {
@@ -61,76 +144,8 @@ struct ClzRecord {
     distributor: Option<String>,
     #[serde(rename = "Added Date")]
     added_date: Option<String>,
-}
-
-/// Login request
-#[derive(Serialize)]
-struct LoginRequest {
-    identity: String,
-    password: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    organization_id: Option<Uuid>,
-}
-
-/// Login response (successful)
-#[derive(Deserialize)]
-struct LoginResponse {
-    token: String,
-}
-
-/// Organization selection response (multi-org user)
-#[derive(Deserialize)]
-struct OrgSelectionResponse {
-    organizations: Vec<OrganizationInfo>,
-    follow_on_token: String,
-}
-
-#[derive(Deserialize)]
-struct OrganizationInfo {
-    id: Uuid,
-    name: String,
-}
-
-/// Select org request
-#[derive(Serialize)]
-struct SelectOrgRequest {
-    follow_on_token: String,
-    organization_id: Uuid,
-}
-
-/// Kind summary from the kinds API
-#[derive(Deserialize)]
-struct KindSummary {
-    id: Uuid,
-    name: String,
-}
-
-/// Create item request
-#[derive(Serialize)]
-struct CreateItemRequest {
-    kind_id: Uuid,
-    name: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    notes: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    date_acquired: Option<NaiveDate>,
-}
-
-/// API error response
-#[derive(Deserialize)]
-struct ErrorResponse {
-    #[allow(dead_code)]
-    error: String,
-    message: String,
-}
-
-/// Import statistics
-#[derive(Default)]
-struct ImportStats {
-    total: usize,
-    imported: usize,
-    skipped: usize,
-    failed: usize,
+    #[serde(rename = "Barcode", default)]
+    barcode: Option<String>,
 }
 
 #[tokio::main]
@@ -142,6 +157,13 @@ async fn main() -> Result<()> {
     let records = read_csv(&args.csv_file)?;
     println!("Found {} records", records.len());
 
+    if args.resume_from == 0 || args.resume_from > records.len().max(1) {
+        anyhow::bail!(
+            "--resume-from must be between 1 and {} (the number of records)",
+            records.len()
+        );
+    }
+
     if args.dry_run {
         println!("\n=== DRY RUN MODE ===");
         println!("Validating records without creating items...\n");
@@ -150,64 +172,62 @@ async fn main() -> Result<()> {
     }
 
     // Get password (only needed for actual import)
-    let password = get_password(&args)?;
-
-    // Create HTTP client
-    let client = Client::new();
+    let password = read_password(args.password.as_deref())?;
 
     // Authenticate
     println!("\nAuthenticating as {}...", args.username);
-    let (token, org_id) = authenticate(
-        &client,
-        &args.api_url,
-        &args.username,
-        &password,
-        args.org_id,
-    )
-    .await?;
+    let client = ApiClient::login(&args.api_url, &args.username, &password, args.org_id).await?;
     println!("Authentication successful!");
 
-    // Look up DVD kind
-    println!("Looking up DVD kind...");
-    let dvd_kind_id = lookup_kind_id(&client, &args.api_url, &token, org_id, "dvd").await?;
+    // Look up DVD and Blu-ray kinds
+    println!("Looking up DVD and Blu-ray kinds...");
+    let dvd_kind_id = client.kind_id("dvd").await?;
+    let bluray_kind_id = client.kind_id("bluray").await?;
     println!("DVD kind id: {}", dvd_kind_id);
+    println!("Blu-ray kind id: {}", bluray_kind_id);
+
+    // Find what is already there, so it is not created twice
+    println!("Fetching existing DVDs and Blu-rays...");
+    let existing = client.items("dvd,bluray").await?;
+    println!("Found {} existing items", existing.len());
+    let existing = ExistingItems::new(existing);
+
+    let journal_path = args
+        .journal
+        .clone()
+        .unwrap_or_else(|| default_journal(&args.csv_file));
+    let mut journal = Journal::new(journal_path);
 
     // Import items
     println!("\nImporting items...\n");
     let stats = import_items(
         &client,
-        &args.api_url,
-        &token,
-        org_id,
-        dvd_kind_id,
+        &args,
+        KindIds {
+            dvd: dvd_kind_id,
+            bluray: bluray_kind_id,
+        },
+        &existing,
         &records,
+        &mut journal,
     )
     .await?;
 
-    // Print summary
-    println!("\n=== Import Summary ===");
-    println!("Total records: {}", stats.total);
-    println!("Imported:      {}", stats.imported);
-    println!("Skipped:       {}", stats.skipped);
-    println!("Failed:        {}", stats.failed);
+    stats.print_summary();
+    if journal.entries > 0 {
+        println!(
+            "\n{} failed records were written to {}",
+            journal.entries,
+            journal.path.display()
+        );
+    }
 
     Ok(())
 }
 
-/// Get password from argument, environment variable, or interactive prompt
-fn get_password(args: &Args) -> Result<String> {
-    if let Some(password) = &args.password {
-        return Ok(password.clone());
-    }
-
-    if let Ok(password) = env::var("VOSTUFF_PASSWORD") {
-        return Ok(password);
-    }
-
-    print!("Password: ");
-    io::stdout().flush()?;
-    let password = rpassword::read_password()?;
-    Ok(password)
+struct KindIds {
+    dvd: Uuid,
+    bluray: Uuid,
 }
 
 /// Read and parse CSV file
@@ -274,169 +294,26 @@ fn parse_clz_date(date_str: &str) -> Option<NaiveDate> {
     NaiveDate::parse_from_str(date_str.trim(), "%b %d, %Y").ok()
 }
 
-/// Authenticate with the API
-async fn authenticate(
-    client: &Client,
-    api_url: &str,
-    username: &str,
-    password: &str,
-    org_id: Option<Uuid>,
-) -> Result<(String, Uuid)> {
-    let login_req = LoginRequest {
-        identity: username.to_string(),
-        password: password.to_string(),
-        organization_id: org_id,
-    };
-
-    let resp = client
-        .post(format!("{}/api/auth/login", api_url))
-        .json(&login_req)
-        .send()
-        .await
-        .context("Failed to connect to API server")?;
-
-    let status = resp.status();
-    let body = resp.text().await?;
-
-    if !status.is_success() {
-        let error: ErrorResponse = serde_json::from_str(&body).unwrap_or_else(|_| ErrorResponse {
-            error: "unknown".to_string(),
-            message: body.clone(),
-        });
-        bail!("Authentication failed: {}", error.message);
-    }
-
-    // Try to parse as LoginResponse first (single org or org_id provided)
-    if let Ok(login_resp) = serde_json::from_str::<LoginResponse>(&body) {
-        // Extract org_id from token claims (we need to get it from the response)
-        // For now, we need the org_id to be provided if not in the response
-        if let Some(org_id) = org_id {
-            return Ok((login_resp.token, org_id));
-        }
-        // If org_id wasn't provided but we got a token, the user has only one org
-        // We need to parse the response differently
-        #[derive(Deserialize)]
-        struct FullLoginResponse {
-            token: String,
-            user: UserInfo,
-        }
-        #[derive(Deserialize)]
-        struct UserInfo {
-            organization: OrgInfo,
-        }
-        #[derive(Deserialize)]
-        struct OrgInfo {
-            id: Uuid,
-        }
-
-        let full_resp: FullLoginResponse =
-            serde_json::from_str(&body).context("Failed to parse login response")?;
-        return Ok((full_resp.token, full_resp.user.organization.id));
-    }
-
-    // Parse as org selection response (multi-org user)
-    let org_selection: OrgSelectionResponse =
-        serde_json::from_str(&body).context("Failed to parse org selection response")?;
-
-    println!("\nUser belongs to multiple organizations:");
-    for (i, org) in org_selection.organizations.iter().enumerate() {
-        println!("  {}. {} ({})", i + 1, org.name, org.id);
-    }
-
-    // Prompt for selection
-    print!(
-        "\nSelect organization (1-{}): ",
-        org_selection.organizations.len()
-    );
-    io::stdout().flush()?;
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    let selection: usize = input.trim().parse().context("Invalid selection")?;
-
-    if selection < 1 || selection > org_selection.organizations.len() {
-        bail!("Invalid selection: {}", selection);
-    }
-
-    let selected_org = &org_selection.organizations[selection - 1];
-    println!("Selected: {}", selected_org.name);
-
-    // Call select-org endpoint
-    let select_req = SelectOrgRequest {
-        follow_on_token: org_selection.follow_on_token,
-        organization_id: selected_org.id,
-    };
-
-    let resp = client
-        .post(format!("{}/api/auth/select-org", api_url))
-        .json(&select_req)
-        .send()
-        .await
-        .context("Failed to select organization")?;
-
-    let status = resp.status();
-    let body = resp.text().await?;
-
-    if !status.is_success() {
-        let error: ErrorResponse = serde_json::from_str(&body).unwrap_or_else(|_| ErrorResponse {
-            error: "unknown".to_string(),
-            message: body.clone(),
-        });
-        bail!("Organization selection failed: {}", error.message);
-    }
-
-    let login_resp: LoginResponse = serde_json::from_str(&body)
-        .context("Failed to parse login response after org selection")?;
-
-    Ok((login_resp.token, selected_org.id))
-}
-
-/// Look up the UUID for a kind by name
-async fn lookup_kind_id(
-    client: &Client,
-    api_url: &str,
-    token: &str,
-    org_id: Uuid,
-    kind_name: &str,
-) -> Result<Uuid> {
-    let resp = client
-        .get(format!("{}/api/organizations/{}/kinds", api_url, org_id))
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await
-        .context("Failed to fetch kinds")?;
-
-    if !resp.status().is_success() {
-        bail!("Failed to fetch kinds: {}", resp.status());
-    }
-
-    let kinds: Vec<KindSummary> = resp.json().await.context("Failed to parse kinds")?;
-
-    kinds
-        .into_iter()
-        .find(|k| k.name == kind_name)
-        .map(|k| k.id)
-        .ok_or_else(|| anyhow::anyhow!("Kind '{}' not found in organisation", kind_name))
-}
-
-/// Import items into vostuff
+/// Import items into vostuff, from the `--resume-from` record on
 async fn import_items(
-    client: &Client,
-    api_url: &str,
-    token: &str,
-    org_id: Uuid,
-    kind_id: Uuid,
+    client: &ApiClient,
+    args: &Args,
+    kinds: KindIds,
+    existing: &ExistingItems,
     records: &[ClzRecord],
+    journal: &mut Journal,
 ) -> Result<ImportStats> {
     let mut stats = ImportStats {
-        total: records.len(),
+        total: records.len() - (args.resume_from - 1),
         ..Default::default()
     };
 
-    for (i, record) in records.iter().enumerate() {
+    for (i, record) in records.iter().enumerate().skip(args.resume_from - 1) {
+        let progress = format!("[{}/{}]", i + 1, records.len());
+
         // Skip records with empty titles
         if record.title.trim().is_empty() {
-            println!("[{}/{}] Skipped: empty title", i + 1, records.len());
+            println!("{} Skipped: empty title", progress);
             stats.skipped += 1;
             continue;
         }
@@ -447,60 +324,75 @@ async fn import_items(
         // Parse date
         let date_acquired = record.added_date.as_ref().and_then(|d| parse_clz_date(d));
 
-        // Create item request
-        let create_req = CreateItemRequest {
-            kind_id,
-            name: record.title.clone(),
-            notes,
-            date_acquired,
+        // Blu-ray and 4K discs get their own kind; everything else is a DVD
+        let disc_format = record.format.as_deref().and_then(bluray_format);
+        let kind_id = if disc_format.is_some() {
+            kinds.bluray
+        } else {
+            kinds.dvd
         };
-
-        // Send request
-        let resp = client
-            .post(format!("{}/api/organizations/{}/items", api_url, org_id))
-            .header("Authorization", format!("Bearer {}", token))
-            .json(&create_req)
-            .send()
-            .await;
-
-        match resp {
-            Ok(response) => {
-                if response.status().is_success() {
-                    println!("[{}/{}] Imported: {}", i + 1, records.len(), record.title);
-                    stats.imported += 1;
-                } else {
-                    let error_body = response.text().await.unwrap_or_default();
-                    let error: ErrorResponse =
-                        serde_json::from_str(&error_body).unwrap_or_else(|_| ErrorResponse {
-                            error: "unknown".to_string(),
-                            message: error_body,
-                        });
-                    eprintln!(
-                        "[{}/{}] Failed: {} - {}",
-                        i + 1,
-                        records.len(),
-                        record.title,
-                        error.message
-                    );
-                    stats.failed += 1;
-                }
+        let barcode = record
+            .barcode
+            .as_ref()
+            .map(|b| b.trim().to_string())
+            .filter(|b| !b.is_empty());
+        let soft_fields = disc_format.map(|f| serde_json::json!({ "disc_format": f }));
+
+        let outcome = match existing.find(kind_id, &record.title, barcode.as_deref()) {
+            Some(_) if args.on_duplicate == OnDuplicate::Skip => {
+                let outcome = Outcome::Skipped("already in vostuff".to_string());
+                stats.record(&progress, &record.title, &outcome);
+                outcome
             }
-            Err(e) => {
-                eprintln!(
-                    "[{}/{}] Failed: {} - {}",
-                    i + 1,
-                    records.len(),
-                    record.title,
-                    e
-                );
-                stats.failed += 1;
+            Some(item_id) => {
+                let update_req = UpdateItemRequest {
+                    notes,
+                    date_acquired,
+                    barcode,
+                    soft_fields,
+                };
+                client
+                    .update_item(item_id, &record.title, &update_req, &progress, &mut stats)
+                    .await
+            }
+            None => {
+                let create_req = CreateItemRequest {
+                    kind_id,
+                    name: record.title.clone(),
+                    description: None,
+                    notes,
+                    date_acquired,
+                    barcode,
+                    catalog_code: None,
+                    soft_fields,
+                    tags: Vec::new(),
+                };
+                client.create_item(&create_req, &progress, &mut stats).await
             }
+        };
+        if let Outcome::Failed(message) = outcome {
+            journal.failed(i + 1, &record.title, &message)?;
         }
     }
 
     Ok(stats)
 }
 
+/// The bluray kind's `disc_format` for a CLZ format such as "Blu-ray" or
+/// "4K Ultra HD + Blu-ray"; None for DVDs and anything else
+fn bluray_format(format: &str) -> Option<&'static str> {
+    let format = format.to_lowercase();
+    let bluray = format.contains("blu-ray") || format.contains("bluray");
+    let uhd = format.contains("4k") || format.contains("uhd") || format.contains("ultra hd");
+    match (uhd, bluray) {
+        (true, true) => Some("uhd_bluray"),
+        (true, false) => Some("uhd"),
+        (false, true) if format.contains("3d") => Some("bluray_3d"),
+        (false, true) => Some("bluray"),
+        (false, false) => None,
+    }
+}
+
 /// Build notes field from CLZ record metadata
 fn build_notes(record: &ClzRecord) -> Option<String> {
     let mut parts = Vec::new();
@@ -0,0 +1,265 @@
+//! CSV Importer - imports any CSV file into vostuff through a column mapping
+//!
+//! LibraryThing, Goodreads and homemade spreadsheets all lay out their
+//! columns differently, so a mapping file says where each column goes: an
+//! item field, a soft field of the import kind, the notes or the tags. See
+//! `vostuff_api::import_mapping` for the format, as TOML or JSON.
+//!
+//! Run with `--suggest-mapping mapping.toml --kind book` to get a mapping
+//! guessed from the headers, adjust it, then import with
+//! `--mapping mapping.toml`. Items are created via the REST API.
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use vostuff_api::import_client::{ApiClient, CreateItemRequest, ImportStats, read_password};
+use vostuff_api::import_mapping::{
+    ImportMapping, SoftFieldSpec, parse_import_date, suggest_mapping, typed_soft_fields,
+};
+
+/// CSV Importer - Import CSV files into vostuff using a column mapping
+#[derive(Parser, Debug)]
+#[command(name = "csv-importer")]
+#[command(about = "Import CSV files into vostuff using a column mapping")]
+struct Args {
+    /// User email for authentication
+    #[arg(short, long)]
+    username: String,
+
+    /// Password (optional, uses VOSTUFF_PASSWORD env var or interactive prompt)
+    #[arg(short, long)]
+    password: Option<String>,
+
+    /// Organization ID (optional, will prompt if user has multiple orgs)
+    #[arg(short, long)]
+    org_id: Option<Uuid>,
+
+    /// API base URL
+    #[arg(long, default_value = "http://localhost:8080")]
+    api_url: String,
+
+    /// Parse and validate without creating items
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Kind to suggest a column mapping for
+    #[arg(long, default_value = "misc")]
+    kind: String,
+
+    /// Write a column mapping guessed from the CSV headers to this file
+    /// (.toml or .json) and exit
+    #[arg(long, conflicts_with = "mapping")]
+    suggest_mapping: Option<PathBuf>,
+
+    /// Column mapping file (.toml or .json)
+    #[arg(long, required_unless_present = "suggest_mapping")]
+    mapping: Option<PathBuf>,
+
+    /// CSV file to import
+    csv_file: PathBuf,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let (headers, rows) = read_csv_rows(&args.csv_file)?;
+    println!("Found {} records", rows.len());
+
+    let mapping = match &args.mapping {
+        Some(path) => {
+            let mapping = read_mapping(path)?;
+            mapping
+                .validate(&headers)
+                .map_err(|e| anyhow::anyhow!("Invalid mapping: {}", e))?;
+            Some(mapping)
+        }
+        None => None,
+    };
+
+    if let Some(mapping) = &mapping
+        && args.dry_run
+    {
+        println!("\n=== DRY RUN MODE ===");
+        validate_mapped_rows(mapping, &headers, &rows);
+        return Ok(());
+    }
+
+    let password = read_password(args.password.as_deref())?;
+
+    println!("\nAuthenticating as {}...", args.username);
+    let client = ApiClient::login(&args.api_url, &args.username, &password, args.org_id).await?;
+
+    let kind_name = mapping.as_ref().map_or(&args.kind, |m| &m.kind).clone();
+    let kind = client.kind(&kind_name).await?;
+
+    let Some(mapping) = mapping else {
+        let soft_fields: Vec<(String, Option<String>)> = kind
+            .fields
+            .iter()
+            .map(|f| (f.name.clone(), f.display_name.clone()))
+            .collect();
+        let suggested = suggest_mapping(&kind_name, &headers, &soft_fields);
+
+        println!("\nSuggested mapping:");
+        for col in &suggested.columns {
+            match col.confidence {
+                Some(c) => println!("  {:<30} -> {} ({:.0}%)", col.column, col.target, c * 100.0),
+                None => println!("  {:<30} -> {}", col.column, col.target),
+            }
+        }
+
+        // Only reachable with --suggest-mapping set
+        let path = args.suggest_mapping.as_ref().expect("suggest_mapping path");
+        write_mapping(path, &suggested)?;
+        println!(
+            "\nWrote {}; review it, then import with --mapping {}",
+            path.display(),
+            path.display()
+        );
+        return Ok(());
+    };
+
+    let fields: Vec<SoftFieldSpec> = kind
+        .fields
+        .iter()
+        .map(|f| SoftFieldSpec {
+            name: f.name.clone(),
+            field_type: f.field_type.clone(),
+            enum_values: f
+                .enum_values
+                .iter()
+                .map(|ev| (ev.value.clone(), ev.display_value.clone()))
+                .collect(),
+        })
+        .collect();
+
+    println!("\nImporting items as {}...\n", kind_name);
+    let mut stats = ImportStats {
+        total: rows.len(),
+        ..Default::default()
+    };
+
+    for (i, values) in rows.iter().enumerate() {
+        let progress = format!("[{}/{}]", i + 1, rows.len());
+        let row = mapping.apply(&headers, values);
+
+        let Some(name) = row.name else {
+            println!("{} Skipped: empty name", progress);
+            stats.skipped += 1;
+            continue;
+        };
+
+        let soft_fields = match typed_soft_fields(&row.soft_fields, &fields) {
+            Ok(soft_fields) => soft_fields,
+            Err(e) => {
+                eprintln!("{} Failed: {} - {}", progress, name, e);
+                stats.failed += 1;
+                continue;
+            }
+        };
+
+        let create_req = CreateItemRequest {
+            kind_id: kind.id,
+            name,
+            description: row.description,
+            notes: row.notes,
+            date_acquired: row.date_acquired.as_deref().and_then(parse_import_date),
+            barcode: row.barcode,
+            catalog_code: row.catalog_code,
+            soft_fields: (!soft_fields.is_empty()).then(|| soft_fields.into()),
+            tags: row.tags,
+        };
+        client.create_item(&create_req, &progress, &mut stats).await;
+    }
+
+    stats.print_summary();
+    Ok(())
+}
+
+/// Whether a mapping file is TOML rather than JSON, by its extension
+fn is_toml(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("toml"))
+}
+
+/// Read a mapping file
+fn read_mapping(path: &Path) -> Result<ImportMapping> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to open mapping file: {}", path.display()))?;
+    if is_toml(path) {
+        toml::from_str(&text).context("Failed to parse mapping file")
+    } else {
+        serde_json::from_str(&text).context("Failed to parse mapping file")
+    }
+}
+
+/// Write a mapping file
+fn write_mapping(path: &Path, mapping: &ImportMapping) -> Result<()> {
+    let text = if is_toml(path) {
+        toml::to_string(mapping)?
+    } else {
+        serde_json::to_string_pretty(mapping)?
+    };
+    std::fs::write(path, text)
+        .with_context(|| format!("Failed to write mapping file: {}", path.display()))
+}
+
+/// Read a CSV file as its headers and raw rows
+fn read_csv_rows(path: &PathBuf) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+    let mut reader = csv::Reader::from_path(path)
+        .with_context(|| format!("Failed to open CSV file: {}", path.display()))?;
+
+    let headers = reader
+        .headers()
+        .context("Failed to read CSV headers")?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+
+    let mut rows = Vec::new();
+    for (line_num, result) in reader.records().enumerate() {
+        match result {
+            Ok(record) => rows.push(record.iter().map(|v| v.to_string()).collect()),
+            Err(e) => {
+                eprintln!("Warning: Skipping line {}: {}", line_num + 2, e);
+            }
+        }
+    }
+
+    Ok((headers, rows))
+}
+
+/// Validate mapped rows without creating items (dry run mode)
+fn validate_mapped_rows(mapping: &ImportMapping, headers: &[String], rows: &[Vec<String>]) {
+    let mut valid = 0;
+    let mut invalid = 0;
+
+    for (i, values) in rows.iter().enumerate() {
+        let row = mapping.apply(headers, values);
+        let mut issues = Vec::new();
+        if row.name.is_none() {
+            issues.push("Empty name".to_string());
+        }
+        if let Some(date) = &row.date_acquired
+            && parse_import_date(date).is_none()
+        {
+            issues.push(format!("Invalid date format: {}", date));
+        }
+
+        if issues.is_empty() {
+            valid += 1;
+        } else {
+            invalid += 1;
+            println!("Record {}: \"{}\"", i + 1, row.name.unwrap_or_default());
+            for issue in issues {
+                println!("  - {}", issue);
+            }
+        }
+    }
+
+    println!("\nValidation complete:");
+    println!("  Valid:   {}", valid);
+    println!("  Invalid: {}", invalid);
+}
@@ -0,0 +1,541 @@
+//! Discogs Importer - imports a Discogs collection into vostuff
+//!
+//! This tool reads a user's collection through the Discogs API, using a
+//! personal access token (Discogs > Settings > Developers), and creates items
+//! in vostuff via the REST API.
+//!
+//! Vinyl, CDs and cassettes become items of those kinds with their details in
+//! soft fields: size, speed, channels, discs, the media and sleeve gradings
+//! from the collection's condition fields, and the label, year and edition
+//! as the record's pressing. Other formats are skipped.
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDate};
+use clap::Parser;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::time::Duration;
+use uuid::Uuid;
+use vostuff_api::import_client::{ApiClient, CreateItemRequest, ImportStats, read_password};
+
+const DISCOGS_API: &str = "https://api.discogs.com";
+
+/// Collection releases fetched per request; the most Discogs allows
+const PER_PAGE: u32 = 100;
+
+/// Discogs Importer - Import a Discogs collection into vostuff
+#[derive(Parser, Debug)]
+#[command(name = "discogs-importer")]
+#[command(about = "Import a Discogs collection into vostuff")]
+struct Args {
+    /// User email for authentication
+    #[arg(short, long)]
+    username: String,
+
+    /// Password (optional, uses VOSTUFF_PASSWORD env var or interactive prompt)
+    #[arg(short, long)]
+    password: Option<String>,
+
+    /// Organization ID (optional, will prompt if user has multiple orgs)
+    #[arg(short, long)]
+    org_id: Option<Uuid>,
+
+    /// API base URL
+    #[arg(long, default_value = "http://localhost:8080")]
+    api_url: String,
+
+    /// Discogs personal access token
+    #[arg(long, env = "DISCOGS_TOKEN", hide_env_values = true)]
+    discogs_token: String,
+
+    /// Fetch and map the collection without creating items
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// GET /oauth/identity
+#[derive(Deserialize)]
+struct Identity {
+    username: String,
+}
+
+/// GET /users/{username}/collection/fields
+#[derive(Deserialize)]
+struct CollectionFields {
+    fields: Vec<CollectionField>,
+}
+
+#[derive(Deserialize)]
+struct CollectionField {
+    id: u64,
+    name: String,
+}
+
+/// A page of GET /users/{username}/collection/folders/0/releases
+#[derive(Deserialize)]
+struct CollectionPage {
+    pagination: Pagination,
+    releases: Vec<CollectionRelease>,
+}
+
+#[derive(Deserialize)]
+struct Pagination {
+    pages: u32,
+    items: usize,
+}
+
+#[derive(Deserialize)]
+struct CollectionRelease {
+    date_added: Option<String>,
+    basic_information: BasicInformation,
+    /// Values of the collection's custom fields, such as Media Condition
+    #[serde(default)]
+    notes: Vec<FieldValue>,
+}
+
+#[derive(Deserialize)]
+struct FieldValue {
+    field_id: u64,
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct BasicInformation {
+    title: String,
+    year: Option<u32>,
+    #[serde(default)]
+    artists: Vec<Artist>,
+    #[serde(default)]
+    labels: Vec<Label>,
+    #[serde(default)]
+    formats: Vec<Format>,
+}
+
+#[derive(Deserialize)]
+struct Artist {
+    name: String,
+    #[serde(default)]
+    join: String,
+}
+
+#[derive(Deserialize)]
+struct Label {
+    name: String,
+    catno: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Format {
+    name: String,
+    qty: Option<String>,
+    text: Option<String>,
+    #[serde(default)]
+    descriptions: Vec<String>,
+}
+
+/// Ids of the collection fields holding gradings
+struct ConditionFields {
+    media: Option<u64>,
+    sleeve: Option<u64>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let discogs = Discogs::new(&args.discogs_token)?;
+
+    println!("Connecting to Discogs...");
+    let discogs_user = discogs.identity().await?;
+    println!("Discogs user: {}", discogs_user);
+    let conditions = discogs.condition_fields(&discogs_user).await?;
+    let releases = discogs.collection(&discogs_user).await?;
+    println!("Found {} releases", releases.len());
+
+    if args.dry_run {
+        println!("\n=== DRY RUN MODE ===");
+        println!("Mapping releases without creating items...\n");
+        for (i, release) in releases.iter().enumerate() {
+            let progress = format!("[{}/{}]", i + 1, releases.len());
+            match map_release(release, &conditions) {
+                Some((kind, item)) => println!(
+                    "{} {} \"{}\" {}",
+                    progress,
+                    kind,
+                    item.name,
+                    Value::Object(item.soft_fields)
+                ),
+                None => println!(
+                    "{} Skipped: \"{}\" ({})",
+                    progress,
+                    release.basic_information.title,
+                    format_names(&release.basic_information)
+                ),
+            }
+        }
+        return Ok(());
+    }
+
+    // Get password (only needed for actual import)
+    let password = read_password(args.password.as_deref())?;
+
+    // Authenticate
+    println!("\nAuthenticating as {}...", args.username);
+    let client = ApiClient::login(&args.api_url, &args.username, &password, args.org_id).await?;
+    println!("Authentication successful!");
+
+    println!("Looking up vinyl, CD and cassette kinds...");
+    let vinyl_kind_id = client.kind_id("vinyl").await?;
+    let cd_kind_id = client.kind_id("cd").await?;
+    let cassette_kind_id = client.kind_id("cassette").await?;
+
+    println!("\nImporting items...\n");
+    let mut stats = ImportStats {
+        total: releases.len(),
+        ..Default::default()
+    };
+    for (i, release) in releases.iter().enumerate() {
+        let progress = format!("[{}/{}]", i + 1, releases.len());
+        let Some((kind, item)) = map_release(release, &conditions) else {
+            println!(
+                "{} Skipped: {} ({})",
+                progress,
+                release.basic_information.title,
+                format_names(&release.basic_information)
+            );
+            stats.skipped += 1;
+            continue;
+        };
+
+        let kind_id = match kind {
+            "vinyl" => vinyl_kind_id,
+            "cd" => cd_kind_id,
+            _ => cassette_kind_id,
+        };
+        let create_req = CreateItemRequest {
+            kind_id,
+            name: item.name,
+            description: item.description,
+            notes: None,
+            date_acquired: item.date_acquired,
+            barcode: None,
+            catalog_code: item.catalog_code,
+            soft_fields: (!item.soft_fields.is_empty()).then(|| item.soft_fields.into()),
+            tags: Vec::new(),
+        };
+        client.create_item(&create_req, &progress, &mut stats).await;
+    }
+
+    stats.print_summary();
+    Ok(())
+}
+
+/// Discogs API client authenticated with a personal access token
+struct Discogs {
+    client: Client,
+    token: String,
+}
+
+impl Discogs {
+    fn new(token: &str) -> Result<Self> {
+        // Discogs refuses requests without an identifying user agent
+        let client = Client::builder()
+            .user_agent(concat!(
+                "VOStuff-DiscogsImporter/",
+                env!("CARGO_PKG_VERSION"),
+                " +https://github.com/FrankTaylorLieder/vostuff"
+            ))
+            .timeout(Duration::from_secs(30))
+            .build()?;
+        Ok(Self {
+            client,
+            token: token.to_string(),
+        })
+    }
+
+    /// GET a Discogs API path, waiting out the rate limit (60 requests a
+    /// minute) when it is reached
+    async fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T> {
+        loop {
+            let resp = self
+                .client
+                .get(format!("{}{}", DISCOGS_API, path))
+                .header("Authorization", format!("Discogs token={}", self.token))
+                .send()
+                .await
+                .context("Failed to connect to Discogs")?;
+
+            match resp.status() {
+                StatusCode::TOO_MANY_REQUESTS => {
+                    println!("Discogs rate limit reached, waiting a minute...");
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                }
+                StatusCode::UNAUTHORIZED => bail!("Discogs rejected the token"),
+                status if !status.is_success() => {
+                    bail!("Discogs request {} failed: {}", path, status)
+                }
+                _ => {
+                    return resp
+                        .json()
+                        .await
+                        .with_context(|| format!("Failed to parse Discogs response for {}", path));
+                }
+            }
+        }
+    }
+
+    /// The Discogs username the token belongs to
+    async fn identity(&self) -> Result<String> {
+        let identity: Identity = self.get("/oauth/identity").await?;
+        Ok(identity.username)
+    }
+
+    /// The collection's Media Condition and Sleeve Condition field ids
+    async fn condition_fields(&self, user: &str) -> Result<ConditionFields> {
+        let fields: CollectionFields = self
+            .get(&format!("/users/{}/collection/fields", user))
+            .await?;
+        let find = |name: &str| {
+            fields
+                .fields
+                .iter()
+                .find(|f| f.name.eq_ignore_ascii_case(name))
+                .map(|f| f.id)
+        };
+        Ok(ConditionFields {
+            media: find("Media Condition"),
+            sleeve: find("Sleeve Condition"),
+        })
+    }
+
+    /// Every release in the collection's "All" folder
+    async fn collection(&self, user: &str) -> Result<Vec<CollectionRelease>> {
+        let mut releases = Vec::new();
+        let mut page = 1;
+        loop {
+            let batch: CollectionPage = self
+                .get(&format!(
+                    "/users/{}/collection/folders/0/releases?page={}&per_page={}",
+                    user, page, PER_PAGE
+                ))
+                .await?;
+            if page == 1 {
+                releases.reserve(batch.pagination.items);
+            }
+            releases.extend(batch.releases);
+            if page >= batch.pagination.pages {
+                return Ok(releases);
+            }
+            println!("Fetched {} releases...", releases.len());
+            page += 1;
+        }
+    }
+}
+
+/// A release mapped to a vostuff item
+struct MappedItem {
+    name: String,
+    description: Option<String>,
+    catalog_code: Option<String>,
+    date_acquired: Option<NaiveDate>,
+    soft_fields: Map<String, Value>,
+}
+
+/// The kind and item for a collection release; None when none of its formats
+/// is vinyl, CD or cassette
+fn map_release(
+    release: &CollectionRelease,
+    conditions: &ConditionFields,
+) -> Option<(&'static str, MappedItem)> {
+    let info = &release.basic_information;
+    let (kind, format) = info
+        .formats
+        .iter()
+        .find_map(|f| format_kind(&f.name).map(|kind| (kind, f)))?;
+
+    let mut soft_fields = Map::new();
+    let qty = format
+        .qty
+        .as_deref()
+        .and_then(|q| q.trim().parse::<u32>().ok())
+        .filter(|q| *q > 0);
+    match kind {
+        "vinyl" => {
+            if let Some(qty) = qty {
+                soft_fields.insert("disks".into(), json!(qty));
+            }
+            for description in &format.descriptions {
+                if let Some((field, value)) = vinyl_detail(description) {
+                    soft_fields.insert(field.into(), json!(value));
+                }
+            }
+            let graded = |field_id: Option<u64>| {
+                let field_id = field_id?;
+                let note = release.notes.iter().find(|n| n.field_id == field_id)?;
+                grading(&note.value)
+            };
+            if let Some(grade) = graded(conditions.media) {
+                soft_fields.insert("media_grading".into(), json!(grade));
+            }
+            if let Some(grade) = graded(conditions.sleeve) {
+                soft_fields.insert("sleeve_grading".into(), json!(grade));
+            }
+            if let Some(pressing) = pressing(info, format) {
+                soft_fields.insert("pressing".into(), json!(pressing));
+            }
+        }
+        "cd" => {
+            if let Some(qty) = qty {
+                soft_fields.insert("disks".into(), json!(qty));
+            }
+        }
+        _ => {
+            if let Some(qty) = qty {
+                soft_fields.insert("cassettes".into(), json!(qty));
+            }
+        }
+    }
+
+    let year = info.year.filter(|y| *y > 0);
+    let description = match artist(info) {
+        Some(artist) => format!("{} - {}", artist, info.title),
+        None => info.title.clone(),
+    };
+    let description = match year {
+        Some(year) => format!("{} ({})", description, year),
+        None => description,
+    };
+
+    Some((
+        kind,
+        MappedItem {
+            name: info.title.clone(),
+            description: Some(description),
+            catalog_code: info
+                .labels
+                .iter()
+                .filter_map(|l| l.catno.as_deref())
+                .map(str::trim)
+                .find(|c| !c.is_empty() && !c.eq_ignore_ascii_case("none"))
+                .map(str::to_string),
+            date_acquired: release
+                .date_added
+                .as_deref()
+                .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+                .map(|d| d.date_naive()),
+            soft_fields,
+        },
+    ))
+}
+
+/// The kind for a Discogs format name
+fn format_kind(name: &str) -> Option<&'static str> {
+    match name {
+        "Vinyl" => Some("vinyl"),
+        "CD" | "CDr" | "SACD" => Some("cd"),
+        "Cassette" => Some("cassette"),
+        _ => None,
+    }
+}
+
+/// The vinyl soft field and value for a Discogs format description such as
+/// `12"` or `33 ⅓ RPM`
+fn vinyl_detail(description: &str) -> Option<(&'static str, &'static str)> {
+    match description {
+        "12\"" => Some(("size", "12_inch")),
+        "10\"" | "7\"" | "11\"" | "8\"" | "9\"" | "6\"" | "5\"" | "3\"" => Some(("size", "other")),
+        "33 ⅓ RPM" => Some(("speed", "33")),
+        "45 RPM" => Some(("speed", "45")),
+        "16 ⅔ RPM" | "78 RPM" | "80 RPM" => Some(("speed", "other")),
+        "Stereo" => Some(("channels", "stereo")),
+        "Mono" => Some(("channels", "mono")),
+        "Quadraphonic" => Some(("channels", "surround")),
+        _ => None,
+    }
+}
+
+/// The grading for a Discogs condition such as "Very Good Plus (VG+)"; None
+/// for Generic, Not Graded and No Cover sleeves
+fn grading(condition: &str) -> Option<&'static str> {
+    let grade = condition
+        .rsplit_once('(')
+        .map_or(condition, |(_, abbrev)| abbrev.trim_end_matches(')'));
+    match grade.trim() {
+        "M" => Some("mint"),
+        "NM or M-" => Some("near_mint"),
+        "VG+" => Some("excellent"),
+        "VG" | "G+" | "G" => Some("good"),
+        "F" => Some("fair"),
+        "P" => Some("poor"),
+        _ => None,
+    }
+}
+
+/// Label, catalogue number, year and edition details of a vinyl release, e.g.
+/// "Harvest SHVL 804, 1973, Reissue, Gatefold"
+fn pressing(info: &BasicInformation, format: &Format) -> Option<String> {
+    let mut parts: Vec<String> = info
+        .labels
+        .iter()
+        .map(|l| match l.catno.as_deref().map(str::trim) {
+            Some(catno) if !catno.is_empty() && !catno.eq_ignore_ascii_case("none") => {
+                format!("{} {}", l.name, catno)
+            }
+            _ => l.name.clone(),
+        })
+        .collect();
+    if let Some(year) = info.year.filter(|y| *y > 0) {
+        parts.push(year.to_string());
+    }
+    // Descriptions not stored in their own field, leaving out release types
+    parts.extend(
+        format
+            .descriptions
+            .iter()
+            .filter(|d| vinyl_detail(d).is_none())
+            .filter(|d| {
+                !matches!(
+                    d.as_str(),
+                    "LP" | "Album" | "EP" | "Single" | "Compilation" | "Mini-Album" | "Maxi-Single"
+                )
+            })
+            .cloned(),
+    );
+    if let Some(text) = format.text.as_deref().filter(|t| !t.trim().is_empty()) {
+        parts.push(text.trim().to_string());
+    }
+
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+/// The credited artists, without the numbers Discogs adds to tell apart
+/// artists of the same name, e.g. "Nirvana (2)"
+fn artist(info: &BasicInformation) -> Option<String> {
+    let artist: String = info
+        .artists
+        .iter()
+        .map(|a| {
+            let name = match a.name.rsplit_once(" (") {
+                Some((name, n)) if n.trim_end_matches(')').parse::<u32>().is_ok() => name,
+                _ => &a.name,
+            };
+            match a.join.trim() {
+                "" => name.to_string(),
+                "," => format!("{}, ", name),
+                join => format!("{} {} ", name, join),
+            }
+        })
+        .collect();
+    let artist = artist.trim_end_matches([',', ' ']).to_string();
+    (!artist.is_empty()).then_some(artist)
+}
+
+/// A release's format names, for reporting skipped releases
+fn format_names(info: &BasicInformation) -> String {
+    let names: Vec<&str> = info.formats.iter().map(|f| f.name.as_str()).collect();
+    if names.is_empty() {
+        "no format".to_string()
+    } else {
+        names.join(", ")
+    }
+}
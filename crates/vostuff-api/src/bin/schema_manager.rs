@@ -2,7 +2,10 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use std::env;
 
+use sqlx::PgPool;
 use vostuff_api::schema::SchemaManager;
+use vostuff_api::test_utils::{DEFAULT_SEED, SEED_PASSWORD, SampleDataLoader, SeedProfile};
+use vostuff_core::config::redact_url_password;
 
 #[derive(Parser)]
 #[command(name = "schema-manager")]
@@ -29,6 +32,17 @@ enum Commands {
         #[arg(long, env = "DATABASE_URL")]
         database_url: Option<String>,
     },
+    #[command(about = "Load a seed data profile into a migrated database")]
+    Seed {
+        #[arg(long, env = "DATABASE_URL")]
+        database_url: Option<String>,
+        /// Seed data to load
+        #[arg(long, value_enum, default_value = "sample")]
+        profile: SeedProfile,
+        /// RNG seed; the same profile and seed give the same data
+        #[arg(long, default_value_t = DEFAULT_SEED)]
+        seed: u64,
+    },
 }
 
 #[tokio::main]
@@ -43,7 +57,7 @@ async fn main() -> Result<()> {
                 .or_else(|| env::var("DATABASE_URL").ok())
                 .unwrap_or(default_db_url);
 
-            println!("Running migrations on: {}", db_url);
+            println!("Running migrations on: {}", redact_url_password(&db_url));
             let schema_manager = SchemaManager::new(&db_url).await?;
             schema_manager.run_migrations().await?;
             println!("Migrations completed successfully!");
@@ -54,7 +68,7 @@ async fn main() -> Result<()> {
                 .or_else(|| env::var("DATABASE_URL").ok())
                 .unwrap_or(default_db_url);
 
-            println!("Resetting database: {}", db_url);
+            println!("Resetting database: {}", redact_url_password(&db_url));
             println!("WARNING: This will delete all data!");
 
             let schema_manager = SchemaManager::new(&db_url).await?;
@@ -67,11 +81,41 @@ async fn main() -> Result<()> {
                 .or_else(|| env::var("DATABASE_URL").ok())
                 .unwrap_or(default_db_url);
 
-            println!("Creating database if needed: {}", db_url);
+            println!(
+                "Creating database if needed: {}",
+                redact_url_password(&db_url)
+            );
             let schema_manager = SchemaManager::new(&db_url).await?;
             println!("Database ready!");
             schema_manager.close().await;
         }
+        Commands::Seed {
+            database_url,
+            profile,
+            seed,
+        } => {
+            let db_url = database_url
+                .or_else(|| env::var("DATABASE_URL").ok())
+                .unwrap_or(default_db_url);
+
+            println!(
+                "Seeding {:?} (seed {}) into: {}",
+                profile,
+                seed,
+                redact_url_password(&db_url)
+            );
+            let pool = PgPool::connect(&db_url).await?;
+            let result = SampleDataLoader::with_seed(&pool, seed)
+                .load_profile(profile)
+                .await?;
+            println!(
+                "Seeded {} organization(s) and {} items; users log in with password {}",
+                result.organization_ids.len(),
+                result.item_count,
+                SEED_PASSWORD
+            );
+            pool.close().await;
+        }
     }
 
     Ok(())
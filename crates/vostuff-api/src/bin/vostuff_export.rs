@@ -0,0 +1,190 @@
+//! vostuff Export - backs up an organization's data
+//!
+//! This tool reads everything in an organization through the REST API: its
+//! items with their tags and loan, missing or disposed details, and its kinds,
+//! locations, collections, tags, borrowers and loans. It writes them as one
+//! JSON file, or as a zip of CSV files whose items `csv-importer` can import
+//! again with the mapping beside each. See `vostuff_api::backup` for the
+//! format.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::{Parser, ValueEnum};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use uuid::Uuid;
+use vostuff_api::backup::{
+    Backup, BackupBorrower, BackupCollection, BackupItem, BackupLoan, FORMAT, VERSION,
+};
+use vostuff_api::import_client::{ApiClient, KindDetail, read_password};
+use vostuff_api::models::{Collection, Location, Tag};
+
+/// What the backup is written as
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Format {
+    /// One JSON file with everything
+    Json,
+    /// A zip of CSV files, one per kind of item and per other list
+    Zip,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Zip => "zip",
+        }
+    }
+}
+
+/// vostuff Export - Back up an organization's data
+#[derive(Parser, Debug)]
+#[command(name = "vostuff-export")]
+#[command(about = "Back up an organization's data as JSON or a zip of CSV files")]
+struct Args {
+    /// User email for authentication
+    #[arg(short, long)]
+    username: String,
+
+    /// Password (optional, uses VOSTUFF_PASSWORD env var or interactive prompt)
+    #[arg(short, long)]
+    password: Option<String>,
+
+    /// Organization ID (optional, will prompt if user has multiple orgs)
+    #[arg(short, long)]
+    org_id: Option<Uuid>,
+
+    /// API base URL
+    #[arg(long, default_value = "http://localhost:8080")]
+    api_url: String,
+
+    /// Backup format
+    #[arg(long, value_enum, default_value = "json")]
+    format: Format,
+
+    /// File to write (default: vostuff-backup-<date>.json or .zip)
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+/// An item as listed with `include=details,tags,collections`
+#[derive(Deserialize)]
+struct ListedItem {
+    #[serde(flatten)]
+    item: BackupItem,
+    #[serde(default)]
+    collections: Vec<Collection>,
+}
+
+/// GET /borrowers
+#[derive(Deserialize)]
+struct BorrowerSummary {
+    id: Uuid,
+}
+
+/// GET /borrowers/{id}/history
+#[derive(Deserialize)]
+struct BorrowerHistory {
+    id: Uuid,
+    name: String,
+    loans: Vec<BackupLoan>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let password = read_password(args.password.as_deref())?;
+
+    println!("Authenticating as {}...", args.username);
+    let client = ApiClient::login(&args.api_url, &args.username, &password, args.org_id).await?;
+
+    let backup = fetch_backup(&client).await?;
+    println!(
+        "\nFound {} items, {} locations, {} collections, {} tags and {} borrowers",
+        backup.items.len(),
+        backup.locations.len(),
+        backup.collections.len(),
+        backup.tags.len(),
+        backup.borrowers.len()
+    );
+
+    let output = args.output.unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "vostuff-backup-{}.{}",
+            backup.exported_at.format("%Y-%m-%d"),
+            args.format.extension()
+        ))
+    });
+    let file =
+        File::create(&output).with_context(|| format!("Failed to create {}", output.display()))?;
+    match args.format {
+        Format::Json => serde_json::to_writer_pretty(BufWriter::new(file), &backup)?,
+        Format::Zip => backup.write_zip(file)?,
+    }
+    println!("Wrote {}", output.display());
+    Ok(())
+}
+
+/// Read everything in the client's organization
+async fn fetch_backup(client: &ApiClient) -> Result<Backup> {
+    let exported_at = Utc::now();
+
+    println!("Fetching kinds, locations and tags...");
+    let kinds: Vec<KindDetail> = client.get("kinds", &[]).await?;
+    let locations: Vec<Location> = client.get("locations", &[]).await?;
+    let tags: Vec<Tag> = client.get("tags", &[]).await?;
+
+    println!("Fetching items...");
+    let listed: Vec<ListedItem> = client
+        .list_items(&[("include", "details,tags,collections".to_string())])
+        .await?;
+
+    // Collections list no items, so gather them from the items
+    let collections: Vec<Collection> = client.get("collections", &[]).await?;
+    let mut item_ids: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for listed in &listed {
+        for collection in &listed.collections {
+            item_ids
+                .entry(collection.id)
+                .or_default()
+                .push(listed.item.item.id);
+        }
+    }
+    let collections = collections
+        .into_iter()
+        .map(|collection| BackupCollection {
+            item_ids: item_ids.remove(&collection.id).unwrap_or_default(),
+            collection,
+        })
+        .collect();
+
+    println!("Fetching borrowers and loans...");
+    let summaries: Vec<BorrowerSummary> = client.get("borrowers", &[]).await?;
+    let mut borrowers = Vec::new();
+    for summary in summaries {
+        let history: BorrowerHistory = client
+            .get(&format!("borrowers/{}/history", summary.id), &[])
+            .await?;
+        borrowers.push(BackupBorrower {
+            id: history.id,
+            name: history.name,
+            loans: history.loans,
+        });
+    }
+
+    Ok(Backup {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        exported_at,
+        organization_id: client.org_id,
+        kinds,
+        locations,
+        collections,
+        tags,
+        borrowers,
+        items: listed.into_iter().map(|l| l.item).collect(),
+    })
+}
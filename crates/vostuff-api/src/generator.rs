@@ -0,0 +1,604 @@
+//! Synthetic items for load testing and index tuning.
+//!
+//! [`ItemGenerator`] writes items straight to the database in batches,
+//! bypassing the API, so hundreds of thousands can be created in minutes.
+//! Distributions follow a typical collection rather than being uniform:
+//! records and CDs outnumber everything else, most items are on the shelf,
+//! a few tags and shelves are far more popular than the rest (Zipf-like), and
+//! recent acquisitions outnumber old ones. The same seed gives the same items.
+//!
+//! Used by the `stress-10k` seed profile and the `bench` binary.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use chrono::{NaiveDate, Utc};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::{Rng, SeedableRng, seq::SliceRandom};
+use rand_chacha::ChaCha8Rng;
+use serde_json::{Value, json};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Kind names and their relative weights in a typical collection
+pub const DEFAULT_KIND_MIX: &[(&str, u32)] = &[
+    ("vinyl", 30),
+    ("cd", 25),
+    ("book", 20),
+    ("cassette", 8),
+    ("dvd", 7),
+    ("bluray", 6),
+    ("score", 5),
+    ("video_game", 4),
+    ("electronics", 3),
+    ("misc", 2),
+];
+
+/// Items inserted per transaction unless set otherwise
+pub const DEFAULT_BATCH_SIZE: usize = 1_000;
+
+/// States and their weights per thousand items
+const STATE_MIX: &[(&str, u32)] = &[
+    ("current", 880),
+    ("loaned", 60),
+    ("missing", 35),
+    ("disposed", 25),
+];
+
+/// Chance of an item having 0, 1, 2 or 3 tags, in percent
+const TAG_COUNT_MIX: &[u32] = &[45, 30, 17, 8];
+
+/// Share of items that have been used, and of those in a collection
+const USED_SHARE: f64 = 0.4;
+const COLLECTED_SHARE: f64 = 0.25;
+
+/// Oldest acquisition, in days before today
+const MAX_AGE_DAYS: f64 = 20.0 * 365.0;
+
+/// Tag vocabulary, most popular first
+const TAGS: &[&str] = &[
+    "favorite",
+    "vintage",
+    "jazz",
+    "rock",
+    "first-pressing",
+    "signed",
+    "rare",
+    "classical",
+    "gift",
+    "to-sell",
+    "mint-condition",
+    "limited-edition",
+    "soundtrack",
+    "import",
+    "reissue",
+    "needs-cleaning",
+    "box-set",
+    "live",
+    "promo",
+    "coloured-vinyl",
+    "hardback",
+    "first-edition",
+    "reference",
+    "kids",
+    "lent-often",
+    "damaged",
+    "duplicate",
+    "wishlist-upgrade",
+    "picture-disc",
+    "bootleg",
+];
+
+const ADJECTIVES: &[&str] = &[
+    "Midnight", "Blue", "Silver", "Northern", "Electric", "Quiet", "Golden", "Broken", "Summer",
+    "Winter", "Velvet", "Distant", "Paper", "Glass", "Wild", "Hidden", "Last", "Crimson", "Lonely",
+    "Burning", "Secret", "Hollow", "Bright", "Faded", "Endless", "Sudden", "Little", "Open",
+];
+const NOUNS: &[&str] = &[
+    "Harbour", "Lights", "Garden", "River", "Machine", "Letters", "Horizon", "Station", "Echoes",
+    "Island", "Road", "Dreams", "Empire", "Tides", "Signals", "Mirror", "Orchard", "Frontier",
+    "Parade", "Weather", "Kingdom", "Engine", "Sessions", "Streets", "Hours", "Waves", "Fields",
+];
+const CREATORS: &[&str] = &[
+    "The Hollow Pines",
+    "Mara Ellison",
+    "Juno Vance",
+    "The Saltmarsh Trio",
+    "Priya Raman",
+    "Oskar Lind",
+    "Tomas Greer",
+    "The Long Weekends",
+    "Hana Okafor",
+    "Felix Moreau",
+    "Ruth Calder",
+    "Night Owl Orchestra",
+    "Dara Quinn",
+    "The Copper Kettles",
+    "Ines Albrecht",
+    "Marcus Hale",
+    "Sunday Drivers",
+    "Yuki Tanaka",
+    "The Brass Lanterns",
+    "Elena Petrova",
+];
+const BORROWERS: &[&str] = &[
+    "John Doe",
+    "Jane Smith",
+    "Alex Kim",
+    "Sofia Rossi",
+    "Sam Patel",
+    "Chris O'Neill",
+    "Maya Cohen",
+    "Leo Martins",
+];
+const GRADES: &[&str] = &["mint", "near_mint", "excellent", "good", "fair", "poor"];
+
+/// What a generation run created
+#[derive(Debug, Default)]
+pub struct GenerationReport {
+    pub items: usize,
+    pub by_state: BTreeMap<&'static str, usize>,
+    pub by_kind: BTreeMap<String, usize>,
+    pub tags: usize,
+    pub elapsed: Duration,
+}
+
+/// Writes synthetic items for one organization at a time
+pub struct ItemGenerator<'a> {
+    pool: &'a PgPool,
+    rng: ChaCha8Rng,
+    kinds: Vec<(String, u32)>,
+    batch_size: usize,
+    progress: bool,
+}
+
+/// Organization data items are spread over, looked up once per run
+struct Targets {
+    kinds: Vec<(String, Uuid)>,
+    kind_weights: WeightedIndex<u32>,
+    locations: Vec<Uuid>,
+    location_weights: Option<WeightedIndex<f64>>,
+    collections: Vec<Uuid>,
+    collection_weights: Option<WeightedIndex<f64>>,
+    tag_weights: WeightedIndex<f64>,
+    borrowers: HashMap<&'static str, Uuid>,
+    user_id: Option<Uuid>,
+}
+
+struct GeneratedItem {
+    id: Uuid,
+    kind: usize,
+    state: &'static str,
+    name: String,
+    description: String,
+    catalog_code: String,
+    location_id: Option<Uuid>,
+    date_acquired: NaiveDate,
+    soft_fields: Value,
+    /// Date loaned, went missing or was disposed of, for those states
+    state_date: NaiveDate,
+    due_back: Option<NaiveDate>,
+    borrower: &'static str,
+    last_used: Option<NaiveDate>,
+    collection_id: Option<Uuid>,
+    tags: Vec<&'static str>,
+}
+
+impl<'a> ItemGenerator<'a> {
+    pub fn new(pool: &'a PgPool, seed: u64) -> Self {
+        Self {
+            pool,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            kinds: DEFAULT_KIND_MIX
+                .iter()
+                .map(|(kind, weight)| (kind.to_string(), *weight))
+                .collect(),
+            batch_size: DEFAULT_BATCH_SIZE,
+            progress: false,
+        }
+    }
+
+    /// Generate only these kinds, with relative weights
+    pub fn with_kinds(mut self, kinds: &[(&str, u32)]) -> Self {
+        self.kinds = kinds
+            .iter()
+            .map(|(kind, weight)| (kind.to_string(), *weight))
+            .collect();
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Print a line after each batch
+    pub fn with_progress(mut self, progress: bool) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Add `count` items to an organization. Items are spread over its
+    /// existing locations and collections; tags and borrowers are created as
+    /// needed, and uses are attributed to its first member.
+    pub async fn generate(&mut self, org_id: Uuid, count: usize) -> Result<GenerationReport> {
+        let started = Instant::now();
+        let targets = self.targets(org_id).await?;
+        let mut report = GenerationReport::default();
+
+        while report.items < count {
+            let size = self.batch_size.min(count - report.items);
+            let batch: Vec<GeneratedItem> = (0..size)
+                .map(|n| self.random_item(&targets, report.items + n))
+                .collect();
+            insert_batch(self.pool, org_id, &targets, &batch).await?;
+
+            for item in &batch {
+                *report.by_state.entry(item.state).or_default() += 1;
+                *report
+                    .by_kind
+                    .entry(targets.kinds[item.kind].0.clone())
+                    .or_default() += 1;
+                report.tags += item.tags.len();
+            }
+            report.items += batch.len();
+            if self.progress {
+                println!(
+                    "  ✓ Generated {} of {} items ({:.1}s)",
+                    report.items,
+                    count,
+                    started.elapsed().as_secs_f64()
+                );
+            }
+        }
+
+        report.elapsed = started.elapsed();
+        Ok(report)
+    }
+
+    async fn targets(&self, org_id: Uuid) -> Result<Targets> {
+        let mut kinds = Vec::new();
+        let mut weights = Vec::new();
+        for (name, weight) in &self.kinds {
+            let id: Uuid =
+                sqlx::query_scalar("SELECT id FROM kinds WHERE name = $1 AND org_id IS NULL")
+                    .bind(name)
+                    .fetch_optional(self.pool)
+                    .await?
+                    .with_context(|| format!("No shared kind named '{}'", name))?;
+            kinds.push((name.clone(), id));
+            weights.push(*weight);
+        }
+        if kinds.is_empty() {
+            bail!("No kinds to generate");
+        }
+
+        let locations: Vec<Uuid> =
+            sqlx::query_scalar("SELECT id FROM locations WHERE organization_id = $1 ORDER BY name")
+                .bind(org_id)
+                .fetch_all(self.pool)
+                .await?;
+        let collections: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM collections WHERE organization_id = $1 ORDER BY name",
+        )
+        .bind(org_id)
+        .fetch_all(self.pool)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO tags (organization_id, name)
+             SELECT $1, name FROM UNNEST($2::text[]) AS t(name)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(org_id)
+        .bind(TAGS)
+        .execute(self.pool)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO borrowers (organization_id, name)
+             SELECT $1, name FROM UNNEST($2::text[]) AS t(name)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(org_id)
+        .bind(BORROWERS)
+        .execute(self.pool)
+        .await?;
+        // Names are unique ignoring case, so an existing "john doe" is reused
+        let rows: Vec<(Uuid, String)> = sqlx::query_as(
+            "SELECT id, name FROM borrowers
+             WHERE organization_id = $1 AND LOWER(name) = ANY(SELECT LOWER(n) FROM UNNEST($2::text[]) AS t(n))",
+        )
+        .bind(org_id)
+        .bind(BORROWERS)
+        .fetch_all(self.pool)
+        .await?;
+        let mut borrowers = HashMap::new();
+        for name in BORROWERS {
+            let (id, _) = rows
+                .iter()
+                .find(|(_, n)| n.eq_ignore_ascii_case(name))
+                .context("Borrower missing after insert")?;
+            borrowers.insert(*name, *id);
+        }
+
+        let user_id = sqlx::query_scalar(
+            "SELECT user_id FROM user_organizations WHERE organization_id = $1
+             ORDER BY created_at LIMIT 1",
+        )
+        .bind(org_id)
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(Targets {
+            kinds,
+            kind_weights: WeightedIndex::new(weights)?,
+            location_weights: zipf(locations.len()),
+            locations,
+            collection_weights: zipf(collections.len()),
+            collections,
+            tag_weights: zipf(TAGS.len()).expect("tag vocabulary is not empty"),
+            borrowers,
+            user_id,
+        })
+    }
+
+    fn random_item(&mut self, targets: &Targets, n: usize) -> GeneratedItem {
+        let rng = &mut self.rng;
+        let today = Utc::now().date_naive();
+
+        let kind = targets.kind_weights.sample(rng);
+        let state = STATE_MIX[WeightedIndex::new(STATE_MIX.iter().map(|(_, w)| w))
+            .expect("state weights are valid")
+            .sample(rng)]
+        .0;
+        let title = format!(
+            "{} {}",
+            ADJECTIVES.choose(rng).unwrap(),
+            NOUNS.choose(rng).unwrap()
+        );
+        let creator = CREATORS.choose(rng).unwrap();
+
+        // Squaring skews acquisitions towards recent years
+        let age_days = (rng.gen_range(0.0f64..1.0).powi(2) * MAX_AGE_DAYS) as i64 + 1;
+        let date_acquired = today - chrono::Duration::days(age_days);
+        let state_date = today - chrono::Duration::days(rng.gen_range(0..age_days.min(365)));
+        let due_back = rng
+            .gen_bool(0.5)
+            .then(|| state_date + chrono::Duration::days(rng.gen_range(7..=42)));
+        let last_used = rng
+            .gen_bool(USED_SHARE)
+            .then(|| today - chrono::Duration::days(rng.gen_range(0..age_days.min(730))));
+
+        let tag_count = WeightedIndex::new(TAG_COUNT_MIX)
+            .expect("tag count weights are valid")
+            .sample(rng);
+        let mut tags: Vec<&'static str> = (0..tag_count)
+            .map(|_| TAGS[targets.tag_weights.sample(rng)])
+            .collect();
+        tags.sort_unstable();
+        tags.dedup();
+
+        let kind_name = targets.kinds[kind].0.as_str();
+        GeneratedItem {
+            // Ids are not derived from the seed, so a run can be repeated
+            id: Uuid::new_v4(),
+            kind,
+            state,
+            name: format!("{} - {}", title, creator),
+            description: format!("{}: {} by {}", kind_name, title, creator),
+            catalog_code: format!("GEN-{:06}", n + 1),
+            location_id: targets
+                .location_weights
+                .as_ref()
+                .map(|w| targets.locations[w.sample(rng)]),
+            date_acquired,
+            soft_fields: soft_fields(rng, kind_name),
+            state_date,
+            due_back,
+            borrower: BORROWERS.choose(rng).unwrap(),
+            last_used,
+            collection_id: targets
+                .collection_weights
+                .as_ref()
+                .filter(|_| rng.gen_bool(COLLECTED_SHARE))
+                .map(|w| targets.collections[w.sample(rng)]),
+            tags,
+        }
+    }
+}
+
+/// Weights 1, 1/2, 1/3, ... so the first entries dominate; None when empty
+fn zipf(len: usize) -> Option<WeightedIndex<f64>> {
+    WeightedIndex::new((1..=len).map(|rank| 1.0 / rank as f64)).ok()
+}
+
+fn soft_fields(rng: &mut ChaCha8Rng, kind: &str) -> Value {
+    match kind {
+        "vinyl" => json!({
+            "size": (["12_inch", "12_inch", "12_inch", "10_inch", "6_inch"].choose(rng).unwrap()),
+            "speed": (["33", "33", "45"].choose(rng).unwrap()),
+            "channels": (["stereo", "stereo", "mono"].choose(rng).unwrap()),
+            "disks": rng.gen_range(1..=2),
+            "media_grading": (GRADES.choose(rng).unwrap()),
+            "sleeve_grading": (GRADES.choose(rng).unwrap()),
+        }),
+        "cd" | "dvd" => {
+            json!({ "disks": if rng.gen_bool(0.85) { 1 } else { rng.gen_range(2..=4) } })
+        }
+        "cassette" => json!({ "cassettes": rng.gen_range(1..=2) }),
+        "book" => json!({
+            "author": (CREATORS.choose(rng).unwrap()),
+            "year": rng.gen_range(1950..=2025),
+            "book_format": (["paperback", "paperback", "hardback"].choose(rng).unwrap()),
+        }),
+        "bluray" => json!({
+            "disc_format": (["bluray", "bluray", "uhd", "uhd_bluray"].choose(rng).unwrap()),
+            "disks": if rng.gen_bool(0.8) { 1 } else { 2 },
+            "disc_region": (["a", "b", "b", "region_free"].choose(rng).unwrap()),
+        }),
+        "video_game" => json!({
+            "platform": (["snes", "n64", "ps2", "ps4", "switch", "xbox_360"].choose(rng).unwrap()),
+            "region": (["pal", "pal", "ntsc_u", "ntsc_j"].choose(rng).unwrap()),
+            "completeness": (["complete", "complete", "boxed", "loose"].choose(rng).unwrap()),
+            "condition": (GRADES.choose(rng).unwrap()),
+        }),
+        _ => json!({}),
+    }
+}
+
+async fn insert_batch(
+    pool: &PgPool,
+    org_id: Uuid,
+    targets: &Targets,
+    batch: &[GeneratedItem],
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "INSERT INTO items
+             (id, organization_id, kind_id, state, name, description, catalog_code,
+              location_id, date_acquired, soft_fields, last_used_at)
+         SELECT id, $1, kind_id, state::item_state, name, description, catalog_code,
+                location_id, date_acquired, soft_fields, last_used::timestamptz
+         FROM UNNEST($2::uuid[], $3::uuid[], $4::text[], $5::text[], $6::text[], $7::text[],
+                     $8::uuid[], $9::date[], $10::jsonb[], $11::date[])
+              AS t(id, kind_id, state, name, description, catalog_code, location_id,
+                   date_acquired, soft_fields, last_used)",
+    )
+    .bind(org_id)
+    .bind(batch.iter().map(|i| i.id).collect::<Vec<_>>())
+    .bind(
+        batch
+            .iter()
+            .map(|i| targets.kinds[i.kind].1)
+            .collect::<Vec<_>>(),
+    )
+    .bind(batch.iter().map(|i| i.state).collect::<Vec<_>>())
+    .bind(batch.iter().map(|i| i.name.as_str()).collect::<Vec<_>>())
+    .bind(
+        batch
+            .iter()
+            .map(|i| i.description.as_str())
+            .collect::<Vec<_>>(),
+    )
+    .bind(
+        batch
+            .iter()
+            .map(|i| i.catalog_code.as_str())
+            .collect::<Vec<_>>(),
+    )
+    .bind(batch.iter().map(|i| i.location_id).collect::<Vec<_>>())
+    .bind(batch.iter().map(|i| i.date_acquired).collect::<Vec<_>>())
+    .bind(batch.iter().map(|i| &i.soft_fields).collect::<Vec<_>>())
+    .bind(batch.iter().map(|i| i.last_used).collect::<Vec<_>>())
+    .execute(&mut *tx)
+    .await?;
+
+    insert_state_details(&mut tx, org_id, targets, batch).await?;
+
+    let collected: Vec<&GeneratedItem> =
+        batch.iter().filter(|i| i.collection_id.is_some()).collect();
+    sqlx::query(
+        "INSERT INTO item_collections (item_id, collection_id)
+         SELECT * FROM UNNEST($1::uuid[], $2::uuid[])",
+    )
+    .bind(collected.iter().map(|i| i.id).collect::<Vec<_>>())
+    .bind(
+        collected
+            .iter()
+            .map(|i| i.collection_id)
+            .collect::<Vec<_>>(),
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let (tag_items, tag_names): (Vec<Uuid>, Vec<&str>) = batch
+        .iter()
+        .flat_map(|i| i.tags.iter().map(move |tag| (i.id, *tag)))
+        .unzip();
+    sqlx::query(
+        "INSERT INTO item_tags (item_id, organization_id, tag_name)
+         SELECT id, $1, tag FROM UNNEST($2::uuid[], $3::text[]) AS t(id, tag)",
+    )
+    .bind(org_id)
+    .bind(tag_items)
+    .bind(tag_names)
+    .execute(&mut *tx)
+    .await?;
+
+    // One logged use per used item, matching its last_used_at
+    let used: Vec<&GeneratedItem> = batch.iter().filter(|i| i.last_used.is_some()).collect();
+    sqlx::query(
+        "INSERT INTO usage_log (organization_id, item_id, user_id, used_at)
+         SELECT $1, id, $2, used::timestamptz FROM UNNEST($3::uuid[], $4::date[]) AS t(id, used)",
+    )
+    .bind(org_id)
+    .bind(targets.user_id)
+    .bind(used.iter().map(|i| i.id).collect::<Vec<_>>())
+    .bind(used.iter().map(|i| i.last_used).collect::<Vec<_>>())
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Loan, missing and disposed details, and the open loan in the loan history
+async fn insert_state_details(
+    tx: &mut Transaction<'_, Postgres>,
+    org_id: Uuid,
+    targets: &Targets,
+    batch: &[GeneratedItem],
+) -> Result<()> {
+    let in_state = |state: &str| -> Vec<&GeneratedItem> {
+        batch.iter().filter(|i| i.state == state).collect()
+    };
+
+    let loaned = in_state("loaned");
+    sqlx::query(
+        "INSERT INTO item_loan_details (item_id, date_loaned, date_due_back, loaned_to)
+         SELECT * FROM UNNEST($1::uuid[], $2::date[], $3::date[], $4::text[])",
+    )
+    .bind(loaned.iter().map(|i| i.id).collect::<Vec<_>>())
+    .bind(loaned.iter().map(|i| i.state_date).collect::<Vec<_>>())
+    .bind(loaned.iter().map(|i| i.due_back).collect::<Vec<_>>())
+    .bind(loaned.iter().map(|i| i.borrower).collect::<Vec<_>>())
+    .execute(&mut **tx)
+    .await?;
+    sqlx::query(
+        "INSERT INTO item_loans (organization_id, item_id, borrower_id, date_loaned, date_due_back)
+         SELECT $1, id, borrower_id, date_loaned, date_due_back
+         FROM UNNEST($2::uuid[], $3::uuid[], $4::date[], $5::date[])
+              AS t(id, borrower_id, date_loaned, date_due_back)",
+    )
+    .bind(org_id)
+    .bind(loaned.iter().map(|i| i.id).collect::<Vec<_>>())
+    .bind(
+        loaned
+            .iter()
+            .map(|i| targets.borrowers[i.borrower])
+            .collect::<Vec<_>>(),
+    )
+    .bind(loaned.iter().map(|i| i.state_date).collect::<Vec<_>>())
+    .bind(loaned.iter().map(|i| i.due_back).collect::<Vec<_>>())
+    .execute(&mut **tx)
+    .await?;
+
+    for (table, column, state) in [
+        ("item_missing_details", "date_missing", "missing"),
+        ("item_disposed_details", "date_disposed", "disposed"),
+    ] {
+        let items = in_state(state);
+        sqlx::query(&format!(
+            "INSERT INTO {} (item_id, {}) SELECT * FROM UNNEST($1::uuid[], $2::date[])",
+            table, column
+        ))
+        .bind(items.iter().map(|i| i.id).collect::<Vec<_>>())
+        .bind(items.iter().map(|i| i.state_date).collect::<Vec<_>>())
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,622 @@
+//! REST API client shared by the import tools
+//!
+//! The importers (`clz-importer`, `csv-importer`, `discogs-importer`) create
+//! items through the API rather than the database, so they work against any
+//! server and go through the same validation as the web app. [`ApiClient`]
+//! logs in, asking which organization to use when the user has several,
+//! finds kinds and creates items, counting the outcomes in [`ImportStats`].
+//! [`ExistingItems`] finds items already in the organization, so an import
+//! run again can skip or update them instead of creating them twice.
+
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, Write};
+
+use anyhow::{Context, Result, bail};
+use chrono::NaiveDate;
+use reqwest::Client;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use uuid::Uuid;
+
+/// Login request
+#[derive(Serialize)]
+struct LoginRequest {
+    identity: String,
+    password: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    organization_id: Option<Uuid>,
+}
+
+/// Login response (successful)
+#[derive(Deserialize)]
+struct LoginResponse {
+    token: String,
+}
+
+/// Organization selection response (multi-org user)
+#[derive(Deserialize)]
+struct OrgSelectionResponse {
+    organizations: Vec<OrganizationInfo>,
+    follow_on_token: String,
+}
+
+#[derive(Deserialize)]
+struct OrganizationInfo {
+    id: Uuid,
+    name: String,
+}
+
+/// Select org request
+#[derive(Serialize)]
+struct SelectOrgRequest {
+    follow_on_token: String,
+    organization_id: Uuid,
+}
+
+/// Kind summary from the kinds API
+#[derive(Deserialize)]
+struct KindSummary {
+    id: Uuid,
+    name: String,
+}
+
+/// Kind details, including its soft fields
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KindDetail {
+    pub id: Uuid,
+    pub name: String,
+    pub display_name: Option<String>,
+    /// Shared by every organization rather than the organization's own
+    pub is_shared: bool,
+    pub fields: Vec<KindField>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KindField {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub field_type: String,
+    pub enum_values: Vec<EnumValue>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnumValue {
+    pub value: String,
+    pub display_value: Option<String>,
+}
+
+/// Create item request
+#[derive(Serialize)]
+pub struct CreateItemRequest {
+    pub kind_id: Uuid,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_acquired: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub catalog_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soft_fields: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Update item request; fields left as None are unchanged
+#[derive(Serialize, Default)]
+pub struct UpdateItemRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_acquired: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<String>,
+    /// Merged into the item's soft fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soft_fields: Option<serde_json::Value>,
+}
+
+/// An item already in the organization
+#[derive(Deserialize)]
+pub struct ExistingItem {
+    pub id: Uuid,
+    pub kind_id: Uuid,
+    pub name: String,
+    pub barcode: Option<String>,
+}
+
+/// Page of the items list
+#[derive(Deserialize)]
+struct ItemPage<T> {
+    items: Vec<T>,
+    next_cursor: Option<String>,
+}
+
+/// The organization's items by barcode and by kind and name, to find the
+/// ones a record duplicates
+#[derive(Default)]
+pub struct ExistingItems {
+    by_barcode: HashMap<String, Uuid>,
+    by_name: HashMap<(Uuid, String), Uuid>,
+}
+
+impl ExistingItems {
+    pub fn new(items: Vec<ExistingItem>) -> Self {
+        let mut existing = Self::default();
+        for item in items {
+            if let Some(barcode) = item.barcode.filter(|b| !b.trim().is_empty()) {
+                existing
+                    .by_barcode
+                    .entry(barcode.trim().to_string())
+                    .or_insert(item.id);
+            }
+            existing
+                .by_name
+                .entry((item.kind_id, normalize_name(&item.name)))
+                .or_insert(item.id);
+        }
+        existing
+    }
+
+    /// The item with the barcode, else the item of the kind with the name,
+    /// ignoring case and spacing
+    pub fn find(&self, kind_id: Uuid, name: &str, barcode: Option<&str>) -> Option<Uuid> {
+        barcode
+            .and_then(|b| self.by_barcode.get(b.trim()))
+            .or_else(|| self.by_name.get(&(kind_id, normalize_name(name))))
+            .copied()
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// What became of one record
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Imported,
+    Updated,
+    /// Why the record was left out
+    Skipped(String),
+    /// Why the record could not be imported
+    Failed(String),
+}
+
+/// API error response
+#[derive(Deserialize)]
+struct ErrorResponse {
+    #[allow(dead_code)]
+    error: String,
+    message: String,
+}
+
+impl ErrorResponse {
+    /// The error in a response body, or the body itself when it is not one
+    fn parse(body: String) -> Self {
+        serde_json::from_str(&body).unwrap_or(Self {
+            error: "unknown".to_string(),
+            message: body,
+        })
+    }
+}
+
+/// Import statistics
+#[derive(Default)]
+pub struct ImportStats {
+    pub total: usize,
+    pub imported: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+impl ImportStats {
+    pub fn print_summary(&self) {
+        println!("\n=== Import Summary ===");
+        println!("Total records: {}", self.total);
+        println!("Imported:      {}", self.imported);
+        if self.updated > 0 {
+            println!("Updated:       {}", self.updated);
+        }
+        println!("Skipped:       {}", self.skipped);
+        println!("Failed:        {}", self.failed);
+    }
+
+    /// Print and count what became of a record
+    pub fn record(&mut self, progress: &str, name: &str, outcome: &Outcome) {
+        match outcome {
+            Outcome::Imported => {
+                println!("{} Imported: {}", progress, name);
+                self.imported += 1;
+            }
+            Outcome::Updated => {
+                println!("{} Updated: {}", progress, name);
+                self.updated += 1;
+            }
+            Outcome::Skipped(reason) => {
+                println!("{} Skipped: {} ({})", progress, name, reason);
+                self.skipped += 1;
+            }
+            Outcome::Failed(message) => {
+                eprintln!("{} Failed: {} - {}", progress, name, message);
+                self.failed += 1;
+            }
+        }
+    }
+}
+
+/// Get password from argument, `VOSTUFF_PASSWORD`, or interactive prompt
+pub fn read_password(password: Option<&str>) -> Result<String> {
+    if let Some(password) = password {
+        return Ok(password.to_string());
+    }
+
+    if let Ok(password) = env::var("VOSTUFF_PASSWORD") {
+        return Ok(password);
+    }
+
+    print!("Password: ");
+    io::stdout().flush()?;
+    let password = rpassword::read_password()?;
+    Ok(password)
+}
+
+/// A logged-in session with one organization
+pub struct ApiClient {
+    client: Client,
+    api_url: String,
+    token: String,
+    pub org_id: Uuid,
+}
+
+impl ApiClient {
+    /// Log in; a user in several organizations is asked to pick one unless
+    /// `org_id` is given
+    pub async fn login(
+        api_url: &str,
+        username: &str,
+        password: &str,
+        org_id: Option<Uuid>,
+    ) -> Result<Self> {
+        let client = Client::new();
+        let (token, org_id) = authenticate(&client, api_url, username, password, org_id).await?;
+        Ok(Self {
+            client,
+            api_url: api_url.to_string(),
+            token,
+            org_id,
+        })
+    }
+
+    /// Look up the UUID for a kind by name
+    pub async fn kind_id(&self, kind_name: &str) -> Result<Uuid> {
+        let resp = self
+            .client
+            .get(format!(
+                "{}/api/organizations/{}/kinds",
+                self.api_url, self.org_id
+            ))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .send()
+            .await
+            .context("Failed to fetch kinds")?;
+
+        if !resp.status().is_success() {
+            bail!("Failed to fetch kinds: {}", resp.status());
+        }
+
+        let kinds: Vec<KindSummary> = resp.json().await.context("Failed to parse kinds")?;
+
+        kinds
+            .into_iter()
+            .find(|k| k.name == kind_name)
+            .map(|k| k.id)
+            .ok_or_else(|| anyhow::anyhow!("Kind '{}' not found in organisation", kind_name))
+    }
+
+    /// Fetch a kind, with its soft fields, by name
+    pub async fn kind(&self, kind_name: &str) -> Result<KindDetail> {
+        let kind_id = self.kind_id(kind_name).await?;
+
+        let resp = self
+            .client
+            .get(format!(
+                "{}/api/organizations/{}/kinds/{}",
+                self.api_url, self.org_id, kind_id
+            ))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .send()
+            .await
+            .context("Failed to fetch kind")?;
+
+        if !resp.status().is_success() {
+            bail!("Failed to fetch kind: {}", resp.status());
+        }
+
+        resp.json().await.context("Failed to parse kind")
+    }
+
+    /// GET an organization resource, e.g. `locations`
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T> {
+        let resp = self
+            .client
+            .get(format!(
+                "{}/api/organizations/{}/{}",
+                self.api_url, self.org_id, path
+            ))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .query(query)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch {}", path))?;
+
+        if !resp.status().is_success() {
+            bail!("Failed to fetch {}: {}", path, resp.status());
+        }
+
+        resp.json()
+            .await
+            .with_context(|| format!("Failed to parse {}", path))
+    }
+
+    /// Every item the item list returns for the query, page by page
+    pub async fn list_items<T: DeserializeOwned>(
+        &self,
+        query: &[(&str, String)],
+    ) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        let mut after: Option<String> = None;
+        loop {
+            let mut page_query = query.to_vec();
+            page_query.push(("per_page", "500".to_string()));
+            page_query.extend(after.take().map(|a| ("after", a)));
+            let page: ItemPage<T> = self.get("items", &page_query).await?;
+            items.extend(page.items);
+            match page.next_cursor {
+                Some(cursor) => after = Some(cursor),
+                None => return Ok(items),
+            }
+        }
+    }
+
+    /// Every item of the kinds (comma-separated names), to find duplicates
+    pub async fn items(&self, kinds: &str) -> Result<Vec<ExistingItem>> {
+        self.list_items(&[
+            ("kind", kinds.to_string()),
+            ("fields", "id,kind_id,name,barcode".to_string()),
+        ])
+        .await
+    }
+
+    /// Send a create item request, recording the outcome in `stats`
+    pub async fn create_item(
+        &self,
+        create_req: &CreateItemRequest,
+        progress: &str,
+        stats: &mut ImportStats,
+    ) -> Outcome {
+        let resp = self
+            .client
+            .post(format!(
+                "{}/api/organizations/{}/items",
+                self.api_url, self.org_id
+            ))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .json(create_req)
+            .send()
+            .await;
+
+        let outcome = match resp {
+            Ok(response) if response.status().is_success() => Outcome::Imported,
+            // Barcode already present in this org, e.g. re-running an import
+            Ok(response) if response.status() == reqwest::StatusCode::CONFLICT => {
+                Outcome::Skipped("barcode already exists".to_string())
+            }
+            Ok(response) => Outcome::Failed(
+                ErrorResponse::parse(response.text().await.unwrap_or_default()).message,
+            ),
+            Err(e) => Outcome::Failed(e.to_string()),
+        };
+        stats.record(progress, &create_req.name, &outcome);
+        outcome
+    }
+
+    /// Send an update item request, recording the outcome in `stats`
+    pub async fn update_item(
+        &self,
+        item_id: Uuid,
+        name: &str,
+        update_req: &UpdateItemRequest,
+        progress: &str,
+        stats: &mut ImportStats,
+    ) -> Outcome {
+        let resp = self
+            .client
+            .patch(format!(
+                "{}/api/organizations/{}/items/{}",
+                self.api_url, self.org_id, item_id
+            ))
+            .header("Authorization", format!("Bearer {}", self.token))
+            .json(update_req)
+            .send()
+            .await;
+
+        let outcome = match resp {
+            Ok(response) if response.status().is_success() => Outcome::Updated,
+            Ok(response) => Outcome::Failed(
+                ErrorResponse::parse(response.text().await.unwrap_or_default()).message,
+            ),
+            Err(e) => Outcome::Failed(e.to_string()),
+        };
+        stats.record(progress, name, &outcome);
+        outcome
+    }
+}
+
+/// Authenticate with the API
+async fn authenticate(
+    client: &Client,
+    api_url: &str,
+    username: &str,
+    password: &str,
+    org_id: Option<Uuid>,
+) -> Result<(String, Uuid)> {
+    let login_req = LoginRequest {
+        identity: username.to_string(),
+        password: password.to_string(),
+        organization_id: org_id,
+    };
+
+    let resp = client
+        .post(format!("{}/api/auth/login", api_url))
+        .json(&login_req)
+        .send()
+        .await
+        .context("Failed to connect to API server")?;
+
+    let status = resp.status();
+    let body = resp.text().await?;
+
+    if !status.is_success() {
+        bail!(
+            "Authentication failed: {}",
+            ErrorResponse::parse(body).message
+        );
+    }
+
+    // Try to parse as LoginResponse first (single org or org_id provided)
+    if let Ok(login_resp) = serde_json::from_str::<LoginResponse>(&body) {
+        if let Some(org_id) = org_id {
+            return Ok((login_resp.token, org_id));
+        }
+        // If org_id wasn't provided but we got a token, the user has only one
+        // org, named in the response
+        #[derive(Deserialize)]
+        struct FullLoginResponse {
+            token: String,
+            user: UserInfo,
+        }
+        #[derive(Deserialize)]
+        struct UserInfo {
+            organization: OrgInfo,
+        }
+        #[derive(Deserialize)]
+        struct OrgInfo {
+            id: Uuid,
+        }
+
+        let full_resp: FullLoginResponse =
+            serde_json::from_str(&body).context("Failed to parse login response")?;
+        return Ok((full_resp.token, full_resp.user.organization.id));
+    }
+
+    // Parse as org selection response (multi-org user)
+    let org_selection: OrgSelectionResponse =
+        serde_json::from_str(&body).context("Failed to parse org selection response")?;
+
+    println!("\nUser belongs to multiple organizations:");
+    for (i, org) in org_selection.organizations.iter().enumerate() {
+        println!("  {}. {} ({})", i + 1, org.name, org.id);
+    }
+
+    // Prompt for selection
+    print!(
+        "\nSelect organization (1-{}): ",
+        org_selection.organizations.len()
+    );
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let selection: usize = input.trim().parse().context("Invalid selection")?;
+
+    if selection < 1 || selection > org_selection.organizations.len() {
+        bail!("Invalid selection: {}", selection);
+    }
+
+    let selected_org = &org_selection.organizations[selection - 1];
+    println!("Selected: {}", selected_org.name);
+
+    // Call select-org endpoint
+    let select_req = SelectOrgRequest {
+        follow_on_token: org_selection.follow_on_token,
+        organization_id: selected_org.id,
+    };
+
+    let resp = client
+        .post(format!("{}/api/auth/select-org", api_url))
+        .json(&select_req)
+        .send()
+        .await
+        .context("Failed to select organization")?;
+
+    let status = resp.status();
+    let body = resp.text().await?;
+
+    if !status.is_success() {
+        bail!(
+            "Organization selection failed: {}",
+            ErrorResponse::parse(body).message
+        );
+    }
+
+    let login_resp: LoginResponse = serde_json::from_str(&body)
+        .context("Failed to parse login response after org selection")?;
+
+    Ok((login_resp.token, selected_org.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_existing_items_match_by_barcode_then_kind_and_name() {
+        let dvd = Uuid::new_v4();
+        let bluray = Uuid::new_v4();
+        let (alien, heat) = (Uuid::new_v4(), Uuid::new_v4());
+        let existing = ExistingItems::new(vec![
+            ExistingItem {
+                id: alien,
+                kind_id: dvd,
+                name: "Alien  (Director's Cut)".to_string(),
+                barcode: None,
+            },
+            ExistingItem {
+                id: heat,
+                kind_id: bluray,
+                name: "Heat".to_string(),
+                barcode: Some("5039036062376".to_string()),
+            },
+        ]);
+
+        assert_eq!(
+            existing.find(dvd, "alien (director's cut)", None),
+            Some(alien)
+        );
+        // Another kind's item of the same name is a different item
+        assert_eq!(existing.find(bluray, "Alien (Director's Cut)", None), None);
+        // A barcode matches whatever the title and kind
+        assert_eq!(
+            existing.find(dvd, "Heat (1995)", Some(" 5039036062376 ")),
+            Some(heat)
+        );
+        assert_eq!(
+            existing.find(bluray, "Heat", Some("0000000000000")),
+            Some(heat)
+        );
+        assert_eq!(existing.find(dvd, "Ronin", Some("0000000000000")), None);
+    }
+}
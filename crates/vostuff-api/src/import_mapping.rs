@@ -0,0 +1,773 @@
+//! Heuristic CSV column mapping for imports
+//!
+//! Spreadsheet exports name their columns inconsistently ("Artist - Title",
+//! "Cat#", "Media Grade", ...). `suggest_mapping` fuzzy-matches the headers
+//! against item fields and a kind's soft fields to produce a pre-filled
+//! `ImportMapping`, which the user reviews and adjusts before importing.
+//!
+//! Mappings are stored as JSON or TOML, e.g. for a Goodreads export:
+//!
+//! ```toml
+//! kind = "book"
+//! tags = ["goodreads"]
+//!
+//! [[columns]]
+//! column = "Title"
+//! target = "name"
+//!
+//! [[columns]]
+//! column = "Author"
+//! target = "soft:author"
+//!
+//! [[columns]]
+//! column = "Bookshelves"
+//! target = "tags"
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Minimum similarity for a header to be mapped to a target
+const MATCH_THRESHOLD: f64 = 0.75;
+
+/// Where a CSV column's value goes on the created item
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum MappingTarget {
+    Name,
+    Description,
+    /// Appended to the item notes as "- **Header:** value"; may be used by
+    /// several columns
+    Notes,
+    DateAcquired,
+    Barcode,
+    CatalogCode,
+    /// Tag names separated by commas or semicolons; may be used by several
+    /// columns
+    Tags,
+    /// A soft field of the import kind, by field name
+    SoftField(String),
+    Ignore,
+}
+
+impl MappingTarget {
+    /// Whether several columns may share this target
+    fn allows_many(&self) -> bool {
+        matches!(
+            self,
+            MappingTarget::Notes | MappingTarget::Tags | MappingTarget::Ignore
+        )
+    }
+}
+
+impl fmt::Display for MappingTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingTarget::Name => f.write_str("name"),
+            MappingTarget::Description => f.write_str("description"),
+            MappingTarget::Notes => f.write_str("notes"),
+            MappingTarget::DateAcquired => f.write_str("date_acquired"),
+            MappingTarget::Barcode => f.write_str("barcode"),
+            MappingTarget::CatalogCode => f.write_str("catalog_code"),
+            MappingTarget::Tags => f.write_str("tags"),
+            MappingTarget::SoftField(name) => write!(f, "soft:{}", name),
+            MappingTarget::Ignore => f.write_str("ignore"),
+        }
+    }
+}
+
+impl FromStr for MappingTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(MappingTarget::Name),
+            "description" => Ok(MappingTarget::Description),
+            "notes" => Ok(MappingTarget::Notes),
+            "date_acquired" => Ok(MappingTarget::DateAcquired),
+            "barcode" => Ok(MappingTarget::Barcode),
+            "catalog_code" => Ok(MappingTarget::CatalogCode),
+            "tags" => Ok(MappingTarget::Tags),
+            "ignore" => Ok(MappingTarget::Ignore),
+            other => match other.strip_prefix("soft:") {
+                Some(name) if !name.is_empty() => Ok(MappingTarget::SoftField(name.to_string())),
+                _ => Err(format!("Unknown mapping target: {}", other)),
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for MappingTarget {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<MappingTarget> for String {
+    fn from(target: MappingTarget) -> Self {
+        target.to_string()
+    }
+}
+
+/// Mapping of one CSV column
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    /// CSV header, exactly as it appears in the file
+    pub column: String,
+    pub target: MappingTarget,
+    /// Match score from `suggest_mapping`; informational only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+}
+
+/// Column mapping for importing a CSV file as items of one kind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportMapping {
+    /// Kind name the items are created as
+    pub kind: String,
+    /// Tags given to every imported item, e.g. to find them again later
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub columns: Vec<ColumnMapping>,
+}
+
+/// Values of one CSV row, routed according to an `ImportMapping`
+#[derive(Debug, Default, PartialEq)]
+pub struct MappedRow {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub notes: Option<String>,
+    /// Raw date text; see `parse_import_date`
+    pub date_acquired: Option<String>,
+    pub barcode: Option<String>,
+    pub catalog_code: Option<String>,
+    /// Raw soft field text keyed by field name; typed by the caller
+    pub soft_fields: BTreeMap<String, String>,
+    /// The mapping's own tags, then those from tag columns, without repeats
+    pub tags: Vec<String>,
+}
+
+impl ImportMapping {
+    /// Check the mapping is usable against the given CSV headers
+    pub fn validate(&self, headers: &[String]) -> Result<(), String> {
+        let mut seen = HashSet::new();
+        for col in &self.columns {
+            if !headers.contains(&col.column) {
+                return Err(format!("Mapped column not in CSV: {}", col.column));
+            }
+            if !col.target.allows_many() && !seen.insert(&col.target) {
+                return Err(format!("Target mapped more than once: {}", col.target));
+            }
+        }
+        if !seen.contains(&MappingTarget::Name) {
+            return Err("No column is mapped to name".to_string());
+        }
+        Ok(())
+    }
+
+    /// Route a CSV row's values to item fields. Blank values are skipped.
+    pub fn apply(&self, headers: &[String], values: &[String]) -> MappedRow {
+        let mut row = MappedRow {
+            tags: self.tags.clone(),
+            ..Default::default()
+        };
+        let mut notes = Vec::new();
+
+        for col in &self.columns {
+            let Some(value) = headers
+                .iter()
+                .position(|h| *h == col.column)
+                .and_then(|i| values.get(i))
+                .map(|v| unquote(v.trim()))
+                .filter(|v| !v.is_empty())
+            else {
+                continue;
+            };
+
+            match &col.target {
+                MappingTarget::Name => row.name = Some(value.to_string()),
+                MappingTarget::Description => row.description = Some(value.to_string()),
+                MappingTarget::Notes => notes.push(format!("- **{}:** {}", col.column, value)),
+                MappingTarget::DateAcquired => row.date_acquired = Some(value.to_string()),
+                MappingTarget::Barcode => row.barcode = Some(value.to_string()),
+                MappingTarget::CatalogCode => row.catalog_code = Some(value.to_string()),
+                MappingTarget::Tags => row.tags.extend(
+                    value
+                        .split([',', ';'])
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string),
+                ),
+                MappingTarget::SoftField(name) => {
+                    row.soft_fields.insert(name.clone(), value.to_string());
+                }
+                MappingTarget::Ignore => {}
+            }
+        }
+
+        if !notes.is_empty() {
+            row.notes = Some(notes.join("\n"));
+        }
+        let mut seen = HashSet::new();
+        row.tags.retain(|t| seen.insert(t.to_lowercase()));
+        row
+    }
+}
+
+/// A value without the `="..."` wrapping spreadsheet exports such as
+/// Goodreads use to keep ISBNs from being read as numbers
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix("=\"")
+        .and_then(|v| v.strip_suffix('"'))
+        .map_or(value, str::trim)
+}
+
+/// A soft field of the import kind, as needed to type CSV text
+#[derive(Debug, Clone)]
+pub struct SoftFieldSpec {
+    pub name: String,
+    /// string, number, boolean, date or enum
+    pub field_type: String,
+    /// An enum field's values with their display text
+    pub enum_values: Vec<(String, Option<String>)>,
+}
+
+impl SoftFieldSpec {
+    /// Convert CSV text to a JSON value of the field's type
+    pub fn value(&self, raw: &str) -> Result<Value, String> {
+        match self.field_type.as_str() {
+            // Whole numbers stay integers, e.g. a year
+            "number" => raw
+                .parse::<i64>()
+                .map(Value::from)
+                .or_else(|_| raw.parse::<f64>().map(Value::from))
+                .map_err(|_| format!("{}: not a number: {}", self.name, raw)),
+            "boolean" => match raw.to_lowercase().as_str() {
+                "true" | "yes" | "y" | "1" => Ok(true.into()),
+                "false" | "no" | "n" | "0" => Ok(false.into()),
+                _ => Err(format!("{}: not a yes/no value: {}", self.name, raw)),
+            },
+            "date" => parse_import_date(raw)
+                .map(|d| d.to_string().into())
+                .ok_or_else(|| format!("{}: invalid date: {}", self.name, raw)),
+            // Accept either the stored value or its display text, e.g. "Near Mint"
+            "enum" => self
+                .enum_values
+                .iter()
+                .find(|(value, display)| {
+                    value.eq_ignore_ascii_case(raw)
+                        || display
+                            .as_deref()
+                            .is_some_and(|d| d.eq_ignore_ascii_case(raw))
+                })
+                .map(|(value, _)| value.clone().into())
+                .ok_or_else(|| format!("{}: unknown value: {}", self.name, raw)),
+            _ => Ok(raw.into()),
+        }
+    }
+}
+
+/// Type a mapped row's soft field text by the import kind's fields; the first
+/// value that does not fit is the error
+pub fn typed_soft_fields(
+    raw: &BTreeMap<String, String>,
+    fields: &[SoftFieldSpec],
+) -> Result<Map<String, Value>, String> {
+    raw.iter()
+        .map(|(name, text)| {
+            let field = fields
+                .iter()
+                .find(|f| &f.name == name)
+                .ok_or_else(|| format!("kind has no field '{}'", name))?;
+            Ok((name.clone(), field.value(text)?))
+        })
+        .collect()
+}
+
+/// Parse the date formats commonly found in collection exports
+pub fn parse_import_date(s: &str) -> Option<NaiveDate> {
+    const FORMATS: &[&str] = &["%Y-%m-%d", "%b %d, %Y", "%d %b %Y", "%d/%m/%Y", "%Y/%m/%d"];
+    let s = s.trim();
+    FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(s, fmt).ok())
+}
+
+// ── Suggestion ──────────────────────────────────────────────────────────────
+
+/// Known spellings of the built-in item fields
+fn builtin_synonyms() -> Vec<(MappingTarget, &'static [&'static str])> {
+    vec![
+        (
+            MappingTarget::Name,
+            &[
+                "name",
+                "title",
+                "artist title",
+                "album",
+                "album title",
+                "item",
+            ],
+        ),
+        (
+            MappingTarget::Description,
+            &["description", "summary", "subtitle", "artist", "author"],
+        ),
+        (
+            MappingTarget::Notes,
+            &["notes", "note", "comments", "comment", "remarks"],
+        ),
+        (
+            MappingTarget::DateAcquired,
+            &[
+                "date acquired",
+                "acquired",
+                "added date",
+                "date added",
+                "purchase date",
+                "date purchased",
+            ],
+        ),
+        (
+            MappingTarget::Barcode,
+            &["barcode", "upc", "ean", "isbn", "isbn 13", "isbn13", "gtin"],
+        ),
+        (
+            MappingTarget::CatalogCode,
+            &[
+                "catalog code",
+                "catalog number",
+                "catalogue number",
+                "catalog",
+                "catalogue",
+                "cat no",
+                "catno",
+                "cat",
+            ],
+        ),
+        (
+            MappingTarget::Tags,
+            &["tags", "tag", "bookshelves", "shelves", "keywords"],
+        ),
+    ]
+}
+
+/// Extra spellings for well-known soft fields, used when the kind has them
+fn soft_field_synonyms(name: &str) -> &'static [&'static str] {
+    match name {
+        "media_grading" => &["condition", "media grade", "media condition", "grade"],
+        "sleeve_grading" => &["sleeve grade", "sleeve condition", "cover condition"],
+        "author" => &["primary author", "authors", "writer"],
+        "publisher" => &["publication"],
+        "year" => &[
+            "year published",
+            "publication year",
+            "original publication year",
+        ],
+        "book_format" => &["binding", "format"],
+        _ => &[],
+    }
+}
+
+/// Lowercase and reduce punctuation to single spaces: "Artist - Title" → "artist title"
+fn normalize(s: &str) -> String {
+    s.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn similarity(header: &str, candidate: &str) -> f64 {
+    if header == candidate {
+        return 1.0;
+    }
+    // Word-order independent match, e.g. "grade media" vs "media grade"
+    let h: HashSet<&str> = header.split(' ').collect();
+    let c: HashSet<&str> = candidate.split(' ').collect();
+    let jaccard = h.intersection(&c).count() as f64 / h.union(&c).count() as f64;
+    strsim::normalized_levenshtein(header, candidate).max(jaccard * 0.9)
+}
+
+/// Suggest a mapping for the given CSV headers
+///
+/// `soft_fields` lists the import kind's soft fields as (name, display name).
+/// Each target other than notes and tags is used at most once, best matches
+/// first, with the kind's own fields winning ties over item fields ("Author"
+/// is a book's author rather than its description); headers without a good
+/// match are mapped to ignore.
+pub fn suggest_mapping(
+    kind: &str,
+    headers: &[String],
+    soft_fields: &[(String, Option<String>)],
+) -> ImportMapping {
+    let mut candidates: Vec<(MappingTarget, Vec<String>)> = Vec::new();
+    for (name, display_name) in soft_fields {
+        let mut names = vec![normalize(name)];
+        names.extend(display_name.as_deref().map(normalize));
+        names.extend(soft_field_synonyms(name).iter().map(|n| n.to_string()));
+        candidates.push((MappingTarget::SoftField(name.clone()), names));
+    }
+    candidates.extend(
+        builtin_synonyms()
+            .into_iter()
+            .map(|(target, names)| (target, names.iter().map(|n| n.to_string()).collect())),
+    );
+
+    // Score every (header, target) pair above the threshold
+    let mut scored = Vec::new();
+    for (col_idx, header) in headers.iter().enumerate() {
+        let header = normalize(header);
+        for (target_idx, (_, names)) in candidates.iter().enumerate() {
+            let best = names
+                .iter()
+                .map(|n| similarity(&header, n))
+                .fold(0.0, f64::max);
+            if best >= MATCH_THRESHOLD {
+                scored.push((best, col_idx, target_idx));
+            }
+        }
+    }
+    // Best first; ties go to the earlier column, then the earlier target
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+    let mut assigned: Vec<Option<(MappingTarget, f64)>> = vec![None; headers.len()];
+    let mut used = HashSet::new();
+    for (score, col_idx, target_idx) in scored {
+        let target = &candidates[target_idx].0;
+        if assigned[col_idx].is_some() || used.contains(&target_idx) {
+            continue;
+        }
+        if !target.allows_many() {
+            used.insert(target_idx);
+        }
+        assigned[col_idx] = Some((target.clone(), score));
+    }
+
+    let columns = headers
+        .iter()
+        .zip(assigned)
+        .map(|(column, assignment)| {
+            let (target, confidence) = match assignment {
+                Some((target, score)) => (target, Some((score * 100.0).round() / 100.0)),
+                None => (MappingTarget::Ignore, None),
+            };
+            ColumnMapping {
+                column: column.clone(),
+                target,
+                confidence,
+            }
+        })
+        .collect();
+
+    ImportMapping {
+        kind: kind.to_string(),
+        tags: Vec::new(),
+        columns,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn vinyl_fields() -> Vec<(String, Option<String>)> {
+        vec![
+            (
+                "media_grading".to_string(),
+                Some("Media Grading".to_string()),
+            ),
+            (
+                "sleeve_grading".to_string(),
+                Some("Sleeve Grading".to_string()),
+            ),
+            ("size".to_string(), Some("Size".to_string())),
+        ]
+    }
+
+    fn target_of(mapping: &ImportMapping, column: &str) -> MappingTarget {
+        mapping
+            .columns
+            .iter()
+            .find(|c| c.column == column)
+            .unwrap()
+            .target
+            .clone()
+    }
+
+    #[test]
+    fn test_suggest_common_headers() {
+        let h = headers(&[
+            "Artist - Title",
+            "Condition",
+            "Sleeve Grade",
+            "Cat#",
+            "UPC",
+            "Date Added",
+            "Comments",
+            "Pressing Plant",
+        ]);
+        let mapping = suggest_mapping("vinyl", &h, &vinyl_fields());
+
+        assert_eq!(target_of(&mapping, "Artist - Title"), MappingTarget::Name);
+        assert_eq!(
+            target_of(&mapping, "Condition"),
+            MappingTarget::SoftField("media_grading".to_string())
+        );
+        assert_eq!(
+            target_of(&mapping, "Sleeve Grade"),
+            MappingTarget::SoftField("sleeve_grading".to_string())
+        );
+        assert_eq!(target_of(&mapping, "Cat#"), MappingTarget::CatalogCode);
+        assert_eq!(target_of(&mapping, "UPC"), MappingTarget::Barcode);
+        assert_eq!(
+            target_of(&mapping, "Date Added"),
+            MappingTarget::DateAcquired
+        );
+        assert_eq!(target_of(&mapping, "Comments"), MappingTarget::Notes);
+        assert_eq!(target_of(&mapping, "Pressing Plant"), MappingTarget::Ignore);
+    }
+
+    #[test]
+    fn test_suggest_uses_each_target_once() {
+        let h = headers(&["Title", "Album Title", "Media Grade", "Media Grading"]);
+        let mapping = suggest_mapping("vinyl", &h, &vinyl_fields());
+
+        assert_eq!(target_of(&mapping, "Title"), MappingTarget::Name);
+        assert_eq!(target_of(&mapping, "Album Title"), MappingTarget::Ignore);
+        // Equally good matches go to the first column
+        assert_eq!(
+            target_of(&mapping, "Media Grade"),
+            MappingTarget::SoftField("media_grading".to_string())
+        );
+        assert_eq!(target_of(&mapping, "Media Grading"), MappingTarget::Ignore);
+    }
+
+    #[test]
+    fn test_soft_synonyms_need_the_field() {
+        // A CD has no grading field, so "Condition" stays unmapped
+        let mapping = suggest_mapping("cd", &headers(&["Title", "Condition"]), &[]);
+        assert_eq!(target_of(&mapping, "Condition"), MappingTarget::Ignore);
+    }
+
+    #[test]
+    fn test_mapping_json_round_trip() {
+        let json = r#"{"kind":"vinyl","columns":[
+            {"column":"Title","target":"name"},
+            {"column":"Grade","target":"soft:media_grading"}
+        ]}"#;
+        let mapping: ImportMapping = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            mapping.columns[1].target,
+            MappingTarget::SoftField("media_grading".to_string())
+        );
+
+        let out = serde_json::to_value(&mapping).unwrap();
+        assert_eq!(out["columns"][1]["target"], "soft:media_grading");
+        assert!(out["columns"][0].get("confidence").is_none());
+
+        let bad = r#"{"kind":"vinyl","columns":[{"column":"Title","target":"colour"}]}"#;
+        assert!(serde_json::from_str::<ImportMapping>(bad).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let h = headers(&["Title", "Notes", "Other"]);
+        let mut mapping = suggest_mapping("cd", &h, &[]);
+        assert!(mapping.validate(&h).is_ok());
+
+        mapping.columns[2].target = MappingTarget::Name;
+        assert!(mapping.validate(&h).is_err());
+
+        mapping.columns[2].target = MappingTarget::Ignore;
+        mapping.columns[0].target = MappingTarget::Ignore;
+        assert!(mapping.validate(&h).is_err());
+    }
+
+    #[test]
+    fn test_apply_routes_values() {
+        let h = headers(&["Title", "Label", "Pressing", "Grade", "Barcode"]);
+        let mapping = ImportMapping {
+            kind: "vinyl".to_string(),
+            tags: Vec::new(),
+            columns: vec![
+                ColumnMapping {
+                    column: "Title".to_string(),
+                    target: MappingTarget::Name,
+                    confidence: None,
+                },
+                ColumnMapping {
+                    column: "Label".to_string(),
+                    target: MappingTarget::Notes,
+                    confidence: None,
+                },
+                ColumnMapping {
+                    column: "Pressing".to_string(),
+                    target: MappingTarget::Notes,
+                    confidence: None,
+                },
+                ColumnMapping {
+                    column: "Grade".to_string(),
+                    target: MappingTarget::SoftField("media_grading".to_string()),
+                    confidence: None,
+                },
+                ColumnMapping {
+                    column: "Barcode".to_string(),
+                    target: MappingTarget::Barcode,
+                    confidence: None,
+                },
+            ],
+        };
+
+        let row = mapping.apply(
+            &h,
+            &headers(&[" Abbey Road ", "Apple", "UK 1st", "near_mint", ""]),
+        );
+        assert_eq!(row.name.as_deref(), Some("Abbey Road"));
+        assert_eq!(
+            row.notes.as_deref(),
+            Some("- **Label:** Apple\n- **Pressing:** UK 1st")
+        );
+        assert_eq!(row.soft_fields["media_grading"], "near_mint");
+        assert_eq!(row.barcode, None);
+    }
+
+    #[test]
+    fn test_suggest_goodreads_headers() {
+        let h = headers(&[
+            "Title",
+            "Author",
+            "ISBN13",
+            "Publisher",
+            "Year Published",
+            "Binding",
+            "Bookshelves",
+            "Date Added",
+        ]);
+        let book_fields: Vec<(String, Option<String>)> =
+            ["author", "isbn", "publisher", "year", "book_format"]
+                .iter()
+                .map(|n| (n.to_string(), None))
+                .collect();
+        let mapping = suggest_mapping("book", &h, &book_fields);
+
+        // The book's own author field beats the description synonym
+        assert_eq!(
+            target_of(&mapping, "Author"),
+            MappingTarget::SoftField("author".to_string())
+        );
+        assert_eq!(target_of(&mapping, "ISBN13"), MappingTarget::Barcode);
+        assert_eq!(
+            target_of(&mapping, "Year Published"),
+            MappingTarget::SoftField("year".to_string())
+        );
+        assert_eq!(
+            target_of(&mapping, "Binding"),
+            MappingTarget::SoftField("book_format".to_string())
+        );
+        assert_eq!(target_of(&mapping, "Bookshelves"), MappingTarget::Tags);
+    }
+
+    #[test]
+    fn test_apply_collects_tags() {
+        let h = headers(&["Title", "ISBN", "Bookshelves", "Genre"]);
+        let mapping: ImportMapping = toml::from_str(
+            r#"
+            kind = "book"
+            tags = ["goodreads"]
+
+            [[columns]]
+            column = "Title"
+            target = "name"
+
+            [[columns]]
+            column = "ISBN"
+            target = "barcode"
+
+            [[columns]]
+            column = "Bookshelves"
+            target = "tags"
+
+            [[columns]]
+            column = "Genre"
+            target = "tags"
+            "#,
+        )
+        .unwrap();
+        assert!(mapping.validate(&h).is_ok());
+
+        let row = mapping.apply(
+            &h,
+            &headers(&["Dune", "=\"0441172717\"", "to-read; sci-fi,", "Sci-Fi"]),
+        );
+        assert_eq!(row.barcode.as_deref(), Some("0441172717"));
+        assert_eq!(row.tags, vec!["goodreads", "to-read", "sci-fi"]);
+
+        // And back again, with the mapping's tags ahead of its columns
+        let out = toml::to_string(&mapping).unwrap();
+        assert!(out.starts_with("kind = \"book\"\ntags = [\"goodreads\"]"));
+        let back: ImportMapping = toml::from_str(&out).unwrap();
+        assert_eq!(back.columns[2].target, MappingTarget::Tags);
+    }
+
+    #[test]
+    fn test_typed_soft_fields() {
+        let fields = vec![
+            SoftFieldSpec {
+                name: "media_grading".to_string(),
+                field_type: "enum".to_string(),
+                enum_values: vec![("near_mint".to_string(), Some("Near Mint".to_string()))],
+            },
+            SoftFieldSpec {
+                name: "disks".to_string(),
+                field_type: "number".to_string(),
+                enum_values: Vec::new(),
+            },
+        ];
+        let raw = |pairs: &[(&str, &str)]| -> BTreeMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        let typed = typed_soft_fields(
+            &raw(&[("media_grading", "near mint"), ("disks", "2")]),
+            &fields,
+        );
+        assert_eq!(
+            Value::Object(typed.unwrap()),
+            serde_json::json!({"media_grading": "near_mint", "disks": 2})
+        );
+        assert_eq!(
+            typed_soft_fields(&raw(&[("disks", "two")]), &fields),
+            Err("disks: not a number: two".to_string())
+        );
+        assert_eq!(
+            typed_soft_fields(&raw(&[("colour", "red")]), &fields),
+            Err("kind has no field 'colour'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_import_date() {
+        let expected = NaiveDate::from_ymd_opt(2022, 11, 9);
+        assert_eq!(parse_import_date("2022-11-09"), expected);
+        assert_eq!(parse_import_date("Nov 09, 2022"), expected);
+        assert_eq!(parse_import_date("9 Nov 2022"), expected);
+        assert_eq!(parse_import_date("09/11/2022"), expected);
+        assert_eq!(parse_import_date("last week"), None);
+    }
+}
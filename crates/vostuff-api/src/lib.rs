@@ -1,7 +1,12 @@
 pub mod api;
+pub mod backup;
+pub mod generator;
+pub mod import_client;
+pub mod import_mapping;
 pub mod schema;
 pub mod test_utils;
 
 // Re-export core modules for convenience
 pub use vostuff_core::auth;
 pub use vostuff_core::models;
+pub use vostuff_core::permissions;
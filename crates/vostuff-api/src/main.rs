@@ -1,11 +1,284 @@
-// Main function
-fn main() {
-    println!("VOStuff - Stuff tracking application");
-    println!("Use 'cargo run --bin schema-manager' to manage the database schema");
+//! The API server, run as `api-server`: serves the REST API under `/api`,
+//! with Swagger UI and optional Prometheus metrics alongside.
 
-    println!("fibonacci(15) = {}", fibonacci(15));
+use axum::{
+    Router,
+    extract::Request,
+    http::{HeaderValue, Method, header},
+};
+use clap::Parser;
+use sqlx::{Connection, PgConnection, PgPool};
+use std::sync::Arc;
+use std::time::Duration;
+use tower_http::{compression::CompressionLayer, cors::CorsLayer, trace::TraceLayer};
+
+use vostuff_api::api::{
+    docs,
+    imports::ImportItemsJob,
+    jobs::{self, JobHandler},
+    live::LiveSink,
+    lookup::LookupItemsJob,
+    mail::{InviteMailSink, MailConfig, SendEmailJob},
+    metrics,
+    outbox::{self, EventSink, LogSink},
+    retention::{self, HISTORY_COMPACTION_INTERVAL},
+    state::{AppState, TermsOfService},
+};
+use vostuff_core::auth::ldap::{LdapBackend, LdapConfig};
+use vostuff_core::auth::oidc::{OidcConfig, OidcProvider};
+use vostuff_core::config::Config;
+use vostuff_core::storage::StorageConfig;
+use vostuff_core::telemetry;
+
+/// Attempts to connect to the database before giving up, so the server can
+/// start alongside a database that is still starting up
+const DB_CONNECT_ATTEMPTS: u32 = 10;
+
+/// Longest wait between database connection attempts
+const DB_CONNECT_MAX_DELAY: Duration = Duration::from_secs(10);
+
+#[derive(Parser)]
+#[command(name = "api-server")]
+#[command(about = "VOStuff REST API server")]
+struct Cli {
+    /// Check the configuration, print it and exit without starting the server
+    #[arg(long)]
+    check_config: bool,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    // Settings from vostuff.toml and the environment, checked before anything starts
+    let config = Config::load()?;
+    if cli.check_config {
+        return check_config(&config);
+    }
+
+    // Logging, and trace export when an OTLP endpoint is configured
+    let _telemetry = telemetry::init(
+        "vostuff-api",
+        "api_server=debug,tower_http=debug,axum=debug",
+        config.otlp_endpoint.as_deref(),
+    )?;
+    if config.uses_dev_jwt_secret() {
+        tracing::warn!("JWT_SECRET is not set; using the development secret");
+    }
+    let jwt_secret = config.jwt_secret.clone();
+
+    tracing::info!("Connecting to database: {}", config.redacted_database_url());
+    let pool = connect_pool(&config.database_url).await?;
+
+    let storage_config = StorageConfig::from_config(&config);
+    tracing::info!("Using storage backend: {:?}", storage_config);
+    let storage = storage_config.build(&jwt_secret)?;
+
+    let mut state = AppState::new(pool.clone(), jwt_secret.clone())
+        .with_access_token_ttl_minutes(config.access_token_ttl_minutes)
+        .with_export_retention_days(config.data_export_retention_days)
+        .with_storage(storage)
+        .with_share_url(config.share_url.clone())
+        .with_item_url(config.item_url.clone());
+
+    // Deliver events written to the outbox: invites are emailed, changes are
+    // streamed to clients watching the organization, and webhooks plug in as further sinks
+    let mail_config = MailConfig::from_config(&config);
+    let sinks: Vec<Arc<dyn EventSink>> = vec![
+        Arc::new(LogSink),
+        Arc::new(InviteMailSink::new(
+            mail_config.build_mailer(),
+            &jwt_secret,
+            mail_config.invite_url.clone(),
+        )),
+        Arc::new(LiveSink::new(state.live.clone())),
+    ];
+    outbox::spawn_dispatcher(pool.clone(), sinks, Duration::from_secs(1));
+
+    // Run background jobs, such as broadcast emails
+    let job_handlers: Vec<Arc<dyn JobHandler>> = vec![
+        Arc::new(SendEmailJob::new(mail_config.build_mailer())),
+        Arc::new(LookupItemsJob::new(
+            pool.clone(),
+            state.metadata_providers.to_vec(),
+        )),
+        Arc::new(ImportItemsJob::new(pool.clone(), state.storage.clone())),
+    ];
+    jobs::spawn_worker(pool.clone(), job_handlers, Duration::from_secs(1));
+
+    // Compact old item history for organizations with a retention period
+    retention::spawn_history_compaction(pool.clone(), HISTORY_COMPACTION_INTERVAL);
+
+    // Optional password login against the organization's directory
+    if let Some(ldap_config) = LdapConfig::from_config(&config) {
+        tracing::info!("Enabling LDAP login via {}", ldap_config.url);
+        state = state.with_auth_backend(Arc::new(LdapBackend::new(ldap_config)));
+    }
+
+    // Optional login through an external identity provider
+    if let Some(oidc_config) = OidcConfig::from_config(&config) {
+        tracing::info!("Enabling OIDC login via {}", oidc_config.issuer);
+        let provider = OidcProvider::discover(oidc_config).await?;
+        state = state.with_oidc(Arc::new(provider));
+    }
+
+    // Hosted instances can require users to accept their terms of service
+    if let Some(version) = config.terms_version.clone() {
+        tracing::info!(
+            "Requiring acceptance of terms of service version {}",
+            version
+        );
+        state = state.with_terms(TermsOfService {
+            version,
+            url: config.terms_url.clone(),
+        });
+    }
+
+    // Request and database pool metrics for Prometheus at /metrics
+    if config.metrics_enabled {
+        tracing::info!("Serving Prometheus metrics at /metrics");
+        state = state.with_metrics(metrics::install_recorder());
+    }
+
+    // Build API router using shared function
+    let api_router = vostuff_api::api::handlers::build_router(state.clone());
+
+    // Build main app with Swagger UI
+    let mut app = Router::new()
+        .merge(docs::router())
+        .merge(metrics::router(state))
+        .nest("/api", api_router)
+        .layer(CompressionLayer::new())
+        .layer(TraceLayer::new_for_http().make_span_with(request_span));
+
+    // Browser clients on other origins, such as a separately hosted front end
+    if !config.cors_origins.is_empty() {
+        tracing::info!(
+            "Allowing cross-origin requests from {:?}",
+            config.cors_origins
+        );
+        app = app.layer(cors_layer(&config.cors_origins)?);
+    }
+
+    // Start server
+    let addr = config.bind_address;
+    tracing::info!("Starting server on {}", addr);
+    tracing::info!("Swagger UI available at http://{}{}", addr, docs::DOCS_PATH);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    tracing::info!("Server stopped");
+    Ok(())
+}
+
+/// Validate the settings read at startup without connecting to anything,
+/// such as before deploying a new configuration
+fn check_config(config: &Config) -> anyhow::Result<()> {
+    let ldap = LdapConfig::from_config(config);
+    let oidc = OidcConfig::from_config(config);
+    let _ = cors_layer(&config.cors_origins)?;
+
+    println!("Configuration is valid");
+    println!("{:#?}", config);
+    match ldap {
+        Some(ldap) => println!("LDAP login via {}", ldap.url),
+        None => println!("LDAP login disabled"),
+    }
+    match oidc {
+        Some(oidc) => println!("OIDC login via {}", oidc.issuer),
+        None => println!("OIDC login disabled"),
+    }
+    Ok(())
+}
+
+/// Connect to the database, retrying with backoff while it is unreachable.
+/// Each attempt is a single connection, as the pool itself keeps retrying
+/// until its acquire timeout before reporting an error.
+async fn connect_pool(database_url: &str) -> anyhow::Result<PgPool> {
+    let mut delay = Duration::from_millis(500);
+    let mut attempt = 1;
+    loop {
+        match PgConnection::connect(database_url).await {
+            Ok(connection) => {
+                connection.close().await?;
+                return Ok(PgPool::connect(database_url).await?);
+            }
+            Err(e) if attempt < DB_CONNECT_ATTEMPTS => {
+                tracing::warn!(
+                    "Database connection failed (attempt {} of {}), retrying in {:?}: {}",
+                    attempt,
+                    DB_CONNECT_ATTEMPTS,
+                    delay,
+                    e
+                );
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(DB_CONNECT_MAX_DELAY);
+                attempt += 1;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Resolves on Ctrl+C or SIGTERM, such as from `docker stop`; requests in
+/// progress are then allowed to finish before the server exits
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to listen for Ctrl+C");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    tracing::info!("Shutting down; waiting for requests in progress");
+}
+
+/// Span for each request, continuing the caller's trace if it sent one, such
+/// as the web server's server function; SQL statements are its children
+fn request_span(request: &Request) -> tracing::Span {
+    let span = tracing::info_span!(
+        "request",
+        otel.name = %format!("{} {}", request.method(), request.uri().path()),
+        otel.kind = "server",
+        method = %request.method(),
+        uri = %request.uri(),
+    );
+    telemetry::continue_trace(&span, request.headers());
+    span
 }
 
-fn fibonacci(n: usize) -> usize {
-    n + 1
+/// CORS for the configured origins. The API authenticates with bearer tokens,
+/// so credentials (cookies) are not allowed.
+fn cors_layer(origins: &[String]) -> anyhow::Result<CorsLayer> {
+    let origins = origins
+        .iter()
+        .map(|origin| origin.parse::<HeaderValue>())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+        .expose_headers([header::LOCATION]))
 }
@@ -1,11 +1,54 @@
+use crate::api::handlers::borrowers::record_loan;
+use crate::api::models::UserRole;
 use crate::auth::PasswordHasher;
+use crate::generator::{DEFAULT_KIND_MIX, ItemGenerator};
+use crate::permissions::Permissions;
 use anyhow::Result;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde_json::{Value, json};
 use sqlx::{PgPool, Row};
+use std::sync::Mutex;
 use uuid::Uuid;
 
+/// Seed used unless another is given, so repeated loads produce the same data
+pub const DEFAULT_SEED: u64 = 42;
+
+/// Password of every user the seed profiles create
+pub const SEED_PASSWORD: &str = "secret123";
+
+/// Named sets of seed data.
+///
+/// Generated values (acquisition dates, generated items, their states, tags
+/// and collections) come from an RNG with a fixed seed, so loading a profile
+/// into an empty database with the same seed gives the same data every time,
+/// apart from database-assigned ids and dates relative to today.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SeedProfile {
+    /// Coke and Pepsi with about 50 items each, as the README's test credentials expect
+    Sample,
+    /// One organization, one admin and three items; a quick smoke test
+    Minimal,
+    /// A record collection: vinyl, CDs, cassettes and audio equipment
+    DemoMusic,
+    /// A lending library: books, scores and DVDs, with loans out
+    DemoLibrary,
+    /// One organization with 10,000 generated items of every kind, for benchmarks
+    #[value(name = "stress-10k")]
+    Stress10k,
+}
+
+/// What a seed profile created
+#[derive(Debug)]
+pub struct SeedResult {
+    pub organization_ids: Vec<Uuid>,
+    pub user_ids: Vec<Uuid>,
+    pub item_count: usize,
+}
+
 pub struct SampleDataLoader<'a> {
     pool: &'a PgPool,
+    rng: Mutex<ChaCha8Rng>,
 }
 
 pub struct SampleDataResult {
@@ -17,7 +60,32 @@ pub struct SampleDataResult {
 
 impl<'a> SampleDataLoader<'a> {
     pub fn new(pool: &'a PgPool) -> Self {
-        Self { pool }
+        Self::with_seed(pool, DEFAULT_SEED)
+    }
+
+    pub fn with_seed(pool: &'a PgPool, seed: u64) -> Self {
+        Self {
+            pool,
+            rng: Mutex::new(ChaCha8Rng::seed_from_u64(seed)),
+        }
+    }
+
+    /// Load a seed profile
+    pub async fn load_profile(&self, profile: SeedProfile) -> Result<SeedResult> {
+        match profile {
+            SeedProfile::Sample => {
+                let result = self.load_sample_data().await?;
+                Ok(SeedResult {
+                    organization_ids: vec![result.coke_org_id, result.pepsi_org_id],
+                    user_ids: vec![result.bob_user_id, result.alice_user_id],
+                    item_count: 2 * SAMPLE_ITEMS_PER_ORG,
+                })
+            }
+            SeedProfile::Minimal => self.load_minimal().await,
+            SeedProfile::DemoMusic => self.load_demo_music().await,
+            SeedProfile::DemoLibrary => self.load_demo_library().await,
+            SeedProfile::Stress10k => self.load_stress().await,
+        }
     }
 
     pub async fn load_sample_data(&self) -> Result<SampleDataResult> {
@@ -37,14 +105,10 @@ impl<'a> SampleDataLoader<'a> {
             .await?;
 
         // Associate users with orgs and assign roles
-        self.add_user_to_org(bob_id, coke_id, vec!["USER".to_string()])
+        self.add_user_to_org(bob_id, coke_id, vec![UserRole::User])
+            .await?;
+        self.add_user_to_org(alice_id, pepsi_id, vec![UserRole::User, UserRole::Admin])
             .await?;
-        self.add_user_to_org(
-            alice_id,
-            pepsi_id,
-            vec!["USER".to_string(), "ADMIN".to_string()],
-        )
-        .await?;
 
         // Create locations for each org
         let coke_locations = self.create_locations(coke_id).await?;
@@ -78,6 +142,189 @@ impl<'a> SampleDataLoader<'a> {
         })
     }
 
+    async fn load_minimal(&self) -> Result<SeedResult> {
+        println!("Loading minimal seed data...");
+        let org_id = self.create_org("Acme", "A minimal organization").await?;
+        let user_id = self
+            .create_user("Ada", "ada@acme.example", Some(SEED_PASSWORD))
+            .await?;
+        self.add_user_to_org(user_id, org_id, vec![UserRole::User, UserRole::Admin])
+            .await?;
+
+        let rec = sqlx::query!(
+            "INSERT INTO locations (organization_id, name) VALUES ($1, $2) RETURNING id",
+            org_id,
+            "Shelf"
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        let items = [
+            (
+                "vinyl",
+                "Kind of Blue - Miles Davis",
+                json!({ "size": "12_inch", "speed": "33" }),
+            ),
+            ("cd", "OK Computer - Radiohead", json!({ "disks": 1 })),
+            (
+                "book",
+                "Dune - Frank Herbert",
+                json!({ "author": "Frank Herbert", "year": 1965 }),
+            ),
+        ];
+        let item_count = items.len();
+        for (kind, name, soft_fields) in items {
+            let kind_id = self.get_kind_id(kind).await?;
+            self.create_item(org_id, kind_id, "current", name, name, rec.id, soft_fields)
+                .await?;
+        }
+
+        println!("✓ Minimal seed data loaded");
+        Ok(SeedResult {
+            organization_ids: vec![org_id],
+            user_ids: vec![user_id],
+            item_count,
+        })
+    }
+
+    async fn load_demo_music(&self) -> Result<SeedResult> {
+        println!("Loading music demo data...");
+        let org_id = self
+            .create_org("Vinyl Vault", "A record collector's shelves")
+            .await?;
+        let user_id = self
+            .create_user("Dee", "dee@music.example", Some(SEED_PASSWORD))
+            .await?;
+        self.add_user_to_org(user_id, org_id, vec![UserRole::User, UserRole::Admin])
+            .await?;
+        let locations = self.create_locations(org_id).await?;
+        let collections = self.create_collections(org_id).await?;
+        self.create_tags(org_id).await?;
+
+        self.create_vinyl_items(
+            org_id,
+            self.get_kind_id("vinyl").await?,
+            &locations,
+            &collections,
+        )
+        .await?;
+        self.create_cd_items(
+            org_id,
+            self.get_kind_id("cd").await?,
+            &locations,
+            &collections,
+        )
+        .await?;
+        self.create_cassette_items(org_id, self.get_kind_id("cassette").await?, &locations)
+            .await?;
+        self.create_electronics_items(org_id, self.get_kind_id("electronics").await?, &locations)
+            .await?;
+        let generated = self
+            .generate_items(
+                org_id,
+                &[("vinyl", 5), ("cd", 4), ("cassette", 1)],
+                DEMO_GENERATED_ITEMS,
+            )
+            .await?;
+
+        println!("✓ Music demo data loaded");
+        Ok(SeedResult {
+            organization_ids: vec![org_id],
+            user_ids: vec![user_id],
+            item_count: 10 + 10 + 8 + 4 + generated,
+        })
+    }
+
+    async fn load_demo_library(&self) -> Result<SeedResult> {
+        println!("Loading library demo data...");
+        let org_id = self
+            .create_org("Town Library", "A small lending library")
+            .await?;
+        let user_id = self
+            .create_user("Lee", "lee@library.example", Some(SEED_PASSWORD))
+            .await?;
+        self.add_user_to_org(user_id, org_id, vec![UserRole::User, UserRole::Admin])
+            .await?;
+        let locations = self.create_locations(org_id).await?;
+        let collections = self.create_collections(org_id).await?;
+        self.create_tags(org_id).await?;
+
+        self.create_book_items(
+            org_id,
+            self.get_kind_id("book").await?,
+            &locations,
+            &collections,
+        )
+        .await?;
+        self.create_score_items(org_id, self.get_kind_id("score").await?, &locations)
+            .await?;
+        self.create_dvd_items(org_id, self.get_kind_id("dvd").await?, &locations)
+            .await?;
+        let generated = self
+            .generate_items(
+                org_id,
+                &[("book", 3), ("score", 1), ("dvd", 1)],
+                DEMO_GENERATED_ITEMS,
+            )
+            .await?;
+
+        println!("✓ Library demo data loaded");
+        Ok(SeedResult {
+            organization_ids: vec![org_id],
+            user_ids: vec![user_id],
+            item_count: 8 + 6 + 6 + generated,
+        })
+    }
+
+    async fn load_stress(&self) -> Result<SeedResult> {
+        println!("Loading stress test data...");
+        let org_id = self
+            .create_org("Stress Test", "10,000 generated items")
+            .await?;
+        let user_id = self
+            .create_user("Sam", "sam@stress.example", Some(SEED_PASSWORD))
+            .await?;
+        self.add_user_to_org(user_id, org_id, vec![UserRole::User, UserRole::Admin])
+            .await?;
+        self.create_locations(org_id).await?;
+        self.create_collections(org_id).await?;
+        self.create_tags(org_id).await?;
+
+        let generated = self
+            .generate_items(org_id, DEFAULT_KIND_MIX, STRESS_ITEMS)
+            .await?;
+
+        println!("✓ Stress test data loaded");
+        Ok(SeedResult {
+            organization_ids: vec![org_id],
+            user_ids: vec![user_id],
+            item_count: generated,
+        })
+    }
+
+    /// Add `count` generated items of the given kinds, spread over the
+    /// organization's locations and collections. Returns the number created.
+    async fn generate_items(
+        &self,
+        org_id: Uuid,
+        kinds: &[(&str, u32)],
+        count: usize,
+    ) -> Result<usize> {
+        // Drawn from the profile's RNG so the whole profile follows one seed
+        let seed = self.rng.lock().unwrap().r#gen::<u64>();
+        let report = ItemGenerator::new(self.pool, seed)
+            .with_kinds(kinds)
+            .with_progress(true)
+            .generate(org_id, count)
+            .await?;
+        Ok(report.items)
+    }
+
+    /// Days before today an item was acquired, up to a year
+    fn acquired_days_ago(&self) -> i32 {
+        self.rng.lock().unwrap().gen_range(0..365)
+    }
+
     async fn create_org(&self, name: &str, description: &str) -> Result<Uuid> {
         let rec = sqlx::query!(
             "INSERT INTO organizations (name, description) VALUES ($1, $2) RETURNING id",
@@ -115,13 +362,20 @@ impl<'a> SampleDataLoader<'a> {
         Ok(id)
     }
 
-    async fn add_user_to_org(&self, user_id: Uuid, org_id: Uuid, roles: Vec<String>) -> Result<()> {
+    async fn add_user_to_org(
+        &self,
+        user_id: Uuid,
+        org_id: Uuid,
+        roles: Vec<UserRole>,
+    ) -> Result<()> {
         sqlx::query(
-            "INSERT INTO user_organizations (user_id, organization_id, roles) VALUES ($1, $2, $3)",
+            "INSERT INTO user_organizations (user_id, organization_id, roles, permissions)
+             VALUES ($1, $2, $3, $4)",
         )
         .bind(user_id)
         .bind(org_id)
         .bind(&roles)
+        .bind(Permissions::for_roles(&roles).into_vec())
         .execute(self.pool)
         .await?;
         println!("  ✓ Added user to organization with roles: {:?}", roles);
@@ -267,7 +521,7 @@ impl<'a> SampleDataLoader<'a> {
         locations: &[Uuid],
         collections: &[Uuid],
     ) -> Result<()> {
-        let vinyl_data = vec![
+        let vinyl_data = [
             (
                 "Kind of Blue - Miles Davis",
                 "12_inch",
@@ -408,6 +662,7 @@ impl<'a> SampleDataLoader<'a> {
                     )
                     .execute(self.pool)
                     .await?;
+                    record_loan(self.pool, org_id, item_id).await?;
                 }
                 "missing" => {
                     sqlx::query!(
@@ -447,7 +702,7 @@ impl<'a> SampleDataLoader<'a> {
         locations: &[Uuid],
         collections: &[Uuid],
     ) -> Result<()> {
-        let cd_data = vec![
+        let cd_data = [
             ("OK Computer - Radiohead", 1, "current"),
             ("The Joshua Tree - U2", 1, "current"),
             ("Nevermind - Nirvana", 1, "current"),
@@ -488,6 +743,7 @@ impl<'a> SampleDataLoader<'a> {
                     )
                     .execute(self.pool)
                     .await?;
+                    record_loan(self.pool, org_id, item_id).await?;
                 }
                 "missing" => {
                     sqlx::query!(
@@ -511,7 +767,7 @@ impl<'a> SampleDataLoader<'a> {
         kind_id: Uuid,
         locations: &[Uuid],
     ) -> Result<()> {
-        let cassette_data = vec![
+        let cassette_data = [
             ("Appetite for Destruction - Guns N' Roses", 1, "current"),
             ("Purple Rain - Prince", 1, "current"),
             ("Born in the U.S.A. - Bruce Springsteen", 1, "current"),
@@ -558,7 +814,7 @@ impl<'a> SampleDataLoader<'a> {
         locations: &[Uuid],
         collections: &[Uuid],
     ) -> Result<()> {
-        let book_data = vec![
+        let book_data = [
             ("The Lord of the Rings - J.R.R. Tolkien", "current"),
             ("1984 - George Orwell", "current"),
             ("To Kill a Mockingbird - Harper Lee", "loaned"),
@@ -579,7 +835,7 @@ impl<'a> SampleDataLoader<'a> {
                     name,
                     &format!("Book: {}", name),
                     location_id,
-                    json!({}),
+                    json!({ "author": name.rsplit(" - ").next(), "book_format": "paperback" }),
                 )
                 .await?;
 
@@ -595,6 +851,7 @@ impl<'a> SampleDataLoader<'a> {
                 )
                 .execute(self.pool)
                 .await?;
+                record_loan(self.pool, org_id, item_id).await?;
             }
 
             if idx < 2 {
@@ -612,7 +869,7 @@ impl<'a> SampleDataLoader<'a> {
         kind_id: Uuid,
         locations: &[Uuid],
     ) -> Result<()> {
-        let score_data = vec![
+        let score_data = [
             ("Beethoven - Symphony No. 9", "current"),
             ("Bach - Well-Tempered Clavier", "current"),
             ("Mozart - Requiem", "current"),
@@ -650,7 +907,7 @@ impl<'a> SampleDataLoader<'a> {
         kind_id: Uuid,
         locations: &[Uuid],
     ) -> Result<()> {
-        let electronics_data = vec![
+        let electronics_data = [
             ("Technics SL-1200 Turntable", "current"),
             ("Sony Walkman WM-D6C", "current"),
             ("Pioneer Elite VSX-LX504 Receiver", "current"),
@@ -696,7 +953,7 @@ impl<'a> SampleDataLoader<'a> {
         locations: &[Uuid],
         collections: &[Uuid],
     ) -> Result<()> {
-        let misc_data = vec![
+        let misc_data = [
             ("Concert Poster - Woodstock 1969", "current"),
             ("Signed Band T-Shirt - Metallica", "current"),
             ("Record Storage Crate - Vintage", "current"),
@@ -737,7 +994,7 @@ impl<'a> SampleDataLoader<'a> {
         kind_id: Uuid,
         locations: &[Uuid],
     ) -> Result<()> {
-        let dvd_data = vec![
+        let dvd_data = [
             (
                 "The Lord of the Rings: The Fellowship of the Ring",
                 2,
@@ -773,6 +1030,7 @@ impl<'a> SampleDataLoader<'a> {
                 )
                 .execute(self.pool)
                 .await?;
+                record_loan(self.pool, org_id, item_id).await?;
             }
         }
 
@@ -780,6 +1038,7 @@ impl<'a> SampleDataLoader<'a> {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn create_item(
         &self,
         org_id: Uuid,
@@ -793,7 +1052,7 @@ impl<'a> SampleDataLoader<'a> {
         let row = sqlx::query(
             r#"INSERT INTO items
             (organization_id, kind_id, state, name, description, location_id, date_acquired, soft_fields)
-            VALUES ($1, $2, $3::item_state, $4, $5, $6, CURRENT_DATE - (random() * 365)::int, $7)
+            VALUES ($1, $2, $3::item_state, $4, $5, $6, CURRENT_DATE - $8::int, $7)
             RETURNING id"#,
         )
         .bind(org_id)
@@ -803,6 +1062,7 @@ impl<'a> SampleDataLoader<'a> {
         .bind(description)
         .bind(location_id)
         .bind(soft_fields)
+        .bind(self.acquired_days_ago())
         .fetch_one(self.pool)
         .await?;
 
@@ -833,3 +1093,12 @@ impl<'a> SampleDataLoader<'a> {
         Ok(())
     }
 }
+
+/// Items in each organization of the sample profile
+const SAMPLE_ITEMS_PER_ORG: usize = 10 + 10 + 8 + 8 + 6 + 4 + 4 + 6;
+
+/// Generated items added to the hand-written ones in the demo profiles
+const DEMO_GENERATED_ITEMS: usize = 120;
+
+/// Items in the stress profile
+const STRESS_ITEMS: usize = 10_000;
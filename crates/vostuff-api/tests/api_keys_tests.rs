@@ -0,0 +1,245 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{TestFixture, TestResponse};
+use serde_json::{Value, json};
+
+/// Call the API authenticated with an API key
+async fn with_key(
+    fixture: &TestFixture,
+    method: &str,
+    path: &str,
+    body: Option<&Value>,
+    key: &str,
+) -> TestResponse {
+    fixture
+        .ctx
+        .request_with_headers(method, path, body, &[("X-Api-Key", key)])
+        .await
+}
+
+/// Create a key as the token's user; returns the key and its ID
+async fn create_key(fixture: &TestFixture, token: &str, body: Value) -> (String, String) {
+    let response = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/api-keys", fixture.org1_id),
+            &body,
+            Some(token),
+        )
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    (
+        response.body["key"].as_str().unwrap().to_string(),
+        response.body["api_key"]["id"].as_str().unwrap().to_string(),
+    )
+}
+
+#[tokio::test]
+async fn test_api_key_authenticates_with_its_roles() {
+    let fixture = TestFixture::new().await;
+    let base = format!("/api/organizations/{}/api-keys", fixture.org1_id);
+
+    // An admin creates a key limited to the USER role
+    let response = fixture
+        .ctx
+        .post(
+            &base,
+            &json!({"name": "CLZ importer", "roles": ["USER"]}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let key = response.body["key"].as_str().unwrap().to_string();
+    assert!(key.starts_with("vsk_"));
+    assert!(key.starts_with(response.body["api_key"]["key_prefix"].as_str().unwrap()));
+    assert_eq!(response.body["api_key"]["roles"], json!(["USER"]));
+
+    // The key works in place of a token, for its own organization only
+    let items = format!("/api/organizations/{}/items", fixture.org1_id);
+    let response = with_key(&fixture, "GET", &items, None, &key).await;
+    response.assert_success();
+    let response = with_key(&fixture, "GET", "/api/auth/me", None, &key).await;
+    response.assert_success();
+    assert_eq!(response.body["id"], fixture.user2_id.to_string());
+    let other_org = format!("/api/organizations/{}/items", fixture.org2_id);
+    let response = with_key(&fixture, "GET", &other_org, None, &key).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    // Admin-only operations need a key with the ADMIN role
+    let locations = format!("/api/organizations/{}/locations", fixture.org1_id);
+    let response = with_key(
+        &fixture,
+        "POST",
+        &locations,
+        Some(&json!({"name": "Shed"})),
+        &key,
+    )
+    .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    // Use is recorded, and the key itself is never shown again
+    let response = fixture.ctx.get(&base, Some(&fixture.user2_token)).await;
+    response.assert_success();
+    let listed = &response.body[0];
+    assert!(listed["last_used_at"].is_string());
+    assert!(listed.get("key").is_none());
+    assert!(listed.get("key_hash").is_none());
+
+    // Unknown keys are rejected rather than treated as anonymous
+    let response = with_key(&fixture, "GET", &items, None, "vsk_not_a_real_key").await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(response.body["error"], "auth.invalid_api_key");
+}
+
+#[tokio::test]
+async fn test_api_key_scope_and_management() {
+    let fixture = TestFixture::new().await;
+    let base = format!("/api/organizations/{}/api-keys", fixture.org1_id);
+
+    // Members cannot grant roles they do not hold
+    let response = fixture
+        .ctx
+        .post(
+            &base,
+            &json!({"name": "sneaky", "roles": ["ADMIN"]}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    for body in [
+        json!({"name": " "}),
+        json!({"name": "none", "roles": []}),
+        json!({"name": "old", "expires_at": "2000-01-01T00:00:00Z"}),
+    ] {
+        let response = fixture
+            .ctx
+            .post(&base, &body, Some(&fixture.user1_token))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    let (user_key, user_key_id) = create_key(
+        &fixture,
+        &fixture.user1_token,
+        json!({"name": "backup script"}),
+    )
+    .await;
+    let (_, admin_key_id) = create_key(
+        &fixture,
+        &fixture.user2_token,
+        json!({"name": "admin script"}),
+    )
+    .await;
+
+    // Keys cannot manage keys
+    let response = with_key(
+        &fixture,
+        "POST",
+        &base,
+        Some(&json!({"name": "child"})),
+        &user_key,
+    )
+    .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    // Members see their own keys; admins see everyone's
+    let response = fixture.ctx.get(&base, Some(&fixture.user1_token)).await;
+    assert_eq!(response.body.as_array().unwrap().len(), 1);
+    let response = fixture.ctx.get(&base, Some(&fixture.user2_token)).await;
+    assert_eq!(response.body.as_array().unwrap().len(), 2);
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/{}", base, admin_key_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "api_key.not_found");
+
+    let response = fixture
+        .ctx
+        .patch(
+            &format!("{}/{}", base, user_key_id),
+            &json!({"name": "nightly backup"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["name"], "nightly backup");
+
+    // Deleting a key revokes it at once
+    let response = fixture
+        .ctx
+        .delete(
+            &format!("{}/{}", base, user_key_id),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NO_CONTENT);
+    let items = format!("/api/organizations/{}/items", fixture.org1_id);
+    let response = with_key(&fixture, "GET", &items, None, &user_key).await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_api_key_follows_owner_membership() {
+    let fixture = TestFixture::new().await;
+    let items = format!("/api/organizations/{}/items", fixture.org1_id);
+    let (key, _) = create_key(
+        &fixture,
+        &fixture.user2_token,
+        json!({"name": "admin script"}),
+    )
+    .await;
+
+    // Demoting the owner narrows the key to the roles they still hold
+    sqlx::query(
+        "UPDATE user_organizations SET roles = '{USER}' WHERE user_id = $1 AND organization_id = $2",
+    )
+    .bind(fixture.user2_id)
+    .bind(fixture.org1_id)
+    .execute(&fixture.ctx.pool)
+    .await
+    .unwrap();
+    let locations = format!("/api/organizations/{}/locations", fixture.org1_id);
+    let response = with_key(
+        &fixture,
+        "POST",
+        &locations,
+        Some(&json!({"name": "Shed"})),
+        &key,
+    )
+    .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    with_key(&fixture, "GET", &items, None, &key)
+        .await
+        .assert_success();
+
+    // Deactivated owners' keys stop working
+    sqlx::query("UPDATE users SET is_active = FALSE WHERE id = $1")
+        .bind(fixture.user2_id)
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    let response = with_key(&fixture, "GET", &items, None, &key).await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(response.body["error"], "auth.user_deactivated");
+
+    // As do those of owners who have left the organization
+    sqlx::query("UPDATE users SET is_active = TRUE WHERE id = $1")
+        .bind(fixture.user2_id)
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM user_organizations WHERE user_id = $1 AND organization_id = $2")
+        .bind(fixture.user2_id)
+        .bind(fixture.org1_id)
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    let response = with_key(&fixture, "GET", &items, None, &key).await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(response.body["error"], "auth.invalid_api_key");
+}
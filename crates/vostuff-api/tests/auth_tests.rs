@@ -1,9 +1,16 @@
 mod common;
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
 use axum::http::StatusCode;
-use common::{TestContext, TestFixture};
+use common::{TestContext, TestFixture, TestResponse};
 use serde_json::json;
-use vostuff_api::api::models::{LoginResponse, OrgSelectionResponse, UserInfo};
+use vostuff_api::api::models::{LoginResponse, OrgSelectionResponse, UserInfo, UserRole};
+use vostuff_api::api::state::TermsOfService;
+use vostuff_api::auth::{FollowOnClaims, TokenManager};
+use vostuff_core::auth::backend::{AuthBackend, LoginAccount, Verdict};
 
 #[tokio::test]
 async fn test_login_single_org() {
@@ -14,7 +21,7 @@ async fn test_login_single_org() {
     let user_id = ctx
         .create_user("Alice", "alice@test.com", "password123")
         .await;
-    ctx.add_user_to_org(user_id, org_id, vec!["USER".to_string()])
+    ctx.add_user_to_org(user_id, org_id, vec![UserRole::User])
         .await;
 
     // Login without specifying org (should auto-select)
@@ -35,6 +42,9 @@ async fn test_login_single_org() {
     assert!(!login_resp.token.is_empty());
     assert_eq!(login_resp.user.identity, "alice@test.com");
     assert_eq!(login_resp.user.organization.id, org_id);
+    assert_eq!(login_resp.organizations.len(), 1);
+    assert_eq!(login_resp.organizations[0].id, org_id);
+    assert_eq!(login_resp.organizations[0].roles, vec![UserRole::User]);
 }
 
 #[tokio::test]
@@ -47,9 +57,9 @@ async fn test_login_multi_org_requires_selection() {
 
     // Create user in both orgs
     let user_id = ctx.create_user("Bob", "bob@test.com", "password123").await;
-    ctx.add_user_to_org(user_id, org1_id, vec!["USER".to_string()])
+    ctx.add_user_to_org(user_id, org1_id, vec![UserRole::User])
         .await;
-    ctx.add_user_to_org(user_id, org2_id, vec!["ADMIN".to_string()])
+    ctx.add_user_to_org(user_id, org2_id, vec![UserRole::Admin])
         .await;
 
     // Login without specifying org
@@ -85,14 +95,10 @@ async fn test_login_with_org_selection() {
     let user_id = ctx
         .create_user("Charlie", "charlie@test.com", "password123")
         .await;
-    ctx.add_user_to_org(user_id, org1_id, vec!["USER".to_string()])
+    ctx.add_user_to_org(user_id, org1_id, vec![UserRole::User])
+        .await;
+    ctx.add_user_to_org(user_id, org2_id, vec![UserRole::User, UserRole::Admin])
         .await;
-    ctx.add_user_to_org(
-        user_id,
-        org2_id,
-        vec!["USER".to_string(), "ADMIN".to_string()],
-    )
-    .await;
 
     // Login with explicit org selection (org2)
     let response = ctx
@@ -111,7 +117,21 @@ async fn test_login_with_org_selection() {
 
     let login_resp: LoginResponse = response.json();
     assert_eq!(login_resp.user.organization.id, org2_id);
-    assert_eq!(login_resp.user.roles, vec!["USER", "ADMIN"]);
+    assert_eq!(login_resp.user.roles, vec![UserRole::User, UserRole::Admin]);
+
+    // Other memberships are listed too, with their own roles
+    let memberships: Vec<_> = login_resp
+        .organizations
+        .iter()
+        .map(|o| (o.id, o.roles.clone()))
+        .collect();
+    assert_eq!(
+        memberships,
+        vec![
+            (org1_id, vec![UserRole::User]),
+            (org2_id, vec![UserRole::User, UserRole::Admin]),
+        ]
+    );
 }
 
 #[tokio::test]
@@ -122,7 +142,7 @@ async fn test_login_invalid_credentials() {
     let user_id = ctx
         .create_user("Alice", "alice@test.com", "password123")
         .await;
-    ctx.add_user_to_org(user_id, org_id, vec!["USER".to_string()])
+    ctx.add_user_to_org(user_id, org_id, vec![UserRole::User])
         .await;
 
     // Wrong password
@@ -138,6 +158,431 @@ async fn test_login_invalid_credentials() {
         .await;
 
     response.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(response.body["error"], "auth.invalid_credentials");
+}
+
+#[tokio::test]
+async fn test_admin_password_reset_forces_change_at_login() {
+    let fixture = TestFixture::new().await;
+    let ctx = &fixture.ctx;
+
+    let sysadmin_token = ctx.sysadmin_token().await;
+
+    let reset_path = format!("/api/admin/users/{}/reset-password", fixture.user1_id);
+
+    // Org admins are not system admins
+    ctx.post(&reset_path, &json!({}), Some(&fixture.user2_token))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let response = ctx
+        .post(&reset_path, &json!({}), Some(&sysadmin_token))
+        .await;
+    response.assert_success();
+    let temporary = response.body["temporary_password"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let login = |password: &str, new_password: Option<&str>| {
+        let body = json!({
+            "identity": "user1@test.com",
+            "password": password,
+            "organization_id": fixture.org1_id,
+            "new_password": new_password,
+        });
+        async move { ctx.post("/api/auth/login", &body, None).await }
+    };
+
+    // The old password no longer works
+    login("password123", None)
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    // The temporary password only asks for a new one
+    let response = login(&temporary, None).await;
+    response.assert_success();
+    assert_eq!(response.body["password_change_required"], true);
+    assert!(response.body.get("token").is_none());
+
+    let response = login(&temporary, Some(&temporary)).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.body["error"], "validation.failed");
+
+    let response = login(&temporary, Some("newpassword456")).await;
+    response.assert_success();
+    assert!(response.body["token"].is_string());
+
+    // The new password is now the only one, with no further change required
+    login(&temporary, None)
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    ctx.login("user1@test.com", "newpassword456", Some(fixture.org1_id))
+        .await;
+
+    ctx.post(
+        &format!("/api/admin/users/{}/reset-password", uuid::Uuid::new_v4()),
+        &json!({}),
+        Some(&sysadmin_token),
+    )
+    .await
+    .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_terms_must_be_accepted_before_login() {
+    let mut fixture = TestFixture::new().await;
+    let require_terms = |fixture: &mut TestFixture, version: &str| {
+        let state = fixture.ctx.state.clone().with_terms(TermsOfService {
+            version: version.to_string(),
+            url: Some("https://example.com/terms".to_string()),
+        });
+        fixture.ctx.set_state(state);
+    };
+
+    // Sessions from before terms were required
+    let earlier = login_session(&fixture, "user1@test.com").await;
+    require_terms(&mut fixture, "2026-10");
+    let ctx = &fixture.ctx;
+
+    let login = || async {
+        ctx.post(
+            "/api/auth/login",
+            &json!({"identity": "user1@test.com", "password": "password123"}),
+            None,
+        )
+        .await
+    };
+    let accept = |token: &str, version: &str| {
+        let body = json!({
+            "follow_on_token": token,
+            "version": version,
+            "organization_id": fixture.org1_id,
+        });
+        async move { ctx.post("/api/auth/accept-terms", &body, None).await }
+    };
+
+    let response = login().await;
+    response.assert_success();
+    assert_eq!(response.body["terms_acceptance_required"], true);
+    assert_eq!(response.body["version"], "2026-10");
+    assert_eq!(response.body["url"], "https://example.com/terms");
+    assert!(response.body.get("token").is_none());
+    let follow_on_token = response.body["follow_on_token"].as_str().unwrap();
+
+    // The token cannot be used to skip the terms
+    let response = select_org(ctx, follow_on_token, fixture.org1_id).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(response.body["error"], "auth.terms_not_accepted");
+    let response = refresh(ctx, &earlier.refresh_token).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(response.body["error"], "auth.terms_not_accepted");
+
+    let response = accept(follow_on_token, "2026-01").await;
+    response.assert_status(StatusCode::CONFLICT);
+    assert_eq!(response.body["error"], "auth.terms_version_mismatch");
+
+    let response = accept(follow_on_token, "2026-10").await;
+    response.assert_success();
+    let session: LoginResponse = response.json();
+    assert_eq!(session.user.organization.id, fixture.org1_id);
+    accept(follow_on_token, "2026-10")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    // Accepted once, later logins go straight through
+    assert!(login().await.body["token"].is_string());
+
+    // The acceptance is part of the user's personal data
+    let response = ctx
+        .get("/api/auth/me/data-export", Some(&session.token))
+        .await;
+    assert_eq!(response.body["user"]["terms_accepted_version"], "2026-10");
+    assert_eq!(response.body["terms_acceptances"][0]["version"], "2026-10");
+
+    // New terms are asked for again, and end sessions at their next refresh
+    require_terms(&mut fixture, "2027-01");
+    let ctx = &fixture.ctx;
+    refresh(ctx, &session.refresh_token)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    let response = ctx
+        .post(
+            "/api/auth/login",
+            &json!({"identity": "user1@test.com", "password": "password123"}),
+            None,
+        )
+        .await;
+    assert_eq!(response.body["version"], "2027-01");
+}
+
+#[tokio::test]
+async fn test_repeated_failed_logins_lock_the_account() {
+    let fixture = TestFixture::new().await;
+    let ctx = &fixture.ctx;
+    let sysadmin_token = ctx.sysadmin_token().await;
+    let user_path = format!("/api/admin/users/{}", fixture.user1_id);
+    let login = |password: &'static str| async move {
+        ctx.post(
+            "/api/auth/login",
+            &json!({"identity": "user1@test.com", "password": password}),
+            None,
+        )
+        .await
+    };
+
+    for _ in 0..5 {
+        login("wrong").await.assert_status(StatusCode::UNAUTHORIZED);
+    }
+
+    // Locked: the right password gets the same answer as a wrong one
+    let response = login("password123").await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(response.body["error"], "auth.invalid_credentials");
+    let user = ctx.get(&user_path, Some(&sysadmin_token)).await;
+    assert_eq!(user.body["failed_login_attempts"], 5);
+    assert!(user.body["locked_until"].is_string());
+
+    // Only system admins may unlock
+    let unlock_path = format!("{}/unlock", user_path);
+    ctx.post(&unlock_path, &json!({}), Some(&fixture.user2_token))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    let unlocked = ctx
+        .post(&unlock_path, &json!({}), Some(&sysadmin_token))
+        .await;
+    unlocked.assert_success();
+    assert_eq!(unlocked.body["failed_login_attempts"], 0);
+    assert!(unlocked.body["locked_until"].is_null());
+    login("password123").await.assert_success();
+
+    // Once a lock has run out, the next failure locks for twice as long
+    sqlx::query(
+        "UPDATE users SET failed_login_attempts = 5, locked_until = NOW() - INTERVAL '1 second'
+         WHERE id = $1",
+    )
+    .bind(fixture.user1_id)
+    .execute(&ctx.pool)
+    .await
+    .unwrap();
+    login("wrong").await.assert_status(StatusCode::UNAUTHORIZED);
+    let relocked: bool = sqlx::query_scalar(
+        "SELECT locked_until > NOW() + INTERVAL '100 seconds' FROM users WHERE id = $1",
+    )
+    .bind(fixture.user1_id)
+    .fetch_one(&ctx.pool)
+    .await
+    .unwrap();
+    assert!(relocked);
+
+    // A successful login resets the count
+    sqlx::query("UPDATE users SET failed_login_attempts = 3, locked_until = NULL WHERE id = $1")
+        .bind(fixture.user1_id)
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+    login("password123").await.assert_success();
+    let user = ctx.get(&user_path, Some(&sysadmin_token)).await;
+    assert_eq!(user.body["failed_login_attempts"], 0);
+}
+
+/// Directory backend knowing one password per identity
+struct FakeDirectory(HashMap<&'static str, &'static str>);
+
+#[async_trait]
+impl AuthBackend for FakeDirectory {
+    fn name(&self) -> &str {
+        "fake-directory"
+    }
+
+    async fn verify(&self, account: &LoginAccount<'_>, password: &str) -> anyhow::Result<Verdict> {
+        Ok(match self.0.get(account.identity) {
+            Some(&known) if known == password => Verdict::Accepted,
+            _ => Verdict::Rejected,
+        })
+    }
+}
+
+async fn password_login(ctx: &TestContext, identity: &str, password: &str) -> TestResponse {
+    ctx.post(
+        "/api/auth/login",
+        &json!({"identity": identity, "password": password}),
+        None,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_directory_backend_checks_users_without_a_password() {
+    let mut fixture = TestFixture::new().await;
+    let dana_id = fixture
+        .ctx
+        .create_user("Dana", "dana@test.com", "unused")
+        .await;
+    fixture
+        .ctx
+        .add_user_to_org(dana_id, fixture.org1_id, vec![UserRole::User])
+        .await;
+    sqlx::query("UPDATE users SET password_hash = NULL WHERE id = $1")
+        .bind(dana_id)
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+
+    // Without a directory, a user with no password here cannot log in with one
+    password_login(&fixture.ctx, "dana@test.com", "directory-secret")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    let directory = FakeDirectory(HashMap::from([
+        ("dana@test.com", "directory-secret"),
+        ("user1@test.com", "directory-secret"),
+    ]));
+    let state = fixture
+        .ctx
+        .state
+        .clone()
+        .with_auth_backend(Arc::new(directory));
+    fixture.ctx.set_state(state);
+
+    let response = password_login(&fixture.ctx, "dana@test.com", "directory-secret").await;
+    response.assert_success();
+    assert_eq!(response.json::<LoginResponse>().user.id, dana_id);
+
+    // A wrong directory password counts towards the lockout
+    password_login(&fixture.ctx, "dana@test.com", "wrong")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    let attempts: i32 = sqlx::query_scalar("SELECT failed_login_attempts FROM users WHERE id = $1")
+        .bind(dana_id)
+        .fetch_one(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(attempts, 1);
+
+    // A password stored here is checked here, not against the directory
+    password_login(&fixture.ctx, "user1@test.com", "directory-secret")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    password_login(&fixture.ctx, "user1@test.com", "password123")
+        .await
+        .assert_success();
+}
+
+#[tokio::test]
+async fn test_user_responses_never_include_the_password_hash() {
+    let fixture = TestFixture::new().await;
+    let ctx = &fixture.ctx;
+    let sysadmin_token = ctx.sysadmin_token().await;
+
+    let created = ctx
+        .post(
+            "/api/admin/users",
+            &json!({"name": "Oidc Only", "identity": "oidc@test.com"}),
+            Some(&sysadmin_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    assert_eq!(created.body["has_password"], false);
+
+    let listed = ctx.get("/api/admin/users", Some(&sysadmin_token)).await;
+    listed.assert_success();
+    let org_users = ctx
+        .get(
+            &format!("/api/admin/organizations/{}/users", fixture.org1_id),
+            Some(&sysadmin_token),
+        )
+        .await;
+    org_users.assert_success();
+    for users in [&listed.body, &org_users.body] {
+        let user1 = users
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|u| u["id"] == fixture.user1_id.to_string())
+            .unwrap();
+        assert_eq!(user1["has_password"], true);
+        assert!(user1.get("password_hash").is_none());
+        assert!(!users.to_string().contains("$argon2"));
+    }
+}
+
+#[tokio::test]
+async fn test_deactivated_user_cannot_log_in_or_use_token() {
+    let fixture = TestFixture::new().await;
+    let ctx = &fixture.ctx;
+    let sysadmin_token = ctx.sysadmin_token().await;
+    let items_path = format!("/api/organizations/{}/items", fixture.org1_id);
+    let login_body = json!({
+        "identity": "user1@test.com",
+        "password": "password123",
+        "organization_id": fixture.org1_id,
+    });
+
+    let response = ctx
+        .post(
+            &format!("/api/admin/users/{}/deactivate", fixture.user1_id),
+            &json!({}),
+            Some(&sysadmin_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["is_active"], false);
+
+    // The existing token is rejected, and so is a new login
+    let response = ctx.get(&items_path, Some(&fixture.user1_token)).await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(response.body["error"], "auth.user_deactivated");
+
+    let response = ctx.post("/api/auth/login", &login_body, None).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(response.body["error"], "auth.user_deactivated");
+
+    // A wrong password still looks like any other failed login
+    let response = ctx
+        .post(
+            "/api/auth/login",
+            &json!({"identity": "user1@test.com", "password": "wrong"}),
+            None,
+        )
+        .await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(response.body["error"], "auth.invalid_credentials");
+
+    // The user and their memberships are kept
+    let response = ctx
+        .get(
+            &format!("/api/admin/users/{}/organizations", fixture.user1_id),
+            Some(&sysadmin_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body.as_array().unwrap().len(), 1);
+
+    let response = ctx
+        .post(
+            &format!("/api/admin/users/{}/activate", fixture.user1_id),
+            &json!({}),
+            Some(&sysadmin_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["is_active"], true);
+
+    ctx.get(&items_path, Some(&fixture.user1_token))
+        .await
+        .assert_success();
+    ctx.post("/api/auth/login", &login_body, None)
+        .await
+        .assert_success();
+
+    ctx.post(
+        &format!("/api/admin/users/{}/deactivate", uuid::Uuid::new_v4()),
+        &json!({}),
+        Some(&sysadmin_token),
+    )
+    .await
+    .assert_status(StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
@@ -152,9 +597,9 @@ async fn test_select_org_after_multi_org_login() {
     let user_id = ctx
         .create_user("Dave", "dave@test.com", "password123")
         .await;
-    ctx.add_user_to_org(user_id, org1_id, vec!["USER".to_string()])
+    ctx.add_user_to_org(user_id, org1_id, vec![UserRole::User])
         .await;
-    ctx.add_user_to_org(user_id, org2_id, vec!["ADMIN".to_string()])
+    ctx.add_user_to_org(user_id, org2_id, vec![UserRole::Admin])
         .await;
 
     // First login to get follow-on token
@@ -188,7 +633,287 @@ async fn test_select_org_after_multi_org_login() {
 
     let final_login: LoginResponse = select_response.json();
     assert_eq!(final_login.user.organization.id, org2_id);
-    assert_eq!(final_login.user.roles, vec!["ADMIN"]);
+    assert_eq!(final_login.user.roles, vec![UserRole::Admin]);
+    assert_eq!(final_login.organizations.len(), 2);
+    assert!(final_login.organizations.iter().any(|o| o.id == org1_id));
+}
+
+/// Log in a user belonging to two orgs and return (follow-on token, org1, org2)
+async fn multi_org_login(ctx: &TestContext) -> (String, uuid::Uuid, uuid::Uuid) {
+    let org1_id = ctx.create_organization("Org1", "First Org").await;
+    let org2_id = ctx.create_organization("Org2", "Second Org").await;
+    let user_id = ctx
+        .create_user("Erin", "erin@test.com", "password123")
+        .await;
+    ctx.add_user_to_org(user_id, org1_id, vec![UserRole::User])
+        .await;
+    ctx.add_user_to_org(user_id, org2_id, vec![UserRole::User])
+        .await;
+
+    let login_response = ctx
+        .post(
+            "/api/auth/login",
+            &json!({"identity": "erin@test.com", "password": "password123"}),
+            None,
+        )
+        .await;
+    login_response.assert_status(StatusCode::OK);
+    let org_selection: OrgSelectionResponse = login_response.json();
+    (org_selection.follow_on_token, org1_id, org2_id)
+}
+
+async fn select_org(ctx: &TestContext, follow_on_token: &str, org_id: uuid::Uuid) -> TestResponse {
+    ctx.post(
+        "/api/auth/select-org",
+        &json!({"follow_on_token": follow_on_token, "organization_id": org_id}),
+        None,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_follow_on_token_replay_rejected() {
+    let ctx = TestContext::new().await;
+    let (follow_on_token, org1_id, org2_id) = multi_org_login(&ctx).await;
+
+    select_org(&ctx, &follow_on_token, org1_id)
+        .await
+        .assert_status(StatusCode::OK);
+
+    // Replaying the token fails, whichever org is asked for
+    for org_id in [org1_id, org2_id] {
+        let replay = select_org(&ctx, &follow_on_token, org_id).await;
+        replay.assert_status(StatusCode::UNAUTHORIZED);
+        assert_eq!(replay.body["error"], "auth.follow_on_token_reused");
+    }
+
+    // A fresh login issues a new, usable token
+    let fresh_token = ctx
+        .post(
+            "/api/auth/login",
+            &json!({"identity": "erin@test.com", "password": "password123"}),
+            None,
+        )
+        .await
+        .json::<OrgSelectionResponse>()
+        .follow_on_token;
+    select_org(&ctx, &fresh_token, org2_id)
+        .await
+        .assert_status(StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_expired_or_wrong_kind_of_token_rejected_for_org_selection() {
+    let ctx = TestContext::new().await;
+    let (follow_on_token, org1_id, _) = multi_org_login(&ctx).await;
+    let claims = TokenManager::new("test_jwt_secret_for_integration_tests")
+        .validate_follow_on_token(&follow_on_token)
+        .unwrap();
+
+    // Same token id and user, but past its expiry
+    let now = chrono::Utc::now().timestamp();
+    let expired = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &FollowOnClaims {
+            iat: now - 600,
+            exp: now - 1,
+            ..claims
+        },
+        &jsonwebtoken::EncodingKey::from_secret(b"test_jwt_secret_for_integration_tests"),
+    )
+    .unwrap();
+
+    // A session token is not a follow-on token
+    let session = ctx
+        .login("erin@test.com", "password123", Some(org1_id))
+        .await;
+
+    for token in [expired, session] {
+        let response = select_org(&ctx, &token, org1_id).await;
+        response.assert_status(StatusCode::UNAUTHORIZED);
+        assert_eq!(response.body["error"], "auth.invalid_follow_on_token");
+    }
+
+    // The rejected attempts did not use up the real token
+    select_org(&ctx, &follow_on_token, org1_id)
+        .await
+        .assert_status(StatusCode::OK);
+}
+
+async fn login_session(fixture: &TestFixture, identity: &str) -> LoginResponse {
+    let response = fixture
+        .ctx
+        .post(
+            "/api/auth/login",
+            &json!({
+                "identity": identity,
+                "password": "password123",
+                "organization_id": fixture.org1_id
+            }),
+            None,
+        )
+        .await;
+    response.assert_status(StatusCode::OK);
+    response.json()
+}
+
+async fn refresh(ctx: &TestContext, refresh_token: &str) -> TestResponse {
+    ctx.post(
+        "/api/auth/refresh",
+        &json!({"refresh_token": refresh_token}),
+        None,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_refresh_rotates_tokens_and_detects_reuse() {
+    let fixture = TestFixture::new().await;
+    let ctx = &fixture.ctx;
+
+    let login = login_session(&fixture, "user1@test.com").await;
+    assert_eq!(login.expires_in, 15 * 60);
+    assert_eq!(login.refresh_expires_in, 30 * 24 * 60 * 60);
+    assert_eq!(login.user.roles, vec![UserRole::User]);
+
+    // Roles granted since login are picked up by the refresh
+    sqlx::query(
+        "UPDATE user_organizations SET roles = ARRAY['USER', 'ADMIN']
+         WHERE user_id = $1 AND organization_id = $2",
+    )
+    .bind(fixture.user1_id)
+    .bind(fixture.org1_id)
+    .execute(&ctx.pool)
+    .await
+    .unwrap();
+
+    let response = refresh(ctx, &login.refresh_token).await;
+    response.assert_status(StatusCode::OK);
+    let renewed: LoginResponse = response.json();
+    assert_ne!(renewed.refresh_token, login.refresh_token);
+    assert_eq!(renewed.user.organization.id, fixture.org1_id);
+    assert_eq!(renewed.user.roles, vec![UserRole::User, UserRole::Admin]);
+    ctx.get("/api/auth/me", Some(&renewed.token))
+        .await
+        .assert_status(StatusCode::OK);
+
+    // A parallel refresh moments later is let through
+    let response = refresh(ctx, &login.refresh_token).await;
+    response.assert_status(StatusCode::OK);
+    let parallel: LoginResponse = response.json();
+
+    // Replaying the exchanged token later revokes the whole session
+    sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = revoked_at - INTERVAL '1 minute'
+         WHERE revoked_at IS NOT NULL",
+    )
+    .execute(&ctx.pool)
+    .await
+    .unwrap();
+    let replay = refresh(ctx, &login.refresh_token).await;
+    replay.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(replay.body["error"], "auth.refresh_token_reused");
+    for token in [&renewed.refresh_token, &parallel.refresh_token] {
+        let revoked = refresh(ctx, token).await;
+        revoked.assert_status(StatusCode::UNAUTHORIZED);
+        assert_eq!(revoked.body["error"], "auth.invalid_refresh_token");
+    }
+
+    // Other sessions are unaffected
+    let other = login_session(&fixture, "user1@test.com").await;
+    refresh(ctx, &other.refresh_token)
+        .await
+        .assert_status(StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_access_token_lifetime_is_configurable() {
+    let mut fixture = TestFixture::new().await;
+    let state = fixture.ctx.state.clone().with_access_token_ttl_minutes(5);
+    fixture.ctx.set_state(state);
+
+    let login = login_session(&fixture, "user1@test.com").await;
+    assert_eq!(login.expires_in, 5 * 60);
+    let claims = TokenManager::new(&fixture.ctx.state.jwt_secret)
+        .validate_token(&login.token)
+        .unwrap();
+    assert_eq!(claims.exp - claims.iat, 5 * 60);
+
+    let response = refresh(&fixture.ctx, &login.refresh_token).await;
+    response.assert_status(StatusCode::OK);
+    assert_eq!(response.json::<LoginResponse>().expires_in, 5 * 60);
+}
+
+#[tokio::test]
+async fn test_refresh_rejected_for_wrong_token_removed_member_or_reset_password() {
+    let fixture = TestFixture::new().await;
+    let ctx = &fixture.ctx;
+
+    // Access tokens are not refresh tokens
+    let response = refresh(ctx, &fixture.user1_token).await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(response.body["error"], "auth.invalid_refresh_token");
+
+    let login = login_session(&fixture, "user2@test.com").await;
+    sqlx::query("DELETE FROM user_organizations WHERE user_id = $1 AND organization_id = $2")
+        .bind(login.user.id)
+        .bind(fixture.org1_id)
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+    let response = refresh(ctx, &login.refresh_token).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(response.body["error"], "auth.not_member");
+
+    // An administrator password reset ends existing sessions
+    let login = login_session(&fixture, "user1@test.com").await;
+    let sysadmin_token = ctx.sysadmin_token().await;
+    ctx.post(
+        &format!("/api/admin/users/{}/reset-password", fixture.user1_id),
+        &json!({}),
+        Some(&sysadmin_token),
+    )
+    .await
+    .assert_success();
+    let response = refresh(ctx, &login.refresh_token).await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(response.body["error"], "auth.invalid_refresh_token");
+}
+
+#[tokio::test]
+async fn test_logout_revokes_token_and_session() {
+    let fixture = TestFixture::new().await;
+    let ctx = &fixture.ctx;
+
+    let session = login_session(&fixture, "user1@test.com").await;
+    let other = login_session(&fixture, "user1@test.com").await;
+
+    ctx.post("/api/auth/logout", &json!({}), Some(&session.token))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    // The token and its refresh token no longer work
+    let response = ctx.get("/api/auth/me", Some(&session.token)).await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(response.body["error"], "auth.invalid_token");
+    ctx.post("/api/auth/logout", &json!({}), Some(&session.token))
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    let response = refresh(ctx, &session.refresh_token).await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(response.body["error"], "auth.invalid_refresh_token");
+
+    // The user's other sessions carry on
+    ctx.get("/api/auth/me", Some(&other.token))
+        .await
+        .assert_status(StatusCode::OK);
+    refresh(ctx, &other.refresh_token)
+        .await
+        .assert_status(StatusCode::OK);
+
+    ctx.post("/api/auth/logout", &json!({}), None)
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
 }
 
 #[tokio::test]
@@ -207,7 +932,34 @@ async fn test_auth_me_endpoint() {
     assert_eq!(user_info.id, fixture.user1_id);
     assert_eq!(user_info.identity, "user1@test.com");
     assert_eq!(user_info.organization.id, fixture.org1_id);
-    assert_eq!(user_info.roles, vec!["USER"]);
+    assert_eq!(user_info.roles, vec![UserRole::User]);
+}
+
+#[tokio::test]
+async fn test_auth_me_includes_org_description_and_settings() {
+    let fixture = TestFixture::new().await;
+
+    fixture
+        .ctx
+        .put(
+            &format!("/api/organizations/{}/settings", fixture.org1_id),
+            &json!({"max_loan_days": 14}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_success();
+
+    let response = fixture
+        .ctx
+        .get("/api/auth/me", Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    assert_eq!(
+        response.body["organization"]["description"],
+        "First test organization"
+    );
+    assert_eq!(response.body["settings"]["max_loan_days"], 14);
+    assert!(response.body["settings"]["max_loans_per_borrower"].is_null());
 }
 
 #[tokio::test]
@@ -219,6 +971,7 @@ async fn test_auth_me_without_token() {
 
     // Should return 401 Unauthorized
     response.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(response.body["error"], "auth.required");
 }
 
 #[tokio::test]
@@ -230,6 +983,24 @@ async fn test_auth_me_with_invalid_token() {
 
     // Should return 401 Unauthorized
     response.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(response.body["error"], "auth.invalid_token");
+}
+
+#[tokio::test]
+async fn test_follow_on_token_not_accepted_as_access_token() {
+    let ctx = TestContext::new().await;
+    let (follow_on_token, org1_id, _) = multi_org_login(&ctx).await;
+
+    let response = ctx.get("/api/auth/me", Some(&follow_on_token)).await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+
+    let response = ctx
+        .get(
+            &format!("/api/organizations/{}/items", org1_id),
+            Some(&follow_on_token),
+        )
+        .await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
 }
 
 #[tokio::test]
@@ -247,5 +1018,89 @@ async fn test_auth_me_returns_correct_org() {
     let user_info: UserInfo = response.json();
     assert_eq!(user_info.id, fixture.user3_id);
     assert_eq!(user_info.organization.id, fixture.org2_id);
-    assert_eq!(user_info.roles, vec!["USER"]);
+    assert_eq!(user_info.roles, vec![UserRole::User]);
+}
+
+#[tokio::test]
+async fn test_personal_data_export() {
+    let fixture = TestFixture::new().await;
+
+    // An audit entry attributed to user1
+    sqlx::query(
+        "INSERT INTO audit_log (item_id, organization_id, change_details, user_id)
+         VALUES (gen_random_uuid(), $1, 'Renamed item', $2)",
+    )
+    .bind(fixture.org1_id)
+    .bind(fixture.user1_id)
+    .execute(&fixture.ctx.pool)
+    .await
+    .unwrap();
+
+    let response = fixture
+        .ctx
+        .get("/api/auth/me/data-export", Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+
+    let report = response.body;
+    assert_eq!(report["user"]["id"], fixture.user1_id.to_string());
+    assert_eq!(report["user"]["identity"], "user1@test.com");
+    assert!(report["user"].get("password_hash").is_none());
+    assert_eq!(
+        report["memberships"][0]["organization_id"],
+        fixture.org1_id.to_string()
+    );
+    assert_eq!(report["audit_entries"][0]["change_details"], "Renamed item");
+
+    // Another user's export does not include user1's entries
+    let response = fixture
+        .ctx
+        .get("/api/auth/me/data-export", Some(&fixture.user2_token))
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["audit_entries"], json!([]));
+
+    let response = fixture.ctx.get("/api/auth/me/data-export", None).await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_deletion_request_anonymizes_history() {
+    let fixture = TestFixture::new().await;
+
+    sqlx::query(
+        "INSERT INTO audit_log (item_id, organization_id, change_details, user_id)
+         VALUES (gen_random_uuid(), $1, 'Created item', $2)",
+    )
+    .bind(fixture.org1_id)
+    .bind(fixture.user1_id)
+    .execute(&fixture.ctx.pool)
+    .await
+    .unwrap();
+
+    let response = fixture
+        .ctx
+        .post(
+            "/api/auth/me/deletion-request",
+            &json!({}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["anonymized_audit_entries"], 1);
+
+    // The entry is kept for the org but no longer attributed
+    let attributed: Option<uuid::Uuid> =
+        sqlx::query_scalar("SELECT user_id FROM audit_log WHERE organization_id = $1")
+            .bind(fixture.org1_id)
+            .fetch_one(&fixture.ctx.pool)
+            .await
+            .unwrap();
+    assert!(attributed.is_none());
+
+    let response = fixture
+        .ctx
+        .get("/api/auth/me/data-export", Some(&fixture.user1_token))
+        .await;
+    assert_eq!(response.body["audit_entries"], json!([]));
 }
@@ -4,6 +4,7 @@ use axum::http::StatusCode;
 use common::TestFixture;
 use serde_json::json;
 use uuid::Uuid;
+use vostuff_api::api::models::UserRole;
 use vostuff_core::auth::SYSTEM_ORG_ID;
 
 // Book is a shared kind from the seed migration, usable by any org.
@@ -125,6 +126,161 @@ async fn test_admin_can_manage_kinds_and_locations() {
         .assert_status(StatusCode::CREATED);
 }
 
+// ── Read-only (VIEWER) members ───────────────────────────────────────────────
+
+async fn viewer_token(f: &TestFixture) -> String {
+    let viewer_id = f
+        .ctx
+        .create_user("Viewer", "viewer@test.com", "password123")
+        .await;
+    f.ctx
+        .add_user_to_org(viewer_id, f.org1_id, vec![UserRole::Viewer])
+        .await;
+    f.ctx
+        .login("viewer@test.com", "password123", Some(f.org1_id))
+        .await
+}
+
+#[tokio::test]
+async fn test_viewer_can_read_but_not_change() {
+    let f = TestFixture::new().await;
+    let token = viewer_token(&f).await;
+    let book_id = Uuid::parse_str(BOOK_KIND_ID).unwrap();
+
+    f.ctx
+        .get(
+            &format!("/api/organizations/{}/items", f.org1_id),
+            Some(&token),
+        )
+        .await
+        .assert_success();
+
+    let res = f
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", f.org1_id),
+            &json!({"kind_id": book_id, "name": "A book"}),
+            Some(&token),
+        )
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(res.body["error"], "auth.forbidden");
+
+    f.ctx
+        .post(
+            &format!("/api/organizations/{}/pages", f.org1_id),
+            &json!({"title": "Shelving map"}),
+            Some(&token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_viewer_can_manage_own_preferences() {
+    let f = TestFixture::new().await;
+    let token = viewer_token(&f).await;
+
+    f.ctx
+        .put(
+            &format!("/api/organizations/{}/preferences", f.org1_id),
+            &json!({"per_page": 50}),
+            Some(&token),
+        )
+        .await
+        .assert_success();
+}
+
+// ── Curators and per-member permissions ──────────────────────────────────────
+
+#[tokio::test]
+async fn test_curator_can_manage_catalog_but_not_settings() {
+    let f = TestFixture::new().await;
+    let curator_id = f
+        .ctx
+        .create_user("Curator", "curator@test.com", "password123")
+        .await;
+    f.ctx
+        .add_user_to_org(curator_id, f.org1_id, vec![UserRole::Curator])
+        .await;
+    let token = f
+        .ctx
+        .login("curator@test.com", "password123", Some(f.org1_id))
+        .await;
+
+    f.ctx
+        .post(
+            &format!("/api/organizations/{}/kinds", f.org1_id),
+            &json!({"name": "widget", "field_ids": []}),
+            Some(&token),
+        )
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    f.ctx
+        .put(
+            &format!("/api/organizations/{}/settings", f.org1_id),
+            &json!({"max_loans_per_borrower": 3}),
+            Some(&token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_member_permissions_can_differ_from_roles() {
+    let f = TestFixture::new().await;
+    let sysadmin_id = f
+        .ctx
+        .create_user("Root", "root@test.com", "password123")
+        .await;
+    f.ctx
+        .add_user_to_org(sysadmin_id, SYSTEM_ORG_ID, vec![UserRole::Admin])
+        .await;
+    let sysadmin_token = f
+        .ctx
+        .login("root@test.com", "password123", Some(SYSTEM_ORG_ID))
+        .await;
+
+    // user1 keeps the USER role but may only read and export history
+    let res = f
+        .ctx
+        .patch(
+            &format!(
+                "/api/admin/users/{}/organizations/{}",
+                f.user1_id, f.org1_id
+            ),
+            &json!({"roles": ["USER"], "permissions": ["history:export", "items:read"]}),
+            Some(&sysadmin_token),
+        )
+        .await;
+    res.assert_success();
+    assert_eq!(
+        res.body["permissions"],
+        json!(["items:read", "history:export"])
+    );
+
+    let token = f
+        .ctx
+        .login("user1@test.com", "password123", Some(f.org1_id))
+        .await;
+    let me = f.ctx.get("/api/auth/me", Some(&token)).await;
+    assert_eq!(me.body["roles"], json!(["USER"]));
+    assert_eq!(
+        me.body["permissions"],
+        json!(["items:read", "history:export"])
+    );
+
+    f.ctx
+        .post(
+            &format!("/api/organizations/{}/items", f.org1_id),
+            &json!({"kind_id": Uuid::parse_str(BOOK_KIND_ID).unwrap(), "name": "A book"}),
+            Some(&token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
+
 // ── System administration (/admin/*) ─────────────────────────────────────────
 
 #[tokio::test]
@@ -147,7 +303,7 @@ async fn test_system_admin_can_access_system_admin() {
         .create_user("Root", "root@test.com", "password123")
         .await;
     f.ctx
-        .add_user_to_org(sysadmin_id, SYSTEM_ORG_ID, vec!["ADMIN".to_string()])
+        .add_user_to_org(sysadmin_id, SYSTEM_ORG_ID, vec![UserRole::Admin])
         .await;
     let sysadmin_token = f
         .ctx
@@ -0,0 +1,342 @@
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{Duration, Local};
+use common::TestFixture;
+use serde_json::json;
+use vostuff_api::api::models::UserRole;
+
+// Fixed UUIDs from seed migration
+const VINYL_KIND_ID: &str = "00000000-0000-0000-0000-000000000001";
+
+/// Create an item, returning its ID
+async fn create_item(fixture: &TestFixture, name: &str) -> String {
+    fixture
+        .create_item(json!({"kind_id": VINYL_KIND_ID, "name": name}))
+        .await
+        .to_string()
+}
+
+async fn patch_item(
+    fixture: &TestFixture,
+    item_id: &str,
+    body: serde_json::Value,
+    token: &str,
+) -> common::TestResponse {
+    fixture
+        .ctx
+        .patch(
+            &format!("/api/organizations/{}/items/{}", fixture.org1_id, item_id),
+            &body,
+            Some(token),
+        )
+        .await
+}
+
+/// Create an item and lend it out, returning the item ID
+async fn lend_item(fixture: &TestFixture, name: &str, borrower: &str, days_ago: i64) -> String {
+    let item_id = create_item(fixture, name).await;
+
+    let date_loaned = Local::now().date_naive() - Duration::days(days_ago);
+    fixture
+        .ctx
+        .patch(
+            &format!("/api/organizations/{}/items/{}", fixture.org1_id, item_id),
+            &json!({
+                "state": "loaned",
+                "loan_date_loaned": date_loaned,
+                "loan_loaned_to": borrower
+            }),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+
+    item_id
+}
+
+#[tokio::test]
+async fn test_borrower_history_tracks_loans_and_returns() {
+    let fixture = TestFixture::new().await;
+
+    let returned_id = lend_item(&fixture, "Abbey Road", "Carol", 10).await;
+    lend_item(&fixture, "Revolver", " carol ", 2).await;
+    lend_item(&fixture, "Help!", "Dave", 1).await;
+
+    // Returning the first item closes its loan
+    fixture
+        .ctx
+        .patch(
+            &format!(
+                "/api/organizations/{}/items/{}",
+                fixture.org1_id, returned_id
+            ),
+            &json!({"state": "current"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+
+    let list = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/borrowers", fixture.org1_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    list.assert_success();
+    let borrowers = list.body.as_array().unwrap();
+    // Names match case-insensitively, so both loans go to one Carol
+    assert_eq!(borrowers.len(), 2);
+    assert_eq!(borrowers[0]["name"], "Carol");
+    assert_eq!(borrowers[0]["loan_count"], 2);
+    assert_eq!(borrowers[0]["outstanding_count"], 1);
+    let carol_id = borrowers[0]["id"].as_str().unwrap();
+
+    let history = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/borrowers/{}/history",
+                fixture.org1_id, carol_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    history.assert_success();
+
+    let loans = history.body["loans"].as_array().unwrap();
+    assert_eq!(loans.len(), 2);
+    assert_eq!(loans[0]["item_name"], "Revolver");
+    assert!(loans[0]["date_returned"].is_null());
+    assert_eq!(loans[1]["item_name"], "Abbey Road");
+    assert!(loans[1]["date_returned"].is_string());
+
+    let outstanding = history.body["outstanding"].as_array().unwrap();
+    assert_eq!(outstanding.len(), 1);
+    assert_eq!(outstanding[0]["item_name"], "Revolver");
+
+    assert_eq!(history.body["average_return_days"].as_f64(), Some(10.0));
+}
+
+#[tokio::test]
+async fn test_loaned_item_details_link_borrower() {
+    let fixture = TestFixture::new().await;
+    let item_id = lend_item(&fixture, "Abbey Road", "Carol", 3).await;
+
+    let details = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items/{}/details",
+                fixture.org1_id, item_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    details.assert_success();
+    assert_eq!(details.body["loan_details"]["loaned_to"], "Carol");
+    assert!(details.body["loan_details"]["borrower_id"].is_string());
+}
+
+#[tokio::test]
+async fn test_borrower_history_is_org_scoped() {
+    let fixture = TestFixture::new().await;
+    lend_item(&fixture, "Abbey Road", "Carol", 3).await;
+
+    let list = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/borrowers", fixture.org1_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    let carol_id = list.body[0]["id"].as_str().unwrap().to_string();
+
+    // A borrower from another org is not visible under this one
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/borrowers/{}/history",
+                fixture.org2_id, carol_id
+            ),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_loan_limits_enforced_with_override() {
+    let fixture = TestFixture::new().await;
+    let settings_path = format!("/api/organizations/{}/settings", fixture.org1_id);
+    let limits = json!({"max_loans_per_borrower": 1, "max_loan_days": 14});
+
+    // Only admins set policy
+    fixture
+        .ctx
+        .put(&settings_path, &limits, Some(&fixture.user1_token))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    fixture
+        .ctx
+        .put(&settings_path, &limits, Some(&fixture.user2_token))
+        .await
+        .assert_success();
+
+    let today = Local::now().date_naive();
+    let first = create_item(&fixture, "Abbey Road").await;
+    let second = create_item(&fixture, "Revolver").await;
+
+    patch_item(
+        &fixture,
+        &first,
+        json!({"state": "loaned", "loan_loaned_to": "Carol", "loan_date_due_back": today + Duration::days(7)}),
+        &fixture.user1_token,
+    )
+    .await
+    .assert_success();
+
+    // Carol is at her limit
+    let response = patch_item(
+        &fixture,
+        &second,
+        json!({"state": "loaned", "loan_loaned_to": "carol", "loan_date_due_back": today + Duration::days(7)}),
+        &fixture.user1_token,
+    )
+    .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(response.body["error"], "loan.limit_exceeded");
+
+    // Too long, or open-ended
+    for due in [Some(today + Duration::days(30)), None] {
+        let response = patch_item(
+            &fixture,
+            &second,
+            json!({"state": "loaned", "loan_loaned_to": "Dave", "loan_date_due_back": due}),
+            &fixture.user1_token,
+        )
+        .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+        assert_eq!(response.body["error"], "loan.limit_exceeded");
+    }
+
+    // Plain users may not override
+    let over_limit = json!({
+        "state": "loaned",
+        "loan_loaned_to": "Carol",
+        "loan_date_due_back": today + Duration::days(60),
+        "override_loan_limits": true
+    });
+    let response = patch_item(&fixture, &second, over_limit.clone(), &fixture.user1_token).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(response.body["error"], "auth.forbidden");
+
+    // A loan manager may
+    let manager_id = fixture
+        .ctx
+        .create_user("Loan Manager", "loans@test.com", "password123")
+        .await;
+    fixture
+        .ctx
+        .add_user_to_org(
+            manager_id,
+            fixture.org1_id,
+            vec![UserRole::User, UserRole::LoanManager],
+        )
+        .await;
+    let manager_token = fixture
+        .ctx
+        .login("loans@test.com", "password123", Some(fixture.org1_id))
+        .await;
+    patch_item(&fixture, &second, over_limit, &manager_token)
+        .await
+        .assert_success();
+}
+
+#[tokio::test]
+async fn test_invalid_loan_settings_rejected() {
+    let fixture = TestFixture::new().await;
+    let settings_path = format!("/api/organizations/{}/settings", fixture.org1_id);
+
+    let response = fixture
+        .ctx
+        .put(
+            &settings_path,
+            &json!({"max_loan_days": 0}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    // Unset limits read back as null
+    let response = fixture
+        .ctx
+        .get(&settings_path, Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    assert!(response.body["max_loan_days"].is_null());
+    assert!(response.body["max_loans_per_borrower"].is_null());
+}
+
+#[tokio::test]
+async fn test_item_loan_history_keeps_past_loans() {
+    let fixture = TestFixture::new().await;
+
+    let item_id = lend_item(&fixture, "Blue Train", "Erin", 20).await;
+    patch_item(
+        &fixture,
+        &item_id,
+        json!({"state": "current"}),
+        &fixture.user1_token,
+    )
+    .await
+    .assert_success();
+
+    let date_loaned = Local::now().date_naive() - Duration::days(3);
+    patch_item(
+        &fixture,
+        &item_id,
+        json!({
+            "state": "loaned",
+            "loan_date_loaned": date_loaned,
+            "loan_loaned_to": "Frank"
+        }),
+        &fixture.user1_token,
+    )
+    .await
+    .assert_success();
+
+    let loans = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items/{}/loans",
+                fixture.org1_id, item_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    loans.assert_success();
+    let loans = loans.body.as_array().unwrap();
+    assert_eq!(loans.len(), 2);
+    assert_eq!(loans[0]["borrower_name"], "Frank");
+    assert!(loans[0]["date_returned"].is_null());
+    assert_eq!(loans[1]["borrower_name"], "Erin");
+    assert!(loans[1]["date_returned"].is_string());
+
+    // Another organization cannot see the item's loans
+    let other = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items/{}/loans",
+                fixture.org2_id, item_id
+            ),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    other.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(other.body["error"], "item.not_found");
+}
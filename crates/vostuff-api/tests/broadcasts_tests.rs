@@ -0,0 +1,174 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use common::TestFixture;
+use serde_json::json;
+use vostuff_api::api::{
+    jobs::{self, JobHandler},
+    mail::{Email, Mailer, SendEmailJob},
+    models::UserRole,
+};
+
+/// Mailer that keeps what it was asked to send
+#[derive(Default)]
+struct RecordingMailer {
+    sent: Mutex<Vec<Email>>,
+}
+
+#[async_trait]
+impl Mailer for RecordingMailer {
+    async fn send(&self, email: &Email) -> anyhow::Result<()> {
+        self.sent.lock().unwrap().push(email.clone());
+        Ok(())
+    }
+}
+
+/// Messages of the broadcasts a member is shown
+async fn shown_to(fixture: &TestFixture, token: &str) -> Vec<String> {
+    let response = fixture.ctx.get("/api/auth/me", Some(token)).await;
+    response.assert_success();
+    response.body["broadcasts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b["message"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_broadcasts_reach_targeted_organizations_until_withdrawn() {
+    let fixture = TestFixture::new().await;
+    let admin = fixture.ctx.sysadmin_token().await;
+    let expires_at = Utc::now() + Duration::days(2);
+
+    // Organization admins are not system admins
+    fixture
+        .ctx
+        .post(
+            "/api/admin/broadcasts",
+            &json!({"message": "Downtime", "expires_at": expires_at}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    for body in [
+        json!({"message": " ", "expires_at": expires_at}),
+        json!({"message": "Late", "expires_at": Utc::now() - Duration::hours(1)}),
+        json!({"message": "Nobody", "expires_at": expires_at, "organization_ids": []}),
+        json!({"message": "Unknown", "expires_at": expires_at, "organization_ids": [uuid::Uuid::new_v4()]}),
+    ] {
+        let response = fixture
+            .ctx
+            .post("/api/admin/broadcasts", &body, Some(&admin))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        assert_eq!(response.body["error"], "validation.failed");
+    }
+
+    let everyone = fixture
+        .ctx
+        .post(
+            "/api/admin/broadcasts",
+            &json!({"message": "Maintenance on Sunday 02:00-04:00 UTC", "expires_at": expires_at}),
+            Some(&admin),
+        )
+        .await;
+    everyone.assert_status(StatusCode::CREATED);
+    assert!(everyone.body["organization_ids"].is_null());
+    let org1_only = fixture
+        .ctx
+        .post(
+            "/api/admin/broadcasts",
+            &json!({
+                "message": "Your data is moving region",
+                "expires_at": expires_at,
+                "organization_ids": [fixture.org1_id],
+            }),
+            Some(&admin),
+        )
+        .await;
+    org1_only.assert_status(StatusCode::CREATED);
+
+    assert_eq!(
+        shown_to(&fixture, &fixture.user1_token).await,
+        vec![
+            "Maintenance on Sunday 02:00-04:00 UTC",
+            "Your data is moving region"
+        ]
+    );
+    assert_eq!(
+        shown_to(&fixture, &fixture.user3_token).await,
+        vec!["Maintenance on Sunday 02:00-04:00 UTC"]
+    );
+
+    // Expired broadcasts are listed for admins but no longer shown
+    sqlx::query("UPDATE broadcasts SET expires_at = NOW() - INTERVAL '1 minute' WHERE id = $1")
+        .bind(uuid::Uuid::parse_str(everyone.body["id"].as_str().unwrap()).unwrap())
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    assert!(shown_to(&fixture, &fixture.user3_token).await.is_empty());
+    let listed = fixture.ctx.get("/api/admin/broadcasts", Some(&admin)).await;
+    listed.assert_success();
+    assert_eq!(listed.body.as_array().unwrap().len(), 2);
+
+    let path = format!(
+        "/api/admin/broadcasts/{}",
+        org1_only.body["id"].as_str().unwrap()
+    );
+    fixture
+        .ctx
+        .delete(&path, Some(&admin))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    assert!(shown_to(&fixture, &fixture.user1_token).await.is_empty());
+    fixture
+        .ctx
+        .delete(&path, Some(&admin))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_broadcast_emailed_once_to_each_targeted_member() {
+    let fixture = TestFixture::new().await;
+    let admin = fixture.ctx.sysadmin_token().await;
+    // A member of both organizations
+    fixture
+        .ctx
+        .add_user_to_org(fixture.user1_id, fixture.org2_id, vec![UserRole::User])
+        .await;
+
+    let response = fixture
+        .ctx
+        .post(
+            "/api/admin/broadcasts",
+            &json!({
+                "message": "Scheduled downtime tonight",
+                "expires_at": Utc::now() + Duration::days(1),
+                "organization_ids": [fixture.org1_id, fixture.org2_id],
+                "email": true,
+            }),
+            Some(&admin),
+        )
+        .await;
+    response.assert_status(StatusCode::CREATED);
+
+    let mailer = Arc::new(RecordingMailer::default());
+    let handlers: Vec<Arc<dyn JobHandler>> = vec![Arc::new(SendEmailJob::new(mailer.clone()))];
+    while jobs::run_next(&fixture.ctx.pool, &handlers).await.unwrap() {}
+
+    let mut sent = mailer.sent.lock().unwrap().clone();
+    sent.sort_by(|a, b| a.to.cmp(&b.to));
+    let recipients: Vec<&str> = sent.iter().map(|e| e.to.as_str()).collect();
+    assert_eq!(
+        recipients,
+        vec!["user1@test.com", "user2@test.com", "user3@test.com"]
+    );
+    assert!(sent[0].body.starts_with("Scheduled downtime tonight"));
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::TestContext;
+
+#[tokio::test]
+async fn test_changelog_needs_no_login() {
+    let ctx = TestContext::new().await;
+
+    let response = ctx.get("/api/changelog", None).await;
+    response.assert_success();
+    let releases = response.body.as_array().unwrap();
+    assert!(!releases.is_empty());
+    let latest = releases[0]["version"].as_str().unwrap();
+    assert!(releases[0]["date"].is_string());
+    let change = &releases[0]["changes"][0];
+    assert!(
+        ["added", "changed", "deprecated", "removed", "fixed"]
+            .contains(&change["kind"].as_str().unwrap())
+    );
+    assert!(change["endpoints"].is_array());
+
+    // Nothing is newer than the latest release
+    let response = ctx
+        .get(&format!("/api/changelog?since={}", latest), None)
+        .await;
+    response.assert_success();
+    assert_eq!(response.body, serde_json::json!([]));
+
+    // A version this server never had returns everything
+    let response = ctx.get("/api/changelog?since=1999.1.1", None).await;
+    assert_eq!(response.body.as_array().unwrap().len(), releases.len());
+}
@@ -0,0 +1,558 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::json;
+use uuid::Uuid;
+
+// Fixed UUIDs from seed migration
+const CD_KIND_ID: &str = "00000000-0000-0000-0000-000000000002";
+
+async fn create_item(fixture: &TestFixture, name: &str) -> String {
+    fixture
+        .create_item(json!({"kind_id": CD_KIND_ID, "name": name}))
+        .await
+        .to_string()
+}
+
+fn item_names(body: &serde_json::Value) -> Vec<&str> {
+    body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_collection_items_add_list_remove() {
+    let fixture = TestFixture::new().await;
+
+    let collection = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/collections", fixture.org1_id),
+            &json!({"name": "Desert Island Discs"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    collection.assert_status(StatusCode::CREATED);
+    let collection_id = collection.body["id"].as_str().unwrap().to_string();
+    let members_path = format!(
+        "/api/organizations/{}/collections/{}/items",
+        fixture.org1_id, collection_id
+    );
+
+    let blue = create_item(&fixture, "Blue").await;
+    let hejira = create_item(&fixture, "Hejira").await;
+    create_item(&fixture, "Not In Collection").await;
+
+    for item_id in [&blue, &hejira, &blue] {
+        fixture
+            .ctx
+            .post(
+                &members_path,
+                &json!({"item_id": item_id}),
+                Some(&fixture.user1_token),
+            )
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+    }
+
+    let response = fixture
+        .ctx
+        .get(&members_path, Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["total"], 2);
+    assert_eq!(item_names(&response.body), vec!["Blue", "Hejira"]);
+
+    // The item list filters the same way
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items?collection_id={}",
+                fixture.org1_id, collection_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(item_names(&response.body), vec!["Blue", "Hejira"]);
+
+    fixture
+        .ctx
+        .delete(
+            &format!("{}/{}", members_path, blue),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let response = fixture
+        .ctx
+        .delete(
+            &format!("{}/{}", members_path, blue),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "item.not_found");
+
+    let response = fixture
+        .ctx
+        .get(&members_path, Some(&fixture.user1_token))
+        .await;
+    assert_eq!(item_names(&response.body), vec!["Hejira"]);
+}
+
+#[tokio::test]
+async fn test_collection_items_not_found() {
+    let fixture = TestFixture::new().await;
+
+    let collection = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/collections", fixture.org1_id),
+            &json!({"name": "Favourites"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    let collection_id = collection.body["id"].as_str().unwrap().to_string();
+
+    // Unknown collection
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/collections/{}/items",
+                fixture.org1_id,
+                Uuid::new_v4()
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "collection.not_found");
+
+    // Unknown item
+    let response = fixture
+        .ctx
+        .post(
+            &format!(
+                "/api/organizations/{}/collections/{}/items",
+                fixture.org1_id, collection_id
+            ),
+            &json!({"item_id": Uuid::new_v4()}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "item.not_found");
+
+    // Another organization's collection
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/collections/{}/items",
+                fixture.org2_id, collection_id
+            ),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_collection_create_update_delete() {
+    let fixture = TestFixture::new().await;
+    let collections_path = format!("/api/organizations/{}/collections", fixture.org1_id);
+
+    let created = fixture
+        .ctx
+        .post(
+            &collections_path,
+            &json!({"name": "Jazz", "description": "Mostly bebop", "notes": "*Alphabetical*"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    assert_eq!(created.body["organization_id"], fixture.org1_id.to_string());
+    let collection_path = format!(
+        "{}/{}",
+        collections_path,
+        created.body["id"].as_str().unwrap()
+    );
+
+    // Members without catalog:write cannot manage collections
+    fixture
+        .ctx
+        .post(
+            &collections_path,
+            &json!({"name": "Mine"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    fixture
+        .ctx
+        .patch(
+            &collection_path,
+            &json!({"name": "Mine"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    fixture
+        .ctx
+        .delete(&collection_path, Some(&fixture.user1_token))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    // Fields left out of an update are kept
+    let response = fixture
+        .ctx
+        .patch(
+            &collection_path,
+            &json!({"notes": "By label"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["name"], "Jazz");
+    assert_eq!(response.body["description"], "Mostly bebop");
+    assert_eq!(response.body["notes"], "By label");
+
+    let response = fixture
+        .ctx
+        .get(&collections_path, Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    assert_eq!(response.body.as_array().unwrap().len(), 1);
+    assert_eq!(response.body[0]["notes"], "By label");
+
+    fixture
+        .ctx
+        .delete(&collection_path, Some(&fixture.user2_token))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let response = fixture
+        .ctx
+        .delete(&collection_path, Some(&fixture.user2_token))
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "collection.not_found");
+    let response = fixture
+        .ctx
+        .patch(
+            &collection_path,
+            &json!({"name": "Gone"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_delete_collection_with_members() {
+    let fixture = TestFixture::new().await;
+
+    let collection = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/collections", fixture.org1_id),
+            &json!({"name": "Favourites"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    let collection_path = format!(
+        "/api/organizations/{}/collections/{}",
+        fixture.org1_id,
+        collection.body["id"].as_str().unwrap()
+    );
+    let item_id = create_item(&fixture, "Blue").await;
+    fixture
+        .ctx
+        .post(
+            &format!("{}/items", collection_path),
+            &json!({"item_id": item_id}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    fixture
+        .ctx
+        .delete(&collection_path, Some(&fixture.user2_token))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    // The items stay, just no longer in the collection
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items/{}/full",
+                fixture.org1_id, item_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["item"]["name"], "Blue");
+    assert_eq!(response.body["collections"], json!([]));
+
+    fixture
+        .ctx
+        .get(
+            &format!("{}/items", collection_path),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_collections_stay_within_organization() {
+    let fixture = TestFixture::new().await;
+
+    // A collection and an item belonging to org2
+    let theirs: Uuid = sqlx::query_scalar(
+        "INSERT INTO collections (organization_id, name) VALUES ($1, 'Theirs') RETURNING id",
+    )
+    .bind(fixture.org2_id)
+    .fetch_one(&fixture.ctx.pool)
+    .await
+    .unwrap();
+    let their_item = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org2_id),
+            &json!({"kind_id": CD_KIND_ID, "name": "Their Record"}),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    their_item.assert_status(StatusCode::CREATED);
+    let their_item_id = their_item.body["id"].as_str().unwrap().to_string();
+
+    // Addressed through org1, org2's collection does not exist
+    let their_path = format!(
+        "/api/organizations/{}/collections/{}",
+        fixture.org1_id, theirs
+    );
+    let our_item_id = create_item(&fixture, "Our Record").await;
+    for response in [
+        fixture
+            .ctx
+            .get(&format!("{}/items", their_path), Some(&fixture.user2_token))
+            .await,
+        fixture
+            .ctx
+            .post(
+                &format!("{}/items", their_path),
+                &json!({"item_id": our_item_id}),
+                Some(&fixture.user2_token),
+            )
+            .await,
+        fixture
+            .ctx
+            .patch(
+                &their_path,
+                &json!({"name": "Ours"}),
+                Some(&fixture.user2_token),
+            )
+            .await,
+        fixture
+            .ctx
+            .delete(&their_path, Some(&fixture.user2_token))
+            .await,
+    ] {
+        response.assert_status(StatusCode::NOT_FOUND);
+        assert_eq!(response.body["error"], "collection.not_found");
+    }
+
+    // Nor can org2's items be added to an org1 collection
+    let ours = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/collections", fixture.org1_id),
+            &json!({"name": "Ours"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    let response = fixture
+        .ctx
+        .post(
+            &format!(
+                "/api/organizations/{}/collections/{}/items",
+                fixture.org1_id,
+                ours.body["id"].as_str().unwrap()
+            ),
+            &json!({"item_id": their_item_id}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "item.not_found");
+
+    // org2's collection is untouched and not listed for org1
+    let name: String = sqlx::query_scalar("SELECT name FROM collections WHERE id = $1")
+        .bind(theirs)
+        .fetch_one(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(name, "Theirs");
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/collections", fixture.org1_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    assert_eq!(response.body.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_collection_cover_order_and_item_count() {
+    let fixture = TestFixture::new().await;
+    let collections_path = format!("/api/organizations/{}/collections", fixture.org1_id);
+    let create = |name: &'static str, sort_order: Option<i32>| {
+        let fixture = &fixture;
+        let path = collections_path.clone();
+        async move {
+            let mut body = json!({"name": name});
+            if let Some(sort_order) = sort_order {
+                body["sort_order"] = json!(sort_order);
+            }
+            let created = fixture
+                .ctx
+                .post(&path, &body, Some(&fixture.user2_token))
+                .await;
+            created.assert_status(StatusCode::CREATED);
+            format!("{}/{}", path, created.body["id"].as_str().unwrap())
+        }
+    };
+    let jazz_path = create("Jazz", None).await;
+    create("Blues", Some(1)).await;
+    create("Ambient", Some(1)).await;
+
+    let response = fixture
+        .ctx
+        .get(&collections_path, Some(&fixture.user1_token))
+        .await;
+    let names: Vec<&str> = response
+        .body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Jazz", "Ambient", "Blues"]);
+
+    let blue = create_item(&fixture, "Blue Train").await;
+    let kind_of_blue = create_item(&fixture, "Kind of Blue").await;
+    for item_id in [&blue, &kind_of_blue] {
+        fixture
+            .ctx
+            .post(
+                &format!("{}/items", jazz_path),
+                &json!({"item_id": item_id}),
+                Some(&fixture.user1_token),
+            )
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+    }
+
+    let response = fixture
+        .ctx
+        .get(&jazz_path, Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["name"], "Jazz");
+    assert_eq!(response.body["item_count"], 2);
+    assert_eq!(response.body["cover_item_id"], serde_json::Value::Null);
+    assert_eq!(response.body["sort_order"], 0);
+
+    // The cover must be one of the collection's items
+    let outsider = create_item(&fixture, "Not In Collection").await;
+    let response = fixture
+        .ctx
+        .patch(
+            &jazz_path,
+            &json!({"cover_item_id": outsider}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.body["error"], "collection.cover_not_in_collection");
+
+    let response = fixture
+        .ctx
+        .patch(
+            &jazz_path,
+            &json!({"cover_item_id": blue, "sort_order": 5}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["cover_item_id"], blue.as_str());
+    assert_eq!(response.body["sort_order"], 5);
+
+    // Other changes keep the cover; the nil UUID clears it
+    let response = fixture
+        .ctx
+        .patch(
+            &jazz_path,
+            &json!({"name": "Hard Bop"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    assert_eq!(response.body["cover_item_id"], blue.as_str());
+    let response = fixture
+        .ctx
+        .patch(
+            &jazz_path,
+            &json!({"cover_item_id": Uuid::nil()}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["cover_item_id"], serde_json::Value::Null);
+
+    // Taking the cover out of the collection clears it
+    fixture
+        .ctx
+        .patch(
+            &jazz_path,
+            &json!({"cover_item_id": kind_of_blue}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_success();
+    fixture
+        .ctx
+        .delete(
+            &format!("{}/items/{}", jazz_path, kind_of_blue),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let response = fixture
+        .ctx
+        .get(&jazz_path, Some(&fixture.user1_token))
+        .await;
+    assert_eq!(response.body["cover_item_id"], serde_json::Value::Null);
+    assert_eq!(response.body["item_count"], 1);
+
+    // Other organizations' collections are not found
+    let response = fixture
+        .ctx
+        .get(
+            &jazz_path.replace(&fixture.org1_id.to_string(), &fixture.org2_id.to_string()),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "collection.not_found");
+}
@@ -1,15 +1,22 @@
+// Shared by several test binaries; not every helper is used by each one.
+#![allow(dead_code)]
+
 use axum::{
     Router,
     body::Body,
-    http::{Request, StatusCode},
+    http::{HeaderMap, Request, StatusCode},
 };
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 use sqlx::PgPool;
 use tower::ServiceExt;
 use uuid::Uuid;
-use vostuff_api::api::{models::LoginRequest, state::AppState};
-use vostuff_core::auth::PasswordHasher;
+use vostuff_api::api::{
+    models::{LoginRequest, UserRole},
+    state::AppState,
+};
+use vostuff_core::auth::{PasswordHasher, SYSTEM_ORG_ID};
+use vostuff_core::permissions::Permissions;
 
 /// Test context that holds database pool and app state
 pub struct TestContext {
@@ -38,11 +45,25 @@ impl TestContext {
 
         // Build the app router and nest under /api (same as in main)
         let api_router = vostuff_api::api::handlers::build_router(state.clone());
-        let app = axum::Router::new().nest("/api", api_router);
+        let app = axum::Router::new()
+            .merge(vostuff_api::api::docs::router())
+            .nest("/api", api_router);
 
         Self { pool, state, app }
     }
 
+    /// Replace the app state, e.g. to enable an optional feature, and rebuild the router
+    pub fn set_state(&mut self, state: AppState) {
+        self.app = axum::Router::new()
+            .merge(vostuff_api::api::docs::router())
+            .merge(vostuff_api::api::metrics::router(state.clone()))
+            .nest(
+                "/api",
+                vostuff_api::api::handlers::build_router(state.clone()),
+            );
+        self.state = state;
+    }
+
     /// Clean all tables in the database, preserving shared kinds/fields seed data
     async fn clean_database(pool: &PgPool) {
         // Delete in dependency order; use DELETE (not TRUNCATE CASCADE) so that
@@ -55,20 +76,29 @@ impl TestContext {
             "item_disposed_details",
             "item_missing_details",
             "item_loan_details",
+            "item_loans",
+            "item_suggestions",
             "items",
         ] {
             sqlx::query(&format!("DELETE FROM {}", table))
                 .execute(pool)
                 .await
-                .expect(&format!("Failed to delete from {}", table));
+                .unwrap_or_else(|e| panic!("Failed to delete from {}: {}", table, e));
         }
 
         // Org-specific content
-        for table in ["tags", "collections", "locations"] {
+        for table in [
+            "item_imports",
+            "tags",
+            "collections",
+            "smart_collections",
+            "locations",
+            "borrowers",
+        ] {
             sqlx::query(&format!("DELETE FROM {}", table))
                 .execute(pool)
                 .await
-                .expect(&format!("Failed to delete from {}", table));
+                .unwrap_or_else(|e| panic!("Failed to delete from {}: {}", table, e));
         }
 
         // Org-specific kinds and fields; CASCADE removes kind_fields and enum_values
@@ -84,6 +114,10 @@ impl TestContext {
 
         // Users and orgs (preserve the SYSTEM org at the fixed UUID)
         for stmt in [
+            "DELETE FROM data_exports",
+            "DELETE FROM broadcasts",
+            "DELETE FROM outbox_events",
+            "DELETE FROM jobs",
             "DELETE FROM user_organizations",
             "DELETE FROM users",
             "DELETE FROM organizations WHERE id != 'ffffffff-ffff-ffff-ffff-ffffffffffff'",
@@ -127,13 +161,16 @@ impl TestContext {
         rec.id
     }
 
-    /// Add user to organization with roles
-    pub async fn add_user_to_org(&self, user_id: Uuid, org_id: Uuid, roles: Vec<String>) {
+    /// Add user to organization with roles, and the permissions they grant
+    pub async fn add_user_to_org(&self, user_id: Uuid, org_id: Uuid, roles: Vec<UserRole>) {
+        let permissions = Permissions::for_roles(&roles).into_vec();
         sqlx::query!(
-            "INSERT INTO user_organizations (user_id, organization_id, roles) VALUES ($1, $2, $3)",
+            "INSERT INTO user_organizations (user_id, organization_id, roles, permissions)
+             VALUES ($1, $2, $3, $4)",
             user_id,
             org_id,
-            &roles
+            &roles as _,
+            &permissions as _
         )
         .execute(&self.pool)
         .await
@@ -146,6 +183,7 @@ impl TestContext {
             identity: identity.to_string(),
             password: password.to_string(),
             organization_id: org_id,
+            new_password: None,
         };
 
         let response = self.post("/api/auth/login", &login_req, None).await;
@@ -168,6 +206,17 @@ impl TestContext {
         }
     }
 
+    /// Create a SYSTEM-org admin and return their token
+    pub async fn sysadmin_token(&self) -> String {
+        let sysadmin_id = self
+            .create_user("Root", "root@test.com", "password123")
+            .await;
+        self.add_user_to_org(sysadmin_id, SYSTEM_ORG_ID, vec![UserRole::Admin])
+            .await;
+        self.login("root@test.com", "password123", Some(SYSTEM_ORG_ID))
+            .await
+    }
+
     /// Make a GET request
     pub async fn get(&self, path: &str, token: Option<&str>) -> TestResponse {
         self.request("GET", path, None::<&()>, token).await
@@ -193,6 +242,16 @@ impl TestContext {
         self.request("PATCH", path, Some(body), token).await
     }
 
+    /// Make a PUT request
+    pub async fn put<T: serde::Serialize>(
+        &self,
+        path: &str,
+        body: &T,
+        token: Option<&str>,
+    ) -> TestResponse {
+        self.request("PUT", path, Some(body), token).await
+    }
+
     /// Make a DELETE request
     pub async fn delete(&self, path: &str, token: Option<&str>) -> TestResponse {
         self.request("DELETE", path, None::<&()>, token).await
@@ -206,11 +265,27 @@ impl TestContext {
         body: Option<&T>,
         token: Option<&str>,
     ) -> TestResponse {
-        let mut request_builder = Request::builder().method(method).uri(path);
-
         // Add Authorization header if token provided
-        if let Some(token) = token {
-            request_builder = request_builder.header("Authorization", format!("Bearer {}", token));
+        let authorization = token.map(|token| format!("Bearer {}", token));
+        let headers: Vec<(&str, &str)> = authorization
+            .iter()
+            .map(|value| ("Authorization", value.as_str()))
+            .collect();
+        self.request_with_headers(method, path, body, &headers)
+            .await
+    }
+
+    /// Make an HTTP request with the given headers, e.g. to authenticate other than by token
+    pub async fn request_with_headers<T: serde::Serialize>(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&T>,
+        headers: &[(&str, &str)],
+    ) -> TestResponse {
+        let mut request_builder = Request::builder().method(method).uri(path);
+        for (name, value) in headers {
+            request_builder = request_builder.header(*name, *value);
         }
 
         // Add body if provided
@@ -225,7 +300,55 @@ impl TestContext {
                 .body(Body::empty())
                 .expect("Failed to build request")
         };
+        self.send(request).await
+    }
 
+    /// Make a POST request with a raw body, e.g. an uploaded file
+    pub async fn post_bytes(
+        &self,
+        path: &str,
+        content_type: &str,
+        body: Vec<u8>,
+        token: Option<&str>,
+    ) -> TestResponse {
+        self.bytes_request("POST", path, content_type, body, token)
+            .await
+    }
+
+    /// Make a PUT request with a raw body, e.g. an uploaded image
+    pub async fn put_bytes(
+        &self,
+        path: &str,
+        content_type: &str,
+        body: Vec<u8>,
+        token: Option<&str>,
+    ) -> TestResponse {
+        self.bytes_request("PUT", path, content_type, body, token)
+            .await
+    }
+
+    async fn bytes_request(
+        &self,
+        method: &str,
+        path: &str,
+        content_type: &str,
+        body: Vec<u8>,
+        token: Option<&str>,
+    ) -> TestResponse {
+        let mut request_builder = Request::builder()
+            .method(method)
+            .uri(path)
+            .header("Content-Type", content_type);
+        if let Some(token) = token {
+            request_builder = request_builder.header("Authorization", format!("Bearer {}", token));
+        }
+        let request = request_builder
+            .body(Body::from(body))
+            .expect("Failed to build request");
+        self.send(request).await
+    }
+
+    async fn send(&self, request: Request<Body>) -> TestResponse {
         // Execute request
         let response = self
             .app
@@ -235,6 +358,7 @@ impl TestContext {
             .expect("Failed to execute request");
 
         let status = response.status();
+        let headers = response.headers().clone();
         let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
             .await
             .expect("Failed to read response body");
@@ -246,7 +370,11 @@ impl TestContext {
                 .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&body_bytes).to_string()))
         };
 
-        TestResponse { status, body }
+        TestResponse {
+            status,
+            headers,
+            body,
+        }
     }
 }
 
@@ -254,6 +382,7 @@ impl TestContext {
 #[derive(Debug)]
 pub struct TestResponse {
     pub status: StatusCode,
+    pub headers: HeaderMap,
     pub body: Value,
 }
 
@@ -321,15 +450,11 @@ impl TestFixture {
             .await;
 
         // Add users to organizations with roles
-        ctx.add_user_to_org(user1_id, org1_id, vec!["USER".to_string()])
+        ctx.add_user_to_org(user1_id, org1_id, vec![UserRole::User])
             .await;
-        ctx.add_user_to_org(
-            user2_id,
-            org1_id,
-            vec!["USER".to_string(), "ADMIN".to_string()],
-        )
-        .await;
-        ctx.add_user_to_org(user3_id, org2_id, vec!["USER".to_string()])
+        ctx.add_user_to_org(user2_id, org1_id, vec![UserRole::User, UserRole::Admin])
+            .await;
+        ctx.add_user_to_org(user3_id, org2_id, vec![UserRole::User])
             .await;
 
         // Login users to get tokens
@@ -355,4 +480,19 @@ impl TestFixture {
             user3_token,
         }
     }
+
+    /// Create an item in org1 as its admin, who may also create new tags,
+    /// returning the item's ID
+    pub async fn create_item(&self, item: Value) -> Uuid {
+        let response = self
+            .ctx
+            .post(
+                &format!("/api/organizations/{}/items", self.org1_id),
+                &item,
+                Some(&self.user2_token),
+            )
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        response.body["id"].as_str().unwrap().parse().unwrap()
+    }
 }
@@ -0,0 +1,70 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestContext;
+use serde_json::json;
+use vostuff_api::api::docs::{DOCS_PATH, OPENAPI_PATH};
+
+#[tokio::test]
+async fn test_swagger_ui_is_served() {
+    let ctx = TestContext::new().await;
+
+    let redirect = ctx.get(DOCS_PATH, None).await;
+    assert!(redirect.status.is_redirection());
+    assert_eq!(redirect.headers["location"], format!("{}/", DOCS_PATH));
+
+    let page = ctx.get(&format!("{}/", DOCS_PATH), None).await;
+    page.assert_success();
+    assert!(
+        page.headers["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/html")
+    );
+}
+
+#[tokio::test]
+async fn test_every_documented_operation_is_routed() {
+    let ctx = TestContext::new().await;
+    let spec = ctx.get(OPENAPI_PATH, None).await;
+    spec.assert_success();
+    assert_eq!(spec.body["info"]["title"], "VOStuff API");
+
+    let paths = spec.body["paths"].as_object().unwrap();
+    assert!(paths.contains_key("/api/organizations/{org_id}/items"));
+
+    let mut unrouted = Vec::new();
+    for (path, operations) in paths {
+        // Any value reaches the handler; its extractors may then reject it
+        let uri = path
+            .split('/')
+            .map(|segment| {
+                if segment.starts_with('{') {
+                    "00000000-0000-0000-0000-000000000000"
+                } else {
+                    segment
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        for method in operations.as_object().unwrap().keys() {
+            let method = method.to_uppercase();
+            let body = matches!(method.as_str(), "POST" | "PUT" | "PATCH").then(|| json!({}));
+            let response = ctx
+                .request_with_headers(&method, &uri, body.as_ref(), &[])
+                .await;
+            // The router answers unknown paths with an empty 404 and unknown
+            // methods with 405; handlers always explain themselves
+            if response.status == StatusCode::METHOD_NOT_ALLOWED
+                || (response.status == StatusCode::NOT_FOUND && response.body.is_null())
+            {
+                unrouted.push(format!("{} {}", method, path));
+            }
+        }
+    }
+    assert!(
+        unrouted.is_empty(),
+        "Documented but not routed: {:?}",
+        unrouted
+    );
+}
@@ -0,0 +1,280 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::json;
+use uuid::Uuid;
+
+// Fixed UUIDs from seed migration
+const VINYL_KIND_ID: &str = "00000000-0000-0000-0000-000000000001";
+const CD_KIND_ID: &str = "00000000-0000-0000-0000-000000000002";
+
+fn group_names(group: &serde_json::Value) -> Vec<&str> {
+    group["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_duplicates_are_grouped_by_kind_and_similar_name() {
+    let fixture = TestFixture::new().await;
+    for (kind, name) in [
+        (CD_KIND_ID, "Kind of Blue"),
+        (CD_KIND_ID, "Kind Of Blue!"),
+        (CD_KIND_ID, "Kind of Blues"),
+        (CD_KIND_ID, "Blue Train"),
+        // Same name, other kind: not a duplicate of the CDs
+        (VINYL_KIND_ID, "Kind of Blue"),
+    ] {
+        fixture
+            .create_item(json!({"kind_id": kind, "name": name}))
+            .await;
+    }
+
+    let path = format!("/api/organizations/{}/items/duplicates", fixture.org1_id);
+    let response = fixture.ctx.get(&path, Some(&fixture.user1_token)).await;
+    response.assert_success();
+    let groups = response.body.as_array().unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0]["kind_name"], "cd");
+    assert_eq!(groups[0]["similarity"], 1.0);
+    // Oldest first
+    assert_eq!(
+        group_names(&groups[0]),
+        ["Kind of Blue", "Kind Of Blue!", "Kind of Blues"]
+    );
+
+    // A strict threshold leaves out the misspelling
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}?threshold=1&kind=cd", path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(
+        group_names(&response.body[0]),
+        ["Kind of Blue", "Kind Of Blue!"]
+    );
+
+    let response = fixture
+        .ctx
+        .get(&format!("{}?kind=vinyl", path), Some(&fixture.user1_token))
+        .await;
+    assert_eq!(response.body, json!([]));
+
+    let response = fixture
+        .ctx
+        .get(&format!("{}?threshold=0", path), Some(&fixture.user1_token))
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    // Other organizations' items are not matched
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/items/duplicates", fixture.org2_id),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    assert_eq!(response.body, json!([]));
+}
+
+#[tokio::test]
+async fn test_merge_moves_links_and_fills_missing_fields() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+
+    let keep = fixture
+        .create_item(json!({"kind_id": CD_KIND_ID, "name": "Kind of Blue", "description": "Mono"}))
+        .await;
+    let duplicate = fixture
+        .create_item(json!({
+            "kind_id": CD_KIND_ID,
+            "name": "Kind Of Blue",
+            "description": "Stereo",
+            "barcode": "5099706424420",
+            "catalog_code": "CK 64935"
+        }))
+        .await;
+
+    // Tags, one shared, and a collection on the duplicate
+    for tag in ["jazz", "modal"] {
+        fixture
+            .ctx
+            .post(
+                &format!("{}/tags", org_path),
+                &json!({"name": tag}),
+                Some(&fixture.user2_token),
+            )
+            .await
+            .assert_success();
+    }
+    for (item_id, tag) in [(keep, "jazz"), (duplicate, "jazz"), (duplicate, "modal")] {
+        sqlx::query(
+            "INSERT INTO item_tags (item_id, organization_id, tag_name) VALUES ($1, $2, $3)",
+        )
+        .bind(item_id)
+        .bind(fixture.org1_id)
+        .bind(tag)
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    }
+    let collection = fixture
+        .ctx
+        .post(
+            &format!("{}/collections", org_path),
+            &json!({"name": "Favourites"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    collection.assert_success();
+    let collection_id = collection.body["id"].as_str().unwrap().to_string();
+    fixture
+        .ctx
+        .post(
+            &format!("{}/collections/{}/items", org_path, collection_id),
+            &json!({"item_id": duplicate}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_success();
+
+    // A loan of the duplicate, since returned
+    let item_path = |id: Uuid| format!("{}/items/{}", org_path, id);
+    for body in [
+        json!({"state": "loaned", "loan_loaned_to": "Alice"}),
+        json!({"state": "current"}),
+    ] {
+        fixture
+            .ctx
+            .patch(&item_path(duplicate), &body, Some(&fixture.user1_token))
+            .await
+            .assert_success();
+    }
+
+    let response = fixture
+        .ctx
+        .post(
+            &format!("{}/merge", item_path(keep)),
+            &json!({"duplicate_id": duplicate}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    // The kept item's own values win; missing ones come from the duplicate
+    assert_eq!(response.body["description"], "Mono");
+    assert_eq!(response.body["barcode"], "5099706424420");
+    assert_eq!(response.body["catalog_code"], "CK 64935");
+
+    fixture
+        .ctx
+        .get(&item_path(duplicate), Some(&fixture.user1_token))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    let full = fixture
+        .ctx
+        .get(
+            &format!("{}/full", item_path(keep)),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    full.assert_success();
+    let mut tags: Vec<&str> = full.body["tags"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t.as_str().unwrap())
+        .collect();
+    tags.sort();
+    assert_eq!(tags, ["jazz", "modal"]);
+    assert_eq!(full.body["collections"][0]["name"], "Favourites");
+    assert_eq!(full.body["history"][0]["action"], "merged");
+    assert_eq!(
+        full.body["history"][0]["change_details"],
+        "Merged 'Kind Of Blue' into this item"
+    );
+
+    let loans = fixture
+        .ctx
+        .get(
+            &format!("{}/loans", item_path(keep)),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    loans.assert_success();
+    assert_eq!(loans.body.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_merge_refuses_other_kinds_loans_and_itself() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+    let cd = fixture
+        .create_item(json!({"kind_id": CD_KIND_ID, "name": "Blue Train"}))
+        .await;
+    let vinyl = fixture
+        .create_item(json!({"kind_id": VINYL_KIND_ID, "name": "Blue Train"}))
+        .await;
+    let loaned = fixture
+        .create_item(json!({"kind_id": CD_KIND_ID, "name": "Blue Train"}))
+        .await;
+    fixture
+        .ctx
+        .patch(
+            &format!("{}/items/{}", org_path, loaned),
+            &json!({"state": "loaned", "loan_loaned_to": "Bob"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+
+    let merge = |duplicate: Uuid| {
+        let path = format!("{}/items/{}/merge", org_path, cd);
+        let token = fixture.user1_token.clone();
+        let ctx = &fixture.ctx;
+        async move {
+            ctx.post(&path, &json!({"duplicate_id": duplicate}), Some(&token))
+                .await
+        }
+    };
+
+    let response = merge(vinyl).await;
+    response.assert_status(StatusCode::CONFLICT);
+    assert_eq!(response.body["error"], "item.merge_conflict");
+
+    merge(loaned).await.assert_status(StatusCode::CONFLICT);
+    merge(cd).await.assert_status(StatusCode::BAD_REQUEST);
+    merge(Uuid::new_v4())
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    // Nothing was deleted
+    for id in [vinyl, loaned] {
+        fixture
+            .ctx
+            .get(
+                &format!("{}/items/{}", org_path, id),
+                Some(&fixture.user1_token),
+            )
+            .await
+            .assert_success();
+    }
+
+    // Merging needs a login
+    let response = fixture
+        .ctx
+        .post(
+            &format!("{}/items/{}/merge", org_path, cd),
+            &json!({"duplicate_id": vinyl}),
+            None,
+        )
+        .await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,198 @@
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    body::{Body, BodyDataStream},
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
+use common::TestFixture;
+use futures::StreamExt;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+use uuid::Uuid;
+use vostuff_api::api::{
+    live::{LiveSink, RESYNC},
+    outbox::{self, EventSink, OutboxEvent},
+};
+
+// Fixed UUIDs from seed migration
+const VINYL_KIND_ID: &str = "00000000-0000-0000-0000-000000000001";
+
+/// An organization's event stream, opened as the holder of `token`
+async fn open_stream(fixture: &TestFixture, org_id: Uuid, token: &str) -> BodyDataStream {
+    let request = Request::builder()
+        .uri(format!("/api/organizations/{}/events", org_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = fixture.ctx.app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+    response.into_body().into_data_stream()
+}
+
+/// The next `count` events from a stream, as (type, data)
+async fn next_events(stream: &mut BodyDataStream, count: usize) -> Vec<(String, Value)> {
+    let mut text = String::new();
+    let mut events = Vec::new();
+    while events.len() < count {
+        let chunk = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("Timed out waiting for an event")
+            .expect("Stream ended")
+            .unwrap();
+        text.push_str(std::str::from_utf8(&chunk).unwrap());
+        while let Some(end) = text.find("\n\n") {
+            let frame: String = text.drain(..end + 2).collect();
+            let field = |name: &str| {
+                frame
+                    .lines()
+                    .find_map(|line| line.strip_prefix(&format!("{}: ", name)))
+                    .map(str::to_string)
+            };
+            // Keep-alive comments have no event
+            if let Some(event_type) = field("event") {
+                let data = serde_json::from_str(&field("data").unwrap()).unwrap();
+                events.push((event_type, data));
+            }
+        }
+    }
+    events
+}
+
+async fn dispatch(fixture: &TestFixture) {
+    let sinks: Vec<Arc<dyn EventSink>> =
+        vec![Arc::new(LiveSink::new(fixture.ctx.state.live.clone()))];
+    outbox::dispatch_pending(&fixture.ctx.pool, &sinks)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_changes_are_streamed_to_organization_members() {
+    let fixture = TestFixture::new().await;
+    let org1 = |path: &str| format!("/api/organizations/{}/{}", fixture.org1_id, path);
+    let mut org1_stream = open_stream(&fixture, fixture.org1_id, &fixture.user1_token).await;
+    let mut org2_stream = open_stream(&fixture, fixture.org2_id, &fixture.user3_token).await;
+
+    let item = fixture
+        .ctx
+        .post(
+            &org1("items"),
+            &json!({"kind_id": VINYL_KIND_ID, "name": "Blue Train"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    item.assert_status(StatusCode::CREATED);
+    let collection = fixture
+        .ctx
+        .post(
+            &org1("collections"),
+            &json!({"name": "Jazz"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    collection.assert_status(StatusCode::CREATED);
+    fixture
+        .ctx
+        .post(
+            &org1(&format!(
+                "collections/{}/items",
+                collection.body["id"].as_str().unwrap()
+            )),
+            &json!({"item_id": item.body["id"]}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org2_id),
+            &json!({"kind_id": VINYL_KIND_ID, "name": "Requiem"}),
+            Some(&fixture.user3_token),
+        )
+        .await
+        .assert_status(StatusCode::CREATED);
+    fixture
+        .ctx
+        .post(
+            &org1("tags"),
+            &json!({"name": "hard-bop"}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::CREATED);
+    fixture
+        .ctx
+        .delete(&org1("tags/hard-bop"), Some(&fixture.user2_token))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    dispatch(&fixture).await;
+
+    let events = next_events(&mut org1_stream, 5).await;
+    let types: Vec<_> = events.iter().map(|(t, _)| t.as_str()).collect();
+    assert_eq!(
+        types,
+        [
+            "item.created",
+            "collection.created",
+            "collection.item_added",
+            "tag.created",
+            "tag.deleted"
+        ]
+    );
+    assert_eq!(events[0].1["name"], "Blue Train");
+    assert_eq!(events[1].1["name"], "Jazz");
+    assert_eq!(events[2].1["item_id"], item.body["id"]);
+    assert_eq!(events[2].1["collection_id"], collection.body["id"]);
+    assert_eq!(events[4].1["name"], "hard-bop");
+
+    // Only the other organization's own change reaches its stream
+    let events = next_events(&mut org2_stream, 1).await;
+    assert_eq!(events[0].0, "item.created");
+    assert_eq!(events[0].1["name"], "Requiem");
+}
+
+#[tokio::test]
+async fn test_events_require_membership() {
+    let fixture = TestFixture::new().await;
+    let path = format!("/api/organizations/{}/events", fixture.org1_id);
+
+    fixture
+        .ctx
+        .get(&path, Some(&fixture.user3_token))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    fixture
+        .ctx
+        .get(&path, None)
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_slow_subscriber_is_told_to_resync() {
+    let fixture = TestFixture::new().await;
+    let mut stream = open_stream(&fixture, fixture.org1_id, &fixture.user1_token).await;
+
+    // More changes than a subscriber's buffer holds, before it reads any
+    for id in 0..2000 {
+        fixture.ctx.state.live.publish(OutboxEvent {
+            id,
+            organization_id: fixture.org1_id,
+            event_type: outbox::ITEM_UPDATED.to_string(),
+            payload: json!({}),
+            created_at: Utc::now(),
+        });
+    }
+
+    let events = next_events(&mut stream, 2).await;
+    assert_eq!(events[0].0, RESYNC);
+    assert!(events[0].1.as_u64().unwrap() > 0);
+    assert_eq!(events[1].0, "item.updated");
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use axum::http::{StatusCode, header};
+use common::TestFixture;
+use serde_json::json;
+use uuid::Uuid;
+
+// Fixed UUIDs from seed migration
+const VINYL_KIND_ID: &str = "00000000-0000-0000-0000-000000000001";
+
+#[tokio::test]
+async fn test_deleting_organization_keeps_final_export() {
+    let fixture = TestFixture::new().await;
+    let token = fixture.ctx.sysadmin_token().await;
+
+    let created = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            &json!({"kind_id": Uuid::parse_str(VINYL_KIND_ID).unwrap(), "name": "Blue Train"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_success();
+
+    let deleted = fixture
+        .ctx
+        .delete(
+            &format!("/api/admin/organizations/{}", fixture.org1_id),
+            Some(&token),
+        )
+        .await;
+    deleted.assert_status(StatusCode::NO_CONTENT);
+    let location = deleted.headers[header::LOCATION].to_str().unwrap();
+
+    // The export is listed with its download link
+    let listed = fixture.ctx.get("/api/admin/exports", Some(&token)).await;
+    listed.assert_success();
+    let exports = listed.body.as_array().unwrap();
+    assert_eq!(exports.len(), 1);
+    assert_eq!(exports[0]["subject_type"], "organization");
+    assert_eq!(exports[0]["subject_id"], fixture.org1_id.to_string());
+    assert_eq!(exports[0]["download_url"], location);
+
+    // The archive holds the org's data even though the org is gone
+    let archive = fixture.ctx.get(location, Some(&token)).await;
+    archive.assert_success();
+    assert_eq!(
+        archive.body["organization"]["id"],
+        fixture.org1_id.to_string()
+    );
+    assert_eq!(archive.body["items"][0]["name"], "Blue Train");
+    assert_eq!(archive.body["items"][0]["kind_name"], "vinyl");
+    assert_eq!(archive.body["members"].as_array().unwrap().len(), 2);
+
+    // Only SYSTEM-org admins can read exports
+    let res = fixture.ctx.get(location, Some(&fixture.user3_token)).await;
+    res.assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_deleting_user_keeps_final_export_without_password() {
+    let fixture = TestFixture::new().await;
+    let token = fixture.ctx.sysadmin_token().await;
+
+    let deleted = fixture
+        .ctx
+        .delete(
+            &format!("/api/admin/users/{}", fixture.user2_id),
+            Some(&token),
+        )
+        .await;
+    deleted.assert_status(StatusCode::NO_CONTENT);
+    let location = deleted.headers[header::LOCATION].to_str().unwrap();
+
+    let archive = fixture.ctx.get(location, Some(&token)).await;
+    archive.assert_success();
+    assert_eq!(archive.body["user"]["id"], fixture.user2_id.to_string());
+    assert!(archive.body["user"].get("password_hash").is_none());
+    assert_eq!(
+        archive.body["memberships"][0]["organization_id"],
+        fixture.org1_id.to_string()
+    );
+    assert_eq!(
+        archive.body["memberships"][0]["roles"],
+        json!(["USER", "ADMIN"])
+    );
+
+    // Deleting an unknown user does not create an export
+    let res = fixture
+        .ctx
+        .delete(
+            &format!("/api/admin/users/{}", Uuid::new_v4()),
+            Some(&token),
+        )
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+    let listed = fixture.ctx.get("/api/admin/exports", Some(&token)).await;
+    assert_eq!(listed.body.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_expired_exports_are_purged() {
+    let fixture = TestFixture::new().await;
+    let token = fixture.ctx.sysadmin_token().await;
+
+    let deleted = fixture
+        .ctx
+        .delete(
+            &format!("/api/admin/organizations/{}", fixture.org2_id),
+            Some(&token),
+        )
+        .await;
+    deleted.assert_status(StatusCode::NO_CONTENT);
+    let location = deleted.headers[header::LOCATION].to_str().unwrap();
+
+    // Move the export past its retention window
+    sqlx::query("UPDATE data_exports SET expires_at = NOW() - INTERVAL '1 day'")
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+
+    let res = fixture.ctx.get(location, Some(&token)).await;
+    res.assert_status(StatusCode::NOT_FOUND);
+
+    let listed = fixture.ctx.get("/api/admin/exports", Some(&token)).await;
+    listed.assert_success();
+    assert!(listed.body.as_array().unwrap().is_empty());
+}
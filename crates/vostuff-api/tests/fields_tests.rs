@@ -145,14 +145,14 @@ async fn test_create_string_field() {
         .ctx
         .post(
             &format!("/api/organizations/{}/fields", f.org1_id),
-            &json!({"name": "edition", "display_name": "Edition", "field_type": "string"}),
+            &json!({"name": "printing", "display_name": "Printing", "field_type": "string"}),
             Some(&f.user2_token),
         )
         .await;
 
     res.assert_status(StatusCode::CREATED);
-    assert_eq!(res.body["name"], "edition");
-    assert_eq!(res.body["display_name"], "Edition");
+    assert_eq!(res.body["name"], "printing");
+    assert_eq!(res.body["display_name"], "Printing");
     assert_eq!(res.body["field_type"], "string");
     assert_eq!(res.body["is_shared"], false);
     assert_eq!(res.body["org_id"], f.org1_id.to_string());
@@ -169,7 +169,7 @@ async fn test_create_enum_field_with_values() {
         .post(
             &format!("/api/organizations/{}/fields", f.org1_id),
             &json!({
-                "name": "condition",
+                "name": "wear",
                 "field_type": "enum",
                 "enum_values": [
                     {"value": "new",  "display_value": "New",  "sort_order": 1},
@@ -204,7 +204,7 @@ async fn test_create_field_conflicts_with_shared_name() {
         .await;
 
     res.assert_status(StatusCode::CONFLICT);
-    assert_eq!(res.body["error"], "name_conflict");
+    assert_eq!(res.body["error"], "field.name_conflict");
 }
 
 #[tokio::test]
@@ -225,7 +225,7 @@ async fn test_create_field_conflicts_within_org() {
         .await;
 
     res.assert_status(StatusCode::CONFLICT);
-    assert_eq!(res.body["error"], "name_conflict");
+    assert_eq!(res.body["error"], "field.name_conflict");
 }
 
 #[tokio::test]
@@ -246,7 +246,7 @@ async fn test_create_non_enum_field_with_enum_values_is_rejected() {
         .await;
 
     res.assert_status(StatusCode::BAD_REQUEST);
-    assert_eq!(res.body["error"], "invalid_enum_values");
+    assert_eq!(res.body["error"], "field.invalid_enum_values");
 }
 
 #[tokio::test]
@@ -320,7 +320,7 @@ async fn test_update_enum_field_remove_value_in_use_is_blocked() {
         .await;
 
     res.assert_status(StatusCode::CONFLICT);
-    assert_eq!(res.body["error"], "enum_value_in_use");
+    assert_eq!(res.body["error"], "field.enum_value_in_use");
     // Error message should mention the blocked value
     let msg = res.body["message"].as_str().unwrap();
     assert!(msg.contains("new"), "message should mention 'new': {}", msg);
@@ -360,7 +360,7 @@ async fn test_update_non_enum_field_with_enum_values_is_rejected() {
         .await;
 
     res.assert_status(StatusCode::BAD_REQUEST);
-    assert_eq!(res.body["error"], "not_enum_field");
+    assert_eq!(res.body["error"], "field.not_enum");
 }
 
 #[tokio::test]
@@ -403,7 +403,7 @@ async fn test_delete_field_in_use_by_kind_is_blocked() {
         .await;
 
     res.assert_status(StatusCode::CONFLICT);
-    assert_eq!(res.body["error"], "field_in_use");
+    assert_eq!(res.body["error"], "field.in_use");
 }
 
 #[tokio::test]
@@ -442,3 +442,90 @@ async fn test_list_fields_shows_org_fields() {
         .any(|f| f["name"].as_str() == Some("my_custom_field"));
     assert!(has_custom, "org field should appear in list");
 }
+
+#[tokio::test]
+async fn test_claim_shared_field_names_merges_or_renames_org_fields() {
+    let f = TestFixture::new().await;
+    let pool = &f.ctx.pool;
+    let drop_shared =
+        "DELETE FROM fields WHERE org_id IS NULL AND name IN ('clash_grade', 'clash_year')";
+    sqlx::query(drop_shared).execute(pool).await.unwrap();
+
+    // Org fields made before a migration adds shared ones of the same names
+    let grade_id = create_enum_field(&f, "clash_grade", &["good"]).await;
+    let year_id = create_field(&f, "clash_year", "string").await;
+    let res = f
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/kinds", f.org1_id),
+            &json!({"name": "clash_kind", "field_ids": [grade_id, year_id]}),
+            Some(&f.user2_token),
+        )
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let kind_id = res.body["id"].as_str().unwrap().to_string();
+    let res = f
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", f.org1_id),
+            &json!({"kind_id": kind_id, "name": "Clashing item",
+                    "soft_fields": {"clash_grade": "good", "clash_year": "1999"}}),
+            Some(&f.user2_token),
+        )
+        .await;
+    res.assert_success();
+    let item_id = res.body["id"].as_str().unwrap().to_string();
+
+    // The shared grade has the org's values and more; the shared year is a number
+    let shared_grade_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO fields (org_id, name, field_type) VALUES (NULL, 'clash_grade', 'enum') RETURNING id",
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    sqlx::query("INSERT INTO enum_values (field_id, value) VALUES ($1, 'good'), ($1, 'poor')")
+        .bind(shared_grade_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO fields (org_id, name, field_type) VALUES (NULL, 'clash_year', 'number')",
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+    sqlx::query("SELECT claim_shared_field_names()")
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let kind = f
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/kinds/{}", f.org1_id, kind_id),
+            Some(&f.user2_token),
+        )
+        .await;
+    let item = f
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/items/{}", f.org1_id, item_id),
+            Some(&f.user2_token),
+        )
+        .await;
+    sqlx::query(drop_shared).execute(pool).await.unwrap();
+
+    kind.assert_success();
+    let fields = kind.body["fields"].as_array().unwrap();
+    let names: Vec<&str> = fields.iter().map(|f| f["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["clash_grade", "clash_year_org"]);
+    // Merged into the shared field, keeping the items' keys
+    assert_eq!(fields[0]["id"], json!(shared_grade_id));
+    // Renamed, with the items' key renamed to match
+    assert_eq!(fields[1]["id"], json!(year_id));
+    item.assert_success();
+    assert_eq!(
+        item.body["soft_fields"],
+        json!({"clash_grade": "good", "clash_year_org": "1999"})
+    );
+}
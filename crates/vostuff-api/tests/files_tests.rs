@@ -0,0 +1,50 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use common::TestFixture;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_presigned_url_serves_file() {
+    let fixture = TestFixture::new().await;
+    let storage = &fixture.ctx.state.storage;
+    let key = format!("tests/{}.txt", Uuid::new_v4());
+
+    storage.put(&key, b"hello".to_vec()).await.unwrap();
+    let url = storage
+        .presign(&key, Duration::from_secs(60))
+        .await
+        .unwrap();
+    let path = url.trim_start_matches("http://localhost:8080");
+
+    // No token needed: the signature grants access
+    let response = fixture.ctx.get(path, None).await;
+    response.assert_success();
+    assert_eq!(response.body, "hello");
+
+    // A tampered signature is rejected
+    let tampered = format!("{}0", path);
+    let response = fixture.ctx.get(&tampered, None).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    storage.delete(&key).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_presigned_url_expires() {
+    let fixture = TestFixture::new().await;
+    let storage = &fixture.ctx.state.storage;
+    let key = format!("tests/{}.txt", Uuid::new_v4());
+
+    storage.put(&key, b"hello".to_vec()).await.unwrap();
+    let url = storage.presign(&key, Duration::ZERO).await.unwrap();
+    let path = url.trim_start_matches("http://localhost:8080");
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let response = fixture.ctx.get(path, None).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    storage.delete(&key).await.unwrap();
+}
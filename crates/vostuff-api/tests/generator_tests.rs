@@ -0,0 +1,72 @@
+mod common;
+
+use common::TestContext;
+use vostuff_api::api::models::UserRole;
+use vostuff_api::generator::ItemGenerator;
+
+#[tokio::test]
+async fn test_generated_items_are_consistent_and_searchable() {
+    let ctx = TestContext::new().await;
+    let org_id = ctx.create_organization("Bench", "Generated items").await;
+    let user_id = ctx
+        .create_user("Bench", "bench@example.com", "secret123")
+        .await;
+    ctx.add_user_to_org(user_id, org_id, vec![UserRole::User])
+        .await;
+    sqlx::query("INSERT INTO locations (organization_id, name) VALUES ($1, 'Shelf')")
+        .bind(org_id)
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+
+    let report = ItemGenerator::new(&ctx.pool, 1)
+        .with_batch_size(300)
+        .generate(org_id, 2_000)
+        .await
+        .unwrap();
+    assert_eq!(report.items, 2_000);
+    assert_eq!(report.by_state.values().sum::<usize>(), 2_000);
+    assert!(report.by_state["current"] > 1_600);
+    assert!(report.by_kind["vinyl"] > report.by_kind["misc"]);
+
+    let (items, tags, located): (i64, i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*),
+                (SELECT COUNT(*) FROM item_tags WHERE organization_id = $1),
+                COUNT(location_id)
+         FROM items WHERE organization_id = $1",
+    )
+    .bind(org_id)
+    .fetch_one(&ctx.pool)
+    .await
+    .unwrap();
+    assert_eq!(items, 2_000);
+    assert_eq!(tags as usize, report.tags);
+    assert_eq!(located, 2_000);
+
+    // Every item in a state has its details, and every loan an open history row
+    let unmatched: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM items i
+         WHERE i.organization_id = $1 AND CASE i.state
+             WHEN 'loaned' THEN NOT EXISTS (SELECT 1 FROM item_loan_details d WHERE d.item_id = i.id)
+                 OR NOT EXISTS (SELECT 1 FROM item_loans l
+                                WHERE l.item_id = i.id AND l.date_returned IS NULL)
+             WHEN 'missing' THEN NOT EXISTS (SELECT 1 FROM item_missing_details d WHERE d.item_id = i.id)
+             WHEN 'disposed' THEN NOT EXISTS (SELECT 1 FROM item_disposed_details d WHERE d.item_id = i.id)
+             ELSE false END",
+    )
+    .bind(org_id)
+    .fetch_one(&ctx.pool)
+    .await
+    .unwrap();
+    assert_eq!(unmatched, 0);
+
+    let token = ctx.login("bench@example.com", "secret123", None).await;
+    let loaned = ctx
+        .get(
+            &format!("/api/organizations/{}/items?state=loaned", org_id),
+            Some(&token),
+        )
+        .await;
+    loaned.assert_success();
+    assert_eq!(loaned.body["total"], report.by_state["loaned"]);
+}
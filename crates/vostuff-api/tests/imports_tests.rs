@@ -0,0 +1,300 @@
+mod common;
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::{Value, json};
+use uuid::Uuid;
+use vostuff_api::api::{
+    imports::ImportItemsJob,
+    jobs::{self, JobHandler},
+};
+
+const BOOK_KIND_ID: &str = "00000000-0000-0000-0000-000000000004";
+
+const BOOKS_CSV: &str = "\
+Title,Author,Year,ISBN13,Bookshelves
+Dune,Frank Herbert,1965,=\"9780441172719\",\"sci-fi, classics\"
+,Nobody,2001,,
+Dune Messiah,Frank Herbert,1969,=\"9780441172719\",sci-fi
+Emma,Jane Austen,soon,,
+";
+
+fn books_mapping() -> Value {
+    json!({
+        "kind": "book",
+        "tags": ["imported"],
+        "columns": [
+            {"column": "Title", "target": "name"},
+            {"column": "Author", "target": "soft:author"},
+            {"column": "Year", "target": "soft:year"},
+            {"column": "ISBN13", "target": "barcode"},
+            {"column": "Bookshelves", "target": "tags"}
+        ]
+    })
+}
+
+/// Run queued jobs as the API server's worker would
+async fn run_jobs(fixture: &TestFixture) {
+    let handlers: Vec<Arc<dyn JobHandler>> = vec![Arc::new(ImportItemsJob::new(
+        fixture.ctx.pool.clone(),
+        fixture.ctx.state.storage.clone(),
+    ))];
+    while jobs::run_next(&fixture.ctx.pool, &handlers).await.unwrap() {}
+}
+
+#[tokio::test]
+async fn test_import_creates_items_and_reports_rows() {
+    let fixture = TestFixture::new().await;
+    let response = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/imports", fixture.org1_id),
+            &json!({
+                "csv": BOOKS_CSV,
+                "filename": "goodreads_library_export.csv",
+                "mapping": books_mapping()
+            }),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::ACCEPTED);
+    assert_eq!(response.body["status"], "pending");
+    assert_eq!(response.body["kind_id"], BOOK_KIND_ID);
+    assert_eq!(response.body["total_rows"], 4);
+    assert_eq!(response.body["processed_rows"], 0);
+    let import_id = response.body["id"].as_str().unwrap().to_string();
+
+    run_jobs(&fixture).await;
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/imports/{}",
+                fixture.org1_id, import_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    let import = &response.body;
+    assert_eq!(import["status"], "succeeded");
+    assert_eq!(import["filename"], "goodreads_library_export.csv");
+    assert_eq!(import["kind_name"], "book");
+    assert_eq!(import["processed_rows"], 4);
+    assert_eq!(import["imported_rows"], 1);
+    assert_eq!(import["skipped_rows"], 2);
+    assert_eq!(import["failed_rows"], 1);
+    assert!(import["finished_at"].is_string());
+
+    // The uploaded CSV is not kept once the import is done
+    let key: String = sqlx::query_scalar("SELECT storage_key FROM item_imports WHERE id = $1")
+        .bind(Uuid::parse_str(&import_id).unwrap())
+        .fetch_one(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    assert!(fixture.ctx.state.storage.get(&key).await.is_err());
+
+    let errors = import["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[0]["row"], 2);
+    assert_eq!(errors[0]["name"], Value::Null);
+    assert_eq!(errors[0]["skipped"], true);
+    assert_eq!(errors[1]["row"], 3);
+    assert_eq!(errors[1]["name"], "Dune Messiah");
+    assert_eq!(errors[1]["skipped"], true);
+    assert_eq!(errors[2]["row"], 4);
+    assert_eq!(errors[2]["skipped"], false);
+    assert_eq!(errors[2]["message"], "year: not a number: soon");
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    let items = response.body["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["name"], "Dune");
+    assert_eq!(items[0]["barcode"], "9780441172719");
+    assert_eq!(
+        items[0]["soft_fields"],
+        json!({"author": "Frank Herbert", "year": 1965})
+    );
+
+    let tags: Vec<String> = sqlx::query_scalar("SELECT tag_name FROM item_tags ORDER BY tag_name")
+        .fetch_all(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(tags, ["classics", "imported", "sci-fi"]);
+}
+
+#[tokio::test]
+async fn test_import_guesses_a_mapping_from_the_headers() {
+    let fixture = TestFixture::new().await;
+    let response = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/imports", fixture.org1_id),
+            &json!({
+                "csv": "Title,Author,Pages\nEmma,Jane Austen,474\n",
+                "kind": "book"
+            }),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::ACCEPTED);
+    let columns = response.body["mapping"]["columns"].as_array().unwrap();
+    let target = |column: &str| {
+        columns
+            .iter()
+            .find(|c| c["column"] == column)
+            .map(|c| c["target"].clone())
+            .unwrap()
+    };
+    assert_eq!(target("Title"), "name");
+    assert_eq!(target("Author"), "soft:author");
+    assert_eq!(target("Pages"), "ignore");
+    let import_id = response.body["id"].as_str().unwrap().to_string();
+
+    run_jobs(&fixture).await;
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/imports/{}",
+                fixture.org1_id, import_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["status"], "succeeded");
+    assert_eq!(response.body["imported_rows"], 1);
+}
+
+#[tokio::test]
+async fn test_import_tags_need_catalog_write() {
+    let fixture = TestFixture::new().await;
+    // A USER may import, but not create tags
+    let response = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/imports", fixture.org1_id),
+            &json!({"csv": BOOKS_CSV, "mapping": books_mapping()}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::ACCEPTED);
+    let import_id = response.body["id"].as_str().unwrap().to_string();
+
+    run_jobs(&fixture).await;
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/imports/{}",
+                fixture.org1_id, import_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["imported_rows"], 0);
+    assert_eq!(response.body["failed_rows"], 3);
+    let message = response.body["errors"][0]["message"].as_str().unwrap();
+    assert!(message.contains("catalog:write"), "{}", message);
+}
+
+#[tokio::test]
+async fn test_import_rejects_unusable_files_and_mappings() {
+    let fixture = TestFixture::new().await;
+    let path = format!("/api/organizations/{}/imports", fixture.org1_id);
+    let import = |body: Value| {
+        let path = path.clone();
+        let fixture = &fixture;
+        async move {
+            fixture
+                .ctx
+                .post(&path, &body, Some(&fixture.user1_token))
+                .await
+        }
+    };
+
+    // Headers only
+    let response = import(json!({"csv": "Title,Author\n", "kind": "book"})).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    // No column to name items by
+    let response = import(json!({"csv": "Colour,Size\nred,3\n"})).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let response = import(json!({"csv": "Title\nEmma\n", "kind": "spaceship"})).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let mut mapping = books_mapping();
+    mapping["columns"][0]["column"] = json!("Name");
+    let response = import(json!({"csv": BOOKS_CSV, "mapping": mapping})).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert!(
+        response.body["message"]
+            .as_str()
+            .unwrap()
+            .contains("Mapped column not in CSV: Name")
+    );
+
+    // The kind's fields only
+    let mut mapping = books_mapping();
+    mapping["columns"][1]["target"] = json!("soft:disks");
+    let response = import(json!({"csv": BOOKS_CSV, "mapping": mapping})).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item_imports")
+        .fetch_one(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn test_import_belongs_to_its_organization() {
+    let fixture = TestFixture::new().await;
+    let body = json!({"csv": "Title\nEmma\n"});
+    let path = format!("/api/organizations/{}/imports", fixture.org1_id);
+
+    let response = fixture.ctx.post(&path, &body, None).await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+
+    let response = fixture
+        .ctx
+        .post(&path, &body, Some(&fixture.user3_token))
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    let response = fixture
+        .ctx
+        .post(&path, &body, Some(&fixture.user1_token))
+        .await;
+    response.assert_status(StatusCode::ACCEPTED);
+    let import_id = response.body["id"].as_str().unwrap().to_string();
+
+    // Not visible through another organization
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/imports/{}",
+                fixture.org2_id, import_id
+            ),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "import.not_found");
+}
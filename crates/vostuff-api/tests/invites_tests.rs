@@ -0,0 +1,267 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::json;
+use vostuff_api::api::{
+    mail::{Email, InviteMailSink, Mailer},
+    outbox::{self, EventSink},
+};
+
+/// Mailer that keeps what it was asked to send
+#[derive(Default)]
+struct RecordingMailer {
+    sent: Mutex<Vec<Email>>,
+}
+
+#[async_trait]
+impl Mailer for RecordingMailer {
+    async fn send(&self, email: &Email) -> anyhow::Result<()> {
+        self.sent.lock().unwrap().push(email.clone());
+        Ok(())
+    }
+}
+
+/// Deliver pending outbox events through the invite mail sink; returns the emails sent
+async fn send_invite_mail(fixture: &TestFixture) -> Vec<Email> {
+    let mailer = Arc::new(RecordingMailer::default());
+    let sinks: Vec<Arc<dyn EventSink>> = vec![Arc::new(InviteMailSink::new(
+        mailer.clone(),
+        &fixture.ctx.state.jwt_secret,
+        "http://web.test/accept-invite".to_string(),
+    ))];
+    outbox::dispatch_pending(&fixture.ctx.pool, &sinks)
+        .await
+        .unwrap();
+    mailer.sent.lock().unwrap().clone()
+}
+
+/// The token from the invite link in an email
+fn link_token(email: &Email) -> String {
+    let start = email.body.find("?token=").unwrap() + "?token=".len();
+    email.body[start..]
+        .split_whitespace()
+        .next()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_invite_creates_account_and_membership() {
+    let fixture = TestFixture::new().await;
+    let invites = format!("/api/organizations/{}/invites", fixture.org1_id);
+
+    // Plain members cannot invite
+    fixture
+        .ctx
+        .post(
+            &invites,
+            &json!({"email": "new@test.com"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let created = fixture
+        .ctx
+        .post(
+            &invites,
+            &json!({"email": "new@test.com", "roles": ["CURATOR"]}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    assert_eq!(created.body["roles"], json!(["CURATOR"]));
+    assert!(created.body.get("token").is_none());
+
+    let listed = fixture.ctx.get(&invites, Some(&fixture.user2_token)).await;
+    listed.assert_success();
+    assert_eq!(listed.body.as_array().unwrap().len(), 1);
+
+    // The link is emailed to the invited address
+    let sent = send_invite_mail(&fixture).await;
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].to, "new@test.com");
+    assert_eq!(sent[0].subject, "Join Test Org 1 on VOStuff");
+    assert!(sent[0].body.starts_with("User Two has invited you"));
+    let token = link_token(&sent[0]);
+
+    // A new account needs a name and password
+    fixture
+        .ctx
+        .post("/api/auth/accept-invite", &json!({"token": token}), None)
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let accepted = fixture
+        .ctx
+        .post(
+            "/api/auth/accept-invite",
+            &json!({"token": token, "name": "New Person", "password": "secret123"}),
+            None,
+        )
+        .await;
+    accepted.assert_status(StatusCode::CREATED);
+    assert_eq!(
+        accepted.body["organization_id"],
+        fixture.org1_id.to_string()
+    );
+    assert_eq!(accepted.body["roles"], json!(["CURATOR"]));
+
+    // The new member can log in, and the link cannot be used again
+    fixture
+        .ctx
+        .login("new@test.com", "secret123", Some(fixture.org1_id))
+        .await;
+    let reused = fixture
+        .ctx
+        .post(
+            "/api/auth/accept-invite",
+            &json!({"token": token, "name": "Someone", "password": "other"}),
+            None,
+        )
+        .await;
+    reused.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(reused.body["error"], "invite.invalid");
+
+    let listed = fixture.ctx.get(&invites, Some(&fixture.user2_token)).await;
+    assert!(listed.body.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_invite_existing_user_revoke_and_replace() {
+    let fixture = TestFixture::new().await;
+    let invites = format!("/api/organizations/{}/invites", fixture.org1_id);
+    let invite = |email: &'static str| {
+        let invites = invites.clone();
+        let fixture = &fixture;
+        async move {
+            let response = fixture
+                .ctx
+                .post(
+                    &invites,
+                    &json!({"email": email}),
+                    Some(&fixture.user2_token),
+                )
+                .await;
+            response.assert_status(StatusCode::CREATED);
+            response.body["id"].as_str().unwrap().to_string()
+        }
+    };
+    let accept = |token: String| {
+        let fixture = &fixture;
+        async move {
+            fixture
+                .ctx
+                .post("/api/auth/accept-invite", &json!({"token": token}), None)
+                .await
+        }
+    };
+
+    // Existing members cannot be invited
+    fixture
+        .ctx
+        .post(
+            &invites,
+            &json!({"email": "USER1@test.com"}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::CONFLICT);
+
+    // An existing account joins without choosing a password
+    invite("user3@test.com").await;
+    let sent = send_invite_mail(&fixture).await;
+    let accepted = accept(link_token(&sent[0])).await;
+    accepted.assert_status(StatusCode::CREATED);
+    assert_eq!(accepted.body["user_id"], fixture.user3_id.to_string());
+    assert_eq!(accepted.body["roles"], json!(["USER"]));
+
+    // A revoked invite's link stops working
+    let revoked_id = invite("revoked@test.com").await;
+    let sent = send_invite_mail(&fixture).await;
+    fixture
+        .ctx
+        .delete(
+            &format!("{}/{}", invites, revoked_id),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    accept(link_token(&sent[0]))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    fixture
+        .ctx
+        .delete(
+            &format!("{}/{}", invites, revoked_id),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    // Inviting again replaces the pending invite and its link
+    invite("twice@test.com").await;
+    let first = send_invite_mail(&fixture).await;
+    invite("twice@test.com").await;
+    let second = send_invite_mail(&fixture).await;
+    let body = json!({"name": "Twice", "password": "secret123"});
+    let mut with_first = body.clone();
+    with_first["token"] = json!(link_token(&first[0]));
+    fixture
+        .ctx
+        .post("/api/auth/accept-invite", &with_first, None)
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    let mut with_second = body;
+    with_second["token"] = json!(link_token(&second[0]));
+    fixture
+        .ctx
+        .post("/api/auth/accept-invite", &with_second, None)
+        .await
+        .assert_status(StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn test_invite_cannot_grant_more_than_the_inviter_holds() {
+    let fixture = TestFixture::new().await;
+    let invites = format!("/api/organizations/{}/invites", fixture.org1_id);
+
+    // A member who may manage members, but not settings
+    sqlx::query(
+        "UPDATE user_organizations
+         SET permissions = ARRAY['items:read', 'items:write', 'members:manage']
+         WHERE user_id = $1 AND organization_id = $2",
+    )
+    .bind(fixture.user1_id)
+    .bind(fixture.org1_id)
+    .execute(&fixture.ctx.pool)
+    .await
+    .unwrap();
+    let token = fixture
+        .ctx
+        .login("user1@test.com", "password123", Some(fixture.org1_id))
+        .await;
+
+    fixture
+        .ctx
+        .post(
+            &invites,
+            &json!({"email": "admin@test.com", "roles": ["ADMIN"]}),
+            Some(&token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    fixture
+        .ctx
+        .post(
+            &invites,
+            &json!({"email": "viewer@test.com", "roles": ["VIEWER"]}),
+            Some(&token),
+        )
+        .await
+        .assert_status(StatusCode::CREATED);
+}
@@ -1,6 +1,7 @@
 mod common;
 
-use axum::http::StatusCode;
+use axum::http::{StatusCode, header};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use common::TestFixture;
 use serde_json::json;
 use uuid::Uuid;
@@ -100,6 +101,105 @@ async fn test_create_vinyl_with_soft_fields() {
     );
 }
 
+#[tokio::test]
+async fn test_create_item_reports_invalid_soft_fields() {
+    let fixture = TestFixture::new().await;
+    let vinyl_id = Uuid::parse_str(VINYL_KIND_ID).unwrap();
+
+    let response = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            &json!({
+                "kind_id": vinyl_id,
+                "name": "Abbey Road",
+                "soft_fields": {"size": "13_inch", "disks": "two"}
+            }),
+            Some(&fixture.user1_token),
+        )
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.body["error"], "validation.failed");
+    let mut fields: Vec<&str> = response.body["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["field"].as_str().unwrap())
+        .collect();
+    fields.sort();
+    assert_eq!(fields, ["disks", "size"]);
+}
+
+#[tokio::test]
+async fn test_item_payloads_are_validated() {
+    let fixture = TestFixture::new().await;
+    let vinyl_id = Uuid::parse_str(VINYL_KIND_ID).unwrap();
+    let items_path = format!("/api/organizations/{}/items", fixture.org1_id);
+
+    // Blank names, over-long codes and negative disk counts never reach the database
+    let response = fixture
+        .ctx
+        .post(
+            &items_path,
+            &json!({
+                "kind_id": vinyl_id,
+                "name": " ",
+                "barcode": "1".repeat(65)
+            }),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.body["error"], "validation.failed");
+    let fields: Vec<&str> = response.body["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, ["name", "barcode"]);
+
+    let response = fixture
+        .ctx
+        .post(
+            &items_path,
+            &json!({"kind_id": vinyl_id, "name": "Revolver", "soft_fields": {"disks": -1}}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.body["fields"][0]["field"], "disks");
+
+    let created = fixture
+        .ctx
+        .post(
+            &items_path,
+            &json!({"kind_id": vinyl_id, "name": "Revolver", "soft_fields": {"disks": 2}}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    let item_id = created.body["id"].as_str().unwrap();
+
+    // A loan cannot be due back before it was made
+    let response = fixture
+        .ctx
+        .patch(
+            &format!("{}/{}", items_path, item_id),
+            &json!({
+                "state": "loaned",
+                "loan_loaned_to": "Ringo",
+                "loan_date_loaned": "2026-03-10",
+                "loan_date_due_back": "2026-03-01"
+            }),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.body["fields"][0]["field"], "loan_date_due_back");
+}
+
 #[tokio::test]
 async fn test_update_item() {
     let fixture = TestFixture::new().await;
@@ -181,6 +281,7 @@ async fn test_delete_item() {
         )
         .await;
     assert_eq!(get_response.status, StatusCode::NOT_FOUND);
+    assert_eq!(get_response.body["error"], "item.not_found");
 }
 
 #[tokio::test]
@@ -264,6 +365,165 @@ async fn test_list_items_with_pagination() {
     assert_eq!(page2.body["items"].as_array().unwrap().len(), 5);
 }
 
+/// Ids of every item in org1 under `query`, walking the list with cursors
+/// `per_page` at a time
+async fn walk_with_cursors(fixture: &TestFixture, query: &str, per_page: usize) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut after: Option<String> = None;
+    loop {
+        let mut path = format!(
+            "/api/organizations/{}/items?{}&per_page={}",
+            fixture.org1_id, query, per_page
+        );
+        if let Some(cursor) = &after {
+            path.push_str(&format!("&after={}", cursor));
+        }
+        let page = fixture.ctx.get(&path, Some(&fixture.user1_token)).await;
+        page.assert_success();
+        let items = page.body["items"].as_array().unwrap();
+        assert!(items.len() <= per_page);
+        ids.extend(items.iter().map(|i| i["id"].as_str().unwrap().to_string()));
+        match page.body["next_cursor"].as_str() {
+            Some(cursor) => after = Some(cursor.to_string()),
+            None => return ids,
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_list_items_with_cursor() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+    let shelf = fixture
+        .ctx
+        .post(
+            &format!("{}/locations", org_path),
+            &json!({"name": "Shelf A"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    shelf.assert_success();
+
+    // Repeated names and missing locations make ties and NULLs to page across
+    for (i, name) in ["Same", "Same", "Same", "Alpha", "Zulu", "Mid", "Mid"]
+        .iter()
+        .enumerate()
+    {
+        let location = (i % 2 == 0).then(|| shelf.body["id"].clone());
+        fixture
+            .ctx
+            .post(
+                &format!("{}/items", org_path),
+                &json!({"kind_id": BOOK_KIND_ID, "name": name, "location_id": location}),
+                Some(&fixture.user1_token),
+            )
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+
+    for query in [
+        "sort_by=name",
+        "sort_by=name&sort_order=desc",
+        "sort_by=location_id",
+        "sort_by=location_id&sort_order=desc",
+        "sort_by=created_at&sort_order=desc",
+        "sort_by=state",
+    ] {
+        let all = fixture
+            .ctx
+            .get(
+                &format!("{}/items?{}&per_page=100", org_path, query),
+                Some(&fixture.user1_token),
+            )
+            .await;
+        all.assert_success();
+        assert!(all.body["next_cursor"].is_null());
+        let expected: Vec<String> = all.body["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|i| i["id"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(expected.len(), 7);
+        for per_page in [1, 2, 3] {
+            assert_eq!(
+                walk_with_cursors(&fixture, query, per_page).await,
+                expected,
+                "{} by {}",
+                query,
+                per_page
+            );
+        }
+    }
+
+    // Offset pages hand out a cursor too, to switch over from
+    let page = fixture
+        .ctx
+        .get(
+            &format!("{}/items?sort_by=name&per_page=2&page=2", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    page.assert_success();
+    let cursor = page.body["next_cursor"].as_str().unwrap().to_string();
+    let next = fixture
+        .ctx
+        .get(
+            &format!(
+                "{}/items?sort_by=name&per_page=2&after={}",
+                org_path, cursor
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    let offset_next = fixture
+        .ctx
+        .get(
+            &format!("{}/items?sort_by=name&per_page=2&page=3", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    assert_eq!(next.body["items"], offset_next.body["items"]);
+
+    // Well-formed cursors whose value does not fit the sort column
+    let crafted = |sort: &str, value: &str| {
+        URL_SAFE_NO_PAD.encode(
+            json!({"sort": sort, "desc": false, "value": value, "id": Uuid::new_v4()}).to_string(),
+        )
+    };
+    let bad_date = format!("sort_by=created_at&after={}", crafted("created_at", "soon"));
+    let bad_location = format!(
+        "sort_by=location_id&after={}",
+        crafted("location_id", "shelf")
+    );
+    let bad_state = format!("sort_by=state&after={}", crafted("state", "lost"));
+
+    for (query, message) in [
+        ("sort_by=name&after=garbage", "Invalid cursor"),
+        (&bad_date as &str, "Invalid cursor"),
+        (&bad_location, "Invalid cursor"),
+        (&bad_state, "Invalid cursor"),
+        (
+            &format!("sort_by=kind&after={}", cursor) as &str,
+            "The cursor is for a different sort order",
+        ),
+        (
+            &format!("search=same&after={}", cursor),
+            "Cursors cannot page search results ordered by relevance; set sort_by",
+        ),
+    ] {
+        let response = fixture
+            .ctx
+            .get(
+                &format!("{}/items?{}", org_path, query),
+                Some(&fixture.user1_token),
+            )
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        assert_eq!(response.body["message"], message);
+    }
+}
+
 #[tokio::test]
 async fn test_filter_items_by_kind() {
     let fixture = TestFixture::new().await;
@@ -305,18 +565,1706 @@ async fn test_filter_items_by_kind() {
 }
 
 #[tokio::test]
-async fn test_create_item_without_authentication() {
+async fn test_filter_items_by_barcode() {
     let fixture = TestFixture::new().await;
-    let book_id = Uuid::parse_str(BOOK_KIND_ID).unwrap();
+    let cd_id = Uuid::parse_str(CD_KIND_ID).unwrap();
+
+    for (name, barcode) in [
+        ("Kind of Blue", "5099706424420"),
+        ("Blue Train", "0724349534026"),
+    ] {
+        fixture
+            .ctx
+            .post(
+                &format!("/api/organizations/{}/items", fixture.org1_id),
+                &json!({"kind_id": cd_id, "name": name, "barcode": barcode}),
+                Some(&fixture.user1_token),
+            )
+            .await
+            .assert_success();
+    }
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items?barcode=5099706424420",
+                fixture.org1_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+
+    response.assert_success();
+    let items = response.body["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["name"], "Kind of Blue");
+    assert_eq!(items[0]["barcode"], "5099706424420");
+
+    // Exact match only: a prefix finds nothing
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/items?barcode=50997", fixture.org1_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+
+    response.assert_success();
+    assert_eq!(response.body["total"], 0);
+}
+
+#[tokio::test]
+async fn test_filter_items_by_catalog_code() {
+    let fixture = TestFixture::new().await;
+    let cd_id = Uuid::parse_str(CD_KIND_ID).unwrap();
+
+    for (name, catalog_code) in [("Blue Train", "BLP 1577"), ("Kind of Blue", "CL 1355")] {
+        fixture
+            .ctx
+            .post(
+                &format!("/api/organizations/{}/items", fixture.org1_id),
+                &json!({"kind_id": cd_id, "name": name, "catalog_code": catalog_code}),
+                Some(&fixture.user1_token),
+            )
+            .await
+            .assert_success();
+    }
+
+    // Case is ignored, as are surrounding spaces
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items?catalog_code=%20blp%201577",
+                fixture.org1_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+
+    response.assert_success();
+    let items = response.body["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["name"], "Blue Train");
 
     let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items?catalog_code=BLP",
+                fixture.org1_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+
+    response.assert_success();
+    assert_eq!(response.body["total"], 0);
+}
+
+#[tokio::test]
+async fn test_barcode_unique_per_org() {
+    let fixture = TestFixture::new().await;
+    let cd_id = Uuid::parse_str(CD_KIND_ID).unwrap();
+    let item = json!({"kind_id": cd_id, "name": "Kind of Blue", "barcode": "5099706424420"});
+
+    fixture
         .ctx
         .post(
             &format!("/api/organizations/{}/items", fixture.org1_id),
-            &json!({"kind_id": book_id, "name": "Unauthorized"}),
-            None,
+            &item,
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+
+    // Same barcode in the same org is rejected
+    let duplicate = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            &item,
+            Some(&fixture.user1_token),
         )
         .await;
+    duplicate.assert_status(StatusCode::CONFLICT);
+    assert_eq!(duplicate.body["error"], "item.duplicate_barcode");
 
-    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+    // Another org may hold the same barcode
+    fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org2_id),
+            &item,
+            Some(&fixture.user3_token),
+        )
+        .await
+        .assert_success();
+
+    // Updating another item onto a taken barcode is rejected too
+    let other = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            &json!({"kind_id": cd_id, "name": "Blue Train", "catalog_code": "BLP 1577"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    other.assert_success();
+    assert_eq!(other.body["catalog_code"], "BLP 1577");
+    assert!(other.body["barcode"].is_null());
+    let other_id = other.body["id"].as_str().unwrap();
+
+    fixture
+        .ctx
+        .patch(
+            &format!("/api/organizations/{}/items/{}", fixture.org1_id, other_id),
+            &json!({"barcode": "5099706424420"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::CONFLICT);
+
+    let updated = fixture
+        .ctx
+        .patch(
+            &format!("/api/organizations/{}/items/{}", fixture.org1_id, other_id),
+            &json!({"barcode": "0724349534026"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    updated.assert_success();
+    assert_eq!(updated.body["barcode"], "0724349534026");
+
+    // An empty string clears the barcode
+    let cleared = fixture
+        .ctx
+        .patch(
+            &format!("/api/organizations/{}/items/{}", fixture.org1_id, other_id),
+            &json!({"barcode": ""}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    cleared.assert_success();
+    assert!(cleared.body["barcode"].is_null());
+}
+
+#[tokio::test]
+async fn test_negative_dimensions_rejected() {
+    let fixture = TestFixture::new().await;
+    let vinyl_id = Uuid::parse_str(VINYL_KIND_ID).unwrap();
+
+    let response = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            &json!({"kind_id": vinyl_id, "name": "Bad", "depth_mm": -3}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.body["error"], "item.invalid_measurement");
+}
+
+#[tokio::test]
+async fn test_bulk_create_items() {
+    let fixture = TestFixture::new().await;
+    let book_id = Uuid::parse_str(BOOK_KIND_ID).unwrap();
+    let cd_id = Uuid::parse_str(CD_KIND_ID).unwrap();
+    let path = format!("/api/organizations/{}/items/bulk", fixture.org1_id);
+    let count_items = || async {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM items WHERE organization_id = $1")
+            .bind(fixture.org1_id)
+            .fetch_one(&fixture.ctx.pool)
+            .await
+            .unwrap()
+    };
+
+    let created = fixture
+        .ctx
+        .post(
+            &path,
+            &json!({"items": [
+                {"kind_id": book_id, "name": "Dune"},
+                {"kind_id": cd_id, "name": "Kind of Blue", "barcode": "5099706424420"},
+            ]}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    let items = created.body.as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["name"], "Dune");
+    assert_eq!(items[1]["kind_name"], "cd");
+    assert!(items[1]["warnings"].as_array().unwrap().is_empty());
+    assert_eq!(count_items().await, 2);
+
+    // One bad row rejects the whole batch, naming the row
+    let rejected = fixture
+        .ctx
+        .post(
+            &path,
+            &json!({"items": [
+                {"kind_id": book_id, "name": "Emma"},
+                {"kind_id": Uuid::new_v4(), "name": "Mystery"},
+            ]}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    rejected.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(rejected.body["message"], "Item 2: Kind not found");
+    assert_eq!(count_items().await, 2);
+
+    let duplicate = fixture
+        .ctx
+        .post(
+            &path,
+            &json!({"items": [{"kind_id": cd_id, "name": "Again", "barcode": "5099706424420"}]}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    duplicate.assert_status(StatusCode::CONFLICT);
+    assert_eq!(duplicate.body["error"], "item.duplicate_barcode");
+
+    fixture
+        .ctx
+        .post(&path, &json!({"items": []}), Some(&fixture.user1_token))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    let too_many: Vec<_> = (0..101)
+        .map(|i| json!({"kind_id": book_id, "name": format!("Book {}", i)}))
+        .collect();
+    fixture
+        .ctx
+        .post(
+            &path,
+            &json!({"items": too_many}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(count_items().await, 2);
+}
+
+#[tokio::test]
+async fn test_bulk_update_items() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+    let path = format!("{}/items/bulk-update", org_path);
+    let create = |body: serde_json::Value, what: &'static str| {
+        let fixture = &fixture;
+        let path = format!("{}/{}", org_path, what);
+        async move {
+            let response = fixture
+                .ctx
+                .post(&path, &body, Some(&fixture.user2_token))
+                .await;
+            response.assert_status(StatusCode::CREATED);
+            response.body["id"].as_str().unwrap().to_string()
+        }
+    };
+    let shelf = create(json!({"name": "New shelf"}), "locations").await;
+    let mut records = Vec::new();
+    for name in ["Blue", "Hejira", "Court and Spark"] {
+        records.push(
+            create(
+                json!({"kind_id": VINYL_KIND_ID, "name": name, "tags": ["folk", "unsorted"]}),
+                "items",
+            )
+            .await,
+        );
+    }
+    let untouched = create(json!({"kind_id": VINYL_KIND_ID, "name": "Mingus"}), "items").await;
+    fixture
+        .ctx
+        .patch(
+            &format!("{}/items/{}", org_path, records[2]),
+            &json!({"state": "loaned", "loan_date_loaned": "2026-01-02", "loan_loaned_to": "Carol"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+
+    let response = fixture
+        .ctx
+        .post(
+            &path,
+            &json!({
+                "item_ids": [records[1], records[0], records[2], records[0]],
+                "location_id": shelf,
+                "state": "current",
+                "add_tags": ["shelved"],
+                "remove_tags": ["unsorted"]
+            }),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_success();
+    let items = response.body.as_array().unwrap();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0]["name"], "Hejira");
+    assert!(items.iter().all(|i| i["location_id"] == shelf.as_str()));
+    assert!(items.iter().all(|i| i["state"] == "current"));
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "{}/items?location_id={}&tags=shelved&include=tags",
+                org_path, shelf
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    assert_eq!(response.body["total"], 3);
+    assert_eq!(
+        response.body["items"][0]["tags"],
+        json!(["folk", "shelved"])
+    );
+
+    // The returned loan is closed, and each item records the change
+    let open_loans: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM item_loans WHERE date_returned IS NULL")
+            .fetch_one(&fixture.ctx.pool)
+            .await
+            .unwrap();
+    assert_eq!(open_loans, 0);
+    let (action, changes): (String, serde_json::Value) = sqlx::query_as(
+        "SELECT action, changes FROM audit_log WHERE item_id = $1 ORDER BY change_date DESC, id DESC LIMIT 1",
+    )
+    .bind(Uuid::parse_str(&records[0]).unwrap())
+    .fetch_one(&fixture.ctx.pool)
+    .await
+    .unwrap();
+    assert_eq!(action, "updated");
+    assert_eq!(changes["location_id"]["to"], shelf.as_str());
+    assert_eq!(
+        changes["tags"],
+        json!({"from": ["folk", "unsorted"], "to": ["folk", "shelved"]})
+    );
+    let action: String = sqlx::query_scalar(
+        "SELECT action FROM audit_log WHERE item_id = $1 ORDER BY change_date DESC, id DESC LIMIT 1",
+    )
+    .bind(Uuid::parse_str(&records[2]).unwrap())
+    .fetch_one(&fixture.ctx.pool)
+    .await
+    .unwrap();
+    assert_eq!(action, "state_changed");
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/items/{}", org_path, untouched),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    assert!(response.body["location_id"].is_null());
+}
+
+#[tokio::test]
+async fn test_bulk_update_items_is_all_or_nothing() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+    let path = format!("{}/items/bulk-update", org_path);
+    let created = fixture
+        .ctx
+        .post(
+            &format!("{}/items", org_path),
+            &json!({"kind_id": CD_KIND_ID, "name": "Blue"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    let item_id = created.body["id"].as_str().unwrap().to_string();
+    let update = |body: serde_json::Value, token: &str| {
+        let fixture = &fixture;
+        let path = path.clone();
+        let token = token.to_string();
+        async move { fixture.ctx.post(&path, &body, Some(&token)).await }
+    };
+    let user1 = fixture.user1_token.clone();
+
+    // An unknown item stops the whole change
+    let response = update(
+        json!({"item_ids": [item_id, Uuid::new_v4()], "state": "missing"}),
+        &user1,
+    )
+    .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "item.not_found");
+
+    for body in [
+        json!({"item_ids": [], "state": "missing"}),
+        json!({"item_ids": [item_id]}),
+        json!({"item_ids": [item_id], "state": "loaned"}),
+        json!({"item_ids": [item_id], "location_id": Uuid::new_v4()}),
+        json!({"item_ids": [item_id], "add_tags": ["jazz"], "remove_tags": ["jazz"]}),
+    ] {
+        update(body, &user1)
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    // Only members with catalog:write may create tags along the way
+    let response = update(json!({"item_ids": [item_id], "add_tags": ["jazz"]}), &user1).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    // Members of other organizations cannot change them
+    let response = update(
+        json!({"item_ids": [item_id], "state": "missing"}),
+        &fixture.user3_token,
+    )
+    .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/items/{}", org_path, item_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    assert_eq!(response.body["state"], "current");
+}
+
+#[tokio::test]
+async fn test_create_item_with_tags() {
+    let fixture = TestFixture::new().await;
+    let book_id = Uuid::parse_str(BOOK_KIND_ID).unwrap();
+    let items_path = format!("/api/organizations/{}/items", fixture.org1_id);
+    let tags_of = |id: String| {
+        let path = format!("{}/{}/full", items_path, id);
+        let fixture = &fixture;
+        async move {
+            let full = fixture.ctx.get(&path, Some(&fixture.user1_token)).await;
+            full.assert_success();
+            let mut tags: Vec<String> = full.body["tags"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|t| t.as_str().unwrap().to_string())
+                .collect();
+            tags.sort();
+            tags
+        }
+    };
+    fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/tags", fixture.org1_id),
+            &json!({"name": "scifi"}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    // An admin's new tags are created along the way
+    let created = fixture
+        .ctx
+        .post(
+            &items_path,
+            &json!({"kind_id": book_id, "name": "Dune", "tags": ["scifi", " to-read ", "scifi"]}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    let id = created.body["id"].as_str().unwrap().to_string();
+    assert_eq!(tags_of(id).await, ["scifi", "to-read"]);
+
+    // A member without catalog:write may only use existing tags
+    let created = fixture
+        .ctx
+        .post(
+            &items_path,
+            &json!({"kind_id": book_id, "name": "Emma", "tags": ["to-read"]}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    let id = created.body["id"].as_str().unwrap().to_string();
+    assert_eq!(tags_of(id).await, ["to-read"]);
+
+    let denied = fixture
+        .ctx
+        .post(
+            &format!("{}/bulk", items_path),
+            &json!({"items": [{"kind_id": book_id, "name": "Persuasion", "tags": ["classics"]}]}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    denied.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(
+        denied.body["message"],
+        "Item 1: The catalog:write permission is required to create tags: classics"
+    );
+
+    fixture
+        .ctx
+        .post(
+            &items_path,
+            &json!({"kind_id": book_id, "name": "Persuasion", "tags": [""]}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_rotation_skips_recently_used_items() {
+    let fixture = TestFixture::new().await;
+    let book_id = Uuid::parse_str(BOOK_KIND_ID).unwrap();
+    let items = format!("/api/organizations/{}/items", fixture.org1_id);
+    let created = fixture
+        .ctx
+        .post(
+            &format!("{}/bulk", items),
+            &json!({"items": [
+                {"kind_id": book_id, "name": "Dune"},
+                {"kind_id": book_id, "name": "Emma"},
+                {"kind_id": book_id, "name": "Ulysses"},
+            ]}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    let ids: Vec<String> = created
+        .body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["id"].as_str().unwrap().to_string())
+        .collect();
+    assert!(created.body[0]["last_used_at"].is_null());
+    let suggested = |query: &'static str| {
+        let items = items.clone();
+        let fixture = &fixture;
+        async move {
+            let response = fixture
+                .ctx
+                .get(
+                    &format!("{}/rotation{}", items, query),
+                    Some(&fixture.user1_token),
+                )
+                .await;
+            response.assert_success();
+            let mut names: Vec<String> = response
+                .body
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|item| item["name"].as_str().unwrap().to_string())
+                .collect();
+            names.sort();
+            names
+        }
+    };
+
+    let used = fixture
+        .ctx
+        .post(
+            &format!("{}/{}/used", items, ids[0]),
+            &json!({}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    used.assert_success();
+    assert!(used.body["last_used_at"].is_string());
+
+    // Only current items not used recently are suggested
+    sqlx::query("UPDATE items SET state = 'missing'::item_state WHERE id = $1")
+        .bind(Uuid::parse_str(&ids[2]).unwrap())
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(suggested("").await, vec!["Emma"]);
+    assert_eq!(suggested("?min_days=0").await, vec!["Dune", "Emma"]);
+    assert_eq!(suggested("?min_days=0&count=1").await.len(), 1);
+    assert!(suggested("?kind=cd").await.is_empty());
+
+    // Another organization's items cannot be marked
+    fixture
+        .ctx
+        .post(
+            &format!(
+                "/api/organizations/{}/items/{}/used",
+                fixture.org2_id, ids[1]
+            ),
+            &json!({}),
+            Some(&fixture.user3_token),
+        )
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_log_item_usage() {
+    let fixture = TestFixture::new().await;
+    let book_id = Uuid::parse_str(BOOK_KIND_ID).unwrap();
+    let created = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            &json!({"kind_id": book_id, "name": "Dune"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    let item = format!(
+        "/api/organizations/{}/items/{}",
+        fixture.org1_id,
+        created.body["id"].as_str().unwrap()
+    );
+    let log_use = |body: serde_json::Value| {
+        let item = item.clone();
+        let fixture = &fixture;
+        async move {
+            fixture
+                .ctx
+                .post(&format!("{}/used", item), &body, Some(&fixture.user1_token))
+                .await
+        }
+    };
+
+    let now = log_use(json!({})).await;
+    now.assert_success();
+    let last_used_at = now.body["last_used_at"].clone();
+
+    // An earlier use is logged without moving last_used_at back
+    let earlier =
+        log_use(json!({"used_at": "2020-05-01T20:00:00Z", "note": "  Read aloud  "})).await;
+    earlier.assert_success();
+    assert_eq!(earlier.body["last_used_at"], last_used_at);
+    log_use(json!({"used_at": "2999-01-01T00:00:00Z"}))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let usage = fixture
+        .ctx
+        .get(&format!("{}/usage", item), Some(&fixture.user2_token))
+        .await;
+    usage.assert_success();
+    assert_eq!(usage.body["total"], 2);
+    let entries = usage.body["items"].as_array().unwrap();
+    assert_eq!(entries[0]["used_at"], last_used_at);
+    assert_eq!(entries[1]["note"], "Read aloud");
+    assert_eq!(entries[1]["user_name"], "User One");
+
+    let full = fixture
+        .ctx
+        .get(&format!("{}/full", item), Some(&fixture.user1_token))
+        .await;
+    assert_eq!(full.body["usage"].as_array().unwrap().len(), 2);
+
+    // Uses are not item changes
+    let history = fixture
+        .ctx
+        .get(&format!("{}/history", item), Some(&fixture.user1_token))
+        .await;
+    assert_eq!(history.body["total"], 1);
+}
+
+#[tokio::test]
+async fn test_suspicious_values_saved_with_warnings() {
+    let fixture = TestFixture::new().await;
+    let vinyl_id = Uuid::parse_str(VINYL_KIND_ID).unwrap();
+    let tomorrow = chrono::Local::now().date_naive() + chrono::Duration::days(1);
+
+    let created = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            &json!({"kind_id": vinyl_id, "name": "Giant Steps", "soft_fields": {"disks": 1}}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    assert_eq!(created.body["warnings"], json!([]));
+    let item_id = created.body["id"].as_str().unwrap();
+
+    // The update is saved; the warnings only flag it
+    let updated = fixture
+        .ctx
+        .patch(
+            &format!("/api/organizations/{}/items/{}", fixture.org1_id, item_id),
+            &json!({"date_acquired": tomorrow, "soft_fields": {"disks": 0}}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    updated.assert_success();
+    assert_eq!(updated.body["soft_fields"]["disks"], 0);
+    assert_eq!(
+        updated.body["warnings"],
+        json!(["date_acquired is in the future", "disks=0 looks wrong"])
+    );
+}
+
+#[tokio::test]
+async fn test_create_item_without_authentication() {
+    let fixture = TestFixture::new().await;
+    let book_id = Uuid::parse_str(BOOK_KIND_ID).unwrap();
+
+    let response = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            &json!({"kind_id": book_id, "name": "Unauthorized"}),
+            None,
+        )
+        .await;
+
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_item_details_follow_state() {
+    let fixture = TestFixture::new().await;
+    let book_id = Uuid::parse_str(BOOK_KIND_ID).unwrap();
+
+    let created = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            &json!({"kind_id": book_id, "name": "Dune"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    let item = format!(
+        "/api/organizations/{}/items/{}",
+        fixture.org1_id,
+        created.body["id"].as_str().unwrap()
+    );
+    let details = || async {
+        let response = fixture
+            .ctx
+            .get(&format!("{}/details", item), Some(&fixture.user1_token))
+            .await;
+        response.assert_success();
+        response.body
+    };
+
+    let body = details().await;
+    assert_eq!(body["item"]["name"], "Dune");
+    assert_eq!(body["item"]["kind_name"], "book");
+    assert_eq!(body["missing_details"], json!(null));
+
+    fixture
+        .ctx
+        .patch(
+            &item,
+            &json!({"state": "missing", "missing_date_missing": "2026-01-02"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+    let body = details().await;
+    assert_eq!(body["missing_details"]["date_missing"], "2026-01-02");
+    assert_eq!(body["loan_details"], json!(null));
+
+    // Only the details of the current state are returned
+    fixture
+        .ctx
+        .patch(
+            &item,
+            &json!({"state": "disposed", "disposed_date_disposed": "2026-02-03"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+    let body = details().await;
+    assert_eq!(body["item"]["state"], "disposed");
+    assert_eq!(body["disposed_details"]["date_disposed"], "2026-02-03");
+    assert_eq!(body["missing_details"], json!(null));
+
+    // Not visible from another organization's path
+    fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items/{}/details",
+                fixture.org2_id,
+                created.body["id"].as_str().unwrap()
+            ),
+            Some(&fixture.user3_token),
+        )
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_batch_item_details() {
+    let fixture = TestFixture::new().await;
+    let book_id = Uuid::parse_str(BOOK_KIND_ID).unwrap();
+    let create = |org_id: Uuid, name: &'static str, token: &str| {
+        let token = token.to_string();
+        let fixture = &fixture;
+        async move {
+            let created = fixture
+                .ctx
+                .post(
+                    &format!("/api/organizations/{}/items", org_id),
+                    &json!({"kind_id": book_id, "name": name}),
+                    Some(&token),
+                )
+                .await;
+            created.assert_status(StatusCode::CREATED);
+            created.body["id"].as_str().unwrap().to_string()
+        }
+    };
+    let dune = create(fixture.org1_id, "Dune", &fixture.user1_token).await;
+    let emma = create(fixture.org1_id, "Emma", &fixture.user1_token).await;
+    let other_org = create(fixture.org2_id, "Ulysses", &fixture.user3_token).await;
+    fixture
+        .ctx
+        .patch(
+            &format!("/api/organizations/{}/items/{}", fixture.org1_id, emma),
+            &json!({"state": "missing", "missing_date_missing": "2026-01-02"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+
+    let path = format!("/api/organizations/{}/items/details", fixture.org1_id);
+    let response = fixture
+        .ctx
+        .post(
+            &path,
+            &json!({"item_ids": [emma, Uuid::new_v4(), other_org, dune, emma]}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    let details = response.body.as_array().unwrap();
+    let names: Vec<&str> = details
+        .iter()
+        .map(|d| d["item"]["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Emma", "Dune"]);
+    assert_eq!(details[0]["missing_details"]["date_missing"], "2026-01-02");
+    assert_eq!(details[1]["missing_details"], json!(null));
+
+    let too_many: Vec<Uuid> = (0..101).map(|_| Uuid::new_v4()).collect();
+    fixture
+        .ctx
+        .post(
+            &path,
+            &json!({ "item_ids": too_many }),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_list_items_field_selection_and_include() {
+    let fixture = TestFixture::new().await;
+    let book_id = Uuid::parse_str(BOOK_KIND_ID).unwrap();
+
+    let created = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            &json!({"kind_id": book_id, "name": "Dune", "notes": "A very long note"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    let item_id: Uuid = created.body["id"].as_str().unwrap().parse().unwrap();
+
+    fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/tags", fixture.org1_id),
+            &json!({"name": "scifi"}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_success();
+    sqlx::query(
+        "INSERT INTO item_tags (item_id, organization_id, tag_name) VALUES ($1, $2, 'scifi')",
+    )
+    .bind(item_id)
+    .bind(fixture.org1_id)
+    .execute(&fixture.ctx.pool)
+    .await
+    .unwrap();
+    let collection = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/collections", fixture.org1_id),
+            &json!({"name": "Arrakis"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    collection.assert_status(StatusCode::CREATED);
+    fixture
+        .ctx
+        .post(
+            &format!(
+                "/api/organizations/{}/collections/{}/items",
+                fixture.org1_id,
+                collection.body["id"].as_str().unwrap()
+            ),
+            &json!({"item_id": item_id}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    fixture
+        .ctx
+        .patch(
+            &format!("/api/organizations/{}/items/{}", fixture.org1_id, item_id),
+            &json!({"state": "missing", "missing_date_missing": "2026-01-02"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+
+    // Slim payload: only the requested fields, plus id
+    let slim = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items?fields=name,state",
+                fixture.org1_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    slim.assert_success();
+    assert_eq!(
+        slim.body["items"][0],
+        json!({"id": item_id, "name": "Dune", "state": "missing"})
+    );
+
+    // Fat payload: full item plus details, tags and collections
+    let fat = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items?include=details,tags,collections",
+                fixture.org1_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    fat.assert_success();
+    let item = &fat.body["items"][0];
+    assert_eq!(item["notes"], "A very long note");
+    assert_eq!(item["tags"], json!(["scifi"]));
+    assert_eq!(item["missing_details"]["date_missing"], "2026-01-02");
+    assert_eq!(item["loan_details"], json!(null));
+    assert_eq!(item["collections"][0]["name"], "Arrakis");
+    assert_eq!(item["collections"][0]["id"], collection.body["id"]);
+
+    let bad = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items?fields=name,password",
+                fixture.org1_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    bad.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(bad.body["error"], "validation.failed");
+}
+
+#[tokio::test]
+async fn test_get_item_full() {
+    let fixture = TestFixture::new().await;
+    let book_id = Uuid::parse_str(BOOK_KIND_ID).unwrap();
+
+    let created = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            &json!({"kind_id": book_id, "name": "Neuromancer"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    let item_id: Uuid = created.body["id"].as_str().unwrap().parse().unwrap();
+
+    let collection = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/collections", fixture.org1_id),
+            &json!({"name": "Cyberpunk"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    collection.assert_success();
+    let collection_id: Uuid = collection.body["id"].as_str().unwrap().parse().unwrap();
+    sqlx::query("INSERT INTO item_collections (item_id, collection_id) VALUES ($1, $2)")
+        .bind(item_id)
+        .bind(collection_id)
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    for (days_ago, details) in [(2, "Created"), (1, "Moved to shelf")] {
+        sqlx::query(
+            "INSERT INTO audit_log (item_id, organization_id, change_date, change_details)
+             VALUES ($1, $2, NOW() - make_interval(days => $3), $4)",
+        )
+        .bind(item_id)
+        .bind(fixture.org1_id)
+        .bind(days_ago)
+        .bind(details)
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    }
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items/{}/full",
+                fixture.org1_id, item_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["item"]["name"], "Neuromancer");
+    assert_eq!(response.body["loan_details"], json!(null));
+    assert_eq!(response.body["tags"], json!([]));
+    assert_eq!(response.body["collections"][0]["name"], "Cyberpunk");
+    // Newest first, starting with the entry recorded by the create above
+    assert_eq!(
+        response.body["history"][0]["change_details"],
+        "Created 'Neuromancer'"
+    );
+    assert_eq!(
+        response.body["history"][1]["change_details"],
+        "Moved to shelf"
+    );
+    assert_eq!(response.body["history"][2]["change_details"], "Created");
+
+    // Not visible from another organization
+    let other = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items/{}/full",
+                fixture.org2_id, item_id
+            ),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    other.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_list_items_filtered_by_tags() {
+    let fixture = TestFixture::new().await;
+    let cd_id = Uuid::parse_str(CD_KIND_ID).unwrap();
+    let items_path = format!("/api/organizations/{}/items", fixture.org1_id);
+
+    for tag in ["jazz", "signed", "rare"] {
+        fixture
+            .ctx
+            .post(
+                &format!("/api/organizations/{}/tags", fixture.org1_id),
+                &json!({"name": tag}),
+                Some(&fixture.user2_token),
+            )
+            .await
+            .assert_success();
+    }
+
+    for (name, tags) in [
+        ("Kind of Blue", vec!["jazz", "signed"]),
+        ("A Love Supreme", vec!["jazz"]),
+        ("Nevermind", vec!["signed", "rare"]),
+        ("Untagged", vec![]),
+    ] {
+        let created = fixture
+            .ctx
+            .post(
+                &items_path,
+                &json!({"kind_id": cd_id, "name": name}),
+                Some(&fixture.user1_token),
+            )
+            .await;
+        created.assert_status(StatusCode::CREATED);
+        let item_id: Uuid = created.body["id"].as_str().unwrap().parse().unwrap();
+        for tag in tags {
+            sqlx::query(
+                "INSERT INTO item_tags (item_id, organization_id, tag_name) VALUES ($1, $2, $3)",
+            )
+            .bind(item_id)
+            .bind(fixture.org1_id)
+            .bind(tag)
+            .execute(&fixture.ctx.pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    let names = |body: &serde_json::Value| -> Vec<String> {
+        body["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|i| i["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // Any of the tags (default)
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}?tags=jazz,rare", items_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["total"], 3);
+    assert_eq!(
+        names(&response.body),
+        vec!["A Love Supreme", "Kind of Blue", "Nevermind"]
+    );
+
+    // All of the tags
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}?tags=jazz,signed&tag_match=all", items_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["total"], 1);
+    assert_eq!(names(&response.body), vec!["Kind of Blue"]);
+
+    // Combined with other filters
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}?tags=signed&search=never", items_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(names(&response.body), vec!["Nevermind"]);
+}
+
+/// Names of the items a search finds, in the order returned
+async fn search_names(fixture: &TestFixture, query: &str) -> Vec<String> {
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items?search={}",
+                fixture.org1_id, query
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    response.body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_list_items_search_ranked() {
+    let fixture = TestFixture::new().await;
+    let cd_id = Uuid::parse_str(CD_KIND_ID).unwrap();
+    let items_path = format!("/api/organizations/{}/items", fixture.org1_id);
+
+    for item in [
+        json!({"kind_id": cd_id, "name": "Somethin' Else", "notes": "Has a blue cover"}),
+        json!({"kind_id": cd_id, "name": "Moanin'", "description": "Blue Note reissue"}),
+        json!({"kind_id": cd_id, "name": "Kind of Blue"}),
+        json!({"kind_id": cd_id, "name": "Giant Steps"}),
+    ] {
+        fixture
+            .ctx
+            .post(&items_path, &item, Some(&fixture.user1_token))
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+
+    // Name matches rank above description, then notes
+    assert_eq!(
+        search_names(&fixture, "BLUE").await,
+        vec!["Kind of Blue", "Moanin'", "Somethin' Else"]
+    );
+
+    // Every word must match, each as a word prefix
+    assert_eq!(
+        search_names(&fixture, "kin%20blu").await,
+        vec!["Kind of Blue"]
+    );
+    assert_eq!(
+        search_names(&fixture, "blue%20steps").await,
+        Vec::<String>::new()
+    );
+
+    // Query syntax in the input is treated as plain text
+    assert_eq!(
+        search_names(&fixture, "giant%20%26%20!").await,
+        vec!["Giant Steps"]
+    );
+
+    // An explicit sort overrides the ranking
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}?search=blue&sort_by=name&sort_order=desc", items_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["total"], 3);
+    assert_eq!(response.body["items"][0]["name"], "Somethin' Else");
+}
+
+#[tokio::test]
+async fn test_item_history_records_changes() {
+    let fixture = TestFixture::new().await;
+    let items_path = format!("/api/organizations/{}/items", fixture.org1_id);
+
+    let created = fixture
+        .ctx
+        .post(
+            &items_path,
+            &json!({"kind_id": CD_KIND_ID, "name": "Blue"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    let item_id = created.body["id"].as_str().unwrap().to_string();
+    let item_path = format!("{}/{}", items_path, item_id);
+
+    // Edited by one member, lent out by another
+    fixture
+        .ctx
+        .patch(
+            &item_path,
+            &json!({"notes": "Signed copy"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+    fixture
+        .ctx
+        .patch(
+            &item_path,
+            &json!({"state": "loaned", "loan_loaned_to": "Alice"}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_success();
+    // An update that changes nothing leaves no entry
+    fixture
+        .ctx
+        .patch(
+            &item_path,
+            &json!({"notes": "Signed copy"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+    fixture
+        .ctx
+        .delete(&item_path, Some(&fixture.user2_token))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    // History outlives the item
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/history", item_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["total"], 4);
+    let entries = response.body["items"].as_array().unwrap();
+    let actions: Vec<&str> = entries
+        .iter()
+        .map(|e| e["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        actions,
+        vec!["deleted", "state_changed", "updated", "created"]
+    );
+
+    let (deleted, loaned, edited, created) = (&entries[0], &entries[1], &entries[2], &entries[3]);
+    assert_eq!(created["user_id"], fixture.user1_id.to_string());
+    assert_eq!(created["change_details"], "Created 'Blue'");
+    assert_eq!(
+        created["changes"]["name"],
+        json!({"from": null, "to": "Blue"})
+    );
+
+    assert_eq!(
+        edited["changes"],
+        json!({"notes": {"from": null, "to": "Signed copy"}})
+    );
+    assert_eq!(edited["change_details"], "Updated notes");
+
+    assert_ne!(loaned["user_id"], fixture.user1_id.to_string());
+    assert!(loaned["user_name"].is_string());
+    assert_eq!(
+        loaned["change_details"],
+        "State changed from current to loaned"
+    );
+    assert_eq!(
+        loaned["changes"]["state"],
+        json!({"from": "current", "to": "loaned"})
+    );
+    assert_eq!(loaned["changes"]["loan_loaned_to"], json!({"to": "Alice"}));
+
+    assert_eq!(deleted["change_details"], "Deleted 'Blue'");
+    assert_eq!(deleted["changes"]["notes"]["from"], "Signed copy");
+
+    // Not visible from another organization
+    let other = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items/{}/history",
+                fixture.org2_id, item_id
+            ),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    other.assert_success();
+    assert_eq!(other.body["total"], 0);
+}
+
+#[tokio::test]
+async fn test_item_facets_follow_filters() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+
+    let shelf = fixture
+        .ctx
+        .post(
+            &format!("{}/locations", org_path),
+            &json!({"name": "Shelf A"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    shelf.assert_success();
+    let shelf_id = shelf.body["id"].as_str().unwrap().to_string();
+
+    for tag in ["jazz", "signed"] {
+        fixture
+            .ctx
+            .post(
+                &format!("{}/tags", org_path),
+                &json!({"name": tag}),
+                Some(&fixture.user2_token),
+            )
+            .await
+            .assert_success();
+    }
+
+    for (name, kind_id, location, tags) in [
+        (
+            "Kind of Blue",
+            CD_KIND_ID,
+            Some(&shelf_id),
+            vec!["jazz", "signed"],
+        ),
+        ("A Love Supreme", CD_KIND_ID, None, vec!["jazz"]),
+        ("Blue Train", VINYL_KIND_ID, Some(&shelf_id), vec![]),
+    ] {
+        let created = fixture
+            .ctx
+            .post(
+                &format!("{}/items", org_path),
+                &json!({"kind_id": kind_id, "name": name, "location_id": location}),
+                Some(&fixture.user1_token),
+            )
+            .await;
+        created.assert_status(StatusCode::CREATED);
+        let item_id: Uuid = created.body["id"].as_str().unwrap().parse().unwrap();
+        for tag in tags {
+            sqlx::query(
+                "INSERT INTO item_tags (item_id, organization_id, tag_name) VALUES ($1, $2, $3)",
+            )
+            .bind(item_id)
+            .bind(fixture.org1_id)
+            .bind(tag)
+            .execute(&fixture.ctx.pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    let counts = |facet: &serde_json::Value| -> Vec<(String, i64)> {
+        facet
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| {
+                (
+                    f["label"].as_str().unwrap_or("-").to_string(),
+                    f["count"].as_i64().unwrap(),
+                )
+            })
+            .collect()
+    };
+    let pairs = |expected: &[(&str, i64)]| -> Vec<(String, i64)> {
+        expected.iter().map(|(l, c)| (l.to_string(), *c)).collect()
+    };
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/items/facets", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    let facets = &response.body;
+    assert_eq!(facets["total"], 3);
+    assert_eq!(counts(&facets["kinds"]), pairs(&[("cd", 2), ("vinyl", 1)]));
+    assert_eq!(counts(&facets["states"]), pairs(&[("current", 3)]));
+    assert_eq!(
+        counts(&facets["locations"]),
+        pairs(&[("Shelf A", 2), ("-", 1)])
+    );
+    assert_eq!(facets["locations"][0]["value"], shelf_id);
+    assert!(facets["locations"][1]["value"].is_null());
+    assert_eq!(
+        counts(&facets["tags"]),
+        pairs(&[("jazz", 2), ("signed", 1)])
+    );
+
+    // Counts follow the list filters
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/items/facets?location_id={}", org_path, shelf_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    let facets = &response.body;
+    assert_eq!(facets["total"], 2);
+    assert_eq!(counts(&facets["kinds"]), pairs(&[("cd", 1), ("vinyl", 1)]));
+    assert_eq!(
+        counts(&facets["tags"]),
+        pairs(&[("jazz", 1), ("signed", 1)])
+    );
+}
+
+#[tokio::test]
+async fn test_export_items_csv_and_json() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+
+    for (name, kind_id, soft_fields) in [
+        (
+            "Abbey Road",
+            VINYL_KIND_ID,
+            json!({"size": "12_inch", "disks": 2}),
+        ),
+        ("Kind of Blue, Legacy", CD_KIND_ID, json!({})),
+    ] {
+        fixture
+            .ctx
+            .post(
+                &format!("{}/items", org_path),
+                &json!({"kind_id": kind_id, "name": name, "soft_fields": soft_fields}),
+                Some(&fixture.user1_token),
+            )
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+    fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org2_id),
+            &json!({"kind_id": VINYL_KIND_ID, "name": "Other org item"}),
+            Some(&fixture.user3_token),
+        )
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let csv = fixture
+        .ctx
+        .get(
+            &format!("{}/items/export", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    csv.assert_success();
+    assert!(
+        csv.headers[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/csv")
+    );
+    assert!(
+        csv.headers[header::CONTENT_DISPOSITION]
+            .to_str()
+            .unwrap()
+            .contains("vostuff-items-")
+    );
+    let text = csv.body.as_str().unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 3, "header plus two items: {}", text);
+    assert!(lines[0].starts_with("id,name,kind,state,"));
+    assert!(lines[0].ends_with(",field:disks,field:size"));
+    assert!(lines[1].contains(",Abbey Road,vinyl,current,"));
+    assert!(lines[1].ends_with(",2,12_inch"));
+    assert!(lines[2].contains(",\"Kind of Blue, Legacy\",cd,"));
+    assert!(!text.contains("Other org item"));
+
+    // The export follows the item list's sort
+    let csv = fixture
+        .ctx
+        .get(
+            &format!("{}/items/export?sort_by=name&sort_order=desc", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    let text = csv.body.as_str().unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert!(lines[1].contains(",\"Kind of Blue, Legacy\",cd,"));
+    assert!(lines[2].contains(",Abbey Road,vinyl,current,"));
+
+    let json = fixture
+        .ctx
+        .get(
+            &format!("{}/items/export?format=json&kind=vinyl", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    json.assert_success();
+    let items = json.body.as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["name"], "Abbey Road");
+    assert_eq!(items[0]["soft_fields"]["disks"], 2);
+    assert_eq!(items[0]["tags"], json!([]));
+
+    fixture
+        .ctx
+        .get(
+            &format!("{}/items/export?format=xml", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_export_history_for_period() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+
+    let created = fixture
+        .ctx
+        .post(
+            &format!("{}/items", org_path),
+            &json!({"kind_id": VINYL_KIND_ID, "name": "Giant Steps"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    let item_path = format!(
+        "{}/items/{}",
+        org_path,
+        created.body["id"].as_str().unwrap()
+    );
+    fixture
+        .ctx
+        .patch(
+            &item_path,
+            &json!({"notes": "First pressing"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+    fixture
+        .ctx
+        .delete(&item_path, Some(&fixture.user2_token))
+        .await
+        .assert_success();
+
+    let today = chrono::Utc::now().date_naive();
+    let csv = fixture
+        .ctx
+        .get(
+            &format!("{}/history/export?from={}&to={}", org_path, today, today),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    csv.assert_success();
+    assert!(
+        csv.headers[header::CONTENT_DISPOSITION]
+            .to_str()
+            .unwrap()
+            .contains("vostuff-history-")
+    );
+    let text = csv.body.as_str().unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(
+        lines[0],
+        "id,change_date,item_id,item_name,action,user_id,user_name,summary,changes"
+    );
+    assert_eq!(lines.len(), 4, "header plus three changes: {}", text);
+    for (line, action) in lines[1..].iter().zip(["created", "updated", "deleted"]) {
+        assert!(
+            line.contains(&format!(",Giant Steps,{},", action)),
+            "{}",
+            line
+        );
+    }
+
+    let json = fixture
+        .ctx
+        .get(
+            &format!("{}/history/export?format=json", org_path),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    json.assert_success();
+    let entries = json.body.as_array().unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[1]["changes"]["notes"]["to"], "First pressing");
+
+    let tomorrow = today.succ_opt().unwrap();
+    let later = fixture
+        .ctx
+        .get(
+            &format!("{}/history/export?format=json&from={}", org_path, tomorrow),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    later.assert_success();
+    assert_eq!(later.body, json!([]));
+
+    fixture
+        .ctx
+        .get(
+            &format!("{}/history/export?from={}&to={}", org_path, tomorrow, today),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    fixture
+        .ctx
+        .get(
+            &format!("{}/history/export", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
 }
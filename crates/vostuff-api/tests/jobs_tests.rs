@@ -0,0 +1,224 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::json;
+use uuid::Uuid;
+use vostuff_api::api::jobs::{self, Job, JobHandler};
+
+/// Handler that remembers the payloads it ran, optionally failing every run
+struct RecordingHandler {
+    payloads: Mutex<Vec<serde_json::Value>>,
+    fail: bool,
+}
+
+impl RecordingHandler {
+    fn new(fail: bool) -> Arc<Self> {
+        Arc::new(Self {
+            payloads: Mutex::new(Vec::new()),
+            fail,
+        })
+    }
+}
+
+#[async_trait]
+impl JobHandler for RecordingHandler {
+    fn kind(&self) -> &str {
+        "test.record"
+    }
+
+    async fn run(&self, job: &Job) -> anyhow::Result<()> {
+        if self.fail {
+            anyhow::bail!("service unavailable");
+        }
+        self.payloads.lock().unwrap().push(job.payload.clone());
+        Ok(())
+    }
+}
+
+async fn enqueue(fixture: &TestFixture, kind: &str, payload: serde_json::Value) -> Uuid {
+    let mut conn = fixture.ctx.pool.acquire().await.unwrap();
+    jobs::enqueue(&mut conn, kind, Some(fixture.org1_id), payload)
+        .await
+        .unwrap()
+}
+
+async fn load(fixture: &TestFixture, id: Uuid) -> Job {
+    sqlx::query_as::<_, Job>("SELECT * FROM jobs WHERE id = $1")
+        .bind(id)
+        .fetch_one(&fixture.ctx.pool)
+        .await
+        .unwrap()
+}
+
+/// Make a pending job due now instead of after its backoff
+async fn make_due(fixture: &TestFixture, id: Uuid) {
+    sqlx::query("UPDATE jobs SET run_at = NOW() WHERE id = $1")
+        .bind(id)
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_job_runs_once_and_succeeds() {
+    let fixture = TestFixture::new().await;
+    let handler = RecordingHandler::new(false);
+    let handlers: Vec<Arc<dyn JobHandler>> = vec![handler.clone()];
+
+    let id = enqueue(&fixture, "test.record", json!({"n": 1})).await;
+    assert!(jobs::run_next(&fixture.ctx.pool, &handlers).await.unwrap());
+    assert!(!jobs::run_next(&fixture.ctx.pool, &handlers).await.unwrap());
+
+    assert_eq!(*handler.payloads.lock().unwrap(), vec![json!({"n": 1})]);
+    let job = load(&fixture, id).await;
+    assert_eq!(job.status, "succeeded");
+    assert_eq!(job.attempts, 1);
+    assert!(job.finished_at.is_some());
+}
+
+#[tokio::test]
+async fn test_failing_job_is_retried_with_backoff_until_it_fails() {
+    let fixture = TestFixture::new().await;
+    let handlers: Vec<Arc<dyn JobHandler>> = vec![RecordingHandler::new(true)];
+
+    let id = enqueue(&fixture, "test.record", json!({})).await;
+    sqlx::query("UPDATE jobs SET max_attempts = 2 WHERE id = $1")
+        .bind(id)
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+
+    assert!(jobs::run_next(&fixture.ctx.pool, &handlers).await.unwrap());
+    let job = load(&fixture, id).await;
+    assert_eq!(job.status, "pending");
+    assert_eq!(job.attempts, 1);
+    assert_eq!(job.last_error.as_deref(), Some("service unavailable"));
+    assert!(job.run_at > job.started_at.unwrap());
+
+    // Not due again until the backoff has passed
+    assert!(!jobs::run_next(&fixture.ctx.pool, &handlers).await.unwrap());
+
+    make_due(&fixture, id).await;
+    assert!(jobs::run_next(&fixture.ctx.pool, &handlers).await.unwrap());
+    let job = load(&fixture, id).await;
+    assert_eq!(job.status, "failed");
+    assert_eq!(job.attempts, 2);
+    assert!(job.finished_at.is_some());
+
+    // A failed job is not run again
+    make_due(&fixture, id).await;
+    assert!(!jobs::run_next(&fixture.ctx.pool, &handlers).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_job_without_handler_fails() {
+    let fixture = TestFixture::new().await;
+    let handlers: Vec<Arc<dyn JobHandler>> = vec![RecordingHandler::new(false)];
+
+    let id = enqueue(&fixture, "test.unknown", json!({})).await;
+    assert!(jobs::run_next(&fixture.ctx.pool, &handlers).await.unwrap());
+
+    let job = load(&fixture, id).await;
+    assert_eq!(job.status, "pending");
+    assert_eq!(
+        job.last_error.as_deref(),
+        Some("no handler for job kind test.unknown")
+    );
+}
+
+#[tokio::test]
+async fn test_abandoned_job_is_requeued() {
+    let fixture = TestFixture::new().await;
+
+    let id = enqueue(&fixture, "test.record", json!({})).await;
+    sqlx::query(
+        "UPDATE jobs SET status = 'running', attempts = 1, started_at = NOW() - INTERVAL '1 hour'
+         WHERE id = $1",
+    )
+    .bind(id)
+    .execute(&fixture.ctx.pool)
+    .await
+    .unwrap();
+
+    assert_eq!(jobs::requeue_abandoned(&fixture.ctx.pool).await.unwrap(), 1);
+    let job = load(&fixture, id).await;
+    assert_eq!(job.status, "pending");
+
+    let handlers: Vec<Arc<dyn JobHandler>> = vec![RecordingHandler::new(false)];
+    assert!(jobs::run_next(&fixture.ctx.pool, &handlers).await.unwrap());
+    assert_eq!(load(&fixture, id).await.status, "succeeded");
+}
+
+#[tokio::test]
+async fn test_admin_lists_and_gets_jobs() {
+    let fixture = TestFixture::new().await;
+    let admin = fixture.ctx.sysadmin_token().await;
+    let handlers: Vec<Arc<dyn JobHandler>> = vec![RecordingHandler::new(false)];
+
+    let done = enqueue(&fixture, "test.record", json!({"n": 1})).await;
+    assert!(jobs::run_next(&fixture.ctx.pool, &handlers).await.unwrap());
+    let queued = enqueue(&fixture, "test.other", json!({"n": 2})).await;
+
+    let response = fixture.ctx.get("/api/admin/jobs", Some(&admin)).await;
+    response.assert_success();
+    let ids: Vec<&str> = response
+        .body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|j| j["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec![queued.to_string(), done.to_string()]);
+
+    let response = fixture
+        .ctx
+        .get("/api/admin/jobs?status=succeeded", Some(&admin))
+        .await;
+    response.assert_success();
+    assert_eq!(response.body.as_array().unwrap().len(), 1);
+    assert_eq!(response.body[0]["id"], done.to_string());
+
+    let response = fixture
+        .ctx
+        .get("/api/admin/jobs?kind=test.other", Some(&admin))
+        .await;
+    response.assert_success();
+    assert_eq!(response.body.as_array().unwrap().len(), 1);
+    assert_eq!(response.body[0]["payload"]["n"], 2);
+
+    fixture
+        .ctx
+        .get("/api/admin/jobs?status=stuck", Some(&admin))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let response = fixture
+        .ctx
+        .get(&format!("/api/admin/jobs/{}", done), Some(&admin))
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["status"], "succeeded");
+    assert_eq!(response.body["kind"], "test.record");
+
+    let response = fixture
+        .ctx
+        .get(&format!("/api/admin/jobs/{}", Uuid::new_v4()), Some(&admin))
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "job.not_found");
+}
+
+#[tokio::test]
+async fn test_jobs_require_system_admin() {
+    let fixture = TestFixture::new().await;
+
+    fixture
+        .ctx
+        .get("/api/admin/jobs", Some(&fixture.user2_token))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
@@ -8,6 +8,8 @@ use uuid::Uuid;
 // Fixed UUIDs from seed migration
 const VINYL_KIND_ID: &str = "00000000-0000-0000-0000-000000000001";
 const BOOK_KIND_ID: &str = "00000000-0000-0000-0000-000000000004";
+const VIDEO_GAME_KIND_ID: &str = "00000000-0000-0000-0000-000000000009";
+const BLURAY_KIND_ID: &str = "00000000-0000-0000-0000-00000000000a";
 const SIZE_FIELD_ID: &str = "00000000-0000-0000-0001-000000000001";
 
 // ── Test helpers ─────────────────────────────────────────────────────────────
@@ -107,6 +109,133 @@ async fn test_get_shared_kind_with_fields() {
     assert!(!evs.is_empty(), "size field should have enum values");
 }
 
+#[tokio::test]
+async fn test_video_game_kind_validates_its_fields() {
+    let f = TestFixture::new().await;
+    let kind_id = Uuid::parse_str(VIDEO_GAME_KIND_ID).unwrap();
+
+    let res = f
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/kinds/{}", f.org1_id, kind_id),
+            Some(&f.user2_token),
+        )
+        .await;
+    res.assert_success();
+    assert_eq!(res.body["name"], "video_game");
+    let names: Vec<&str> = res.body["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["platform", "region", "completeness", "condition"]);
+
+    let item_id = create_item(
+        &f,
+        kind_id,
+        "Super Metroid",
+        json!({"platform": "snes", "region": "pal", "completeness": "complete", "condition": "good"}),
+    )
+    .await;
+    let res = f
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/items/{}", f.org1_id, item_id),
+            Some(&f.user2_token),
+        )
+        .await;
+    assert_eq!(res.body["soft_fields"]["platform"], "snes");
+
+    let res = f
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", f.org1_id),
+            &json!({"kind_id": kind_id, "name": "Halo", "soft_fields": {"platform": "dreamcast_2"}}),
+            Some(&f.user2_token),
+        )
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_bluray_kind_shares_the_disks_field() {
+    let f = TestFixture::new().await;
+    let kind_id = Uuid::parse_str(BLURAY_KIND_ID).unwrap();
+
+    let item_id = create_item(
+        &f,
+        kind_id,
+        "Arrival",
+        json!({"disc_format": "uhd_bluray", "disks": 2, "disc_region": "b", "edition": "Steelbook"}),
+    )
+    .await;
+    let res = f
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/items/{}", f.org1_id, item_id),
+            Some(&f.user2_token),
+        )
+        .await;
+    assert_eq!(res.body["kind_name"], "bluray");
+    assert_eq!(res.body["soft_fields"]["disks"], 2);
+    assert_eq!(res.body["soft_fields"]["edition"], "Steelbook");
+
+    // Blu-ray regions are letters, not the video game ones
+    let res = f
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", f.org1_id),
+            &json!({"kind_id": kind_id, "name": "Dune", "soft_fields": {"disc_region": "pal"}}),
+            Some(&f.user2_token),
+        )
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_book_details_are_set_and_updated() {
+    let f = TestFixture::new().await;
+    let book_id = Uuid::parse_str(BOOK_KIND_ID).unwrap();
+
+    let item_id = create_item(
+        &f,
+        book_id,
+        "Dune",
+        json!({"author": "Frank Herbert", "isbn": "9780441172719", "book_format": "paperback"}),
+    )
+    .await;
+    let item_path = format!("/api/organizations/{}/items/{}", f.org1_id, item_id);
+    f.ctx
+        .patch(
+            &item_path,
+            &json!({"soft_fields": {"publisher": "Ace", "year": 1990}}),
+            Some(&f.user2_token),
+        )
+        .await
+        .assert_success();
+
+    let res = f
+        .ctx
+        .get(&format!("{}/details", item_path), Some(&f.user2_token))
+        .await;
+    res.assert_success();
+    let details = &res.body["item"]["soft_fields"];
+    assert_eq!(details["author"], "Frank Herbert");
+    assert_eq!(details["publisher"], "Ace");
+    assert_eq!(details["year"], 1990);
+
+    let res = f
+        .ctx
+        .patch(
+            &item_path,
+            &json!({"soft_fields": {"book_format": "scroll"}}),
+            Some(&f.user2_token),
+        )
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_get_kind_not_found() {
     let f = TestFixture::new().await;
@@ -125,7 +254,7 @@ async fn test_get_kind_not_found() {
 #[tokio::test]
 async fn test_create_kind_with_fields() {
     let f = TestFixture::new().await;
-    let field_id = create_field(&f, "author", "string").await;
+    let field_id = create_field(&f, "editor", "string").await;
     let size_id = Uuid::parse_str(SIZE_FIELD_ID).unwrap();
 
     let res = f
@@ -165,7 +294,7 @@ async fn test_create_kind_name_conflicts_with_shared() {
         .await;
 
     res.assert_status(StatusCode::CONFLICT);
-    assert_eq!(res.body["error"], "name_conflict");
+    assert_eq!(res.body["error"], "kind.name_conflict");
 }
 
 #[tokio::test]
@@ -251,9 +380,15 @@ async fn test_update_kind_remove_field_without_data_succeeds() {
 #[tokio::test]
 async fn test_update_kind_remove_field_with_data_blocked_without_force() {
     let f = TestFixture::new().await;
-    let field_id = create_field(&f, "publisher", "string").await;
+    let field_id = create_field(&f, "series", "string").await;
     let kind_id = create_kind(&f, "books2", &[field_id]).await;
-    create_item(&f, kind_id, "My Book", json!({"publisher": "Penguin"})).await;
+    create_item(
+        &f,
+        kind_id,
+        "My Book",
+        json!({"series": "Penguin Classics"}),
+    )
+    .await;
 
     // Try to remove the field without force
     let res = f
@@ -269,7 +404,7 @@ async fn test_update_kind_remove_field_with_data_blocked_without_force() {
         .await;
 
     res.assert_status(StatusCode::CONFLICT);
-    assert_eq!(res.body["error"], "data_loss_required");
+    assert_eq!(res.body["error"], "kind.data_loss_required");
 }
 
 #[tokio::test]
@@ -367,7 +502,7 @@ async fn test_delete_kind_with_items_is_blocked() {
         .await;
 
     res.assert_status(StatusCode::CONFLICT);
-    assert_eq!(res.body["error"], "kind_in_use");
+    assert_eq!(res.body["error"], "kind.in_use");
 }
 
 #[tokio::test]
@@ -410,7 +545,7 @@ async fn test_override_kind_creates_org_copy() {
 
     // Fields should be copied
     let fields = res.body["fields"].as_array().unwrap();
-    let vinyl_fields_count = 6; // size, speed, channels, disks, media_grading, sleeve_grading
+    let vinyl_fields_count = 7; // size, speed, channels, disks, media_grading, sleeve_grading, pressing
     assert_eq!(fields.len(), vinyl_fields_count);
 }
 
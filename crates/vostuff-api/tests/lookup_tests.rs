@@ -0,0 +1,256 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::json;
+use vostuff_api::api::lookup::{Metadata, MetadataProvider};
+
+const CD_KIND_ID: &str = "00000000-0000-0000-0000-000000000002";
+const BOOK_KIND_ID: &str = "00000000-0000-0000-0000-000000000004";
+
+/// Provider that knows one code and remembers what it was asked
+struct FakeProvider {
+    name: &'static str,
+    kind: &'static str,
+    code: &'static str,
+    found: Metadata,
+    asked: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl MetadataProvider for FakeProvider {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn supports(&self, kind: &str) -> bool {
+        kind == self.kind
+    }
+
+    async fn lookup(&self, code: &str) -> anyhow::Result<Option<Metadata>> {
+        self.asked.lock().unwrap().push(code.to_string());
+        Ok((code == self.code).then(|| self.found.clone()))
+    }
+}
+
+/// Provider whose service is down
+struct BrokenProvider;
+
+#[async_trait]
+impl MetadataProvider for BrokenProvider {
+    fn name(&self) -> &str {
+        "broken"
+    }
+
+    fn supports(&self, _kind: &str) -> bool {
+        true
+    }
+
+    async fn lookup(&self, _code: &str) -> anyhow::Result<Option<Metadata>> {
+        anyhow::bail!("connection refused")
+    }
+}
+
+/// A fixture whose lookups go to a fake record shop and a fake library
+fn with_fake_providers(fixture: &mut TestFixture) -> (Arc<FakeProvider>, Arc<FakeProvider>) {
+    let music = Arc::new(FakeProvider {
+        name: "music",
+        kind: "cd",
+        code: "5099706424420",
+        found: Metadata {
+            title: Some("Kind of Blue".into()),
+            description: Some("Miles Davis - Kind of Blue (1959)".into()),
+            catalog_code: Some("CK 64935".into()),
+            kind: Some("cd".into()),
+            soft_fields: json!({"disks": 1}).as_object().unwrap().clone(),
+            source_url: Some("https://example.com/release/1".into()),
+        },
+        asked: Mutex::new(Vec::new()),
+    });
+    let books = Arc::new(FakeProvider {
+        name: "books",
+        kind: "book",
+        code: "9780441172719",
+        found: Metadata {
+            title: Some("Dune".into()),
+            description: Some("Frank Herbert - Dune (1965)".into()),
+            kind: Some("book".into()),
+            // Not a field of the book kind, so left out
+            soft_fields: json!({"author": "Frank Herbert", "year": 1965, "pages": 412})
+                .as_object()
+                .unwrap()
+                .clone(),
+            ..Default::default()
+        },
+        asked: Mutex::new(Vec::new()),
+    });
+    let state = fixture
+        .ctx
+        .state
+        .clone()
+        .with_metadata_providers(vec![music.clone(), books.clone()]);
+    fixture.ctx.set_state(state);
+    (music, books)
+}
+
+#[tokio::test]
+async fn test_isbn_lookup_prefills_a_book() {
+    let mut fixture = TestFixture::new().await;
+    let (music, _) = with_fake_providers(&mut fixture);
+    let path = |isbn: &str| {
+        format!(
+            "/api/organizations/{}/lookup/isbn/{}",
+            fixture.org1_id, isbn
+        )
+    };
+
+    let response = fixture
+        .ctx
+        .get(&path("978-0-441-17271-9"), Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["provider"], "books");
+    let item = &response.body["item"];
+    assert_eq!(item["kind_id"], BOOK_KIND_ID);
+    assert_eq!(item["name"], "Dune");
+    assert_eq!(item["barcode"], "9780441172719");
+    assert_eq!(
+        item["soft_fields"],
+        json!({"author": "Frank Herbert", "year": 1965})
+    );
+    // Only services covering books are asked
+    assert!(music.asked.lock().unwrap().is_empty());
+
+    // The prefilled item can be created as it is
+    let response = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            item,
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CREATED);
+
+    let response = fixture
+        .ctx
+        .get(&path("9780441172710"), Some(&fixture.user1_token))
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let response = fixture
+        .ctx
+        .get(&path("0441172717"), Some(&fixture.user1_token))
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "item.lookup_not_found");
+}
+
+#[tokio::test]
+async fn test_barcode_lookup_asks_services_in_turn() {
+    let mut fixture = TestFixture::new().await;
+    let (music, books) = with_fake_providers(&mut fixture);
+    let path = |code: &str| {
+        format!(
+            "/api/organizations/{}/lookup/barcode/{}",
+            fixture.org1_id, code
+        )
+    };
+
+    let response = fixture
+        .ctx
+        .get(&path("5099706424420"), Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["provider"], "music");
+    assert_eq!(response.body["source_url"], "https://example.com/release/1");
+    let item = &response.body["item"];
+    assert_eq!(item["kind_id"], CD_KIND_ID);
+    assert_eq!(item["catalog_code"], "CK 64935");
+    assert_eq!(item["soft_fields"], json!({"disks": 1}));
+    assert!(books.asked.lock().unwrap().is_empty());
+
+    // A book's barcode goes straight to the library
+    let response = fixture
+        .ctx
+        .get(&path("9780441172719"), Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["provider"], "books");
+    assert_eq!(music.asked.lock().unwrap().len(), 1);
+
+    // Unknown codes are asked of every service
+    let response = fixture
+        .ctx
+        .get(&path("0000000000000"), Some(&fixture.user1_token))
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(music.asked.lock().unwrap().len(), 2);
+    assert_eq!(books.asked.lock().unwrap().len(), 2);
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}?kind=electronics", path("5099706424420")),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let response = fixture
+        .ctx
+        .get(&path("ABC123"), Some(&fixture.user1_token))
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_lookup_passes_over_failing_services() {
+    let mut fixture = TestFixture::new().await;
+    let (music, _) = with_fake_providers(&mut fixture);
+    let path = format!(
+        "/api/organizations/{}/lookup/barcode/5099706424420",
+        fixture.org1_id
+    );
+
+    let state = fixture
+        .ctx
+        .state
+        .clone()
+        .with_metadata_providers(vec![Arc::new(BrokenProvider), music]);
+    fixture.ctx.set_state(state);
+    let response = fixture.ctx.get(&path, Some(&fixture.user1_token)).await;
+    response.assert_success();
+    assert_eq!(response.body["provider"], "music");
+
+    // Only when every service fails is the lookup unavailable
+    let state = fixture
+        .ctx
+        .state
+        .clone()
+        .with_metadata_providers(vec![Arc::new(BrokenProvider)]);
+    fixture.ctx.set_state(state);
+    let response = fixture.ctx.get(&path, Some(&fixture.user1_token)).await;
+    response.assert_status(StatusCode::BAD_GATEWAY);
+    assert_eq!(response.body["error"], "item.lookup_unavailable");
+}
+
+#[tokio::test]
+async fn test_lookup_needs_a_member_of_the_organization() {
+    let mut fixture = TestFixture::new().await;
+    with_fake_providers(&mut fixture);
+    let path = format!(
+        "/api/organizations/{}/lookup/barcode/5099706424420",
+        fixture.org1_id
+    );
+
+    let response = fixture.ctx.get(&path, None).await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+
+    // Another organization's member cannot use this one's lookup
+    let response = fixture.ctx.get(&path, Some(&fixture.user3_token)).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestFixture;
+use vostuff_api::api::metrics;
+
+#[tokio::test]
+async fn test_metrics_disabled_by_default() {
+    let mut fixture = TestFixture::new().await;
+    // Rebuild with the metrics router included, as the server does
+    let state = fixture.ctx.state.clone();
+    fixture.ctx.set_state(state);
+
+    fixture
+        .ctx
+        .get("/metrics", None)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_metrics_count_requests_by_route() {
+    let mut fixture = TestFixture::new().await;
+    let state = fixture
+        .ctx
+        .state
+        .clone()
+        .with_metrics(metrics::install_recorder());
+    fixture.ctx.set_state(state);
+
+    let items_path = format!("/api/organizations/{}/items", fixture.org1_id);
+    fixture
+        .ctx
+        .get(&items_path, Some(&fixture.user1_token))
+        .await
+        .assert_success();
+    fixture
+        .ctx
+        .get(&items_path, None)
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    let response = fixture.ctx.get("/metrics", None).await;
+    response.assert_success();
+    let body = response.body.as_str().unwrap();
+
+    // Labelled by route pattern, not the org id in the path
+    let route = r#"route="/api/organizations/:org_id/items""#;
+    for status in ["200", "401"] {
+        assert!(
+            body.lines()
+                .any(|line| line.starts_with("http_requests_total{")
+                    && line.contains(route)
+                    && line.contains(&format!(r#"status="{}""#, status))),
+            "no {} count for the items route in:\n{}",
+            status,
+            body
+        );
+    }
+    assert!(!body.contains(&fixture.org1_id.to_string()));
+    assert!(body.contains("http_request_duration_seconds_bucket{"));
+    assert!(body.contains("db_pool_connections "));
+    assert!(body.contains("db_pool_idle_connections "));
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_page_edits_are_versioned() {
+    let fixture = TestFixture::new().await;
+    let base = format!("/api/organizations/{}/pages", fixture.org1_id);
+
+    let response = fixture
+        .ctx
+        .post(
+            &base,
+            &json!({"title": " How we grade vinyl ", "body": "Goldmine scale."}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    assert_eq!(response.body["title"], "How we grade vinyl");
+    assert_eq!(response.body["version"], 1);
+    assert_eq!(response.body["updated_by_name"], "User One");
+    let page_url = format!("{}/{}", base, response.body["id"].as_str().unwrap());
+
+    // Another member edits the first version
+    let response = fixture
+        .ctx
+        .patch(
+            &page_url,
+            &json!({"version": 1, "body": "Goldmine scale, sleeve and disc separately."}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["version"], 2);
+    assert_eq!(response.body["title"], "How we grade vinyl");
+    assert_eq!(response.body["updated_by_name"], "User Two");
+
+    // An edit based on the old version is refused rather than lost
+    let response = fixture
+        .ctx
+        .patch(
+            &page_url,
+            &json!({"version": 1, "body": "Overwrite"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+    assert_eq!(response.body["error"], "page.version_conflict");
+
+    // Saving unchanged text makes no new version
+    let response = fixture
+        .ctx
+        .patch(
+            &page_url,
+            &json!({"version": 2, "title": "How we grade vinyl"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["version"], 2);
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/versions", page_url),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    let versions = response.body.as_array().unwrap();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0]["version"], 2);
+    assert_eq!(versions[1]["body"], "Goldmine scale.");
+    assert_eq!(versions[1]["edited_by_name"], "User One");
+
+    let response = fixture.ctx.get(&base, Some(&fixture.user1_token)).await;
+    response.assert_success();
+    assert_eq!(response.body[0]["title"], "How we grade vinyl");
+    assert!(response.body[0].get("body").is_none());
+}
+
+#[tokio::test]
+async fn test_page_titles_and_permissions() {
+    let fixture = TestFixture::new().await;
+    let base = format!("/api/organizations/{}/pages", fixture.org1_id);
+
+    let response = fixture
+        .ctx
+        .post(
+            &base,
+            &json!({"title": "Shelving map"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let page_id = response.body["id"].as_str().unwrap().to_string();
+    let page_url = format!("{}/{}", base, page_id);
+
+    // Titles are unique per organization, ignoring case
+    let response = fixture
+        .ctx
+        .post(
+            &base,
+            &json!({"title": "SHELVING MAP"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+    assert_eq!(response.body["error"], "page.title_conflict");
+
+    let response = fixture
+        .ctx
+        .post(&base, &json!({"title": "  "}), Some(&fixture.user1_token))
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    // Pages are not visible through another organization
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/pages/{}", fixture.org2_id, page_id),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "page.not_found");
+
+    // Only admins delete
+    let response = fixture
+        .ctx
+        .delete(&page_url, Some(&fixture.user1_token))
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    let response = fixture
+        .ctx
+        .delete(&page_url, Some(&fixture.user2_token))
+        .await;
+    response.assert_status(StatusCode::NO_CONTENT);
+    let response = fixture.ctx.get(&page_url, Some(&fixture.user1_token)).await;
+    response.assert_status(StatusCode::NOT_FOUND);
+}
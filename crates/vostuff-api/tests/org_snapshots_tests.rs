@@ -0,0 +1,456 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+// Fixed UUIDs from seed migration
+const BOOK_KIND_ID: &str = "00000000-0000-0000-0000-000000000004";
+
+/// POST to org1 as its admin, returning the created record's ID
+async fn create(fixture: &TestFixture, path: &str, body: Value) -> String {
+    let response = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/{}", fixture.org1_id, path),
+            &body,
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    response.body["id"].as_str().unwrap().to_string()
+}
+
+/// Give org1 a little of everything a snapshot holds
+async fn fill_org1(fixture: &TestFixture) {
+    let location_id = create(fixture, "locations", json!({"name": "Games cupboard"})).await;
+    let field_id = create(
+        fixture,
+        "fields",
+        json!({"name": "players", "field_type": "enum",
+               "enum_values": [{"value": "two", "sort_order": 0}, {"value": "many", "sort_order": 1}]}),
+    )
+    .await;
+    let kind_id = create(
+        fixture,
+        "kinds",
+        json!({"name": "boardgame", "field_ids": [field_id]}),
+    )
+    .await;
+    let chess_id = create(
+        fixture,
+        "items",
+        json!({"kind_id": kind_id, "name": "Chess", "location_id": location_id,
+               "soft_fields": {"players": "two"}, "tags": ["classic"]}),
+    )
+    .await;
+    let dune_id = create(
+        fixture,
+        "items",
+        json!({"kind_id": BOOK_KIND_ID, "name": "Dune", "soft_fields": {"author": "Frank Herbert"}}),
+    )
+    .await;
+    let collection_id = create(fixture, "collections", json!({"name": "Favourites"})).await;
+    for item_id in [&chess_id, &dune_id] {
+        fixture
+            .ctx
+            .post(
+                &format!(
+                    "/api/organizations/{}/collections/{}/items",
+                    fixture.org1_id, collection_id
+                ),
+                &json!({"item_id": item_id}),
+                Some(&fixture.user2_token),
+            )
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+    }
+    fixture
+        .ctx
+        .patch(
+            &format!(
+                "/api/organizations/{}/collections/{}",
+                fixture.org1_id, collection_id
+            ),
+            &json!({"cover_item_id": chess_id}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_success();
+    fixture
+        .ctx
+        .patch(
+            &format!("/api/organizations/{}/items/{}", fixture.org1_id, dune_id),
+            &json!({"state": "loaned", "loan_date_loaned": "2026-01-02", "loan_loaned_to": "Carol"}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_success();
+    create(
+        fixture,
+        "pages",
+        json!({"title": "House rules", "body": "No peeking."}),
+    )
+    .await;
+}
+
+async fn export(fixture: &TestFixture, token: &str) -> Value {
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/admin/organizations/{}/export", fixture.org1_id),
+            Some(token),
+        )
+        .await;
+    response.assert_success();
+    response.body
+}
+
+async fn count(fixture: &TestFixture, query: &str, org_id: Uuid) -> i64 {
+    sqlx::query_scalar(query)
+        .bind(org_id)
+        .fetch_one(&fixture.ctx.pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_export_and_import_copies_organization_with_new_ids() {
+    let fixture = TestFixture::new().await;
+    let token = fixture.ctx.sysadmin_token().await;
+    fill_org1(&fixture).await;
+
+    let snapshot = export(&fixture, &token).await;
+    assert_eq!(snapshot["snapshot_type"], "organization");
+    assert_eq!(snapshot["organization"]["name"], "Test Org 1");
+    assert_eq!(snapshot["items"].as_array().unwrap().len(), 2);
+    assert_eq!(snapshot["kinds"][0]["name"], "boardgame");
+    assert_eq!(snapshot["item_loans"].as_array().unwrap().len(), 1);
+    // Users stay behind
+    assert!(snapshot.get("members").is_none());
+    assert!(snapshot["items"][0].get("search_vector").is_none());
+
+    // Imported twice into the same instance: each copy is separate
+    let mut org_ids = Vec::new();
+    for _ in 0..2 {
+        let response = fixture
+            .ctx
+            .post("/api/admin/organizations/import", &snapshot, Some(&token))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        assert_eq!(response.body["name"], "Test Org 1");
+        org_ids.push(Uuid::parse_str(response.body["id"].as_str().unwrap()).unwrap());
+    }
+    assert_ne!(org_ids[0], org_ids[1]);
+    assert_ne!(org_ids[0], fixture.org1_id);
+
+    let org_id = org_ids[0];
+    let (name, players, location, kind, kind_org): (String, Value, String, String, Option<Uuid>) =
+        sqlx::query_as(
+            "SELECT i.name, i.soft_fields->'players', l.name, k.name, k.org_id
+             FROM items i JOIN locations l ON l.id = i.location_id JOIN kinds k ON k.id = i.kind_id
+             WHERE i.organization_id = $1 AND i.name = 'Chess'",
+        )
+        .bind(org_id)
+        .fetch_one(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        (name.as_str(), location.as_str(), kind.as_str()),
+        ("Chess", "Games cupboard", "boardgame")
+    );
+    assert_eq!(players, "two");
+    assert_eq!(kind_org, Some(org_id));
+
+    // The shared book kind is kept; the loan follows the new item and borrower
+    let (kind_id, state, borrower): (Uuid, String, String) = sqlx::query_as(
+        "SELECT i.kind_id, i.state::text, b.name
+         FROM items i
+         JOIN item_loans il ON il.item_id = i.id AND il.organization_id = i.organization_id
+         JOIN borrowers b ON b.id = il.borrower_id AND b.organization_id = i.organization_id
+         WHERE i.organization_id = $1 AND i.name = 'Dune'",
+    )
+    .bind(org_id)
+    .fetch_one(&fixture.ctx.pool)
+    .await
+    .unwrap();
+    assert_eq!(kind_id.to_string(), BOOK_KIND_ID);
+    assert_eq!(state, "loaned");
+    assert_eq!(borrower, "Carol");
+
+    assert_eq!(
+        count(
+            &fixture,
+            "SELECT COUNT(*) FROM item_collections ic JOIN collections c ON c.id = ic.collection_id
+             WHERE c.organization_id = $1",
+            org_id
+        )
+        .await,
+        2
+    );
+    // The cover follows its item
+    assert_eq!(
+        count(
+            &fixture,
+            "SELECT COUNT(*) FROM collections c JOIN items i ON i.id = c.cover_item_id
+             WHERE c.organization_id = $1 AND i.organization_id = $1 AND i.name = 'Chess'",
+            org_id
+        )
+        .await,
+        1
+    );
+    assert_eq!(
+        count(
+            &fixture,
+            "SELECT COUNT(*) FROM item_tags WHERE organization_id = $1 AND tag_name = 'classic'",
+            org_id
+        )
+        .await,
+        1
+    );
+    assert_eq!(
+        count(
+            &fixture,
+            "SELECT COUNT(*) FROM org_page_versions v JOIN org_pages p ON p.id = v.page_id
+             WHERE p.organization_id = $1",
+            org_id
+        )
+        .await,
+        1
+    );
+    assert_eq!(
+        count(
+            &fixture,
+            "SELECT COUNT(*) FROM audit_log WHERE organization_id = $1 AND user_id IS NOT NULL",
+            org_id
+        )
+        .await,
+        0
+    );
+    assert!(
+        count(
+            &fixture,
+            "SELECT COUNT(*) FROM audit_log WHERE organization_id = $1",
+            org_id
+        )
+        .await
+            > 0
+    );
+    assert_eq!(
+        count(
+            &fixture,
+            "SELECT COUNT(*) FROM user_organizations WHERE organization_id = $1",
+            org_id
+        )
+        .await,
+        0
+    );
+
+    // The original is untouched
+    assert_eq!(
+        count(
+            &fixture,
+            "SELECT COUNT(*) FROM items WHERE organization_id = $1",
+            fixture.org1_id
+        )
+        .await,
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_import_rejects_snapshots_that_do_not_fit() {
+    let fixture = TestFixture::new().await;
+    let token = fixture.ctx.sysadmin_token().await;
+    fill_org1(&fixture).await;
+    let snapshot = export(&fixture, &token).await;
+    let import = |body: Value| {
+        let fixture = &fixture;
+        let token = token.clone();
+        async move {
+            fixture
+                .ctx
+                .post("/api/admin/organizations/import", &body, Some(&token))
+                .await
+        }
+    };
+
+    let response = import(json!({"name": "Not a snapshot"})).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let mut future = snapshot.clone();
+    future["format_version"] = json!(2);
+    import(future).await.assert_status(StatusCode::BAD_REQUEST);
+
+    let mut bad_section = snapshot.clone();
+    bad_section["items"] = json!("Chess");
+    import(bad_section)
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    // An item of a kind neither in the snapshot nor shared
+    let mut unknown_kind = snapshot.clone();
+    unknown_kind["items"][0]["kind_id"] = json!(Uuid::new_v4());
+    let response = import(unknown_kind).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert!(
+        response.body["message"]
+            .as_str()
+            .unwrap()
+            .contains("which is not in the snapshot")
+    );
+
+    // A tag on another organization's item
+    let org2_item: Uuid = sqlx::query_scalar(
+        "INSERT INTO items (organization_id, kind_id, name) VALUES ($1, $2, 'Theirs') RETURNING id",
+    )
+    .bind(fixture.org2_id)
+    .bind(Uuid::parse_str(BOOK_KIND_ID).unwrap())
+    .fetch_one(&fixture.ctx.pool)
+    .await
+    .unwrap();
+    let mut foreign_item = snapshot.clone();
+    foreign_item["item_tags"][0]["item_id"] = json!(org2_item);
+    import(foreign_item)
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    // A record that references only snapshot IDs but still does not fit
+    // reports no database details
+    let mut bad_state = snapshot.clone();
+    bad_state["items"][0]["state"] = json!("vanished");
+    let response = import(bad_state).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.body["message"],
+        "The snapshot could not be imported: its records do not fit this instance"
+    );
+
+    // Nothing is left of the failed imports
+    let orgs: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM organizations WHERE name = 'Test Org 1'")
+            .fetch_one(&fixture.ctx.pool)
+            .await
+            .unwrap();
+    assert_eq!(orgs, 1);
+}
+
+#[tokio::test]
+async fn test_import_keeps_records_inside_the_new_organization() {
+    let fixture = TestFixture::new().await;
+    let token = fixture.ctx.sysadmin_token().await;
+    fill_org1(&fixture).await;
+    let snapshot = export(&fixture, &token).await;
+    let import = |body: Value| {
+        let fixture = &fixture;
+        let token = token.clone();
+        async move {
+            fixture
+                .ctx
+                .post("/api/admin/organizations/import", &body, Some(&token))
+                .await
+        }
+    };
+    let shared_field: Uuid =
+        sqlx::query_scalar("SELECT id FROM fields WHERE org_id IS NULL LIMIT 1")
+            .fetch_one(&fixture.ctx.pool)
+            .await
+            .unwrap();
+
+    let shared_rows = || async {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT (SELECT COUNT(*) FROM enum_values WHERE field_id = $1)
+                  + (SELECT COUNT(*) FROM kind_fields WHERE kind_id = $2)",
+        )
+        .bind(shared_field)
+        .bind(Uuid::parse_str(BOOK_KIND_ID).unwrap())
+        .fetch_one(&fixture.ctx.pool)
+        .await
+        .unwrap()
+    };
+    let before = shared_rows().await;
+
+    // Shared fields and kinds may only be used, not changed
+    let mut shared_value = snapshot.clone();
+    shared_value["enum_values"][0]["field_id"] = json!(shared_field);
+    import(shared_value)
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let mut shared_kind = snapshot.clone();
+    shared_kind["kind_fields"][0]["kind_id"] = json!(BOOK_KIND_ID);
+    import(shared_kind)
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    assert_eq!(shared_rows().await, before);
+
+    // Fields and kinds without an owner are given the new organization rather
+    // than becoming shared
+    let mut unowned = snapshot.clone();
+    unowned["fields"][0]
+        .as_object_mut()
+        .unwrap()
+        .remove("org_id");
+    unowned["kinds"][0]
+        .as_object_mut()
+        .unwrap()
+        .remove("org_id");
+    let response = import(unowned).await;
+    response.assert_status(StatusCode::CREATED);
+    let new_org_id = Uuid::parse_str(response.body["id"].as_str().unwrap()).unwrap();
+    for table in ["fields", "kinds"] {
+        assert_eq!(
+            count(
+                &fixture,
+                &format!("SELECT COUNT(*) FROM {} WHERE org_id = $1", table),
+                new_org_id
+            )
+            .await,
+            1,
+            "{}",
+            table
+        );
+    }
+    let shared_names: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM fields WHERE org_id IS NULL AND name = 'players'")
+            .fetch_one(&fixture.ctx.pool)
+            .await
+            .unwrap();
+    assert_eq!(shared_names, 0);
+}
+
+#[tokio::test]
+async fn test_snapshots_need_a_system_admin() {
+    let fixture = TestFixture::new().await;
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/admin/organizations/{}/export", fixture.org1_id),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    let response = fixture
+        .ctx
+        .post(
+            "/api/admin/organizations/import",
+            &json!({}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    let token = fixture.ctx.sysadmin_token().await;
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/admin/organizations/{}/export", Uuid::new_v4()),
+            Some(&token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "organization.not_found");
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::json;
+use uuid::Uuid;
+use vostuff_api::api::outbox::{self, EventSink, OutboxEvent};
+
+// Fixed UUIDs from seed migration
+const VINYL_KIND_ID: &str = "00000000-0000-0000-0000-000000000001";
+
+/// Sink that remembers what it was given, optionally failing every delivery
+#[derive(Default)]
+struct RecordingSink {
+    events: Mutex<Vec<OutboxEvent>>,
+    fail: bool,
+}
+
+#[async_trait]
+impl EventSink for RecordingSink {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    async fn deliver(&self, event: &OutboxEvent) -> anyhow::Result<()> {
+        if self.fail {
+            anyhow::bail!("endpoint unavailable");
+        }
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+async fn create_item(fixture: &TestFixture, name: &str) -> Uuid {
+    fixture
+        .create_item(json!({"kind_id": VINYL_KIND_ID, "name": name}))
+        .await
+}
+
+#[tokio::test]
+async fn test_item_changes_are_delivered_from_outbox() {
+    let fixture = TestFixture::new().await;
+    let item_path = |id: Uuid| format!("/api/organizations/{}/items/{}", fixture.org1_id, id);
+
+    let item_id = create_item(&fixture, "Blue Train").await;
+    fixture
+        .ctx
+        .patch(
+            &item_path(item_id),
+            &json!({"name": "Blue Train (mono)"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+    fixture
+        .ctx
+        .delete(&item_path(item_id), Some(&fixture.user1_token))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let sink = Arc::new(RecordingSink::default());
+    let sinks: Vec<Arc<dyn EventSink>> = vec![sink.clone()];
+    let dispatched = outbox::dispatch_pending(&fixture.ctx.pool, &sinks)
+        .await
+        .unwrap();
+    assert_eq!(dispatched, 3);
+
+    let events = sink.events.lock().unwrap().clone();
+    let types: Vec<_> = events.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(types, ["item.created", "item.updated", "item.deleted"]);
+    assert!(events.iter().all(|e| e.organization_id == fixture.org1_id));
+    assert_eq!(events[1].payload["name"], "Blue Train (mono)");
+    assert_eq!(events[2].payload["id"], item_id.to_string());
+
+    // Dispatched events are not delivered again
+    let dispatched = outbox::dispatch_pending(&fixture.ctx.pool, &sinks)
+        .await
+        .unwrap();
+    assert_eq!(dispatched, 0);
+    assert_eq!(sink.events.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_failed_delivery_is_retried_later() {
+    let fixture = TestFixture::new().await;
+    create_item(&fixture, "Kind of Blue").await;
+
+    let failing: Vec<Arc<dyn EventSink>> = vec![Arc::new(RecordingSink {
+        fail: true,
+        ..Default::default()
+    })];
+    let dispatched = outbox::dispatch_pending(&fixture.ctx.pool, &failing)
+        .await
+        .unwrap();
+    assert_eq!(dispatched, 0);
+
+    let (attempts, last_error, backed_off): (i32, Option<String>, bool) =
+        sqlx::query_as("SELECT attempts, last_error, next_attempt_at > NOW() FROM outbox_events")
+            .fetch_one(&fixture.ctx.pool)
+            .await
+            .unwrap();
+    assert_eq!(attempts, 1);
+    assert_eq!(
+        last_error.as_deref(),
+        Some("recording: endpoint unavailable")
+    );
+    assert!(backed_off);
+
+    // Once the retry is due, a working sink receives the event
+    sqlx::query("UPDATE outbox_events SET next_attempt_at = NOW()")
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    let sink = Arc::new(RecordingSink::default());
+    let sinks: Vec<Arc<dyn EventSink>> = vec![sink.clone()];
+    let dispatched = outbox::dispatch_pending(&fixture.ctx.pool, &sinks)
+        .await
+        .unwrap();
+    assert_eq!(dispatched, 1);
+    assert_eq!(
+        sink.events.lock().unwrap()[0].payload["name"],
+        "Kind of Blue"
+    );
+}
+
+#[tokio::test]
+async fn test_rejected_change_writes_no_event() {
+    let fixture = TestFixture::new().await;
+    let items_path = format!("/api/organizations/{}/items", fixture.org1_id);
+
+    create_item(&fixture, "First").await;
+    let body = json!({
+        "kind_id": Uuid::parse_str(VINYL_KIND_ID).unwrap(),
+        "name": "Second",
+        "barcode": "5012345678900",
+    });
+    fixture
+        .ctx
+        .post(&items_path, &body, Some(&fixture.user1_token))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    // Duplicate barcode fails inside the transaction
+    let response = fixture
+        .ctx
+        .post(&items_path, &body, Some(&fixture.user1_token))
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM outbox_events")
+        .fetch_one(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+}
@@ -0,0 +1,220 @@
+mod common;
+
+use std::io::Write;
+
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::json;
+use uuid::Uuid;
+
+// Fixed UUIDs from seed migration
+const CD_KIND_ID: &str = "00000000-0000-0000-0000-000000000002";
+
+/// A zip archive holding the given files
+fn zip_of(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, data) in files {
+        writer
+            .start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(data).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+async fn create_cd(fixture: &TestFixture, item: serde_json::Value) -> String {
+    let mut item = item;
+    item["kind_id"] = json!(CD_KIND_ID);
+    fixture.create_item(item).await.to_string()
+}
+
+#[tokio::test]
+async fn test_batch_photo_upload_matches_by_barcode_and_catalogue_number() {
+    let fixture = TestFixture::new().await;
+    let blue_train = create_cd(
+        &fixture,
+        json!({"name": "Blue Train", "catalog_code": "BLP 1577"}),
+    )
+    .await;
+    let kind_of_blue = create_cd(
+        &fixture,
+        json!({"name": "Kind of Blue", "barcode": "5099706424420"}),
+    )
+    .await;
+    // Two pressings share a catalogue number
+    for name in ["Abbey Road", "Abbey Road (reissue)"] {
+        create_cd(&fixture, json!({"name": name, "catalog_code": "PCS 7088"})).await;
+    }
+
+    let archive = zip_of(&[
+        ("shelf/blp1577.JPG", b"front"),
+        ("shelf/BLP 1577_back.jpg", b"back"),
+        ("5099706424420.png", b"cover"),
+        ("PCS7088.jpg", b"ambiguous"),
+        ("unknown.jpg", b"nothing"),
+        ("notes.txt", b"not a photo"),
+        ("__MACOSX/._blp1577.JPG", b"resource fork"),
+    ]);
+    let path = format!("/api/organizations/{}/items/photos/import", fixture.org1_id);
+
+    // Members of other organizations may not upload
+    fixture
+        .ctx
+        .post_bytes(
+            &path,
+            "application/zip",
+            archive.clone(),
+            Some(&fixture.user3_token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let response = fixture
+        .ctx
+        .post_bytes(
+            &path,
+            "application/zip",
+            archive,
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+
+    let attached: Vec<(&str, &str)> = response.body["attached"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| {
+            (
+                a["filename"].as_str().unwrap(),
+                a["item_id"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        attached,
+        vec![
+            ("blp1577.JPG", blue_train.as_str()),
+            ("BLP 1577_back.jpg", blue_train.as_str()),
+            ("5099706424420.png", kind_of_blue.as_str()),
+        ]
+    );
+    let unmatched: Vec<(&str, &str)> = response.body["unmatched"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|u| {
+            (
+                u["filename"].as_str().unwrap(),
+                u["reason"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(unmatched.len(), 3);
+    assert_eq!(
+        unmatched[0],
+        ("PCS7088.jpg", "2 items have this catalogue number")
+    );
+    assert_eq!(
+        unmatched[1],
+        (
+            "unknown.jpg",
+            "no item has this barcode or catalogue number"
+        )
+    );
+    assert_eq!(unmatched[2].0, "notes.txt");
+
+    // The photos are listed with links that serve the image
+    let photos_path = format!(
+        "/api/organizations/{}/items/{}/photos",
+        fixture.org1_id, blue_train
+    );
+    let photos = fixture
+        .ctx
+        .get(&photos_path, Some(&fixture.user1_token))
+        .await;
+    photos.assert_success();
+    let photos = photos.body.as_array().unwrap().clone();
+    assert_eq!(photos.len(), 2);
+    assert_eq!(photos[0]["content_type"], "image/jpeg");
+    let link = photos[0]["url"]
+        .as_str()
+        .unwrap()
+        .trim_start_matches("http://localhost:8080");
+    let image = fixture.ctx.get(link, None).await;
+    image.assert_success();
+    assert!(matches!(image.body.as_str(), Some("front" | "back")));
+
+    // Other organizations cannot see them
+    let other_org_path = format!(
+        "/api/organizations/{}/items/{}/photos",
+        fixture.org2_id, blue_train
+    );
+    let response = fixture
+        .ctx
+        .get(&other_org_path, Some(&fixture.user3_token))
+        .await;
+    response.assert_success();
+    assert!(response.body.as_array().unwrap().is_empty());
+
+    // Removing a photo
+    let photo_id = photos[0]["id"].as_str().unwrap();
+    fixture
+        .ctx
+        .delete(
+            &format!("{}/{}", photos_path, photo_id),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    fixture
+        .ctx
+        .get(link, None)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    fixture
+        .ctx
+        .delete(
+            &format!("{}/{}", photos_path, Uuid::new_v4()),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_batch_photo_upload_rejects_non_zip() {
+    let fixture = TestFixture::new().await;
+    let response = fixture
+        .ctx
+        .post_bytes(
+            &format!("/api/organizations/{}/items/photos/import", fixture.org1_id),
+            "application/zip",
+            b"not a zip".to_vec(),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.body["error"], "photo.archive_invalid");
+}
+
+#[tokio::test]
+async fn test_batch_photo_upload_rejects_archive_with_too_many_files() {
+    let fixture = TestFixture::new().await;
+    let names: Vec<String> = (0..1001).map(|n| format!("{}.jpg", n)).collect();
+    let files: Vec<(&str, &[u8])> = names
+        .iter()
+        .map(|name| (name.as_str(), b"jpeg".as_slice()))
+        .collect();
+    let response = fixture
+        .ctx
+        .post_bytes(
+            &format!("/api/organizations/{}/items/photos/import", fixture.org1_id),
+            "application/zip",
+            zip_of(&files),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.body["error"], "photo.archive_invalid");
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use axum::http::{StatusCode, header};
+use common::TestFixture;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_preferences_export_and_import() {
+    let fixture = TestFixture::new().await;
+    let base = format!("/api/organizations/{}/preferences", fixture.org1_id);
+
+    // Defaults before anything is saved
+    let response = fixture.ctx.get(&base, Some(&fixture.user1_token)).await;
+    response.assert_success();
+    assert_eq!(response.body["saved_searches"], json!([]));
+    assert!(response.body["item_columns"].is_null());
+
+    let prefs = json!({
+        "item_columns": ["kind", "name", "location"],
+        "saved_searches": [
+            {"name": "On loan", "query": "state=loaned"},
+            {"name": "Blue records", "query": "kind=vinyl&search=blue"},
+        ],
+        "per_page": 50,
+        "sort_by": "created_at",
+        "sort_order": "desc",
+    });
+    let response = fixture
+        .ctx
+        .put(&base, &prefs, Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+
+    let response = fixture
+        .ctx
+        .get(&format!("{}/export", base), Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    let disposition = response.headers[header::CONTENT_DISPOSITION]
+        .to_str()
+        .unwrap();
+    assert!(disposition.starts_with("attachment; filename=\"vostuff-preferences-"));
+    assert_eq!(response.body["format_version"], 1);
+    assert_eq!(response.body["preferences"], prefs);
+    let file = response.body;
+
+    // Another member takes the file; their own preferences are replaced
+    let response = fixture
+        .ctx
+        .post(
+            &format!("{}/import", base),
+            &file,
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_success();
+    let response = fixture.ctx.get(&base, Some(&fixture.user2_token)).await;
+    assert_eq!(response.body, prefs);
+
+    // Preferences are kept per organization
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/preferences", fixture.org2_id),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["saved_searches"], json!([]));
+
+    // They are part of the user's personal data export
+    let response = fixture
+        .ctx
+        .get("/api/auth/me/data-export", Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["preferences"][0]["preferences"], prefs);
+}
+
+#[tokio::test]
+async fn test_invalid_preferences_rejected() {
+    let fixture = TestFixture::new().await;
+    let base = format!("/api/organizations/{}/preferences", fixture.org1_id);
+
+    for prefs in [
+        json!({"item_columns": ["name", "name"]}),
+        json!({"saved_searches": [{"name": " ", "query": "state=loaned"}]}),
+        json!({"saved_searches": [
+            {"name": "Loans", "query": "state=loaned"},
+            {"name": "loans", "query": "state=missing"},
+        ]}),
+        json!({"per_page": 0}),
+        json!({"sort_by": "password"}),
+        json!({"sort_order": "sideways"}),
+    ] {
+        let response = fixture
+            .ctx
+            .put(&base, &prefs, Some(&fixture.user1_token))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        assert_eq!(response.body["error"], "preference.invalid");
+    }
+
+    // Files from an unknown format version are refused
+    let response = fixture
+        .ctx
+        .post(
+            &format!("{}/import", base),
+            &json!({"format_version": 2, "preferences": {}}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.body["error"], "preference.invalid");
+}
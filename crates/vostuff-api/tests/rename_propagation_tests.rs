@@ -0,0 +1,488 @@
+mod common;
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::{Value, json};
+use uuid::Uuid;
+use vostuff_api::api::{
+    live::LiveSink,
+    outbox::{self, EventSink},
+};
+
+// Fixed UUIDs from seed migration
+const CD_KIND_ID: &str = "00000000-0000-0000-0000-000000000002";
+
+async fn create(fixture: &TestFixture, what: &str, body: Value) -> String {
+    let response = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/{}", fixture.org1_id, what),
+            &body,
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    response.body["id"].as_str().unwrap().to_string()
+}
+
+/// Names of the events waiting in the outbox, oldest first
+async fn pending_event_types(fixture: &TestFixture) -> Vec<String> {
+    sqlx::query_scalar(
+        "SELECT event_type FROM outbox_events WHERE dispatched_at IS NULL ORDER BY id",
+    )
+    .fetch_all(&fixture.ctx.pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_location_rename_propagates() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+
+    let location_id = create(&fixture, "locations", json!({"name": "Loft"})).await;
+    create(
+        &fixture,
+        "items",
+        json!({"kind_id": CD_KIND_ID, "name": "Kind of Blue", "location_id": location_id}),
+    )
+    .await;
+
+    // A saved search filtering by the location, by id
+    let saved_query = format!("location_id={}", location_id);
+    fixture
+        .ctx
+        .put(
+            &format!("{}/preferences", org_path),
+            &json!({"saved_searches": [{"name": "Loft", "query": saved_query}]}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+
+    let response = fixture
+        .ctx
+        .patch(
+            &format!("{}/locations/{}", org_path, location_id),
+            &json!({"name": "Attic"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["name"], "Attic");
+
+    // Location list
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/locations", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body[0]["name"], "Attic");
+
+    // Filter counts
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/items/facets", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["locations"][0]["value"], location_id);
+    assert_eq!(response.body["locations"][0]["label"], "Attic");
+
+    // Storage report
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/reports/storage", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body[0]["location_name"], "Attic");
+
+    // Item export
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/items/export?format=json", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body[0]["location"], "Attic");
+
+    // The saved search still finds the item
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/preferences", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    let query = response.body["saved_searches"][0]["query"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/items?{}", org_path, query),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["total"], 1);
+    assert_eq!(response.body["items"][0]["name"], "Kind of Blue");
+
+    // Open clients hear about the rename
+    assert_eq!(
+        pending_event_types(&fixture).await.last().unwrap(),
+        "location.updated"
+    );
+    let mut receiver = fixture.ctx.state.live.subscribe();
+    let sinks: Vec<Arc<dyn EventSink>> =
+        vec![Arc::new(LiveSink::new(fixture.ctx.state.live.clone()))];
+    outbox::dispatch_pending(&fixture.ctx.pool, &sinks)
+        .await
+        .unwrap();
+    let mut renamed = None;
+    while let Ok(event) = receiver.try_recv() {
+        if event.event_type == "location.updated" {
+            renamed = Some(event);
+        }
+    }
+    let renamed = renamed.expect("location.updated reaches live streams");
+    assert_eq!(renamed.organization_id, fixture.org1_id);
+    assert_eq!(renamed.payload["id"], location_id);
+    assert_eq!(renamed.payload["name"], "Attic");
+}
+
+#[tokio::test]
+async fn test_collection_rename_propagates() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+
+    let collection_id = create(
+        &fixture,
+        "collections",
+        json!({"name": "Jazz", "description": "Mostly bebop"}),
+    )
+    .await;
+    let item_id = create(
+        &fixture,
+        "items",
+        json!({"kind_id": CD_KIND_ID, "name": "Giant Steps"}),
+    )
+    .await;
+    fixture
+        .ctx
+        .post(
+            &format!("{}/collections/{}/items", org_path, collection_id),
+            &json!({"item_id": item_id}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    // Only the name changes; the description is kept
+    let response = fixture
+        .ctx
+        .patch(
+            &format!("{}/collections/{}", org_path, collection_id),
+            &json!({"name": "Modern Jazz"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["name"], "Modern Jazz");
+    assert_eq!(response.body["description"], "Mostly bebop");
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/collections", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body[0]["name"], "Modern Jazz");
+
+    // Item details list the collection under its new name
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/items/{}/full", org_path, item_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["collections"][0]["name"], "Modern Jazz");
+
+    // Filtering by the collection id is unaffected
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/items?collection_id={}", org_path, collection_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["total"], 1);
+
+    assert_eq!(
+        pending_event_types(&fixture).await.last().unwrap(),
+        "collection.updated"
+    );
+}
+
+#[tokio::test]
+async fn test_rename_rejections() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+
+    let loft = create(&fixture, "locations", json!({"name": "Loft"})).await;
+    create(&fixture, "locations", json!({"name": "Cellar"})).await;
+    let collection_id = create(&fixture, "collections", json!({"name": "Jazz"})).await;
+    let location_path = format!("{}/locations/{}", org_path, loft);
+    let collection_path = format!("{}/collections/{}", org_path, collection_id);
+
+    let response = fixture
+        .ctx
+        .patch(
+            &location_path,
+            &json!({"name": "Cellar"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+    assert_eq!(response.body["error"], "location.name_conflict");
+
+    for (path, body) in [
+        (&location_path, json!({"name": "  "})),
+        (&collection_path, json!({"name": ""})),
+    ] {
+        fixture
+            .ctx
+            .patch(path, &body, Some(&fixture.user2_token))
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    // Members without catalog:write cannot rename
+    for path in [&location_path, &collection_path] {
+        fixture
+            .ctx
+            .patch(path, &json!({"name": "Mine"}), Some(&fixture.user1_token))
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+    }
+
+    for what in ["locations", "collections"] {
+        let response = fixture
+            .ctx
+            .patch(
+                &format!("{}/{}/{}", org_path, what, Uuid::new_v4()),
+                &json!({"name": "Missing"}),
+                Some(&fixture.user2_token),
+            )
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    // Nothing was renamed, and no events were written
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/locations", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    let names: Vec<_> = response
+        .body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| l["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Cellar", "Loft"]);
+    assert!(
+        !pending_event_types(&fixture)
+            .await
+            .iter()
+            .any(|t| t.ends_with(".updated"))
+    );
+}
+
+#[tokio::test]
+async fn test_location_merge_moves_items_and_deletes_source() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+
+    let shelf = create(&fixture, "locations", json!({"name": "Shelf A"})).await;
+    let crate_id = create(&fixture, "locations", json!({"name": "Old crate"})).await;
+    let mut moved = Vec::new();
+    for name in ["Blue", "Hejira"] {
+        moved.push(
+            create(
+                &fixture,
+                "items",
+                json!({"kind_id": CD_KIND_ID, "name": name, "location_id": crate_id}),
+            )
+            .await,
+        );
+    }
+    create(
+        &fixture,
+        "items",
+        json!({"kind_id": CD_KIND_ID, "name": "Kind of Blue", "location_id": shelf}),
+    )
+    .await;
+    fixture
+        .ctx
+        .put(
+            &format!("{}/preferences", org_path),
+            &json!({"saved_searches": [{"name": "Crate", "query": format!("location_id={}", crate_id)}]}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+
+    let response = fixture
+        .ctx
+        .post(
+            &format!("{}/locations/{}/merge", org_path, shelf),
+            &json!({"source_location_id": crate_id}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["location"]["name"], "Shelf A");
+    assert_eq!(response.body["items_moved"], 2);
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/locations", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    assert_eq!(response.body.as_array().unwrap().len(), 1);
+    assert_eq!(response.body[0]["id"], shelf);
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/items?location_id={}", org_path, shelf),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    assert_eq!(response.body["total"], 3);
+
+    // Each moved item records the move in its history
+    for item_id in &moved {
+        let changes: Value = sqlx::query_scalar(
+            "SELECT changes FROM audit_log WHERE item_id = $1 AND action = 'updated'",
+        )
+        .bind(Uuid::parse_str(item_id).unwrap())
+        .fetch_one(&fixture.ctx.pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            changes["location_id"],
+            json!({"from": crate_id, "to": shelf})
+        );
+    }
+
+    // The saved search now filters by the location kept
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/preferences", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    assert_eq!(
+        response.body["saved_searches"][0]["query"],
+        format!("location_id={}", shelf)
+    );
+
+    let events = pending_event_types(&fixture).await;
+    assert_eq!(events.last().unwrap(), "location.deleted");
+    assert_eq!(
+        events[events.len() - 3..events.len() - 1],
+        ["item.updated", "item.updated"]
+    );
+}
+
+#[tokio::test]
+async fn test_location_merge_rejections() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+
+    let loft = create(&fixture, "locations", json!({"name": "Loft"})).await;
+    let cellar = create(&fixture, "locations", json!({"name": "Cellar"})).await;
+    create(
+        &fixture,
+        "items",
+        json!({"kind_id": CD_KIND_ID, "name": "Blue", "location_id": cellar}),
+    )
+    .await;
+    let merge = |into: String, source: String, token: String| {
+        let fixture = &fixture;
+        let path = format!("{}/locations/{}/merge", org_path, into);
+        async move {
+            fixture
+                .ctx
+                .post(&path, &json!({"source_location_id": source}), Some(&token))
+                .await
+        }
+    };
+
+    merge(loft.clone(), loft.clone(), fixture.user2_token.clone())
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    merge(loft.clone(), cellar.clone(), fixture.user1_token.clone())
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    for (into, source) in [
+        (loft.clone(), Uuid::new_v4().to_string()),
+        (Uuid::new_v4().to_string(), cellar.clone()),
+    ] {
+        let response = merge(into, source, fixture.user2_token.clone()).await;
+        response.assert_status(StatusCode::NOT_FOUND);
+        assert_eq!(response.body["error"], "location.not_found");
+    }
+
+    // A location in another organization is not found either
+    let elsewhere: Uuid = sqlx::query_scalar(
+        "INSERT INTO locations (organization_id, name) VALUES ($1, 'Elsewhere') RETURNING id",
+    )
+    .bind(fixture.org2_id)
+    .fetch_one(&fixture.ctx.pool)
+    .await
+    .unwrap();
+    merge(
+        loft.clone(),
+        elsewhere.to_string(),
+        fixture.user2_token.clone(),
+    )
+    .await
+    .assert_status(StatusCode::NOT_FOUND);
+
+    // Nothing moved
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/items?location_id={}", org_path, cellar),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    assert_eq!(response.body["total"], 1);
+}
@@ -0,0 +1,436 @@
+mod common;
+
+use chrono::Datelike;
+use common::TestFixture;
+use serde_json::json;
+use uuid::Uuid;
+
+// Fixed UUIDs from seed migration
+const VINYL_KIND_ID: &str = "00000000-0000-0000-0000-000000000001";
+const CD_KIND_ID: &str = "00000000-0000-0000-0000-000000000002";
+
+#[tokio::test]
+async fn test_storage_report_totals_per_location() {
+    let fixture = TestFixture::new().await;
+    let vinyl_id = Uuid::parse_str(VINYL_KIND_ID).unwrap();
+
+    // Locations are admin-managed
+    let shelf = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/locations", fixture.org1_id),
+            &json!({"name": "Shelf A"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    shelf.assert_success();
+    let shelf_id = shelf.body["id"].as_str().unwrap().to_string();
+
+    for (name, depth, weight) in [("LP 1", 4, 180), ("LP 2", 6, 220)] {
+        fixture
+            .ctx
+            .post(
+                &format!("/api/organizations/{}/items", fixture.org1_id),
+                &json!({
+                    "kind_id": vinyl_id,
+                    "name": name,
+                    "location_id": shelf_id,
+                    "width_mm": 315,
+                    "height_mm": 315,
+                    "depth_mm": depth,
+                    "weight_g": weight
+                }),
+                Some(&fixture.user1_token),
+            )
+            .await
+            .assert_success();
+    }
+
+    // Unmeasured item without a location
+    fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            &json!({"kind_id": vinyl_id, "name": "LP 3"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/reports/storage", fixture.org1_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+
+    let rows = response.body.as_array().unwrap();
+    assert_eq!(rows.len(), 2);
+
+    assert_eq!(rows[0]["location_name"], "Shelf A");
+    assert_eq!(rows[0]["item_count"], 2);
+    assert_eq!(rows[0]["measured_count"], 2);
+    assert!((rows[0]["shelf_length_m"].as_f64().unwrap() - 0.010).abs() < 1e-9);
+    assert!((rows[0]["total_weight_kg"].as_f64().unwrap() - 0.4).abs() < 1e-9);
+
+    assert!(rows[1]["location_id"].is_null());
+    assert_eq!(rows[1]["item_count"], 1);
+    assert_eq!(rows[1]["measured_count"], 0);
+    assert_eq!(rows[1]["shelf_length_m"].as_f64().unwrap(), 0.0);
+}
+
+#[tokio::test]
+async fn test_item_state_summary_matches_filtered_list() {
+    let fixture = TestFixture::new().await;
+    let vinyl_id = Uuid::parse_str(VINYL_KIND_ID).unwrap();
+    let items_path = format!("/api/organizations/{}/items", fixture.org1_id);
+    let today = chrono::Local::now().date_naive();
+    let last_year = today.with_day(1).unwrap().with_month(1).unwrap() - chrono::Duration::days(1);
+
+    for (name, change) in [
+        (
+            "On loan",
+            json!({"state": "loaned", "loan_loaned_to": "Alice"}),
+        ),
+        (
+            "Lost",
+            json!({"state": "missing", "missing_date_missing": today}),
+        ),
+        (
+            "Sold",
+            json!({"state": "disposed", "disposed_date_disposed": today}),
+        ),
+        (
+            "Sold long ago",
+            json!({"state": "disposed", "disposed_date_disposed": last_year}),
+        ),
+        ("On the shelf", json!({})),
+    ] {
+        let created = fixture
+            .ctx
+            .post(
+                &items_path,
+                &json!({"kind_id": vinyl_id, "name": name}),
+                Some(&fixture.user1_token),
+            )
+            .await;
+        created.assert_success();
+        fixture
+            .ctx
+            .patch(
+                &format!("{}/{}", items_path, created.body["id"].as_str().unwrap()),
+                &change,
+                Some(&fixture.user1_token),
+            )
+            .await
+            .assert_success();
+    }
+
+    let summary = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/reports/item-states", fixture.org1_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    summary.assert_success();
+    assert_eq!(summary.body["loaned"], 1);
+    assert_eq!(summary.body["missing"], 1);
+    assert_eq!(summary.body["disposed_this_year"], 1);
+    let year_start = summary.body["year_start"].as_str().unwrap();
+
+    // The widget's drill-down link lists exactly the counted items
+    let listed = fixture
+        .ctx
+        .get(
+            &format!(
+                "{}?state=disposed&disposed_since={}",
+                items_path, year_start
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    listed.assert_success();
+    assert_eq!(listed.body["total"], 1);
+    assert_eq!(listed.body["items"][0]["name"], "Sold");
+}
+
+#[tokio::test]
+async fn test_overdue_loans_report_sorted_and_paginated() {
+    let fixture = TestFixture::new().await;
+    let vinyl_id = Uuid::parse_str(VINYL_KIND_ID).unwrap();
+    let items_path = format!("/api/organizations/{}/items", fixture.org1_id);
+    let today = chrono::Local::now().date_naive();
+
+    // (name, days until due back); None = open-ended loan
+    for (name, due_in) in [
+        ("Two Weeks Late", Some(-14)),
+        ("Three Days Late", Some(-3)),
+        ("Due Today", Some(0)),
+        ("Due Next Week", Some(7)),
+        ("Open Ended", None),
+    ] {
+        let created = fixture
+            .ctx
+            .post(
+                &items_path,
+                &json!({"kind_id": vinyl_id, "name": name}),
+                Some(&fixture.user1_token),
+            )
+            .await;
+        created.assert_success();
+        let item_id = created.body["id"].as_str().unwrap().to_string();
+        fixture
+            .ctx
+            .patch(
+                &format!("{}/{}", items_path, item_id),
+                &json!({
+                    "state": "loaned",
+                    "loan_loaned_to": "Carol",
+                    "loan_date_loaned": today - chrono::Duration::days(30),
+                    "loan_date_due_back": due_in.map(|d| today + chrono::Duration::days(d))
+                }),
+                Some(&fixture.user1_token),
+            )
+            .await
+            .assert_success();
+    }
+
+    let report_path = format!(
+        "/api/organizations/{}/reports/overdue-loans",
+        fixture.org1_id
+    );
+
+    // Default order: most overdue first
+    let response = fixture
+        .ctx
+        .get(&report_path, Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["total"], 2);
+    let rows = response.body["items"].as_array().unwrap();
+    assert_eq!(rows[0]["item_name"], "Two Weeks Late");
+    assert_eq!(rows[0]["days_overdue"], 14);
+    assert_eq!(rows[0]["loaned_to"], "Carol");
+    assert_eq!(rows[1]["item_name"], "Three Days Late");
+    assert_eq!(rows[1]["days_overdue"], 3);
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "{}?sort_by=date_due_back&sort_order=desc&per_page=1&page=1",
+                report_path
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["total"], 2);
+    assert_eq!(response.body["total_pages"], 2);
+    let rows = response.body["items"].as_array().unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["item_name"], "Three Days Late");
+
+    // Other organizations see nothing
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/reports/overdue-loans",
+                fixture.org2_id
+            ),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["total"], 0);
+}
+
+#[tokio::test]
+async fn test_organization_stats() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+
+    let shelf = fixture
+        .ctx
+        .post(
+            &format!("{}/locations", org_path),
+            &json!({"name": "Shelf A"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    shelf.assert_success();
+    let shelf_id = shelf.body["id"].as_str().unwrap().to_string();
+
+    let collection = fixture
+        .ctx
+        .post(
+            &format!("{}/collections", org_path),
+            &json!({"name": "Favourites"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    collection.assert_success();
+    let collection_id = collection.body["id"].as_str().unwrap().to_string();
+    fixture
+        .ctx
+        .post(
+            &format!("{}/collections", org_path),
+            &json!({"name": "Empty"}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_success();
+
+    for (name, kind_id, location) in [
+        ("LP 1", VINYL_KIND_ID, Some(&shelf_id)),
+        ("LP 2", VINYL_KIND_ID, None),
+        ("CD 1", CD_KIND_ID, Some(&shelf_id)),
+    ] {
+        let created = fixture
+            .ctx
+            .post(
+                &format!("{}/items", org_path),
+                &json!({"kind_id": kind_id, "name": name, "location_id": location}),
+                Some(&fixture.user1_token),
+            )
+            .await;
+        created.assert_success();
+        if name != "LP 2" {
+            fixture
+                .ctx
+                .post(
+                    &format!("{}/collections/{}/items", org_path, collection_id),
+                    &json!({"item_id": created.body["id"]}),
+                    Some(&fixture.user1_token),
+                )
+                .await
+                .assert_success();
+        }
+    }
+
+    let response = fixture
+        .ctx
+        .get(&format!("{}/stats", org_path), Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    let stats = &response.body;
+
+    assert_eq!(stats["total_items"], 3);
+
+    let by_kind = stats["by_kind"].as_array().unwrap();
+    assert_eq!(by_kind.len(), 2);
+    assert_eq!(by_kind[0]["id"], VINYL_KIND_ID);
+    assert_eq!(by_kind[0]["count"], 2);
+    assert_eq!(by_kind[1]["id"], CD_KIND_ID);
+    assert_eq!(by_kind[1]["count"], 1);
+
+    let by_state = stats["by_state"].as_array().unwrap();
+    assert_eq!(by_state.len(), 4);
+    let current = by_state.iter().find(|s| s["state"] == "current").unwrap();
+    assert_eq!(current["count"], 3);
+    let loaned = by_state.iter().find(|s| s["state"] == "loaned").unwrap();
+    assert_eq!(loaned["count"], 0);
+
+    let by_location = stats["by_location"].as_array().unwrap();
+    assert_eq!(by_location.len(), 2);
+    assert_eq!(by_location[0]["name"], "Shelf A");
+    assert_eq!(by_location[0]["count"], 2);
+    assert!(by_location[1]["id"].is_null());
+    assert_eq!(by_location[1]["count"], 1);
+
+    let by_collection = stats["by_collection"].as_array().unwrap();
+    assert_eq!(by_collection.len(), 2);
+    assert_eq!(by_collection[0]["name"], "Favourites");
+    assert_eq!(by_collection[0]["count"], 2);
+    assert_eq!(by_collection[1]["name"], "Empty");
+    assert_eq!(by_collection[1]["count"], 0);
+
+    // Twelve months, ending with this one, which holds every new item
+    let months = stats["added_per_month"].as_array().unwrap();
+    assert_eq!(months.len(), 12);
+    let this_month = chrono::Local::now().date_naive().with_day(1).unwrap();
+    assert_eq!(months[11]["month"], this_month.to_string());
+    assert_eq!(months[11]["count"], 3);
+    assert_eq!(months[0]["count"], 0);
+
+    // Other organizations see only their own items
+    let other = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/stats", fixture.org2_id),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    other.assert_success();
+    assert_eq!(other.body["total_items"], 0);
+}
+
+#[tokio::test]
+async fn test_usage_report_most_and_least_used() {
+    let fixture = TestFixture::new().await;
+    let cd_id = Uuid::parse_str(CD_KIND_ID).unwrap();
+    let items = format!("/api/organizations/{}/items", fixture.org1_id);
+
+    let mut ids = Vec::new();
+    for name in ["Often", "Once", "Never"] {
+        let created = fixture
+            .ctx
+            .post(
+                &items,
+                &json!({"kind_id": cd_id, "name": name}),
+                Some(&fixture.user1_token),
+            )
+            .await;
+        created.assert_success();
+        ids.push(created.body["id"].as_str().unwrap().to_string());
+    }
+    for (id, used_at) in [
+        (&ids[0], "2024-01-10T12:00:00Z"),
+        (&ids[0], "2025-03-01T12:00:00Z"),
+        (&ids[1], "2024-06-01T12:00:00Z"),
+    ] {
+        fixture
+            .ctx
+            .post(
+                &format!("{}/{}/used", items, id),
+                &json!({"used_at": used_at}),
+                Some(&fixture.user1_token),
+            )
+            .await
+            .assert_success();
+    }
+
+    let names = |list: &serde_json::Value| -> Vec<String> {
+        list.as_array()
+            .unwrap()
+            .iter()
+            .map(|u| u["item_name"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let path = format!("/api/organizations/{}/reports/usage", fixture.org1_id);
+    let report = fixture.ctx.get(&path, Some(&fixture.user1_token)).await;
+    report.assert_success();
+    assert_eq!(names(&report.body["most_used"]), vec!["Often", "Once"]);
+    assert_eq!(report.body["most_used"][0]["use_count"], 2);
+    assert_eq!(
+        names(&report.body["least_used"]),
+        vec!["Never", "Once", "Often"]
+    );
+    assert!(report.body["least_used"][0]["last_used_at"].is_null());
+
+    // Only uses since the given date count
+    let report = fixture
+        .ctx
+        .get(
+            &format!("{}?since=2025-01-01&limit=1", path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    assert_eq!(names(&report.body["most_used"]), vec!["Often"]);
+    assert_eq!(report.body["most_used"][0]["use_count"], 1);
+    assert_eq!(names(&report.body["least_used"]), vec!["Never"]);
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::json;
+use vostuff_api::api::retention;
+
+// Fixed UUIDs from seed migration
+const VINYL_KIND_ID: &str = "00000000-0000-0000-0000-000000000001";
+
+/// Create an item and return its path
+async fn create_item(fixture: &TestFixture, name: &str) -> String {
+    let item_id = fixture
+        .create_item(json!({"kind_id": VINYL_KIND_ID, "name": name}))
+        .await;
+    format!("/api/organizations/{}/items/{}", fixture.org1_id, item_id)
+}
+
+async fn history_actions(fixture: &TestFixture, item_path: &str) -> Vec<(String, String)> {
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/history", item_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    response.body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| {
+            (
+                e["action"].as_str().unwrap().to_string(),
+                e["change_details"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_history_retention_compacts_old_updates() {
+    let fixture = TestFixture::new().await;
+    let ctx = &fixture.ctx;
+    let settings_path = format!("/api/organizations/{}/settings", fixture.org1_id);
+
+    let busy = create_item(&fixture, "Blue Train").await;
+    for (notes, token) in [
+        ("Signed", &fixture.user1_token),
+        ("Signed, first pressing", &fixture.user2_token),
+    ] {
+        ctx.patch(&busy, &json!({"notes": notes}), Some(token))
+            .await
+            .assert_success();
+    }
+    ctx.patch(
+        &busy,
+        &json!({"state": "missing"}),
+        Some(&fixture.user1_token),
+    )
+    .await
+    .assert_success();
+    let quiet = create_item(&fixture, "Giant Steps").await;
+    ctx.patch(
+        &quiet,
+        &json!({"notes": "Mono"}),
+        Some(&fixture.user1_token),
+    )
+    .await
+    .assert_success();
+
+    // Everything so far happened three years ago
+    sqlx::query(
+        "UPDATE audit_log SET change_date = change_date - INTERVAL '3 years'
+         WHERE organization_id = $1",
+    )
+    .bind(fixture.org1_id)
+    .execute(&ctx.pool)
+    .await
+    .unwrap();
+    ctx.patch(
+        &busy,
+        &json!({"notes": "Recent"}),
+        Some(&fixture.user1_token),
+    )
+    .await
+    .assert_success();
+
+    // Without a retention period nothing is compacted
+    assert_eq!(
+        retention::compact_history(&ctx.pool).await.unwrap(),
+        0,
+        "no organization has a retention period"
+    );
+
+    ctx.put(
+        &settings_path,
+        &json!({"history_retention_months": 0}),
+        Some(&fixture.user2_token),
+    )
+    .await
+    .assert_status(StatusCode::BAD_REQUEST);
+    ctx.put(
+        &settings_path,
+        &json!({"history_retention_months": 24}),
+        Some(&fixture.user2_token),
+    )
+    .await
+    .assert_success();
+
+    assert_eq!(retention::compact_history(&ctx.pool).await.unwrap(), 3);
+
+    // The old updates became one summary; creation and recent entries remain
+    let history = history_actions(&fixture, &busy).await;
+    let actions: Vec<&str> = history.iter().map(|(a, _)| a.as_str()).collect();
+    assert_eq!(actions, vec!["updated", "summarized", "created"]);
+    assert_eq!(history[1].1, "Summarized changes to notes, state");
+
+    let response = ctx
+        .get(&format!("{}/history", busy), Some(&fixture.user1_token))
+        .await;
+    let summary = &response.body["items"][1];
+    assert_eq!(
+        summary["changes"],
+        json!({
+            "notes": {"from": null, "to": "Signed, first pressing"},
+            "state": {"from": "current", "to": "missing"}
+        })
+    );
+    // Changes by several users are no longer attributed to one
+    assert!(summary["user_id"].is_null());
+
+    // A lone old update is left alone, and compaction is idempotent
+    let actions: Vec<String> = history_actions(&fixture, &quiet)
+        .await
+        .into_iter()
+        .map(|(a, _)| a)
+        .collect();
+    assert_eq!(actions, vec!["updated", "created"]);
+    assert_eq!(
+        retention::compact_organization(&ctx.pool, fixture.org1_id, 24)
+            .await
+            .unwrap(),
+        0
+    );
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::TestContext;
+use serde_json::json;
+use sqlx::PgPool;
+use vostuff_api::api::models::LoginResponse;
+use vostuff_api::test_utils::{SEED_PASSWORD, SampleDataLoader, SeedProfile};
+
+/// Every item with its details, tags and collections, in a stable order
+async fn snapshot(pool: &PgPool) -> Vec<String> {
+    sqlx::query_scalar(
+        "SELECT concat_ws(' | ', i.name, i.state::text, i.date_acquired::text,
+                          i.soft_fields::text, loc.name, ld.loaned_to, ld.date_loaned::text,
+                          md.date_missing::text, dd.date_disposed::text,
+                          (SELECT string_agg(t.tag_name, ',' ORDER BY t.tag_name)
+                           FROM item_tags t WHERE t.item_id = i.id),
+                          (SELECT string_agg(c.name, ',' ORDER BY c.name)
+                           FROM item_collections ic JOIN collections c ON c.id = ic.collection_id
+                           WHERE ic.item_id = i.id))
+         FROM items i
+         LEFT JOIN locations loc ON loc.id = i.location_id
+         LEFT JOIN item_loan_details ld ON ld.item_id = i.id
+         LEFT JOIN item_missing_details md ON md.item_id = i.id
+         LEFT JOIN item_disposed_details dd ON dd.item_id = i.id
+         ORDER BY 1",
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+async fn seeded_snapshot(profile: SeedProfile, seed: u64) -> Vec<String> {
+    // A new context starts from an empty database
+    let ctx = TestContext::new().await;
+    let result = SampleDataLoader::with_seed(&ctx.pool, seed)
+        .load_profile(profile)
+        .await
+        .unwrap();
+    let items = snapshot(&ctx.pool).await;
+    assert_eq!(items.len(), result.item_count);
+    items
+}
+
+#[tokio::test]
+async fn test_seed_profiles_are_reproducible() {
+    let first = seeded_snapshot(SeedProfile::DemoMusic, 7).await;
+    let again = seeded_snapshot(SeedProfile::DemoMusic, 7).await;
+    assert_eq!(first, again);
+
+    let other_seed = seeded_snapshot(SeedProfile::DemoMusic, 8).await;
+    assert_eq!(first.len(), other_seed.len());
+    assert_ne!(first, other_seed);
+
+    let library = seeded_snapshot(SeedProfile::DemoLibrary, 7).await;
+    assert!(library.iter().any(|item| item.contains("| loaned |")));
+}
+
+#[tokio::test]
+async fn test_minimal_profile_user_can_log_in() {
+    let ctx = TestContext::new().await;
+    let result = SampleDataLoader::new(&ctx.pool)
+        .load_profile(SeedProfile::Minimal)
+        .await
+        .unwrap();
+    assert_eq!(result.organization_ids.len(), 1);
+    assert_eq!(result.item_count, 3);
+
+    let response = ctx
+        .post(
+            "/api/auth/login",
+            &json!({"identity": "ada@acme.example", "password": SEED_PASSWORD}),
+            None,
+        )
+        .await;
+    response.assert_success();
+    let login: LoginResponse = response.json();
+    assert_eq!(login.user.organization.id, result.organization_ids[0]);
+
+    let items = ctx
+        .get(
+            &format!("/api/organizations/{}/items", result.organization_ids[0]),
+            Some(&login.token),
+        )
+        .await;
+    items.assert_success();
+    assert_eq!(items.body["total"], 3);
+}
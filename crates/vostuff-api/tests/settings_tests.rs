@@ -0,0 +1,573 @@
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use common::TestFixture;
+use serde_json::json;
+use vostuff_api::api::models::UserRole;
+use vostuff_core::auth::SYSTEM_ORG_ID;
+
+#[tokio::test]
+async fn test_announcement_visible_to_members() {
+    let fixture = TestFixture::new().await;
+    let settings_path = format!("/api/organizations/{}/settings", fixture.org1_id);
+    let expires_on = Utc::now().date_naive() + Duration::days(3);
+
+    let response = fixture
+        .ctx
+        .put(
+            &settings_path,
+            &json!({"announcement": {
+                "message": "Stocktake this weekend - don't move items",
+                "expires_on": expires_on,
+            }}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_success();
+
+    // Plain members see it
+    let response = fixture
+        .ctx
+        .get(&settings_path, Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    assert_eq!(
+        response.body["announcement"]["message"],
+        "Stocktake this weekend - don't move items"
+    );
+    assert_eq!(
+        response.body["announcement"]["expires_on"],
+        expires_on.to_string()
+    );
+
+    // Only admins may set one
+    let response = fixture
+        .ctx
+        .put(
+            &settings_path,
+            &json!({"announcement": {"message": "Hello", "expires_on": expires_on}}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_invalid_announcements_rejected() {
+    let fixture = TestFixture::new().await;
+    let settings_path = format!("/api/organizations/{}/settings", fixture.org1_id);
+    let today = Utc::now().date_naive();
+
+    for announcement in [
+        json!({"message": "  ", "expires_on": today}),
+        json!({"message": "x".repeat(501), "expires_on": today}),
+        json!({"message": "Too late", "expires_on": today - Duration::days(1)}),
+    ] {
+        let response = fixture
+            .ctx
+            .put(
+                &settings_path,
+                &json!({ "announcement": announcement }),
+                Some(&fixture.user2_token),
+            )
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        assert_eq!(response.body["error"], "setting.invalid");
+    }
+}
+
+#[tokio::test]
+async fn test_accent_color_must_be_hex() {
+    let fixture = TestFixture::new().await;
+    let settings_path = format!("/api/organizations/{}/settings", fixture.org1_id);
+
+    for color in ["red", "#12345", "#12345g"] {
+        let response = fixture
+            .ctx
+            .put(
+                &settings_path,
+                &json!({ "accent_color": color }),
+                Some(&fixture.user2_token),
+            )
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        assert_eq!(response.body["error"], "setting.invalid");
+    }
+
+    fixture
+        .ctx
+        .put(
+            &settings_path,
+            &json!({ "accent_color": "#2A7AE2" }),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_success();
+    let response = fixture
+        .ctx
+        .get("/api/auth/me", Some(&fixture.user1_token))
+        .await;
+    assert_eq!(response.body["settings"]["accent_color"], "#2A7AE2");
+}
+
+/// The logo link members see in /auth/me
+async fn logo_url(fixture: &TestFixture) -> Option<String> {
+    let response = fixture
+        .ctx
+        .get("/api/auth/me", Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    response.body["logo_url"].as_str().map(str::to_string)
+}
+
+#[tokio::test]
+async fn test_logo_upload_replace_and_remove() {
+    let fixture = TestFixture::new().await;
+    let logo_path = format!("/api/organizations/{}/settings/logo", fixture.org1_id);
+    let storage = &fixture.ctx.state.storage;
+    let key_of = |url: &str| {
+        url.trim_start_matches("http://localhost:8080/api/files/")
+            .split('?')
+            .next()
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(logo_url(&fixture).await, None);
+
+    // Only members who may change settings can set it
+    fixture
+        .ctx
+        .put_bytes(
+            &logo_path,
+            "image/png",
+            b"png".to_vec(),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    for (content_type, body) in [("image/svg+xml", b"<svg/>".to_vec()), ("image/png", vec![])] {
+        let response = fixture
+            .ctx
+            .put_bytes(&logo_path, content_type, body, Some(&fixture.user2_token))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        assert_eq!(response.body["error"], "setting.invalid");
+    }
+
+    let response = fixture
+        .ctx
+        .put_bytes(
+            &logo_path,
+            "image/png",
+            b"first".to_vec(),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_success();
+    let first_key = key_of(response.body["url"].as_str().unwrap());
+    let url = logo_url(&fixture).await.unwrap();
+    assert_eq!(key_of(&url), first_key);
+    let image = fixture
+        .ctx
+        .get(url.trim_start_matches("http://localhost:8080"), None)
+        .await;
+    assert_eq!(image.body, "first");
+
+    // A new logo replaces the old one, which is deleted
+    let response = fixture
+        .ctx
+        .put_bytes(
+            &logo_path,
+            "image/jpeg",
+            b"second".to_vec(),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_success();
+    let second_key = key_of(response.body["url"].as_str().unwrap());
+    assert!(second_key.ends_with(".jpg"));
+    assert!(storage.get(&first_key).await.is_err());
+
+    fixture
+        .ctx
+        .delete(&logo_path, Some(&fixture.user2_token))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    assert_eq!(logo_url(&fixture).await, None);
+    assert!(storage.get(&second_key).await.is_err());
+}
+
+#[tokio::test]
+async fn test_expired_announcement_hidden() {
+    let fixture = TestFixture::new().await;
+    let settings_path = format!("/api/organizations/{}/settings", fixture.org1_id);
+    let yesterday = Utc::now().date_naive() - Duration::days(1);
+
+    sqlx::query("UPDATE organizations SET settings = $2 WHERE id = $1")
+        .bind(fixture.org1_id)
+        .bind(json!({"announcement": {"message": "Old news", "expires_on": yesterday}}))
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+
+    let response = fixture
+        .ctx
+        .get(&settings_path, Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    assert!(response.body["announcement"].is_null());
+}
+
+#[tokio::test]
+async fn test_default_member_roles_applied_to_new_members() {
+    let fixture = TestFixture::new().await;
+    let ctx = &fixture.ctx;
+    let settings_path = format!("/api/organizations/{}/settings", fixture.org1_id);
+
+    let response = ctx
+        .put(
+            &settings_path,
+            &json!({"default_member_roles": []}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.body["error"], "setting.invalid");
+
+    // Typos are not role names
+    ctx.put(
+        &settings_path,
+        &json!({"default_member_roles": ["AMDIN"]}),
+        Some(&fixture.user2_token),
+    )
+    .await
+    .assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+
+    ctx.put(
+        &settings_path,
+        &json!({"default_member_roles": ["USER", "LOAN_MANAGER"]}),
+        Some(&fixture.user2_token),
+    )
+    .await
+    .assert_success();
+
+    let sysadmin_id = ctx
+        .create_user("Root", "root@test.com", "password123")
+        .await;
+    ctx.add_user_to_org(sysadmin_id, SYSTEM_ORG_ID, vec![UserRole::Admin])
+        .await;
+    let sysadmin_token = ctx
+        .login("root@test.com", "password123", Some(SYSTEM_ORG_ID))
+        .await;
+    let new_user_id = ctx
+        .create_user("Newcomer", "new@test.com", "password123")
+        .await;
+    let membership_path =
+        |org_id| format!("/api/admin/users/{}/organizations/{}", new_user_id, org_id);
+
+    // Org1 has defaults; org2 falls back to USER
+    let response = ctx
+        .post(
+            &membership_path(fixture.org1_id),
+            &json!({}),
+            Some(&sysadmin_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    assert_eq!(response.body["roles"], json!(["USER", "LOAN_MANAGER"]));
+
+    let response = ctx
+        .post(
+            &membership_path(fixture.org2_id),
+            &json!({}),
+            Some(&sysadmin_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    assert_eq!(response.body["roles"], json!(["USER"]));
+
+    // Explicit roles must not be empty
+    let response = ctx
+        .patch(
+            &membership_path(fixture.org1_id),
+            &json!({"roles": []}),
+            Some(&sysadmin_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.body["error"], "validation.failed");
+}
+
+#[tokio::test]
+async fn test_read_only_setting_refuses_changes() {
+    let fixture = TestFixture::new().await;
+    let ctx = &fixture.ctx;
+    let settings_path = format!("/api/organizations/{}/settings", fixture.org1_id);
+    let locations_path = format!("/api/organizations/{}/locations", fixture.org1_id);
+
+    ctx.put(
+        &settings_path,
+        &json!({"read_only": true}),
+        Some(&fixture.user2_token),
+    )
+    .await
+    .assert_success();
+
+    let response = ctx
+        .post(
+            &locations_path,
+            &json!({"name": "Shelf A"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::LOCKED);
+    assert_eq!(response.body["error"], "organization.read_only");
+
+    // Reading still works, and members are told
+    ctx.get(&locations_path, Some(&fixture.user1_token))
+        .await
+        .assert_success();
+    let response = ctx.get("/api/auth/me", Some(&fixture.user1_token)).await;
+    response.assert_success();
+    assert_eq!(response.body["read_only"], true);
+
+    // Admins can turn it off again
+    ctx.put(
+        &settings_path,
+        &json!({"read_only": false}),
+        Some(&fixture.user2_token),
+    )
+    .await
+    .assert_success();
+    ctx.post(
+        &locations_path,
+        &json!({"name": "Shelf A"}),
+        Some(&fixture.user2_token),
+    )
+    .await
+    .assert_status(StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn test_system_admin_read_only_cannot_be_lifted_by_org() {
+    let fixture = TestFixture::new().await;
+    let ctx = &fixture.ctx;
+    let sysadmin_id = ctx
+        .create_user("Root", "root@test.com", "password123")
+        .await;
+    ctx.add_user_to_org(sysadmin_id, SYSTEM_ORG_ID, vec![UserRole::Admin])
+        .await;
+    let sysadmin_token = ctx
+        .login("root@test.com", "password123", Some(SYSTEM_ORG_ID))
+        .await;
+    let read_only_path = format!("/api/admin/organizations/{}/read-only", fixture.org1_id);
+    let settings_path = format!("/api/organizations/{}/settings", fixture.org1_id);
+
+    // Only system admins may freeze an organization
+    ctx.put(
+        &read_only_path,
+        &json!({"read_only": true}),
+        Some(&fixture.user2_token),
+    )
+    .await
+    .assert_status(StatusCode::FORBIDDEN);
+
+    let response = ctx
+        .put(
+            &read_only_path,
+            &json!({"read_only": true}),
+            Some(&sysadmin_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["read_only"], true);
+    let response = ctx.get(&read_only_path, Some(&sysadmin_token)).await;
+    response.assert_success();
+    assert_eq!(response.body["read_only"], true);
+
+    let response = ctx
+        .put(
+            &settings_path,
+            &json!({"read_only": false}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::LOCKED);
+    assert_eq!(response.body["error"], "organization.read_only");
+    ctx.post(
+        &format!("/api/organizations/{}/locations", fixture.org1_id),
+        &json!({"name": "Shelf A"}),
+        Some(&fixture.user2_token),
+    )
+    .await
+    .assert_status(StatusCode::LOCKED);
+    let response = ctx.get("/api/auth/me", Some(&fixture.user1_token)).await;
+    assert_eq!(response.body["read_only"], true);
+
+    ctx.put(
+        &read_only_path,
+        &json!({"read_only": false}),
+        Some(&sysadmin_token),
+    )
+    .await
+    .assert_success();
+    ctx.put(
+        &settings_path,
+        &json!({"max_loan_days": 14}),
+        Some(&fixture.user2_token),
+    )
+    .await
+    .assert_success();
+
+    ctx.get(
+        &format!(
+            "/api/admin/organizations/{}/read-only",
+            uuid::Uuid::new_v4()
+        ),
+        Some(&sysadmin_token),
+    )
+    .await
+    .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_label_templates_saved_and_validated() {
+    let fixture = TestFixture::new().await;
+    let settings_path = format!("/api/organizations/{}/settings", fixture.org1_id);
+    let shelf = json!({
+        "name": "Shelf label",
+        "fields": ["name", "catalog_code", "location"],
+        "width_mm": 62,
+        "height_mm": 29,
+        "include_qr": true,
+    });
+
+    fixture
+        .ctx
+        .put(
+            &settings_path,
+            &json!({ "label_templates": [shelf] }),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_success();
+    let response = fixture
+        .ctx
+        .get(&settings_path, Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    let templates = response.body["label_templates"].as_array().unwrap();
+    assert_eq!(templates.len(), 1);
+    assert_eq!(
+        templates[0]["fields"],
+        json!(["name", "catalog_code", "location"])
+    );
+    assert_eq!(templates[0]["include_qr"], true);
+    assert_eq!(templates[0]["include_barcode"], false);
+
+    let template = |overrides: serde_json::Value| {
+        let mut template = shelf.clone();
+        template
+            .as_object_mut()
+            .unwrap()
+            .extend(overrides.as_object().unwrap().clone());
+        template
+    };
+    for templates in [
+        json!([template(json!({"name": " "}))]),
+        json!([shelf, template(json!({"name": "SHELF LABEL"}))]),
+        json!([template(json!({"fields": ["name", "password"]}))]),
+        json!([template(json!({"fields": ["name", "name"]}))]),
+        json!([template(json!({"fields": [], "include_qr": false}))]),
+        json!([template(json!({"width_mm": 5}))]),
+        json!([template(json!({"height_mm": 1000}))]),
+    ] {
+        let response = fixture
+            .ctx
+            .put(
+                &settings_path,
+                &json!({ "label_templates": templates }),
+                Some(&fixture.user2_token),
+            )
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        assert_eq!(response.body["error"], "setting.invalid");
+    }
+}
+
+#[tokio::test]
+async fn test_labels_printed_with_a_template() {
+    let fixture = TestFixture::new().await;
+    fixture
+        .ctx
+        .put(
+            &format!("/api/organizations/{}/settings", fixture.org1_id),
+            &json!({ "label_templates": [{
+                "name": "Shelf label",
+                "fields": ["name", "location"],
+                "width_mm": 62,
+                "height_mm": 29,
+                "include_qr": true,
+                "include_barcode": true,
+            }] }),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_success();
+
+    let mut item_ids = Vec::new();
+    for (name, barcode) in [("Blue Train", "4006381333931"), ("Dune", "LIB-0042")] {
+        let response = fixture
+            .ctx
+            .post(
+                &format!("/api/organizations/{}/items", fixture.org1_id),
+                &json!({
+                    "kind_id": "00000000-0000-0000-0000-000000000001",
+                    "name": name,
+                    "barcode": barcode,
+                }),
+                Some(&fixture.user1_token),
+            )
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        item_ids.push(response.body["id"].as_str().unwrap().to_string());
+    }
+
+    let labels_path = format!("/api/organizations/{}/items/labels", fixture.org1_id);
+    let response = fixture
+        .ctx
+        .post(
+            &labels_path,
+            &json!({"template": "Shelf label", "item_ids": item_ids}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.headers["content-type"], "application/pdf");
+    let pdf = response.body.as_str().unwrap();
+    assert!(pdf.starts_with("%PDF-"));
+    assert!(pdf.contains("/Count 2"));
+
+    let response = fixture
+        .ctx
+        .post(
+            &labels_path,
+            &json!({"template": "Spine label", "item_ids": item_ids}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    // Another organization's items cannot be printed
+    let response = fixture
+        .ctx
+        .post(
+            &labels_path,
+            &json!({"template": "Shelf label", "item_ids": [item_ids[0], uuid::Uuid::new_v4()]}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "item.not_found");
+}
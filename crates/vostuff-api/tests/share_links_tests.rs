@@ -0,0 +1,153 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::json;
+use uuid::Uuid;
+
+// Fixed UUIDs from seed migration
+const VINYL_KIND_ID: &str = "00000000-0000-0000-0000-000000000001";
+
+async fn create_vinyl(fixture: &TestFixture) -> Uuid {
+    fixture
+        .create_item(json!({
+            "kind_id": VINYL_KIND_ID,
+            "name": "Blue Train",
+            "description": "John Coltrane - 1957",
+            "notes": "Bought from a friend",
+            "barcode": "0602557093487",
+            "soft_fields": {"speed": "33"}
+        }))
+        .await
+}
+
+#[tokio::test]
+async fn test_share_link_shows_chosen_fields_without_login() {
+    let fixture = TestFixture::new().await;
+    let item_id = create_vinyl(&fixture).await;
+
+    let link = fixture
+        .ctx
+        .post(
+            &format!(
+                "/api/organizations/{}/items/{}/share-link",
+                fixture.org1_id, item_id
+            ),
+            &json!({"fields": ["description", "soft_fields"], "expires_in_hours": 2}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    link.assert_success();
+    let token = link.body["token"].as_str().unwrap();
+    assert_eq!(
+        link.body["url"],
+        format!("http://localhost:3001/shared?token={}", token)
+    );
+    assert_eq!(link.body["fields"], json!(["description", "soft_fields"]));
+
+    let shared = fixture
+        .ctx
+        .get(&format!("/api/shared-items/{}", token), None)
+        .await;
+    shared.assert_success();
+    assert_eq!(shared.body["name"], "Blue Train");
+    assert_eq!(
+        shared.body["fields"],
+        json!({"description": "John Coltrane - 1957", "soft_fields": {"speed": "33"}})
+    );
+    assert_eq!(shared.body["expires_at"], link.body["expires_at"]);
+
+    // The page shows the item as it is now
+    fixture
+        .ctx
+        .patch(
+            &format!("/api/organizations/{}/items/{}", fixture.org1_id, item_id),
+            &json!({"description": "Blue Note 1577"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+    let shared = fixture
+        .ctx
+        .get(&format!("/api/shared-items/{}", token), None)
+        .await;
+    assert_eq!(shared.body["fields"]["description"], "Blue Note 1577");
+
+    // Deleting the item stops the link working
+    fixture
+        .ctx
+        .delete(
+            &format!("/api/organizations/{}/items/{}", fixture.org1_id, item_id),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+    fixture
+        .ctx
+        .get(&format!("/api/shared-items/{}", token), None)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_share_link_defaults_and_limits() {
+    let fixture = TestFixture::new().await;
+    let item_id = create_vinyl(&fixture).await;
+    let path = format!(
+        "/api/organizations/{}/items/{}/share-link",
+        fixture.org1_id, item_id
+    );
+
+    let link = fixture
+        .ctx
+        .post(&path, &json!({}), Some(&fixture.user1_token))
+        .await;
+    link.assert_success();
+    assert_eq!(
+        link.body["fields"],
+        json!(["kind_name", "description", "soft_fields"])
+    );
+
+    for body in [
+        json!({"fields": ["location_id"]}),
+        json!({"expires_in_hours": 0}),
+        json!({"expires_in_hours": 24 * 31}),
+    ] {
+        let response = fixture
+            .ctx
+            .post(&path, &body, Some(&fixture.user1_token))
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+        assert_eq!(response.body["error"], "validation.failed");
+    }
+
+    // Another organization's item cannot be shared
+    fixture
+        .ctx
+        .post(
+            &format!(
+                "/api/organizations/{}/items/{}/share-link",
+                fixture.org2_id, item_id
+            ),
+            &json!({}),
+            Some(&fixture.user3_token),
+        )
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_invalid_share_link_is_refused() {
+    let fixture = TestFixture::new().await;
+
+    let response = fixture.ctx.get("/api/shared-items/not-a-token", None).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(response.body["error"], "item.share_link_invalid");
+
+    // A session token is not a share link
+    let response = fixture
+        .ctx
+        .get(&format!("/api/shared-items/{}", fixture.user1_token), None)
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+}
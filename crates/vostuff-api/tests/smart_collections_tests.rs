@@ -0,0 +1,319 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+// Fixed UUIDs from seed migration
+const VINYL_KIND_ID: &str = "00000000-0000-0000-0000-000000000001";
+const CD_KIND_ID: &str = "00000000-0000-0000-0000-000000000002";
+
+async fn create_item(fixture: &TestFixture, kind_id: &str, name: &str, tags: &[&str]) -> String {
+    fixture
+        .create_item(json!({"kind_id": kind_id, "name": name, "tags": tags}))
+        .await
+        .to_string()
+}
+
+async fn loan(fixture: &TestFixture, item_id: &str) {
+    fixture
+        .ctx
+        .patch(
+            &format!("/api/organizations/{}/items/{}", fixture.org1_id, item_id),
+            &json!({"state": "loaned", "loan_date_loaned": "2026-01-02", "loan_loaned_to": "Carol"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_success();
+}
+
+async fn create_smart_collection(fixture: &TestFixture, body: Value) -> String {
+    let response = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/smart-collections", fixture.org1_id),
+            &body,
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    response.body["id"].as_str().unwrap().to_string()
+}
+
+async fn item_names(fixture: &TestFixture, collection_id: &str) -> Vec<String> {
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/smart-collections/{}/items",
+                fixture.org1_id, collection_id
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    let mut names: Vec<String> = response.body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["name"].as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn test_smart_collection_items_follow_its_filter() {
+    let fixture = TestFixture::new().await;
+
+    let blue = create_item(&fixture, VINYL_KIND_ID, "Blue", &["folk"]).await;
+    let hejira = create_item(&fixture, VINYL_KIND_ID, "Hejira", &["folk", "jazz"]).await;
+    let court = create_item(&fixture, CD_KIND_ID, "Court and Spark", &["folk"]).await;
+    loan(&fixture, &blue).await;
+    loan(&fixture, &court).await;
+
+    let loaned_vinyl = create_smart_collection(
+        &fixture,
+        json!({"name": "All loaned vinyl", "filter": {"kinds": ["vinyl"], "states": ["loaned"]}}),
+    )
+    .await;
+    assert_eq!(item_names(&fixture, &loaned_vinyl).await, vec!["Blue"]);
+
+    // Membership is worked out on each listing
+    loan(&fixture, &hejira).await;
+    assert_eq!(
+        item_names(&fixture, &loaned_vinyl).await,
+        vec!["Blue", "Hejira"]
+    );
+
+    let folk_jazz = create_smart_collection(
+        &fixture,
+        json!({"name": "Folk jazz", "filter": {"tags": ["folk", "jazz"], "all_tags": true}}),
+    )
+    .await;
+    assert_eq!(item_names(&fixture, &folk_jazz).await, vec!["Hejira"]);
+
+    let everything =
+        create_smart_collection(&fixture, json!({"name": "Everything", "filter": {}})).await;
+    assert_eq!(
+        item_names(&fixture, &everything).await,
+        vec!["Blue", "Court and Spark", "Hejira"]
+    );
+
+    let search = create_smart_collection(
+        &fixture,
+        json!({"name": "Spark", "filter": {"search": "spark"}}),
+    )
+    .await;
+    assert_eq!(item_names(&fixture, &search).await, vec!["Court and Spark"]);
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/smart-collections", fixture.org1_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    let names: Vec<&str> = response
+        .body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["All loaned vinyl", "Everything", "Folk jazz", "Spark"]
+    );
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/smart-collections/{}",
+                fixture.org1_id, loaned_vinyl
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["filter"]["kinds"], json!(["vinyl"]));
+    assert_eq!(response.body["filter"]["states"], json!(["loaned"]));
+    assert_eq!(response.body["filter"]["all_tags"], false);
+}
+
+#[tokio::test]
+async fn test_smart_collection_update_and_delete() {
+    let fixture = TestFixture::new().await;
+    create_item(&fixture, VINYL_KIND_ID, "Blue", &[]).await;
+    create_item(&fixture, CD_KIND_ID, "Court and Spark", &[]).await;
+    let collection_id = create_smart_collection(
+        &fixture,
+        json!({"name": "Records", "description": "On the shelf", "filter": {"kinds": ["vinyl"]}}),
+    )
+    .await;
+    let path = format!(
+        "/api/organizations/{}/smart-collections/{}",
+        fixture.org1_id, collection_id
+    );
+
+    let response = fixture
+        .ctx
+        .patch(
+            &path,
+            &json!({"name": "Discs", "filter": {"kinds": ["vinyl", "cd"]}}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["name"], "Discs");
+    assert_eq!(response.body["description"], "On the shelf");
+    assert_eq!(
+        item_names(&fixture, &collection_id).await,
+        vec!["Blue", "Court and Spark"]
+    );
+
+    // Only the name changes; the filter is kept
+    let response = fixture
+        .ctx
+        .patch(&path, &json!({"name": "Music"}), Some(&fixture.user2_token))
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["filter"]["kinds"], json!(["vinyl", "cd"]));
+
+    fixture
+        .ctx
+        .delete(&path, Some(&fixture.user2_token))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    let response = fixture.ctx.get(&path, Some(&fixture.user1_token)).await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "smart_collection.not_found");
+    fixture
+        .ctx
+        .get(&format!("{}/items", path), Some(&fixture.user1_token))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    fixture
+        .ctx
+        .delete(&path, Some(&fixture.user2_token))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    // The items are untouched
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/items", fixture.org1_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    assert_eq!(response.body["total"], 2);
+}
+
+#[tokio::test]
+async fn test_smart_collection_rejects_bad_filters() {
+    let fixture = TestFixture::new().await;
+    let path = format!("/api/organizations/{}/smart-collections", fixture.org1_id);
+
+    let response = fixture
+        .ctx
+        .post(
+            &path,
+            &json!({"name": "Cartridges", "filter": {"kinds": ["vinyl", "eight_track"]}}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.body["fields"][0]["field"], "filter.kinds.1");
+
+    let response = fixture
+        .ctx
+        .post(
+            &path,
+            &json!({"name": " ", "filter": {"tags": [""]}}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    let fields: Vec<&str> = response.body["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, vec!["name", "filter.tags.0"]);
+
+    // Unknown states are not a filter at all
+    fixture
+        .ctx
+        .post(
+            &path,
+            &json!({"name": "Lost", "filter": {"states": ["stolen"]}}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+
+    create_smart_collection(
+        &fixture,
+        json!({"name": "Loaned", "filter": {"states": ["loaned"]}}),
+    )
+    .await;
+    let response = fixture
+        .ctx
+        .post(
+            &path,
+            &json!({"name": "Loaned", "filter": {}}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+    assert_eq!(response.body["error"], "smart_collection.name_conflict");
+}
+
+#[tokio::test]
+async fn test_smart_collections_need_catalog_write_and_stay_in_organization() {
+    let fixture = TestFixture::new().await;
+
+    fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/smart-collections", fixture.org1_id),
+            &json!({"name": "Mine", "filter": {}}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let collection_id =
+        create_smart_collection(&fixture, json!({"name": "Everything", "filter": {}})).await;
+
+    // user3 belongs to org2 only
+    fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/smart-collections/{}/items",
+                fixture.org1_id, collection_id
+            ),
+            Some(&fixture.user3_token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/smart-collections/{}",
+                fixture.org1_id,
+                Uuid::new_v4()
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,287 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::json;
+use uuid::Uuid;
+use vostuff_api::api::{
+    jobs::{self, JobHandler},
+    lookup::{LookupItemsJob, Metadata, MetadataProvider},
+};
+
+const CD_KIND_ID: &str = "00000000-0000-0000-0000-000000000002";
+const MISC_KIND_ID: &str = "00000000-0000-0000-0000-000000000007";
+
+/// Provider that knows one CD and remembers the barcodes it was asked about
+#[derive(Default)]
+struct FakeProvider {
+    asked: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl MetadataProvider for FakeProvider {
+    fn name(&self) -> &str {
+        "fake"
+    }
+
+    fn supports(&self, kind: &str) -> bool {
+        kind == "cd"
+    }
+
+    async fn lookup(&self, code: &str) -> anyhow::Result<Option<Metadata>> {
+        self.asked.lock().unwrap().push(code.to_string());
+        Ok((code == "5099706424420").then(|| Metadata {
+            description: Some("Miles Davis - Kind of Blue (1959)".into()),
+            catalog_code: Some("CK 64935".into()),
+            source_url: Some("https://example.com/release/1".into()),
+            ..Default::default()
+        }))
+    }
+}
+
+async fn create_item(fixture: &TestFixture, kind_id: &str, body: serde_json::Value) -> Uuid {
+    let mut body = body;
+    body["kind_id"] = json!(kind_id);
+    fixture.create_item(body).await
+}
+
+/// Queue a lookup of the items and run it with the fake provider
+async fn enrich(
+    fixture: &TestFixture,
+    provider: Arc<FakeProvider>,
+    ids: &[Uuid],
+) -> serde_json::Value {
+    let response = fixture
+        .ctx
+        .post(
+            &format!("/api/organizations/{}/items/enrich", fixture.org1_id),
+            &json!({"item_ids": ids}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+
+    let handlers: Vec<Arc<dyn JobHandler>> = vec![Arc::new(LookupItemsJob::new(
+        fixture.ctx.pool.clone(),
+        vec![provider],
+    ))];
+    while jobs::run_next(&fixture.ctx.pool, &handlers).await.unwrap() {}
+    response.body
+}
+
+async fn suggestions(fixture: &TestFixture) -> Vec<serde_json::Value> {
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/items/suggestions", fixture.org1_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    response.body.as_array().unwrap().clone()
+}
+
+#[tokio::test]
+async fn test_lookup_suggests_details_for_review() {
+    let fixture = TestFixture::new().await;
+    let provider = Arc::new(FakeProvider::default());
+
+    let kind_of_blue = create_item(
+        &fixture,
+        CD_KIND_ID,
+        json!({"name": "Kind of Blue", "barcode": "5099706424420"}),
+    )
+    .await;
+    let unknown = create_item(
+        &fixture,
+        CD_KIND_ID,
+        json!({"name": "Demo Tape", "barcode": "0000000000000"}),
+    )
+    .await;
+    let no_barcode = create_item(&fixture, CD_KIND_ID, json!({"name": "Blue Train"})).await;
+    let complete = create_item(
+        &fixture,
+        CD_KIND_ID,
+        json!({
+            "name": "Giant Steps",
+            "barcode": "0081227136123",
+            "description": "John Coltrane",
+            "catalog_code": "SD 1311"
+        }),
+    )
+    .await;
+    let unsupported = create_item(
+        &fixture,
+        MISC_KIND_ID,
+        json!({"name": "Lamp", "barcode": "4006381333931"}),
+    )
+    .await;
+
+    let queued = enrich(
+        &fixture,
+        provider.clone(),
+        &[kind_of_blue, unknown, no_barcode, complete, unsupported],
+    )
+    .await;
+    assert!(queued["job_id"].is_string());
+    assert_eq!(
+        queued["queued"],
+        json!([kind_of_blue, unknown, unsupported])
+    );
+    assert_eq!(queued["skipped"], json!([no_barcode, complete]));
+    // Only kinds the provider covers are looked up
+    let mut asked = provider.asked.lock().unwrap().clone();
+    asked.sort();
+    assert_eq!(asked, ["0000000000000", "5099706424420"]);
+
+    let pending = suggestions(&fixture).await;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0]["item_id"], json!(kind_of_blue));
+    assert_eq!(pending[0]["item_name"], "Kind of Blue");
+    assert_eq!(pending[0]["provider"], "fake");
+    assert_eq!(
+        pending[0]["changes"],
+        json!({"description": "Miles Davis - Kind of Blue (1959)", "catalog_code": "CK 64935"})
+    );
+    assert_eq!(
+        pending[0]["current"],
+        json!({"description": null, "catalog_code": null})
+    );
+
+    // An item awaiting review is not looked up again
+    let again = enrich(&fixture, provider.clone(), &[kind_of_blue]).await;
+    assert!(again["job_id"].is_null());
+    assert_eq!(again["skipped"], json!([kind_of_blue]));
+
+    // Other organizations neither see nor decide the suggestion
+    let id = pending[0]["id"].as_str().unwrap();
+    let response = fixture
+        .ctx
+        .post(
+            &format!(
+                "/api/organizations/{}/items/suggestions/{}/apply",
+                fixture.org2_id, id
+            ),
+            &json!({}),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "item.suggestion_not_found");
+
+    let response = fixture
+        .ctx
+        .post(
+            &format!(
+                "/api/organizations/{}/items/suggestions/{}/apply",
+                fixture.org1_id, id
+            ),
+            &json!({}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(
+        response.body["description"],
+        "Miles Davis - Kind of Blue (1959)"
+    );
+    assert_eq!(response.body["catalog_code"], "CK 64935");
+    assert!(suggestions(&fixture).await.is_empty());
+
+    // The change is in the item's history
+    let response = fixture
+        .ctx
+        .get(
+            &format!(
+                "/api/organizations/{}/items/{}/history",
+                fixture.org1_id, kind_of_blue
+            ),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert!(response.body.to_string().contains("CK 64935"));
+
+    // A decided suggestion cannot be decided again
+    let response = fixture
+        .ctx
+        .post(
+            &format!(
+                "/api/organizations/{}/items/suggestions/{}/dismiss",
+                fixture.org1_id, id
+            ),
+            &json!({}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_dismissed_suggestion_leaves_the_item_unchanged() {
+    let fixture = TestFixture::new().await;
+    let provider = Arc::new(FakeProvider::default());
+
+    // Only the missing catalog code is suggested
+    let item_id = create_item(
+        &fixture,
+        CD_KIND_ID,
+        json!({"name": "Kind of Blue", "barcode": "5099706424420", "description": "Mono"}),
+    )
+    .await;
+    enrich(&fixture, provider, &[item_id]).await;
+
+    let pending = suggestions(&fixture).await;
+    assert_eq!(pending[0]["changes"], json!({"catalog_code": "CK 64935"}));
+    assert_eq!(pending[0]["current"]["description"], "Mono");
+
+    let response = fixture
+        .ctx
+        .post(
+            &format!(
+                "/api/organizations/{}/items/suggestions/{}/dismiss",
+                fixture.org1_id,
+                pending[0]["id"].as_str().unwrap()
+            ),
+            &json!({}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NO_CONTENT);
+    assert!(suggestions(&fixture).await.is_empty());
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/items/{}", fixture.org1_id, item_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    assert!(response.body["catalog_code"].is_null());
+}
+
+#[tokio::test]
+async fn test_enrich_checks_the_request() {
+    let fixture = TestFixture::new().await;
+    let path = format!("/api/organizations/{}/items/enrich", fixture.org1_id);
+
+    let response = fixture
+        .ctx
+        .post(&path, &json!({"item_ids": []}), Some(&fixture.user1_token))
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let too_many: Vec<Uuid> = (0..101).map(|_| Uuid::new_v4()).collect();
+    let response = fixture
+        .ctx
+        .post(
+            &path,
+            &json!({"item_ids": too_many}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,288 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestFixture;
+use serde_json::json;
+use uuid::Uuid;
+
+// Fixed UUIDs from seed migration
+const CD_KIND_ID: &str = "00000000-0000-0000-0000-000000000002";
+
+fn tag_names(body: &serde_json::Value) -> Vec<&str> {
+    body.as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_suggest_tags_prefix_fuzzy_and_usage_order() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+
+    for tag in ["jazz", "jazz-fusion", "jam", "rock"] {
+        fixture
+            .ctx
+            .post(
+                &format!("{}/tags", org_path),
+                &json!({"name": tag}),
+                Some(&fixture.user2_token),
+            )
+            .await
+            .assert_success();
+    }
+
+    for (name, tags) in [
+        ("Bitches Brew", vec!["jazz-fusion"]),
+        ("Heavy Weather", vec!["jazz-fusion"]),
+        ("Kind of Blue", vec!["jazz"]),
+    ] {
+        let created = fixture
+            .ctx
+            .post(
+                &format!("{}/items", org_path),
+                &json!({"kind_id": CD_KIND_ID, "name": name}),
+                Some(&fixture.user1_token),
+            )
+            .await;
+        created.assert_status(StatusCode::CREATED);
+        let item_id: Uuid = created.body["id"].as_str().unwrap().parse().unwrap();
+        for tag in tags {
+            sqlx::query(
+                "INSERT INTO item_tags (item_id, organization_id, tag_name) VALUES ($1, $2, $3)",
+            )
+            .bind(item_id)
+            .bind(fixture.org1_id)
+            .bind(tag)
+            .execute(&fixture.ctx.pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    // Prefix matches, most used first, case-insensitive
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/tags/suggest?q=JA", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(
+        tag_names(&response.body),
+        vec!["jazz-fusion", "jazz", "jam"]
+    );
+    assert_eq!(response.body[0]["usage_count"], 2);
+    assert_eq!(response.body[2]["usage_count"], 0);
+
+    // Typos still find similar tags
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/tags/suggest?q=jazzz", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(tag_names(&response.body), vec!["jazz-fusion", "jazz"]);
+
+    // LIKE wildcards are matched literally
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/tags/suggest?q=%25", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert!(tag_names(&response.body).is_empty());
+
+    // Limit caps the results
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/tags/suggest?q=&limit=2", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(tag_names(&response.body), vec!["jazz-fusion", "jazz"]);
+
+    // Other organizations' tags are never suggested
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/tags/suggest?q=ja", fixture.org2_id),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    response.assert_success();
+    assert!(tag_names(&response.body).is_empty());
+}
+
+#[tokio::test]
+async fn test_tag_create_list_and_duplicates() {
+    let fixture = TestFixture::new().await;
+    let tags_path = format!("/api/organizations/{}/tags", fixture.org1_id);
+
+    for tag in ["signed", "first-press"] {
+        let response = fixture
+            .ctx
+            .post(
+                &tags_path,
+                &json!({"name": tag}),
+                Some(&fixture.user2_token),
+            )
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        assert_eq!(response.body["name"], tag);
+        assert_eq!(
+            response.body["organization_id"],
+            fixture.org1_id.to_string()
+        );
+    }
+
+    let response = fixture
+        .ctx
+        .post(
+            &tags_path,
+            &json!({"name": "signed"}),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+    assert_eq!(response.body["error"], "tag.name_conflict");
+
+    // Members without catalog:write cannot manage tags
+    fixture
+        .ctx
+        .post(
+            &tags_path,
+            &json!({"name": "mine"}),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    fixture
+        .ctx
+        .delete(&format!("{}/signed", tags_path), Some(&fixture.user1_token))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let response = fixture
+        .ctx
+        .get(&tags_path, Some(&fixture.user1_token))
+        .await;
+    response.assert_success();
+    assert_eq!(tag_names(&response.body), vec!["first-press", "signed"]);
+
+    // Tag names are per organization
+    sqlx::query("INSERT INTO tags (organization_id, name) VALUES ($1, 'signed')")
+        .bind(fixture.org2_id)
+        .execute(&fixture.ctx.pool)
+        .await
+        .unwrap();
+    let response = fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/tags", fixture.org2_id),
+            Some(&fixture.user3_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(tag_names(&response.body), vec!["signed"]);
+
+    // ...and another organization's are out of reach
+    fixture
+        .ctx
+        .get(
+            &format!("/api/organizations/{}/tags", fixture.org2_id),
+            Some(&fixture.user1_token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    fixture
+        .ctx
+        .delete(
+            &format!("/api/organizations/{}/tags/signed", fixture.org2_id),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_delete_tag_in_use() {
+    let fixture = TestFixture::new().await;
+    let org_path = format!("/api/organizations/{}", fixture.org1_id);
+
+    fixture
+        .ctx
+        .post(
+            &format!("{}/tags", org_path),
+            &json!({"name": "signed"}),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::CREATED);
+    let created = fixture
+        .ctx
+        .post(
+            &format!("{}/items", org_path),
+            &json!({"kind_id": CD_KIND_ID, "name": "Blue"}),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    let item_id: Uuid = created.body["id"].as_str().unwrap().parse().unwrap();
+    sqlx::query(
+        "INSERT INTO item_tags (item_id, organization_id, tag_name) VALUES ($1, $2, 'signed')",
+    )
+    .bind(item_id)
+    .bind(fixture.org1_id)
+    .execute(&fixture.ctx.pool)
+    .await
+    .unwrap();
+
+    // Deleting a tag in use untags its items but keeps them
+    fixture
+        .ctx
+        .delete(
+            &format!("{}/tags/signed", org_path),
+            Some(&fixture.user2_token),
+        )
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/items/{}/full", org_path, item_id),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["tags"], json!([]));
+
+    let response = fixture
+        .ctx
+        .get(
+            &format!("{}/items?tags=signed", org_path),
+            Some(&fixture.user1_token),
+        )
+        .await;
+    response.assert_success();
+    assert_eq!(response.body["total"], 0);
+
+    let response = fixture
+        .ctx
+        .delete(
+            &format!("{}/tags/signed", org_path),
+            Some(&fixture.user2_token),
+        )
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.body["error"], "tag.not_found");
+}
@@ -2,19 +2,31 @@ use anyhow::{Result, anyhow};
 use argon2::{
     Argon2,
     password_hash::{
-        PasswordHash, PasswordHasher as ArgonHasher, PasswordVerifier, SaltString, rand_core::OsRng,
+        PasswordHash, PasswordHasher as ArgonHasher, PasswordVerifier, SaltString,
+        rand_core::{OsRng, RngCore},
     },
 };
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
+use crate::enums::{Permission, UserRole};
+use crate::permissions::Permissions;
+
+pub mod backend;
+#[cfg(feature = "ldap")]
+pub mod ldap;
+#[cfg(feature = "oidc")]
+pub mod oidc;
+
 /// The SYSTEM organization id (all-ones, ffffffff-ffff-ffff-ffff-ffffffffffff).
 ///
 /// Deliberately distinct from `Uuid::nil()` (all-zeros), which is reserved as the
 /// "no organization" sentinel used by an unauthenticated `AuthContext`. A user holding
-/// the `ADMIN` role with this org selected is treated as a system-wide super-admin.
+/// the `admin:users` permission with this org selected is treated as a system-wide
+/// super-admin.
 pub const SYSTEM_ORG_ID: Uuid = Uuid::from_bytes([0xFF; 16]);
 
 /// Password hashing utilities using Argon2
@@ -40,26 +52,215 @@ impl PasswordHasher {
             .verify_password(password.as_bytes(), &parsed_hash)
             .is_ok())
     }
+
+    /// Generate a random temporary password for an administrator reset
+    ///
+    /// Avoids look-alike characters (0/O, 1/l/I) since it is read out or copied by hand.
+    pub fn generate_temporary_password() -> String {
+        const ALPHABET: &[u8] = b"abcdefghijkmnpqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+        (0..TEMPORARY_PASSWORD_LENGTH)
+            .map(|_| ALPHABET[(OsRng.next_u32() as usize) % ALPHABET.len()] as char)
+            .collect()
+    }
+}
+
+/// Length of passwords from `PasswordHasher::generate_temporary_password`
+pub const TEMPORARY_PASSWORD_LENGTH: usize = 16;
+
+/// Consecutive failed password logins after which an account is locked
+pub const LOGIN_LOCKOUT_THRESHOLD: i32 = 5;
+
+/// Length of the first lock; each further failure after it ends doubles it
+pub const LOGIN_LOCKOUT_BASE_SECONDS: i64 = 60;
+
+/// Longest lock, reached after about ten further failures
+pub const LOGIN_LOCKOUT_MAX_SECONDS: i64 = 24 * 60 * 60;
+
+/// How long to lock an account after its `failed_attempts`-th consecutive
+/// failed login; None below the threshold
+pub fn login_lockout(failed_attempts: i32) -> Option<Duration> {
+    let over = failed_attempts - LOGIN_LOCKOUT_THRESHOLD;
+    if over < 0 {
+        return None;
+    }
+    let seconds = LOGIN_LOCKOUT_BASE_SECONDS
+        .saturating_mul(1 << over.min(32))
+        .min(LOGIN_LOCKOUT_MAX_SECONDS);
+    Some(Duration::seconds(seconds))
+}
+
+/// Request header carrying an API key, as an alternative to a bearer token
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Prefix of every API key, so a leaked key is easy to recognise
+pub const API_KEY_PREFIX: &str = "vsk_";
+
+/// Random characters in an API key after the prefix (about 238 bits)
+const API_KEY_RANDOM_LENGTH: usize = 40;
+
+/// Characters of an API key kept in the clear, to tell keys apart in listings
+const API_KEY_DISPLAY_LENGTH: usize = 12;
+
+/// Long-lived keys for scripts and other machine clients
+pub struct ApiKeys;
+
+impl ApiKeys {
+    /// Generate a new key. It is shown to its creator once; only its hash is kept.
+    pub fn generate() -> String {
+        const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+        let random: String = (0..API_KEY_RANDOM_LENGTH)
+            .map(|_| ALPHABET[(OsRng.next_u32() as usize) % ALPHABET.len()] as char)
+            .collect();
+        format!("{}{}", API_KEY_PREFIX, random)
+    }
+
+    /// The hash stored for a key. Keys are long and random, unlike passwords,
+    /// so a fast hash is safe and lets a presented key be looked up directly.
+    pub fn hash(key: &str) -> String {
+        hex::encode(Sha256::digest(key.as_bytes()))
+    }
+
+    /// The leading characters of a key, shown in place of the key itself
+    pub fn display_prefix(key: &str) -> String {
+        key.chars().take(API_KEY_DISPLAY_LENGTH).collect()
+    }
+}
+
+/// Issuer (`iss`) of every token this server signs
+pub const TOKEN_ISSUER: &str = "vostuff";
+
+/// Audience (`aud`) of every token this server signs
+pub const TOKEN_AUDIENCE: &str = "vostuff-api";
+
+/// What a token may be used for, carried in its `typ` claim.
+///
+/// All tokens share a signing key, so each validator checks the type it
+/// expects; a follow-on token can never pass as an access token, or vice versa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenType {
+    /// Session token for API requests
+    Access,
+    /// Exchanged once for an access token after choosing an organization
+    FollowOn,
+    /// Exchanged once for a new access and refresh token pair
+    Refresh,
+    /// Password reset link
+    Reset,
+    /// Organization invitation link
+    Invite,
+    /// Public read-only link to one item
+    Share,
+    /// Round trip through an external identity provider's login
+    OidcState,
 }
 
 /// JWT token claims for authenticated users
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
-    pub sub: Uuid,             // Subject (user ID)
-    pub identity: String,      // User identity (email)
-    pub organization_id: Uuid, // Selected organization
-    pub roles: Vec<String>,    // User roles in this organization
-    pub iat: i64,              // Issued at
-    pub exp: i64,              // Expiration time
+    pub sub: Uuid,                // Subject (user ID)
+    pub identity: String,         // User identity (email)
+    pub organization_id: Uuid,    // Selected organization
+    pub roles: Vec<UserRole>,     // User roles in this organization
+    pub permissions: Permissions, // User permissions in this organization
+    pub jti: Uuid,                // Token ID, recorded by the server on logout
+    pub sid: Uuid,                // Session: the login this token descends from
+    pub typ: TokenType,           // Always Access
+    pub aud: String,              // TOKEN_AUDIENCE
+    pub iss: String,              // TOKEN_ISSUER
+    pub iat: i64,                 // Issued at
+    pub exp: i64,                 // Expiration time
 }
 
-/// Follow-on token claims for org selection (short-lived)
+/// How long an access token is accepted; clients renew it with a refresh token
+pub const ACCESS_TOKEN_TTL_MINUTES: i64 = 15;
+
+/// How long a refresh token can be exchanged, if not revoked first
+pub const REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+
+/// How long an exchanged refresh token may be exchanged again, so parallel
+/// requests renewing one session do not count as token theft
+pub const REFRESH_TOKEN_REUSE_GRACE_SECONDS: i64 = 30;
+
+/// How long a follow-on token can be exchanged for a session token
+pub const FOLLOW_ON_TOKEN_TTL_MINUTES: i64 = 2;
+
+/// Follow-on token claims for org selection (short-lived, single-use)
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FollowOnClaims {
     pub sub: Uuid,        // Subject (user ID)
     pub identity: String, // User identity (email)
+    pub jti: Uuid,        // Token ID, recorded by the server when the token is used
+    pub typ: TokenType,   // Always FollowOn
+    pub aud: String,      // TOKEN_AUDIENCE
+    pub iss: String,      // TOKEN_ISSUER
     pub iat: i64,         // Issued at
-    pub exp: i64,         // Expiration time (5 minutes)
+    pub exp: i64,         // Expiration time (FOLLOW_ON_TOKEN_TTL_MINUTES)
+}
+
+/// Refresh token claims (long-lived, single-use, rotated on every refresh)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshClaims {
+    pub sub: Uuid,             // Subject (user ID)
+    pub organization_id: Uuid, // Organization the session is for
+    pub jti: Uuid,             // Token ID, tracked by the server for rotation and revocation
+    pub typ: TokenType,        // Always Refresh
+    pub aud: String,           // TOKEN_AUDIENCE
+    pub iss: String,           // TOKEN_ISSUER
+    pub iat: i64,              // Issued at
+    pub exp: i64,              // Expiration time (REFRESH_TOKEN_TTL_DAYS)
+}
+
+/// How long an organization invite can be accepted
+pub const INVITE_TOKEN_TTL_DAYS: i64 = 7;
+
+/// Invite link claims; the server keeps the invite itself, so revoking or
+/// accepting it stops the link working
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InviteClaims {
+    pub sub: Uuid,             // Subject (invite ID)
+    pub organization_id: Uuid, // Organization the invite is for
+    pub email: String,         // Address the invite was sent to
+    pub typ: TokenType,        // Always Invite
+    pub aud: String,           // TOKEN_AUDIENCE
+    pub iss: String,           // TOKEN_ISSUER
+    pub iat: i64,              // Issued at
+    pub exp: i64,              // Expiration time, the invite's expiry
+}
+
+/// How long a share link works unless the sharer chooses otherwise
+pub const SHARE_LINK_DEFAULT_TTL_HOURS: i64 = 7 * 24;
+
+/// Longest a share link can be made to work for
+pub const SHARE_LINK_MAX_TTL_HOURS: i64 = 30 * 24;
+
+/// Share link claims; the link shows the item as it is when opened, reduced
+/// to the chosen fields, and cannot be revoked before it expires
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShareClaims {
+    pub sub: Uuid,             // Subject (item ID)
+    pub organization_id: Uuid, // Organization the item belongs to
+    pub fields: Vec<String>,   // Item fields shown besides the name
+    pub typ: TokenType,        // Always Share
+    pub aud: String,           // TOKEN_AUDIENCE
+    pub iss: String,           // TOKEN_ISSUER
+    pub iat: i64,              // Issued at
+    pub exp: i64,              // Expiration time
+}
+
+/// How long a user has to sign in at the identity provider
+pub const OIDC_STATE_TTL_MINUTES: i64 = 10;
+
+/// OIDC `state` claims: the login attempt's nonce, carried through the
+/// identity provider and back so no server-side record is needed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OidcStateClaims {
+    pub nonce: String,  // Expected in the ID token's `nonce` claim
+    pub typ: TokenType, // Always OidcState
+    pub aud: String,    // TOKEN_AUDIENCE
+    pub iss: String,    // TOKEN_ISSUER
+    pub iat: i64,       // Issued at
+    pub exp: i64,       // Expiration time (OIDC_STATE_TTL_MINUTES)
 }
 
 /// JWT token manager
@@ -76,7 +277,9 @@ impl TokenManager {
         let decoding_key = DecodingKey::from_secret(secret.as_ref());
 
         let mut validation = Validation::new(Algorithm::HS256);
-        validation.set_required_spec_claims(&["exp", "sub", "iat"]);
+        validation.set_required_spec_claims(&["exp", "sub", "iat", "aud", "iss"]);
+        validation.set_audience(&[TOKEN_AUDIENCE]);
+        validation.set_issuer(&[TOKEN_ISSUER]);
 
         Self {
             encoding_key,
@@ -86,22 +289,33 @@ impl TokenManager {
     }
 
     /// Generate a JWT token for a user with selected organization
+    ///
+    /// `session_id` identifies the login, shared by every token renewed from it.
+    #[allow(clippy::too_many_arguments)]
     pub fn generate_token(
         &self,
         user_id: Uuid,
         identity: String,
         organization_id: Uuid,
-        roles: Vec<String>,
-        expires_in_hours: i64,
+        roles: Vec<UserRole>,
+        permissions: Permissions,
+        session_id: Uuid,
+        expires_in_minutes: i64,
     ) -> Result<String> {
         let now = Utc::now();
-        let exp = now + Duration::hours(expires_in_hours);
+        let exp = now + Duration::minutes(expires_in_minutes);
 
         let claims = Claims {
             sub: user_id,
             identity,
             organization_id,
             roles,
+            permissions,
+            jti: Uuid::new_v4(),
+            sid: session_id,
+            typ: TokenType::Access,
+            aud: TOKEN_AUDIENCE.to_string(),
+            iss: TOKEN_ISSUER.to_string(),
             iat: now.timestamp(),
             exp: exp.timestamp(),
         };
@@ -110,14 +324,18 @@ impl TokenManager {
             .map_err(|e| anyhow!("Failed to generate token: {}", e))
     }
 
-    /// Generate a follow-on token for org selection (5 minute expiry)
+    /// Generate a follow-on token for org selection (FOLLOW_ON_TOKEN_TTL_MINUTES expiry)
     pub fn generate_follow_on_token(&self, user_id: Uuid, identity: String) -> Result<String> {
         let now = Utc::now();
-        let exp = now + Duration::minutes(5);
+        let exp = now + Duration::minutes(FOLLOW_ON_TOKEN_TTL_MINUTES);
 
         let claims = FollowOnClaims {
             sub: user_id,
             identity,
+            jti: Uuid::new_v4(),
+            typ: TokenType::FollowOn,
+            aud: TOKEN_AUDIENCE.to_string(),
+            iss: TOKEN_ISSUER.to_string(),
             iat: now.timestamp(),
             exp: exp.timestamp(),
         };
@@ -126,19 +344,206 @@ impl TokenManager {
             .map_err(|e| anyhow!("Failed to generate follow-on token: {}", e))
     }
 
-    /// Validate a follow-on token
+    /// Generate a refresh token for a session (REFRESH_TOKEN_TTL_DAYS expiry).
+    ///
+    /// Returns the claims alongside the token so the caller can record its `jti`.
+    pub fn generate_refresh_token(
+        &self,
+        user_id: Uuid,
+        organization_id: Uuid,
+    ) -> Result<(String, RefreshClaims)> {
+        let now = Utc::now();
+        let exp = now + Duration::days(REFRESH_TOKEN_TTL_DAYS);
+
+        let claims = RefreshClaims {
+            sub: user_id,
+            organization_id,
+            jti: Uuid::new_v4(),
+            typ: TokenType::Refresh,
+            aud: TOKEN_AUDIENCE.to_string(),
+            iss: TOKEN_ISSUER.to_string(),
+            iat: now.timestamp(),
+            exp: exp.timestamp(),
+        };
+
+        let token = encode(&Header::default(), &claims, &self.encoding_key)
+            .map_err(|e| anyhow!("Failed to generate refresh token: {}", e))?;
+        Ok((token, claims))
+    }
+
+    /// Validate a refresh token's signature and expiry.
+    ///
+    /// Rotation and revocation are enforced by the caller, which tracks each `jti`.
+    pub fn validate_refresh_token(&self, token: &str) -> Result<RefreshClaims> {
+        let mut validation = self.validation.clone();
+        validation.set_required_spec_claims(&["exp", "sub", "iat", "aud", "iss", "jti"]);
+
+        let token_data = decode::<RefreshClaims>(token, &self.decoding_key, &validation)
+            .map_err(|e| anyhow!("Failed to validate refresh token: {}", e))?;
+
+        if token_data.claims.typ != TokenType::Refresh {
+            return Err(anyhow!(
+                "Failed to validate refresh token: wrong token type {:?}",
+                token_data.claims.typ
+            ));
+        }
+
+        Ok(token_data.claims)
+    }
+
+    /// Generate the link token for an invite, expiring with the invite
+    pub fn generate_invite_token(
+        &self,
+        invite_id: Uuid,
+        organization_id: Uuid,
+        email: String,
+        expires_at: DateTime<Utc>,
+    ) -> Result<String> {
+        let claims = InviteClaims {
+            sub: invite_id,
+            organization_id,
+            email,
+            typ: TokenType::Invite,
+            aud: TOKEN_AUDIENCE.to_string(),
+            iss: TOKEN_ISSUER.to_string(),
+            iat: Utc::now().timestamp(),
+            exp: expires_at.timestamp(),
+        };
+
+        encode(&Header::default(), &claims, &self.encoding_key)
+            .map_err(|e| anyhow!("Failed to generate invite token: {}", e))
+    }
+
+    /// Validate an invite token's signature and expiry.
+    ///
+    /// Whether the invite is still pending is checked by the caller.
+    pub fn validate_invite_token(&self, token: &str) -> Result<InviteClaims> {
+        let token_data = decode::<InviteClaims>(token, &self.decoding_key, &self.validation)
+            .map_err(|e| anyhow!("Failed to validate invite token: {}", e))?;
+
+        if token_data.claims.typ != TokenType::Invite {
+            return Err(anyhow!(
+                "Failed to validate invite token: wrong token type {:?}",
+                token_data.claims.typ
+            ));
+        }
+
+        Ok(token_data.claims)
+    }
+
+    /// Generate the token for a link sharing one item's `fields` until `expires_at`
+    pub fn generate_share_token(
+        &self,
+        item_id: Uuid,
+        organization_id: Uuid,
+        fields: Vec<String>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<String> {
+        let claims = ShareClaims {
+            sub: item_id,
+            organization_id,
+            fields,
+            typ: TokenType::Share,
+            aud: TOKEN_AUDIENCE.to_string(),
+            iss: TOKEN_ISSUER.to_string(),
+            iat: Utc::now().timestamp(),
+            exp: expires_at.timestamp(),
+        };
+
+        encode(&Header::default(), &claims, &self.encoding_key)
+            .map_err(|e| anyhow!("Failed to generate share token: {}", e))
+    }
+
+    /// Validate a share token's signature and expiry.
+    ///
+    /// Whether the item still exists is checked by the caller.
+    pub fn validate_share_token(&self, token: &str) -> Result<ShareClaims> {
+        let token_data = decode::<ShareClaims>(token, &self.decoding_key, &self.validation)
+            .map_err(|e| anyhow!("Failed to validate share token: {}", e))?;
+
+        if token_data.claims.typ != TokenType::Share {
+            return Err(anyhow!(
+                "Failed to validate share token: wrong token type {:?}",
+                token_data.claims.typ
+            ));
+        }
+
+        Ok(token_data.claims)
+    }
+
+    /// Generate the `state` for an OIDC login with a fresh nonce.
+    ///
+    /// Returns the state token and the nonce to send to the provider.
+    pub fn generate_oidc_state(&self) -> Result<(String, String)> {
+        let now = Utc::now();
+        let exp = now + Duration::minutes(OIDC_STATE_TTL_MINUTES);
+
+        let claims = OidcStateClaims {
+            nonce: Uuid::new_v4().simple().to_string(),
+            typ: TokenType::OidcState,
+            aud: TOKEN_AUDIENCE.to_string(),
+            iss: TOKEN_ISSUER.to_string(),
+            iat: now.timestamp(),
+            exp: exp.timestamp(),
+        };
+
+        let token = encode(&Header::default(), &claims, &self.encoding_key)
+            .map_err(|e| anyhow!("Failed to generate OIDC state: {}", e))?;
+        Ok((token, claims.nonce))
+    }
+
+    /// Validate an OIDC `state` returned by the provider's redirect
+    pub fn validate_oidc_state(&self, state: &str) -> Result<OidcStateClaims> {
+        let mut validation = self.validation.clone();
+        validation.set_required_spec_claims(&["exp", "iat", "aud", "iss"]);
+
+        let token_data = decode::<OidcStateClaims>(state, &self.decoding_key, &validation)
+            .map_err(|e| anyhow!("Failed to validate OIDC state: {}", e))?;
+
+        if token_data.claims.typ != TokenType::OidcState {
+            return Err(anyhow!(
+                "Failed to validate OIDC state: wrong token type {:?}",
+                token_data.claims.typ
+            ));
+        }
+
+        Ok(token_data.claims)
+    }
+
+    /// Validate a follow-on token's signature and expiry.
+    ///
+    /// Single use is enforced by the caller, which records each `jti` it accepts.
     pub fn validate_follow_on_token(&self, token: &str) -> Result<FollowOnClaims> {
-        let token_data = decode::<FollowOnClaims>(token, &self.decoding_key, &self.validation)
+        // No clock-skew leeway: these tokens only live for a couple of minutes
+        let mut validation = self.validation.clone();
+        validation.leeway = 0;
+        validation.set_required_spec_claims(&["exp", "sub", "iat", "aud", "iss", "jti"]);
+
+        let token_data = decode::<FollowOnClaims>(token, &self.decoding_key, &validation)
             .map_err(|e| anyhow!("Failed to validate follow-on token: {}", e))?;
 
+        if token_data.claims.typ != TokenType::FollowOn {
+            return Err(anyhow!(
+                "Failed to validate follow-on token: wrong token type {:?}",
+                token_data.claims.typ
+            ));
+        }
+
         Ok(token_data.claims)
     }
 
-    /// Validate and decode a JWT token
+    /// Validate and decode an access token; any other token type is rejected
     pub fn validate_token(&self, token: &str) -> Result<Claims> {
         let token_data = decode::<Claims>(token, &self.decoding_key, &self.validation)
             .map_err(|e| anyhow!("Failed to validate token: {}", e))?;
 
+        if token_data.claims.typ != TokenType::Access {
+            return Err(anyhow!(
+                "Failed to validate token: wrong token type {:?}",
+                token_data.claims.typ
+            ));
+        }
+
         Ok(token_data.claims)
     }
 }
@@ -149,8 +554,15 @@ pub struct AuthContext {
     pub user_id: Uuid,
     pub identity: String,
     pub organization_id: Uuid,
-    pub roles: Vec<String>,
+    pub roles: Vec<UserRole>,
+    pub permissions: Permissions,
     pub is_authenticated: bool,
+    /// The presented token's `jti`, `sid` and `exp`; nil and zero when unauthenticated
+    pub token_id: Uuid,
+    pub session_id: Uuid,
+    pub token_expires_at: i64,
+    /// Set when the request authenticated with an API key instead of a token
+    pub api_key_id: Option<Uuid>,
 }
 
 impl AuthContext {
@@ -161,7 +573,12 @@ impl AuthContext {
             identity: String::new(),
             organization_id: Uuid::nil(),
             roles: Vec::new(),
+            permissions: Permissions::default(),
             is_authenticated: false,
+            token_id: Uuid::nil(),
+            session_id: Uuid::nil(),
+            token_expires_at: 0,
+            api_key_id: None,
         }
     }
 
@@ -172,7 +589,36 @@ impl AuthContext {
             identity: claims.identity,
             organization_id: claims.organization_id,
             roles: claims.roles,
+            permissions: claims.permissions,
+            is_authenticated: true,
+            token_id: claims.jti,
+            session_id: claims.sid,
+            token_expires_at: claims.exp,
+            api_key_id: None,
+        }
+    }
+
+    /// Create an authenticated context for an API key, acting for its owner
+    /// with the key's roles and permissions. There is no token or session.
+    pub fn from_api_key(
+        api_key_id: Uuid,
+        user_id: Uuid,
+        identity: String,
+        organization_id: Uuid,
+        roles: Vec<UserRole>,
+        permissions: Permissions,
+    ) -> Self {
+        Self {
+            user_id,
+            identity,
+            organization_id,
+            roles,
+            permissions,
             is_authenticated: true,
+            token_id: Uuid::nil(),
+            session_id: Uuid::nil(),
+            token_expires_at: 0,
+            api_key_id: Some(api_key_id),
         }
     }
 
@@ -192,19 +638,24 @@ impl AuthContext {
     }
 
     /// Check if user has a specific role
-    pub fn has_role(&self, role: &str) -> bool {
-        self.is_authenticated && self.roles.contains(&role.to_string())
+    pub fn has_role(&self, role: UserRole) -> bool {
+        self.is_authenticated && self.roles.contains(&role)
     }
 
-    /// Check if user is admin
-    pub fn is_admin(&self) -> bool {
-        self.has_role("ADMIN")
+    /// Check if user has a specific permission in their selected organization
+    pub fn can(&self, permission: Permission) -> bool {
+        self.is_authenticated && self.permissions.contains(&permission)
+    }
+
+    /// Check if user may change the organization's data: any permission but `items:read`
+    pub fn can_write(&self) -> bool {
+        self.is_authenticated && self.permissions.allows_changes()
     }
 
     /// Check if user is a system-wide super-admin: authenticated, currently operating
-    /// with the SYSTEM org selected, and holding the ADMIN role there.
+    /// with the SYSTEM org selected, and holding `admin:users` there.
     pub fn is_system_admin(&self) -> bool {
-        self.is_authenticated && self.organization_id == SYSTEM_ORG_ID && self.has_role("ADMIN")
+        self.organization_id == SYSTEM_ORG_ID && self.can(Permission::AdminUsers)
     }
 }
 
@@ -212,6 +663,23 @@ impl AuthContext {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_login_lockout_doubles_up_to_the_maximum() {
+        assert_eq!(login_lockout(LOGIN_LOCKOUT_THRESHOLD - 1), None);
+        assert_eq!(
+            login_lockout(LOGIN_LOCKOUT_THRESHOLD),
+            Some(Duration::seconds(LOGIN_LOCKOUT_BASE_SECONDS))
+        );
+        assert_eq!(
+            login_lockout(LOGIN_LOCKOUT_THRESHOLD + 2),
+            Some(Duration::seconds(LOGIN_LOCKOUT_BASE_SECONDS * 4))
+        );
+        assert_eq!(
+            login_lockout(i32::MAX),
+            Some(Duration::seconds(LOGIN_LOCKOUT_MAX_SECONDS))
+        );
+    }
+
     #[test]
     fn test_password_hashing() {
         let password = "test_password_123";
@@ -224,17 +692,37 @@ mod tests {
         assert!(!PasswordHasher::verify_password("wrong_password", &hash).unwrap());
     }
 
+    #[test]
+    fn test_temporary_password() {
+        let first = PasswordHasher::generate_temporary_password();
+        let second = PasswordHasher::generate_temporary_password();
+
+        assert_eq!(first.len(), TEMPORARY_PASSWORD_LENGTH);
+        assert!(first.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert!(!first.contains(['0', 'O', '1', 'l', 'I']));
+        assert_ne!(first, second);
+    }
+
     #[test]
     fn test_jwt_token() {
         let manager = TokenManager::new("test_secret_key_for_testing");
         let user_id = Uuid::new_v4();
         let identity = "test@example.com".to_string();
         let org_id = Uuid::new_v4();
-        let roles = vec!["USER".to_string(), "ADMIN".to_string()];
+        let roles = vec![UserRole::User, UserRole::Admin];
+        let session_id = Uuid::new_v4();
 
         // Generate token
         let token = manager
-            .generate_token(user_id, identity.clone(), org_id, roles.clone(), 24)
+            .generate_token(
+                user_id,
+                identity.clone(),
+                org_id,
+                roles.clone(),
+                Permissions::for_roles(&roles),
+                session_id,
+                ACCESS_TOKEN_TTL_MINUTES,
+            )
             .unwrap();
 
         // Validate token
@@ -243,6 +731,106 @@ mod tests {
         assert_eq!(claims.identity, identity);
         assert_eq!(claims.organization_id, org_id);
         assert_eq!(claims.roles, roles);
+        assert_eq!(claims.permissions, Permissions::for_roles(&roles));
+        assert_eq!(claims.typ, TokenType::Access);
+        assert_eq!(claims.aud, TOKEN_AUDIENCE);
+        assert_eq!(claims.iss, TOKEN_ISSUER);
+        assert_eq!(claims.exp - claims.iat, ACCESS_TOKEN_TTL_MINUTES * 60);
+        assert_eq!(claims.sid, session_id);
+
+        // Each token has its own id, even within a session
+        let other = manager
+            .generate_token(
+                user_id,
+                identity.clone(),
+                org_id,
+                roles.clone(),
+                Permissions::for_roles(&roles),
+                session_id,
+                ACCESS_TOKEN_TTL_MINUTES,
+            )
+            .unwrap();
+        assert_ne!(manager.validate_token(&other).unwrap().jti, claims.jti);
+
+        // A follow-on token is not an access token
+        let follow_on = manager.generate_follow_on_token(user_id, identity).unwrap();
+        assert!(manager.validate_token(&follow_on).is_err());
+    }
+
+    #[test]
+    fn test_token_type_audience_and_issuer_checked() {
+        let secret = "test_secret_key_for_testing";
+        let manager = TokenManager::new(secret);
+        let now = Utc::now().timestamp();
+        let sign = |typ, aud: &str, iss: &str| {
+            let claims = Claims {
+                sub: Uuid::new_v4(),
+                identity: "test@example.com".to_string(),
+                organization_id: Uuid::new_v4(),
+                roles: vec![UserRole::Admin],
+                permissions: Permissions::for_roles(&[UserRole::Admin]),
+                jti: Uuid::new_v4(),
+                sid: Uuid::new_v4(),
+                typ,
+                aud: aud.to_string(),
+                iss: iss.to_string(),
+                iat: now,
+                exp: now + 3600,
+            };
+            encode(
+                &Header::default(),
+                &claims,
+                &EncodingKey::from_secret(secret.as_ref()),
+            )
+            .unwrap()
+        };
+
+        let valid = sign(TokenType::Access, TOKEN_AUDIENCE, TOKEN_ISSUER);
+        assert!(manager.validate_token(&valid).is_ok());
+
+        // Access-shaped claims with another type, audience or issuer are refused
+        for typ in [
+            TokenType::FollowOn,
+            TokenType::Refresh,
+            TokenType::Reset,
+            TokenType::Invite,
+            TokenType::OidcState,
+        ] {
+            let token = sign(typ, TOKEN_AUDIENCE, TOKEN_ISSUER);
+            assert!(manager.validate_token(&token).is_err(), "{:?}", typ);
+        }
+        let other_audience = sign(TokenType::Access, "some-other-api", TOKEN_ISSUER);
+        assert!(manager.validate_token(&other_audience).is_err());
+        let other_issuer = sign(TokenType::Access, TOKEN_AUDIENCE, "someone-else");
+        assert!(manager.validate_token(&other_issuer).is_err());
+    }
+
+    #[test]
+    fn test_unknown_role_in_token_rejected() {
+        let secret = "test_secret_key_for_testing";
+        let manager = TokenManager::new(secret);
+        let now = Utc::now().timestamp();
+        let claims = serde_json::json!({
+            "sub": Uuid::new_v4(),
+            "identity": "test@example.com",
+            "organization_id": Uuid::new_v4(),
+            "roles": ["SUPERUSER"],
+            "permissions": ["items:read"],
+            "jti": Uuid::new_v4(),
+            "sid": Uuid::new_v4(),
+            "typ": TokenType::Access,
+            "aud": TOKEN_AUDIENCE,
+            "iss": TOKEN_ISSUER,
+            "iat": now,
+            "exp": now + 3600,
+        });
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret.as_ref()),
+        )
+        .unwrap();
+        assert!(manager.validate_token(&token).is_err());
     }
 
     #[test]
@@ -260,6 +848,165 @@ mod tests {
         let claims = manager.validate_follow_on_token(&token).unwrap();
         assert_eq!(claims.sub, user_id);
         assert_eq!(claims.identity, identity);
+        assert_eq!(claims.exp - claims.iat, FOLLOW_ON_TOKEN_TTL_MINUTES * 60);
+
+        // Each token has its own id
+        let other = manager
+            .generate_follow_on_token(user_id, identity.clone())
+            .unwrap();
+        assert_ne!(
+            manager.validate_follow_on_token(&other).unwrap().jti,
+            claims.jti
+        );
+
+        // A session token cannot stand in for a follow-on token
+        let session = manager
+            .generate_token(
+                user_id,
+                identity,
+                Uuid::new_v4(),
+                vec![],
+                Permissions::default(),
+                Uuid::new_v4(),
+                24,
+            )
+            .unwrap();
+        assert!(manager.validate_follow_on_token(&session).is_err());
+    }
+
+    #[test]
+    fn test_invite_token() {
+        let manager = TokenManager::new("test_secret_key_for_testing");
+        let invite_id = Uuid::new_v4();
+        let org_id = Uuid::new_v4();
+        let expires_at = Utc::now() + Duration::days(INVITE_TOKEN_TTL_DAYS);
+
+        let token = manager
+            .generate_invite_token(invite_id, org_id, "new@example.com".to_string(), expires_at)
+            .unwrap();
+        let claims = manager.validate_invite_token(&token).unwrap();
+        assert_eq!(claims.sub, invite_id);
+        assert_eq!(claims.organization_id, org_id);
+        assert_eq!(claims.email, "new@example.com");
+        assert_eq!(claims.exp, expires_at.timestamp());
+
+        // An invite is not a session, and a session is not an invite
+        assert!(manager.validate_token(&token).is_err());
+        let follow_on = manager
+            .generate_follow_on_token(Uuid::new_v4(), "new@example.com".to_string())
+            .unwrap();
+        assert!(manager.validate_invite_token(&follow_on).is_err());
+
+        // Expired invites are refused
+        let expired = manager
+            .generate_invite_token(
+                invite_id,
+                org_id,
+                "new@example.com".to_string(),
+                Utc::now() - Duration::days(1),
+            )
+            .unwrap();
+        assert!(manager.validate_invite_token(&expired).is_err());
+    }
+
+    #[test]
+    fn test_share_token() {
+        let manager = TokenManager::new("test_secret_key_for_testing");
+        let item_id = Uuid::new_v4();
+        let org_id = Uuid::new_v4();
+        let fields = vec!["description".to_string(), "soft_fields".to_string()];
+        let expires_at = Utc::now() + Duration::hours(SHARE_LINK_DEFAULT_TTL_HOURS);
+
+        let token = manager
+            .generate_share_token(item_id, org_id, fields.clone(), expires_at)
+            .unwrap();
+        let claims = manager.validate_share_token(&token).unwrap();
+        assert_eq!(claims.sub, item_id);
+        assert_eq!(claims.organization_id, org_id);
+        assert_eq!(claims.fields, fields);
+        assert_eq!(claims.exp, expires_at.timestamp());
+
+        // A share link is not a session or an invite, and an invite is not a share link
+        assert!(manager.validate_token(&token).is_err());
+        assert!(manager.validate_invite_token(&token).is_err());
+        let invite = manager
+            .generate_invite_token(item_id, org_id, "new@example.com".to_string(), expires_at)
+            .unwrap();
+        assert!(manager.validate_share_token(&invite).is_err());
+
+        // Expired links are refused
+        let expired = manager
+            .generate_share_token(item_id, org_id, fields, Utc::now() - Duration::hours(1))
+            .unwrap();
+        assert!(manager.validate_share_token(&expired).is_err());
+    }
+
+    #[test]
+    fn test_refresh_token() {
+        let manager = TokenManager::new("test_secret_key_for_testing");
+        let user_id = Uuid::new_v4();
+        let org_id = Uuid::new_v4();
+
+        let (token, issued) = manager.generate_refresh_token(user_id, org_id).unwrap();
+        let claims = manager.validate_refresh_token(&token).unwrap();
+        assert_eq!(claims.sub, user_id);
+        assert_eq!(claims.organization_id, org_id);
+        assert_eq!(claims.jti, issued.jti);
+        assert_eq!(
+            claims.exp - claims.iat,
+            REFRESH_TOKEN_TTL_DAYS * 24 * 60 * 60
+        );
+
+        // Refresh and access tokens cannot stand in for each other
+        assert!(manager.validate_token(&token).is_err());
+        let session = manager
+            .generate_token(
+                user_id,
+                "test@example.com".to_string(),
+                org_id,
+                vec![],
+                Permissions::default(),
+                Uuid::new_v4(),
+                15,
+            )
+            .unwrap();
+        assert!(manager.validate_refresh_token(&session).is_err());
+    }
+
+    #[test]
+    fn test_oidc_state() {
+        let manager = TokenManager::new("test_secret_key_for_testing");
+
+        let (state, nonce) = manager.generate_oidc_state().unwrap();
+        let claims = manager.validate_oidc_state(&state).unwrap();
+        assert_eq!(claims.nonce, nonce);
+        assert_eq!(claims.exp - claims.iat, OIDC_STATE_TTL_MINUTES * 60);
+        assert_ne!(manager.generate_oidc_state().unwrap().1, nonce);
+
+        // Only this server's signature is accepted, and no other token type
+        let other = TokenManager::new("another_secret");
+        assert!(other.validate_oidc_state(&state).is_err());
+        let follow_on = manager
+            .generate_follow_on_token(Uuid::new_v4(), "test@example.com".to_string())
+            .unwrap();
+        assert!(manager.validate_oidc_state(&follow_on).is_err());
+        assert!(manager.validate_token(&state).is_err());
+    }
+
+    #[test]
+    fn test_api_keys() {
+        let key = ApiKeys::generate();
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert_eq!(key.len(), API_KEY_PREFIX.len() + API_KEY_RANDOM_LENGTH);
+        assert_ne!(ApiKeys::generate(), key);
+
+        let hash = ApiKeys::hash(&key);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(ApiKeys::hash(&key), hash);
+        assert_ne!(ApiKeys::hash(&ApiKeys::generate()), hash);
+
+        assert!(key.starts_with(&ApiKeys::display_prefix(&key)));
+        assert_eq!(ApiKeys::display_prefix(&key).len(), API_KEY_DISPLAY_LENGTH);
     }
 
     #[test]
@@ -271,17 +1018,48 @@ mod tests {
             user_id: Uuid::new_v4(),
             identity: "test@example.com".to_string(),
             organization_id: org_id,
-            roles: vec!["USER".to_string(), "ADMIN".to_string()],
+            roles: vec![UserRole::User, UserRole::Admin],
+            permissions: Permissions::for_roles(&[UserRole::User, UserRole::Admin]),
             is_authenticated: true,
+            token_id: Uuid::new_v4(),
+            session_id: Uuid::new_v4(),
+            token_expires_at: Utc::now().timestamp() + 60,
+            api_key_id: None,
         };
 
         assert!(context.has_org_access(org_id));
         assert!(!context.has_org_access(other_org_id));
         assert_eq!(context.organization_id(), org_id);
         assert!(context.is_authenticated());
-        assert!(context.has_role("USER"));
-        assert!(context.has_role("ADMIN"));
-        assert!(!context.has_role("SUPERUSER"));
-        assert!(context.is_admin());
+        assert!(context.has_role(UserRole::User));
+        assert!(context.has_role(UserRole::Admin));
+        assert!(!context.has_role(UserRole::LoanManager));
+        assert!(context.can(Permission::SettingsWrite));
+        assert!(context.can_write());
+        assert!(!context.is_system_admin());
+
+        let viewer = AuthContext {
+            roles: vec![UserRole::Viewer],
+            permissions: Permissions::for_roles(&[UserRole::Viewer]),
+            ..context.clone()
+        };
+        assert!(viewer.has_org_access(org_id));
+        assert!(viewer.can(Permission::ItemsRead));
+        assert!(!viewer.can_write());
+
+        // Permissions, not roles, decide what a member may do
+        let custom = AuthContext {
+            roles: vec![UserRole::User],
+            permissions: vec![Permission::ItemsRead, Permission::HistoryExport].into(),
+            ..context.clone()
+        };
+        assert!(custom.can(Permission::HistoryExport));
+        assert!(!custom.can(Permission::ItemsWrite));
+
+        let system_admin = AuthContext {
+            organization_id: SYSTEM_ORG_ID,
+            ..context
+        };
+        assert!(system_admin.is_system_admin());
     }
 }
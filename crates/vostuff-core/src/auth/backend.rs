@@ -0,0 +1,84 @@
+//! Password login backends.
+//!
+//! A password login names an existing user by their identity; how the
+//! password is checked depends on the deployment. Each [`AuthBackend`] either
+//! decides the login or passes it on, and the API tries them in order: a user
+//! with a password stored in VOStuff is checked by [`PasswordBackend`], and
+//! anyone else can be checked against a directory such as LDAP or Active
+//! Directory (`auth::ldap`, behind the `ldap` feature).
+//!
+//! Backends only check credentials. Accounts, memberships, lockout and
+//! deactivation stay in VOStuff whichever backend accepts the password.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::PasswordHasher;
+
+/// The user a password login is for, as stored in VOStuff
+#[derive(Debug, Clone, Copy)]
+pub struct LoginAccount<'a> {
+    pub identity: &'a str,
+    /// None when the user has no VOStuff password, such as an OIDC or
+    /// directory user
+    pub password_hash: Option<&'a str>,
+}
+
+/// A backend's answer for a login
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// The password is right
+    Accepted,
+    /// The password is wrong
+    Rejected,
+    /// This backend does not hold the user's password; ask the next one
+    NotApplicable,
+}
+
+/// Checks the password of a login
+#[async_trait]
+pub trait AuthBackend: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &str;
+
+    async fn verify(&self, account: &LoginAccount<'_>, password: &str) -> Result<Verdict>;
+}
+
+/// Checks the Argon2 hash stored with the user; passes on users without one
+pub struct PasswordBackend;
+
+#[async_trait]
+impl AuthBackend for PasswordBackend {
+    fn name(&self) -> &str {
+        "password"
+    }
+
+    async fn verify(&self, account: &LoginAccount<'_>, password: &str) -> Result<Verdict> {
+        let Some(hash) = account.password_hash else {
+            return Ok(Verdict::NotApplicable);
+        };
+        Ok(if PasswordHasher::verify_password(password, hash)? {
+            Verdict::Accepted
+        } else {
+            Verdict::Rejected
+        })
+    }
+}
+
+/// Ask each backend in turn; the first that does not pass decides.
+/// NotApplicable when none holds the user's password.
+pub async fn verify(
+    backends: &[Arc<dyn AuthBackend>],
+    account: &LoginAccount<'_>,
+    password: &str,
+) -> Result<Verdict> {
+    for backend in backends {
+        match backend.verify(account, password).await? {
+            Verdict::NotApplicable => continue,
+            verdict => return Ok(verdict),
+        }
+    }
+    Ok(Verdict::NotApplicable)
+}
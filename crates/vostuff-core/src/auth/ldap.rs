@@ -0,0 +1,225 @@
+//! Password login against an LDAP directory or Active Directory.
+//!
+//! Users keep their directory password: a login binds to the directory as the
+//! user, and is accepted if the bind succeeds. The user's DN comes either from
+//! a template (`[ldap] user_dn`, such as `uid={identity},ou=people,dc=example,dc=com`,
+//! or `{identity}` for Active Directory's `user@domain` names) or from a search
+//! under `[ldap] base_dn` made with a service account. Accounts must still exist
+//! in VOStuff, created by an admin or through an invite, with the identity
+//! the directory knows them by.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use ldap3::{Ldap, LdapConnAsync, LdapConnSettings, Scope, SearchEntry, dn_escape, ldap_escape};
+
+use super::backend::{AuthBackend, LoginAccount, Verdict};
+use crate::config::{Config, LDAP_IDENTITY_PLACEHOLDER};
+
+/// Placeholder for the user's identity in `[ldap] user_dn` and `user_filter`
+pub const IDENTITY_PLACEHOLDER: &str = LDAP_IDENTITY_PLACEHOLDER;
+
+/// Filter used to find users when `[ldap] user_filter` is unset
+const DEFAULT_USER_FILTER: &str = "(mail={identity})";
+
+/// LDAP result code for a wrong password, or a DN that does not exist
+const INVALID_CREDENTIALS: u32 = 49;
+
+/// How a login's identity becomes the DN to bind as
+#[derive(Debug, Clone)]
+pub enum UserLookup {
+    /// Substitute the identity into a DN template
+    Template(String),
+    /// Bind as a service account and search for the user's entry
+    Search {
+        base_dn: String,
+        /// Filter with the identity substituted; must match exactly one entry
+        filter: String,
+        bind_dn: String,
+        bind_password: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct LdapConfig {
+    /// `ldap://` or `ldaps://` server URL
+    pub url: String,
+    /// Upgrade an `ldap://` connection with StartTLS
+    pub starttls: bool,
+    pub lookup: UserLookup,
+    /// Connection and operation timeout
+    pub timeout: Duration,
+}
+
+impl LdapConfig {
+    /// The directory in the `[ldap]` settings, which [`Config::load`] has
+    /// checked; None when LDAP login is off
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let ldap = &config.ldap;
+        let url = ldap.url.clone()?;
+        let lookup = match &ldap.user_dn {
+            Some(template) => UserLookup::Template(template.clone()),
+            None => UserLookup::Search {
+                base_dn: ldap.base_dn.clone().unwrap_or_default(),
+                filter: ldap
+                    .user_filter
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_USER_FILTER.to_string()),
+                bind_dn: ldap.bind_dn.clone().unwrap_or_default(),
+                bind_password: ldap.bind_password.clone().unwrap_or_default(),
+            },
+        };
+        Some(Self {
+            url,
+            starttls: ldap.starttls,
+            lookup,
+            timeout: Duration::from_secs(ldap.timeout_seconds),
+        })
+    }
+}
+
+/// Checks passwords by binding to the directory as the user
+pub struct LdapBackend {
+    config: LdapConfig,
+}
+
+impl LdapBackend {
+    pub fn new(config: LdapConfig) -> Self {
+        Self { config }
+    }
+
+    async fn connect(&self) -> Result<Ldap> {
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(self.config.timeout)
+            .set_starttls(self.config.starttls);
+        let (conn, ldap) = LdapConnAsync::with_settings(settings, &self.config.url)
+            .await
+            .context("Connecting to the LDAP server")?;
+        ldap3::drive!(conn);
+        Ok(ldap)
+    }
+
+    /// The DN of the user named by `identity`; None when a search finds no
+    /// single entry for them
+    async fn user_dn(&self, identity: &str) -> Result<Option<String>> {
+        let (base_dn, filter, bind_dn, bind_password) = match &self.config.lookup {
+            UserLookup::Template(template) => {
+                return Ok(Some(
+                    template.replace(IDENTITY_PLACEHOLDER, &dn_escape(identity)),
+                ));
+            }
+            UserLookup::Search {
+                base_dn,
+                filter,
+                bind_dn,
+                bind_password,
+            } => (base_dn, filter, bind_dn, bind_password),
+        };
+
+        let mut ldap = self.connect().await?;
+        ldap.with_timeout(self.config.timeout)
+            .simple_bind(bind_dn, bind_password)
+            .await?
+            .success()
+            .context("Binding as LDAP_BIND_DN")?;
+        let (entries, _) = ldap
+            .with_timeout(self.config.timeout)
+            .search(
+                base_dn,
+                Scope::Subtree,
+                &user_filter(filter, identity),
+                vec!["1.1"],
+            )
+            .await?
+            .success()?;
+        let _ = ldap.unbind().await;
+
+        // An identity matching several entries names no one in particular
+        Ok(match <[_; 1]>::try_from(entries) {
+            Ok([entry]) => Some(SearchEntry::construct(entry).dn),
+            Err(_) => None,
+        })
+    }
+
+    /// Bind as `dn`; Ok(false) when the directory rejects the password
+    async fn bind(&self, dn: &str, password: &str) -> Result<bool> {
+        let mut ldap = self.connect().await?;
+        let result = ldap
+            .with_timeout(self.config.timeout)
+            .simple_bind(dn, password)
+            .await?;
+        let _ = ldap.unbind().await;
+        if result.rc == INVALID_CREDENTIALS {
+            return Ok(false);
+        }
+        result.success()?;
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl AuthBackend for LdapBackend {
+    fn name(&self) -> &str {
+        "ldap"
+    }
+
+    async fn verify(&self, account: &LoginAccount<'_>, password: &str) -> Result<Verdict> {
+        // An empty password would be an anonymous bind, which many servers accept
+        if password.is_empty() {
+            return Ok(Verdict::Rejected);
+        }
+        let Some(dn) = self.user_dn(account.identity).await? else {
+            return Ok(Verdict::Rejected);
+        };
+        Ok(if self.bind(&dn, password).await? {
+            Verdict::Accepted
+        } else {
+            Verdict::Rejected
+        })
+    }
+}
+
+/// The search filter with the identity substituted, escaped as a filter value
+fn user_filter(filter: &str, identity: &str) -> String {
+    filter.replace(IDENTITY_PLACEHOLDER, &ldap_escape(identity))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backend(lookup: UserLookup) -> LdapBackend {
+        LdapBackend::new(LdapConfig {
+            url: "ldap://localhost".into(),
+            starttls: false,
+            lookup,
+            timeout: Duration::from_secs(1),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_identity_is_escaped_in_dn_template() {
+        let ldap = backend(UserLookup::Template(
+            "uid={identity},ou=people,dc=example,dc=com".into(),
+        ));
+        assert_eq!(
+            ldap.user_dn("smith, j").await.unwrap().unwrap(),
+            "uid=smith\\2c j,ou=people,dc=example,dc=com"
+        );
+        // Active Directory user principal names are bound as is
+        let ldap = backend(UserLookup::Template("{identity}".into()));
+        assert_eq!(
+            ldap.user_dn("jo@corp.example.com").await.unwrap().unwrap(),
+            "jo@corp.example.com"
+        );
+    }
+
+    #[test]
+    fn test_identity_cannot_widen_search_filter() {
+        assert_eq!(
+            user_filter("(mail={identity})", "*)(uid=*"),
+            "(mail=\\2a\\29\\28uid=\\2a)"
+        );
+    }
+}
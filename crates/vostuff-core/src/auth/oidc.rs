@@ -0,0 +1,274 @@
+//! Login through an external OpenID Connect identity provider.
+//!
+//! Any provider that publishes discovery metadata works: Google, or a
+//! self-hosted one such as Keycloak or Authentik. The user signs in at the
+//! provider, which redirects back with a code. The code is exchanged for an ID
+//! token whose subject (or verified email) names the user; from there login
+//! carries on as with a password and issues the same tokens.
+
+use std::sync::RwLock;
+
+use anyhow::{Context, Result, anyhow, bail};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header, jwk::JwkSet};
+use serde::Deserialize;
+
+pub use crate::config::IdentityClaim;
+use crate::config::{Config, OidcProviderKind};
+
+/// Issuer used when `[oidc] provider` is `google`
+pub const GOOGLE_ISSUER: &str = "https://accounts.google.com";
+
+/// Scopes requested from the provider
+const SCOPES: &str = "openid email profile";
+
+/// Signature algorithms accepted on ID tokens; symmetric ones are never
+/// accepted, as the key would be the client secret
+const ID_TOKEN_ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    /// Issuer URL; discovery metadata is read from
+    /// `<issuer>/.well-known/openid-configuration`
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Where the provider sends the user back to, registered with the provider
+    pub redirect_url: String,
+    pub identity_claim: IdentityClaim,
+    /// Provider name shown on the login page
+    pub display_name: String,
+}
+
+impl OidcConfig {
+    /// The provider in the `[oidc]` settings, which [`Config::load`] has
+    /// checked; None when OIDC login is off
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let oidc = &config.oidc;
+        let client_id = oidc.client_id.clone()?;
+        let (default_issuer, default_name) = match oidc.provider {
+            OidcProviderKind::Google => (GOOGLE_ISSUER, "Google"),
+            OidcProviderKind::Generic => ("", "Single Sign-On"),
+        };
+        Some(Self {
+            issuer: oidc
+                .issuer
+                .clone()
+                .unwrap_or_else(|| default_issuer.to_string()),
+            client_id,
+            client_secret: oidc.client_secret.clone().unwrap_or_default(),
+            redirect_url: oidc.redirect_url.clone().unwrap_or_default(),
+            identity_claim: oidc.identity_claim,
+            display_name: oidc
+                .display_name
+                .clone()
+                .unwrap_or_else(|| default_name.to_string()),
+        })
+    }
+}
+
+/// The parts of the provider's discovery document used here
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    #[serde(default)]
+    nonce: Option<String>,
+    #[serde(default)]
+    email: Option<String>,
+    /// A boolean, though some providers send the string "true"
+    #[serde(default)]
+    email_verified: Option<serde_json::Value>,
+}
+
+/// A configured provider, with its discovery metadata and signing keys
+pub struct OidcProvider {
+    config: OidcConfig,
+    metadata: ProviderMetadata,
+    http: reqwest::Client,
+    /// Cached signing keys, re-fetched when a token names an unknown key
+    jwks: RwLock<JwkSet>,
+}
+
+impl OidcProvider {
+    /// Fetch the provider's discovery metadata and signing keys
+    pub async fn discover(config: OidcConfig) -> Result<Self> {
+        let http = reqwest::Client::new();
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            config.issuer.trim_end_matches('/')
+        );
+        let metadata: ProviderMetadata = get_json(&http, &url)
+            .await
+            .context("Failed to read OIDC discovery metadata")?;
+        if metadata.issuer.trim_end_matches('/') != config.issuer.trim_end_matches('/') {
+            bail!(
+                "OIDC discovery issuer '{}' does not match configured issuer '{}'",
+                metadata.issuer,
+                config.issuer
+            );
+        }
+        let jwks = get_json(&http, &metadata.jwks_uri)
+            .await
+            .context("Failed to read OIDC signing keys")?;
+
+        Ok(Self {
+            config,
+            metadata,
+            http,
+            jwks: RwLock::new(jwks),
+        })
+    }
+
+    pub fn display_name(&self) -> &str {
+        &self.config.display_name
+    }
+
+    pub fn identity_claim(&self) -> IdentityClaim {
+        self.config.identity_claim
+    }
+
+    /// The provider URL to send the user to, carrying `state` and `nonce`
+    pub fn authorization_url(&self, state: &str, nonce: &str) -> Result<String> {
+        let url = reqwest::Url::parse_with_params(
+            &self.metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", self.config.redirect_url.as_str()),
+                ("scope", SCOPES),
+                ("state", state),
+                ("nonce", nonce),
+            ],
+        )
+        .context("Invalid OIDC authorization endpoint")?;
+        Ok(url.into())
+    }
+
+    /// Exchange an authorization code and return the identity it proves, as
+    /// selected by `identity_claim`. `nonce` is the one sent with the request.
+    pub async fn authenticate(&self, code: &str, nonce: &str) -> Result<String> {
+        let response = self
+            .http
+            .post(&self.metadata.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", self.config.redirect_url.as_str()),
+                ("client_id", self.config.client_id.as_str()),
+                ("client_secret", self.config.client_secret.as_str()),
+            ])
+            .send()
+            .await
+            .context("OIDC token request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("OIDC token request rejected: {} {}", status, body);
+        }
+        let tokens: TokenResponse = response
+            .json()
+            .await
+            .context("Invalid OIDC token response")?;
+
+        let claims = self.verify_id_token(&tokens.id_token).await?;
+        if claims.nonce.as_deref() != Some(nonce) {
+            bail!("OIDC ID token nonce does not match the login request");
+        }
+
+        match self.config.identity_claim {
+            IdentityClaim::Subject => Ok(claims.sub),
+            IdentityClaim::Email => {
+                let verified = matches!(claims.email_verified, Some(serde_json::Value::Bool(true)))
+                    || matches!(
+                        claims.email_verified.as_ref().and_then(|v| v.as_str()),
+                        Some("true")
+                    );
+                match claims.email {
+                    Some(email) if verified => Ok(email),
+                    Some(_) => bail!("OIDC email address is not verified"),
+                    None => bail!("OIDC ID token has no email; is the email scope allowed?"),
+                }
+            }
+        }
+    }
+
+    /// Check an ID token's signature, issuer, audience and expiry
+    async fn verify_id_token(&self, id_token: &str) -> Result<IdTokenClaims> {
+        let header = decode_header(id_token).context("Malformed OIDC ID token")?;
+        if !ID_TOKEN_ALGORITHMS.contains(&header.alg) {
+            bail!("OIDC ID token uses unsupported algorithm {:?}", header.alg);
+        }
+
+        let key = match self.signing_key(header.kid.as_deref())? {
+            Some(key) => key,
+            None => {
+                // The provider may have rotated its keys since we last looked
+                let jwks = get_json(&self.http, &self.metadata.jwks_uri)
+                    .await
+                    .context("Failed to refresh OIDC signing keys")?;
+                *self
+                    .jwks
+                    .write()
+                    .map_err(|_| anyhow!("OIDC key cache poisoned"))? = jwks;
+                self.signing_key(header.kid.as_deref())?
+                    .ok_or_else(|| anyhow!("OIDC ID token signed with an unknown key"))?
+            }
+        };
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_required_spec_claims(&["exp", "iat", "iss", "aud", "sub"]);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_issuer(&[&self.metadata.issuer]);
+
+        let data = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .context("OIDC ID token rejected")?;
+        Ok(data.claims)
+    }
+
+    /// The cached key with this id; with no id, the only key if there is one
+    fn signing_key(&self, kid: Option<&str>) -> Result<Option<DecodingKey>> {
+        let jwks = self
+            .jwks
+            .read()
+            .map_err(|_| anyhow!("OIDC key cache poisoned"))?;
+        let jwk = match kid {
+            Some(kid) => jwks.find(kid),
+            None if jwks.keys.len() == 1 => jwks.keys.first(),
+            None => None,
+        };
+        jwk.map(|jwk| DecodingKey::from_jwk(jwk).context("Unusable OIDC signing key"))
+            .transpose()
+    }
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(http: &reqwest::Client, url: &str) -> Result<T> {
+    Ok(http
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
@@ -0,0 +1,269 @@
+//! Enums stored in the database as strings.
+//!
+//! Each enum's wire form (JSON and API query parameters) is the same string
+//! as its database value, so one table per enum drives serde, `Display` and
+//! `FromStr`. Convert with `as_str()` / `parse()` rather than writing another
+//! `match` in a handler or component.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use utoipa::ToSchema;
+
+/// A string that names no variant of the enum being parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownVariant {
+    pub enum_name: &'static str,
+    pub value: String,
+}
+
+impl fmt::Display for UnknownVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown {} '{}'", self.enum_name, self.value)
+    }
+}
+
+impl std::error::Error for UnknownVariant {}
+
+macro_rules! db_enum {
+    (
+        $(#[doc = $enum_doc:tt])*
+        $name:ident {
+            $( $(#[doc = $doc:tt])* $variant:ident => $value:tt, )*
+        }
+    ) => {
+        $(#[doc = $enum_doc])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[cfg_attr(feature = "server", derive(ToSchema))]
+        pub enum $name {
+            $(
+                $(#[doc = $doc])*
+                #[serde(rename = $value)]
+                $variant,
+            )*
+        }
+
+        impl $name {
+            /// All variants, in declaration order
+            pub const ALL: &'static [$name] = &[$($name::$variant,)*];
+
+            /// The database and wire value
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $value,)*
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = UnknownVariant;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $($value => Ok($name::$variant),)*
+                    _ => Err(UnknownVariant {
+                        enum_name: stringify!($name),
+                        value: s.to_string(),
+                    }),
+                }
+            }
+        }
+    };
+}
+
+db_enum! {
+    /// Lifecycle state of an item (`item_state` in the database)
+    ItemState {
+        Current => "current",
+        Loaned => "loaned",
+        Missing => "missing",
+        Disposed => "disposed",
+    }
+}
+
+impl ItemState {
+    /// Label for display in the UI
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ItemState::Current => "Current",
+            ItemState::Loaned => "Loaned",
+            ItemState::Missing => "Missing",
+            ItemState::Disposed => "Disposed",
+        }
+    }
+}
+
+db_enum! {
+    /// Value type of a soft field (`field_type` in the database)
+    FieldType {
+        String => "string",
+        Text => "text",
+        Date => "date",
+        Datetime => "datetime",
+        Number => "number",
+        Enum => "enum",
+        Boolean => "boolean",
+    }
+}
+
+impl FieldType {
+    pub fn is_enum(&self) -> bool {
+        matches!(self, FieldType::Enum)
+    }
+}
+
+db_enum! {
+    /// A user's role within an organization (`user_organizations.roles`)
+    UserRole {
+        User => "USER",
+        Admin => "ADMIN",
+        /// May override loan limit policies
+        LoanManager => "LOAN_MANAGER",
+        /// May read the organization's data but not change it
+        Viewer => "VIEWER",
+        /// May manage the catalogue (kinds, fields, locations, tags, collections) and pages
+        Curator => "CURATOR",
+    }
+}
+
+db_enum! {
+    /// Something a member may do in an organization (`user_organizations.permissions`)
+    Permission {
+        /// View items, catalogue, pages, reports and history
+        ItemsRead => "items:read",
+        /// Create, change and delete items, and file them in collections
+        ItemsWrite => "items:write",
+        /// Manage kinds, fields, locations, tags and collections
+        CatalogWrite => "catalog:write",
+        /// Create and edit pages
+        PagesWrite => "pages:write",
+        /// Delete pages
+        PagesDelete => "pages:delete",
+        /// Export the organization's item history
+        HistoryExport => "history:export",
+        /// Change organization settings
+        SettingsWrite => "settings:write",
+        /// Override loan limit policies
+        LoansOverride => "loans:override",
+        /// See and revoke other members' API keys
+        MembersManage => "members:manage",
+        /// Administer users and organizations (meaningful in the SYSTEM organization)
+        AdminUsers => "admin:users",
+    }
+}
+
+// Roles and permissions are stored as TEXT[] columns rather than Postgres
+// enums, so they encode and decode through their string form.
+#[cfg(feature = "server")]
+mod text_array_sqlx {
+    use sqlx::{
+        Decode, Encode, Postgres, Type,
+        encode::IsNull,
+        error::BoxDynError,
+        postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueRef},
+    };
+
+    use super::{Permission, UserRole};
+
+    macro_rules! text_sqlx {
+        ($name:ident) => {
+            impl Type<Postgres> for $name {
+                fn type_info() -> PgTypeInfo {
+                    <&str as Type<Postgres>>::type_info()
+                }
+
+                fn compatible(ty: &PgTypeInfo) -> bool {
+                    <&str as Type<Postgres>>::compatible(ty)
+                }
+            }
+
+            impl PgHasArrayType for $name {
+                fn array_type_info() -> PgTypeInfo {
+                    <&str as PgHasArrayType>::array_type_info()
+                }
+
+                fn array_compatible(ty: &PgTypeInfo) -> bool {
+                    <&str as PgHasArrayType>::array_compatible(ty)
+                }
+            }
+
+            impl Encode<'_, Postgres> for $name {
+                fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+                    <&str as Encode<Postgres>>::encode(self.as_str(), buf)
+                }
+            }
+
+            impl<'r> Decode<'r, Postgres> for $name {
+                fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+                    Ok(<&str as Decode<Postgres>>::decode(value)?.parse()?)
+                }
+            }
+        };
+    }
+
+    text_sqlx!(UserRole);
+    text_sqlx!(Permission);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trips<T>(all: &[T])
+    where
+        T: fmt::Display + FromStr<Err = UnknownVariant> + Serialize + PartialEq + fmt::Debug,
+    {
+        let mut seen = std::collections::HashSet::new();
+        for v in all {
+            let s = v.to_string();
+            assert!(seen.insert(s.clone()), "duplicate value {}", s);
+            assert_eq!(s.parse::<T>().as_ref(), Ok(v));
+            assert_eq!(serde_json::to_value(v).unwrap(), s);
+        }
+    }
+
+    #[test]
+    fn test_item_state_round_trip() {
+        assert_round_trips(ItemState::ALL);
+        assert_eq!(ItemState::ALL.len(), 4);
+        assert_eq!(ItemState::Loaned.as_str(), "loaned");
+    }
+
+    #[test]
+    fn test_field_type_round_trip() {
+        assert_round_trips(FieldType::ALL);
+        assert_eq!(FieldType::ALL.len(), 7);
+        assert!(FieldType::Enum.is_enum());
+        assert!(!FieldType::Text.is_enum());
+    }
+
+    #[test]
+    fn test_user_role_round_trip() {
+        assert_round_trips(UserRole::ALL);
+        assert_eq!(UserRole::LoanManager.as_str(), "LOAN_MANAGER");
+        assert_eq!("VIEWER".parse::<UserRole>(), Ok(UserRole::Viewer));
+    }
+
+    #[test]
+    fn test_permission_round_trip() {
+        assert_round_trips(Permission::ALL);
+        assert_eq!(Permission::ItemsWrite.as_str(), "items:write");
+        assert!("items:admin".parse::<Permission>().is_err());
+    }
+
+    #[test]
+    fn test_unknown_values_rejected() {
+        let err = "lost".parse::<ItemState>().unwrap_err();
+        assert_eq!(err.to_string(), "unknown ItemState 'lost'");
+        assert!("Loaned".parse::<ItemState>().is_err());
+        assert!("admin".parse::<UserRole>().is_err());
+        assert!("json".parse::<FieldType>().is_err());
+    }
+}
@@ -0,0 +1,229 @@
+//! Machine-readable error codes returned in `ErrorResponse.error`.
+//!
+//! Codes are dotted strings, `<area>.<reason>`, and are part of the API
+//! contract: clients match on the code and show the accompanying message.
+//! Add a variant here rather than inventing a string in a handler.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use utoipa::ToSchema;
+
+macro_rules! error_codes {
+    ($( $(#[doc = $doc:tt])* $variant:ident => $code:tt, )*) => {
+        /// Every error code the API can return
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[cfg_attr(feature = "server", derive(ToSchema))]
+        pub enum ErrorCode {
+            $(
+                $(#[doc = $doc])*
+                #[serde(rename = $code)]
+                $variant,
+            )*
+        }
+
+        impl ErrorCode {
+            /// All codes, in catalog order
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)*];
+
+            /// The wire form of the code, e.g. `item.not_found`
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $code,)*
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// No valid token was supplied
+    AuthRequired => "auth.required",
+    /// The bearer token is malformed, expired, revoked or not an access token
+    AuthInvalidToken => "auth.invalid_token",
+    /// Wrong identity or password
+    AuthInvalidCredentials => "auth.invalid_credentials",
+    /// The user belongs to no organization, so cannot log in
+    AuthNoOrganization => "auth.no_organization",
+    /// The user is not a member of the requested organization
+    AuthNotMember => "auth.not_member",
+    /// The follow-on token is malformed or expired
+    AuthInvalidFollowOnToken => "auth.invalid_follow_on_token",
+    /// The follow-on token has already been exchanged
+    AuthFollowOnTokenReused => "auth.follow_on_token_reused",
+    /// The refresh token is malformed, expired or revoked
+    AuthInvalidRefreshToken => "auth.invalid_refresh_token",
+    /// The refresh token was already exchanged; the session has been revoked
+    AuthRefreshTokenReused => "auth.refresh_token_reused",
+    /// The API key is unknown or expired, or its owner has left the organization
+    AuthInvalidApiKey => "auth.invalid_api_key",
+    /// No external identity provider is configured
+    AuthOidcNotConfigured => "auth.oidc_not_configured",
+    /// The identity provider login failed, expired or matched no user
+    AuthOidcFailed => "auth.oidc_failed",
+    /// Authenticated, but lacking the role the operation needs
+    AuthForbidden => "auth.forbidden",
+    /// The user has been deactivated by an administrator
+    AuthUserDeactivated => "auth.user_deactivated",
+    /// The user has not accepted the current terms of service; log in again to accept them
+    AuthTermsNotAccepted => "auth.terms_not_accepted",
+    /// The terms accepted are not the current version, which changed meanwhile
+    AuthTermsVersionMismatch => "auth.terms_version_mismatch",
+
+    /// The request is well-formed but its content is invalid
+    ValidationFailed => "validation.failed",
+
+    OrganizationNotFound => "organization.not_found",
+    /// The organization is read-only, by its settings or a system administrator
+    OrganizationReadOnly => "organization.read_only",
+
+    UserNotFound => "user.not_found",
+    /// The user is already a member of the organization
+    UserAlreadyMember => "user.already_member",
+    /// The user is not a member of the organization
+    UserNotMember => "user.not_member",
+
+    ItemNotFound => "item.not_found",
+    /// Another item in the organization has the same barcode
+    ItemDuplicateBarcode => "item.duplicate_barcode",
+    /// A dimension or weight is out of range
+    ItemInvalidMeasurement => "item.invalid_measurement",
+    /// The share link is malformed or has expired
+    ItemShareLinkInvalid => "item.share_link_invalid",
+    /// No open lookup suggestion has this id; it may have been applied or dismissed
+    ItemSuggestionNotFound => "item.suggestion_not_found",
+    /// No metadata service knows the ISBN or barcode
+    ItemLookupNotFound => "item.lookup_not_found",
+    /// Every metadata service asked for the ISBN or barcode failed
+    ItemLookupUnavailable => "item.lookup_unavailable",
+    /// The items cannot be merged: they are of different kinds, or the
+    /// duplicate is on loan
+    ItemMergeConflict => "item.merge_conflict",
+
+    /// Lending the item would break the organization's loan policy
+    LoanLimitExceeded => "loan.limit_exceeded",
+
+    KindNotFound => "kind.not_found",
+    /// Another kind visible to the organization has the same name
+    KindNameConflict => "kind.name_conflict",
+    /// Items still use the kind
+    KindInUse => "kind.in_use",
+    /// Removing fields would discard item data; retry with `force`
+    KindDataLossRequired => "kind.data_loss_required",
+    /// The organization already overrides this shared kind
+    KindOverrideExists => "kind.override_exists",
+    /// The kind is not a shared kind, so cannot be overridden
+    KindNotShared => "kind.not_shared",
+    /// The kind is already a shared kind, so cannot be reverted
+    KindAlreadyShared => "kind.already_shared",
+    /// No shared kind has this kind's name to revert to
+    KindNoSharedKind => "kind.no_shared_kind",
+
+    FieldNotFound => "field.not_found",
+    /// Another field visible to the organization has the same name
+    FieldNameConflict => "field.name_conflict",
+    /// Kinds still use the field
+    FieldInUse => "field.in_use",
+    /// An enum field needs at least one value, with no duplicates
+    FieldInvalidEnumValues => "field.invalid_enum_values",
+    /// Items still hold an enum value being removed
+    FieldEnumValueInUse => "field.enum_value_in_use",
+    /// The operation only applies to enum fields
+    FieldNotEnum => "field.not_enum",
+
+    LocationNotFound => "location.not_found",
+    /// Another location in the organization has the same name
+    LocationNameConflict => "location.name_conflict",
+    CollectionNotFound => "collection.not_found",
+    /// A collection's cover must be one of its items
+    CollectionCoverNotInCollection => "collection.cover_not_in_collection",
+    SmartCollectionNotFound => "smart_collection.not_found",
+    /// Another smart collection in the organization has the same name
+    SmartCollectionNameConflict => "smart_collection.name_conflict",
+    TagNotFound => "tag.not_found",
+    /// The organization already has a tag with this name
+    TagNameConflict => "tag.name_conflict",
+    BorrowerNotFound => "borrower.not_found",
+
+    /// A setting key or value is not accepted
+    SettingInvalid => "setting.invalid",
+
+    /// A preference value or preferences file is not accepted
+    PreferenceInvalid => "preference.invalid",
+
+    ApiKeyNotFound => "api_key.not_found",
+
+    InviteNotFound => "invite.not_found",
+    /// The invite link is malformed or expired, or the invite was revoked or already accepted
+    InviteInvalid => "invite.invalid",
+
+    PageNotFound => "page.not_found",
+    /// Another page in the organization has the same title
+    PageTitleConflict => "page.title_conflict",
+    /// The page was edited since the version the change was based on
+    PageVersionConflict => "page.version_conflict",
+
+    BroadcastNotFound => "broadcast.not_found",
+
+    /// The export does not exist or has expired
+    ExportNotFound => "export.not_found",
+
+    JobNotFound => "job.not_found",
+
+    ImportNotFound => "import.not_found",
+
+    FileNotFound => "file.not_found",
+    /// The signed file link is invalid or has expired
+    FileLinkInvalid => "file.link_invalid",
+
+    PhotoNotFound => "photo.not_found",
+    /// The upload is not a readable zip archive
+    PhotoArchiveInvalid => "photo.archive_invalid",
+
+    /// Unexpected server-side failure
+    InternalError => "internal.error",
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ErrorCode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ErrorCode::ALL
+            .iter()
+            .copied()
+            .find(|code| code.as_str() == s)
+            .ok_or(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_round_trip() {
+        for code in ErrorCode::ALL {
+            let json = serde_json::to_value(code).unwrap();
+            assert_eq!(json, code.as_str());
+            assert_eq!(code.as_str().parse::<ErrorCode>(), Ok(*code));
+        }
+        assert!("not_found".parse::<ErrorCode>().is_err());
+    }
+
+    #[test]
+    fn test_codes_are_unique_and_dotted() {
+        let mut seen = std::collections::HashSet::new();
+        for code in ErrorCode::ALL {
+            let (area, reason) = code.as_str().split_once('.').unwrap();
+            assert!(!area.is_empty() && !reason.is_empty());
+            assert!(seen.insert(code.as_str()), "duplicate {}", code);
+        }
+    }
+}
@@ -1,3 +1,12 @@
 #[cfg(feature = "server")]
 pub mod auth;
+#[cfg(feature = "server")]
+pub mod config;
+pub mod enums;
+pub mod error_code;
 pub mod models;
+pub mod permissions;
+#[cfg(feature = "storage")]
+pub mod storage;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
@@ -5,16 +5,7 @@ use serde_json::Value;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-// Item states
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[cfg_attr(feature = "server", derive(ToSchema))]
-#[serde(rename_all = "snake_case")]
-pub enum ItemState {
-    Current,
-    Loaned,
-    Missing,
-    Disposed,
-}
+pub use crate::enums::{FieldType, ItemState, Permission, UserRole};
 
 // Item response
 #[derive(Debug, Serialize, Deserialize)]
@@ -31,13 +22,35 @@ pub struct Item {
     pub location_id: Option<Uuid>,
     pub date_entered: DateTime<Utc>,
     pub date_acquired: Option<NaiveDate>,
+    /// Scanned barcode (EAN/UPC/ISBN), unique within the organization
+    pub barcode: Option<String>,
+    /// Publisher catalogue code
+    pub catalog_code: Option<String>,
+    /// Physical dimensions in millimetres; depth is the shelf thickness
+    pub width_mm: Option<i32>,
+    pub height_mm: Option<i32>,
+    pub depth_mm: Option<i32>,
+    /// Weight in grams
+    pub weight_g: Option<i32>,
+    /// When the item was last played or used; None if never marked
+    pub last_used_at: Option<DateTime<Utc>>,
     pub soft_fields: Value,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+// Item returned from create and update, with non-fatal validation warnings
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct ItemWithWarnings {
+    #[serde(flatten)]
+    pub item: Item,
+    /// Values that were saved but look like mistakes, e.g. "date_acquired is in the future"
+    pub warnings: Vec<String>,
+}
+
 // Create item request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema))]
 pub struct CreateItemRequest {
     pub kind_id: Uuid,
@@ -46,11 +59,190 @@ pub struct CreateItemRequest {
     pub notes: Option<String>,
     pub location_id: Option<Uuid>,
     pub date_acquired: Option<NaiveDate>,
+    pub barcode: Option<String>,
+    pub catalog_code: Option<String>,
+    pub width_mm: Option<i32>,
+    pub height_mm: Option<i32>,
+    pub depth_mm: Option<i32>,
+    pub weight_g: Option<i32>,
     pub soft_fields: Option<Value>,
+    /// Tag names; tags the organization lacks are created, which needs the
+    /// catalog:write permission
+    pub tags: Option<Vec<String>>,
+}
+
+// Create several items at once; all are created or none are
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct BulkCreateItemsRequest {
+    pub items: Vec<CreateItemRequest>,
+}
+
+impl BulkCreateItemsRequest {
+    /// Most items one request may create
+    pub const MAX_ITEMS: usize = 100;
+}
+
+// Make the same change to several items at once; all change or none do
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct BulkUpdateItemsRequest {
+    pub item_ids: Vec<Uuid>,
+    /// Move every item to this location
+    pub location_id: Option<Uuid>,
+    /// Put every item in this state; not `loaned`, as each loan needs its
+    /// borrower
+    pub state: Option<ItemState>,
+    /// Tag names to add; tags the organization lacks are created, which needs
+    /// the catalog:write permission
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    /// Tag names to remove
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+}
+
+impl BulkUpdateItemsRequest {
+    /// Most items one request may change
+    pub const MAX_ITEMS: usize = 500;
+}
+
+// Fetch the full details of several items at once
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct ItemDetailsRequest {
+    pub item_ids: Vec<Uuid>,
+}
+
+impl ItemDetailsRequest {
+    /// Most items one request may fetch
+    pub const MAX_ITEMS: usize = 100;
+}
+
+// Look up details for several items in the background
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct EnrichItemsRequest {
+    pub item_ids: Vec<Uuid>,
+}
+
+impl EnrichItemsRequest {
+    /// Most items one request may queue
+    pub const MAX_ITEMS: usize = 100;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct EnrichItemsResponse {
+    /// The lookup job; None when no item needed a lookup
+    pub job_id: Option<Uuid>,
+    /// Items with a barcode and a missing description or catalog code
+    pub queued: Vec<Uuid>,
+    /// Items not looked up: already complete, without a barcode, awaiting
+    /// review, or not found
+    pub skipped: Vec<Uuid>,
+}
+
+// Details a metadata lookup found for an item, awaiting review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
+pub struct ItemSuggestion {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub item_name: String,
+    /// Where the details came from, such as `musicbrainz` or `openlibrary`
+    pub provider: String,
+    /// The matching record at the provider
+    pub source_url: Option<String>,
+    /// Suggested values by item field (`description`, `catalog_code`)
+    pub changes: Value,
+    /// The item's current values of the same fields
+    pub current: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+// A new item prefilled from a metadata service's record for an ISBN or barcode
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct ItemLookup {
+    /// The service that knew the code, such as `musicbrainz` or `openlibrary`
+    pub provider: String,
+    /// The matching record at the provider
+    pub source_url: Option<String>,
+    /// Ready to send to `POST .../items`, after adding a location or
+    /// correcting what the service got wrong
+    pub item: CreateItemRequest,
 }
 
-// Update item request
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::IntoParams))]
+pub struct BarcodeLookupParams {
+    /// Kind the item is, such as `cd`; only services covering it are asked.
+    /// Guessed from the service's record when absent
+    pub kind: Option<String>,
+}
+
+// Import items from a CSV file on the server, in a background job
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct CreateImportRequest {
+    /// The CSV text, with a header row
+    pub csv: String,
+    /// File name shown with the import, e.g. `goodreads_library_export.csv`
+    pub filename: Option<String>,
+    /// Column mapping, as used by `csv-importer`: `{"kind", "tags",
+    /// "columns": [{"column", "target"}]}`. Guessed from the headers when absent
+    #[cfg_attr(feature = "server", schema(value_type = Option<Object>))]
+    pub mapping: Option<Value>,
+    /// Kind to guess a mapping for when none is given; default `misc`
+    pub kind: Option<String>,
+}
+
+// A CSV import and its progress
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
+pub struct ItemImport {
+    pub id: Uuid,
+    pub filename: Option<String>,
+    pub kind_id: Uuid,
+    pub kind_name: String,
+    /// The column mapping used
+    pub mapping: Value,
+    /// pending, running, succeeded or failed
+    pub status: String,
+    pub total_rows: i32,
+    /// Rows dealt with so far: imported, skipped or failed
+    pub processed_rows: i32,
+    pub imported_rows: i32,
+    /// Rows without a name, or whose barcode is already in use
+    pub skipped_rows: i32,
+    pub failed_rows: i32,
+    /// The first rows not imported, and why
+    #[cfg_attr(feature = "server", sqlx(json))]
+    pub errors: Vec<ImportRowError>,
+    /// Why the import stopped, when it failed as a whole
+    pub last_error: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+// A CSV row that was not imported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct ImportRowError {
+    /// Data row number, the first row after the headers being 1
+    pub row: i32,
+    /// The row's item name, if it has one
+    pub name: Option<String>,
+    /// Skipped (no name, or a barcode already in use) rather than failed
+    pub skipped: bool,
+    pub message: String,
+}
+
+// Update item request
+#[derive(Debug, Default, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema))]
 pub struct UpdateItemRequest {
     pub name: Option<String>,
@@ -59,6 +251,15 @@ pub struct UpdateItemRequest {
     pub location_id: Option<Uuid>,
     pub date_acquired: Option<NaiveDate>,
     pub state: Option<ItemState>,
+    /// Set to an empty string to clear
+    pub barcode: Option<String>,
+    /// Set to an empty string to clear
+    pub catalog_code: Option<String>,
+    /// Dimensions (mm) and weight (g); set to 0 to clear
+    pub width_mm: Option<i32>,
+    pub height_mm: Option<i32>,
+    pub depth_mm: Option<i32>,
+    pub weight_g: Option<i32>,
     /// Soft field values to merge into the item's existing soft_fields.
     /// Keys present will overwrite existing values; absent keys are unchanged.
     pub soft_fields: Option<Value>,
@@ -66,6 +267,9 @@ pub struct UpdateItemRequest {
     pub loan_date_loaned: Option<NaiveDate>,
     pub loan_date_due_back: Option<NaiveDate>,
     pub loan_loaned_to: Option<String>,
+    /// Skip the organization's loan limits; requires LOAN_MANAGER or ADMIN
+    #[serde(default)]
+    pub override_loan_limits: bool,
     // Missing details
     pub missing_date_missing: Option<NaiveDate>,
     // Disposed details
@@ -80,6 +284,8 @@ pub struct LoanDetails {
     pub date_loaned: NaiveDate,
     pub date_due_back: Option<NaiveDate>,
     pub loaned_to: String,
+    /// Borrower the open loan is recorded against
+    pub borrower_id: Option<Uuid>,
 }
 
 // Missing state details
@@ -109,6 +315,131 @@ pub struct ItemFullDetails {
     pub disposed_details: Option<DisposedDetails>,
 }
 
+// Audit log entry for an item
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
+pub struct ItemHistoryEntry {
+    pub id: Uuid,
+    pub change_date: Option<DateTime<Utc>>,
+    /// One-line summary of the change
+    pub change_details: String,
+    /// created, updated, state_changed, merged or deleted
+    pub action: String,
+    /// Who made the change; None if unknown or anonymized
+    pub user_id: Option<Uuid>,
+    pub user_name: Option<String>,
+    /// Changed fields as `{"field": {"from": old, "to": new}}`; state details
+    /// set by the change (`loan_*`, `missing_*`, `disposed_*`) have only `to`
+    pub changes: serde_json::Value,
+}
+
+// One logged use of an item
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
+pub struct UsageEntry {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub used_at: DateTime<Utc>,
+    /// Who logged the use; None if unknown or anonymized
+    pub user_id: Option<Uuid>,
+    pub user_name: Option<String>,
+    pub note: Option<String>,
+}
+
+// Log a use of an item; `{}` logs a use now
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct LogUsageRequest {
+    /// When the item was used; defaults to now and must not be in the future
+    pub used_at: Option<DateTime<Utc>>,
+    /// Free text, e.g. "Side B only"
+    pub note: Option<String>,
+}
+
+// A photo attached to an item
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct ItemPhoto {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    /// Name of the uploaded file
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+    /// Short-lived link to the image
+    pub url: String,
+}
+
+// Outcome of a batch photo upload
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct PhotoImportResult {
+    pub attached: Vec<AttachedPhoto>,
+    /// Files that were not attached to any item
+    pub unmatched: Vec<UnmatchedPhoto>,
+}
+
+// A file from a batch upload and the item it was attached to
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct AttachedPhoto {
+    pub filename: String,
+    pub photo_id: Uuid,
+    pub item_id: Uuid,
+    pub item_name: String,
+}
+
+// A file from a batch upload that was not attached, and why
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct UnmatchedPhoto {
+    pub filename: String,
+    /// e.g. "no item has this barcode or catalogue number"
+    pub reason: String,
+}
+
+// Everything the expanded item view shows, in one response
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct ItemAggregate {
+    #[serde(flatten)]
+    pub details: ItemFullDetails,
+    pub tags: Vec<String>,
+    pub collections: Vec<Collection>,
+    /// Most recent audit entries, newest first
+    pub history: Vec<ItemHistoryEntry>,
+    /// Most recent logged uses, newest first
+    pub usage: Vec<UsageEntry>,
+    /// Oldest first
+    pub photos: Vec<ItemPhoto>,
+}
+
+// One value of an item facet and how many items have it
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct FacetCount {
+    /// Value for the matching list filter: kind name, state, location id or
+    /// tag name. None for items without a location
+    pub value: Option<String>,
+    /// Display name: the location name for locations, otherwise the value
+    pub label: Option<String>,
+    pub count: i64,
+}
+
+// Item counts per filter value, under the current filters
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct ItemFacets {
+    /// Items matching the filters
+    pub total: i64,
+    pub kinds: Vec<FacetCount>,
+    pub states: Vec<FacetCount>,
+    pub locations: Vec<FacetCount>,
+    /// An item with several tags counts once under each
+    pub tags: Vec<FacetCount>,
+}
+
 // Location
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
@@ -126,6 +457,29 @@ pub struct CreateLocationRequest {
     pub name: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct UpdateLocationRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct MergeLocationsRequest {
+    /// Location whose items move to this one; it is then deleted
+    pub source_location_id: Uuid,
+}
+
+// Result of folding one location into another
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct MergeLocationsResponse {
+    /// The location kept
+    pub location: Location,
+    /// Items moved from the deleted location
+    pub items_moved: i64,
+}
+
 // Collection
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
@@ -135,16 +489,104 @@ pub struct Collection {
     pub name: String,
     pub description: Option<String>,
     pub notes: Option<String>,
+    /// Item shown to represent the collection, one of its own
+    #[serde(default)]
+    pub cover_item_id: Option<Uuid>,
+    /// Place in the collections list, lowest first; ties go by name
+    #[serde(default)]
+    pub sort_order: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+// A collection with how many items are in it
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
+pub struct CollectionDetails {
+    #[serde(flatten)]
+    #[cfg_attr(feature = "server", sqlx(flatten))]
+    pub collection: Collection,
+    pub item_count: i64,
+}
+
 #[derive(Debug, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema))]
 pub struct CreateCollectionRequest {
     pub name: String,
     pub description: Option<String>,
     pub notes: Option<String>,
+    /// Default 0
+    pub sort_order: Option<i32>,
+}
+
+// Fields left out keep their current values
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct UpdateCollectionRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub notes: Option<String>,
+    /// An item in the collection; set to the nil UUID to clear
+    pub cover_item_id: Option<Uuid>,
+    pub sort_order: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct AddCollectionItemRequest {
+    pub item_id: Uuid,
+}
+
+// Smart collection: its items are those matching a stored filter, found
+// each time it is listed
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
+pub struct SmartCollection {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    #[cfg_attr(feature = "server", sqlx(json))]
+    pub filter: SmartCollectionFilter,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Which items a smart collection holds; criteria left empty match every item
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct SmartCollectionFilter {
+    /// Kind names; items of any of them match
+    #[serde(default)]
+    pub kinds: Vec<String>,
+    /// Items in any of these states match
+    #[serde(default)]
+    pub states: Vec<ItemState>,
+    /// Tag names; items with any of them match, or with all when `all_tags`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub all_tags: bool,
+    /// Full-text search, as for the item list `search`
+    #[serde(default)]
+    pub search: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct CreateSmartCollectionRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub filter: SmartCollectionFilter,
+}
+
+// Fields left out keep their current values; a filter replaces the old one
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct UpdateSmartCollectionRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub filter: Option<SmartCollectionFilter>,
 }
 
 // Tag
@@ -162,6 +604,30 @@ pub struct CreateTagRequest {
     pub name: String,
 }
 
+// Tag autocomplete
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::IntoParams))]
+pub struct TagSuggestParams {
+    /// Text typed so far; matched as a case-insensitive prefix, then by similarity
+    #[serde(default)]
+    pub q: String,
+    /// Maximum suggestions to return (1-50, default 10)
+    #[serde(default = "default_suggest_limit")]
+    pub limit: i64,
+}
+
+fn default_suggest_limit() -> i64 {
+    10
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
+pub struct TagSuggestion {
+    pub name: String,
+    /// Number of items carrying the tag
+    pub usage_count: i64,
+}
+
 // Organization
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
@@ -187,41 +653,450 @@ pub struct UpdateOrganizationRequest {
     pub description: Option<String>,
 }
 
-// User roles
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+// An organization's read-only state set by a system administrator
+#[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema))]
-#[serde(rename_all = "UPPERCASE")]
-pub enum UserRole {
-    User,
-    Admin,
+pub struct OrganizationReadOnly {
+    pub read_only: bool,
 }
 
-impl UserRole {
-    pub fn as_str(&self) -> &str {
-        match self {
-            UserRole::User => "USER",
-            UserRole::Admin => "ADMIN",
-        }
+// Organization settings, stored as JSONB on the organization. Missing keys
+// take their defaults, so adding a setting needs no migration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct OrganizationSettings {
+    /// Most items one borrower may have on loan at once; None for no limit
+    #[serde(default)]
+    pub max_loans_per_borrower: Option<i32>,
+    /// Longest allowed loan in days; when set, loans need a due back date
+    #[serde(default)]
+    pub max_loan_days: Option<i32>,
+    /// Notice shown to every member of the organization
+    #[serde(default)]
+    pub announcement: Option<Announcement>,
+    /// Roles given to members added without explicit roles; None for `USER` only
+    #[serde(default)]
+    pub default_member_roles: Option<Vec<UserRole>>,
+    /// Item updates older than this many whole months are compacted into
+    /// monthly summaries; None keeps all history
+    #[serde(default)]
+    pub history_retention_months: Option<i32>,
+    /// Accent colour for the web app, as `#rrggbb`; None for the default
+    #[serde(default)]
+    pub accent_color: Option<String>,
+    /// Refuse all changes to the organization's data, such as during an audit;
+    /// only settings may still be changed
+    #[serde(default)]
+    pub read_only: bool,
+    /// Layouts for printing item labels, in the order offered
+    #[serde(default)]
+    pub label_templates: Vec<LabelTemplate>,
+}
+
+impl OrganizationSettings {
+    /// Whether a colour is `#` and six hex digits
+    pub fn is_valid_color(color: &str) -> bool {
+        color.len() == 7
+            && color.starts_with('#')
+            && color[1..].chars().all(|c| c.is_ascii_hexdigit())
     }
+}
 
-    pub fn from_str(s: &str) -> Option<Self> {
-        match s {
-            "USER" => Some(UserRole::User),
-            "ADMIN" => Some(UserRole::Admin),
-            _ => None,
-        }
+// A layout for printing item labels
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct LabelTemplate {
+    pub name: String,
+    /// Item fields printed, top to bottom; one of [`LabelTemplate::FIELDS`]
+    pub fields: Vec<String>,
+    pub width_mm: i32,
+    pub height_mm: i32,
+    /// Print a QR code linking to the item
+    #[serde(default)]
+    pub include_qr: bool,
+    /// Print the item's barcode, when it has one
+    #[serde(default)]
+    pub include_barcode: bool,
+}
+
+impl LabelTemplate {
+    /// Most templates one organization may keep
+    pub const MAX_TEMPLATES: usize = 20;
+    /// Smallest and largest label side in millimetres
+    pub const SIZE_RANGE_MM: std::ops::RangeInclusive<i32> = 10..=300;
+    /// Item fields a label may show
+    pub const FIELDS: &[&str] = &[
+        "name",
+        "kind_name",
+        "state",
+        "location",
+        "barcode",
+        "catalog_code",
+        "description",
+        "date_acquired",
+    ];
+}
+
+// Print labels for items with one of the organization's label templates
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct PrintLabelsRequest {
+    /// Name of the label template
+    pub template: String,
+    /// Items to label, one label each, in order
+    pub item_ids: Vec<Uuid>,
+}
+
+impl PrintLabelsRequest {
+    /// Most labels one request may print
+    pub const MAX_ITEMS: usize = 500;
+}
+
+// The organization's logo
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct OrganizationLogo {
+    /// Short-lived link to the image
+    pub url: String,
+}
+
+// Organization-wide announcement, shown as a banner until it expires
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct Announcement {
+    pub message: String,
+    /// Last day the announcement is shown
+    pub expires_on: NaiveDate,
+}
+
+impl Announcement {
+    /// Maximum message length in characters
+    pub const MAX_LEN: usize = 500;
+
+    pub fn is_active(&self, today: NaiveDate) -> bool {
+        today <= self.expires_on
     }
 }
 
-// User
+// A member's preferences within an organization, stored as JSONB. Missing keys
+// take their defaults, so adding a preference needs no migration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct UserPreferences {
+    /// Item table columns in display order; None for the default layout
+    #[serde(default)]
+    pub item_columns: Option<Vec<String>>,
+    /// Named item list filters
+    #[serde(default)]
+    pub saved_searches: Vec<SavedSearch>,
+    /// Items per page in the item list; None for the default
+    #[serde(default)]
+    pub per_page: Option<i64>,
+    /// Default item list sort column, as for the item list `sort_by`
+    #[serde(default)]
+    pub sort_by: Option<String>,
+    /// Default item list sort direction, `asc` or `desc`
+    #[serde(default)]
+    pub sort_order: Option<String>,
+}
+
+impl UserPreferences {
+    /// Most saved searches one member may keep
+    pub const MAX_SAVED_SEARCHES: usize = 50;
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct SavedSearch {
+    pub name: String,
+    /// Item list query string, e.g. `state=loaned&search=blue`
+    pub query: String,
+}
+
+// Preferences file written by export and read by import
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct PreferencesExport {
+    pub format_version: i32,
+    /// Set on export; ignored on import
+    #[serde(default)]
+    pub exported_at: Option<DateTime<Utc>>,
+    pub preferences: UserPreferences,
+}
+
+impl PreferencesExport {
+    /// Version written by this release and the only one accepted on import
+    pub const FORMAT_VERSION: i32 = 1;
+}
+
+// API key for a script or other machine client; the key itself is only
+// returned when created
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    /// The member the key acts for
+    pub user_id: Uuid,
+    pub name: String,
+    /// Leading characters of the key, to tell keys apart
+    pub key_prefix: String,
+    /// Roles granted to the key; never more than its owner holds at the time of use
+    pub roles: Vec<UserRole>,
+    /// None for a key that does not expire
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    /// Defaults to all the caller's roles; may not include roles the caller lacks
+    #[serde(default)]
+    pub roles: Option<Vec<UserRole>>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct UpdateApiKeyRequest {
+    pub name: Option<String>,
+}
+
+/// A newly created API key, with the key itself; it cannot be retrieved later
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct CreatedApiKey {
+    /// Send in the `X-Api-Key` header
+    pub key: String,
+    pub api_key: ApiKey,
+}
+
+// Pending invitation for someone to join an organization; the invite link is
+// emailed, never returned
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
-pub struct User {
+pub struct Invite {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub email: String,
+    /// Roles and permissions the membership is created with
+    pub roles: Vec<UserRole>,
+    pub permissions: Vec<Permission>,
+    /// Who sent the invite; None once their account is deleted
+    pub invited_by: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct CreateInviteRequest {
+    pub email: String,
+    /// Defaults to the organization's default member roles, else USER
+    #[serde(default)]
+    pub roles: Option<Vec<UserRole>>,
+    /// Defaults to the permissions the roles grant; may not include permissions the caller lacks
+    #[serde(default)]
+    pub permissions: Option<Vec<Permission>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct AcceptInviteRequest {
+    /// The token from the invite link
+    pub token: String,
+    /// Required when no account exists for the invited email yet
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Required when no account exists for the invited email yet
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+// Link to a read-only public page showing one item
+#[derive(Debug, Default, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct CreateShareLinkRequest {
+    /// Hours the link works for; defaults to a week, at most 30 days
+    #[serde(default)]
+    pub expires_in_hours: Option<i64>,
+    /// Item fields shown besides the name; defaults to kind_name, description and soft_fields
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct ShareLink {
+    /// The public page, with the token in its query
+    pub url: String,
+    /// For `GET /api/shared-items/{token}`
+    pub token: String,
+    pub fields: Vec<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+// An item as a share link shows it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct SharedItem {
+    pub name: String,
+    /// The shared fields, keyed as in `Item`
+    pub fields: Value,
+    pub expires_at: DateTime<Utc>,
+}
+
+// Notice from system administrators, such as scheduled downtime, shown as a
+// banner in the targeted organizations until it expires
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
+pub struct Broadcast {
+    pub id: Uuid,
+    pub message: String,
+    /// Organizations shown the notice; None for every organization
+    pub organization_ids: Option<Vec<Uuid>>,
+    pub expires_at: DateTime<Utc>,
+    /// Whether members of the targeted organizations were also emailed
+    pub email: bool,
+    /// None once the author's account is deleted
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct CreateBroadcastRequest {
+    /// At most `Announcement::MAX_LEN` characters
+    pub message: String,
+    /// Omit to reach every organization
+    #[serde(default)]
+    pub organization_ids: Option<Vec<Uuid>>,
+    /// Must be in the future
+    pub expires_at: DateTime<Utc>,
+    /// Also email the members of the targeted organizations
+    #[serde(default)]
+    pub email: bool,
+}
+
+// A broadcast as members see it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
+pub struct BroadcastNotice {
+    pub id: Uuid,
+    pub message: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+// A release in the API changelog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct ChangelogRelease {
+    /// Date-based version, e.g. "2026.10.17"; unique
+    pub version: String,
+    pub date: NaiveDate,
+    pub changes: Vec<ApiChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct ApiChange {
+    pub kind: ApiChangeKind,
+    pub summary: String,
+    /// Endpoints concerned, as "METHOD /path"
+    #[serde(default)]
+    pub endpoints: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ApiChangeKind {
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+}
+
+// Organization documentation page, written in Markdown
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
+pub struct OrgPage {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub title: String,
+    pub body: String,
+    /// Starts at 1 and goes up with each edit
+    pub version: i32,
+    /// Who made the current version; None once their account is deleted
+    pub updated_by: Option<Uuid>,
+    pub updated_by_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Page in the list of an organization's pages, without its body
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
+pub struct OrgPageSummary {
+    pub id: Uuid,
+    pub title: String,
+    pub version: i32,
+    pub updated_by_name: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// One version of a page, as it was saved
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema, sqlx::FromRow))]
+pub struct OrgPageVersion {
+    pub version: i32,
+    pub title: String,
+    pub body: String,
+    pub edited_by: Option<Uuid>,
+    pub edited_by_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct CreateOrgPageRequest {
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct UpdateOrgPageRequest {
+    /// The version the edit was made to; rejected if the page has moved on
+    pub version: i32,
+    pub title: Option<String>,
+    pub body: Option<String>,
+}
+
+// User, as the API returns it. The password hash is only read from the
+// users table by the API and never leaves it.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct UserResponse {
     pub id: Uuid,
     pub name: String,
     pub identity: String,
-    #[serde(skip_serializing)] // Never serialize password hash
-    pub password_hash: Option<String>,
+    /// False for users who sign in only through OIDC or a directory
+    pub has_password: bool,
+    /// False once deactivated: the user can no longer log in
+    pub is_active: bool,
+    /// Consecutive failed password logins since the last successful one
+    pub failed_login_attempts: i32,
+    /// Password login is refused until then after repeated failures; cleared
+    /// by `unlock`
+    pub locked_until: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -244,6 +1119,14 @@ pub struct UpdateUserRequest {
     pub password: Option<String>,
 }
 
+/// Result of an administrator password reset
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct ResetPasswordResponse {
+    /// Shown once; the user must replace it at their next login
+    pub temporary_password: String,
+}
+
 // Authentication models
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema))]
@@ -252,14 +1135,60 @@ pub struct LoginRequest {
     pub password: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub organization_id: Option<Uuid>,
+    /// Replaces the password when the login answers `password_change_required`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_password: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema))]
 pub struct LoginResponse {
+    /// Access token for the `Authorization` header
     pub token: String,
     pub expires_in: i64, // seconds
+    /// Exchanged at `POST /api/auth/refresh` for a new token pair; single use
+    pub refresh_token: String,
+    pub refresh_expires_in: i64, // seconds
     pub user: UserInfo,
+    /// Every organization the user belongs to, including the selected one
+    pub organizations: Vec<OrganizationWithRoles>,
+}
+
+/// Login answer when an administrator reset the password: log in again with
+/// `new_password` set to choose a permanent one
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct PasswordChangeRequiredResponse {
+    /// Always true
+    pub password_change_required: bool,
+    pub message: String,
+}
+
+/// Login answer when the instance requires its terms of service to be
+/// accepted: show them, then send `follow_on_token` to
+/// `POST /api/auth/accept-terms` to continue logging in
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct TermsAcceptanceRequiredResponse {
+    /// Always true
+    pub terms_acceptance_required: bool,
+    /// Version of the terms to accept
+    pub version: String,
+    /// Where the terms can be read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub follow_on_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct AcceptTermsRequest {
+    /// From the login's `TermsAcceptanceRequiredResponse`
+    pub follow_on_token: String,
+    /// The version shown to the user; must still be the current one
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -269,13 +1198,14 @@ pub struct OrgSelectionResponse {
     pub follow_on_token: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema))]
 pub struct OrganizationWithRoles {
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
-    pub roles: Vec<String>,
+    pub roles: Vec<UserRole>,
+    pub permissions: Vec<Permission>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -285,6 +1215,41 @@ pub struct SelectOrgRequest {
     pub organization_id: Uuid,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+// External identity provider available for login
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct OidcProviderInfo {
+    /// Name to show on the login button
+    pub name: String,
+}
+
+// Where to send the user to log in at the identity provider
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct OidcAuthorization {
+    pub authorization_url: String,
+    /// Returned by the provider with the code; keep it to check the redirect
+    /// belongs to this login attempt
+    pub state: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct OidcCallbackRequest {
+    /// `code` from the provider's redirect
+    pub code: String,
+    /// `state` from the provider's redirect
+    pub state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization_id: Option<Uuid>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema))]
 pub struct UserInfo {
@@ -292,7 +1257,26 @@ pub struct UserInfo {
     pub name: String,
     pub identity: String,
     pub organization: Organization,
-    pub roles: Vec<String>,
+    pub roles: Vec<UserRole>,
+    pub permissions: Vec<Permission>,
+}
+
+// The authenticated user with a snapshot of their current organization's
+// settings, so clients need no separate settings fetch per page
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct MeResponse {
+    #[serde(flatten)]
+    pub user: UserInfo,
+    /// Settings of the current organization; an expired announcement is left out
+    pub settings: OrganizationSettings,
+    /// Short-lived link to the current organization's logo, if it has one
+    pub logo_url: Option<String>,
+    /// Current notices from system administrators, oldest first
+    pub broadcasts: Vec<BroadcastNotice>,
+    /// Whether the current organization refuses changes, by its `read_only`
+    /// setting or because a system administrator made it read-only
+    pub read_only: bool,
 }
 
 // User organization membership
@@ -301,7 +1285,8 @@ pub struct UserInfo {
 pub struct UserOrganization {
     pub user_id: Uuid,
     pub organization_id: Uuid,
-    pub roles: Vec<String>,
+    pub roles: Vec<UserRole>,
+    pub permissions: Vec<Permission>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -310,19 +1295,48 @@ pub struct UserOrganization {
 pub struct AddUserToOrgRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub roles: Option<Vec<UserRole>>,
+    /// Defaults to the permissions the roles grant
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Vec<Permission>>,
 }
 
 #[derive(Debug, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema))]
 pub struct UpdateUserOrgRolesRequest {
     pub roles: Vec<UserRole>,
+    /// Defaults to the permissions the roles grant
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Vec<Permission>>,
 }
 
 // Error response
+pub use crate::error_code::ErrorCode;
+
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(ToSchema))]
 pub struct ErrorResponse {
-    pub error: String,
+    pub error: ErrorCode,
+    pub message: String,
+    /// The request fields at fault, for validation errors that can name them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
+}
+
+impl ErrorResponse {
+    pub fn new(error: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            error,
+            message: message.into(),
+            fields: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct FieldError {
+    /// Name of the field in the request, e.g. a soft field's name
+    pub field: String,
     pub message: String,
 }
 
@@ -353,6 +1367,68 @@ pub struct PaginatedResponse<T> {
     pub page: i64,
     pub per_page: i64,
     pub total_pages: i64,
+    /// Pass as `after` to get the following page; only from lists that
+    /// support cursors, and absent on the last page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+// Rotation suggestion parameters
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::IntoParams))]
+pub struct RotationParams {
+    /// Only suggest these kinds (comma-separated names, e.g., "vinyl,cd")
+    pub kind: Option<String>,
+    /// Number of suggestions (1-50, default 5)
+    #[serde(default = "default_rotation_count")]
+    pub count: i64,
+    /// Skip items used within this many days (default 30)
+    #[serde(default = "default_rotation_min_days")]
+    pub min_days: i64,
+}
+
+fn default_rotation_count() -> i64 {
+    5
+}
+
+fn default_rotation_min_days() -> i64 {
+    30
+}
+
+// Duplicate search parameters
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::IntoParams))]
+pub struct DuplicateParams {
+    /// Only look among these kinds (comma-separated names, e.g., "vinyl,cd")
+    pub kind: Option<String>,
+    /// How alike two names must be, as trigram similarity from 0.1 to 1
+    /// (default 0.6; 1 is the same name ignoring case and punctuation)
+    #[serde(default = "default_duplicate_threshold")]
+    pub threshold: f32,
+}
+
+fn default_duplicate_threshold() -> f32 {
+    0.6
+}
+
+// Items of one kind whose names look alike
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct DuplicateGroup {
+    pub kind_id: Uuid,
+    pub kind_name: String,
+    /// Similarity of the group's most alike pair of names
+    pub similarity: f32,
+    /// The items, oldest first
+    pub items: Vec<Item>,
+}
+
+// Merge one item into another
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(ToSchema))]
+pub struct MergeItemsRequest {
+    /// The item to fold in and delete
+    pub duplicate_id: Uuid,
 }
 
 // Item filter parameters
@@ -364,16 +1440,41 @@ pub struct ItemFilterParams {
     pub page: i64,
     #[serde(default = "default_per_page")]
     pub per_page: i64,
+    /// `next_cursor` from the previous page: return the items after it instead
+    /// of `page`. Cheaper than a deep `page`, and stable while items are added
+    /// or removed. Not available when ordering search results by relevance
+    pub after: Option<String>,
     /// Filter by kind names (comma-separated, e.g., "vinyl,cd,book")
     pub kind: Option<String>,
     /// Filter by item states (comma-separated, e.g., "current,loaned")
     pub state: Option<String>,
     /// Filter by location IDs (comma-separated UUIDs)
     pub location_id: Option<String>,
-    /// Text search across name, description, and notes (ILIKE)
+    /// Filter by collection IDs (comma-separated UUIDs); items in any of them match
+    pub collection_id: Option<String>,
+    /// Full-text search across name, description, and notes; every word must
+    /// match the start of a word. Ranked by relevance unless `sort_by` is given
     pub search: Option<String>,
+    /// Exact barcode match
+    pub barcode: Option<String>,
+    /// Catalogue number match, ignoring case
+    pub catalog_code: Option<String>,
+    /// Only items disposed on or after this date (YYYY-MM-DD)
+    pub disposed_since: Option<NaiveDate>,
+    /// Filter by tag names (comma-separated, e.g., "jazz,signed")
+    pub tags: Option<String>,
+    /// How `tags` combine: `any` (default) matches items with at least one of
+    /// the tags, `all` only items with every one
+    pub tag_match: Option<String>,
     /// Sort by column (name, kind, state, location_id, created_at)
     pub sort_by: Option<String>,
     /// Sort direction (asc, desc)
     pub sort_order: Option<String>,
+    /// Only return these item fields (comma-separated, e.g., "id,name,state,location_id");
+    /// `id` is always returned
+    pub fields: Option<String>,
+    /// Embed related data in each item (comma-separated): `details` adds the
+    /// state-specific loan/missing/disposed details, `tags` adds the tag names,
+    /// `collections` the collections the item belongs to
+    pub include: Option<String>,
 }
@@ -0,0 +1,132 @@
+//! What members may do in an organization.
+//!
+//! Each membership stores its own set of permissions. Roles are named presets:
+//! assigning roles without explicit permissions stores the union of the roles'
+//! permissions, and an API key's roles narrow what it may do to the
+//! permissions of those roles.
+
+use serde::{Deserialize, Serialize};
+
+use crate::enums::{Permission, UserRole};
+
+impl UserRole {
+    /// The permissions this role grants
+    pub fn permissions(&self) -> &'static [Permission] {
+        use Permission::*;
+        match self {
+            UserRole::Viewer => &[ItemsRead],
+            UserRole::User => &[ItemsRead, ItemsWrite, PagesWrite],
+            UserRole::LoanManager => &[ItemsRead, ItemsWrite, LoansOverride],
+            UserRole::Curator => &[ItemsRead, ItemsWrite, CatalogWrite, PagesWrite, PagesDelete],
+            UserRole::Admin => Permission::ALL,
+        }
+    }
+}
+
+/// A set of permissions, kept in declaration order without duplicates
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Permissions(Vec<Permission>);
+
+impl Permissions {
+    /// The union of the permissions granted by `roles`
+    pub fn for_roles(roles: &[UserRole]) -> Self {
+        roles
+            .iter()
+            .flat_map(|role| role.permissions().iter().cloned())
+            .collect()
+    }
+
+    pub fn contains(&self, permission: &Permission) -> bool {
+        self.0.contains(permission)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The permissions held by both sets
+    pub fn intersection(&self, other: &Permissions) -> Self {
+        self.0
+            .iter()
+            .filter(|p| other.contains(p))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether any permission goes beyond reading the organization's data
+    pub fn allows_changes(&self) -> bool {
+        self.0.iter().any(|p| *p != Permission::ItemsRead)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Permission> {
+        self.0.iter()
+    }
+
+    pub fn into_vec(self) -> Vec<Permission> {
+        self.0
+    }
+}
+
+impl FromIterator<Permission> for Permissions {
+    fn from_iter<I: IntoIterator<Item = Permission>>(iter: I) -> Self {
+        let held: Vec<Permission> = iter.into_iter().collect();
+        Self(
+            Permission::ALL
+                .iter()
+                .filter(|p| held.contains(p))
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+impl From<Vec<Permission>> for Permissions {
+    fn from(permissions: Vec<Permission>) -> Self {
+        permissions.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_presets() {
+        let viewer = Permissions::for_roles(&[UserRole::Viewer]);
+        assert!(viewer.contains(&Permission::ItemsRead));
+        assert!(!viewer.allows_changes());
+
+        let curator = Permissions::for_roles(&[UserRole::Curator]);
+        assert!(curator.contains(&Permission::CatalogWrite));
+        assert!(!curator.contains(&Permission::SettingsWrite));
+
+        let admin = Permissions::for_roles(&[UserRole::Admin]);
+        assert_eq!(admin.into_vec(), Permission::ALL.to_vec());
+    }
+
+    #[test]
+    fn test_union_is_ordered_without_duplicates() {
+        let permissions = Permissions::for_roles(&[UserRole::LoanManager, UserRole::User]);
+        assert_eq!(
+            permissions.into_vec(),
+            vec![
+                Permission::ItemsRead,
+                Permission::ItemsWrite,
+                Permission::PagesWrite,
+                Permission::LoansOverride,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_intersection() {
+        let user = Permissions::for_roles(&[UserRole::User]);
+        let loan_manager = Permissions::for_roles(&[UserRole::LoanManager]);
+        assert_eq!(
+            user.intersection(&loan_manager).into_vec(),
+            vec![Permission::ItemsRead, Permission::ItemsWrite]
+        );
+        assert!(Permissions::default().intersection(&user).is_empty());
+    }
+}
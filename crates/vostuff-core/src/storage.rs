@@ -0,0 +1,397 @@
+//! Blob storage for exports and other files kept outside the database.
+//!
+//! Deployments pick a backend with [`StorageConfig`]: the local filesystem
+//! (the default) or an S3-compatible bucket. Handlers only see the
+//! [`Storage`] trait.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use object_store::{ObjectStore, aws::AmazonS3, aws::AmazonS3Builder, signer::Signer};
+use sha2::Sha256;
+
+#[cfg(feature = "server")]
+use crate::config::{Config, StorageBackend};
+
+/// Storage errors
+#[derive(Debug)]
+pub enum StorageError {
+    /// No object stored under the key
+    NotFound(String),
+    /// Key is empty or tries to escape the storage root
+    InvalidKey(String),
+    /// Backend failure (I/O, network, configuration)
+    Backend(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::NotFound(key) => write!(f, "Object not found: {}", key),
+            StorageError::InvalidKey(key) => write!(f, "Invalid storage key: {}", key),
+            StorageError::Backend(msg) => write!(f, "Storage error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+/// A blob store addressed by slash-separated keys such as `exports/<id>.json`
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Store an object, replacing any existing one
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), StorageError>;
+
+    /// Fetch an object's contents
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+
+    /// Remove an object; removing a missing object is not an error
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+
+    /// A URL that allows anyone holding it to download the object until it expires
+    async fn presign(&self, key: &str, expires_in: Duration) -> Result<String, StorageError>;
+
+    /// Check a presigned URL's expiry (unix seconds) and signature.
+    ///
+    /// Only backends whose presigned URLs the API serves itself (local storage)
+    /// accept anything; S3 URLs are served by S3.
+    fn verify_presigned(&self, _key: &str, _expires: i64, _signature: &str) -> bool {
+        false
+    }
+}
+
+/// Keys are relative paths of `[A-Za-z0-9._-]` segments; no empty, `.` or `..` segments
+pub fn validate_key(key: &str) -> Result<(), StorageError> {
+    let valid = !key.is_empty()
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidKey(key.to_string()))
+    }
+}
+
+// ── Configuration ──────────────────────────────────────────────────────────
+
+/// Which storage backend to use
+#[derive(Debug, Clone)]
+pub enum StorageConfig {
+    Local {
+        root: PathBuf,
+        /// Base URL presigned links point at; the API serves it
+        public_url: String,
+    },
+    S3 {
+        bucket: String,
+        region: Option<String>,
+        /// Custom endpoint for S3-compatible services such as MinIO
+        endpoint: Option<String>,
+    },
+}
+
+impl StorageConfig {
+    /// The backend chosen by the `[storage]` and `[s3]` settings, which
+    /// [`Config::load`] has checked
+    #[cfg(feature = "server")]
+    pub fn from_config(config: &Config) -> Self {
+        match config.storage.backend {
+            StorageBackend::Local => StorageConfig::Local {
+                root: config.storage.local_root.clone(),
+                public_url: config.storage.public_url.clone(),
+            },
+            StorageBackend::S3 => StorageConfig::S3 {
+                bucket: config.s3.bucket.clone().unwrap_or_default(),
+                region: config.s3.region.clone(),
+                endpoint: config.s3.endpoint.clone(),
+            },
+        }
+    }
+
+    /// Build the configured backend. `signing_secret` signs local presigned URLs.
+    pub fn build(&self, signing_secret: &str) -> Result<Arc<dyn Storage>, StorageError> {
+        match self {
+            StorageConfig::Local { root, public_url } => Ok(Arc::new(LocalStorage::new(
+                root.clone(),
+                public_url.clone(),
+                signing_secret,
+            ))),
+            StorageConfig::S3 {
+                bucket,
+                region,
+                endpoint,
+            } => Ok(Arc::new(S3Storage::new(
+                bucket,
+                region.as_deref(),
+                endpoint.as_deref(),
+            )?)),
+        }
+    }
+}
+
+// ── Local filesystem ───────────────────────────────────────────────────────
+
+/// Stores objects as files under a root directory.
+///
+/// Presigned URLs carry an expiry and an HMAC signature that
+/// [`Storage::verify_presigned`] checks before the API serves the file.
+pub struct LocalStorage {
+    root: PathBuf,
+    public_url: String,
+    signing_key: Vec<u8>,
+}
+
+impl LocalStorage {
+    pub fn new(
+        root: impl Into<PathBuf>,
+        public_url: impl Into<String>,
+        signing_secret: &str,
+    ) -> Self {
+        Self {
+            root: root.into(),
+            public_url: public_url.into().trim_end_matches('/').to_string(),
+            signing_key: signing_secret.as_bytes().to_vec(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+
+    /// HMAC over the key and expiry of a presigned URL
+    fn mac(&self, key: &str, expires: i64) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.signing_key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(format!("{}\n{}", key, expires).as_bytes());
+        mac
+    }
+}
+
+fn io_error(key: &str, err: std::io::Error) -> StorageError {
+    if err.kind() == std::io::ErrorKind::NotFound {
+        StorageError::NotFound(key.to_string())
+    } else {
+        StorageError::Backend(err.to_string())
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| io_error(key, e))?;
+        }
+        // Write then rename so readers never see a partial file; the temporary
+        // name is unique so concurrent writes, even to keys sharing a stem,
+        // never share one
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let tmp = path.with_file_name(format!("{}.{}.partial", file_name, uuid::Uuid::new_v4()));
+        tokio::fs::write(&tmp, data)
+            .await
+            .map_err(|e| io_error(key, e))?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .map_err(|e| io_error(key, e))
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        tokio::fs::read(self.path(key)?)
+            .await
+            .map_err(|e| io_error(key, e))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(key, e)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn presign(&self, key: &str, expires_in: Duration) -> Result<String, StorageError> {
+        validate_key(key)?;
+        let expires = Utc::now().timestamp() + expires_in.as_secs() as i64;
+        Ok(format!(
+            "{}/{}?expires={}&signature={}",
+            self.public_url,
+            key,
+            expires,
+            hex::encode(self.mac(key, expires).finalize().into_bytes())
+        ))
+    }
+
+    fn verify_presigned(&self, key: &str, expires: i64, signature: &str) -> bool {
+        if expires < Utc::now().timestamp() {
+            return false;
+        }
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+        self.mac(key, expires).verify_slice(&signature).is_ok()
+    }
+}
+
+// ── S3 ─────────────────────────────────────────────────────────────────────
+
+/// Stores objects in an S3 (or S3-compatible) bucket
+pub struct S3Storage {
+    store: AmazonS3,
+}
+
+impl S3Storage {
+    pub fn new(
+        bucket: &str,
+        region: Option<&str>,
+        endpoint: Option<&str>,
+    ) -> Result<Self, StorageError> {
+        let mut builder = AmazonS3Builder::from_env().with_bucket_name(bucket);
+        if let Some(region) = region {
+            builder = builder.with_region(region);
+        }
+        if let Some(endpoint) = endpoint {
+            builder = builder
+                .with_endpoint(endpoint)
+                .with_allow_http(endpoint.starts_with("http://"));
+        }
+        let store = builder.build().map_err(object_error)?;
+        Ok(Self { store })
+    }
+}
+
+fn object_error(err: object_store::Error) -> StorageError {
+    match err {
+        object_store::Error::NotFound { path, .. } => StorageError::NotFound(path),
+        other => StorageError::Backend(other.to_string()),
+    }
+}
+
+fn object_path(key: &str) -> Result<object_store::path::Path, StorageError> {
+    validate_key(key)?;
+    Ok(object_store::path::Path::from(key))
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), StorageError> {
+        self.store
+            .put(&object_path(key)?, data.into())
+            .await
+            .map(|_| ())
+            .map_err(object_error)
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let result = self
+            .store
+            .get(&object_path(key)?)
+            .await
+            .map_err(object_error)?;
+        let bytes = result.bytes().await.map_err(object_error)?;
+        Ok(bytes.to_vec())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match self.store.delete(&object_path(key)?).await {
+            Err(object_store::Error::NotFound { .. }) | Ok(()) => Ok(()),
+            Err(e) => Err(object_error(e)),
+        }
+    }
+
+    async fn presign(&self, key: &str, expires_in: Duration) -> Result<String, StorageError> {
+        self.store
+            .signed_url(http::Method::GET, &object_path(key)?, expires_in)
+            .await
+            .map(|url| url.to_string())
+            .map_err(object_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_storage() -> LocalStorage {
+        let root =
+            std::env::temp_dir().join(format!("vostuff-storage-test-{}", uuid::Uuid::new_v4()));
+        LocalStorage::new(root, "http://localhost:8080/api/files/", "secret")
+    }
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key("exports/abc-123.json").is_ok());
+        for key in ["", "/etc/passwd", "a//b", "../x", "a/./b", "a b", "a\\b"] {
+            assert!(validate_key(key).is_err(), "{:?} should be rejected", key);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_put_get_delete() {
+        let storage = temp_storage();
+
+        storage.put("exports/a.json", b"{}".to_vec()).await.unwrap();
+        assert_eq!(storage.get("exports/a.json").await.unwrap(), b"{}");
+
+        storage.delete("exports/a.json").await.unwrap();
+        assert!(matches!(
+            storage.get("exports/a.json").await,
+            Err(StorageError::NotFound(_))
+        ));
+        // Deleting again is fine
+        storage.delete("exports/a.json").await.unwrap();
+
+        // Keys sharing a stem keep their own contents when written together
+        let (json, csv) = tokio::join!(
+            storage.put("exports/b.json", b"json".to_vec()),
+            storage.put("exports/b.csv", b"csv".to_vec())
+        );
+        json.unwrap();
+        csv.unwrap();
+        assert_eq!(storage.get("exports/b.json").await.unwrap(), b"json");
+        assert_eq!(storage.get("exports/b.csv").await.unwrap(), b"csv");
+
+        let _ = std::fs::remove_dir_all(storage.root());
+    }
+
+    #[tokio::test]
+    async fn test_local_presign_verifies() {
+        let storage = temp_storage();
+
+        let url = storage
+            .presign("exports/a.json", Duration::from_secs(60))
+            .await
+            .unwrap();
+        let (path, query) = url.split_once('?').unwrap();
+        assert_eq!(path, "http://localhost:8080/api/files/exports/a.json");
+
+        let params: std::collections::HashMap<_, _> =
+            query.split('&').filter_map(|p| p.split_once('=')).collect();
+        let expires: i64 = params["expires"].parse().unwrap();
+        let signature = params["signature"];
+
+        assert!(storage.verify_presigned("exports/a.json", expires, signature));
+        assert!(!storage.verify_presigned("exports/b.json", expires, signature));
+        assert!(!storage.verify_presigned("exports/a.json", expires + 1, signature));
+        assert!(!storage.verify_presigned("exports/a.json", Utc::now().timestamp() - 1, signature));
+    }
+}
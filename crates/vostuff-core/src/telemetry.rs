@@ -0,0 +1,198 @@
+//! Log output, and trace export to an OpenTelemetry collector.
+//!
+//! Both servers set up logging with [`init`]. Given an OTLP endpoint, INFO
+//! and higher spans are exported too, joined into one trace per browser
+//! request: the web server passes its trace to the API in a W3C
+//! `traceparent` header ([`inject_context`]), the API continues it
+//! ([`continue_trace`]), and every statement sqlx runs becomes a child span
+//! of the span that ran it, timed from sqlx's own `sqlx::query` event.
+
+use std::time::{Duration, SystemTime};
+
+use opentelemetry::trace::{
+    Span as _, SpanKind, TraceContextExt as _, Tracer as _, TracerProvider as _,
+};
+use opentelemetry::{KeyValue, global};
+use opentelemetry_http::{HeaderExtractor, HeaderInjector};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+use opentelemetry_sdk::{Resource, runtime};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_opentelemetry::{OpenTelemetrySpanExt, OtelData, PreSampledTracer};
+use tracing_subscriber::filter::{EnvFilter, filter_fn};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Target of the event sqlx logs after each statement
+const SQL_TARGET: &str = "sqlx::query";
+
+/// Flushes spans not yet exported when dropped; hold it until the server exits
+#[must_use]
+pub struct Telemetry {
+    provider: Option<TracerProvider>,
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Some(Err(e)) = self.provider.take().map(|provider| provider.shutdown()) {
+            eprintln!("Failed to flush traces: {}", e);
+        }
+    }
+}
+
+/// Log to stdout, filtered by `RUST_LOG` or else `default_filter`, and with
+/// an `otlp_endpoint` also export traces as `service_name`
+pub fn init(
+    service_name: &'static str,
+    default_filter: &str,
+    otlp_endpoint: Option<&str>,
+) -> anyhow::Result<Telemetry> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let log_filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
+    let log_layer = tracing_subscriber::fmt::layer().with_filter(log_filter);
+
+    let Some(endpoint) = otlp_endpoint else {
+        tracing_subscriber::registry().with(log_layer).try_init()?;
+        return Ok(Telemetry { provider: None });
+    };
+
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        .build()?;
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new([KeyValue::new("service.name", service_name)]))
+        .build();
+    let tracer = provider.tracer(service_name);
+
+    // Exported independently of the log filter, so RUST_LOG only affects logs
+    tracing_subscriber::registry()
+        .with(log_layer)
+        .with(
+            tracing_opentelemetry::layer()
+                .with_tracer(tracer.clone())
+                .with_filter(filter_fn(is_exported)),
+        )
+        .with(SqlSpans { tracer }.with_filter(filter_fn(|metadata| {
+            is_exported(metadata) || metadata.target() == SQL_TARGET
+        })))
+        .try_init()?;
+    Ok(Telemetry {
+        provider: Some(provider),
+    })
+}
+
+/// Spans sent to the collector; events become annotations on them
+fn is_exported(metadata: &Metadata<'_>) -> bool {
+    *metadata.level() <= Level::INFO
+}
+
+/// Add `span`'s trace to outgoing request headers
+pub fn inject_context(span: &tracing::Span, headers: &mut http::HeaderMap) {
+    let context = span.context();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(headers))
+    });
+}
+
+/// Make `span` part of the trace named in incoming request headers, if any
+pub fn continue_trace(span: &tracing::Span, headers: &http::HeaderMap) {
+    let context =
+        global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)));
+    if context.span().span_context().is_valid() {
+        span.set_parent(context);
+    }
+}
+
+/// Turns each `sqlx::query` event into a client span ending when the event
+/// fires and starting its reported duration before
+struct SqlSpans {
+    tracer: Tracer,
+}
+
+impl<S> Layer<S> for SqlSpans
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if event.metadata().target() != SQL_TARGET {
+            return;
+        }
+        // Statements outside any exported span, such as migrations, are skipped
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        let parent = {
+            let mut extensions = span.extensions_mut();
+            let Some(data) = extensions.get_mut::<OtelData>() else {
+                return;
+            };
+            self.tracer.sampled_context(data)
+        };
+
+        let mut statement = SqlStatement::default();
+        event.record(&mut statement);
+        let end = SystemTime::now();
+        let start = end - Duration::from_secs_f64(statement.elapsed_secs);
+        let text = if statement.text.trim().is_empty() {
+            statement.summary.clone()
+        } else {
+            statement.text.trim().to_string()
+        };
+
+        self.tracer
+            .span_builder(statement.summary)
+            .with_kind(SpanKind::Client)
+            .with_start_time(start)
+            .with_attributes([
+                KeyValue::new("db.system", "postgresql"),
+                KeyValue::new("db.statement", text),
+                KeyValue::new("db.rows_affected", statement.rows_affected as i64),
+                KeyValue::new("db.rows_returned", statement.rows_returned as i64),
+            ])
+            .start_with_context(&self.tracer, &parent)
+            .end_with_timestamp(end);
+    }
+}
+
+/// Fields of a `sqlx::query` event. The full text is only logged when the
+/// summary shortens it.
+#[derive(Default)]
+struct SqlStatement {
+    summary: String,
+    text: String,
+    rows_affected: u64,
+    rows_returned: u64,
+    elapsed_secs: f64,
+}
+
+impl Visit for SqlStatement {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "summary" => self.summary = value.to_string(),
+            "db.statement" => self.text = value.to_string(),
+            _ => {}
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match field.name() {
+            "rows_affected" => self.rows_affected = value,
+            "rows_returned" => self.rows_returned = value,
+            _ => {}
+        }
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = value;
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
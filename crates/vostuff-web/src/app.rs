@@ -2,9 +2,14 @@ use leptos::*;
 use leptos_meta::*;
 use leptos_router::*;
 
+use crate::pages::accept_invite::AcceptInvitePage;
+use crate::pages::borrowers::{BorrowerPage, BorrowersPage};
+use crate::pages::dashboard::DashboardPage;
+use crate::pages::docs::{DocPage, DocsPage};
 use crate::pages::home::HomePage;
 use crate::pages::login::LoginPage;
 use crate::pages::settings::SettingsPage;
+use crate::pages::shared::SharedItemPage;
 
 #[component]
 pub fn App() -> impl IntoView {
@@ -18,6 +23,13 @@ pub fn App() -> impl IntoView {
             <Routes>
                 <Route path="/" view=HomePage/>
                 <Route path="/login" view=LoginPage/>
+                <Route path="/accept-invite" view=AcceptInvitePage/>
+                <Route path="/shared" view=SharedItemPage/>
+                <Route path="/dashboard" view=DashboardPage/>
+                <Route path="/borrowers" view=BorrowersPage/>
+                <Route path="/borrowers/:id" view=BorrowerPage/>
+                <Route path="/docs" view=DocsPage/>
+                <Route path="/docs/:id" view=DocPage/>
                 <Route path="/settings" view=SettingsPage/>
             </Routes>
         </Router>
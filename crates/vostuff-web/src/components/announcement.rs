@@ -0,0 +1,219 @@
+use leptos::*;
+use uuid::Uuid;
+
+use crate::server_fns::auth::BroadcastNotice;
+use crate::server_fns::settings::{Announcement, get_org_settings, update_org_settings};
+
+/// localStorage key remembering which announcement a member dismissed
+fn dismissed_key(org_id: Uuid) -> String {
+    format!("vostuff.announcement.dismissed.{}", org_id)
+}
+
+/// Identifies an announcement so editing it shows the banner again
+fn fingerprint(a: &Announcement) -> String {
+    format!("{}|{}", a.expires_on, a.message)
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window().and_then(|w| w.local_storage().ok().flatten())
+}
+
+/// Banner showing the org's current announcement; members can dismiss it
+///
+/// The announcement comes from the settings snapshot in the current user's info.
+#[component]
+pub fn AnnouncementBanner(org_id: Uuid, announcement: Option<Announcement>) -> impl IntoView {
+    let (dismissed, set_dismissed) = create_signal::<Option<String>>(None);
+
+    // Effects only run in the browser, where localStorage is available
+    create_effect(move |_| {
+        let stored =
+            local_storage().and_then(|s| s.get_item(&dismissed_key(org_id)).ok().flatten());
+        set_dismissed.set(stored);
+    });
+
+    move || {
+        announcement
+            .clone()
+            .filter(|a| dismissed.get().as_deref() != Some(fingerprint(a).as_str()))
+            .map(|a| {
+                let print = fingerprint(&a);
+                view! {
+                    <div class="announcement-banner">
+                        <span>{a.message}</span>
+                        <button
+                            class="announcement-dismiss"
+                            title="Dismiss"
+                            on:click=move |_| {
+                                if let Some(storage) = local_storage() {
+                                    let _ = storage.set_item(&dismissed_key(org_id), &print);
+                                }
+                                set_dismissed.set(Some(print.clone()));
+                            }
+                        >
+                            "×"
+                        </button>
+                    </div>
+                }
+            })
+    }
+}
+
+/// localStorage key remembering that a member dismissed a broadcast
+fn broadcast_dismissed_key(broadcast: &BroadcastNotice) -> String {
+    format!("vostuff.broadcast.dismissed.{}", broadcast.id)
+}
+
+/// Banners for notices from the system administrators; members can dismiss each
+#[component]
+pub fn BroadcastBanners(broadcasts: Vec<BroadcastNotice>) -> impl IntoView {
+    let (dismissed, set_dismissed) = create_signal::<Vec<Uuid>>(Vec::new());
+
+    // Effects only run in the browser, where localStorage is available
+    let stored = broadcasts.clone();
+    create_effect(move |_| {
+        if let Some(storage) = local_storage() {
+            set_dismissed.set(
+                stored
+                    .iter()
+                    .filter(|b| {
+                        storage
+                            .get_item(&broadcast_dismissed_key(b))
+                            .ok()
+                            .flatten()
+                            .is_some()
+                    })
+                    .map(|b| b.id)
+                    .collect(),
+            );
+        }
+    });
+
+    move || {
+        broadcasts
+            .iter()
+            .filter(|b| !dismissed.get().contains(&b.id))
+            .cloned()
+            .map(|b| {
+                let key = broadcast_dismissed_key(&b);
+                view! {
+                    <div class="announcement-banner broadcast-banner">
+                        <span>{b.message}</span>
+                        <button
+                            class="announcement-dismiss"
+                            title="Dismiss"
+                            on:click=move |_| {
+                                if let Some(storage) = local_storage() {
+                                    let _ = storage.set_item(&key, "1");
+                                }
+                                set_dismissed.update(|d| d.push(b.id));
+                            }
+                        >
+                            "×"
+                        </button>
+                    </div>
+                }
+            })
+            .collect_view()
+    }
+}
+
+/// Admin editor for the org announcement
+#[component]
+pub fn AnnouncementSettings(org_id: Uuid) -> impl IntoView {
+    let message = create_rw_signal(String::new());
+    let expires_on = create_rw_signal(String::new());
+    let (status, set_status) = create_signal::<Option<Result<(), String>>>(None);
+
+    let settings_resource =
+        create_resource(move || org_id, |o| async move { get_org_settings(o).await });
+
+    // Fill the form once settings arrive
+    create_effect(move |_| {
+        if let Some(Ok(settings)) = settings_resource.get()
+            && let Some(a) = settings.announcement
+        {
+            message.set(a.message);
+            expires_on.set(a.expires_on.to_string());
+        }
+    });
+
+    // None clears the announcement; settings are replaced as a whole, so re-read them first
+    let save_action = create_action(move |announcement: &Option<Announcement>| {
+        let announcement = announcement.clone();
+        async move {
+            let mut settings = get_org_settings(org_id).await?;
+            settings.announcement = announcement;
+            update_org_settings(org_id, settings).await
+        }
+    });
+
+    create_effect(move |_| {
+        if let Some(result) = save_action.value().get() {
+            set_status.set(Some(result.map(|_| ()).map_err(|e| e.to_string())));
+        }
+    });
+
+    let on_publish = move |_| match chrono::NaiveDate::parse_from_str(&expires_on.get(), "%Y-%m-%d")
+    {
+        Ok(date) => save_action.dispatch(Some(Announcement {
+            message: message.get().trim().to_string(),
+            expires_on: date,
+        })),
+        Err(_) => set_status.set(Some(Err("Choose an expiry date".to_string()))),
+    };
+
+    let on_clear = move |_| {
+        message.set(String::new());
+        expires_on.set(String::new());
+        save_action.dispatch(None);
+    };
+
+    view! {
+        <div class="mgmt-section">
+            <h3>"Announcement"</h3>
+            <p style="color:#888;font-size:13px;">
+                "Shown as a banner to every member until the end of the expiry date."
+            </p>
+            <div class="form-group">
+                <label>"Message"</label>
+                <textarea
+                    class="form-control"
+                    maxlength="500"
+                    placeholder="e.g. Stocktake this weekend - don't move items"
+                    prop:value=message
+                    on:input=move |ev| message.set(event_target_value(&ev))
+                ></textarea>
+            </div>
+            <div class="form-group">
+                <label>"Show until"</label>
+                <input
+                    type="date"
+                    class="form-control"
+                    prop:value=expires_on
+                    on:input=move |ev| expires_on.set(event_target_value(&ev))
+                />
+            </div>
+            <button
+                class="btn btn-primary"
+                disabled=move || save_action.pending().get()
+                on:click=on_publish
+            >
+                "Publish"
+            </button>
+            <button
+                class="btn btn-secondary"
+                style="margin-left:8px;"
+                disabled=move || save_action.pending().get()
+                on:click=on_clear
+            >
+                "Clear"
+            </button>
+            {move || match status.get() {
+                Some(Ok(())) => view! { <span style="margin-left:12px;">"Saved"</span> }.into_view(),
+                Some(Err(e)) => view! { <div class="error">{e}</div> }.into_view(),
+                None => ().into_view(),
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,256 @@
+use leptos::*;
+use wasm_bindgen::prelude::*;
+
+// Camera scanning uses the browser's BarcodeDetector API where there is one,
+// and otherwise decodes video frames with the ZXing library, loaded on first
+// use from the copy vendored under /vendor (see scripts/vendor-zxing.sh).
+// Without a camera (or with a USB scanner, which types the code like a
+// keyboard) there is the manual entry box.
+#[wasm_bindgen(inline_js = r#"
+const ZXING_URL = '/vendor/zxing-library-0.21.3.min.js';
+let zxingLoading = null;
+
+export function cameraScanSupported() {
+    return typeof window !== 'undefined'
+        && !!navigator.mediaDevices
+        && !!navigator.mediaDevices.getUserMedia;
+}
+
+function loadZxing() {
+    if (window.ZXing) return Promise.resolve(window.ZXing);
+    if (!zxingLoading) {
+        zxingLoading = new Promise((resolve, reject) => {
+            const script = document.createElement('script');
+            script.src = ZXING_URL;
+            script.onload = () => resolve(window.ZXing);
+            script.onerror = () => {
+                zxingLoading = null;
+                reject('could not load the barcode decoder');
+            };
+            document.head.appendChild(script);
+        });
+    }
+    return zxingLoading;
+}
+
+// Resolves to { read, delay }: read() gives a promise of the code in the
+// current video frame, or null, and delay is the pause in ms between reads
+function frameDecoder(video) {
+    if ('BarcodeDetector' in window) {
+        const detector = new BarcodeDetector();
+        return Promise.resolve({
+            read: () => detector
+                .detect(video)
+                .then((codes) => (codes.length > 0 ? codes[0].rawValue : null)),
+            delay: 0,
+        });
+    }
+    return loadZxing().then((ZXing) => {
+        const reader = new ZXing.MultiFormatReader();
+        const canvas = document.createElement('canvas');
+        const context = canvas.getContext('2d', { willReadFrequently: true });
+        const read = () => {
+            if (!video.videoWidth) return Promise.resolve(null);
+            canvas.width = video.videoWidth;
+            canvas.height = video.videoHeight;
+            context.drawImage(video, 0, 0);
+            const source = new ZXing.HTMLCanvasElementLuminanceSource(canvas);
+            try {
+                const bitmap = new ZXing.BinaryBitmap(new ZXing.HybridBinarizer(source));
+                return Promise.resolve(reader.decode(bitmap).getText());
+            } catch (e) {
+                // Not found, checksum and format errors all mean no code in this frame
+                return Promise.resolve(null);
+            }
+        };
+        // Decoding in JS is slow; a few frames a second is plenty
+        return { read, delay: 150 };
+    });
+}
+
+export function startBarcodeScan(video, done) {
+    let finished = false;
+    const finish = (code, error) => {
+        if (finished) return;
+        finished = true;
+        const stream = video.srcObject;
+        if (stream) stream.getTracks().forEach((t) => t.stop());
+        video.srcObject = null;
+        video.__stopBarcodeScan = null;
+        done(code, error);
+    };
+    video.__stopBarcodeScan = () => finish(null, null);
+
+    // The stream goes on the video before the decoder loads, so finish()
+    // stops the camera whatever fails afterwards
+    navigator.mediaDevices
+        .getUserMedia({ video: { facingMode: 'environment' } })
+        .then((stream) => {
+            if (finished) {
+                stream.getTracks().forEach((t) => t.stop());
+                return;
+            }
+            video.srcObject = stream;
+            return frameDecoder(video).then((decoder) => {
+                if (finished) return;
+                return video.play().then(() => {
+                    const next = () =>
+                        decoder.delay > 0 ? setTimeout(tick, decoder.delay) : requestAnimationFrame(tick);
+                    const tick = () => {
+                        if (finished) return;
+                        decoder
+                            .read()
+                            .then((code) => {
+                                if (code) finish(code, null);
+                                else next();
+                            })
+                            .catch((e) => finish(null, String(e)));
+                    };
+                    tick();
+                });
+            });
+        })
+        .catch((e) => finish(null, String(e)));
+}
+
+export function stopBarcodeScan(video) {
+    if (video.__stopBarcodeScan) video.__stopBarcodeScan();
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = cameraScanSupported)]
+    fn camera_scan_supported() -> bool;
+
+    /// Starts the camera and calls `done(code, error)` exactly once: with the
+    /// first detected code, with an error message, or with neither if stopped.
+    #[wasm_bindgen(js_name = startBarcodeScan)]
+    fn start_barcode_scan(video: &web_sys::HtmlVideoElement, done: JsValue);
+
+    #[wasm_bindgen(js_name = stopBarcodeScan)]
+    fn stop_barcode_scan(video: &web_sys::HtmlVideoElement);
+}
+
+/// Modal that reads a barcode from the device camera, or from manual entry
+#[component]
+pub fn BarcodeScanner(
+    show: ReadSignal<bool>,
+    on_close: Callback<()>,
+    /// Called with the scanned (or typed) barcode
+    on_scanned: Callback<String>,
+) -> impl IntoView {
+    let video_ref = create_node_ref::<html::Video>();
+    let manual_code = create_rw_signal(String::new());
+    let camera_supported = create_rw_signal(true);
+    let camera_error = create_rw_signal::<Option<String>>(None);
+
+    // Start the camera once the modal (and its <video>) is mounted
+    create_effect(move |_| {
+        if !show.get() {
+            return;
+        }
+        let Some(video) = video_ref.get() else {
+            return;
+        };
+        if !camera_scan_supported() {
+            camera_supported.set(false);
+            return;
+        }
+        camera_supported.set(true);
+        camera_error.set(None);
+
+        let done = Closure::once_into_js(move |code: Option<String>, error: Option<String>| {
+            if let Some(code) = code {
+                manual_code.set(String::new());
+                on_scanned.call(code);
+            } else if let Some(e) = error {
+                camera_error.set(Some(e));
+            }
+        });
+        start_barcode_scan(&video, done);
+    });
+
+    let stop_camera = move || {
+        if let Some(video) = video_ref.get_untracked() {
+            stop_barcode_scan(&video);
+        }
+    };
+
+    let close = move || {
+        stop_camera();
+        manual_code.set(String::new());
+        camera_error.set(None);
+        on_close.call(());
+    };
+
+    let submit_manual = move || {
+        let code = manual_code.get_untracked().trim().to_string();
+        if code.is_empty() {
+            return;
+        }
+        stop_camera();
+        manual_code.set(String::new());
+        on_scanned.call(code);
+    };
+
+    view! {
+        <Show when=move || show.get() fallback=|| ()>
+            <div class="modal-overlay" on:click=move |_| close()>
+                <div class="modal" on:click=move |ev| ev.stop_propagation()>
+                    <div class="modal-header">
+                        <h2>"Scan Barcode"</h2>
+                    </div>
+                    <div class="modal-body">
+                        <Show
+                            when=move || camera_supported.get()
+                            fallback=|| view! {
+                                <p class="scanner-hint">
+                                    "This browser has no camera access. Enter the barcode below."
+                                </p>
+                            }
+                        >
+                            <video
+                                class="scanner-video"
+                                node_ref=video_ref
+                                autoplay=true
+                                muted=true
+                                playsinline=true
+                            />
+                        </Show>
+                        <Show when=move || camera_error.get().is_some() fallback=|| ()>
+                            <div class="error">
+                                {move || format!("Camera unavailable: {}", camera_error.get().unwrap_or_default())}
+                            </div>
+                        </Show>
+                        <div class="form-group">
+                            <label>"Barcode"</label>
+                            <input
+                                type="text"
+                                class="form-control"
+                                placeholder="Type or scan a barcode"
+                                prop:value=manual_code
+                                on:input=move |ev| manual_code.set(event_target_value(&ev))
+                                on:keydown=move |ev: web_sys::KeyboardEvent| {
+                                    if ev.key() == "Enter" {
+                                        submit_manual();
+                                    }
+                                }
+                            />
+                        </div>
+                    </div>
+                    <div class="modal-footer">
+                        <button class="btn btn-secondary" on:click=move |_| close()>
+                            "Cancel"
+                        </button>
+                        <button
+                            class="btn btn-primary"
+                            style="width:auto;"
+                            on:click=move |_| submit_manual()
+                        >
+                            "Search"
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </Show>
+    }
+}
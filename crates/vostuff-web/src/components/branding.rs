@@ -0,0 +1,140 @@
+use leptos::*;
+use uuid::Uuid;
+use web_sys::{FormData, HtmlFormElement};
+
+use crate::server_fns::settings::{
+    OrganizationSettings, delete_org_logo, get_org_settings, update_org_settings, upload_org_logo,
+};
+
+/// Accent used when the organization has not chosen one; matches main.css
+const DEFAULT_ACCENT: &str = "#4a90e2";
+
+/// Accent colour and logo, applied to the header on the next page load
+#[component]
+pub fn BrandingSettings(org_id: Uuid) -> impl IntoView {
+    let accent = create_rw_signal(DEFAULT_ACCENT.to_string());
+    let (status, set_status) = create_signal::<Option<Result<String, String>>>(None);
+
+    let settings_resource =
+        create_resource(move || org_id, |o| async move { get_org_settings(o).await });
+
+    create_effect(move |_| {
+        if let Some(Ok(settings)) = settings_resource.get() {
+            accent.set(
+                settings
+                    .accent_color
+                    .unwrap_or_else(|| DEFAULT_ACCENT.to_string()),
+            );
+        }
+    });
+
+    // Settings are replaced as a whole, so re-read them to keep other sections intact
+    let save_color = create_action(move |color: &Option<String>| {
+        let accent_color = color.clone();
+        async move {
+            let settings = OrganizationSettings {
+                accent_color,
+                ..get_org_settings(org_id).await?
+            };
+            update_org_settings(org_id, settings).await.map(|_| ())
+        }
+    });
+    let upload_logo = create_action(|data: &FormData| upload_org_logo(data.clone().into()));
+    let remove_logo = create_action(move |_: &()| delete_org_logo(org_id));
+
+    for (action, done) in [
+        (save_color.value(), "Accent colour saved"),
+        (upload_logo.value(), "Logo uploaded"),
+        (remove_logo.value(), "Logo removed"),
+    ] {
+        create_effect(move |_| {
+            if let Some(result) = action.get() {
+                set_status.set(Some(
+                    result
+                        .map(|_| format!("{}. Reload the page to see it.", done))
+                        .map_err(|e| e.to_string()),
+                ));
+            }
+        });
+    }
+
+    view! {
+        <div class="mgmt-section">
+            <h3>"Branding"</h3>
+            <p style="color:#888;font-size:13px;">
+                "Shown to every member in the header and on buttons."
+            </p>
+            <div class="form-group">
+                <label>"Accent colour"</label>
+                <input
+                    type="color"
+                    prop:value=accent
+                    on:input=move |ev| accent.set(event_target_value(&ev))
+                />
+            </div>
+            <button
+                class="btn btn-primary"
+                style="width:auto;"
+                disabled=move || save_color.pending().get()
+                on:click=move |_| save_color.dispatch(Some(accent.get_untracked()))
+            >
+                "Save colour"
+            </button>
+            " "
+            <button
+                class="btn btn-secondary"
+                disabled=move || save_color.pending().get()
+                on:click=move |_| {
+                    accent.set(DEFAULT_ACCENT.to_string());
+                    save_color.dispatch(None);
+                }
+            >
+                "Use default"
+            </button>
+
+            <form
+                style="margin-top:16px;"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    let form = event_target::<HtmlFormElement>(&ev);
+                    if let Ok(data) = FormData::new_with_form(&form) {
+                        upload_logo.dispatch(data);
+                    }
+                }
+            >
+                <div class="form-group">
+                    <label>"Logo (PNG, JPEG, WebP or GIF, at most 512 KB)"</label>
+                    <input type="hidden" name="org_id" value=org_id.to_string() />
+                    <input
+                        type="file"
+                        name="logo"
+                        accept="image/png,image/jpeg,image/webp,image/gif"
+                        required
+                    />
+                </div>
+                <button
+                    type="submit"
+                    class="btn btn-primary"
+                    style="width:auto;"
+                    disabled=move || upload_logo.pending().get()
+                >
+                    "Upload logo"
+                </button>
+                " "
+                <button
+                    type="button"
+                    class="btn btn-secondary"
+                    disabled=move || remove_logo.pending().get()
+                    on:click=move |_| remove_logo.dispatch(())
+                >
+                    "Remove logo"
+                </button>
+            </form>
+            {move || match status.get() {
+                Some(Ok(message)) => view! { <p>{message}</p> }.into_view(),
+                Some(Err(e)) => view! { <div class="error">{e}</div> }.into_view(),
+                None => ().into_view(),
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,241 @@
+use leptos::leptos_dom::helpers::TimeoutHandle;
+use leptos::*;
+use leptos_router::*;
+use uuid::Uuid;
+
+use crate::components::filter_dropdown::SEARCH_DEBOUNCE;
+use crate::server_fns::items::{encode_query_value, find_items, get_collections};
+
+/// Most items offered for one search
+const ITEM_LIMIT: i64 = 8;
+
+/// Shortest text that searches items
+const MIN_ITEM_SEARCH: usize = 2;
+
+/// Actions and pages, offered before anything is typed: (section, label, target)
+const COMMANDS: &[(&str, &str, &str)] = &[
+    ("Actions", "Add item", "/?new=1"),
+    ("Actions", "Scan barcode", "/?scan=1"),
+    ("Pages", "Items", "/"),
+    ("Pages", "Dashboard", "/dashboard"),
+    ("Pages", "Borrowers", "/borrowers"),
+    ("Pages", "Docs", "/docs"),
+    ("Pages", "Settings", "/settings"),
+];
+
+/// One line in the palette; choosing it goes to `href`
+#[derive(Clone, Debug, PartialEq)]
+struct Entry {
+    section: &'static str,
+    label: String,
+    hint: Option<String>,
+    href: String,
+}
+
+/// Ctrl/Cmd-K palette for jumping to an item, a collection or a page, or
+/// starting an action. Items are found by name or catalogue number.
+#[component]
+pub fn CommandPalette(org_id: Uuid, open: RwSignal<bool>) -> impl IntoView {
+    let input = create_rw_signal(String::new());
+    let committed = create_rw_signal(String::new());
+    let selected = create_rw_signal(0usize);
+    let input_ref = create_node_ref::<html::Input>();
+
+    let handle = window_event_listener(ev::keydown, move |ev| {
+        if (ev.ctrl_key() || ev.meta_key()) && ev.key().eq_ignore_ascii_case("k") {
+            ev.prevent_default();
+            open.update(|o| *o = !*o);
+        }
+    });
+    on_cleanup(move || handle.remove());
+
+    // Start afresh each time it opens
+    create_effect(move |_| {
+        if open.get() {
+            input.set(String::new());
+            committed.set(String::new());
+            selected.set(0);
+        }
+    });
+    create_effect(move |_| {
+        if open.get()
+            && let Some(el) = input_ref.get()
+        {
+            let _ = el.focus();
+        }
+    });
+
+    // As in the list's search box, only the last keystroke's timer searches
+    let pending = store_value::<Option<TimeoutHandle>>(None);
+    let cancel_pending = move || {
+        if let Some(handle) = pending.get_value() {
+            handle.clear();
+        }
+        pending.set_value(None);
+    };
+    on_cleanup(cancel_pending);
+
+    // Collections are few, so they are fetched on opening and matched here
+    let collections = create_resource(
+        move || open.get().then_some(org_id),
+        |org_id| async move {
+            match org_id {
+                Some(org_id) => get_collections(org_id).await.unwrap_or_default(),
+                None => Vec::new(),
+            }
+        },
+    );
+    let items = create_resource(
+        move || committed.get().trim().to_string(),
+        move |text| async move {
+            if text.chars().count() < MIN_ITEM_SEARCH {
+                return Vec::new();
+            }
+            find_items(org_id, text, ITEM_LIMIT)
+                .await
+                .unwrap_or_default()
+        },
+    );
+
+    let entries = create_memo(move |_| {
+        let text = input.get().trim().to_lowercase();
+        let mut entries: Vec<Entry> = COMMANDS
+            .iter()
+            .filter(|(_, label, _)| label.to_lowercase().contains(&text))
+            .map(|(section, label, href)| Entry {
+                section,
+                label: label.to_string(),
+                hint: None,
+                href: href.to_string(),
+            })
+            .collect();
+        if text.is_empty() {
+            return entries;
+        }
+        entries.extend(
+            collections
+                .get()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|c| c.name.to_lowercase().contains(&text))
+                .map(|c| Entry {
+                    section: "Collections",
+                    label: c.name,
+                    hint: c.description,
+                    href: format!("/?collection_id={}", c.id),
+                }),
+        );
+        entries.extend(items.get().unwrap_or_default().into_iter().map(|item| {
+            let hint = match item.catalog_code {
+                Some(code) => format!("{} · {}", item.kind_name, code),
+                None => item.kind_name,
+            };
+            Entry {
+                section: "Items",
+                href: format!(
+                    "/?search={}&item={}",
+                    encode_query_value(&item.name),
+                    item.id
+                ),
+                label: item.name,
+                hint: Some(hint),
+            }
+        }));
+        entries
+    });
+
+    let navigate = use_navigate();
+    let choose = Callback::new(move |index: usize| {
+        if let Some(entry) = entries.get_untracked().get(index) {
+            cancel_pending();
+            open.set(false);
+            navigate(&entry.href, NavigateOptions::default());
+        }
+    });
+
+    view! {
+        <Show when=move || open.get() fallback=|| ()>
+            <div class="modal-overlay palette-overlay" on:click=move |_| open.set(false)>
+                <div class="modal palette" on:click=move |ev| ev.stop_propagation()>
+                    <input
+                        type="text"
+                        class="palette-input"
+                        placeholder="Go to an item, collection or page..."
+                        node_ref=input_ref
+                        prop:value=move || input.get()
+                        on:input=move |ev| {
+                            let text = event_target_value(&ev);
+                            input.set(text.clone());
+                            selected.set(0);
+                            cancel_pending();
+                            pending
+                                .set_value(
+                                    set_timeout_with_handle(
+                                            move || committed.set(text),
+                                            SEARCH_DEBOUNCE,
+                                        )
+                                        .ok(),
+                                );
+                        }
+                        on:keydown=move |ev: web_sys::KeyboardEvent| {
+                            let count = entries.get_untracked().len();
+                            match ev.key().as_str() {
+                                "ArrowDown" if count > 0 => {
+                                    ev.prevent_default();
+                                    selected.update(|i| *i = (*i + 1) % count);
+                                }
+                                "ArrowUp" if count > 0 => {
+                                    ev.prevent_default();
+                                    selected.update(|i| *i = (*i + count - 1) % count);
+                                }
+                                "Enter" => {
+                                    ev.prevent_default();
+                                    choose.call(selected.get_untracked());
+                                }
+                                "Escape" => open.set(false),
+                                _ => {}
+                            }
+                        }
+                    />
+                    <ul class="palette-results">
+                        {move || {
+                            let entries = entries.get();
+                            if entries.is_empty() {
+                                return view! { <li class="palette-empty">"No matches"</li> }
+                                    .into_view();
+                            }
+                            let mut section = "";
+                            entries
+                                .into_iter()
+                                .enumerate()
+                                .map(|(index, entry)| {
+                                    let heading = (entry.section != section)
+                                        .then(|| {
+                                            section = entry.section;
+                                            view! { <li class="palette-section">{entry.section}</li> }
+                                        });
+                                    view! {
+                                        {heading}
+                                        <li
+                                            class="palette-entry"
+                                            class:selected=move || selected.get() == index
+                                            on:mouseenter=move |_| selected.set(index)
+                                            on:click=move |_| choose.call(index)
+                                        >
+                                            <span>{entry.label}</span>
+                                            {entry
+                                                .hint
+                                                .map(|hint| {
+                                                    view! { <span class="palette-hint">{hint}</span> }
+                                                })}
+                                        </li>
+                                    }
+                                })
+                                .collect_view()
+                        }}
+                    </ul>
+                </div>
+            </div>
+        </Show>
+    }
+}
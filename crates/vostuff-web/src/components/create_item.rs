@@ -2,8 +2,11 @@ use leptos::*;
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::components::save_warnings::SaveWarnings;
 use crate::components::soft_field_helpers::{format_field_name, render_soft_field_input};
-use crate::server_fns::items::{CreateItemRequest, Location, create_item, get_locations};
+use crate::server_fns::items::{
+    CreateItemRequest, Location, create_item, get_locations, lookup_code,
+};
 use crate::server_fns::kinds::{KindFieldDef, get_kind_fields, get_kinds};
 
 #[component]
@@ -12,6 +15,9 @@ pub fn CreateItemModal(
     show: ReadSignal<bool>,
     on_close: Callback<()>,
     on_created: Callback<()>,
+    /// Barcode to prefill when the modal opens (e.g. from a scan with no match)
+    #[prop(optional)]
+    initial_barcode: Option<ReadSignal<String>>,
 ) -> impl IntoView {
     let kind_id = create_rw_signal::<Option<Uuid>>(None);
     let name = create_rw_signal(String::new());
@@ -19,9 +25,13 @@ pub fn CreateItemModal(
     let notes = create_rw_signal(String::new());
     let location_id = create_rw_signal(String::new());
     let date_acquired = create_rw_signal(String::new());
+    let barcode = create_rw_signal(String::new());
+    let catalog_code = create_rw_signal(String::new());
     let soft_field_map = create_rw_signal::<HashMap<String, serde_json::Value>>(HashMap::new());
     let saving = create_rw_signal(false);
     let error = create_rw_signal::<Option<String>>(None);
+    let looking_up = create_rw_signal(false);
+    let lookup_note = create_rw_signal::<Option<String>>(None);
 
     let reset_form = move || {
         kind_id.set(None);
@@ -30,9 +40,53 @@ pub fn CreateItemModal(
         notes.set(String::new());
         location_id.set(String::new());
         date_acquired.set(String::new());
+        barcode.set(String::new());
+        catalog_code.set(String::new());
         soft_field_map.set(HashMap::new());
         saving.set(false);
         error.set(None);
+        lookup_note.set(None);
+    };
+
+    // Fill the form from what a metadata service knows about the barcode,
+    // keeping anything already typed
+    let look_up = move || {
+        let code = barcode.get_untracked().trim().to_string();
+        if code.is_empty() {
+            error.set(Some("Enter a barcode or ISBN to look up".to_string()));
+            return;
+        }
+        error.set(None);
+        lookup_note.set(None);
+        looking_up.set(true);
+        spawn_local(async move {
+            match lookup_code(org_id, code).await {
+                Ok(found) => {
+                    let item = found.item;
+                    // Setting the kind clears the soft fields, so it goes first
+                    kind_id.set(Some(item.kind_id));
+                    if let Some(serde_json::Value::Object(fields)) = item.soft_fields {
+                        soft_field_map.set(fields.into_iter().collect());
+                    }
+                    let fill = |field: RwSignal<String>, value: Option<String>| {
+                        if field.get_untracked().is_empty()
+                            && let Some(value) = value
+                        {
+                            field.set(value);
+                        }
+                    };
+                    fill(name, Some(item.name));
+                    fill(description, item.description);
+                    fill(catalog_code, item.catalog_code);
+                    if let Some(code) = item.barcode {
+                        barcode.set(code);
+                    }
+                    lookup_note.set(Some(format!("Filled in from {}", found.provider)));
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+            looking_up.set(false);
+        });
     };
 
     // Clear soft fields when kind changes
@@ -44,6 +98,15 @@ pub fn CreateItemModal(
         current
     });
 
+    // Prefill the barcode each time the modal opens
+    create_effect(move |_| {
+        if show.get()
+            && let Some(initial) = initial_barcode
+        {
+            barcode.set(initial.get_untracked());
+        }
+    });
+
     let kinds_resource = create_resource(
         move || org_id,
         |org_id| async move { get_kinds(org_id).await },
@@ -75,6 +138,8 @@ pub fn CreateItemModal(
         let nts = notes.get_untracked();
         let loc_str = location_id.get_untracked();
         let date_str = date_acquired.get_untracked();
+        let bc = barcode.get_untracked().trim().to_string();
+        let cat = catalog_code.get_untracked().trim().to_string();
         let raw_map = soft_field_map.get_untracked();
 
         async move {
@@ -103,6 +168,8 @@ pub fn CreateItemModal(
                 } else {
                     chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d").ok()
                 },
+                barcode: if bc.is_empty() { None } else { Some(bc) },
+                catalog_code: if cat.is_empty() { None } else { Some(cat) },
                 soft_fields: if sf_map.is_empty() {
                     None
                 } else {
@@ -110,7 +177,10 @@ pub fn CreateItemModal(
                 },
             };
 
-            create_item(org_id, req).await
+            let item_name = req.name.clone();
+            create_item(org_id, req)
+                .await
+                .map(|warnings| (item_name, warnings))
         }
     });
 
@@ -118,7 +188,8 @@ pub fn CreateItemModal(
         if let Some(result) = save_action.value().get() {
             saving.set(false);
             match result {
-                Ok(()) => {
+                Ok((item_name, warnings)) => {
+                    SaveWarnings::report(item_name, warnings);
                     on_created.call(());
                     on_close.call(());
                     reset_form();
@@ -230,6 +301,39 @@ pub fn CreateItemModal(
                                 on:input=move |ev| date_acquired.set(event_target_value(&ev))
                             />
                         </div>
+                        <div class="form-group">
+                            <label>"Barcode / ISBN"</label>
+                            <div style="display:flex;gap:8px;">
+                                <input
+                                    type="text"
+                                    class="form-control"
+                                    prop:value=barcode
+                                    on:input=move |ev| barcode.set(event_target_value(&ev))
+                                />
+                                <button
+                                    class="btn btn-secondary btn-sm"
+                                    title="Fill in the details from MusicBrainz or OpenLibrary"
+                                    prop:disabled=move || looking_up.get()
+                                    on:click=move |_| look_up()
+                                >
+                                    {move || if looking_up.get() { "Looking up..." } else { "Look up" }}
+                                </button>
+                            </div>
+                            <Show when=move || lookup_note.get().is_some() fallback=|| ()>
+                                <div class="detail-label">
+                                    {move || lookup_note.get().unwrap_or_default()}
+                                </div>
+                            </Show>
+                        </div>
+                        <div class="form-group">
+                            <label>"Catalog Code"</label>
+                            <input
+                                type="text"
+                                class="form-control"
+                                prop:value=catalog_code
+                                on:input=move |ev| catalog_code.set(event_target_value(&ev))
+                            />
+                        </div>
                         <Show when=move || kind_id.get().is_some() fallback=|| ()>
                             {move || {
                                 let fields = kind_fields.get();
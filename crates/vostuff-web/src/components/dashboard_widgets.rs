@@ -0,0 +1,198 @@
+use leptos::*;
+use uuid::Uuid;
+
+use crate::server_fns::items::{encode_query_value, get_rotation, mark_item_used};
+use crate::server_fns::reports::{ItemStateSummary, ItemUsage, UsageReport};
+
+/// A count that links to the items page with the matching filters applied
+#[component]
+pub fn StateWidget(
+    #[prop(into)] title: String,
+    count: i64,
+    /// Items page URL with the filter query, e.g. "/?state=loaned"
+    #[prop(into)]
+    href: String,
+    /// state-badge class for the accent colour
+    #[prop(into)]
+    css_class: String,
+) -> impl IntoView {
+    view! {
+        <a class=format!("dashboard-widget {}", css_class) href=href>
+            <span class="dashboard-widget-count">{count}</span>
+            <span class="dashboard-widget-title">{title}</span>
+        </a>
+    }
+}
+
+/// Loaned, missing and disposed-this-year widgets
+#[component]
+pub fn ItemStateWidgets(summary: ItemStateSummary) -> impl IntoView {
+    view! {
+        <div class="dashboard-widgets">
+            <StateWidget
+                title="On loan"
+                count=summary.loaned
+                href="/?state=loaned"
+                css_class="state-loaned"
+            />
+            <StateWidget
+                title="Missing"
+                count=summary.missing
+                href="/?state=missing"
+                css_class="state-missing"
+            />
+            <StateWidget
+                title="Disposed this year"
+                count=summary.disposed_this_year
+                href=format!("/?state=disposed&disposed_since={}", summary.year_start)
+                css_class="state-disposed"
+            />
+        </div>
+    }
+}
+
+/// Suggestions of what to play or use next, favouring items unused the longest.
+/// Marking an item used drops it from the list; Shuffle draws a new set.
+#[component]
+pub fn RotationPanel(org_id: Uuid) -> impl IntoView {
+    let (version, set_version) = create_signal(0u32);
+    let suggestions = create_resource(
+        move || (org_id, version.get()),
+        |(org_id, _)| async move { get_rotation(org_id, 5).await },
+    );
+    let mark_used = create_action(move |item_id: &Uuid| {
+        let item_id = *item_id;
+        async move {
+            match mark_item_used(org_id, item_id).await {
+                Ok(_) => set_version.update(|v| *v += 1),
+                Err(e) => leptos::logging::error!("Failed to mark item used: {}", e),
+            }
+        }
+    });
+
+    view! {
+        <div class="rotation-panel">
+            <div class="rotation-header">
+                <h2>"Play next"</h2>
+                <button
+                    class="btn btn-secondary btn-sm"
+                    on:click=move |_| set_version.update(|v| *v += 1)
+                >
+                    "Shuffle"
+                </button>
+            </div>
+            <Transition fallback=move || view! { <div class="loading">"Loading..."</div> }>
+                {move || {
+                    suggestions
+                        .get()
+                        .map(|result| match result {
+                            Ok(items) if items.is_empty() => {
+                                view! {
+                                    <p class="rotation-empty">
+                                        "Everything has been used recently."
+                                    </p>
+                                }
+                                    .into_view()
+                            }
+                            Ok(items) => {
+                                view! {
+                                    <ul class="rotation-list">
+                                        {items
+                                            .into_iter()
+                                            .map(|item| {
+                                                let item_id = item.id;
+                                                let last_used = item
+                                                    .last_used_at
+                                                    .map(|d| format!("last used {}", d.format("%Y-%m-%d")))
+                                                    .unwrap_or_else(|| "never used".to_string());
+                                                view! {
+                                                    <li class="rotation-item">
+                                                        <a href=format!(
+                                                            "/?search={}&item={}",
+                                                            encode_query_value(&item.name),
+                                                            item.id,
+                                                        )>{item.name}</a>
+                                                        <span class="rotation-meta">
+                                                            {format!("{} · {}", item.kind_name, last_used)}
+                                                        </span>
+                                                        <button
+                                                            class="btn btn-secondary btn-sm"
+                                                            disabled=move || mark_used.pending().get()
+                                                            on:click=move |_| mark_used.dispatch(item_id)
+                                                        >
+                                                            "Mark used"
+                                                        </button>
+                                                    </li>
+                                                }
+                                            })
+                                            .collect_view()}
+                                    </ul>
+                                }
+                                    .into_view()
+                            }
+                            Err(e) => {
+                                view! {
+                                    <div class="error">{format!("Error loading suggestions: {}", e)}</div>
+                                }
+                                    .into_view()
+                            }
+                        })
+                }}
+            </Transition>
+        </div>
+    }
+}
+
+/// Most and least played current items, side by side
+#[component]
+pub fn UsageLists(report: UsageReport) -> impl IntoView {
+    let list = |items: Vec<ItemUsage>, empty: &'static str| {
+        if items.is_empty() {
+            return view! { <p class="rotation-empty">{empty}</p> }.into_view();
+        }
+        view! {
+            <ul class="rotation-list">
+                {items
+                    .into_iter()
+                    .map(|item| {
+                        let last_used = item
+                            .last_used_at
+                            .map(|d| format!("last used {}", d.format("%Y-%m-%d")))
+                            .unwrap_or_else(|| "never used".to_string());
+                        let uses = match item.use_count {
+                            1 => "1 use".to_string(),
+                            n => format!("{} uses", n),
+                        };
+                        view! {
+                            <li class="rotation-item">
+                                <a href=format!(
+                                    "/?search={}&item={}",
+                                    encode_query_value(&item.item_name),
+                                    item.item_id,
+                                )>{item.item_name}</a>
+                                <span class="rotation-meta">
+                                    {format!("{} · {}", item.kind_name, last_used)}
+                                </span>
+                                <span class="usage-count">{uses}</span>
+                            </li>
+                        }
+                    })
+                    .collect_view()}
+            </ul>
+        }
+        .into_view()
+    };
+
+    view! {
+        <div class="usage-lists">
+            <div>
+                <h2>"Most played"</h2>
+                {list(report.most_used, "Nothing has been marked used yet.")}
+            </div>
+            <div>
+                <h2>"Least played"</h2>
+                {list(report.least_used, "No current items.")}
+            </div>
+        </div>
+    }
+}
@@ -265,11 +265,11 @@ fn EnumValueEditor(rows: RwSignal<Vec<(u32, String, String)>>) -> impl IntoView
                     let value_ref = create_node_ref::<html::Input>();
 
                     create_effect(move |_| {
-                        if focus_key.get() == Some(id) {
-                            if let Some(el) = value_ref.get() {
-                                let _ = el.focus();
-                                focus_key.set(None);
-                            }
+                        if focus_key.get() == Some(id)
+                            && let Some(el) = value_ref.get()
+                        {
+                            let _ = el.focus();
+                            focus_key.set(None);
                         }
                     });
 
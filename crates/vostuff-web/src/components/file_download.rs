@@ -0,0 +1,32 @@
+use wasm_bindgen::prelude::*;
+
+// Saves text or bytes made in the page as a file, through a temporary object URL
+#[wasm_bindgen(inline_js = r#"
+export function downloadFile(filename, type, content) {
+    const url = URL.createObjectURL(new Blob([content], { type }));
+    const link = document.createElement('a');
+    link.href = url;
+    link.download = filename;
+    document.body.appendChild(link);
+    link.click();
+    link.remove();
+    setTimeout(() => URL.revokeObjectURL(url), 0);
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = downloadFile)]
+    fn download_file_js(filename: &str, content_type: &str, content: &str);
+
+    #[wasm_bindgen(js_name = downloadFile)]
+    fn download_bytes_js(filename: &str, content_type: &str, content: &[u8]);
+}
+
+/// Have the browser download `content` as a file; call from event handlers only
+pub fn download_file(filename: &str, content_type: &str, content: &str) {
+    download_file_js(filename, content_type, content);
+}
+
+/// Have the browser download binary `content`, such as a PDF, as a file
+pub fn download_bytes(filename: &str, content_type: &str, content: &[u8]) {
+    download_bytes_js(filename, content_type, content);
+}
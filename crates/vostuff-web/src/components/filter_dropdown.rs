@@ -1,5 +1,10 @@
+use leptos::leptos_dom::helpers::TimeoutHandle;
 use leptos::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// How long typing must pause before the search box commits its text
+pub const SEARCH_DEBOUNCE: Duration = Duration::from_millis(300);
 
 /// A single filter option with a value and display label
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -28,6 +33,9 @@ pub fn FilterDropdown(
     selected: ReadSignal<HashSet<String>>,
     /// Callback when selection changes
     set_selected: WriteSignal<HashSet<String>>,
+    /// Item count per option value, shown beside each option
+    #[prop(optional)]
+    counts: Option<Signal<HashMap<String, i64>>>,
 ) -> impl IntoView {
     let dropdown_id = label.clone();
     let dropdown_id_for_open = dropdown_id.clone();
@@ -161,6 +169,7 @@ pub fn FilterDropdown(
                                 .map(|opt| {
                                     let value_for_check = opt.value.clone();
                                     let value_for_toggle = opt.value.clone();
+                                    let value_for_count = opt.value.clone();
                                     let label = opt.label.clone();
                                     view! {
                                         <label class="filter-option">
@@ -170,6 +179,11 @@ pub fn FilterDropdown(
                                                 on:change=move |_| toggle_option(value_for_toggle.clone())
                                             />
                                             <span class="filter-option-label">{label}</span>
+                                            {move || {
+                                                counts
+                                                    .map(|c| c.get().get(&value_for_count).copied().unwrap_or(0))
+                                                    .map(|n| view! { <span class="filter-option-count">{n}</span> })
+                                            }}
                                         </label>
                                     }
                                 })
@@ -203,28 +217,49 @@ pub fn FilterDropdown(
     }
 }
 
-/// Text search input that submits on Enter
+/// Text search input that submits on Enter or once typing pauses
 #[component]
 pub fn FilterSearchInput(
     /// Current input value
     value: ReadSignal<String>,
     /// Setter for the input value (updated on every keystroke)
     set_value: WriteSignal<String>,
-    /// Setter for the committed search (updated on Enter)
+    /// Setter for the committed search (updated on Enter, or once typing pauses)
     set_committed: WriteSignal<String>,
 ) -> impl IntoView {
+    // Only the last keystroke's timer may commit, so a burst of typing causes
+    // a single search rather than one fetch per character
+    let pending = store_value::<Option<TimeoutHandle>>(None);
+    let cancel_pending = move || {
+        if let Some(handle) = pending.get_value() {
+            handle.clear();
+        }
+        pending.set_value(None);
+    };
+    on_cleanup(cancel_pending);
+
     view! {
         <input
             type="text"
             class="filter-search-input"
-            placeholder="Search... (Enter to submit)"
+            placeholder="Search..."
             prop:value=move || value.get()
             on:input=move |ev| {
-                set_value.set(event_target_value(&ev));
+                let text = event_target_value(&ev);
+                set_value.set(text.clone());
+                cancel_pending();
+                pending.set_value(
+                    set_timeout_with_handle(
+                        move || set_committed.set(text),
+                        SEARCH_DEBOUNCE,
+                    )
+                    .ok(),
+                );
             }
             on:keydown=move |ev: web_sys::KeyboardEvent| {
                 if ev.key() == "Enter" {
                     ev.prevent_default();
+                    cancel_pending();
                     set_committed.set(value.get_untracked());
                 }
             }
@@ -1,12 +1,34 @@
 use leptos::*;
 use leptos_router::*;
+use uuid::Uuid;
 
-use crate::server_fns::auth::logout;
+use crate::components::announcement::{AnnouncementBanner, BroadcastBanners};
+use crate::components::command_palette::CommandPalette;
+use crate::components::whats_new::WhatsNew;
+use crate::server_fns::settings::Announcement;
+
+use crate::server_fns::auth::{BroadcastNotice, logout};
 
 #[component]
-pub fn Header(#[prop(into)] username: String, #[prop(into)] org_name: String) -> impl IntoView {
+pub fn Header(
+    #[prop(into)] username: String,
+    #[prop(into)] org_name: String,
+    org_id: Uuid,
+    announcement: Option<Announcement>,
+    /// `#rrggbb`, checked by the API
+    accent_color: Option<String>,
+    logo_url: Option<String>,
+    broadcasts: Vec<BroadcastNotice>,
+    /// The organization refuses changes
+    #[prop(optional)]
+    read_only: bool,
+) -> impl IntoView {
     let navigate = use_navigate();
     let navigate2 = navigate.clone();
+    let navigate3 = navigate.clone();
+    let navigate4 = navigate.clone();
+    let navigate5 = navigate.clone();
+    let palette_open = create_rw_signal(false);
 
     let handle_logout = create_action(move |_: &()| {
         let nav = navigate.clone();
@@ -26,24 +48,73 @@ pub fn Header(#[prop(into)] username: String, #[prop(into)] org_name: String) ->
     });
 
     view! {
+        {accent_color
+            .map(|color| {
+                view! {
+                    <style>
+                        {format!(
+                            ":root {{ --accent: {}; }} .header {{ border-top: 4px solid var(--accent); }}",
+                            color,
+                        )}
+                    </style>
+                }
+            })}
         <header class="header">
             <div class="header-content">
                 <div class="header-title">
                     <a href="/" style="color: inherit; text-decoration: none; display: inline-flex; align-items: center; gap: 8px;">
-                        <svg
-                            xmlns="http://www.w3.org/2000/svg"
-                            width="18"
-                            height="18"
-                            viewBox="0 0 24 24"
-                            fill="currentColor"
-                            style="flex-shrink: 0; opacity: 0.7;"
-                        >
-                            <path d="M10 20v-6h4v6h5v-8h3L12 3 2 12h3v8z"/>
-                        </svg>
+                        {match logo_url {
+                            Some(url) => view! { <img class="header-logo" src=url alt="" /> }.into_view(),
+                            None => view! {
+                                <svg
+                                    xmlns="http://www.w3.org/2000/svg"
+                                    width="18"
+                                    height="18"
+                                    viewBox="0 0 24 24"
+                                    fill="currentColor"
+                                    style="flex-shrink: 0; opacity: 0.7;"
+                                >
+                                    <path d="M10 20v-6h4v6h5v-8h3L12 3 2 12h3v8z"/>
+                                </svg>
+                            }
+                            .into_view(),
+                        }}
                         "VOStuff - " {org_name}
                     </a>
                 </div>
                 <div class="header-right">
+                    <button
+                        class="btn btn-secondary palette-trigger"
+                        title="Go to an item, collection or page"
+                        on:click=move |_| palette_open.set(true)
+                    >
+                        "Go to..."
+                        <kbd>"Ctrl K"</kbd>
+                    </button>
+                    <button
+                        class="btn btn-secondary"
+                        on:click=move |_| {
+                            navigate4("/dashboard", NavigateOptions::default());
+                        }
+                    >
+                        "Dashboard"
+                    </button>
+                    <button
+                        class="btn btn-secondary"
+                        on:click=move |_| {
+                            navigate3("/borrowers", NavigateOptions::default());
+                        }
+                    >
+                        "Borrowers"
+                    </button>
+                    <button
+                        class="btn btn-secondary"
+                        on:click=move |_| {
+                            navigate5("/docs", NavigateOptions::default());
+                        }
+                    >
+                        "Docs"
+                    </button>
                     <button
                         class="btn btn-secondary"
                         on:click=move |_| {
@@ -65,5 +136,17 @@ pub fn Header(#[prop(into)] username: String, #[prop(into)] org_name: String) ->
                 </div>
             </div>
         </header>
+        <BroadcastBanners broadcasts=broadcasts/>
+        {read_only
+            .then(|| {
+                view! {
+                    <div class="announcement-banner read-only-banner">
+                        "This organization is read-only: you can view its data, but changes are turned off."
+                    </div>
+                }
+            })}
+        <AnnouncementBanner org_id=org_id announcement=announcement/>
+        <CommandPalette org_id=org_id open=palette_open/>
+        <WhatsNew/>
     }
 }
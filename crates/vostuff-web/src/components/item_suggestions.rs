@@ -0,0 +1,161 @@
+use leptos::*;
+use uuid::Uuid;
+
+use crate::server_fns::items::{ItemSuggestion, decide_item_suggestion, get_item_suggestions};
+
+/// Item fields a lookup may fill, with their labels
+const SUGGESTED_FIELDS: [(&str, &str); 2] = [
+    ("description", "Description"),
+    ("catalog_code", "Catalog code"),
+];
+
+/// Review queue for details found by "Enrich selected": each suggestion shows
+/// the item's current values beside the found ones, to apply or dismiss.
+#[component]
+pub fn ItemSuggestionsModal(
+    org_id: Uuid,
+    #[prop(into)] show: Signal<bool>,
+    on_close: Callback<()>,
+    /// Called after a suggestion is applied, as the item has changed
+    on_applied: Callback<()>,
+    #[prop(optional)] read_only: bool,
+) -> impl IntoView {
+    let (refresh, set_refresh) = create_signal(0u32);
+    let error = create_rw_signal::<Option<String>>(None);
+    let busy = create_rw_signal::<Option<Uuid>>(None);
+
+    let suggestions = create_resource(
+        move || (show.get(), refresh.get()),
+        move |(show, _)| async move {
+            if show {
+                get_item_suggestions(org_id)
+                    .await
+                    .map_err(|e| e.to_string())
+            } else {
+                Ok(Vec::new())
+            }
+        },
+    );
+
+    let decide = move |suggestion_id: Uuid, apply: bool| {
+        busy.set(Some(suggestion_id));
+        error.set(None);
+        spawn_local(async move {
+            match decide_item_suggestion(org_id, suggestion_id, apply).await {
+                Ok(()) => {
+                    if apply {
+                        on_applied.call(());
+                    }
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+            busy.set(None);
+            set_refresh.update(|r| *r += 1);
+        });
+    };
+
+    let render_suggestion = move |s: ItemSuggestion| {
+        let id = s.id;
+        let rows = SUGGESTED_FIELDS
+            .iter()
+            .filter_map(|(field, label)| {
+                let found = s.changes.get(*field)?.as_str()?.to_string();
+                let current = s.current[*field]
+                    .as_str()
+                    .filter(|v| !v.is_empty())
+                    .unwrap_or("-")
+                    .to_string();
+                Some(view! {
+                    <tr>
+                        <td class="detail-label">{*label}</td>
+                        <td>{current}</td>
+                        <td>{found}</td>
+                    </tr>
+                })
+            })
+            .collect_view();
+        let source = s.source_url.clone().map(|url| {
+            view! {
+                " "
+                <a href=url target="_blank" rel="noopener">"source"</a>
+            }
+        });
+        view! {
+            <div class="detail-section">
+                <h4>{s.item_name.clone()}</h4>
+                <div class="detail-label">"From " {s.provider.clone()} {source}</div>
+                <table class="items-table">
+                    <thead>
+                        <tr>
+                            <th></th>
+                            <th>"Current"</th>
+                            <th>"Found"</th>
+                        </tr>
+                    </thead>
+                    <tbody>{rows}</tbody>
+                </table>
+                <div class="detail-row">
+                    <button
+                        class="btn btn-secondary"
+                        prop:disabled=move || read_only || busy.get() == Some(id)
+                        on:click=move |_| decide(id, false)
+                    >
+                        "Dismiss"
+                    </button>
+                    <button
+                        class="btn btn-primary"
+                        style="width:auto;"
+                        prop:disabled=move || read_only || busy.get() == Some(id)
+                        on:click=move |_| decide(id, true)
+                    >
+                        "Apply"
+                    </button>
+                </div>
+            </div>
+        }
+    };
+
+    view! {
+        <Show when=move || show.get() fallback=|| ()>
+            <div class="modal-overlay" on:click=move |_| on_close.call(())>
+                <div class="modal" on:click=move |ev| ev.stop_propagation()>
+                    <div class="modal-header">
+                        <h2>"Suggested Details"</h2>
+                    </div>
+                    <div class="modal-body">
+                        <Transition fallback=move || view! { <div class="loading">"Loading..."</div> }>
+                            {move || {
+                                suggestions
+                                    .get()
+                                    .map(|result| match result {
+                                        Ok(list) if list.is_empty() => {
+                                            view! {
+                                                <p>
+                                                    "Nothing to review. Lookups run in the background; "
+                                                    "check back shortly after enriching items."
+                                                </p>
+                                            }
+                                                .into_view()
+                                        }
+                                        Ok(list) => list.into_iter().map(render_suggestion).collect_view(),
+                                        Err(e) => view! { <div class="error">{e}</div> }.into_view(),
+                                    })
+                            }}
+                        </Transition>
+                        <Show when=move || error.get().is_some() fallback=|| ()>
+                            <div class="error">{move || error.get().unwrap_or_default()}</div>
+                        </Show>
+                    </div>
+                    <div class="modal-footer">
+                        <button class="btn btn-secondary" on:click=move |_| set_refresh.update(|r| *r += 1)>
+                            "Refresh"
+                        </button>
+                        <button class="btn btn-secondary" on:click=move |_| on_close.call(())>
+                            "Close"
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </Show>
+    }
+}
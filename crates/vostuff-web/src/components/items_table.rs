@@ -1,25 +1,17 @@
 use leptos::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
-use pulldown_cmark::{Options, Parser, html};
-
+use crate::components::markdown::render_markdown;
+use crate::components::save_warnings::SaveWarnings;
 use crate::components::soft_field_helpers::{
     format_field_name, format_soft_field_value, render_soft_field_input, value_to_edit_str,
 };
 use crate::server_fns::items::{
-    Item, ItemFullDetails, ItemState, Location, UpdateItemRequest, delete_item, get_item_details,
-    update_item,
+    Item, ItemAggregate, ItemFullDetails, ItemState, Location, ShareLink, UpdateItemRequest,
+    create_share_link, delete_item, get_item_full, mark_item_used, update_item,
 };
-use crate::server_fns::kinds::{get_kind_fields, KindFieldDef};
-
-fn render_markdown(text: &str) -> String {
-    let options = Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES;
-    let parser = Parser::new_ext(text, options);
-    let mut html_output = String::new();
-    html::push_html(&mut html_output, parser);
-    html_output
-}
+use crate::server_fns::kinds::{KindFieldDef, get_kind_fields};
 
 fn highlight_match(text: &str, query: &str) -> View {
     if query.is_empty() {
@@ -60,8 +52,47 @@ pub fn ItemsTable(
     #[prop(optional)] expanded_row: Option<ReadSignal<Option<Uuid>>>,
     #[prop(optional)] set_expanded_row: Option<WriteSignal<Option<Uuid>>>,
     org_id: Uuid,
+    /// Disable editing, as the organization refuses changes
+    #[prop(optional)]
+    read_only: bool,
+    /// Checked rows, for bulk actions; no checkbox column when not given
+    #[prop(optional)]
+    selected: Option<RwSignal<HashSet<Uuid>>>,
 ) -> impl IntoView {
     let locations_list = store_value(locations_list);
+    let columns = if selected.is_some() { 5 } else { 4 };
+    let page_ids: Vec<Uuid> = items.iter().map(|i| i.id).collect();
+    let select_header = selected.map(|selected| {
+        let all_checked = {
+            let ids = page_ids.clone();
+            move || {
+                let checked = selected.get();
+                ids.iter().all(|id| checked.contains(id))
+            }
+        };
+        let ids = page_ids.clone();
+        view! {
+            <th class="col-select">
+                <input
+                    type="checkbox"
+                    title="Select all on this page"
+                    prop:checked=all_checked.clone()
+                    on:change=move |_| {
+                        let check = !all_checked();
+                        selected.update(|checked| {
+                            for id in &ids {
+                                if check {
+                                    checked.insert(*id);
+                                } else {
+                                    checked.remove(id);
+                                }
+                            }
+                        });
+                    }
+                />
+            </th>
+        }
+    });
     let (local_expanded, local_set_expanded) = create_signal::<Option<Uuid>>(None);
     let expanded_row = expanded_row.unwrap_or(local_expanded);
     let set_expanded_row = set_expanded_row.unwrap_or(local_set_expanded);
@@ -124,6 +155,7 @@ pub fn ItemsTable(
         <table class="items-table">
             <thead>
                 <tr>
+                    {select_header}
                     <th class="col-type sortable-header" on:click=on_type>{format!("Type{}", ind_type)}</th>
                     <th class="col-name sortable-header" on:click=on_name>{format!("Name{}", ind_name)}</th>
                     <th class="col-state sortable-header" on:click=on_state>{format!("State{}", ind_state)}</th>
@@ -143,16 +175,37 @@ pub fn ItemsTable(
                         let item_for_details = item.clone();
                         let sq = search_query.clone();
                         let sq2 = search_query.clone();
+                        let select_cell = selected.map(|selected| {
+                            view! {
+                                <td class="col-select" on:click=|ev| ev.stop_propagation()>
+                                    <input
+                                        type="checkbox"
+                                        prop:checked=move || selected.get().contains(&item_id)
+                                        on:change=move |ev| {
+                                            let check = event_target_checked(&ev);
+                                            selected.update(|checked| {
+                                                if check {
+                                                    checked.insert(item_id);
+                                                } else {
+                                                    checked.remove(&item_id);
+                                                }
+                                            });
+                                        }
+                                    />
+                                </td>
+                            }
+                        });
                         view! {
                             <tr
                                 class="item-row"
                                 class:expanded=is_expanded
                                 on:click=move |_| toggle_row(item_id)
                             >
+                                {select_cell}
                                 <td class="col-type">{item.kind_name.clone()}</td>
                                 <td class="col-name">{highlight_match(&item.name, &sq)}</td>
                                 <td class="col-state">
-                                    <span class=format!("state-badge {}", item.state.css_class())>
+                                    <span class=format!("state-badge state-{}", item.state)>
                                         {item.state.display_name()}
                                     </span>
                                 </td>
@@ -166,6 +219,8 @@ pub fn ItemsTable(
                                     org_id=org_id
                                     locations_list=locations_list.get_value()
                                     on_item_updated=on_item_updated.unwrap_or(Callback::new(|_| {}))
+                                    read_only=read_only
+                                    columns=columns
                                 />
                             </Show>
                         }
@@ -282,6 +337,149 @@ fn render_soft_fields_with_defs(
     .into_view()
 }
 
+/// Tags, collections and recent history from the item aggregate
+fn render_related(full: &ItemAggregate) -> View {
+    let chips = |names: Vec<String>| {
+        if names.is_empty() {
+            "-".into_view()
+        } else {
+            names
+                .into_iter()
+                .map(|n| view! { <span class="kind-field-chip">{n}</span> })
+                .collect_view()
+        }
+    };
+    let tags = chips(full.tags.clone());
+    let collections = chips(full.collections.iter().map(|c| c.name.clone()).collect());
+    let history = if full.history.is_empty() {
+        ().into_view()
+    } else {
+        let entries = full
+            .history
+            .iter()
+            .map(|h| {
+                let when = h
+                    .change_date
+                    .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default();
+                let by = h
+                    .user_name
+                    .as_ref()
+                    .map(|name| format!(" by {}", name))
+                    .unwrap_or_default();
+                view! {
+                    <li>
+                        <span class="detail-label">{when}</span>
+                        " "
+                        {h.change_details.clone()}
+                        {by}
+                    </li>
+                }
+            })
+            .collect_view();
+        view! {
+            <div class="detail-section">
+                <h4>"Recent History"</h4>
+                <ul class="item-history">{entries}</ul>
+            </div>
+        }
+        .into_view()
+    };
+    let usage = if full.usage.is_empty() {
+        ().into_view()
+    } else {
+        let entries = full
+            .usage
+            .iter()
+            .map(|u| {
+                let by = u
+                    .user_name
+                    .as_ref()
+                    .map(|name| format!(" by {}", name))
+                    .unwrap_or_default();
+                let note = u
+                    .note
+                    .as_ref()
+                    .map(|note| format!(": {}", note))
+                    .unwrap_or_default();
+                view! {
+                    <li>
+                        <span class="detail-label">{u.used_at.format("%Y-%m-%d %H:%M").to_string()}</span>
+                        " Used"
+                        {by}
+                        {note}
+                    </li>
+                }
+            })
+            .collect_view();
+        view! {
+            <div class="detail-section">
+                <h4>"Recent Uses"</h4>
+                <ul class="item-history">{entries}</ul>
+            </div>
+        }
+        .into_view()
+    };
+    let photos = if full.photos.is_empty() {
+        ().into_view()
+    } else {
+        let thumbnails = full
+            .photos
+            .iter()
+            .map(|p| {
+                view! {
+                    <a href=p.url.clone() target="_blank" rel="noopener">
+                        <img class="item-photo" src=p.url.clone() alt=p.filename.clone() />
+                    </a>
+                }
+            })
+            .collect_view();
+        view! {
+            <div class="detail-section">
+                <h4>"Photos"</h4>
+                <div class="item-photos">{thumbnails}</div>
+            </div>
+        }
+        .into_view()
+    };
+    view! {
+        <div class="detail-row">
+            <div class="detail-group">
+                <span class="detail-label">"Tags:"</span>
+                <span class="detail-value">{tags}</span>
+            </div>
+            <div class="detail-group">
+                <span class="detail-label">"Collections:"</span>
+                <span class="detail-value">{collections}</span>
+            </div>
+        </div>
+        {photos}
+        {usage}
+        {history}
+    }
+    .into_view()
+}
+
+/// Format dimensions and weight for display, e.g. "315 × 315 × 4 mm, 180 g"
+fn format_size(item: &Item) -> String {
+    let dims = match (item.width_mm, item.height_mm, item.depth_mm) {
+        (None, None, None) => None,
+        (w, h, d) => Some(format!(
+            "{} × {} × {} mm",
+            w.map(|v| v.to_string()).unwrap_or_else(|| "?".to_string()),
+            h.map(|v| v.to_string()).unwrap_or_else(|| "?".to_string()),
+            d.map(|v| v.to_string()).unwrap_or_else(|| "?".to_string()),
+        )),
+    };
+    let weight = item.weight_g.map(|g| format!("{} g", g));
+    match (dims, weight) {
+        (Some(d), Some(w)) => format!("{}, {}", d, w),
+        (Some(d), None) => d,
+        (None, Some(w)) => w,
+        (None, None) => "-".to_string(),
+    }
+}
+
 fn render_state_details(details: &ItemFullDetails) -> View {
     if let Some(ref loan) = details.loan_details {
         let date_loaned = loan.date_loaned.format("%Y-%m-%d").to_string();
@@ -289,7 +487,13 @@ fn render_state_details(details: &ItemFullDetails) -> View {
             .date_due_back
             .map(|d| d.format("%Y-%m-%d").to_string())
             .unwrap_or_else(|| "-".to_string());
-        let loaned_to = loan.loaned_to.clone();
+        let loaned_to = match loan.borrower_id {
+            Some(id) => view! {
+                <a href=format!("/borrowers/{}", id)>{loan.loaned_to.clone()}</a>
+            }
+            .into_view(),
+            None => loan.loaned_to.clone().into_view(),
+        };
         view! {
             <div class="detail-section">
                 <h4>"Loan Details"</h4>
@@ -351,6 +555,9 @@ fn ItemExpandedRow(
     org_id: Uuid,
     #[prop(default = vec![])] locations_list: Vec<Location>,
     on_item_updated: Callback<()>,
+    read_only: bool,
+    /// Columns in the table, for the details cell to span
+    columns: usize,
 ) -> impl IntoView {
     let item_id = item.id;
     let date_acquired = item
@@ -359,37 +566,12 @@ fn ItemExpandedRow(
         .unwrap_or_else(|| "-".to_string());
 
     let date_entered = item.date_entered.format("%Y-%m-%d %H:%M").to_string();
+    let barcode = item.barcode.clone().unwrap_or_else(|| "-".to_string());
+    let catalog_code = item.catalog_code.clone().unwrap_or_else(|| "-".to_string());
+    let size = format_size(&item);
 
     let (editing, set_editing) = create_signal(false);
-    let (saving, set_saving) = create_signal(false);
 
-    // Editable signals for base fields
-    let (edit_name, set_edit_name) = create_signal(item.name.clone());
-    let (edit_description, set_edit_description) =
-        create_signal(item.description.clone().unwrap_or_default());
-    let (edit_notes, set_edit_notes) = create_signal(item.notes.clone().unwrap_or_default());
-    let (edit_location_id, set_edit_location_id) = create_signal(
-        item.location_id
-            .map(|id| id.to_string())
-            .unwrap_or_default(),
-    );
-    let (edit_date_acquired, set_edit_date_acquired) = create_signal(
-        item.date_acquired
-            .map(|d| d.format("%Y-%m-%d").to_string())
-            .unwrap_or_default(),
-    );
-
-    // Soft field signals — store serde_json::Value directly so types are
-    // preserved through edit and save without any guessing at save time.
-    let init_map: HashMap<String, serde_json::Value> = item
-        .soft_fields
-        .as_object()
-        .cloned()
-        .unwrap_or_default()
-        .into_iter()
-        .collect();
-    let orig_soft_field_map = store_value(init_map.clone());
-    let soft_field_map = create_rw_signal(init_map);
     let soft_fields_stored = store_value(item.soft_fields.clone());
     let kind_id_stored = item.kind_id;
 
@@ -402,204 +584,51 @@ fn ItemExpandedRow(
         }
     });
 
-    let (save_error, set_save_error) = create_signal::<Option<String>>(None);
-
     // Delete signals
     let (confirming_delete, set_confirming_delete) = create_signal(false);
     let (delete_error, set_delete_error) = create_signal::<Option<String>>(None);
 
-    // Loan signals
-    let (edit_loan_date_loaned, set_edit_loan_date_loaned) = create_signal(String::new());
-    let (edit_loan_date_due_back, set_edit_loan_date_due_back) = create_signal(String::new());
-    let (edit_loan_loaned_to, set_edit_loan_loaned_to) = create_signal(String::new());
-
-    // Missing/Disposed signals
-    let (edit_missing_date, set_edit_missing_date) = create_signal(String::new());
-    let (edit_disposed_date, set_edit_disposed_date) = create_signal(String::new());
-
-    // Store fetched details for initializing edit signals
+    // Store fetched details for initializing the edit form
     let (fetched_details, set_fetched_details) = create_signal::<Option<ItemFullDetails>>(None);
 
     let (details_version, set_details_version) = create_signal(0u32);
 
-    let details_resource = create_resource(
-        move || (org_id, item_id, details_version.get()),
-        move |(org_id, item_id, _)| async move { get_item_details(org_id, item_id).await },
-    );
-
-    // Initialize edit signals from details when entering edit mode
-    let init_edit_from_details = move || {
-        if let Some(details) = fetched_details.get() {
-            // Update base fields from the freshly-fetched details so re-entering
-            // edit mode after a save shows the current saved values.
-            set_edit_name.set(details.item.name.clone());
-            set_edit_description
-                .set(details.item.description.clone().unwrap_or_default());
-            set_edit_notes.set(details.item.notes.clone().unwrap_or_default());
-            set_edit_location_id.set(
-                details
-                    .item
-                    .location_id
-                    .map(|id| id.to_string())
-                    .unwrap_or_default(),
-            );
-            set_edit_date_acquired.set(
-                details
-                    .item
-                    .date_acquired
-                    .map(|d| d.format("%Y-%m-%d").to_string())
-                    .unwrap_or_default(),
-            );
-            if let Some(obj) = details.item.soft_fields.as_object() {
-                soft_field_map.update(|m| {
-                    for (k, v) in obj.iter() {
-                        m.insert(k.clone(), v.clone());
-                    }
-                });
-            }
-            if let Some(ref loan) = details.loan_details {
-                set_edit_loan_date_loaned.set(loan.date_loaned.format("%Y-%m-%d").to_string());
-                set_edit_loan_date_due_back.set(
-                    loan.date_due_back
-                        .map(|d| d.format("%Y-%m-%d").to_string())
-                        .unwrap_or_default(),
-                );
-                set_edit_loan_loaned_to.set(loan.loaned_to.clone());
-            }
-            if let Some(ref missing) = details.missing_details {
-                set_edit_missing_date.set(missing.date_missing.format("%Y-%m-%d").to_string());
-            }
-            if let Some(ref disposed) = details.disposed_details {
-                set_edit_disposed_date.set(disposed.date_disposed.format("%Y-%m-%d").to_string());
+    // Updated in place by "Mark used", which otherwise only adds to Recent Uses
+    let (last_used, set_last_used) = create_signal(item.last_used_at);
+    let mark_used_action = create_action(move |_: &()| async move {
+        match mark_item_used(org_id, item_id).await {
+            Ok(updated) => {
+                set_last_used.set(updated.last_used_at);
+                set_details_version.update(|v| *v += 1);
             }
+            Err(e) => leptos::logging::error!("Failed to mark item used: {}", e),
         }
-    };
-
-    let orig_name = store_value(item.name.clone());
-    let orig_description = store_value(item.description.clone().unwrap_or_default());
-    let orig_notes = store_value(item.notes.clone().unwrap_or_default());
-    let orig_location_id = store_value(
-        item.location_id
-            .map(|id| id.to_string())
-            .unwrap_or_default(),
-    );
-    let orig_date_acquired = store_value(
-        item.date_acquired
-            .map(|d| d.format("%Y-%m-%d").to_string())
-            .unwrap_or_default(),
-    );
-
-    let cancel_edit = move || {
-        set_edit_name.set(orig_name.get_value());
-        set_edit_description.set(orig_description.get_value());
-        set_edit_notes.set(orig_notes.get_value());
-        set_edit_location_id.set(orig_location_id.get_value());
-        set_edit_date_acquired.set(orig_date_acquired.get_value());
-        soft_field_map.set(orig_soft_field_map.get_value());
-        init_edit_from_details();
-        set_editing.set(false);
-    };
-
-    let item_state_for_save = store_value(item.state.clone());
-
-    let save_action = create_action(move |_: &()| {
-        let is = item_state_for_save.get_value();
-        let name = edit_name.get();
-        let description = edit_description.get();
-        let notes = edit_notes.get();
-        let location_str = edit_location_id.get();
-        let date_acq_str = edit_date_acquired.get();
-
-        // Values are already correctly typed (stored as serde_json::Value by
-        // the input handlers), so no conversion is needed here.
-        let sf_map: serde_json::Map<String, serde_json::Value> =
-            soft_field_map.get_untracked().into_iter().collect();
-
-        let mut req = UpdateItemRequest {
-            name: Some(name),
-            description: Some(description),
-            notes: Some(notes),
-            location_id: if location_str.is_empty() {
-                None
-            } else {
-                Uuid::parse_str(&location_str).ok()
-            },
-            date_acquired: if date_acq_str.is_empty() {
-                None
-            } else {
-                chrono::NaiveDate::parse_from_str(&date_acq_str, "%Y-%m-%d").ok()
-            },
-            state: None,
-            // Serialize to a JSON string — serde_urlencoded (used by Leptos
-            // server fn transport) loses type info for nested serde_json::Value,
-            // so we pass it as a plain string and parse it back server-side.
-            soft_fields: serde_json::to_string(&serde_json::Value::Object(sf_map)).ok(),
-            loan_date_loaned: None,
-            loan_date_due_back: None,
-            loan_loaned_to: None,
-            missing_date_missing: None,
-            disposed_date_disposed: None,
-        };
+    });
 
-        // State-specific fields
-        match is {
-            ItemState::Loaned => {
-                let dl = edit_loan_date_loaned.get();
-                if !dl.is_empty() {
-                    req.loan_date_loaned = chrono::NaiveDate::parse_from_str(&dl, "%Y-%m-%d").ok();
-                }
-                let ddb = edit_loan_date_due_back.get();
-                if !ddb.is_empty() {
-                    req.loan_date_due_back =
-                        chrono::NaiveDate::parse_from_str(&ddb, "%Y-%m-%d").ok();
-                }
-                let lt = edit_loan_loaned_to.get();
-                if !lt.is_empty() {
-                    req.loan_loaned_to = Some(lt);
-                }
-            }
-            ItemState::Missing => {
-                let dm = edit_missing_date.get();
-                if !dm.is_empty() {
-                    req.missing_date_missing =
-                        chrono::NaiveDate::parse_from_str(&dm, "%Y-%m-%d").ok();
-                }
-            }
-            ItemState::Disposed => {
-                let dd = edit_disposed_date.get();
-                if !dd.is_empty() {
-                    req.disposed_date_disposed =
-                        chrono::NaiveDate::parse_from_str(&dd, "%Y-%m-%d").ok();
-                }
-            }
-            _ => {}
+    // "Share" creates a link with the default fields and expiry, shown to copy
+    let (share_link, set_share_link) = create_signal::<Option<ShareLink>>(None);
+    let share_action = create_action(move |_: &()| async move {
+        match create_share_link(org_id, item_id, None, None).await {
+            Ok(link) => set_share_link.set(Some(link)),
+            Err(e) => leptos::logging::error!("Failed to create share link: {}", e),
         }
-
-        async move { update_item(org_id, item_id, req).await }
     });
 
-    // React to save action completion
-    create_effect(move |_| {
-        if let Some(result) = save_action.value().get() {
-            match result {
-                Ok(()) => {
-                    set_saving.set(false);
-                    set_editing.set(false);
-                    set_details_version.update(|v| *v += 1);
-                    on_item_updated.call(());
-                }
-                Err(e) => {
-                    set_saving.set(false);
-                    let msg = format!("{}", e);
-                    leptos::logging::error!("Failed to save item: {}", msg);
-                    set_save_error.set(Some(msg));
-                }
-            }
-        }
+    let details_resource = create_resource(
+        move || (org_id, item_id, details_version.get()),
+        move |(org_id, item_id, _)| async move { get_item_full(org_id, item_id).await },
+    );
+
+    let on_saved = Callback::new(move |_| {
+        set_editing.set(false);
+        set_details_version.update(|v| *v += 1);
+        on_item_updated.call(());
     });
+    let on_cancel = Callback::new(move |_| set_editing.set(false));
 
     // Delete action
-    let delete_action = create_action(move |_: &()| async move { delete_item(org_id, item_id).await });
+    let delete_action =
+        create_action(move |_: &()| async move { delete_item(org_id, item_id).await });
 
     // React to delete action completion
     create_effect(move |_| {
@@ -619,13 +648,9 @@ fn ItemExpandedRow(
         }
     });
 
-    let locations_for_edit = locations_list.clone();
-    let item_state_for_view = item.state.clone();
-    let kind_name_for_edit = item.kind_name.clone();
-
     view! {
         <tr class="item-expanded" on:click=|e| e.stop_propagation()>
-            <td colspan="4">
+            <td colspan=columns.to_string()>
                 <div class="item-details">
                     <Show
                         when=move || editing.get()
@@ -633,6 +658,9 @@ fn ItemExpandedRow(
                             let location_name = location_name.clone();
                             let date_acquired = date_acquired.clone();
                             let date_entered = date_entered.clone();
+                            let barcode = barcode.clone();
+                            let catalog_code = catalog_code.clone();
+                            let size = size.clone();
                             let search_query = search_query.clone();
                             let item = item.clone();
                             move || {
@@ -666,6 +694,31 @@ fn ItemExpandedRow(
                                             <span class="detail-label">"Date Entered:"</span>
                                             <span class="detail-value">{date_entered.clone()}</span>
                                         </div>
+                                        <div class="detail-group">
+                                            <span class="detail-label">"Last Used:"</span>
+                                            <span class="detail-value">
+                                                {move || {
+                                                    last_used
+                                                        .get()
+                                                        .map(|d| d.format("%Y-%m-%d").to_string())
+                                                        .unwrap_or_else(|| "Never".to_string())
+                                                }}
+                                            </span>
+                                        </div>
+                                    </div>
+                                    <div class="detail-row">
+                                        <div class="detail-group">
+                                            <span class="detail-label">"Barcode:"</span>
+                                            <span class="detail-value">{barcode.clone()}</span>
+                                        </div>
+                                        <div class="detail-group">
+                                            <span class="detail-label">"Catalog Code:"</span>
+                                            <span class="detail-value">{catalog_code.clone()}</span>
+                                        </div>
+                                        <div class="detail-group">
+                                            <span class="detail-label">"Size:"</span>
+                                            <span class="detail-value">{size.clone()}</span>
+                                        </div>
                                     </div>
                                     {move || {
                                         let sf = soft_fields_stored.get_value();
@@ -679,11 +732,13 @@ fn ItemExpandedRow(
                                     <Suspense fallback=move || view! { <div class="loading">"Loading details..."</div> }>
                                         {move || {
                                             details_resource.get().map(|result| match result {
-                                                Ok(details) => {
-                                                    set_fetched_details.set(Some(details.clone()));
-                                                    let state_view = render_state_details(&details);
+                                                Ok(full) => {
+                                                    set_fetched_details.set(Some(full.details.clone()));
+                                                    let state_view = render_state_details(&full.details);
+                                                    let related_view = render_related(&full);
                                                     view! {
                                                         {state_view}
+                                                        {related_view}
                                                     }.into_view()
                                                 }
                                                 Err(_) => ().into_view(),
@@ -693,18 +748,31 @@ fn ItemExpandedRow(
                                     <div class="detail-actions">
                                         <button
                                             class="btn btn-edit"
-                                            on:click=move |_| {
-                                                init_edit_from_details();
-                                                set_editing.set(true);
-                                            }
+                                            disabled=read_only
+                                            on:click=move |_| set_editing.set(true)
                                         >
                                             "Edit"
                                         </button>
+                                        <button
+                                            class="btn btn-secondary btn-sm"
+                                            disabled=move || read_only || mark_used_action.pending().get()
+                                            on:click=move |_| mark_used_action.dispatch(())
+                                        >
+                                            "Mark used"
+                                        </button>
+                                        <button
+                                            class="btn btn-secondary btn-sm"
+                                            disabled=move || share_action.pending().get()
+                                            on:click=move |_| share_action.dispatch(())
+                                        >
+                                            "Share"
+                                        </button>
                                         <Show
                                             when=move || confirming_delete.get()
                                             fallback=move || view! {
                                                 <button
                                                     class="btn btn-danger btn-sm"
+                                                    disabled=read_only
                                                     on:click=move |_| {
                                                         set_delete_error.set(None);
                                                         set_confirming_delete.set(true);
@@ -735,6 +803,24 @@ fn ItemExpandedRow(
                                             </button>
                                         </Show>
                                     </div>
+                                    {move || {
+                                        share_link
+                                            .get()
+                                            .map(|link| {
+                                                let expires = link.expires_at.format("%Y-%m-%d").to_string();
+                                                view! {
+                                                    <div class="share-link">
+                                                        <input
+                                                            type="text"
+                                                            class="form-input"
+                                                            readonly
+                                                            prop:value=link.url
+                                                        />
+                                                        <span>{format!("Works until {}", expires)}</span>
+                                                    </div>
+                                                }
+                                            })
+                                    }}
                                     <Show when=move || delete_error.get().is_some() fallback=|| ()>
                                         <div class="error">
                                             {move || delete_error.get().unwrap_or_default()}
@@ -745,112 +831,20 @@ fn ItemExpandedRow(
                         }
                     >
                         {
-                            let locations_for_edit = locations_for_edit.clone();
-                            let item_state_for_view = item_state_for_view.clone();
-                            let kind_name_for_edit = kind_name_for_edit.clone();
+                            let item = item.clone();
+                            let locations_list = locations_list.clone();
                             move || {
-                                let locs = locations_for_edit.clone();
-                                let is = item_state_for_view.clone();
                                 view! {
-                                    <div class="form-group">
-                                        <label class="form-label">"Name"</label>
-                                        <input
-                                            type="text"
-                                            class="form-control"
-                                            prop:value=edit_name
-                                            on:input=move |ev| set_edit_name.set(event_target_value(&ev))
-                                        />
-                                    </div>
-                                    <div class="form-group">
-                                        <label class="form-label">"Description"</label>
-                                        <input
-                                            type="text"
-                                            class="form-control"
-                                            prop:value=edit_description
-                                            on:input=move |ev| set_edit_description.set(event_target_value(&ev))
-                                        />
-                                    </div>
-                                    <div class="form-group">
-                                        <label class="form-label">"Notes"</label>
-                                        <textarea
-                                            class="form-control"
-                                            style="min-height:80px;resize:vertical;"
-                                            prop:value=edit_notes
-                                            on:input=move |ev| set_edit_notes.set(event_target_value(&ev))
-                                        />
-                                    </div>
-                                    <div class="form-group">
-                                        <label class="form-label">"Location"</label>
-                                        <select
-                                            class="form-control"
-                                            prop:value=edit_location_id
-                                            on:change=move |ev| set_edit_location_id.set(event_target_value(&ev))
-                                        >
-                                            <option value="">"- None -"</option>
-                                            {locs
-                                                .iter()
-                                                .map(|loc| {
-                                                    let val = loc.id.to_string();
-                                                    let name = loc.name.clone();
-                                                    view! { <option value=val>{name}</option> }
-                                                })
-                                                .collect_view()}
-                                        </select>
-                                    </div>
-                                    <div class="form-group">
-                                        <label class="form-label">"Date Acquired"</label>
-                                        <input
-                                            type="date"
-                                            class="form-control"
-                                            prop:value=edit_date_acquired
-                                            on:input=move |ev| set_edit_date_acquired.set(event_target_value(&ev))
-                                        />
-                                    </div>
-                                    <div class="form-group">
-                                        <label class="form-label">"Type"</label>
-                                        <span class="detail-value">{kind_name_for_edit.clone()}</span>
-                                    </div>
-
-                                    // Soft fields edit section — type-specific inputs
-                                    {move || {
-                                        let fields = kind_fields.get();
-                                        if fields.is_empty() {
-                                            render_soft_fields_edit_fallback(soft_field_map)
-                                        } else {
-                                            render_soft_fields_edit_with_defs(&fields, soft_field_map)
-                                        }
-                                    }}
-
-                                    // State-specific edit fields
-                                    {render_state_edit_fields(&is, edit_loan_date_loaned, set_edit_loan_date_loaned, edit_loan_date_due_back, set_edit_loan_date_due_back, edit_loan_loaned_to, set_edit_loan_loaned_to, edit_missing_date, set_edit_missing_date, edit_disposed_date, set_edit_disposed_date)}
-
-                                    <Show when=move || save_error.get().is_some() fallback=|| ()>
-                                        <div class="error">
-                                            {move || save_error.get().unwrap_or_default()}
-                                        </div>
-                                    </Show>
-                                    <div class="detail-actions">
-                                        <button
-                                            class="btn btn-secondary"
-                                            prop:disabled=saving
-                                            on:click=move |_| cancel_edit()
-                                        >
-                                            "Cancel"
-                                        </button>
-                                        <button
-                                            class="btn btn-primary"
-                                            style="width:auto;"
-                                            prop:disabled=saving
-                                            on:click=move |_| {
-                                                set_save_error.set(None);
-                                                set_saving.set(true);
-                                                save_action.dispatch(());
-                                            }
-                                        >
-                                            {move || if saving.get() { "Saving..." } else { "Save" }}
-                                        </button>
-                                    </div>
-                                }.into_view()
+                                    <ItemEditForm
+                                        item=item.clone()
+                                        details=fetched_details.get_untracked()
+                                        org_id=org_id
+                                        kind_fields=kind_fields
+                                        locations_list=locations_list.clone()
+                                        on_saved=on_saved
+                                        on_cancel=on_cancel
+                                    />
+                                }
                             }
                         }
                     </Show>
@@ -860,6 +854,362 @@ fn ItemExpandedRow(
     }
 }
 
+/// Edit form for an expanded row. It is only built when the row enters edit
+/// mode, so expanding a row creates no edit state and cancelling simply drops
+/// the form.
+#[component]
+fn ItemEditForm(
+    item: Item,
+    /// Latest fetched details; fresher than `item` after an earlier save
+    details: Option<ItemFullDetails>,
+    org_id: Uuid,
+    kind_fields: RwSignal<Vec<KindFieldDef>>,
+    locations_list: Vec<Location>,
+    on_saved: Callback<()>,
+    on_cancel: Callback<()>,
+) -> impl IntoView {
+    let item_id = item.id;
+    let current = details.as_ref().map(|d| &d.item).unwrap_or(&item);
+    let fmt_date = |d: chrono::NaiveDate| d.format("%Y-%m-%d").to_string();
+
+    let (saving, set_saving) = create_signal(false);
+    let (save_error, set_save_error) = create_signal::<Option<String>>(None);
+
+    // Editable signals for base fields
+    let (edit_name, set_edit_name) = create_signal(current.name.clone());
+    let (edit_description, set_edit_description) =
+        create_signal(current.description.clone().unwrap_or_default());
+    let (edit_notes, set_edit_notes) = create_signal(current.notes.clone().unwrap_or_default());
+    let (edit_location_id, set_edit_location_id) = create_signal(
+        current
+            .location_id
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+    );
+    let (edit_date_acquired, set_edit_date_acquired) =
+        create_signal(current.date_acquired.map(fmt_date).unwrap_or_default());
+    let (edit_barcode, set_edit_barcode) =
+        create_signal(current.barcode.clone().unwrap_or_default());
+    let (edit_catalog_code, set_edit_catalog_code) =
+        create_signal(current.catalog_code.clone().unwrap_or_default());
+    // Width, height, depth (mm) and weight (g) as edit strings
+    let edit_measurements = create_rw_signal(
+        [
+            current.width_mm,
+            current.height_mm,
+            current.depth_mm,
+            current.weight_g,
+        ]
+        .map(|v| v.map(|n| n.to_string()).unwrap_or_default()),
+    );
+
+    // Soft field signals — store serde_json::Value directly so types are
+    // preserved through edit and save without any guessing at save time.
+    let soft_field_map = create_rw_signal::<HashMap<String, serde_json::Value>>(
+        current
+            .soft_fields
+            .as_object()
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .collect(),
+    );
+
+    // Loan signals
+    let loan = details.as_ref().and_then(|d| d.loan_details.as_ref());
+    let (edit_loan_date_loaned, set_edit_loan_date_loaned) =
+        create_signal(loan.map(|l| fmt_date(l.date_loaned)).unwrap_or_default());
+    let (edit_loan_date_due_back, set_edit_loan_date_due_back) = create_signal(
+        loan.and_then(|l| l.date_due_back)
+            .map(fmt_date)
+            .unwrap_or_default(),
+    );
+    let (edit_loan_loaned_to, set_edit_loan_loaned_to) =
+        create_signal(loan.map(|l| l.loaned_to.clone()).unwrap_or_default());
+
+    // Missing/Disposed signals
+    let (edit_missing_date, set_edit_missing_date) = create_signal(
+        details
+            .as_ref()
+            .and_then(|d| d.missing_details.as_ref())
+            .map(|m| fmt_date(m.date_missing))
+            .unwrap_or_default(),
+    );
+    let (edit_disposed_date, set_edit_disposed_date) = create_signal(
+        details
+            .as_ref()
+            .and_then(|d| d.disposed_details.as_ref())
+            .map(|d| fmt_date(d.date_disposed))
+            .unwrap_or_default(),
+    );
+
+    let item_state = current.state.clone();
+    let kind_name = current.kind_name.clone();
+    let item_state_for_save = store_value(item_state.clone());
+
+    let save_action = create_action(move |_: &()| {
+        let is = item_state_for_save.get_value();
+        let name = edit_name.get();
+        let description = edit_description.get();
+        let notes = edit_notes.get();
+        let location_str = edit_location_id.get();
+        let date_acq_str = edit_date_acquired.get();
+        let barcode = edit_barcode.get();
+        let catalog_code = edit_catalog_code.get();
+        // Blank clears (sent as 0); unparseable input leaves the value unchanged
+        let [width_mm, height_mm, depth_mm, weight_g] = edit_measurements.get().map(|v| {
+            let v = v.trim();
+            if v.is_empty() {
+                Some(0)
+            } else {
+                v.parse::<i32>().ok()
+            }
+        });
+
+        // Values are already correctly typed (stored as serde_json::Value by
+        // the input handlers), so no conversion is needed here.
+        let sf_map: serde_json::Map<String, serde_json::Value> =
+            soft_field_map.get_untracked().into_iter().collect();
+
+        let mut req = UpdateItemRequest {
+            name: Some(name),
+            description: Some(description),
+            notes: Some(notes),
+            location_id: if location_str.is_empty() {
+                None
+            } else {
+                Uuid::parse_str(&location_str).ok()
+            },
+            date_acquired: if date_acq_str.is_empty() {
+                None
+            } else {
+                chrono::NaiveDate::parse_from_str(&date_acq_str, "%Y-%m-%d").ok()
+            },
+            state: None,
+            // Empty strings clear these on the API side
+            barcode: Some(barcode),
+            catalog_code: Some(catalog_code),
+            width_mm,
+            height_mm,
+            depth_mm,
+            weight_g,
+            // Serialize to a JSON string — serde_urlencoded (used by Leptos
+            // server fn transport) loses type info for nested serde_json::Value,
+            // so we pass it as a plain string and parse it back server-side.
+            soft_fields: serde_json::to_string(&serde_json::Value::Object(sf_map)).ok(),
+            loan_date_loaned: None,
+            loan_date_due_back: None,
+            loan_loaned_to: None,
+            missing_date_missing: None,
+            disposed_date_disposed: None,
+        };
+
+        // State-specific fields
+        match is {
+            ItemState::Loaned => {
+                let dl = edit_loan_date_loaned.get();
+                if !dl.is_empty() {
+                    req.loan_date_loaned = chrono::NaiveDate::parse_from_str(&dl, "%Y-%m-%d").ok();
+                }
+                let ddb = edit_loan_date_due_back.get();
+                if !ddb.is_empty() {
+                    req.loan_date_due_back =
+                        chrono::NaiveDate::parse_from_str(&ddb, "%Y-%m-%d").ok();
+                }
+                let lt = edit_loan_loaned_to.get();
+                if !lt.is_empty() {
+                    req.loan_loaned_to = Some(lt);
+                }
+            }
+            ItemState::Missing => {
+                let dm = edit_missing_date.get();
+                if !dm.is_empty() {
+                    req.missing_date_missing =
+                        chrono::NaiveDate::parse_from_str(&dm, "%Y-%m-%d").ok();
+                }
+            }
+            ItemState::Disposed => {
+                let dd = edit_disposed_date.get();
+                if !dd.is_empty() {
+                    req.disposed_date_disposed =
+                        chrono::NaiveDate::parse_from_str(&dd, "%Y-%m-%d").ok();
+                }
+            }
+            _ => {}
+        }
+
+        let item_name = req.name.clone().unwrap_or_default();
+        async move {
+            update_item(org_id, item_id, req)
+                .await
+                .map(|warnings| (item_name, warnings))
+        }
+    });
+
+    // React to save action completion
+    create_effect(move |_| {
+        if let Some(result) = save_action.value().get() {
+            match result {
+                Ok((item_name, warnings)) => {
+                    SaveWarnings::report(item_name, warnings);
+                    set_saving.set(false);
+                    on_saved.call(());
+                }
+                Err(e) => {
+                    set_saving.set(false);
+                    let msg = format!("{}", e);
+                    leptos::logging::error!("Failed to save item: {}", msg);
+                    set_save_error.set(Some(msg));
+                }
+            }
+        }
+    });
+
+    view! {
+            <div class="form-group">
+                <label class="form-label">"Name"</label>
+                <input
+                    type="text"
+                    class="form-control"
+                    prop:value=edit_name
+                    on:input=move |ev| set_edit_name.set(event_target_value(&ev))
+                />
+            </div>
+            <div class="form-group">
+                <label class="form-label">"Description"</label>
+                <input
+                    type="text"
+                    class="form-control"
+                    prop:value=edit_description
+                    on:input=move |ev| set_edit_description.set(event_target_value(&ev))
+                />
+            </div>
+            <div class="form-group">
+                <label class="form-label">"Notes"</label>
+                <textarea
+                    class="form-control"
+                    style="min-height:80px;resize:vertical;"
+                    prop:value=edit_notes
+                    on:input=move |ev| set_edit_notes.set(event_target_value(&ev))
+                />
+            </div>
+            <div class="form-group">
+                <label class="form-label">"Location"</label>
+                <select
+                    class="form-control"
+                    prop:value=edit_location_id
+                    on:change=move |ev| set_edit_location_id.set(event_target_value(&ev))
+                >
+                    <option value="">"- None -"</option>
+                    {locations_list
+                        .iter()
+                        .map(|loc| {
+                            let val = loc.id.to_string();
+                            let name = loc.name.clone();
+                            view! { <option value=val>{name}</option> }
+                        })
+                        .collect_view()}
+                </select>
+            </div>
+            <div class="form-group">
+                <label class="form-label">"Date Acquired"</label>
+                <input
+                    type="date"
+                    class="form-control"
+                    prop:value=edit_date_acquired
+                    on:input=move |ev| set_edit_date_acquired.set(event_target_value(&ev))
+                />
+            </div>
+            <div class="form-group">
+                <label class="form-label">"Barcode"</label>
+                <input
+                    type="text"
+                    class="form-control"
+                    prop:value=edit_barcode
+                    on:input=move |ev| set_edit_barcode.set(event_target_value(&ev))
+                />
+            </div>
+            <div class="form-group">
+                <label class="form-label">"Catalog Code"</label>
+                <input
+                    type="text"
+                    class="form-control"
+                    prop:value=edit_catalog_code
+                    on:input=move |ev| set_edit_catalog_code.set(event_target_value(&ev))
+                />
+            </div>
+            <div class="form-group">
+                <label class="form-label">"Size (W × H × D mm, weight g)"</label>
+                <div style="display:flex;gap:8px;">
+                    {["Width", "Height", "Depth", "Weight"]
+                        .into_iter()
+                        .enumerate()
+                        .map(|(idx, placeholder)| {
+                            view! {
+                                <input
+                                    type="number"
+                                    min="0"
+                                    class="form-control"
+                                    style="width:100px;"
+                                    placeholder=placeholder
+                                    prop:value=move || edit_measurements.with(|m| m[idx].clone())
+                                    on:input=move |ev| {
+                                        let v = event_target_value(&ev);
+                                        edit_measurements.update(|m| m[idx] = v);
+                                    }
+                                />
+                            }
+                        })
+                        .collect_view()}
+                </div>
+            </div>
+            <div class="form-group">
+                <label class="form-label">"Type"</label>
+                <span class="detail-value">{kind_name}</span>
+            </div>
+
+            // Soft fields edit section — type-specific inputs
+            {move || {
+                let fields = kind_fields.get();
+                if fields.is_empty() {
+                    render_soft_fields_edit_fallback(soft_field_map)
+                } else {
+                    render_soft_fields_edit_with_defs(&fields, soft_field_map)
+                }
+            }}
+
+            // State-specific edit fields
+            {render_state_edit_fields(&item_state, edit_loan_date_loaned, set_edit_loan_date_loaned, edit_loan_date_due_back, set_edit_loan_date_due_back, edit_loan_loaned_to, set_edit_loan_loaned_to, edit_missing_date, set_edit_missing_date, edit_disposed_date, set_edit_disposed_date)}
+
+            <Show when=move || save_error.get().is_some() fallback=|| ()>
+                <div class="error">
+                    {move || save_error.get().unwrap_or_default()}
+                </div>
+            </Show>
+            <div class="detail-actions">
+                <button
+                    class="btn btn-secondary"
+                    prop:disabled=saving
+                    on:click=move |_| on_cancel.call(())
+                >
+                    "Cancel"
+                </button>
+                <button
+                    class="btn btn-primary"
+                    style="width:auto;"
+                    prop:disabled=saving
+                    on:click=move |_| {
+                        set_save_error.set(None);
+                        set_saving.set(true);
+                        save_action.dispatch(());
+                    }
+                >
+                    {move || if saving.get() { "Saving..." } else { "Save" }}
+                </button>
+            </div>
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn render_state_edit_fields(
     state: &ItemState,
@@ -983,7 +1333,7 @@ fn render_soft_fields_edit_fallback(
                                     class="edit-input"
                                     prop:value=move || {
                                         soft_field_map.with(|m| {
-                                            m.get(&name).map(|v| value_to_edit_str(v)).unwrap_or_default()
+                                            m.get(&name).map(value_to_edit_str).unwrap_or_default()
                                         })
                                     }
                                     on:input=move |ev| {
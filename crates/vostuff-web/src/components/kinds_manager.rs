@@ -1,6 +1,8 @@
 use leptos::*;
 use uuid::Uuid;
+use vostuff_core::models::ErrorCode;
 
+use crate::server_fns::errors::error_code;
 use crate::server_fns::fields::{Field, get_fields};
 use crate::server_fns::kinds::{
     Kind, create_kind, delete_kind, get_kinds_full, override_kind, revert_kind, update_kind,
@@ -592,11 +594,10 @@ fn EditKindModal(
                 match result {
                     Ok(_) => on_saved.call(()),
                     Err(e) => {
-                        let msg = e.to_string();
-                        if msg.contains("data_loss_required") {
+                        if error_code(&e) == Some(ErrorCode::KindDataLossRequired) {
                             show_force_warning.set(true);
                         } else {
-                            error.set(Some(msg));
+                            error.set(Some(e.to_string()));
                         }
                     }
                 }
@@ -0,0 +1,92 @@
+use leptos::*;
+use uuid::Uuid;
+
+use crate::server_fns::settings::{OrganizationSettings, get_org_settings, update_org_settings};
+
+/// Blank input means no limit
+fn parse_limit(s: &str) -> Option<i32> {
+    s.trim().parse().ok()
+}
+
+fn limit_to_str(v: Option<i32>) -> String {
+    v.map(|n| n.to_string()).unwrap_or_default()
+}
+
+#[component]
+pub fn LoanPolicySettings(org_id: Uuid) -> impl IntoView {
+    let max_loans = create_rw_signal(String::new());
+    let max_days = create_rw_signal(String::new());
+    let (status, set_status) = create_signal::<Option<Result<(), String>>>(None);
+
+    let settings_resource =
+        create_resource(move || org_id, |o| async move { get_org_settings(o).await });
+
+    // Fill the form once settings arrive
+    create_effect(move |_| {
+        if let Some(Ok(settings)) = settings_resource.get() {
+            max_loans.set(limit_to_str(settings.max_loans_per_borrower));
+            max_days.set(limit_to_str(settings.max_loan_days));
+        }
+    });
+
+    // Settings are replaced as a whole, so re-read them to keep other sections intact
+    let save_action = create_action(move |_: &()| {
+        let max_loans_per_borrower = parse_limit(&max_loans.get());
+        let max_loan_days = parse_limit(&max_days.get());
+        async move {
+            let settings = OrganizationSettings {
+                max_loans_per_borrower,
+                max_loan_days,
+                ..get_org_settings(org_id).await?
+            };
+            update_org_settings(org_id, settings).await
+        }
+    });
+
+    create_effect(move |_| {
+        if let Some(result) = save_action.value().get() {
+            set_status.set(Some(result.map(|_| ()).map_err(|e| e.to_string())));
+        }
+    });
+
+    view! {
+        <div class="mgmt-section">
+            <h3>"Loan Limits"</h3>
+            <p style="color:#888;font-size:13px;">
+                "Leave blank for no limit. Loan managers and admins can override these when lending an item."
+            </p>
+            <div class="form-group">
+                <label>"Max items on loan per borrower"</label>
+                <input
+                    type="number"
+                    min="1"
+                    class="form-control"
+                    prop:value=max_loans
+                    on:input=move |ev| max_loans.set(event_target_value(&ev))
+                />
+            </div>
+            <div class="form-group">
+                <label>"Max loan length (days)"</label>
+                <input
+                    type="number"
+                    min="1"
+                    class="form-control"
+                    prop:value=max_days
+                    on:input=move |ev| max_days.set(event_target_value(&ev))
+                />
+            </div>
+            <button
+                class="btn btn-primary"
+                disabled=move || save_action.pending().get()
+                on:click=move |_| save_action.dispatch(())
+            >
+                "Save"
+            </button>
+            {move || match status.get() {
+                Some(Ok(())) => view! { <span style="margin-left:12px;">"Saved"</span> }.into_view(),
+                Some(Err(e)) => view! { <div class="error">{e}</div> }.into_view(),
+                None => ().into_view(),
+            }}
+        </div>
+    }
+}
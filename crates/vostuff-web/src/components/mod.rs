@@ -1,8 +1,23 @@
+pub mod announcement;
+pub mod barcode_scanner;
+pub mod branding;
+pub mod command_palette;
 pub mod create_item;
+pub mod dashboard_widgets;
 pub mod fields_manager;
+pub mod file_download;
 pub mod filter_dropdown;
 pub mod header;
+pub mod item_suggestions;
 pub mod items_table;
 pub mod kinds_manager;
+pub mod loan_policy;
+pub mod markdown;
 pub mod pagination;
+pub mod paste_import;
+pub mod photo_import;
+pub mod print_labels;
+pub mod read_only;
+pub mod save_warnings;
 pub mod soft_field_helpers;
+pub mod whats_new;
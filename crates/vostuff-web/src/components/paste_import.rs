@@ -0,0 +1,283 @@
+use leptos::*;
+use uuid::Uuid;
+
+use crate::components::save_warnings::SaveWarnings;
+use crate::server_fns::items::{CreateItemRequest, Location, create_items, get_locations};
+use crate::server_fns::kinds::{KindSummary, get_kinds};
+
+/// Most rows one paste may create; matches the API's bulk limit
+const MAX_ROWS: usize = 100;
+
+/// One pasted line: what was typed, and the item it makes or why it can't
+#[derive(Clone)]
+struct PastedRow {
+    name: String,
+    kind: String,
+    location: String,
+    item: Result<CreateItemRequest, String>,
+}
+
+/// Parse pasted `name, type, location` lines, tab or comma separated.
+///
+/// Types match a kind's name or display name and locations a location's name,
+/// ignoring case. Blank lines and a `name, type, location` header are skipped.
+fn parse_rows(text: &str, kinds: &[KindSummary], locations: &[Location]) -> Vec<PastedRow> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let separator = if line.contains('\t') { '\t' } else { ',' };
+            let mut cells = line
+                .split(separator)
+                .map(|cell| cell.trim().trim_matches('"').trim().to_string());
+            let name = cells.next().unwrap_or_default();
+            let kind = cells.next().unwrap_or_default();
+            let location = cells.next().unwrap_or_default();
+            let extra = cells.next().is_some();
+            let item = resolve_row(&name, &kind, &location, extra, kinds, locations);
+            PastedRow {
+                name,
+                kind,
+                location,
+                item,
+            }
+        })
+        .filter(|row| {
+            !(row.name.eq_ignore_ascii_case("name") && row.kind.eq_ignore_ascii_case("type"))
+        })
+        .collect()
+}
+
+fn resolve_row(
+    name: &str,
+    kind: &str,
+    location: &str,
+    extra: bool,
+    kinds: &[KindSummary],
+    locations: &[Location],
+) -> Result<CreateItemRequest, String> {
+    if extra {
+        return Err("Expected at most three columns: name, type, location".to_string());
+    }
+    if name.is_empty() {
+        return Err("Name is required".to_string());
+    }
+    if kind.is_empty() {
+        return Err("Type is required".to_string());
+    }
+    let kind_id = kinds
+        .iter()
+        .find(|k| {
+            k.name.eq_ignore_ascii_case(kind)
+                || k.display_name
+                    .as_deref()
+                    .is_some_and(|d| d.eq_ignore_ascii_case(kind))
+        })
+        .map(|k| k.id)
+        .ok_or_else(|| format!("Unknown type \"{}\"", kind))?;
+    let location_id = if location.is_empty() {
+        None
+    } else {
+        let found = locations
+            .iter()
+            .find(|l| l.name.eq_ignore_ascii_case(location))
+            .ok_or_else(|| format!("Unknown location \"{}\"", location))?;
+        Some(found.id)
+    };
+    Ok(CreateItemRequest {
+        kind_id,
+        name: name.to_string(),
+        description: None,
+        notes: None,
+        location_id,
+        date_acquired: None,
+        barcode: None,
+        catalog_code: None,
+        soft_fields: None,
+    })
+}
+
+/// Quick import: paste a few rows of `name, type, location`, check the
+/// preview, and create them all in one go
+#[component]
+pub fn PasteImportModal(
+    org_id: Uuid,
+    show: ReadSignal<bool>,
+    on_close: Callback<()>,
+    on_created: Callback<()>,
+) -> impl IntoView {
+    let text = create_rw_signal(String::new());
+    let saving = create_rw_signal(false);
+    let error = create_rw_signal::<Option<String>>(None);
+
+    let reset_form = move || {
+        text.set(String::new());
+        saving.set(false);
+        error.set(None);
+    };
+
+    let kinds_resource = create_resource(
+        move || org_id,
+        |org_id| async move { get_kinds(org_id).await },
+    );
+
+    let locations_resource = create_resource(
+        move || org_id,
+        |org_id| async move { get_locations(org_id).await },
+    );
+
+    let rows = Signal::derive(move || {
+        let kinds = kinds_resource
+            .get()
+            .and_then(|r| r.ok())
+            .unwrap_or_default();
+        let locations = locations_resource
+            .get()
+            .and_then(|r| r.ok())
+            .unwrap_or_default();
+        parse_rows(&text.get(), &kinds, &locations)
+    });
+
+    let save_action = create_action(move |reqs: &Vec<CreateItemRequest>| {
+        let reqs = reqs.clone();
+        async move {
+            let names: Vec<String> = reqs.iter().map(|r| r.name.clone()).collect();
+            create_items(org_id, reqs).await.map(|warnings| {
+                names
+                    .into_iter()
+                    .zip(warnings)
+                    .flat_map(|(name, ws)| ws.into_iter().map(move |w| format!("{}: {}", name, w)))
+                    .collect::<Vec<_>>()
+            })
+        }
+    });
+
+    create_effect(move |_| {
+        if let Some(result) = save_action.value().get() {
+            saving.set(false);
+            match result {
+                Ok(warnings) => {
+                    let count = rows.get_untracked().len();
+                    SaveWarnings::report(format!("{} pasted items", count), warnings);
+                    on_created.call(());
+                    on_close.call(());
+                    reset_form();
+                }
+                Err(e) => {
+                    error.set(Some(format!("{}", e)));
+                }
+            }
+        }
+    });
+
+    let close_and_reset = move || {
+        reset_form();
+        on_close.call(());
+    };
+
+    view! {
+        <Show when=move || show.get() fallback=|| ()>
+            <div class="modal-overlay" on:click=move |_| close_and_reset()>
+                <div class="modal" on:click=move |ev| ev.stop_propagation()>
+                    <div class="modal-header">
+                        <h2>"Paste Items"</h2>
+                    </div>
+                    <div class="modal-body">
+                        <div class="form-group">
+                            <label>"One item per line: name, type, location (tab or comma separated)"</label>
+                            <textarea
+                                class="form-control"
+                                style="min-height:140px;resize:vertical;font-family:monospace;"
+                                placeholder="Kind of Blue, cd, Living room"
+                                prop:value=text
+                                on:input=move |ev| text.set(event_target_value(&ev))
+                            />
+                        </div>
+                        <Show when=move || !rows.get().is_empty() fallback=|| ()>
+                            <table class="items-table">
+                                <thead>
+                                    <tr>
+                                        <th>"Name"</th>
+                                        <th>"Type"</th>
+                                        <th>"Location"</th>
+                                        <th></th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {move || {
+                                        rows.get()
+                                            .into_iter()
+                                            .map(|row| {
+                                                let status = match &row.item {
+                                                    Ok(_) => view! { <span>"✓"</span> }.into_view(),
+                                                    Err(e) => view! { <span class="error">{e.clone()}</span> }.into_view(),
+                                                };
+                                                view! {
+                                                    <tr>
+                                                        <td>{row.name}</td>
+                                                        <td>{row.kind}</td>
+                                                        <td>{row.location}</td>
+                                                        <td>{status}</td>
+                                                    </tr>
+                                                }
+                                            })
+                                            .collect_view()
+                                    }}
+                                </tbody>
+                            </table>
+                        </Show>
+                        <Show when=move || error.get().is_some() fallback=|| ()>
+                            <div class="error">
+                                {move || error.get().unwrap_or_default()}
+                            </div>
+                        </Show>
+                    </div>
+                    <div class="modal-footer">
+                        <button
+                            class="btn btn-secondary"
+                            prop:disabled=move || saving.get()
+                            on:click=move |_| close_and_reset()
+                        >
+                            "Cancel"
+                        </button>
+                        <button
+                            class="btn btn-primary"
+                            style="width:auto;"
+                            prop:disabled=move || saving.get()
+                            on:click=move |_| {
+                                let parsed = rows.get_untracked();
+                                if parsed.is_empty() {
+                                    error.set(Some("Paste at least one row".to_string()));
+                                    return;
+                                }
+                                if parsed.len() > MAX_ROWS {
+                                    error.set(Some(format!("At most {} rows can be pasted at once", MAX_ROWS)));
+                                    return;
+                                }
+                                let reqs: Result<Vec<_>, _> =
+                                    parsed.into_iter().map(|row| row.item).collect();
+                                match reqs {
+                                    Ok(reqs) => {
+                                        error.set(None);
+                                        saving.set(true);
+                                        save_action.dispatch(reqs);
+                                    }
+                                    Err(_) => {
+                                        error.set(Some("Fix the rows marked above first".to_string()));
+                                    }
+                                }
+                            }
+                        >
+                            {move || {
+                                if saving.get() {
+                                    "Saving...".to_string()
+                                } else {
+                                    format!("Create {} Items", rows.get().len())
+                                }
+                            }}
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </Show>
+    }
+}
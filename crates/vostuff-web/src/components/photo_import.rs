@@ -0,0 +1,111 @@
+use leptos::*;
+use uuid::Uuid;
+use web_sys::{FormData, HtmlFormElement};
+
+use crate::server_fns::items::import_photos;
+
+/// Upload a zip of photos named by barcode or catalogue number, then show
+/// which were attached and which were not
+#[component]
+pub fn PhotoImportModal(
+    org_id: Uuid,
+    show: ReadSignal<bool>,
+    on_close: Callback<()>,
+) -> impl IntoView {
+    let upload_action = create_action(|data: &FormData| import_photos(data.clone().into()));
+    let uploading = upload_action.pending();
+    let result = upload_action.value();
+
+    let close_and_reset = move || {
+        result.set(None);
+        on_close.call(());
+    };
+
+    let summary = move || {
+        result.get().map(|r| match r {
+            Ok(r) => {
+                let unmatched = if r.unmatched.is_empty() {
+                    ().into_view()
+                } else {
+                    let rows = r
+                        .unmatched
+                        .into_iter()
+                        .map(|u| {
+                            view! {
+                                <tr>
+                                    <td>{u.filename}</td>
+                                    <td class="error">{u.reason}</td>
+                                </tr>
+                            }
+                        })
+                        .collect_view();
+                    view! {
+                        <table class="items-table">
+                            <thead>
+                                <tr>
+                                    <th>"Not attached"</th>
+                                    <th></th>
+                                </tr>
+                            </thead>
+                            <tbody>{rows}</tbody>
+                        </table>
+                    }
+                    .into_view()
+                };
+                view! {
+                    <p>{format!("Attached {} photos.", r.attached.len())}</p>
+                    {unmatched}
+                }
+                .into_view()
+            }
+            Err(e) => view! { <div class="error">{e.to_string()}</div> }.into_view(),
+        })
+    };
+
+    view! {
+        <Show when=move || show.get() fallback=|| ()>
+            <div class="modal-overlay" on:click=move |_| close_and_reset()>
+                <div class="modal" on:click=move |ev| ev.stop_propagation()>
+                    <div class="modal-header">
+                        <h2>"Upload Photos"</h2>
+                    </div>
+                    <form on:submit=move |ev| {
+                        ev.prevent_default();
+                        let form = event_target::<HtmlFormElement>(&ev);
+                        if let Ok(data) = FormData::new_with_form(&form) {
+                            upload_action.dispatch(data);
+                        }
+                    }>
+                        <div class="modal-body">
+                            <div class="form-group">
+                                <label>
+                                    "A zip of photos, each named by the item's barcode or catalogue number, e.g. \"PCS 7088.jpg\" or \"PCS 7088_back.jpg\""
+                                </label>
+                                <input type="hidden" name="org_id" value=org_id.to_string() />
+                                <input type="file" name="archive" accept=".zip" required />
+                            </div>
+                            {summary}
+                        </div>
+                        <div class="modal-footer">
+                            <button
+                                type="button"
+                                class="btn btn-secondary"
+                                on:click=move |_| close_and_reset()
+                            >
+                                "Close"
+                            </button>
+                            <button
+                                type="submit"
+                                class="btn btn-primary"
+                                style="width:auto;"
+                                prop:disabled=move || uploading.get()
+                            >
+                                {move || if uploading.get() { "Uploading..." } else { "Upload" }}
+                            </button>
+                        </div>
+                    </form>
+                </div>
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,164 @@
+use leptos::*;
+use uuid::Uuid;
+use vostuff_core::models::{LabelTemplate, PrintLabelsRequest};
+
+use crate::components::file_download::download_bytes;
+use crate::server_fns::items::print_labels;
+use crate::server_fns::settings::get_org_settings;
+
+/// Print dialog: pick one of the organization's label templates and download
+/// a PDF with a label for each checked item
+#[component]
+pub fn PrintLabelsModal(
+    org_id: Uuid,
+    show: ReadSignal<bool>,
+    item_ids: Signal<Vec<Uuid>>,
+    on_close: Callback<()>,
+) -> impl IntoView {
+    let template = create_rw_signal(String::new());
+    let printing = create_rw_signal(false);
+    let error = create_rw_signal::<Option<String>>(None);
+
+    // Loaded each time the dialog opens, so newly saved templates show up
+    let templates_resource = create_resource(
+        move || (org_id, show.get()),
+        |(org_id, show)| async move {
+            if !show {
+                return Ok(Vec::new());
+            }
+            get_org_settings(org_id).await.map(|settings| {
+                settings
+                    .label_templates
+                    .into_iter()
+                    .filter_map(|t| serde_json::from_value::<LabelTemplate>(t).ok())
+                    .collect::<Vec<_>>()
+            })
+        },
+    );
+
+    // Default to the first template once they load
+    create_effect(move |_| {
+        if let Some(Ok(templates)) = templates_resource.get() {
+            let current = template.get_untracked();
+            if !templates.iter().any(|t| t.name == current) {
+                template.set(
+                    templates
+                        .first()
+                        .map(|t| t.name.clone())
+                        .unwrap_or_default(),
+                );
+            }
+        }
+    });
+
+    let print_action = create_action(move |(name, ids): &(String, Vec<Uuid>)| {
+        let (name, ids) = (name.clone(), ids.clone());
+        async move { print_labels(org_id, name, ids).await }
+    });
+
+    create_effect(move |_| {
+        if let Some(result) = print_action.value().get() {
+            printing.set(false);
+            match result {
+                Ok(file) => {
+                    download_bytes(&file.filename, "application/pdf", &file.pdf);
+                    on_close.call(());
+                }
+                Err(e) => error.set(Some(format!("{}", e))),
+            }
+        }
+    });
+
+    let close = move || {
+        error.set(None);
+        on_close.call(());
+    };
+
+    view! {
+        <Show when=move || show.get() fallback=|| ()>
+            <div class="modal-overlay" on:click=move |_| close()>
+                <div class="modal" on:click=move |ev| ev.stop_propagation()>
+                    <div class="modal-header">
+                        <h2>"Print Labels"</h2>
+                    </div>
+                    <div class="modal-body">
+                        <Suspense fallback=move || view! { <p>"Loading templates..."</p> }>
+                            {move || {
+                                templates_resource.get().map(|result| match result {
+                                    Ok(templates) if templates.is_empty() => view! {
+                                        <p>
+                                            "No label templates yet. An administrator can add them "
+                                            "through the organization settings API."
+                                        </p>
+                                    }
+                                    .into_view(),
+                                    Ok(templates) => view! {
+                                        <div class="form-group">
+                                            <label>"Template"</label>
+                                            <select
+                                                class="form-control"
+                                                prop:value=move || template.get()
+                                                on:change=move |ev| template.set(event_target_value(&ev))
+                                            >
+                                                {templates
+                                                    .into_iter()
+                                                    .map(|t| {
+                                                        let label = format!(
+                                                            "{} ({} × {} mm)",
+                                                            t.name, t.width_mm, t.height_mm
+                                                        );
+                                                        let selected = template.get_untracked() == t.name;
+                                                        view! {
+                                                            <option value=t.name selected=selected>{label}</option>
+                                                        }
+                                                    })
+                                                    .collect_view()}
+                                            </select>
+                                        </div>
+                                        <p>{move || format!("One label for each of the {} selected items.", item_ids.get().len())}</p>
+                                    }
+                                    .into_view(),
+                                    Err(e) => view! { <div class="error">{format!("{}", e)}</div> }.into_view(),
+                                })
+                            }}
+                        </Suspense>
+                        <Show when=move || error.get().is_some() fallback=|| ()>
+                            <div class="error">
+                                {move || error.get().unwrap_or_default()}
+                            </div>
+                        </Show>
+                    </div>
+                    <div class="modal-footer">
+                        <button
+                            class="btn btn-secondary"
+                            prop:disabled=move || printing.get()
+                            on:click=move |_| close()
+                        >
+                            "Cancel"
+                        </button>
+                        <button
+                            class="btn btn-primary"
+                            style="width:auto;"
+                            prop:disabled=move || printing.get() || template.get().is_empty()
+                            on:click=move |_| {
+                                let ids = item_ids.get_untracked();
+                                if ids.len() > PrintLabelsRequest::MAX_ITEMS {
+                                    error.set(Some(format!(
+                                        "At most {} labels can be printed at once",
+                                        PrintLabelsRequest::MAX_ITEMS
+                                    )));
+                                    return;
+                                }
+                                error.set(None);
+                                printing.set(true);
+                                print_action.dispatch((template.get_untracked(), ids));
+                            }
+                        >
+                            {move || if printing.get() { "Printing..." } else { "Download PDF" }}
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </Show>
+    }
+}
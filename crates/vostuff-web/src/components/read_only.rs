@@ -0,0 +1,68 @@
+use leptos::*;
+use uuid::Uuid;
+
+use crate::server_fns::settings::{get_org_settings, update_org_settings};
+
+/// Admin switch for the organization's read-only mode
+#[component]
+pub fn ReadOnlySettings(org_id: Uuid) -> impl IntoView {
+    let read_only = create_rw_signal(false);
+    let (status, set_status) = create_signal::<Option<Result<(), String>>>(None);
+
+    let settings_resource =
+        create_resource(move || org_id, |o| async move { get_org_settings(o).await });
+
+    // Fill the form once settings arrive
+    create_effect(move |_| {
+        if let Some(Ok(settings)) = settings_resource.get() {
+            read_only.set(settings.read_only);
+        }
+    });
+
+    // Settings are replaced as a whole, so re-read them to keep other sections intact
+    let save_action = create_action(move |enabled: &bool| {
+        let enabled = *enabled;
+        async move {
+            let mut settings = get_org_settings(org_id).await?;
+            settings.read_only = enabled;
+            update_org_settings(org_id, settings).await
+        }
+    });
+
+    create_effect(move |_| {
+        if let Some(result) = save_action.value().get() {
+            set_status.set(Some(result.map(|_| ()).map_err(|e| e.to_string())));
+        }
+    });
+
+    view! {
+        <div class="mgmt-section">
+            <h3>"Read-only Mode"</h3>
+            <p style="color:#888;font-size:13px;">
+                "Freeze the organization, for example during an audit or a migration. Members can still view everything, but no changes can be made until read-only mode is turned off. Reload the page to see the banner."
+            </p>
+            <div class="form-group">
+                <label>
+                    <input
+                        type="checkbox"
+                        prop:checked=read_only
+                        on:change=move |ev| read_only.set(event_target_checked(&ev))
+                    />
+                    " Organization is read-only"
+                </label>
+            </div>
+            <button
+                class="btn btn-primary"
+                disabled=move || save_action.pending().get()
+                on:click=move |_| save_action.dispatch(read_only.get())
+            >
+                "Save"
+            </button>
+            {move || match status.get() {
+                Some(Ok(())) => view! { <span style="margin-left:12px;">"Saved"</span> }.into_view(),
+                Some(Err(e)) => view! { <div class="error">{e}</div> }.into_view(),
+                None => ().into_view(),
+            }}
+        </div>
+    }
+}
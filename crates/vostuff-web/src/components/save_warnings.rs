@@ -0,0 +1,50 @@
+use leptos::*;
+
+/// Warnings from the most recent item save on this page, as (item name, warnings).
+///
+/// Provided by the items page so the notice survives the list refresh that follows a save.
+#[derive(Clone, Copy)]
+pub struct SaveWarnings(RwSignal<Option<(String, Vec<String>)>>);
+
+impl SaveWarnings {
+    pub fn provide() -> Self {
+        let warnings = SaveWarnings(create_rw_signal(None));
+        provide_context(warnings);
+        warnings
+    }
+
+    /// Record the outcome of a save; a clean save clears any earlier notice
+    pub fn report(item_name: String, warnings: Vec<String>) {
+        if let Some(SaveWarnings(signal)) = use_context::<SaveWarnings>() {
+            signal.set((!warnings.is_empty()).then_some((item_name, warnings)));
+        }
+    }
+}
+
+/// Yellow notice listing warnings for a save that went through
+#[component]
+pub fn SaveWarningsNotice(warnings: SaveWarnings) -> impl IntoView {
+    let SaveWarnings(signal) = warnings;
+    move || {
+        signal.get().map(|(name, warnings)| {
+            view! {
+                <div class="warning-notice">
+                    <button
+                        class="warning-dismiss"
+                        title="Dismiss"
+                        on:click=move |_| signal.set(None)
+                    >
+                        "×"
+                    </button>
+                    <strong>{format!("\"{}\" was saved, but please check:", name)}</strong>
+                    <ul>
+                        {warnings
+                            .into_iter()
+                            .map(|w| view! { <li>{w}</li> })
+                            .collect_view()}
+                    </ul>
+                </div>
+            }
+        })
+    }
+}
@@ -84,7 +84,7 @@ pub fn render_soft_field_input(
     let n1 = name.clone();
     // Convert the stored Value to a display string for HTML inputs
     let current_val =
-        move || soft_field_map.with(|m| m.get(&n1).map(|v| value_to_edit_str(v)).unwrap_or_default());
+        move || soft_field_map.with(|m| m.get(&n1).map(value_to_edit_str).unwrap_or_default());
 
     match field_type.as_str() {
         "boolean" => {
@@ -0,0 +1,109 @@
+use leptos::*;
+
+use crate::server_fns::changelog::{ApiChangeKind, ChangelogRelease, get_changelog};
+
+/// localStorage key remembering the latest release this browser has shown
+const SEEN_KEY: &str = "vostuff.changelog.seen";
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window().and_then(|w| w.local_storage().ok().flatten())
+}
+
+fn kind_label(kind: ApiChangeKind) -> &'static str {
+    match kind {
+        ApiChangeKind::Added => "New",
+        ApiChangeKind::Changed => "Changed",
+        ApiChangeKind::Deprecated => "Deprecated",
+        ApiChangeKind::Removed => "Removed",
+        ApiChangeKind::Fixed => "Fixed",
+    }
+}
+
+/// Dialog listing the releases deployed since this browser last looked
+///
+/// A browser that has never seen the changelog only remembers the latest
+/// release, so new users are not shown the whole history.
+#[component]
+pub fn WhatsNew() -> impl IntoView {
+    let (releases, set_releases) = create_signal::<Vec<ChangelogRelease>>(Vec::new());
+
+    // Effects only run in the browser, where localStorage is available
+    create_effect(move |_| {
+        let Some(storage) = local_storage() else {
+            return;
+        };
+        let seen = storage.get_item(SEEN_KEY).ok().flatten();
+        spawn_local(async move {
+            let Ok(newer) = get_changelog(seen.clone()).await else {
+                return;
+            };
+            match (seen, newer.first()) {
+                (None, Some(latest)) => {
+                    let _ = storage.set_item(SEEN_KEY, &latest.version);
+                }
+                (Some(_), Some(_)) => set_releases.set(newer),
+                (_, None) => {}
+            }
+        });
+    });
+
+    let close = move || {
+        if let (Some(storage), Some(latest)) = (local_storage(), releases.get_untracked().first()) {
+            let _ = storage.set_item(SEEN_KEY, &latest.version);
+        }
+        set_releases.set(Vec::new());
+    };
+
+    move || {
+        let shown = releases.get();
+        (!shown.is_empty()).then(|| {
+            view! {
+                <div class="modal-overlay" on:click=move |_| close()>
+                    <div class="modal" on:click=move |ev| ev.stop_propagation()>
+                        <div class="modal-header">
+                            <h2>"What's new"</h2>
+                        </div>
+                        <div class="modal-body">
+                            {shown
+                                .into_iter()
+                                .map(|release| {
+                                    view! {
+                                        <div class="whats-new-release">
+                                            <h3>
+                                                {release.version}
+                                                <span class="whats-new-date">
+                                                    {release.date.format("%-d %B %Y").to_string()}
+                                                </span>
+                                            </h3>
+                                            <ul>
+                                                {release
+                                                    .changes
+                                                    .into_iter()
+                                                    .map(|change| {
+                                                        view! {
+                                                            <li>
+                                                                <span class="whats-new-kind">
+                                                                    {kind_label(change.kind)}
+                                                                </span>
+                                                                {change.summary}
+                                                            </li>
+                                                        }
+                                                    })
+                                                    .collect_view()}
+                                            </ul>
+                                        </div>
+                                    }
+                                })
+                                .collect_view()}
+                        </div>
+                        <div class="modal-footer">
+                            <button class="btn btn-secondary" on:click=move |_| close()>
+                                "Close"
+                            </button>
+                        </div>
+                    </div>
+                </div>
+            }
+        })
+    }
+}
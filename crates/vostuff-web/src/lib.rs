@@ -3,7 +3,9 @@ pub mod components;
 pub mod pages;
 pub mod server_fns;
 
+#[cfg(feature = "hydrate")]
 use leptos::*;
+#[cfg(feature = "hydrate")]
 use wasm_bindgen::prelude::wasm_bindgen;
 
 pub use app::App;
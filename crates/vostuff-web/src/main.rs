@@ -1,24 +1,26 @@
-use axum::{Router, routing::post};
+use axum::{Router, extract::Request, routing::post};
 use leptos::*;
 use leptos_axum::{LeptosRoutes, generate_route_list};
-use std::env;
-use tower_http::services::ServeDir;
+use tower_http::{services::ServeDir, trace::TraceLayer};
+use vostuff_core::{config::Config, telemetry};
 
 #[tokio::main]
 async fn main() {
-    // Initialize tracing for logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
-        )
-        .init();
+    // Shared settings from vostuff.toml and the environment; the web server
+    // needs the API's address and where to send traces
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
 
-    // Get API base URL from environment
-    let api_base_url =
-        env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
-
-    tracing::info!("API base URL: {}", api_base_url);
+    // Logging, and trace export when an OTLP endpoint is configured
+    let _telemetry = telemetry::init("vostuff-web", "info", config.otlp_endpoint.as_deref())
+        .expect("Failed to initialize tracing");
+    tracing::info!("API base URL: {}", config.api_base_url);
+    vostuff_web::server_fns::set_api_base_url(config.api_base_url);
 
     // Get Leptos configuration
     // cargo-leptos sets LEPTOS_OUTPUT_NAME when running
@@ -34,9 +36,11 @@ async fn main() {
         // Serve static files from the public directory
         .nest_service("/pkg", ServeDir::new("./target/site/pkg"))
         .nest_service("/style", ServeDir::new("./crates/vostuff-web/style"))
+        .nest_service("/vendor", ServeDir::new("./crates/vostuff-web/vendor"))
         .route("/api/*fn", post(leptos_axum::handle_server_fns))
         .leptos_routes(&leptos_options, routes, || view! { <vostuff_web::App/> })
-        .with_state(leptos_options);
+        .with_state(leptos_options)
+        .layer(TraceLayer::new_for_http().make_span_with(request_span));
 
     tracing::info!("VOStuff Web Server starting on {}", addr);
     tracing::info!("Visit http://{}", addr);
@@ -47,3 +51,17 @@ async fn main() {
         .await
         .unwrap();
 }
+
+/// Span for each request, continuing the browser's trace if it sent one;
+/// server functions and their API calls are its children
+fn request_span(request: &Request) -> tracing::Span {
+    let span = tracing::info_span!(
+        "request",
+        otel.name = %format!("{} {}", request.method(), request.uri().path()),
+        otel.kind = "server",
+        method = %request.method(),
+        uri = %request.uri(),
+    );
+    telemetry::continue_trace(&span, request.headers());
+    span
+}
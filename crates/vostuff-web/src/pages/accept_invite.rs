@@ -0,0 +1,118 @@
+use leptos::*;
+use leptos_router::*;
+
+use crate::server_fns::auth::accept_invite;
+
+/// Landing page for an emailed organization invite (`/accept-invite?token=...`)
+#[component]
+pub fn AcceptInvitePage() -> impl IntoView {
+    let query = use_query_map();
+    let token = move || query.with(|q| q.get("token").cloned().unwrap_or_default());
+
+    let (name, set_name) = create_signal(String::new());
+    let (password, set_password) = create_signal(String::new());
+    let (confirm_password, set_confirm_password) = create_signal(String::new());
+    let (error, set_error) = create_signal::<Option<String>>(None);
+    let (accepted, set_accepted) = create_signal(false);
+    let (is_loading, set_is_loading) = create_signal(false);
+
+    let handle_accept = create_action(move |_: &()| {
+        let token_val = token();
+        let name_val = Some(name.get_untracked().trim().to_string()).filter(|n| !n.is_empty());
+        let password_val = Some(password.get_untracked()).filter(|p| !p.is_empty());
+
+        async move {
+            set_is_loading.set(true);
+            match accept_invite(token_val, name_val, password_val).await {
+                Ok(()) => set_accepted.set(true),
+                Err(e) => set_error.set(Some(e.to_string())),
+            }
+            set_is_loading.set(false);
+        }
+    });
+
+    view! {
+        <div class="container">
+            <div class="form">
+                <h1 class="form-title">"Join Organization"</h1>
+                {move || {
+                    if accepted.get() {
+                        view! {
+                            <p class="text-center mb-16">
+                                "You have joined the organization. Log in to get started."
+                            </p>
+                            <a href="/login" class="btn btn-primary">"Go to Login"</a>
+                        }
+                            .into_view()
+                    } else if token().is_empty() {
+                        view! {
+                            <div class="error">
+                                "This invite link is incomplete. Open the link from your invitation email."
+                            </div>
+                        }
+                            .into_view()
+                    } else {
+                        view! {
+                            <p class="text-center mb-16">
+                                "If you already have an account, just accept. Otherwise choose your name and a password."
+                            </p>
+
+                            {move || error.get().map(|e| view! { <div class="error">{e}</div> })}
+
+                            <form on:submit=move |ev| {
+                                ev.prevent_default();
+                                if password.get() != confirm_password.get() {
+                                    set_error.set(Some("Passwords do not match".to_string()));
+                                    return;
+                                }
+                                set_error.set(None);
+                                handle_accept.dispatch(());
+                            }>
+                                <div class="form-group">
+                                    <label class="form-label">"Name"</label>
+                                    <input
+                                        type="text"
+                                        class="form-input"
+                                        prop:value=name
+                                        on:input=move |ev| set_name.set(event_target_value(&ev))
+                                    />
+                                </div>
+
+                                <div class="form-group">
+                                    <label class="form-label">"Password"</label>
+                                    <input
+                                        type="password"
+                                        class="form-input"
+                                        prop:value=password
+                                        on:input=move |ev| set_password.set(event_target_value(&ev))
+                                    />
+                                </div>
+
+                                <div class="form-group">
+                                    <label class="form-label">"Confirm password"</label>
+                                    <input
+                                        type="password"
+                                        class="form-input"
+                                        prop:value=confirm_password
+                                        on:input=move |ev| {
+                                            set_confirm_password.set(event_target_value(&ev))
+                                        }
+                                    />
+                                </div>
+
+                                <button
+                                    type="submit"
+                                    class="btn btn-primary"
+                                    disabled=move || is_loading.get()
+                                >
+                                    {move || if is_loading.get() { "Joining..." } else { "Accept Invite" }}
+                                </button>
+                            </form>
+                        }
+                            .into_view()
+                    }
+                }}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,260 @@
+use leptos::*;
+use leptos_router::*;
+use uuid::Uuid;
+
+use crate::components::header::Header;
+use crate::server_fns::auth::{UserInfo, get_current_user};
+use crate::server_fns::borrowers::{BorrowerLoan, get_borrower_history, get_borrowers};
+
+#[component]
+pub fn BorrowersPage() -> impl IntoView {
+    let user_resource = create_resource(|| (), |_| async move { get_current_user().await });
+
+    view! {
+        <div>
+            <Suspense fallback=move || view! { <div class="container">"Loading..."</div> }>
+                {move || {
+                    user_resource
+                        .get()
+                        .map(|result| match result {
+                            Ok(Some(user_info)) => {
+                                view! { <BorrowerList user_info=user_info/> }.into_view()
+                            }
+                            Ok(None) | Err(_) => {
+                                view! { <Redirect path="/login"/> }.into_view()
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+pub fn BorrowerPage() -> impl IntoView {
+    let user_resource = create_resource(|| (), |_| async move { get_current_user().await });
+
+    view! {
+        <div>
+            <Suspense fallback=move || view! { <div class="container">"Loading..."</div> }>
+                {move || {
+                    user_resource
+                        .get()
+                        .map(|result| match result {
+                            Ok(Some(user_info)) => {
+                                view! { <BorrowerDetail user_info=user_info/> }.into_view()
+                            }
+                            Ok(None) | Err(_) => {
+                                view! { <Redirect path="/login"/> }.into_view()
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn BorrowerList(user_info: UserInfo) -> impl IntoView {
+    let org_id = user_info.organization.id;
+    let borrowers_resource = create_resource(
+        move || org_id,
+        |org_id| async move { get_borrowers(org_id).await },
+    );
+
+    view! {
+        <div>
+            <Header
+                username=user_info.name.clone()
+                org_name=user_info.organization.name.clone()
+                org_id=user_info.organization.id
+                announcement=user_info.settings.announcement.clone()
+                accent_color=user_info.settings.accent_color.clone()
+                logo_url=user_info.logo_url.clone()
+                broadcasts=user_info.broadcasts.clone()
+                read_only=user_info.read_only
+            />
+            <div class="container">
+                <div class="page-header">
+                    <h1>"Borrowers"</h1>
+                </div>
+                <Transition fallback=move || view! { <div class="loading">"Loading..."</div> }>
+                    {move || {
+                        borrowers_resource
+                            .get()
+                            .map(|result| match result {
+                                Ok(borrowers) if borrowers.is_empty() => {
+                                    view! {
+                                        <div class="empty-state">
+                                            <h3>"No borrowers yet"</h3>
+                                            <p>"Borrowers appear here once an item is loaned out."</p>
+                                        </div>
+                                    }
+                                        .into_view()
+                                }
+                                Ok(borrowers) => {
+                                    view! {
+                                        <table class="items-table">
+                                            <thead>
+                                                <tr>
+                                                    <th>"Name"</th>
+                                                    <th>"Outstanding"</th>
+                                                    <th>"Total Loans"</th>
+                                                </tr>
+                                            </thead>
+                                            <tbody>
+                                                {borrowers
+                                                    .into_iter()
+                                                    .map(|b| {
+                                                        view! {
+                                                            <tr>
+                                                                <td>
+                                                                    <a href=format!("/borrowers/{}", b.id)>{b.name}</a>
+                                                                </td>
+                                                                <td>{b.outstanding_count}</td>
+                                                                <td>{b.loan_count}</td>
+                                                            </tr>
+                                                        }
+                                                    })
+                                                    .collect_view()}
+                                            </tbody>
+                                        </table>
+                                    }
+                                        .into_view()
+                                }
+                                Err(e) => {
+                                    view! {
+                                        <div class="error">{format!("Error loading borrowers: {}", e)}</div>
+                                    }
+                                        .into_view()
+                                }
+                            })
+                    }}
+                </Transition>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn BorrowerDetail(user_info: UserInfo) -> impl IntoView {
+    let org_id = user_info.organization.id;
+    let params = use_params_map();
+    let history_resource = create_resource(
+        move || params.with(|p| p.get("id").and_then(|id| Uuid::parse_str(id).ok())),
+        move |borrower_id| async move {
+            match borrower_id {
+                Some(id) => get_borrower_history(org_id, id).await.map(Some),
+                None => Ok(None),
+            }
+        },
+    );
+
+    view! {
+        <div>
+            <Header
+                username=user_info.name.clone()
+                org_name=user_info.organization.name.clone()
+                org_id=user_info.organization.id
+                announcement=user_info.settings.announcement.clone()
+                accent_color=user_info.settings.accent_color.clone()
+                logo_url=user_info.logo_url.clone()
+                broadcasts=user_info.broadcasts.clone()
+                read_only=user_info.read_only
+            />
+            <div class="container">
+                <Transition fallback=move || view! { <div class="loading">"Loading..."</div> }>
+                    {move || {
+                        history_resource
+                            .get()
+                            .map(|result| match result {
+                                Ok(Some(history)) => {
+                                    let average = history
+                                        .average_return_days
+                                        .map(|d| format!("{:.1} days", d))
+                                        .unwrap_or_else(|| "-".to_string());
+                                    view! {
+                                        <div class="page-header">
+                                            <h1>{history.name.clone()}</h1>
+                                            <a href="/borrowers" class="btn btn-secondary">"All Borrowers"</a>
+                                        </div>
+                                        <div class="detail-row">
+                                            <div class="detail-group">
+                                                <span class="detail-label">"Outstanding:"</span>
+                                                <span class="detail-value">{history.outstanding.len()}</span>
+                                            </div>
+                                            <div class="detail-group">
+                                                <span class="detail-label">"Total Loans:"</span>
+                                                <span class="detail-value">{history.loans.len()}</span>
+                                            </div>
+                                            <div class="detail-group">
+                                                <span class="detail-label">"Average Return Time:"</span>
+                                                <span class="detail-value">{average}</span>
+                                            </div>
+                                        </div>
+                                        <h3>"Currently Borrowed"</h3>
+                                        <LoanTable loans=history.outstanding empty="Nothing on loan."/>
+                                        <h3>"History"</h3>
+                                        <LoanTable loans=history.loans empty="No loans recorded."/>
+                                    }
+                                        .into_view()
+                                }
+                                Ok(None) => {
+                                    view! { <div class="error">"Borrower not found"</div> }.into_view()
+                                }
+                                Err(e) => {
+                                    view! {
+                                        <div class="error">{format!("Error loading borrower: {}", e)}</div>
+                                    }
+                                        .into_view()
+                                }
+                            })
+                    }}
+                </Transition>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn LoanTable(loans: Vec<BorrowerLoan>, empty: &'static str) -> impl IntoView {
+    if loans.is_empty() {
+        return view! { <p style="color:#888;font-size:13px;">{empty}</p> }.into_view();
+    }
+
+    let fmt = |d: Option<chrono::NaiveDate>| {
+        d.map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    view! {
+        <table class="items-table">
+            <thead>
+                <tr>
+                    <th class="col-type">"Type"</th>
+                    <th class="col-name">"Item"</th>
+                    <th>"Loaned"</th>
+                    <th>"Due Back"</th>
+                    <th>"Returned"</th>
+                </tr>
+            </thead>
+            <tbody>
+                {loans
+                    .into_iter()
+                    .map(|loan| {
+                        view! {
+                            <tr>
+                                <td class="col-type">{loan.kind_name}</td>
+                                <td class="col-name">{loan.item_name}</td>
+                                <td>{fmt(Some(loan.date_loaned))}</td>
+                                <td>{fmt(loan.date_due_back)}</td>
+                                <td>{fmt(loan.date_returned)}</td>
+                            </tr>
+                        }
+                    })
+                    .collect_view()}
+            </tbody>
+        </table>
+    }
+    .into_view()
+}
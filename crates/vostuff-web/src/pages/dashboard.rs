@@ -0,0 +1,95 @@
+use leptos::*;
+use leptos_router::*;
+
+use crate::components::dashboard_widgets::{ItemStateWidgets, RotationPanel, UsageLists};
+use crate::components::header::Header;
+use crate::server_fns::auth::{UserInfo, get_current_user};
+use crate::server_fns::reports::{get_item_state_summary, get_usage_report};
+
+#[component]
+pub fn DashboardPage() -> impl IntoView {
+    let user_resource = create_resource(|| (), |_| async move { get_current_user().await });
+
+    view! {
+        <div>
+            <Suspense fallback=move || view! { <div class="container">"Loading..."</div> }>
+                {move || {
+                    user_resource
+                        .get()
+                        .map(|result| match result {
+                            Ok(Some(user_info)) => {
+                                view! { <Dashboard user_info=user_info/> }.into_view()
+                            }
+                            Ok(None) | Err(_) => {
+                                view! { <Redirect path="/login"/> }.into_view()
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn Dashboard(user_info: UserInfo) -> impl IntoView {
+    let org_id = user_info.organization.id;
+    let summary_resource = create_resource(
+        move || org_id,
+        |org_id| async move { get_item_state_summary(org_id).await },
+    );
+    let usage_resource = create_resource(
+        move || org_id,
+        |org_id| async move { get_usage_report(org_id).await },
+    );
+
+    view! {
+        <div>
+            <Header
+                username=user_info.name.clone()
+                org_name=user_info.organization.name.clone()
+                org_id=user_info.organization.id
+                announcement=user_info.settings.announcement.clone()
+                accent_color=user_info.settings.accent_color.clone()
+                logo_url=user_info.logo_url.clone()
+                broadcasts=user_info.broadcasts.clone()
+                read_only=user_info.read_only
+            />
+            <div class="container">
+                <div class="page-header">
+                    <h1>"Dashboard"</h1>
+                </div>
+                <Transition fallback=move || view! { <div class="loading">"Loading..."</div> }>
+                    {move || {
+                        summary_resource
+                            .get()
+                            .map(|result| match result {
+                                Ok(summary) => view! { <ItemStateWidgets summary=summary/> }.into_view(),
+                                Err(e) => {
+                                    view! {
+                                        <div class="error">{format!("Error loading dashboard: {}", e)}</div>
+                                    }
+                                        .into_view()
+                                }
+                            })
+                    }}
+                </Transition>
+                <RotationPanel org_id=org_id/>
+                <Transition fallback=move || view! { <div class="loading">"Loading..."</div> }>
+                    {move || {
+                        usage_resource
+                            .get()
+                            .map(|result| match result {
+                                Ok(report) => view! { <UsageLists report=report/> }.into_view(),
+                                Err(e) => {
+                                    view! {
+                                        <div class="error">{format!("Error loading usage: {}", e)}</div>
+                                    }
+                                        .into_view()
+                                }
+                            })
+                    }}
+                </Transition>
+            </div>
+        </div>
+    }
+}
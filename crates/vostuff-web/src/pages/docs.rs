@@ -0,0 +1,522 @@
+use leptos::*;
+use leptos_router::*;
+use uuid::Uuid;
+
+use crate::components::header::Header;
+use crate::components::markdown::render_markdown;
+use crate::server_fns::auth::{UserInfo, get_current_user};
+use crate::server_fns::errors::error_code;
+use crate::server_fns::pages::{
+    OrgPage, create_page, delete_page, get_page, get_page_versions, get_pages, update_page,
+};
+use vostuff_core::models::ErrorCode;
+
+fn format_date(at: chrono::DateTime<chrono::Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M").to_string()
+}
+
+#[component]
+pub fn DocsPage() -> impl IntoView {
+    let user_resource = create_resource(|| (), |_| async move { get_current_user().await });
+
+    view! {
+        <div>
+            <Suspense fallback=move || view! { <div class="container">"Loading..."</div> }>
+                {move || {
+                    user_resource
+                        .get()
+                        .map(|result| match result {
+                            Ok(Some(user_info)) => {
+                                view! { <DocList user_info=user_info/> }.into_view()
+                            }
+                            Ok(None) | Err(_) => {
+                                view! { <Redirect path="/login"/> }.into_view()
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+pub fn DocPage() -> impl IntoView {
+    let user_resource = create_resource(|| (), |_| async move { get_current_user().await });
+
+    view! {
+        <div>
+            <Suspense fallback=move || view! { <div class="container">"Loading..."</div> }>
+                {move || {
+                    user_resource
+                        .get()
+                        .map(|result| match result {
+                            Ok(Some(user_info)) => {
+                                view! { <DocDetail user_info=user_info/> }.into_view()
+                            }
+                            Ok(None) | Err(_) => {
+                                view! { <Redirect path="/login"/> }.into_view()
+                            }
+                        })
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn DocList(user_info: UserInfo) -> impl IntoView {
+    let org_id = user_info.organization.id;
+    let read_only = user_info.read_only;
+    let pages_resource = create_resource(
+        move || org_id,
+        |org_id| async move { get_pages(org_id).await },
+    );
+    let new_title = create_rw_signal(String::new());
+    let error = create_rw_signal::<Option<String>>(None);
+
+    // A new page opens straight away, ready to write
+    let navigate = use_navigate();
+    let create_action = create_action(move |title: &String| {
+        let title = title.clone();
+        async move { create_page(org_id, title, String::new()).await }
+    });
+    create_effect(move |_| {
+        if let Some(result) = create_action.value().get() {
+            match result {
+                Ok(page) => navigate(&format!("/docs/{}?edit=1", page.id), Default::default()),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        }
+    });
+    let submit = move || {
+        let title = new_title.get_untracked().trim().to_string();
+        if !title.is_empty() {
+            error.set(None);
+            create_action.dispatch(title);
+        }
+    };
+
+    view! {
+        <div>
+            <Header
+                username=user_info.name.clone()
+                org_name=user_info.organization.name.clone()
+                org_id=user_info.organization.id
+                announcement=user_info.settings.announcement.clone()
+                accent_color=user_info.settings.accent_color.clone()
+                logo_url=user_info.logo_url.clone()
+                broadcasts=user_info.broadcasts.clone()
+                read_only=user_info.read_only
+            />
+            <div class="container">
+                <div class="page-header">
+                    <h1>"Docs"</h1>
+                    <div class="page-header-actions">
+                        <input
+                            type="text"
+                            class="form-control"
+                            placeholder="New page title"
+                            prop:value=new_title
+                            on:input=move |ev| new_title.set(event_target_value(&ev))
+                            on:keydown=move |ev: web_sys::KeyboardEvent| {
+                                if ev.key() == "Enter" {
+                                    submit();
+                                }
+                            }
+                        />
+                        <button
+                            class="btn btn-primary"
+                            disabled=move || read_only || create_action.pending().get()
+                            on:click=move |_| submit()
+                        >
+                            "New Page"
+                        </button>
+                    </div>
+                </div>
+                {move || error.get().map(|e| view! { <div class="error">{e}</div> })}
+                <Transition fallback=move || view! { <div class="loading">"Loading..."</div> }>
+                    {move || {
+                        pages_resource
+                            .get()
+                            .map(|result| match result {
+                                Ok(pages) if pages.is_empty() => {
+                                    view! {
+                                        <div class="empty-state">
+                                            <h3>"No pages yet"</h3>
+                                            <p>
+                                                "Write down how your collection works: grading rules, shelving maps, naming conventions."
+                                            </p>
+                                        </div>
+                                    }
+                                        .into_view()
+                                }
+                                Ok(pages) => {
+                                    view! {
+                                        <table class="items-table">
+                                            <thead>
+                                                <tr>
+                                                    <th>"Title"</th>
+                                                    <th>"Last Edited"</th>
+                                                    <th>"By"</th>
+                                                </tr>
+                                            </thead>
+                                            <tbody>
+                                                {pages
+                                                    .into_iter()
+                                                    .map(|p| {
+                                                        view! {
+                                                            <tr>
+                                                                <td>
+                                                                    <a href=format!("/docs/{}", p.id)>{p.title}</a>
+                                                                </td>
+                                                                <td>{format_date(p.updated_at)}</td>
+                                                                <td>{p.updated_by_name.unwrap_or_else(|| "-".to_string())}</td>
+                                                            </tr>
+                                                        }
+                                                    })
+                                                    .collect_view()}
+                                            </tbody>
+                                        </table>
+                                    }
+                                        .into_view()
+                                }
+                                Err(e) => {
+                                    view! {
+                                        <div class="error">{format!("Error loading pages: {}", e)}</div>
+                                    }
+                                        .into_view()
+                                }
+                            })
+                    }}
+                </Transition>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn DocDetail(user_info: UserInfo) -> impl IntoView {
+    let org_id = user_info.organization.id;
+    // Pages cannot be changed while the organization is read-only
+    let can_edit = !user_info.read_only;
+    let can_delete = can_edit && user_info.permissions.iter().any(|p| p == "pages:delete");
+    let params = use_params_map();
+    let page_id = move || params.with(|p| p.get("id").and_then(|id| Uuid::parse_str(id).ok()));
+
+    // Bumped after saving so the page and its history reload
+    let (refresh, set_refresh) = create_signal(0u32);
+    let page_resource = create_resource(
+        move || (page_id(), refresh.get()),
+        move |(page_id, _)| async move {
+            match page_id {
+                Some(id) => get_page(org_id, id).await.map(Some),
+                None => Ok(None),
+            }
+        },
+    );
+
+    view! {
+        <div>
+            <Header
+                username=user_info.name.clone()
+                org_name=user_info.organization.name.clone()
+                org_id=user_info.organization.id
+                announcement=user_info.settings.announcement.clone()
+                accent_color=user_info.settings.accent_color.clone()
+                logo_url=user_info.logo_url.clone()
+                broadcasts=user_info.broadcasts.clone()
+                read_only=user_info.read_only
+            />
+            <div class="container">
+                <Transition fallback=move || view! { <div class="loading">"Loading..."</div> }>
+                    {move || {
+                        page_resource
+                            .get()
+                            .map(|result| match result {
+                                Ok(Some(page)) => {
+                                    view! {
+                                        <DocView
+                                            org_id=org_id
+                                            page=page
+                                            can_edit=can_edit
+                                            can_delete=can_delete
+                                            on_saved=Callback::new(move |_| set_refresh.update(|r| *r += 1))
+                                        />
+                                    }
+                                        .into_view()
+                                }
+                                Ok(None) => {
+                                    view! { <div class="error">"Page not found"</div> }.into_view()
+                                }
+                                Err(e) => {
+                                    view! {
+                                        <div class="error">{format!("Error loading page: {}", e)}</div>
+                                    }
+                                        .into_view()
+                                }
+                            })
+                    }}
+                </Transition>
+            </div>
+        </div>
+    }
+}
+
+/// A page, its editor and its history
+#[component]
+fn DocView(
+    org_id: Uuid,
+    page: OrgPage,
+    can_edit: bool,
+    can_delete: bool,
+    on_saved: Callback<()>,
+) -> impl IntoView {
+    let page_id = page.id;
+    let editing =
+        create_rw_signal(can_edit && use_query_map().with_untracked(|q| q.get("edit").is_some()));
+    let title = create_rw_signal(page.title.clone());
+    let body = create_rw_signal(page.body.clone());
+    let error = create_rw_signal::<Option<String>>(None);
+    let show_history = create_rw_signal(false);
+    let confirming_delete = create_rw_signal(false);
+    // Version being looked at in the history, if any
+    let viewing = create_rw_signal::<Option<i32>>(None);
+
+    let save_action = create_action(move |version: &i32| {
+        let version = *version;
+        async move {
+            update_page(
+                org_id,
+                page_id,
+                version,
+                title.get_untracked(),
+                body.get_untracked(),
+            )
+            .await
+        }
+    });
+    create_effect(move |_| {
+        if let Some(result) = save_action.value().get() {
+            match result {
+                Ok(_) => {
+                    editing.set(false);
+                    error.set(None);
+                    on_saved.call(());
+                }
+                Err(e) if error_code(&e) == Some(ErrorCode::PageVersionConflict) => {
+                    error.set(Some(
+                        "Someone else saved this page while you were editing. Copy your changes, then reload to see theirs."
+                            .to_string(),
+                    ));
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        }
+    });
+
+    let navigate = use_navigate();
+    let delete_action =
+        create_action(move |_: &()| async move { delete_page(org_id, page_id).await });
+    create_effect(move |_| {
+        if let Some(result) = delete_action.value().get() {
+            match result {
+                Ok(()) => navigate("/docs", Default::default()),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        }
+    });
+
+    let versions_resource = create_resource(
+        move || show_history.get().then_some(page_id),
+        move |page_id| async move {
+            match page_id {
+                Some(id) => get_page_versions(org_id, id).await,
+                None => Ok(Vec::new()),
+            }
+        },
+    );
+
+    let version = page.version;
+    let original_title = page.title.clone();
+    let original_body = page.body.clone();
+    let rendered = render_markdown(&page.body);
+    let edited_by = format!(
+        "Version {} · {} by {}",
+        page.version,
+        format_date(page.updated_at),
+        page.updated_by_name
+            .clone()
+            .unwrap_or_else(|| "-".to_string())
+    );
+
+    view! {
+        <div class="page-header">
+            <h1>{move || if editing.get() { title.get() } else { page.title.clone() }}</h1>
+            <div class="page-header-actions">
+                <a href="/docs" class="btn btn-secondary">"All Pages"</a>
+                <button class="btn btn-secondary" on:click=move |_| show_history.update(|s| *s = !*s)>
+                    {move || if show_history.get() { "Hide History" } else { "History" }}
+                </button>
+                <Show when=move || can_edit && !editing.get() fallback=|| ()>
+                    <button class="btn btn-primary" on:click=move |_| editing.set(true)>
+                        "Edit"
+                    </button>
+                </Show>
+                <Show when=move || can_delete fallback=|| ()>
+                    <Show
+                        when=move || confirming_delete.get()
+                        fallback=move || {
+                            view! {
+                                <button class="btn btn-danger" on:click=move |_| confirming_delete.set(true)>
+                                    "Delete"
+                                </button>
+                            }
+                        }
+                    >
+                        <span class="delete-confirm-text">"Delete this page and its history?"</span>
+                        <button
+                            class="btn btn-danger"
+                            disabled=move || delete_action.pending().get()
+                            on:click=move |_| delete_action.dispatch(())
+                        >
+                            "Yes, delete"
+                        </button>
+                        <button class="btn btn-secondary" on:click=move |_| confirming_delete.set(false)>
+                            "Cancel"
+                        </button>
+                    </Show>
+                </Show>
+            </div>
+        </div>
+        {move || error.get().map(|e| view! { <div class="error">{e}</div> })}
+        <Show
+            when=move || editing.get()
+            fallback=move || {
+                view! {
+                    <p class="doc-meta">{edited_by.clone()}</p>
+                    <div class="markdown-content doc-body" inner_html=rendered.clone()></div>
+                }
+            }
+        >
+            <div class="doc-editor">
+                <div class="form-group">
+                    <label>"Title"</label>
+                    <input
+                        type="text"
+                        class="form-control"
+                        prop:value=title
+                        on:input=move |ev| title.set(event_target_value(&ev))
+                    />
+                </div>
+                <div class="doc-editor-panes">
+                    <div class="form-group">
+                        <label>"Markdown"</label>
+                        <textarea
+                            class="form-control doc-textarea"
+                            prop:value=body
+                            on:input=move |ev| body.set(event_target_value(&ev))
+                        />
+                    </div>
+                    <div class="form-group">
+                        <label>"Preview"</label>
+                        <div class="markdown-content doc-preview" inner_html=move || render_markdown(&body.get())></div>
+                    </div>
+                </div>
+                <div class="doc-editor-actions">
+                    <button
+                        class="btn btn-secondary"
+                        on:click={
+                            let original_title = original_title.clone();
+                            let original_body = original_body.clone();
+                            move |_| {
+                                title.set(original_title.clone());
+                                body.set(original_body.clone());
+                                error.set(None);
+                                editing.set(false);
+                            }
+                        }
+                    >
+                        "Cancel"
+                    </button>
+                    <button
+                        class="btn btn-primary"
+                        style="width:auto;"
+                        disabled=move || save_action.pending().get()
+                        on:click=move |_| save_action.dispatch(version)
+                    >
+                        "Save"
+                    </button>
+                </div>
+            </div>
+        </Show>
+        <Show when=move || show_history.get() fallback=|| ()>
+            <h3>"History"</h3>
+            <Transition fallback=move || view! { <div class="loading">"Loading..."</div> }>
+                {move || {
+                    versions_resource
+                        .get()
+                        .map(|result| match result {
+                            Ok(versions) => {
+                                versions
+                                    .into_iter()
+                                    .map(|v| {
+                                        let number = v.version;
+                                        let old_body = v.body.clone();
+                                        let old_title = v.title.clone();
+                                        let rendered = render_markdown(&v.body);
+                                        view! {
+                                            <div class="doc-version">
+                                                <div class="doc-version-header">
+                                                    <button
+                                                        class="btn btn-secondary btn-sm"
+                                                        on:click=move |_| {
+                                                            viewing.update(|open| {
+                                                                *open = (*open != Some(number)).then_some(number)
+                                                            })
+                                                        }
+                                                    >
+                                                        {format!("Version {}", v.version)}
+                                                    </button>
+                                                    <span class="doc-meta">
+                                                        {format!(
+                                                            "{} by {}",
+                                                            format_date(v.created_at),
+                                                            v.edited_by_name.unwrap_or_else(|| "-".to_string()),
+                                                        )}
+                                                    </span>
+                                                    <Show when=move || can_edit && number != version fallback=|| ()>
+                                                        <button
+                                                            class="btn btn-secondary btn-sm"
+                                                            title="Load this version into the editor; saving makes it current"
+                                                            on:click={
+                                                                let old_title = old_title.clone();
+                                                                let old_body = old_body.clone();
+                                                                move |_| {
+                                                                    title.set(old_title.clone());
+                                                                    body.set(old_body.clone());
+                                                                    editing.set(true);
+                                                                }
+                                                            }
+                                                        >
+                                                            "Restore"
+                                                        </button>
+                                                    </Show>
+                                                </div>
+                                                <Show when=move || viewing.get() == Some(number) fallback=|| ()>
+                                                    <div class="markdown-content doc-body" inner_html=rendered.clone()></div>
+                                                </Show>
+                                            </div>
+                                        }
+                                    })
+                                    .collect_view()
+                            }
+                            Err(e) => {
+                                view! { <div class="error">{format!("Error loading history: {}", e)}</div> }
+                                    .into_view()
+                            }
+                        })
+                }}
+            </Transition>
+        </Show>
+    }
+}
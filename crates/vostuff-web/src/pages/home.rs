@@ -2,15 +2,25 @@ use leptos::*;
 use leptos_router::*;
 use std::collections::{HashMap, HashSet};
 
+use crate::components::barcode_scanner::BarcodeScanner;
 use crate::components::create_item::CreateItemModal;
+use crate::components::file_download::download_file;
 use crate::components::filter_dropdown::{
     FilterBar, FilterDropdown, FilterOption, FilterSearchInput,
 };
 use crate::components::header::Header;
+use crate::components::item_suggestions::ItemSuggestionsModal;
 use crate::components::items_table::ItemsTable;
 use crate::components::pagination::Pagination;
+use crate::components::paste_import::PasteImportModal;
+use crate::components::photo_import::PhotoImportModal;
+use crate::components::print_labels::PrintLabelsModal;
+use crate::components::save_warnings::{SaveWarnings, SaveWarningsNotice};
 use crate::server_fns::auth::{UserInfo, get_current_user};
-use crate::server_fns::items::{ItemFilters, ItemState, get_items, get_locations};
+use crate::server_fns::items::{
+    FacetCount, ItemFacets, ItemFilters, ItemState, encode_query_value, enrich_items, export_items,
+    get_collections, get_item_facets, get_items, get_locations,
+};
 use crate::server_fns::kinds::get_kinds;
 
 #[component]
@@ -42,21 +52,139 @@ pub fn HomePage() -> impl IntoView {
 #[component]
 fn AuthenticatedHome(user_info: UserInfo) -> impl IntoView {
     let org_id = user_info.organization.id;
+    let read_only = user_info.read_only;
 
     // Modal visibility
     let (show_create, set_show_create) = create_signal(false);
+    let (show_scanner, set_show_scanner) = create_signal(false);
+    let (show_paste, set_show_paste) = create_signal(false);
+    let (show_labels, set_show_labels) = create_signal(false);
+    let (show_photos, set_show_photos) = create_signal(false);
+    let (show_suggestions, set_show_suggestions) = create_signal(false);
+    // Barcode handed to the create modal when a scan matches nothing
+    let (new_item_barcode, set_new_item_barcode) = create_signal(String::new());
 
     // Pagination state
     let (page, set_page) = create_signal(1i64);
     let (per_page, set_per_page) = create_signal(25i64);
 
-    // Filter state
-    let (selected_types, set_selected_types) = create_signal::<HashSet<String>>(HashSet::new());
-    let (selected_states, set_selected_states) = create_signal::<HashSet<String>>(HashSet::new());
+    // Filter state, starting from the URL query so filtered links (e.g. from the
+    // dashboard) open with their filters applied
+    let query_map = use_query_map();
+    let query = query_map.get_untracked();
+
+    let (selected_types, set_selected_types) = create_signal(query_set(&query, "kind"));
+    let (selected_states, set_selected_states) = create_signal(query_set(&query, "state"));
     let (selected_locations, set_selected_locations) =
-        create_signal::<HashSet<String>>(HashSet::new());
-    let (search_input, set_search_input) = create_signal(String::new());
-    let (search_text, set_search_text) = create_signal(String::new());
+        create_signal(query_set(&query, "location_id"));
+    let (collection_filter, set_collection_filter) =
+        create_signal(query_uuid(&query, "collection_id"));
+    let (search_input, set_search_input) = create_signal(query_str(&query, "search"));
+    let (search_text, set_search_text) = create_signal(query_str(&query, "search"));
+    let (barcode_filter, set_barcode_filter) = create_signal(query_str(&query, "barcode"));
+    let (disposed_since, set_disposed_since) = create_signal(query_date(&query, "disposed_since"));
+
+    // Expanded row state (owned here so it persists across refetches)
+    let (expanded_row, set_expanded_row) = create_signal(query_uuid(&query, "item"));
+
+    // The filters as the items page query string
+    let url_query = move || {
+        let sorted = |set: HashSet<String>| {
+            let mut values: Vec<String> = set.into_iter().collect();
+            values.sort();
+            values.join(",")
+        };
+        [
+            ("kind", sorted(selected_types.get())),
+            ("state", sorted(selected_states.get())),
+            ("location_id", sorted(selected_locations.get())),
+            (
+                "collection_id",
+                collection_filter
+                    .get()
+                    .map(|id| id.to_string())
+                    .unwrap_or_default(),
+            ),
+            ("search", search_text.get()),
+            ("barcode", barcode_filter.get()),
+            (
+                "disposed_since",
+                disposed_since
+                    .get()
+                    .map(|d| d.to_string())
+                    .unwrap_or_default(),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, v)| !v.is_empty())
+        .map(|(k, v)| format!("{}={}", k, encode_query_value(&v)))
+        .collect::<Vec<_>>()
+        .join("&")
+    };
+    // The query string last written to the URL, to tell our own updates apart
+    // from links followed while the page is open
+    let written_query = store_value(String::new());
+    let navigate = use_navigate();
+    let replace_query = move |query: &str| {
+        written_query.set_value(query.to_string());
+        let url = if query.is_empty() {
+            "/".to_string()
+        } else {
+            format!("/?{}", query)
+        };
+        navigate(
+            &url,
+            NavigateOptions {
+                replace: true,
+                scroll: false,
+                ..Default::default()
+            },
+        );
+    };
+
+    // Keep the URL in step with the filters so the view can be bookmarked or shared
+    let replace = replace_query.clone();
+    create_effect(move |prev: Option<String>| {
+        let query = url_query();
+
+        // The first run reflects the URL we were opened with
+        if prev.as_ref().is_some_and(|p| *p != query) {
+            replace(&query);
+        }
+        query
+    });
+
+    // Links followed while the page is open (from the command palette, or the
+    // browser's back button) replace the filters. `new`, `scan` and `item`
+    // open the create modal, the scanner or an item, and are then dropped
+    // from the URL so a reload doesn't repeat them.
+    let location = use_location();
+    create_effect(move |opened: Option<()>| {
+        let search = location.search.get();
+        if opened.is_some() && search.trim_start_matches('?') == written_query.get_value() {
+            return;
+        }
+        let query = query_map.get_untracked();
+        if opened.is_some() {
+            set_selected_types.set(query_set(&query, "kind"));
+            set_selected_states.set(query_set(&query, "state"));
+            set_selected_locations.set(query_set(&query, "location_id"));
+            set_collection_filter.set(query_uuid(&query, "collection_id"));
+            set_search_input.set(query_str(&query, "search"));
+            set_search_text.set(query_str(&query, "search"));
+            set_barcode_filter.set(query_str(&query, "barcode"));
+            set_disposed_since.set(query_date(&query, "disposed_since"));
+            set_expanded_row.set(query_uuid(&query, "item"));
+        }
+        if ["new", "scan", "item"]
+            .iter()
+            .any(|key| query.get(key).is_some())
+        {
+            set_show_create.set(query.get("new").is_some());
+            set_show_scanner.set(query.get("scan").is_some());
+            replace_query(&untrack(url_query));
+        }
+    });
 
     // Sort state
     let (sort_by, set_sort_by) = create_signal("name".to_string());
@@ -65,16 +193,47 @@ fn AuthenticatedHome(user_info: UserInfo) -> impl IntoView {
     // Refresh counter to trigger items refetch after edits
     let (refresh_counter, set_refresh_counter) = create_signal(0u32);
 
-    // Expanded row state (owned here so it persists across refetches)
-    let (expanded_row, set_expanded_row) = create_signal::<Option<uuid::Uuid>>(None);
+    // Warnings from the last save, shown above the list
+    let save_warnings = SaveWarnings::provide();
+
+    // The list filters as one value. Memoized so the resources below refetch
+    // only when the filters actually change, not on every signal write.
+    let filters = create_memo(move |_| {
+        let sorted = |set: HashSet<String>| {
+            let mut values: Vec<String> = set.into_iter().collect();
+            values.sort();
+            values
+        };
+        let location_ids = selected_locations
+            .get()
+            .iter()
+            .filter_map(|s| uuid::Uuid::parse_str(s).ok())
+            .collect();
+        let search = search_text.get();
+        let barcode = barcode_filter.get();
+        ItemFilters {
+            kinds: sorted(selected_types.get()),
+            states: sorted(selected_states.get()),
+            location_ids,
+            collection_ids: collection_filter.get().into_iter().collect(),
+            search_query: (!search.is_empty()).then_some(search),
+            barcode: (!barcode.is_empty()).then_some(barcode),
+            disposed_since: disposed_since.get(),
+            ..Default::default()
+        }
+    });
+
+    // Rows checked for bulk actions; kept across pages, cleared with the filters
+    let selected_items = create_rw_signal(HashSet::<uuid::Uuid>::new());
 
-    // Reset to page 1 when filters change
+    // Reset to page 1 when filters change; skipped when already there so the
+    // list isn't fetched twice
     create_effect(move |_| {
-        let _ = selected_types.get();
-        let _ = selected_states.get();
-        let _ = selected_locations.get();
-        let _ = search_text.get();
-        set_page.set(1);
+        filters.track();
+        selected_items.set(HashSet::new());
+        if page.get_untracked() != 1 {
+            set_page.set(1);
+        }
     });
 
     // Fetch locations once (they don't paginate)
@@ -83,84 +242,160 @@ fn AuthenticatedHome(user_info: UserInfo) -> impl IntoView {
         |org_id| async move { get_locations(org_id).await },
     );
 
+    // Name for the collection filter chip
+    let collection_name = create_resource(
+        move || collection_filter.get(),
+        move |collection_id| async move {
+            let id = collection_id?;
+            let collections = get_collections(org_id).await.ok()?;
+            collections.into_iter().find(|c| c.id == id).map(|c| c.name)
+        },
+    );
+
     // Fetch kinds once for the type filter dropdown
     let kinds_resource = create_resource(
         move || org_id,
         |org_id| async move { get_kinds(org_id).await },
     );
 
-    // Fetch items with pagination and filters
-    // Convert HashSets to sorted Vecs for stable comparison in resource source
+    // Fetch items with pagination and filters. Each change of source starts a
+    // new load and the resource only applies the latest one, so a slow response
+    // for earlier filters is dropped rather than replacing newer results.
     let items_resource = create_resource(
         move || {
-            let mut types: Vec<String> = selected_types.get().into_iter().collect();
-            types.sort();
-            let mut states: Vec<String> = selected_states.get().into_iter().collect();
-            states.sort();
-            let mut locations: Vec<String> = selected_locations.get().into_iter().collect();
-            locations.sort();
-            let search = search_text.get();
-            let sb = sort_by.get();
-            let so = sort_order.get();
-            let rc = refresh_counter.get();
             (
                 org_id,
                 page.get(),
                 per_page.get(),
-                types,
-                states,
-                locations,
-                search,
-                sb,
-                so,
-                rc,
+                filters.get(),
+                sort_by.get(),
+                sort_order.get(),
+                refresh_counter.get(),
             )
         },
-        move |(org_id, page, per_page, types, states, locations, search, sb, so, _rc)| {
-            // Build filters from the source values
-            let location_ids: Vec<uuid::Uuid> = locations
-                .iter()
-                .filter_map(|s| uuid::Uuid::parse_str(s).ok())
-                .collect();
-
-            let search_query = if search.is_empty() {
-                None
-            } else {
-                Some(search)
-            };
-
-            let sort_by_opt = Some(sb);
-            let sort_order_opt = Some(so);
-
-            let filters = if types.is_empty()
-                && states.is_empty()
-                && location_ids.is_empty()
-                && search_query.is_none()
-                && sort_by_opt.as_deref() == Some("name")
-                && sort_order_opt.as_deref() == Some("asc")
-            {
+        move |(org_id, page, per_page, filters, sb, so, _rc)| {
+            let filters = if filters == ItemFilters::default() && sb == "name" && so == "asc" {
                 None
             } else {
                 Some(ItemFilters {
-                    kinds: types,
-                    states,
-                    location_ids,
-                    search_query,
-                    sort_by: sort_by_opt,
-                    sort_order: sort_order_opt,
+                    sort_by: Some(sb),
+                    sort_order: Some(so),
+                    ..filters
                 })
             };
-
             async move { get_items(org_id, page, per_page, filters).await }
         },
     );
 
+    // "Export view" downloads every item in the current filters and sort as CSV
+    let (exporting, set_exporting) = create_signal(false);
+    let export_view = move |_| {
+        let filters = ItemFilters {
+            sort_by: Some(sort_by.get_untracked()),
+            sort_order: Some(sort_order.get_untracked()),
+            ..filters.get_untracked()
+        };
+        set_exporting.set(true);
+        spawn_local(async move {
+            match export_items(org_id, filters).await {
+                Ok(file) => download_file(&file.filename, "text/csv", &file.csv),
+                Err(e) => tracing::error!("Export failed: {}", e),
+            }
+            set_exporting.set(false);
+        });
+    };
+
+    // "Enrich selected" queues a background lookup of details for the checked
+    // items; what it finds waits in the suggestions review queue
+    let (enriching, set_enriching) = create_signal(false);
+    let (enrich_notice, set_enrich_notice) = create_signal::<Option<String>>(None);
+    let enrich_selected = move |_| {
+        let item_ids: Vec<uuid::Uuid> = selected_items.get_untracked().into_iter().collect();
+        set_enriching.set(true);
+        spawn_local(async move {
+            let notice = match enrich_items(org_id, item_ids).await {
+                Ok(result) if result.queued.is_empty() => {
+                    "None of the selected items need details: each lacks a barcode, \
+                     is complete or awaits review"
+                        .to_string()
+                }
+                Ok(result) => {
+                    selected_items.set(HashSet::new());
+                    format!(
+                        "Looking up {} item(s){}; review what is found under Suggestions",
+                        result.queued.len(),
+                        if result.skipped.is_empty() {
+                            String::new()
+                        } else {
+                            format!(", {} skipped", result.skipped.len())
+                        }
+                    )
+                }
+                Err(e) => format!("Lookup failed: {}", e),
+            };
+            set_enrich_notice.set(Some(notice));
+            set_enriching.set(false);
+        });
+    };
+
+    // Counts for the filter dropdowns, under the same filters as the list
+    let facets_resource = create_resource(
+        move || (org_id, filters.get(), refresh_counter.get()),
+        |(org_id, filters, _rc)| async move { get_item_facets(org_id, filters).await },
+    );
+    let facet_counts = move |facet: fn(&ItemFacets) -> &Vec<FacetCount>| {
+        Signal::derive(move || {
+            facets_resource
+                .get()
+                .and_then(Result::ok)
+                .map(|f| ItemFacets::counts(facet(&f)))
+                .unwrap_or_default()
+        })
+    };
+    let kind_counts = facet_counts(|f| &f.kinds);
+    let state_counts = facet_counts(|f| &f.states);
+    let location_counts = facet_counts(|f| &f.locations);
+
+    // A scanned barcode filters the list to its matches (expanding a single
+    // match); an unknown barcode opens the create modal with it prefilled.
+    // Lookups run outside the resource, so a generation counter drops the
+    // answer to a scan that has since been superseded by another.
+    let scan_generation = store_value(0u64);
+    let on_scanned = Callback::new(move |code: String| {
+        set_show_scanner.set(false);
+        scan_generation.update_value(|g| *g += 1);
+        let generation = scan_generation.get_value();
+        spawn_local(async move {
+            let filters = ItemFilters {
+                barcode: Some(code.clone()),
+                ..Default::default()
+            };
+            let result = get_items(org_id, 1, 2, Some(filters)).await;
+            if scan_generation.get_value() != generation {
+                return;
+            }
+            match result {
+                Ok(found) if found.total == 0 => {
+                    set_new_item_barcode.set(code);
+                    set_show_create.set(true);
+                }
+                Ok(found) => {
+                    if found.total == 1 {
+                        set_expanded_row.set(found.items.first().map(|i| i.id));
+                    }
+                    set_barcode_filter.set(code);
+                }
+                Err(e) => tracing::error!("Barcode lookup failed: {}", e),
+            }
+        });
+    });
+
     // Build filter options for states (stored for reuse in reactive context)
     let state_options = store_value(
-        ItemState::all()
-            .into_iter()
+        ItemState::ALL
+            .iter()
             .map(|s| FilterOption {
-                value: s.api_value().to_string(),
+                value: s.as_str().to_string(),
                 label: s.display_name().to_string(),
             })
             .collect::<Vec<_>>(),
@@ -171,26 +406,139 @@ fn AuthenticatedHome(user_info: UserInfo) -> impl IntoView {
             <Header
                 username=user_info.name.clone()
                 org_name=user_info.organization.name.clone()
+                org_id=user_info.organization.id
+                announcement=user_info.settings.announcement.clone()
+                accent_color=user_info.settings.accent_color.clone()
+                logo_url=user_info.logo_url.clone()
+                broadcasts=user_info.broadcasts.clone()
+                read_only=user_info.read_only
             />
             <CreateItemModal
                 org_id=org_id
                 show=show_create
-                on_close=Callback::new(move |_| set_show_create.set(false))
+                on_close=Callback::new(move |_| {
+                    set_show_create.set(false);
+                    set_new_item_barcode.set(String::new());
+                })
                 on_created=Callback::new(move |_| {
                     set_show_create.set(false);
+                    set_new_item_barcode.set(String::new());
+                    set_refresh_counter.update(|c| *c += 1);
+                })
+                initial_barcode=new_item_barcode
+            />
+            <PrintLabelsModal
+                org_id=org_id
+                show=show_labels
+                item_ids=Signal::derive(move || selected_items.get().into_iter().collect())
+                on_close=Callback::new(move |_| set_show_labels.set(false))
+            />
+            <PasteImportModal
+                org_id=org_id
+                show=show_paste
+                on_close=Callback::new(move |_| set_show_paste.set(false))
+                on_created=Callback::new(move |_| {
+                    set_show_paste.set(false);
+                    set_refresh_counter.update(|c| *c += 1);
+                })
+            />
+            <PhotoImportModal
+                org_id=org_id
+                show=show_photos
+                on_close=Callback::new(move |_| {
+                    set_show_photos.set(false);
                     set_refresh_counter.update(|c| *c += 1);
                 })
             />
+            <ItemSuggestionsModal
+                org_id=org_id
+                show=show_suggestions
+                on_close=Callback::new(move |_| set_show_suggestions.set(false))
+                on_applied=Callback::new(move |_| set_refresh_counter.update(|c| *c += 1))
+                read_only=read_only
+            />
+            <BarcodeScanner
+                show=show_scanner
+                on_close=Callback::new(move |_| set_show_scanner.set(false))
+                on_scanned=on_scanned
+            />
             <div class="container">
                 <div class="page-header">
                     <h1>"Items"</h1>
-                    <button
-                        class="btn btn-primary"
-                        on:click=move |_| set_show_create.set(true)
-                    >
-                        "Add Item"
-                    </button>
+                    <div class="page-header-actions">
+                        <button
+                            class="btn btn-secondary"
+                            on:click=move |_| set_show_scanner.set(true)
+                        >
+                            "Scan"
+                        </button>
+                        <button
+                            class="btn btn-secondary"
+                            title="Download the items in this view as CSV"
+                            prop:disabled=move || exporting.get()
+                            on:click=export_view
+                        >
+                            {move || if exporting.get() { "Exporting..." } else { "Export view" }}
+                        </button>
+                        <Show when=move || !selected_items.get().is_empty() fallback=|| ()>
+                            <button
+                                class="btn btn-secondary"
+                                title="Look up missing details for the checked items by barcode"
+                                prop:disabled=move || read_only || enriching.get()
+                                on:click=enrich_selected
+                            >
+                                {move || format!("Enrich selected ({})", selected_items.get().len())}
+                            </button>
+                            <button
+                                class="btn btn-secondary"
+                                title="Download a PDF of labels for the checked items"
+                                on:click=move |_| set_show_labels.set(true)
+                            >
+                                {move || format!("Print labels ({})", selected_items.get().len())}
+                            </button>
+                        </Show>
+                        <button
+                            class="btn btn-secondary"
+                            on:click=move |_| set_show_suggestions.set(true)
+                        >
+                            "Suggestions"
+                        </button>
+                        <button
+                            class="btn btn-secondary"
+                            disabled=read_only
+                            on:click=move |_| set_show_paste.set(true)
+                        >
+                            "Paste"
+                        </button>
+                        <button
+                            class="btn btn-secondary"
+                            disabled=read_only
+                            on:click=move |_| set_show_photos.set(true)
+                        >
+                            "Photos"
+                        </button>
+                        <button
+                            class="btn btn-primary"
+                            disabled=read_only
+                            on:click=move |_| set_show_create.set(true)
+                        >
+                            "Add Item"
+                        </button>
+                    </div>
                 </div>
+                <SaveWarningsNotice warnings=save_warnings/>
+                <Show when=move || enrich_notice.get().is_some() fallback=|| ()>
+                    <div class="filter-chip">
+                        {move || enrich_notice.get().unwrap_or_default()}
+                        <button
+                            class="filter-chip-remove"
+                            title="Dismiss"
+                            on:click=move |_| set_enrich_notice.set(None)
+                        >
+                            "×"
+                        </button>
+                    </div>
+                </Show>
 
                 <Transition fallback=move || {
                     view! { <div class="loading">"Loading..."</div> }
@@ -221,10 +569,7 @@ fn AuthenticatedHome(user_info: UserInfo) -> impl IntoView {
                                         label: k.display_name.clone().unwrap_or_else(|| k.name.clone()),
                                     })
                                     .collect();
-                                let has_filters = !selected_types.get().is_empty()
-                                    || !selected_states.get().is_empty()
-                                    || !selected_locations.get().is_empty()
-                                    || !search_text.get().is_empty();
+                                let has_filters = filters.get() != ItemFilters::default();
                                 view! {
                                     <FilterBar>
                                         <FilterSearchInput
@@ -237,19 +582,68 @@ fn AuthenticatedHome(user_info: UserInfo) -> impl IntoView {
                                             options=type_options
                                             selected=selected_types
                                             set_selected=set_selected_types
+                                            counts=kind_counts
                                         />
                                         <FilterDropdown
                                             label="State"
                                             options=state_options.get_value()
                                             selected=selected_states
                                             set_selected=set_selected_states
+                                            counts=state_counts
                                         />
                                         <FilterDropdown
                                             label="Location"
                                             options=location_options
                                             selected=selected_locations
                                             set_selected=set_selected_locations
+                                            counts=location_counts
                                         />
+                                        <Show when=move || !barcode_filter.get().is_empty() fallback=|| ()>
+                                            <span class="filter-chip">
+                                                {move || format!("Barcode: {}", barcode_filter.get())}
+                                                <button
+                                                    class="filter-chip-remove"
+                                                    title="Clear barcode filter"
+                                                    on:click=move |_| set_barcode_filter.set(String::new())
+                                                >
+                                                    "×"
+                                                </button>
+                                            </span>
+                                        </Show>
+                                        <Show when=move || collection_filter.get().is_some() fallback=|| ()>
+                                            <span class="filter-chip">
+                                                {move || {
+                                                    format!(
+                                                        "Collection: {}",
+                                                        collection_name.get().flatten().unwrap_or_default(),
+                                                    )
+                                                }}
+                                                <button
+                                                    class="filter-chip-remove"
+                                                    title="Clear collection filter"
+                                                    on:click=move |_| set_collection_filter.set(None)
+                                                >
+                                                    "×"
+                                                </button>
+                                            </span>
+                                        </Show>
+                                        <Show when=move || disposed_since.get().is_some() fallback=|| ()>
+                                            <span class="filter-chip">
+                                                {move || {
+                                                    format!(
+                                                        "Disposed since: {}",
+                                                        disposed_since.get().map(|d| d.to_string()).unwrap_or_default(),
+                                                    )
+                                                }}
+                                                <button
+                                                    class="filter-chip-remove"
+                                                    title="Clear disposal date filter"
+                                                    on:click=move |_| set_disposed_since.set(None)
+                                                >
+                                                    "×"
+                                                </button>
+                                            </span>
+                                        </Show>
                                         <Show when=move || has_filters fallback=|| ()>
                                             <button
                                                 class="filter-clear-btn"
@@ -257,8 +651,11 @@ fn AuthenticatedHome(user_info: UserInfo) -> impl IntoView {
                                                     set_selected_types.set(std::collections::HashSet::new());
                                                     set_selected_states.set(std::collections::HashSet::new());
                                                     set_selected_locations.set(std::collections::HashSet::new());
+                                                    set_collection_filter.set(None);
                                                     set_search_input.set(String::new());
                                                     set_search_text.set(String::new());
+                                                    set_barcode_filter.set(String::new());
+                                                    set_disposed_since.set(None);
                                                 }
                                             >
                                                 "Clear Filters"
@@ -295,6 +692,8 @@ fn AuthenticatedHome(user_info: UserInfo) -> impl IntoView {
                                                 expanded_row=expanded_row
                                                 set_expanded_row=set_expanded_row
                                                 org_id=org_id
+                                                read_only=read_only
+                                                selected=selected_items
                                             />
                                             <Pagination
                                                 current_page=page
@@ -326,3 +725,31 @@ fn AuthenticatedHome(user_info: UserInfo) -> impl IntoView {
         </div>
     }
 }
+
+// ── URL query values ──────────────────────────────────────────────────────────
+
+fn query_set(query: &ParamsMap, key: &str) -> HashSet<String> {
+    query
+        .get(key)
+        .map(|v| {
+            v.split(',')
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn query_str(query: &ParamsMap, key: &str) -> String {
+    query.get(key).cloned().unwrap_or_default()
+}
+
+fn query_uuid(query: &ParamsMap, key: &str) -> Option<uuid::Uuid> {
+    query.get(key).and_then(|v| uuid::Uuid::parse_str(v).ok())
+}
+
+fn query_date(query: &ParamsMap, key: &str) -> Option<chrono::NaiveDate> {
+    query
+        .get(key)
+        .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+}
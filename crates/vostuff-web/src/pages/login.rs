@@ -3,14 +3,20 @@ use leptos_router::*;
 use uuid::Uuid;
 
 use crate::server_fns::auth::{
-    LoginResponse, OrgSelectionResponse, OrganizationWithRoles, login, select_organization,
+    LoginOutcome, LoginResponse, OrgSelectionResponse, OrganizationWithRoles, TermsToAccept,
+    accept_terms, complete_oidc_login, get_oidc_provider, login, select_organization,
+    start_oidc_login,
 };
 
 #[derive(Clone, Debug)]
 enum LoginState {
     Initial,
     SelectingOrg(OrgSelectionResponse),
-    Success(LoginResponse),
+    /// Password was reset by an administrator; choose a new one
+    ChangingPassword(Option<String>),
+    /// The instance's terms of service must be accepted first
+    AcceptingTerms(TermsToAccept, Option<String>),
+    Success(#[allow(dead_code)] Box<LoginResponse>),
     Error(String),
 }
 
@@ -18,34 +24,105 @@ enum LoginState {
 pub fn LoginPage() -> impl IntoView {
     let (identity, set_identity) = create_signal(String::new());
     let (password, set_password) = create_signal(String::new());
+    let (new_password, set_new_password) = create_signal(String::new());
+    let (confirm_password, set_confirm_password) = create_signal(String::new());
     let (login_state, set_login_state) = create_signal(LoginState::Initial);
     let (is_loading, set_is_loading) = create_signal(false);
+    let (terms_agreed, set_terms_agreed) = create_signal(false);
 
     let navigate = use_navigate();
     let nav1 = navigate.clone();
     let nav2 = navigate.clone();
+    let nav3 = navigate.clone();
+    let nav4 = navigate.clone();
 
-    // Handle login form submission
-    let handle_login = create_action(move |_: &()| {
+    // Handle login form submission; the new password is only sent when
+    // completing a required password change
+    let handle_login = create_action(move |new_password_val: &Option<String>| {
         let identity_val = identity.get();
         let password_val = password.get();
+        let new_password_val = new_password_val.clone();
+        let changing_password = new_password_val.is_some();
         let nav = nav1.clone();
 
         async move {
             set_is_loading.set(true);
 
-            match login(identity_val, password_val, None).await {
-                Ok(Ok(login_resp)) => {
+            match login(identity_val, password_val, None, new_password_val).await {
+                Ok(LoginOutcome::LoggedIn(login_resp)) => {
                     // Direct login success - redirect to home
                     set_login_state.set(LoginState::Success(login_resp));
                     set_is_loading.set(false);
                     nav("/", NavigateOptions::default());
                 }
-                Ok(Err(org_selection)) => {
+                Ok(LoginOutcome::SelectOrg(org_selection)) => {
                     // Need to select organization
                     set_login_state.set(LoginState::SelectingOrg(org_selection));
                     set_is_loading.set(false);
                 }
+                Ok(LoginOutcome::PasswordChangeRequired) => {
+                    set_login_state.set(LoginState::ChangingPassword(None));
+                    set_is_loading.set(false);
+                }
+                Ok(LoginOutcome::TermsAcceptanceRequired(terms)) => {
+                    set_login_state.set(LoginState::AcceptingTerms(terms, None));
+                    set_is_loading.set(false);
+                }
+                Err(e) if changing_password => {
+                    set_login_state.set(LoginState::ChangingPassword(Some(e.to_string())));
+                    set_is_loading.set(false);
+                }
+                Err(e) => {
+                    set_login_state.set(LoginState::Error(e.to_string()));
+                    set_is_loading.set(false);
+                }
+            }
+        }
+    });
+
+    // Single sign-on through an identity provider, when the API has one
+    let oidc_provider = create_resource(|| (), |_| get_oidc_provider());
+
+    let handle_oidc_start = create_action(move |_: &()| async move {
+        set_is_loading.set(true);
+        match start_oidc_login().await {
+            Ok(url) => {
+                // Leave for the provider; it redirects back here with a code
+                let _ = window().location().set_href(&url);
+            }
+            Err(e) => {
+                set_login_state.set(LoginState::Error(e.to_string()));
+                set_is_loading.set(false);
+            }
+        }
+    });
+
+    let handle_oidc_complete = create_action(move |(code, state): &(String, String)| {
+        let code = code.clone();
+        let state = state.clone();
+        let nav = nav3.clone();
+
+        async move {
+            set_is_loading.set(true);
+
+            match complete_oidc_login(code, state).await {
+                Ok(LoginOutcome::LoggedIn(login_resp)) => {
+                    set_login_state.set(LoginState::Success(login_resp));
+                    set_is_loading.set(false);
+                    nav("/", NavigateOptions::default());
+                }
+                Ok(LoginOutcome::SelectOrg(org_selection)) => {
+                    set_login_state.set(LoginState::SelectingOrg(org_selection));
+                    set_is_loading.set(false);
+                }
+                // Only password logins can need a password change
+                Ok(LoginOutcome::PasswordChangeRequired) => {
+                    set_is_loading.set(false);
+                }
+                Ok(LoginOutcome::TermsAcceptanceRequired(terms)) => {
+                    set_login_state.set(LoginState::AcceptingTerms(terms, None));
+                    set_is_loading.set(false);
+                }
                 Err(e) => {
                     set_login_state.set(LoginState::Error(e.to_string()));
                     set_is_loading.set(false);
@@ -54,6 +131,55 @@ pub fn LoginPage() -> impl IntoView {
         }
     });
 
+    // Returning from the identity provider: finish logging in, or show why not
+    let query = use_query_map();
+    create_effect(move |done: Option<()>| {
+        if done.is_some() {
+            return;
+        }
+        let query = query.get_untracked();
+        if let Some(error) = query.get("error") {
+            let detail = query.get("error_description").unwrap_or(error);
+            set_login_state.set(LoginState::Error(format!(
+                "Single sign-on failed: {}",
+                detail
+            )));
+        } else if let (Some(code), Some(state)) = (query.get("code"), query.get("state")) {
+            handle_oidc_complete.dispatch((code.clone(), state.clone()));
+        }
+    });
+
+    // Accepting the terms carries on with the login that asked for them
+    let handle_accept_terms = create_action(move |terms: &TermsToAccept| {
+        let terms = terms.clone();
+        let nav = nav4.clone();
+
+        async move {
+            set_is_loading.set(true);
+
+            match accept_terms(terms.follow_on_token.clone(), terms.version.clone()).await {
+                Ok(LoginOutcome::LoggedIn(login_resp)) => {
+                    set_login_state.set(LoginState::Success(login_resp));
+                    set_is_loading.set(false);
+                    nav("/", NavigateOptions::default());
+                }
+                Ok(LoginOutcome::SelectOrg(org_selection)) => {
+                    set_login_state.set(LoginState::SelectingOrg(org_selection));
+                    set_is_loading.set(false);
+                }
+                // Asked for before the terms, never after
+                Ok(LoginOutcome::PasswordChangeRequired)
+                | Ok(LoginOutcome::TermsAcceptanceRequired(_)) => {
+                    set_is_loading.set(false);
+                }
+                Err(e) => {
+                    set_login_state.set(LoginState::AcceptingTerms(terms, Some(e.to_string())));
+                    set_is_loading.set(false);
+                }
+            }
+        }
+    });
+
     // Handle organization selection
     let handle_org_select = create_action(move |(follow_on_token, org_id): &(String, Uuid)| {
         let token = follow_on_token.clone();
@@ -65,7 +191,7 @@ pub fn LoginPage() -> impl IntoView {
 
             match select_organization(token, org).await {
                 Ok(login_resp) => {
-                    set_login_state.set(LoginState::Success(login_resp));
+                    set_login_state.set(LoginState::Success(Box::new(login_resp)));
                     set_is_loading.set(false);
                     nav("/", NavigateOptions::default());
                 }
@@ -95,7 +221,7 @@ pub fn LoginPage() -> impl IntoView {
 
                             <form on:submit=move |ev| {
                                 ev.prevent_default();
-                                handle_login.dispatch(());
+                                handle_login.dispatch(None);
                             }>
                                 <div class="form-group">
                                     <label class="form-label">"Email"</label>
@@ -135,6 +261,152 @@ pub fn LoginPage() -> impl IntoView {
                                     {move || if is_loading.get() { "Logging in..." } else { "Login" }}
                                 </button>
                             </form>
+
+                            <Suspense fallback=|| ()>
+                                {move || {
+                                    oidc_provider
+                                        .get()
+                                        .and_then(|r| r.ok())
+                                        .flatten()
+                                        .map(|name| {
+                                            view! {
+                                                <div class="login-divider">"or"</div>
+                                                <button
+                                                    type="button"
+                                                    class="btn btn-secondary btn-sso"
+                                                    disabled=move || is_loading.get()
+                                                    on:click=move |_| handle_oidc_start.dispatch(())
+                                                >
+                                                    {format!("Sign in with {}", name)}
+                                                </button>
+                                            }
+                                        })
+                                }}
+
+                            </Suspense>
+                        </div>
+                    }
+                        .into_view()
+                }
+                LoginState::ChangingPassword(err) => {
+                    view! {
+                        <div class="form">
+                            <h1 class="form-title">"Choose a New Password"</h1>
+                            <p class="text-center mb-16">
+                                "Your password was reset by an administrator. Choose a new password to continue."
+                            </p>
+
+                            {err.map(|e| view! { <div class="error">{e}</div> })}
+
+                            <form on:submit=move |ev| {
+                                ev.prevent_default();
+                                if new_password.get() != confirm_password.get() {
+                                    set_login_state
+                                        .set(
+                                            LoginState::ChangingPassword(
+                                                Some("Passwords do not match".to_string()),
+                                            ),
+                                        );
+                                    return;
+                                }
+                                handle_login.dispatch(Some(new_password.get()));
+                            }>
+                                <div class="form-group">
+                                    <label class="form-label">"New password"</label>
+                                    <input
+                                        type="password"
+                                        class="form-input"
+                                        prop:value=new_password
+                                        on:input=move |ev| {
+                                            set_new_password.set(event_target_value(&ev));
+                                        }
+
+                                        required
+                                    />
+                                </div>
+
+                                <div class="form-group">
+                                    <label class="form-label">"Confirm new password"</label>
+                                    <input
+                                        type="password"
+                                        class="form-input"
+                                        prop:value=confirm_password
+                                        on:input=move |ev| {
+                                            set_confirm_password.set(event_target_value(&ev));
+                                        }
+
+                                        required
+                                    />
+                                </div>
+
+                                <button
+                                    type="submit"
+                                    class="btn btn-primary"
+                                    disabled=move || is_loading.get()
+                                >
+                                    {move || {
+                                        if is_loading.get() { "Saving..." } else { "Set Password" }
+                                    }}
+                                </button>
+                            </form>
+                        </div>
+                    }
+                        .into_view()
+                }
+                LoginState::AcceptingTerms(terms, err) => {
+                    let link = terms
+                        .url
+                        .clone()
+                        .map(|url| {
+                            view! {
+                                <p class="text-center mb-16">
+                                    <a href=url target="_blank" rel="noopener">
+                                        "Read the terms of service"
+                                    </a>
+                                </p>
+                            }
+                        });
+                    let version = terms.version.clone();
+                    view! {
+                        <div class="form">
+                            <h1 class="form-title">"Terms of Service"</h1>
+                            <p class="text-center mb-16">
+                                {format!(
+                                    "Please accept the terms of service (version {}) to continue.",
+                                    version,
+                                )}
+                            </p>
+                            {link}
+
+                            {err.map(|e| view! { <div class="error">{e}</div> })}
+
+                            <form on:submit=move |ev| {
+                                ev.prevent_default();
+                                handle_accept_terms.dispatch(terms.clone());
+                            }>
+                                <div class="form-group">
+                                    <label>
+                                        <input
+                                            type="checkbox"
+                                            prop:checked=terms_agreed
+                                            on:change=move |ev| {
+                                                set_terms_agreed.set(event_target_checked(&ev));
+                                            }
+                                        />
+                                        " I have read and accept the terms of service"
+                                    </label>
+                                </div>
+
+                                <button
+                                    type="submit"
+                                    class="btn btn-primary"
+                                    disabled=move || is_loading.get() || !terms_agreed.get()
+                                >
+                                    {move || {
+                                        if is_loading.get() { "Continuing..." } else { "Accept and Continue" }
+                                    }}
+                                </button>
+                            </form>
                         </div>
                     }
                         .into_view()
@@ -1,3 +1,8 @@
+pub mod accept_invite;
+pub mod borrowers;
+pub mod dashboard;
+pub mod docs;
 pub mod home;
 pub mod login;
 pub mod settings;
+pub mod shared;
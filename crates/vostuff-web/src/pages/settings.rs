@@ -1,15 +1,23 @@
 use leptos::*;
 use leptos_router::*;
 
+use crate::components::announcement::AnnouncementSettings;
+use crate::components::branding::BrandingSettings;
 use crate::components::fields_manager::FieldsManager;
 use crate::components::header::Header;
 use crate::components::kinds_manager::KindsManager;
+use crate::components::loan_policy::LoanPolicySettings;
+use crate::components::read_only::ReadOnlySettings;
 use crate::server_fns::auth::{UserInfo, get_current_user};
 
 #[derive(Clone, PartialEq)]
 enum Tab {
     Kinds,
     Fields,
+    Loans,
+    Announcement,
+    Branding,
+    ReadOnly,
 }
 
 #[component]
@@ -46,6 +54,12 @@ fn AuthenticatedSettings(user_info: UserInfo) -> impl IntoView {
             <Header
                 username=user_info.name.clone()
                 org_name=user_info.organization.name.clone()
+                org_id=user_info.organization.id
+                announcement=user_info.settings.announcement.clone()
+                accent_color=user_info.settings.accent_color.clone()
+                logo_url=user_info.logo_url.clone()
+                broadcasts=user_info.broadcasts.clone()
+                read_only=user_info.read_only
             />
             <div class="container">
                 <div class="page-header">
@@ -72,6 +86,38 @@ fn AuthenticatedSettings(user_info: UserInfo) -> impl IntoView {
                     >
                         "Fields"
                     </button>
+                    <button
+                        class=move || {
+                            if active_tab.get() == Tab::Loans { "tab-btn active" } else { "tab-btn" }
+                        }
+                        on:click=move |_| set_active_tab.set(Tab::Loans)
+                    >
+                        "Loans"
+                    </button>
+                    <button
+                        class=move || {
+                            if active_tab.get() == Tab::Announcement { "tab-btn active" } else { "tab-btn" }
+                        }
+                        on:click=move |_| set_active_tab.set(Tab::Announcement)
+                    >
+                        "Announcement"
+                    </button>
+                    <button
+                        class=move || {
+                            if active_tab.get() == Tab::Branding { "tab-btn active" } else { "tab-btn" }
+                        }
+                        on:click=move |_| set_active_tab.set(Tab::Branding)
+                    >
+                        "Branding"
+                    </button>
+                    <button
+                        class=move || {
+                            if active_tab.get() == Tab::ReadOnly { "tab-btn active" } else { "tab-btn" }
+                        }
+                        on:click=move |_| set_active_tab.set(Tab::ReadOnly)
+                    >
+                        "Read-only"
+                    </button>
                 </div>
                 <Show when=move || active_tab.get() == Tab::Kinds fallback=|| ()>
                     <KindsManager org_id=org_id/>
@@ -79,6 +125,18 @@ fn AuthenticatedSettings(user_info: UserInfo) -> impl IntoView {
                 <Show when=move || active_tab.get() == Tab::Fields fallback=|| ()>
                     <FieldsManager org_id=org_id/>
                 </Show>
+                <Show when=move || active_tab.get() == Tab::Loans fallback=|| ()>
+                    <LoanPolicySettings org_id=org_id/>
+                </Show>
+                <Show when=move || active_tab.get() == Tab::Announcement fallback=|| ()>
+                    <AnnouncementSettings org_id=org_id/>
+                </Show>
+                <Show when=move || active_tab.get() == Tab::Branding fallback=|| ()>
+                    <BrandingSettings org_id=org_id/>
+                </Show>
+                <Show when=move || active_tab.get() == Tab::ReadOnly fallback=|| ()>
+                    <ReadOnlySettings org_id=org_id/>
+                </Show>
             </div>
         </div>
     }
@@ -0,0 +1,108 @@
+use leptos::*;
+use leptos_router::*;
+
+use crate::components::soft_field_helpers::{format_field_name, value_to_edit_str};
+use crate::server_fns::errors::error_code;
+use crate::server_fns::items::{SharedItem, get_shared_item};
+use vostuff_core::models::ErrorCode;
+
+/// Label for a shared item field, keyed as in the API's item
+fn field_label(key: &str) -> String {
+    match key {
+        "kind_name" => "Kind".to_string(),
+        "width_mm" => "Width (mm)".to_string(),
+        "height_mm" => "Height (mm)".to_string(),
+        "depth_mm" => "Depth (mm)".to_string(),
+        "weight_g" => "Weight (g)".to_string(),
+        other => format_field_name(other),
+    }
+}
+
+/// The shared fields as label and value, with soft fields listed one by one
+fn shared_rows(item: &SharedItem) -> Vec<(String, String)> {
+    let Some(fields) = item.fields.as_object() else {
+        return Vec::new();
+    };
+    let mut rows = Vec::new();
+    for (key, value) in fields {
+        if key == "soft_fields" {
+            if let Some(soft) = value.as_object() {
+                rows.extend(
+                    soft.iter()
+                        .map(|(k, v)| (format_field_name(k), value_to_edit_str(v))),
+                );
+            }
+        } else if !value.is_null() {
+            rows.push((field_label(key), value_to_edit_str(value)));
+        }
+    }
+    rows
+}
+
+/// Public page for an item shared by link (`/shared?token=...`); no login needed
+#[component]
+pub fn SharedItemPage() -> impl IntoView {
+    let query = use_query_map();
+    let token = move || query.with(|q| q.get("token").cloned().unwrap_or_default());
+
+    let item_resource = create_resource(token, |token| async move {
+        if token.is_empty() {
+            return None;
+        }
+        Some(get_shared_item(token).await)
+    });
+
+    view! {
+        <div class="container">
+            <div class="shared-item">
+                <Suspense fallback=move || view! { <div class="loading">"Loading..."</div> }>
+                    {move || {
+                        item_resource
+                            .get()
+                            .map(|result| match result {
+                                Some(Ok(item)) => {
+                                    let rows = shared_rows(&item);
+                                    let expires = item.expires_at.format("%Y-%m-%d %H:%M UTC").to_string();
+                                    view! {
+                                        <h1>{item.name.clone()}</h1>
+                                        <div class="detail-row">
+                                            {rows
+                                                .into_iter()
+                                                .map(|(label, value)| {
+                                                    view! {
+                                                        <div class="detail-group">
+                                                            <span class="detail-label">{label + ":"}</span>
+                                                            <span class="detail-value">{value}</span>
+                                                        </div>
+                                                    }
+                                                })
+                                                .collect_view()}
+                                        </div>
+                                        <p class="shared-item-note">
+                                            "Shared from VOStuff. This link works until " {expires} "."
+                                        </p>
+                                    }
+                                        .into_view()
+                                }
+                                Some(Err(e)) => {
+                                    let message = match error_code(&e) {
+                                        Some(ErrorCode::ItemNotFound) => "This item is no longer available.",
+                                        _ => "This link is invalid or has expired.",
+                                    };
+                                    view! { <div class="error">{message}</div> }.into_view()
+                                }
+                                None => {
+                                    view! {
+                                        <div class="error">
+                                            "This link is incomplete. Open the full link you were sent."
+                                        </div>
+                                    }
+                                        .into_view()
+                                }
+                            })
+                    }}
+                </Suspense>
+            </div>
+        </div>
+    }
+}
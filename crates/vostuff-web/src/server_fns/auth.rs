@@ -3,12 +3,17 @@ use leptos::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::settings::OrganizationSettings;
+
 // Response types matching the API
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LoginResponse {
     pub token: String,
     pub expires_in: i64,
+    pub refresh_token: String,
+    pub refresh_expires_in: i64,
     pub user: UserInfo,
+    pub organizations: Vec<OrganizationWithRoles>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -24,12 +29,37 @@ pub struct UserInfo {
     pub identity: String,
     pub organization: OrganizationInfo,
     pub roles: Vec<String>,
+    /// What the user may do in the current organization, e.g. `items:write`
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// Current organization's settings; only filled in by get_current_user
+    #[serde(default)]
+    pub settings: OrganizationSettings,
+    /// Short-lived link to the current organization's logo; only filled in by get_current_user
+    #[serde(default)]
+    pub logo_url: Option<String>,
+    /// Current notices from the system administrators; only filled in by get_current_user
+    #[serde(default)]
+    pub broadcasts: Vec<BroadcastNotice>,
+    /// Whether the current organization refuses changes; only filled in by get_current_user
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// A notice from the system administrators, such as scheduled downtime
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BroadcastNotice {
+    pub id: Uuid,
+    pub message: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OrganizationInfo {
     pub id: Uuid,
     pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -39,29 +69,95 @@ pub struct OrganizationWithRoles {
     pub roles: Vec<String>,
 }
 
+/// Cookie holding the state of a login in progress at the identity provider
+#[cfg(feature = "ssr")]
+const OIDC_STATE_COOKIE: &str = "oidc_state";
+/// Seconds a login at the identity provider may take, matching the API's state lifetime
+#[cfg(feature = "ssr")]
+const OIDC_STATE_MAX_AGE: i64 = 600;
+
+/// What a login attempt led to
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum LoginOutcome {
+    LoggedIn(Box<LoginResponse>),
+    /// The user belongs to several organizations and must pick one
+    SelectOrg(OrgSelectionResponse),
+    /// An administrator reset the password; log in again with a new password
+    PasswordChangeRequired,
+    /// The terms of service must be accepted with `accept_terms` first
+    TermsAcceptanceRequired(TermsToAccept),
+}
+
+/// Terms of service shown before logging in
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TermsToAccept {
+    pub version: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    pub follow_on_token: String,
+}
+
+/// What a login answer from the API means, opening the session if it logged in
+#[cfg(feature = "ssr")]
+fn login_outcome(body: &str) -> Result<LoginOutcome, ServerFnError<NoCustomError>> {
+    if let Ok(login_resp) = serde_json::from_str::<LoginResponse>(body) {
+        set_session_cookies(&login_resp);
+        return Ok(LoginOutcome::LoggedIn(Box::new(login_resp)));
+    }
+
+    /// API answers that ask for something before logging in
+    #[derive(serde::Deserialize)]
+    struct Interstitial {
+        #[serde(default)]
+        password_change_required: bool,
+        #[serde(default)]
+        terms_acceptance_required: bool,
+    }
+
+    match serde_json::from_str::<Interstitial>(body) {
+        Ok(r) if r.password_change_required => return Ok(LoginOutcome::PasswordChangeRequired),
+        Ok(r) if r.terms_acceptance_required => {
+            return serde_json::from_str::<TermsToAccept>(body)
+                .map(LoginOutcome::TermsAcceptanceRequired)
+                .map_err(|e| {
+                    ServerFnError::<NoCustomError>::ServerError(format!(
+                        "Failed to parse response: {}",
+                        e
+                    ))
+                });
+        }
+        _ => {}
+    }
+
+    // Otherwise, try to parse as OrgSelectionResponse
+    serde_json::from_str::<OrgSelectionResponse>(body)
+        .map(LoginOutcome::SelectOrg)
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+        })
+}
+
 // Server function to handle login
 #[server(Login, "/api")]
 pub async fn login(
     identity: String,
     password: String,
     organization_id: Option<Uuid>,
-) -> Result<Result<LoginResponse, OrgSelectionResponse>, ServerFnError<NoCustomError>> {
-    use axum::http::HeaderValue;
-    use leptos_axum::ResponseOptions;
-
+    new_password: Option<String>,
+) -> Result<LoginOutcome, ServerFnError<NoCustomError>> {
     // Get API base URL from environment
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
 
     // Prepare login request
     let login_req = serde_json::json!({
         "identity": identity,
         "password": password,
         "organization_id": organization_id,
+        "new_password": new_password,
     });
 
     // Call the REST API
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .post(format!("{}/api/auth/login", api_base_url))
         .json(&login_req)
@@ -74,43 +170,41 @@ pub async fn login(
     let status = response.status();
 
     if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Login failed: {}",
-            error_text
-        )));
+        return Err(super::errors::api_error("Login failed", response).await);
     }
 
-    // Try to parse as LoginResponse first (direct login)
     let body = response.text().await.map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to read response: {}", e))
     })?;
+    login_outcome(&body)
+}
 
-    // Try to deserialize as LoginResponse
-    if let Ok(login_resp) = serde_json::from_str::<LoginResponse>(&body) {
-        // Set the JWT token in HTTP-only cookie
-        let response_options = expect_context::<ResponseOptions>();
-        let cookie = format!(
-            "auth_token={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
-            login_resp.token, login_resp.expires_in
-        );
-        response_options.insert_header(
-            axum::http::header::SET_COOKIE,
-            HeaderValue::from_str(&cookie).unwrap(),
-        );
-
-        return Ok(Ok(login_resp));
+/// Accept the terms of service a login asked for, and carry on logging in
+#[server(AcceptTerms, "/api")]
+pub async fn accept_terms(
+    follow_on_token: String,
+    version: String,
+) -> Result<LoginOutcome, ServerFnError<NoCustomError>> {
+    let api_base_url = super::api_base_url();
+    let response = super::api_client()
+        .post(format!("{}/api/auth/accept-terms", api_base_url))
+        .json(&serde_json::json!({
+            "follow_on_token": follow_on_token,
+            "version": version,
+        }))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to accept terms", response).await);
     }
 
-    // Otherwise, try to parse as OrgSelectionResponse
-    serde_json::from_str::<OrgSelectionResponse>(&body)
-        .map(Err)
-        .map_err(|e| {
-            ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
-        })
+    let body = response.text().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to read response: {}", e))
+    })?;
+    login_outcome(&body)
 }
 
 // Server function to handle organization selection
@@ -119,12 +213,8 @@ pub async fn select_organization(
     follow_on_token: String,
     organization_id: Uuid,
 ) -> Result<LoginResponse, ServerFnError<NoCustomError>> {
-    use axum::http::HeaderValue;
-    use leptos_axum::ResponseOptions;
-
     // Get API base URL from environment
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
 
     // Prepare request
     let select_req = serde_json::json!({
@@ -133,7 +223,7 @@ pub async fn select_organization(
     });
 
     // Call the REST API
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .post(format!("{}/api/auth/select-org", api_base_url))
         .json(&select_req)
@@ -146,69 +236,190 @@ pub async fn select_organization(
     let status = response.status();
 
     if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Org selection failed: {}",
-            error_text
-        )));
+        return Err(super::errors::api_error("Org selection failed", response).await);
     }
 
     let login_resp: LoginResponse = response.json().await.map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
     })?;
 
-    // Set the JWT token in HTTP-only cookie
-    let response_options = expect_context::<ResponseOptions>();
+    set_session_cookies(&login_resp);
+    Ok(login_resp)
+}
+
+/// Name of the external identity provider offered for login, if any
+#[server(GetOidcProvider, "/api")]
+pub async fn get_oidc_provider() -> Result<Option<String>, ServerFnError<NoCustomError>> {
+    let api_base_url = super::api_base_url();
+    let response = super::api_client()
+        .get(format!("{}/api/auth/oidc", api_base_url))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    // Not configured
+    if response.status() == 404 {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to get login provider", response).await);
+    }
+
+    #[derive(serde::Deserialize)]
+    struct ProviderInfo {
+        name: String,
+    }
+    let info: ProviderInfo = response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })?;
+    Ok(Some(info.name))
+}
+
+/// Accept an organization invite from its emailed link. `name` and `password`
+/// create the account when none exists for the invited email.
+#[server(AcceptInvite, "/api")]
+pub async fn accept_invite(
+    token: String,
+    name: Option<String>,
+    password: Option<String>,
+) -> Result<(), ServerFnError<NoCustomError>> {
+    let api_base_url = super::api_base_url();
+    let response = super::api_client()
+        .post(format!("{}/api/auth/accept-invite", api_base_url))
+        .json(&serde_json::json!({
+            "token": token,
+            "name": name,
+            "password": password,
+        }))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to accept invite", response).await);
+    }
+    Ok(())
+}
+
+/// Start logging in at the identity provider; returns the URL to send the
+/// browser to. The login's state is kept in a cookie so that only this browser
+/// can complete it.
+#[server(StartOidcLogin, "/api")]
+pub async fn start_oidc_login() -> Result<String, ServerFnError<NoCustomError>> {
+    use axum::http::HeaderValue;
+    use leptos_axum::ResponseOptions;
+
+    let api_base_url = super::api_base_url();
+    let response = super::api_client()
+        .get(format!("{}/api/auth/oidc/authorize", api_base_url))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Single sign-on unavailable", response).await);
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Authorization {
+        authorization_url: String,
+        state: String,
+    }
+    let authorization: Authorization = response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })?;
+
     let cookie = format!(
-        "auth_token={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
-        login_resp.token, login_resp.expires_in
+        "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
+        OIDC_STATE_COOKIE, authorization.state, OIDC_STATE_MAX_AGE
     );
-    response_options.insert_header(
+    expect_context::<ResponseOptions>().append_header(
         axum::http::header::SET_COOKIE,
         HeaderValue::from_str(&cookie).unwrap(),
     );
 
-    Ok(login_resp)
+    Ok(authorization.authorization_url)
 }
 
-// Server function to get current authenticated user
-#[server(GetCurrentUser, "/api")]
-pub async fn get_current_user() -> Result<Option<UserInfo>, ServerFnError<NoCustomError>> {
-    use axum::http::header::COOKIE;
-    use leptos_axum::extract;
+/// Finish logging in with the `code` and `state` the identity provider
+/// redirected back with
+#[server(CompleteOidcLogin, "/api")]
+pub async fn complete_oidc_login(
+    code: String,
+    state: String,
+) -> Result<LoginOutcome, ServerFnError<NoCustomError>> {
+    use axum::http::{HeaderValue, header::COOKIE};
+    use leptos_axum::{ResponseOptions, extract};
 
-    // Get cookies from request headers
     let headers = extract::<axum::http::HeaderMap>().await.map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to extract headers: {}", e))
     })?;
-
-    // Parse cookies to find auth_token
-    let auth_token = headers
+    let prefix = format!("{}=", OIDC_STATE_COOKIE);
+    let expected_state = headers
         .get(COOKIE)
         .and_then(|cookie_header| cookie_header.to_str().ok())
         .and_then(|cookies| {
             cookies
                 .split(';')
                 .map(|c| c.trim())
-                .find(|c| c.starts_with("auth_token="))
-                .map(|c| c.trim_start_matches("auth_token=").to_string())
+                .find(|c| c.starts_with(&prefix))
+                .map(|c| c.trim_start_matches(&prefix).to_string())
         });
 
-    // If no auth token, return None
-    let token = match auth_token {
-        Some(t) => t,
-        None => return Ok(None),
+    // The state is single use
+    let cookie = format!(
+        "{}=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0",
+        OIDC_STATE_COOKIE
+    );
+    expect_context::<ResponseOptions>().append_header(
+        axum::http::header::SET_COOKIE,
+        HeaderValue::from_str(&cookie).unwrap(),
+    );
+
+    if expected_state.as_deref() != Some(state.as_str()) {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Sign-in was not started from this browser; please try again".to_string(),
+        ));
+    }
+
+    let api_base_url = super::api_base_url();
+    let response = super::api_client()
+        .post(format!("{}/api/auth/oidc/callback", api_base_url))
+        .json(&serde_json::json!({ "code": code, "state": state }))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Login failed", response).await);
+    }
+
+    let body = response.text().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to read response: {}", e))
+    })?;
+    login_outcome(&body)
+}
+
+// Server function to get current authenticated user
+#[server(GetCurrentUser, "/api")]
+pub async fn get_current_user() -> Result<Option<UserInfo>, ServerFnError<NoCustomError>> {
+    // No session, or one that can no longer be renewed, means not logged in
+    let token = match super::items::get_auth_token().await {
+        Ok(t) => t,
+        Err(_) => return Ok(None),
     };
 
     // Get API base URL from environment
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
 
     // Call the /api/auth/me endpoint to get user info
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .get(format!("{}/api/auth/me", api_base_url))
         .header("Authorization", format!("Bearer {}", token))
@@ -225,12 +436,7 @@ pub async fn get_current_user() -> Result<Option<UserInfo>, ServerFnError<NoCust
 
     // If other error, return error
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to get user info: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to get user info", response).await);
     }
 
     // Parse the response - it should match our UserInfo structure
@@ -242,13 +448,20 @@ pub async fn get_current_user() -> Result<Option<UserInfo>, ServerFnError<NoCust
         identity: String,
         organization: ApiOrganization,
         roles: Vec<String>,
+        permissions: Vec<String>,
+        settings: OrganizationSettings,
+        #[serde(default)]
+        logo_url: Option<String>,
+        #[serde(default)]
+        broadcasts: Vec<BroadcastNotice>,
+        #[serde(default)]
+        read_only: bool,
     }
 
     #[derive(serde::Deserialize)]
     struct ApiOrganization {
         id: Uuid,
         name: String,
-        #[allow(dead_code)]
         description: Option<String>,
         #[allow(dead_code)]
         created_at: chrono::DateTime<chrono::Utc>,
@@ -268,8 +481,14 @@ pub async fn get_current_user() -> Result<Option<UserInfo>, ServerFnError<NoCust
         organization: OrganizationInfo {
             id: api_user_info.organization.id,
             name: api_user_info.organization.name,
+            description: api_user_info.organization.description,
         },
         roles: api_user_info.roles,
+        permissions: api_user_info.permissions,
+        settings: api_user_info.settings,
+        logo_url: api_user_info.logo_url,
+        broadcasts: api_user_info.broadcasts,
+        read_only: api_user_info.read_only,
     };
 
     Ok(Some(user_info))
@@ -281,13 +500,54 @@ pub async fn logout() -> Result<(), ServerFnError<NoCustomError>> {
     use axum::http::HeaderValue;
     use leptos_axum::ResponseOptions;
 
-    // Clear the auth cookie
+    // End the session server-side; the cookies are cleared whatever the outcome
+    if let Ok(token) = super::items::get_auth_token().await {
+        let api_base_url = super::api_base_url();
+        let result = super::api_client()
+            .post(format!("{}/api/auth/logout", api_base_url))
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await;
+        if let Err(e) = result {
+            tracing::warn!("Logout request failed: {}", e);
+        }
+    }
+
+    // Clear the session cookies
     let response_options = expect_context::<ResponseOptions>();
-    let cookie = "auth_token=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0";
-    response_options.insert_header(
-        axum::http::header::SET_COOKIE,
-        HeaderValue::from_str(cookie).unwrap(),
-    );
+    for name in ["auth_token", "refresh_token"] {
+        let cookie = format!("{}=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0", name);
+        response_options.append_header(
+            axum::http::header::SET_COOKIE,
+            HeaderValue::from_str(&cookie).unwrap(),
+        );
+    }
 
     Ok(())
 }
+
+/// Store a session's tokens in HTTP-only cookies, each living as long as the token
+#[cfg(feature = "ssr")]
+pub(crate) fn set_session_cookies(session: &LoginResponse) {
+    use axum::http::HeaderValue;
+    use leptos_axum::ResponseOptions;
+
+    let response_options = expect_context::<ResponseOptions>();
+    for (name, value, max_age) in [
+        ("auth_token", &session.token, session.expires_in),
+        (
+            "refresh_token",
+            &session.refresh_token,
+            session.refresh_expires_in,
+        ),
+    ] {
+        let cookie = format!(
+            "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
+            name, value, max_age
+        );
+        response_options.append_header(
+            axum::http::header::SET_COOKIE,
+            HeaderValue::from_str(&cookie).unwrap(),
+        );
+    }
+}
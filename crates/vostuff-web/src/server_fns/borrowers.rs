@@ -0,0 +1,94 @@
+use leptos::server_fn::error::NoCustomError;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BorrowerSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub outstanding_count: i64,
+    pub loan_count: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BorrowerLoan {
+    pub loan_id: Uuid,
+    pub item_id: Uuid,
+    pub item_name: String,
+    pub kind_name: String,
+    pub date_loaned: chrono::NaiveDate,
+    pub date_due_back: Option<chrono::NaiveDate>,
+    pub date_returned: Option<chrono::NaiveDate>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BorrowerHistory {
+    pub id: Uuid,
+    pub name: String,
+    pub loans: Vec<BorrowerLoan>,
+    pub outstanding: Vec<BorrowerLoan>,
+    pub average_return_days: Option<f64>,
+}
+
+#[server(GetBorrowers, "/api")]
+pub async fn get_borrowers(
+    org_id: Uuid,
+) -> Result<Vec<BorrowerSummary>, ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+    let api_base_url = super::api_base_url();
+    let url = format!("{}/api/organizations/{}/borrowers", api_base_url, org_id);
+    let client = super::api_client();
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if response.status() == 401 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Not authenticated".to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to fetch borrowers", response).await);
+    }
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+#[server(GetBorrowerHistory, "/api")]
+pub async fn get_borrower_history(
+    org_id: Uuid,
+    borrower_id: Uuid,
+) -> Result<BorrowerHistory, ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+    let api_base_url = super::api_base_url();
+    let url = format!(
+        "{}/api/organizations/{}/borrowers/{}/history",
+        api_base_url, org_id, borrower_id
+    );
+    let client = super::api_client();
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if response.status() == 401 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Not authenticated".to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to fetch borrower history", response).await);
+    }
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
@@ -0,0 +1,27 @@
+use leptos::server_fn::error::NoCustomError;
+use leptos::*;
+
+pub use vostuff_core::models::{ApiChange, ApiChangeKind, ChangelogRelease};
+
+/// Releases after `since`, newest first; all of them when `since` is None or unknown
+#[server(GetChangelog, "/api")]
+pub async fn get_changelog(
+    since: Option<String>,
+) -> Result<Vec<ChangelogRelease>, ServerFnError<NoCustomError>> {
+    let api_base_url = super::api_base_url();
+    let url = format!("{}/api/changelog", api_base_url);
+    let client = super::api_client();
+    let mut request = client.get(&url);
+    if let Some(since) = since {
+        request = request.query(&[("since", since)]);
+    }
+    let response = request.send().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+    })?;
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to fetch changelog", response).await);
+    }
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
@@ -0,0 +1,34 @@
+//! Error handling for calls from server functions to the REST API.
+//!
+//! A failed API call becomes a `ServerFnError` whose message ends with the
+//! API's error code in brackets, e.g. `Failed to update kind: ... [kind.in_use]`.
+//! Components use [`error_code`] to react to specific failures rather than
+//! matching on message text.
+
+use leptos::server_fn::error::NoCustomError;
+use leptos::*;
+use vostuff_core::models::ErrorCode;
+
+/// Build the error for a non-success API response, keeping its error code
+#[cfg(feature = "ssr")]
+pub async fn api_error(context: &str, response: reqwest::Response) -> ServerFnError<NoCustomError> {
+    use vostuff_core::models::ErrorResponse;
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let message = match serde_json::from_str::<ErrorResponse>(&body) {
+        Ok(err) => format!("{}: {} [{}]", context, err.message, err.error),
+        // Not one of ours, e.g. a proxy error page
+        Err(_) => format!("{}: {} - {}", context, status, body),
+    };
+    ServerFnError::ServerError(message)
+}
+
+/// The API error code carried by a server function error, if any
+pub fn error_code(err: &ServerFnError<NoCustomError>) -> Option<ErrorCode> {
+    let ServerFnError::ServerError(message) = err else {
+        return None;
+    };
+    let (_, code) = message.strip_suffix(']')?.rsplit_once('[')?;
+    code.parse().ok()
+}
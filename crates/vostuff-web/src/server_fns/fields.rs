@@ -26,10 +26,9 @@ pub struct Field {
 #[server(GetFields, "/api")]
 pub async fn get_fields(org_id: Uuid) -> Result<Vec<Field>, ServerFnError<NoCustomError>> {
     let token = super::items::get_auth_token().await?;
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
     let url = format!("{}/api/organizations/{}/fields", api_base_url, org_id);
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .get(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -44,12 +43,7 @@ pub async fn get_fields(org_id: Uuid) -> Result<Vec<Field>, ServerFnError<NoCust
         ));
     }
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to fetch fields: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to fetch fields", response).await);
     }
     response.json().await.map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
@@ -66,15 +60,11 @@ pub async fn create_field(
     enum_values: Option<String>,
 ) -> Result<Field, ServerFnError<NoCustomError>> {
     let token = super::items::get_auth_token().await?;
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
     let url = format!("{}/api/organizations/{}/fields", api_base_url, org_id);
     let ev_val: serde_json::Value = match enum_values {
         Some(ref s) => serde_json::from_str(s).map_err(|e| {
-            ServerFnError::<NoCustomError>::ServerError(format!(
-                "Invalid enum_values JSON: {}",
-                e
-            ))
+            ServerFnError::<NoCustomError>::ServerError(format!("Invalid enum_values JSON: {}", e))
         })?,
         None => serde_json::json!([]),
     };
@@ -84,7 +74,7 @@ pub async fn create_field(
         "field_type": field_type,
         "enum_values": ev_val,
     });
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .post(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -100,12 +90,7 @@ pub async fn create_field(
         ));
     }
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to create field: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to create field", response).await);
     }
     response.json().await.map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
@@ -121,18 +106,14 @@ pub async fn update_field(
     enum_values: Option<String>,
 ) -> Result<Field, ServerFnError<NoCustomError>> {
     let token = super::items::get_auth_token().await?;
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
     let url = format!(
         "{}/api/organizations/{}/fields/{}",
         api_base_url, org_id, field_id
     );
     let ev_val: Option<serde_json::Value> = match enum_values {
         Some(ref s) => Some(serde_json::from_str(s).map_err(|e| {
-            ServerFnError::<NoCustomError>::ServerError(format!(
-                "Invalid enum_values JSON: {}",
-                e
-            ))
+            ServerFnError::<NoCustomError>::ServerError(format!("Invalid enum_values JSON: {}", e))
         })?),
         None => None,
     };
@@ -142,7 +123,7 @@ pub async fn update_field(
     if let Some(ev) = ev_val {
         body["enum_values"] = ev;
     }
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .patch(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -158,12 +139,7 @@ pub async fn update_field(
         ));
     }
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to update field: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to update field", response).await);
     }
     response.json().await.map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
@@ -177,13 +153,12 @@ pub async fn delete_field(
     field_id: Uuid,
 ) -> Result<(), ServerFnError<NoCustomError>> {
     let token = super::items::get_auth_token().await?;
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
     let url = format!(
         "{}/api/organizations/{}/fields/{}",
         api_base_url, org_id, field_id
     );
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .delete(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -198,12 +173,7 @@ pub async fn delete_field(
         ));
     }
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to delete field: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to delete field", response).await);
     }
     Ok(())
 }
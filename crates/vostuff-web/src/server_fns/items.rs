@@ -1,3 +1,4 @@
+use leptos::server_fn::codec::{MultipartData, MultipartFormData};
 use leptos::server_fn::error::NoCustomError;
 use leptos::*;
 use serde::{Deserialize, Serialize};
@@ -5,52 +6,7 @@ use uuid::Uuid;
 
 // Types matching the API response
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
-#[serde(rename_all = "snake_case")]
-pub enum ItemState {
-    Current,
-    Loaned,
-    Missing,
-    Disposed,
-}
-
-impl ItemState {
-    pub fn display_name(&self) -> &'static str {
-        match self {
-            ItemState::Current => "Current",
-            ItemState::Loaned => "Loaned",
-            ItemState::Missing => "Missing",
-            ItemState::Disposed => "Disposed",
-        }
-    }
-
-    pub fn css_class(&self) -> &'static str {
-        match self {
-            ItemState::Current => "state-current",
-            ItemState::Loaned => "state-loaned",
-            ItemState::Missing => "state-missing",
-            ItemState::Disposed => "state-disposed",
-        }
-    }
-
-    pub fn api_value(&self) -> &'static str {
-        match self {
-            ItemState::Current => "current",
-            ItemState::Loaned => "loaned",
-            ItemState::Missing => "missing",
-            ItemState::Disposed => "disposed",
-        }
-    }
-
-    pub fn all() -> Vec<ItemState> {
-        vec![
-            ItemState::Current,
-            ItemState::Loaned,
-            ItemState::Missing,
-            ItemState::Disposed,
-        ]
-    }
-}
+pub use vostuff_core::models::ItemState;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Item {
@@ -66,6 +22,13 @@ pub struct Item {
     pub location_id: Option<Uuid>,
     pub date_entered: chrono::DateTime<chrono::Utc>,
     pub date_acquired: Option<chrono::NaiveDate>,
+    pub barcode: Option<String>,
+    pub catalog_code: Option<String>,
+    pub width_mm: Option<i32>,
+    pub height_mm: Option<i32>,
+    pub depth_mm: Option<i32>,
+    pub weight_g: Option<i32>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -89,6 +52,9 @@ pub struct PaginatedResponse<T> {
 }
 
 /// Helper function to extract auth token from cookies (server-side only)
+///
+/// Once the short-lived access token has expired, the refresh token cookie is
+/// exchanged for a new pair, which is stored back in the cookies.
 #[cfg(feature = "ssr")]
 pub(crate) async fn get_auth_token() -> Result<String, ServerFnError<NoCustomError>> {
     use axum::http::header::COOKIE;
@@ -98,17 +64,45 @@ pub(crate) async fn get_auth_token() -> Result<String, ServerFnError<NoCustomErr
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to extract headers: {}", e))
     })?;
 
-    headers
-        .get(COOKIE)
-        .and_then(|cookie_header| cookie_header.to_str().ok())
-        .and_then(|cookies| {
-            cookies
-                .split(';')
-                .map(|c| c.trim())
-                .find(|c| c.starts_with("auth_token="))
-                .map(|c| c.trim_start_matches("auth_token=").to_string())
-        })
-        .ok_or_else(|| ServerFnError::<NoCustomError>::ServerError("Not authenticated".to_string()))
+    let cookie = |name: &str| {
+        let prefix = format!("{}=", name);
+        headers
+            .get(COOKIE)
+            .and_then(|cookie_header| cookie_header.to_str().ok())
+            .and_then(|cookies| {
+                cookies
+                    .split(';')
+                    .map(|c| c.trim())
+                    .find(|c| c.starts_with(&prefix))
+                    .map(|c| c.trim_start_matches(&prefix).to_string())
+            })
+    };
+
+    if let Some(token) = cookie("auth_token") {
+        return Ok(token);
+    }
+    let refresh_token = cookie("refresh_token").ok_or_else(|| {
+        ServerFnError::<NoCustomError>::ServerError("Not authenticated".to_string())
+    })?;
+
+    let api_base_url = super::api_base_url();
+    let response = super::api_client()
+        .post(format!("{}/api/auth/refresh", api_base_url))
+        .json(&serde_json::json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Session expired", response).await);
+    }
+    let session: super::auth::LoginResponse = response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })?;
+
+    super::auth::set_session_cookies(&session);
+    Ok(session.token)
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -117,6 +111,8 @@ pub struct LoanDetails {
     pub date_loaned: chrono::NaiveDate,
     pub date_due_back: Option<chrono::NaiveDate>,
     pub loaned_to: String,
+    #[serde(default)]
+    pub borrower_id: Option<Uuid>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -139,23 +135,70 @@ pub struct ItemFullDetails {
     pub disposed_details: Option<DisposedDetails>,
 }
 
-/// Fetch full details for a single item
-#[server(GetItemDetails, "/api")]
-pub async fn get_item_details(
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemCollection {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemHistoryEntry {
+    pub id: Uuid,
+    pub change_date: Option<chrono::DateTime<chrono::Utc>>,
+    pub change_details: String,
+    /// Who made the change, if known
+    #[serde(default)]
+    pub user_name: Option<String>,
+}
+
+/// One logged use of an item
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UsageEntry {
+    pub used_at: chrono::DateTime<chrono::Utc>,
+    /// Who logged the use, if known
+    #[serde(default)]
+    pub user_name: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// A photo of an item
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemPhoto {
+    pub filename: String,
+    /// Short-lived link to the image
+    pub url: String,
+}
+
+/// An item with its state details, tags, collections, recent history, uses and photos
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemAggregate {
+    #[serde(flatten)]
+    pub details: ItemFullDetails,
+    pub tags: Vec<String>,
+    pub collections: Vec<ItemCollection>,
+    pub history: Vec<ItemHistoryEntry>,
+    pub usage: Vec<UsageEntry>,
+    #[serde(default)]
+    pub photos: Vec<ItemPhoto>,
+}
+
+/// Fetch an item with everything its expanded view shows, in one API call
+#[server(GetItemFull, "/api")]
+pub async fn get_item_full(
     org_id: Uuid,
     item_id: Uuid,
-) -> Result<ItemFullDetails, ServerFnError<NoCustomError>> {
+) -> Result<ItemAggregate, ServerFnError<NoCustomError>> {
     let token = get_auth_token().await?;
 
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
 
     let url = format!(
-        "{}/api/organizations/{}/items/{}/details",
+        "{}/api/organizations/{}/items/{}/full",
         api_base_url, org_id, item_id
     );
 
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .get(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -172,12 +215,7 @@ pub async fn get_item_details(
     }
 
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to fetch item details: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to fetch item", response).await);
     }
 
     response.json().await.map_err(|e| {
@@ -185,6 +223,14 @@ pub async fn get_item_details(
     })
 }
 
+/// Create/update response: the item plus non-fatal warnings (item fields not needed here)
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, Deserialize)]
+struct SavedItem {
+    #[serde(default)]
+    warnings: Vec<String>,
+}
+
 /// Update item request (web-side)
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct UpdateItemRequest {
@@ -200,6 +246,19 @@ pub struct UpdateItemRequest {
     pub date_acquired: Option<chrono::NaiveDate>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub state: Option<ItemState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub catalog_code: Option<String>,
+    // Dimensions (mm) and weight (g); 0 clears on the API side
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width_mm: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height_mm: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth_mm: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight_g: Option<i32>,
     // Soft fields are JSON-encoded as a String for transport (serde_urlencoded
     // loses type information for nested serde_json::Value). The server fn
     // parses it back before forwarding to the API.
@@ -226,11 +285,10 @@ pub async fn update_item(
     org_id: Uuid,
     item_id: Uuid,
     req: UpdateItemRequest,
-) -> Result<(), ServerFnError<NoCustomError>> {
+) -> Result<Vec<String>, ServerFnError<NoCustomError>> {
     let token = get_auth_token().await?;
 
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
 
     let url = format!(
         "{}/api/organizations/{}/items/{}",
@@ -243,15 +301,13 @@ pub async fn update_item(
     let mut body = serde_json::to_value(&req).map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Serialization error: {}", e))
     })?;
-    if let Some(sf_str) = &req.soft_fields {
-        if let (Ok(sf_val), Some(obj)) =
-            (serde_json::from_str(sf_str), body.as_object_mut())
-        {
-            obj.insert("soft_fields".to_string(), sf_val);
-        }
+    if let Some(sf_str) = &req.soft_fields
+        && let (Ok(sf_val), Some(obj)) = (serde_json::from_str(sf_str), body.as_object_mut())
+    {
+        obj.insert("soft_fields".to_string(), sf_val);
     }
 
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .patch(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -269,34 +325,28 @@ pub async fn update_item(
     }
 
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to update item: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to update item", response).await);
     }
 
-    Ok(())
+    let saved: SavedItem = response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })?;
+    Ok(saved.warnings)
 }
 
 /// Delete an item
 #[server(DeleteItem, "/api")]
-pub async fn delete_item(
-    org_id: Uuid,
-    item_id: Uuid,
-) -> Result<(), ServerFnError<NoCustomError>> {
+pub async fn delete_item(org_id: Uuid, item_id: Uuid) -> Result<(), ServerFnError<NoCustomError>> {
     let token = get_auth_token().await?;
 
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
 
     let url = format!(
         "{}/api/organizations/{}/items/{}",
         api_base_url, org_id, item_id
     );
 
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .delete(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -313,25 +363,395 @@ pub async fn delete_item(
     }
 
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to delete item: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to delete item", response).await);
     }
 
     Ok(())
 }
 
+/// Log a use of an item now; returns the updated item
+#[server(MarkItemUsed, "/api")]
+pub async fn mark_item_used(
+    org_id: Uuid,
+    item_id: Uuid,
+) -> Result<Item, ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let api_base_url = super::api_base_url();
+
+    let url = format!(
+        "{}/api/organizations/{}/items/{}/used",
+        api_base_url, org_id, item_id
+    );
+
+    let client = super::api_client();
+    let response = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&serde_json::json!({}))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to mark item used", response).await);
+    }
+
+    response.json::<Item>().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+pub use vostuff_core::models::{EnrichItemsResponse, ItemSuggestion};
+
+/// Queue a background lookup of details for the items
+#[server(EnrichItems, "/api")]
+pub async fn enrich_items(
+    org_id: Uuid,
+    item_ids: Vec<Uuid>,
+) -> Result<EnrichItemsResponse, ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let api_base_url = super::api_base_url();
+
+    let url = format!("{}/api/organizations/{}/items/enrich", api_base_url, org_id);
+
+    let client = super::api_client();
+    let response = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&serde_json::json!({ "item_ids": item_ids }))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to look up items", response).await);
+    }
+
+    response.json::<EnrichItemsResponse>().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+/// Lookup suggestions awaiting review
+#[server(GetItemSuggestions, "/api")]
+pub async fn get_item_suggestions(
+    org_id: Uuid,
+) -> Result<Vec<ItemSuggestion>, ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let api_base_url = super::api_base_url();
+
+    let url = format!(
+        "{}/api/organizations/{}/items/suggestions",
+        api_base_url, org_id
+    );
+
+    let client = super::api_client();
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to fetch suggestions", response).await);
+    }
+
+    response.json::<Vec<ItemSuggestion>>().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+/// Apply or dismiss a lookup suggestion
+#[server(DecideItemSuggestion, "/api")]
+pub async fn decide_item_suggestion(
+    org_id: Uuid,
+    suggestion_id: Uuid,
+    apply: bool,
+) -> Result<(), ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let api_base_url = super::api_base_url();
+
+    let url = format!(
+        "{}/api/organizations/{}/items/suggestions/{}/{}",
+        api_base_url,
+        org_id,
+        suggestion_id,
+        if apply { "apply" } else { "dismiss" }
+    );
+
+    let client = super::api_client();
+    let response = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&serde_json::json!({}))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to update suggestion", response).await);
+    }
+
+    Ok(())
+}
+
+pub use vostuff_core::models::ItemLookup;
+
+/// Prefill a new item from its barcode or ISBN; ISBN-10s and hyphenated
+/// ISBNs are looked up as ISBNs
+#[server(LookupCode, "/api")]
+pub async fn lookup_code(
+    org_id: Uuid,
+    code: String,
+) -> Result<ItemLookup, ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let api_base_url = super::api_base_url();
+
+    // Codes are digits, hyphens and X; anything else could not match and
+    // would need escaping in the path
+    let code: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+    let endpoint = if code.len() == 10 || code.contains(['-', 'X', 'x']) {
+        "isbn"
+    } else {
+        "barcode"
+    };
+    let url = format!(
+        "{}/api/organizations/{}/lookup/{}/{}",
+        api_base_url, org_id, endpoint, code
+    );
+
+    let client = super::api_client();
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to look up the code", response).await);
+    }
+
+    response.json::<ItemLookup>().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+pub use vostuff_core::models::{ShareLink, SharedItem};
+
+/// Create a link to a public page showing one item; None keeps the API's
+/// default fields and expiry
+#[server(CreateShareLink, "/api")]
+pub async fn create_share_link(
+    org_id: Uuid,
+    item_id: Uuid,
+    fields: Option<Vec<String>>,
+    expires_in_hours: Option<i64>,
+) -> Result<ShareLink, ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let api_base_url = super::api_base_url();
+
+    let url = format!(
+        "{}/api/organizations/{}/items/{}/share-link",
+        api_base_url, org_id, item_id
+    );
+
+    let client = super::api_client();
+    let response = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&serde_json::json!({
+            "fields": fields,
+            "expires_in_hours": expires_in_hours,
+        }))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to create share link", response).await);
+    }
+
+    response.json::<ShareLink>().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+/// The item a share link points to; needs no login
+#[server(GetSharedItem, "/api")]
+pub async fn get_shared_item(token: String) -> Result<SharedItem, ServerFnError<NoCustomError>> {
+    let api_base_url = super::api_base_url();
+
+    let url = format!("{}/api/shared-items/{}", api_base_url, token);
+
+    let response = super::api_client().get(&url).send().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+    })?;
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to load shared item", response).await);
+    }
+
+    response.json::<SharedItem>().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+/// A file from a batch photo upload and the item it was attached to
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AttachedPhoto {
+    pub filename: String,
+    pub item_name: String,
+}
+
+/// A file from a batch photo upload that was not attached, and why
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnmatchedPhoto {
+    pub filename: String,
+    pub reason: String,
+}
+
+/// Outcome of a batch photo upload
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PhotoImportResult {
+    pub attached: Vec<AttachedPhoto>,
+    pub unmatched: Vec<UnmatchedPhoto>,
+}
+
+/// Attach a zip of photos to items by file name; the form carries `org_id`
+/// and the zip as `archive`
+#[server(name = ImportPhotos, prefix = "/api", input = MultipartFormData)]
+pub async fn import_photos(
+    data: MultipartData,
+) -> Result<PhotoImportResult, ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let mut org_id = None;
+    let mut archive = None;
+    let mut form = data.into_inner().ok_or_else(|| {
+        ServerFnError::<NoCustomError>::ServerError("Expected a multipart form".to_string())
+    })?;
+    while let Some(field) = form.next_field().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to read upload: {}", e))
+    })? {
+        let name = field.name().unwrap_or_default().to_string();
+        let bytes = field.bytes().await.map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("Failed to read upload: {}", e))
+        })?;
+        match name.as_str() {
+            "org_id" => {
+                org_id = std::str::from_utf8(&bytes)
+                    .ok()
+                    .and_then(|s| s.parse::<Uuid>().ok())
+            }
+            "archive" => archive = Some(bytes),
+            _ => {}
+        }
+    }
+    let (Some(org_id), Some(archive)) = (org_id, archive) else {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Choose a zip file to upload".to_string(),
+        ));
+    };
+
+    let api_base_url = super::api_base_url();
+
+    let url = format!(
+        "{}/api/organizations/{}/items/photos/import",
+        api_base_url, org_id
+    );
+
+    let client = super::api_client();
+    let response = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/zip")
+        .body(archive)
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to upload photos", response).await);
+    }
+
+    response.json::<PhotoImportResult>().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+/// Suggest items to play or use next, favouring those unused the longest
+#[server(GetRotation, "/api")]
+pub async fn get_rotation(
+    org_id: Uuid,
+    count: i64,
+) -> Result<Vec<Item>, ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let api_base_url = super::api_base_url();
+
+    let url = format!(
+        "{}/api/organizations/{}/items/rotation?count={}",
+        api_base_url, org_id, count
+    );
+
+    let client = super::api_client();
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to load suggestions", response).await);
+    }
+
+    response.json::<Vec<Item>>().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
 /// Filter parameters for items query
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct ItemFilters {
     pub kinds: Vec<String>,
     pub states: Vec<String>,
     pub location_ids: Vec<Uuid>,
+    /// Items in any of these collections (set by the command palette)
+    pub collection_ids: Vec<Uuid>,
     pub search_query: Option<String>,
+    /// Exact barcode match (set by the scanner)
+    pub barcode: Option<String>,
+    /// Only items disposed on or after this date (set by dashboard links)
+    pub disposed_since: Option<chrono::NaiveDate>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
 }
@@ -346,8 +766,7 @@ pub async fn get_items(
 ) -> Result<PaginatedResponse<Item>, ServerFnError<NoCustomError>> {
     let token = get_auth_token().await?;
 
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
 
     // Build query string with filters
     let mut url = format!(
@@ -356,30 +775,7 @@ pub async fn get_items(
     );
 
     if let Some(ref f) = filters {
-        if !f.kinds.is_empty() {
-            url.push_str(&format!("&kind={}", f.kinds.join(",")));
-        }
-        if !f.states.is_empty() {
-            url.push_str(&format!("&state={}", f.states.join(",")));
-        }
-        if !f.location_ids.is_empty() {
-            let loc_str: Vec<String> = f.location_ids.iter().map(|id| id.to_string()).collect();
-            url.push_str(&format!("&location_id={}", loc_str.join(",")));
-        }
-        if let Some(ref q) = f.search_query
-            && !q.is_empty()
-        {
-            // Manual percent-encoding for the search query
-            let encoded: String = q
-                .chars()
-                .map(|c| match c {
-                    'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
-                    ' ' => "+".to_string(),
-                    _ => format!("%{:02X}", c as u32),
-                })
-                .collect();
-            url.push_str(&format!("&search={}", encoded));
-        }
+        url.push_str(&filter_query(f));
         if let Some(ref sb) = f.sort_by {
             url.push_str(&format!("&sort_by={}", sb));
         }
@@ -394,7 +790,7 @@ pub async fn get_items(
         filters
     );
 
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .get(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -411,12 +807,7 @@ pub async fn get_items(
     }
 
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to fetch items: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to fetch items", response).await);
     }
 
     response.json().await.map_err(|e| {
@@ -424,6 +815,227 @@ pub async fn get_items(
     })
 }
 
+/// Query string pieces (`&kind=...`) for the filters shared by the item list and facets
+#[cfg(feature = "ssr")]
+fn filter_query(f: &ItemFilters) -> String {
+    let mut query = String::new();
+    if !f.kinds.is_empty() {
+        query.push_str(&format!("&kind={}", f.kinds.join(",")));
+    }
+    if !f.states.is_empty() {
+        query.push_str(&format!("&state={}", f.states.join(",")));
+    }
+    if !f.location_ids.is_empty() {
+        let loc_str: Vec<String> = f.location_ids.iter().map(|id| id.to_string()).collect();
+        query.push_str(&format!("&location_id={}", loc_str.join(",")));
+    }
+    if !f.collection_ids.is_empty() {
+        let ids: Vec<String> = f.collection_ids.iter().map(|id| id.to_string()).collect();
+        query.push_str(&format!("&collection_id={}", ids.join(",")));
+    }
+    if let Some(ref q) = f.search_query
+        && !q.is_empty()
+    {
+        query.push_str(&format!("&search={}", encode_query_value(q)));
+    }
+    if let Some(ref b) = f.barcode
+        && !b.is_empty()
+    {
+        query.push_str(&format!("&barcode={}", encode_query_value(b)));
+    }
+    if let Some(d) = f.disposed_since {
+        query.push_str(&format!("&disposed_since={}", d));
+    }
+    query
+}
+
+/// Item counts per filter value under the current filters
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ItemFacets {
+    pub total: i64,
+    pub kinds: Vec<FacetCount>,
+    pub states: Vec<FacetCount>,
+    pub locations: Vec<FacetCount>,
+    pub tags: Vec<FacetCount>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FacetCount {
+    /// Filter value; None for items without a location
+    pub value: Option<String>,
+    pub count: i64,
+}
+
+impl ItemFacets {
+    /// Count per filter value of one facet, for the filter dropdown badges
+    pub fn counts(facet: &[FacetCount]) -> std::collections::HashMap<String, i64> {
+        facet
+            .iter()
+            .filter_map(|f| f.value.clone().map(|v| (v, f.count)))
+            .collect()
+    }
+}
+
+/// Fetch item counts per kind, state, location and tag for the filter dropdowns
+#[server(GetItemFacets, "/api")]
+pub async fn get_item_facets(
+    org_id: Uuid,
+    filters: ItemFilters,
+) -> Result<ItemFacets, ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let api_base_url = super::api_base_url();
+    let url = format!(
+        "{}/api/organizations/{}/items/facets?{}",
+        api_base_url,
+        org_id,
+        filter_query(&filters).trim_start_matches('&')
+    );
+
+    let client = super::api_client();
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to fetch item counts", response).await);
+    }
+
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+/// An item export file as the API named it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemExportFile {
+    pub filename: String,
+    pub csv: String,
+}
+
+/// Export the items matching the filters as CSV, in the list's sort order
+#[server(ExportItems, "/api")]
+pub async fn export_items(
+    org_id: Uuid,
+    filters: ItemFilters,
+) -> Result<ItemExportFile, ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let api_base_url = super::api_base_url();
+    let mut url = format!(
+        "{}/api/organizations/{}/items/export?format=csv{}",
+        api_base_url,
+        org_id,
+        filter_query(&filters)
+    );
+    if let Some(ref sb) = filters.sort_by {
+        url.push_str(&format!("&sort_by={}", sb));
+    }
+    if let Some(ref so) = filters.sort_order {
+        url.push_str(&format!("&sort_order={}", so));
+    }
+
+    let client = super::api_client();
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to export items", response).await);
+    }
+
+    // attachment; filename="vostuff-items-<date>.csv"
+    let filename = response
+        .headers()
+        .get(reqwest::header::CONTENT_DISPOSITION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split_once("filename="))
+        .map(|(_, name)| name.trim_matches('"').to_string())
+        .unwrap_or_else(|| "vostuff-items.csv".to_string());
+    let csv = response.text().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to read export: {}", e))
+    })?;
+
+    Ok(ItemExportFile { filename, csv })
+}
+
+/// A PDF of item labels as the API named it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemLabelsFile {
+    pub filename: String,
+    pub pdf: Vec<u8>,
+}
+
+/// Render labels for the items with the organization's named label template
+#[server(PrintLabels, "/api")]
+pub async fn print_labels(
+    org_id: Uuid,
+    template: String,
+    item_ids: Vec<Uuid>,
+) -> Result<ItemLabelsFile, ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let api_base_url = super::api_base_url();
+    let url = format!("{}/api/organizations/{}/items/labels", api_base_url, org_id);
+
+    let client = super::api_client();
+    let response = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&serde_json::json!({ "template": template, "item_ids": item_ids }))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to print labels", response).await);
+    }
+
+    let filename = response
+        .headers()
+        .get(reqwest::header::CONTENT_DISPOSITION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split_once("filename="))
+        .map(|(_, name)| name.trim_matches('"').to_string())
+        .unwrap_or_else(|| "vostuff-labels.pdf".to_string());
+    let pdf = response.bytes().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to read labels: {}", e))
+    })?;
+
+    Ok(ItemLabelsFile {
+        filename,
+        pdf: pdf.to_vec(),
+    })
+}
+
+/// Manual percent-encoding for free-text query string values.
+///
+/// Also used for the items page URL, so spaces become `%20` (the router does
+/// not decode `+`) and non-ASCII text is encoded as UTF-8 bytes.
+pub fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
 /// Create item request (web-side)
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CreateItemRequest {
@@ -433,6 +1045,8 @@ pub struct CreateItemRequest {
     pub notes: Option<String>,
     pub location_id: Option<Uuid>,
     pub date_acquired: Option<chrono::NaiveDate>,
+    pub barcode: Option<String>,
+    pub catalog_code: Option<String>,
     // JSON-encoded string for transport; parsed back in the server fn.
     pub soft_fields: Option<String>,
 }
@@ -442,30 +1056,80 @@ pub struct CreateItemRequest {
 pub async fn create_item(
     org_id: Uuid,
     req: CreateItemRequest,
-) -> Result<(), ServerFnError<NoCustomError>> {
+) -> Result<Vec<String>, ServerFnError<NoCustomError>> {
     let token = get_auth_token().await?;
 
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
 
     let url = format!("{}/api/organizations/{}/items", api_base_url, org_id);
 
     let mut body = serde_json::to_value(&req).map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Serialization error: {}", e))
     })?;
-    if let Some(sf_str) = &req.soft_fields {
-        if let (Ok(sf_val), Some(obj)) =
-            (serde_json::from_str(sf_str), body.as_object_mut())
+    if let Some(sf_str) = &req.soft_fields
+        && let (Ok(sf_val), Some(obj)) = (serde_json::from_str(sf_str), body.as_object_mut())
+    {
+        obj.insert("soft_fields".to_string(), sf_val);
+    }
+
+    let client = super::api_client();
+    let response = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if response.status() == 401 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Not authenticated".to_string(),
+        ));
+    }
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to create item", response).await);
+    }
+
+    let saved: SavedItem = response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })?;
+    Ok(saved.warnings)
+}
+
+/// Create several items at once via the bulk POST API; all are created or none are.
+/// Returns each item's warnings, in request order.
+#[server(CreateItems, "/api")]
+pub async fn create_items(
+    org_id: Uuid,
+    reqs: Vec<CreateItemRequest>,
+) -> Result<Vec<Vec<String>>, ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let api_base_url = super::api_base_url();
+
+    let url = format!("{}/api/organizations/{}/items/bulk", api_base_url, org_id);
+
+    let mut items = Vec::with_capacity(reqs.len());
+    for req in &reqs {
+        let mut item = serde_json::to_value(req).map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("Serialization error: {}", e))
+        })?;
+        if let Some(sf_str) = &req.soft_fields
+            && let (Ok(sf_val), Some(obj)) = (serde_json::from_str(sf_str), item.as_object_mut())
         {
             obj.insert("soft_fields".to_string(), sf_val);
         }
+        items.push(item);
     }
 
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .post(&url)
         .header("Authorization", format!("Bearer {}", token))
-        .json(&body)
+        .json(&serde_json::json!({ "items": items }))
         .send()
         .await
         .map_err(|e| {
@@ -479,15 +1143,13 @@ pub async fn create_item(
     }
 
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to create item: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to create items", response).await);
     }
 
-    Ok(())
+    let saved: Vec<SavedItem> = response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })?;
+    Ok(saved.into_iter().map(|s| s.warnings).collect())
 }
 
 /// Fetch all locations for an organization
@@ -495,10 +1157,9 @@ pub async fn create_item(
 pub async fn get_locations(org_id: Uuid) -> Result<Vec<Location>, ServerFnError<NoCustomError>> {
     let token = get_auth_token().await?;
 
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
 
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .get(format!(
             "{}/api/organizations/{}/locations",
@@ -518,15 +1179,116 @@ pub async fn get_locations(org_id: Uuid) -> Result<Vec<Location>, ServerFnError<
     }
 
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to fetch locations: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to fetch locations", response).await);
+    }
+
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Collection {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Fetch all collections for an organization
+#[server(GetCollections, "/api")]
+pub async fn get_collections(
+    org_id: Uuid,
+) -> Result<Vec<Collection>, ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let api_base_url = super::api_base_url();
+
+    let client = super::api_client();
+    let response = client
+        .get(format!(
+            "{}/api/organizations/{}/collections",
+            api_base_url, org_id
+        ))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+
+    if response.status() == 401 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Not authenticated".to_string(),
+        ));
+    }
+
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to fetch collections", response).await);
     }
 
     response.json().await.map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
     })
 }
+
+/// An item offered by the command palette
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemMatch {
+    pub id: Uuid,
+    pub name: String,
+    pub kind_name: String,
+    pub catalog_code: Option<String>,
+}
+
+/// Items whose catalogue number is `text`, followed by items whose name,
+/// description or notes match it; at most `limit` in all
+#[server(FindItems, "/api")]
+pub async fn find_items(
+    org_id: Uuid,
+    text: String,
+    limit: i64,
+) -> Result<Vec<ItemMatch>, ServerFnError<NoCustomError>> {
+    let token = get_auth_token().await?;
+
+    let api_base_url = super::api_base_url();
+    let value = encode_query_value(text.trim());
+
+    let client = super::api_client();
+    let mut matches: Vec<ItemMatch> = Vec::new();
+    for filter in ["catalog_code", "search"] {
+        let url = format!(
+            "{}/api/organizations/{}/items?{}={}&per_page={}&fields=id,name,kind_name,catalog_code",
+            api_base_url, org_id, filter, value, limit
+        );
+        let response = client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(|e| {
+                ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+            })?;
+
+        if response.status() == 401 {
+            return Err(ServerFnError::<NoCustomError>::ServerError(
+                "Not authenticated".to_string(),
+            ));
+        }
+
+        if !response.status().is_success() {
+            return Err(super::errors::api_error("Failed to find items", response).await);
+        }
+
+        let page: PaginatedResponse<ItemMatch> = response.json().await.map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+        })?;
+        for item in page.items {
+            if !matches.iter().any(|m| m.id == item.id) {
+                matches.push(item);
+            }
+        }
+    }
+
+    matches.truncate(limit.max(0) as usize);
+    Ok(matches)
+}
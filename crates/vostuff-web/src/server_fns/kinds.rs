@@ -55,13 +55,12 @@ pub async fn get_kind_fields(
     kind_id: Uuid,
 ) -> Result<Vec<KindFieldDef>, ServerFnError<NoCustomError>> {
     let token = super::items::get_auth_token().await?;
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
     let url = format!(
         "{}/api/organizations/{}/kinds/{}",
         api_base_url, org_id, kind_id
     );
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .get(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -76,12 +75,7 @@ pub async fn get_kind_fields(
         ));
     }
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to fetch kind: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to fetch kind", response).await);
     }
     let kind: KindWithFields = response.json().await.map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
@@ -92,12 +86,11 @@ pub async fn get_kind_fields(
 #[server(GetKinds, "/api")]
 pub async fn get_kinds(org_id: Uuid) -> Result<Vec<KindSummary>, ServerFnError<NoCustomError>> {
     let token = super::items::get_auth_token().await?;
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
 
     let url = format!("{}/api/organizations/{}/kinds", api_base_url, org_id);
 
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .get(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -114,12 +107,7 @@ pub async fn get_kinds(org_id: Uuid) -> Result<Vec<KindSummary>, ServerFnError<N
     }
 
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to fetch kinds: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to fetch kinds", response).await);
     }
 
     response.json().await.map_err(|e| {
@@ -131,10 +119,9 @@ pub async fn get_kinds(org_id: Uuid) -> Result<Vec<KindSummary>, ServerFnError<N
 #[server(GetKindsFull, "/api")]
 pub async fn get_kinds_full(org_id: Uuid) -> Result<Vec<Kind>, ServerFnError<NoCustomError>> {
     let token = super::items::get_auth_token().await?;
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
     let url = format!("{}/api/organizations/{}/kinds", api_base_url, org_id);
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .get(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -149,12 +136,7 @@ pub async fn get_kinds_full(org_id: Uuid) -> Result<Vec<Kind>, ServerFnError<NoC
         ));
     }
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to fetch kinds: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to fetch kinds", response).await);
     }
     response.json().await.map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
@@ -170,8 +152,7 @@ pub async fn create_kind(
     field_ids: String,
 ) -> Result<Kind, ServerFnError<NoCustomError>> {
     let token = super::items::get_auth_token().await?;
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
     let url = format!("{}/api/organizations/{}/kinds", api_base_url, org_id);
     let ids: Vec<Uuid> = serde_json::from_str(&field_ids).map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Invalid field_ids JSON: {}", e))
@@ -181,7 +162,7 @@ pub async fn create_kind(
         "display_name": display_name,
         "field_ids": ids,
     });
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .post(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -197,12 +178,7 @@ pub async fn create_kind(
         ));
     }
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to create kind: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to create kind", response).await);
     }
     response.json().await.map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
@@ -219,8 +195,7 @@ pub async fn update_kind(
     force: bool,
 ) -> Result<Kind, ServerFnError<NoCustomError>> {
     let token = super::items::get_auth_token().await?;
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
     let mut url = format!(
         "{}/api/organizations/{}/kinds/{}",
         api_base_url, org_id, kind_id
@@ -238,7 +213,7 @@ pub async fn update_kind(
         "display_name": display_name,
         "field_ids": ids,
     });
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .patch(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -254,12 +229,7 @@ pub async fn update_kind(
         ));
     }
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to update kind: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to update kind", response).await);
     }
     response.json().await.map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
@@ -270,13 +240,12 @@ pub async fn update_kind(
 #[server(DeleteKind, "/api")]
 pub async fn delete_kind(org_id: Uuid, kind_id: Uuid) -> Result<(), ServerFnError<NoCustomError>> {
     let token = super::items::get_auth_token().await?;
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
     let url = format!(
         "{}/api/organizations/{}/kinds/{}",
         api_base_url, org_id, kind_id
     );
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .delete(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -291,12 +260,7 @@ pub async fn delete_kind(org_id: Uuid, kind_id: Uuid) -> Result<(), ServerFnErro
         ));
     }
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to delete kind: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to delete kind", response).await);
     }
     Ok(())
 }
@@ -308,13 +272,12 @@ pub async fn override_kind(
     kind_id: Uuid,
 ) -> Result<Kind, ServerFnError<NoCustomError>> {
     let token = super::items::get_auth_token().await?;
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
     let url = format!(
         "{}/api/organizations/{}/kinds/{}/override",
         api_base_url, org_id, kind_id
     );
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .post(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -330,12 +293,7 @@ pub async fn override_kind(
         ));
     }
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to override kind: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to override kind", response).await);
     }
     response.json().await.map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
@@ -349,13 +307,12 @@ pub async fn revert_kind(
     kind_id: Uuid,
 ) -> Result<RevertResponse, ServerFnError<NoCustomError>> {
     let token = super::items::get_auth_token().await?;
-    let api_base_url =
-        std::env::var("API_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    let api_base_url = super::api_base_url();
     let url = format!(
         "{}/api/organizations/{}/kinds/{}/revert",
         api_base_url, org_id, kind_id
     );
-    let client = reqwest::Client::new();
+    let client = super::api_client();
     let response = client
         .post(&url)
         .header("Authorization", format!("Bearer {}", token))
@@ -371,12 +328,7 @@ pub async fn revert_kind(
         ));
     }
     if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(ServerFnError::<NoCustomError>::ServerError(format!(
-            "Failed to revert kind: {} - {}",
-            status, body
-        )));
+        return Err(super::errors::api_error("Failed to revert kind", response).await);
     }
     response.json().await.map_err(|e| {
         ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
@@ -1,4 +1,76 @@
 pub mod auth;
+pub mod borrowers;
+pub mod changelog;
+pub mod errors;
 pub mod fields;
 pub mod items;
 pub mod kinds;
+pub mod pages;
+pub mod reports;
+pub mod settings;
+
+#[cfg(feature = "ssr")]
+static API_BASE_URL: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+/// Point server functions at the API; called once at startup with the
+/// configured `api_base_url`
+#[cfg(feature = "ssr")]
+pub fn set_api_base_url(url: String) {
+    let _ = API_BASE_URL.set(url.trim_end_matches('/').to_string());
+}
+
+/// Base URL of the REST API, or the development default before startup sets it
+#[cfg(feature = "ssr")]
+pub fn api_base_url() -> &'static str {
+    API_BASE_URL
+        .get()
+        .map(String::as_str)
+        .unwrap_or("http://localhost:8080")
+}
+
+#[cfg(feature = "ssr")]
+static API_CLIENT: std::sync::OnceLock<reqwest_middleware::ClientWithMiddleware> =
+    std::sync::OnceLock::new();
+
+/// Client for calls to the API, shared so connections are reused. Each call
+/// is traced as a child of the calling server function's span, and the trace
+/// is passed on to the API.
+#[cfg(feature = "ssr")]
+pub fn api_client() -> &'static reqwest_middleware::ClientWithMiddleware {
+    API_CLIENT.get_or_init(|| {
+        reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+            .with(TraceApiCalls)
+            .build()
+    })
+}
+
+#[cfg(feature = "ssr")]
+struct TraceApiCalls;
+
+#[cfg(feature = "ssr")]
+#[async_trait::async_trait]
+impl reqwest_middleware::Middleware for TraceApiCalls {
+    async fn handle(
+        &self,
+        mut request: reqwest::Request,
+        extensions: &mut http::Extensions,
+        next: reqwest_middleware::Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        use tracing::Instrument;
+
+        let span = tracing::info_span!(
+            "api_call",
+            otel.name = %format!("{} {}", request.method(), request.url().path()),
+            otel.kind = "client",
+            http.request.method = %request.method(),
+            url.full = %request.url(),
+            http.response.status_code = tracing::field::Empty,
+        );
+        vostuff_core::telemetry::inject_context(&span, request.headers_mut());
+        let response = next.run(request, extensions).instrument(span.clone()).await;
+        if let Ok(response) = &response {
+            span.record("http.response.status_code", response.status().as_u16());
+        }
+        response
+    }
+}
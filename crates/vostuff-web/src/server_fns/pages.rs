@@ -0,0 +1,225 @@
+use leptos::server_fn::error::NoCustomError;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// An organization documentation page, with its Markdown body
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrgPage {
+    pub id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub version: i32,
+    pub updated_by_name: Option<String>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrgPageSummary {
+    pub id: Uuid,
+    pub title: String,
+    pub version: i32,
+    pub updated_by_name: Option<String>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrgPageVersion {
+    pub version: i32,
+    pub title: String,
+    pub body: String,
+    pub edited_by_name: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[server(GetPages, "/api")]
+pub async fn get_pages(org_id: Uuid) -> Result<Vec<OrgPageSummary>, ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+    let api_base_url = super::api_base_url();
+    let url = format!("{}/api/organizations/{}/pages", api_base_url, org_id);
+    let client = super::api_client();
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if response.status() == 401 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Not authenticated".to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to fetch pages", response).await);
+    }
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+#[server(GetPage, "/api")]
+pub async fn get_page(
+    org_id: Uuid,
+    page_id: Uuid,
+) -> Result<OrgPage, ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+    let api_base_url = super::api_base_url();
+    let url = format!(
+        "{}/api/organizations/{}/pages/{}",
+        api_base_url, org_id, page_id
+    );
+    let client = super::api_client();
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if response.status() == 401 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Not authenticated".to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to fetch page", response).await);
+    }
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+#[server(CreatePage, "/api")]
+pub async fn create_page(
+    org_id: Uuid,
+    title: String,
+    body: String,
+) -> Result<OrgPage, ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+    let api_base_url = super::api_base_url();
+    let url = format!("{}/api/organizations/{}/pages", api_base_url, org_id);
+    let client = super::api_client();
+    let response = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&serde_json::json!({"title": title, "body": body}))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if response.status() == 401 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Not authenticated".to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to create page", response).await);
+    }
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+/// Save an edit made to `version` of the page; fails with
+/// `page.version_conflict` if someone else has saved since
+#[server(UpdatePage, "/api")]
+pub async fn update_page(
+    org_id: Uuid,
+    page_id: Uuid,
+    version: i32,
+    title: String,
+    body: String,
+) -> Result<OrgPage, ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+    let api_base_url = super::api_base_url();
+    let url = format!(
+        "{}/api/organizations/{}/pages/{}",
+        api_base_url, org_id, page_id
+    );
+    let client = super::api_client();
+    let response = client
+        .patch(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&serde_json::json!({"version": version, "title": title, "body": body}))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if response.status() == 401 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Not authenticated".to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to save page", response).await);
+    }
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+#[server(DeletePage, "/api")]
+pub async fn delete_page(org_id: Uuid, page_id: Uuid) -> Result<(), ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+    let api_base_url = super::api_base_url();
+    let url = format!(
+        "{}/api/organizations/{}/pages/{}",
+        api_base_url, org_id, page_id
+    );
+    let client = super::api_client();
+    let response = client
+        .delete(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if response.status() == 401 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Not authenticated".to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to delete page", response).await);
+    }
+    Ok(())
+}
+
+#[server(GetPageVersions, "/api")]
+pub async fn get_page_versions(
+    org_id: Uuid,
+    page_id: Uuid,
+) -> Result<Vec<OrgPageVersion>, ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+    let api_base_url = super::api_base_url();
+    let url = format!(
+        "{}/api/organizations/{}/pages/{}/versions",
+        api_base_url, org_id, page_id
+    );
+    let client = super::api_client();
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if response.status() == 401 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Not authenticated".to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to fetch page history", response).await);
+    }
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
@@ -0,0 +1,88 @@
+use leptos::server_fn::error::NoCustomError;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Counts behind the dashboard's item state widgets
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemStateSummary {
+    pub loaned: i64,
+    pub missing: i64,
+    pub disposed_this_year: i64,
+    /// First day of the current year, used as the disposed_since filter
+    pub year_start: chrono::NaiveDate,
+}
+
+#[server(GetItemStateSummary, "/api")]
+pub async fn get_item_state_summary(
+    org_id: Uuid,
+) -> Result<ItemStateSummary, ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+    let api_base_url = super::api_base_url();
+    let url = format!(
+        "{}/api/organizations/{}/reports/item-states",
+        api_base_url, org_id
+    );
+    let client = super::api_client();
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if response.status() == 401 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Not authenticated".to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to fetch item state summary", response).await);
+    }
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+/// How often one item has been used
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemUsage {
+    pub item_id: Uuid,
+    pub item_name: String,
+    pub kind_name: String,
+    pub use_count: i64,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Most and least used current items
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub most_used: Vec<ItemUsage>,
+    pub least_used: Vec<ItemUsage>,
+}
+
+#[server(GetUsageReport, "/api")]
+pub async fn get_usage_report(org_id: Uuid) -> Result<UsageReport, ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+    let api_base_url = super::api_base_url();
+    let url = format!(
+        "{}/api/organizations/{}/reports/usage",
+        api_base_url, org_id
+    );
+    let client = super::api_client();
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to fetch usage report", response).await);
+    }
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
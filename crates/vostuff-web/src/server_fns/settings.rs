@@ -0,0 +1,177 @@
+use leptos::server_fn::codec::{MultipartData, MultipartFormData};
+use leptos::server_fn::error::NoCustomError;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Organization-wide settings; None means no limit
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OrganizationSettings {
+    #[serde(default)]
+    pub max_loans_per_borrower: Option<i32>,
+    #[serde(default)]
+    pub max_loan_days: Option<i32>,
+    #[serde(default)]
+    pub announcement: Option<Announcement>,
+    /// Roles for members added without explicit roles; kept as-is when saving
+    #[serde(default)]
+    pub default_member_roles: Option<Vec<String>>,
+    /// Months of full item history kept; kept as-is when saving
+    #[serde(default)]
+    pub history_retention_months: Option<i32>,
+    /// Accent colour as `#rrggbb`; None for the default
+    #[serde(default)]
+    pub accent_color: Option<String>,
+    /// Refuse changes to the organization's data
+    #[serde(default)]
+    pub read_only: bool,
+    /// Item label layouts; kept as-is when saving
+    #[serde(default)]
+    pub label_templates: Vec<serde_json::Value>,
+}
+
+/// Organization-wide notice shown as a banner until it expires
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Announcement {
+    pub message: String,
+    pub expires_on: chrono::NaiveDate,
+}
+
+#[server(GetOrgSettings, "/api")]
+pub async fn get_org_settings(
+    org_id: Uuid,
+) -> Result<OrganizationSettings, ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+    let api_base_url = super::api_base_url();
+    let url = format!("{}/api/organizations/{}/settings", api_base_url, org_id);
+    let client = super::api_client();
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if response.status() == 401 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Not authenticated".to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to fetch settings", response).await);
+    }
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+#[server(UpdateOrgSettings, "/api")]
+pub async fn update_org_settings(
+    org_id: Uuid,
+    settings: OrganizationSettings,
+) -> Result<OrganizationSettings, ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+    let api_base_url = super::api_base_url();
+    let url = format!("{}/api/organizations/{}/settings", api_base_url, org_id);
+    let client = super::api_client();
+    let response = client
+        .put(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&settings)
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if response.status() == 401 {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Not authenticated".to_string(),
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to update settings", response).await);
+    }
+    response.json().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to parse response: {}", e))
+    })
+}
+
+/// Set the organization's logo; the form carries `org_id` and the image as `logo`
+#[server(name = UploadOrgLogo, prefix = "/api", input = MultipartFormData)]
+pub async fn upload_org_logo(data: MultipartData) -> Result<(), ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+
+    let mut org_id = None;
+    let mut logo = None;
+    let mut form = data.into_inner().ok_or_else(|| {
+        ServerFnError::<NoCustomError>::ServerError("Expected a multipart form".to_string())
+    })?;
+    while let Some(field) = form.next_field().await.map_err(|e| {
+        ServerFnError::<NoCustomError>::ServerError(format!("Failed to read upload: {}", e))
+    })? {
+        let name = field.name().unwrap_or_default().to_string();
+        let content_type = field.content_type().map(|m| m.to_string());
+        let bytes = field.bytes().await.map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("Failed to read upload: {}", e))
+        })?;
+        match name.as_str() {
+            "org_id" => {
+                org_id = std::str::from_utf8(&bytes)
+                    .ok()
+                    .and_then(|s| s.parse::<Uuid>().ok())
+            }
+            "logo" => logo = Some((content_type.unwrap_or_default(), bytes)),
+            _ => {}
+        }
+    }
+    let (Some(org_id), Some((content_type, logo))) = (org_id, logo) else {
+        return Err(ServerFnError::<NoCustomError>::ServerError(
+            "Choose an image to upload".to_string(),
+        ));
+    };
+
+    let api_base_url = super::api_base_url();
+    let url = format!(
+        "{}/api/organizations/{}/settings/logo",
+        api_base_url, org_id
+    );
+    let client = super::api_client();
+    let response = client
+        .put(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", content_type)
+        .body(logo)
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to set logo", response).await);
+    }
+    Ok(())
+}
+
+#[server(DeleteOrgLogo, "/api")]
+pub async fn delete_org_logo(org_id: Uuid) -> Result<(), ServerFnError<NoCustomError>> {
+    let token = super::items::get_auth_token().await?;
+    let api_base_url = super::api_base_url();
+    let url = format!(
+        "{}/api/organizations/{}/settings/logo",
+        api_base_url, org_id
+    );
+    let client = super::api_client();
+    let response = client
+        .delete(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| {
+            ServerFnError::<NoCustomError>::ServerError(format!("API request failed: {}", e))
+        })?;
+    if !response.status().is_success() {
+        return Err(super::errors::api_error("Failed to remove logo", response).await);
+    }
+    Ok(())
+}
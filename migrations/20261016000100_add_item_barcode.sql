@@ -0,0 +1,6 @@
+-- Add a barcode column to items so scanned codes (EAN/UPC/ISBN) can be
+-- matched back to existing items.
+ALTER TABLE items ADD COLUMN barcode VARCHAR(64);
+
+CREATE INDEX idx_items_org_barcode ON items(organization_id, barcode)
+    WHERE barcode IS NOT NULL;
@@ -0,0 +1,12 @@
+-- Barcodes identify a single copy within an organization, so enforce
+-- uniqueness per org (different orgs may hold the same release).
+DROP INDEX IF EXISTS idx_items_org_barcode;
+CREATE UNIQUE INDEX idx_items_org_barcode ON items(organization_id, barcode)
+    WHERE barcode IS NOT NULL;
+
+-- Publisher catalogue code (e.g. "PCS 7088"). Not unique: many copies of a
+-- release share the same code.
+ALTER TABLE items ADD COLUMN catalog_code VARCHAR(64);
+
+CREATE INDEX idx_items_org_catalog_code ON items(organization_id, catalog_code)
+    WHERE catalog_code IS NOT NULL;
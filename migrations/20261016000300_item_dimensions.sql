@@ -0,0 +1,8 @@
+-- Physical size and weight of items for storage planning.
+-- Normalized units: millimetres and grams. depth_mm is the thickness an item
+-- occupies along a shelf when filed spine-out.
+ALTER TABLE items
+    ADD COLUMN width_mm INTEGER CHECK (width_mm > 0),
+    ADD COLUMN height_mm INTEGER CHECK (height_mm > 0),
+    ADD COLUMN depth_mm INTEGER CHECK (depth_mm > 0),
+    ADD COLUMN weight_g INTEGER CHECK (weight_g > 0);
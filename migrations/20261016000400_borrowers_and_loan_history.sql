@@ -0,0 +1,50 @@
+-- Borrowers: people items are lent to, one row per name per organization
+CREATE TABLE borrowers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_borrowers_org_name ON borrowers(organization_id, LOWER(name));
+
+CREATE TRIGGER update_borrowers_updated_at BEFORE UPDATE ON borrowers FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Loan history. item_loan_details holds the current loan only; every loan is
+-- also recorded here and closed (date_returned set) when the item leaves the
+-- loaned state.
+CREATE TABLE item_loans (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    item_id UUID NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    borrower_id UUID NOT NULL REFERENCES borrowers(id) ON DELETE CASCADE,
+    date_loaned DATE NOT NULL,
+    date_due_back DATE,
+    date_returned DATE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX idx_item_loans_borrower ON item_loans(borrower_id);
+-- At most one open loan per item
+CREATE UNIQUE INDEX idx_item_loans_open ON item_loans(item_id) WHERE date_returned IS NULL;
+
+CREATE TRIGGER update_item_loans_updated_at BEFORE UPDATE ON item_loans FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Backfill borrowers and open loans from items currently on loan
+INSERT INTO borrowers (organization_id, name)
+SELECT DISTINCT ON (i.organization_id, LOWER(TRIM(d.loaned_to)))
+       i.organization_id, TRIM(d.loaned_to)
+FROM item_loan_details d
+JOIN items i ON i.id = d.item_id
+WHERE i.state = 'loaned' AND TRIM(d.loaned_to) <> ''
+ORDER BY i.organization_id, LOWER(TRIM(d.loaned_to)), d.date_loaned;
+
+INSERT INTO item_loans (organization_id, item_id, borrower_id, date_loaned, date_due_back)
+SELECT i.organization_id, i.id, b.id, d.date_loaned, d.date_due_back
+FROM item_loan_details d
+JOIN items i ON i.id = d.item_id
+JOIN borrowers b ON b.organization_id = i.organization_id
+                AND LOWER(b.name) = LOWER(TRIM(d.loaned_to))
+WHERE i.state = 'loaned';
@@ -0,0 +1,9 @@
+-- Per-organization settings (loan limits etc.), see OrganizationSettings
+ALTER TABLE organizations ADD COLUMN settings JSONB NOT NULL DEFAULT '{}';
+
+-- LOAN_MANAGER may override loan limit policies
+ALTER TABLE user_organizations DROP CONSTRAINT valid_user_org_roles;
+ALTER TABLE user_organizations
+ADD CONSTRAINT valid_user_org_roles CHECK (
+    roles <@ ARRAY['USER', 'ADMIN', 'LOAN_MANAGER']::TEXT[]
+);
@@ -0,0 +1,13 @@
+-- Final data exports taken when a user or organization is deleted.
+-- subject_id is not a foreign key: the subject no longer exists once the export is written.
+CREATE TABLE data_exports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subject_type VARCHAR(20) NOT NULL CHECK (subject_type IN ('user', 'organization')),
+    subject_id UUID NOT NULL,
+    subject_name VARCHAR(255) NOT NULL,
+    archive JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX idx_data_exports_expires_at ON data_exports(expires_at);
@@ -0,0 +1,5 @@
+-- Attribute audit entries to the user who made the change. Nullable: entries
+-- are kept but anonymized when the user asks for their data to be removed.
+ALTER TABLE audit_log ADD COLUMN user_id UUID REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_audit_log_user_id ON audit_log(user_id);
@@ -0,0 +1,4 @@
+-- Export archives now live in blob storage (see vostuff_core::storage);
+-- archives written before this stay inline in the archive column.
+ALTER TABLE data_exports ADD COLUMN storage_key VARCHAR(255);
+ALTER TABLE data_exports ALTER COLUMN archive DROP NOT NULL;
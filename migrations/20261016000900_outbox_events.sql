@@ -0,0 +1,17 @@
+-- Transactional outbox: events are written in the same transaction as the change
+-- they describe and delivered afterwards by the API's background dispatcher.
+-- organization_id is not a foreign key so events outlive the rows they describe.
+CREATE TABLE outbox_events (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id UUID NOT NULL,
+    event_type VARCHAR(100) NOT NULL,
+    payload JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    dispatched_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_outbox_events_pending ON outbox_events(next_attempt_at, id)
+    WHERE dispatched_at IS NULL;
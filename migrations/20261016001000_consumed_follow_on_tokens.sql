@@ -0,0 +1,12 @@
+-- Follow-on tokens from the multi-org login are single-use: each token id is
+-- recorded here when exchanged for a session token, and a second exchange is
+-- rejected. Rows can be purged once expires_at passes, as the token itself has
+-- expired by then.
+CREATE TABLE consumed_follow_on_tokens (
+    jti UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    consumed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_consumed_follow_on_tokens_expires_at ON consumed_follow_on_tokens(expires_at);
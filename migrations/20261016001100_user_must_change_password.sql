@@ -0,0 +1,4 @@
+-- Set when an administrator resets a user's password to a temporary one. Login
+-- with the temporary password is refused until the user chooses a new password,
+-- which clears the flag.
+ALTER TABLE users ADD COLUMN must_change_password BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+-- Deactivated users keep their row, memberships and history attribution but
+-- cannot log in, and tokens issued to them are rejected.
+ALTER TABLE users ADD COLUMN is_active BOOLEAN NOT NULL DEFAULT TRUE;
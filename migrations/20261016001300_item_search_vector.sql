@@ -0,0 +1,10 @@
+-- Full-text search over items. Name matches rank above description, then
+-- notes. The 'simple' configuration does no stemming or stop-word removal, as
+-- titles are often in other languages or are single words like "Nevermind".
+ALTER TABLE items ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', coalesce(name, '')), 'A') ||
+    setweight(to_tsvector('simple', coalesce(description, '')), 'B') ||
+    setweight(to_tsvector('simple', coalesce(notes, '')), 'C')
+) STORED;
+
+CREATE INDEX idx_items_search_vector ON items USING GIN (search_vector);
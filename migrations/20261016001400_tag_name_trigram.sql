@@ -0,0 +1,5 @@
+-- Trigram index on tag names for autocomplete. It serves both the prefix
+-- (ILIKE 'q%') and the fuzzy (%) matches used by the tag suggest endpoint.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_tags_name_trgm ON tags USING GIN (name gin_trgm_ops);
@@ -0,0 +1,9 @@
+-- Structured item audit entries: what kind of change it was and a JSON diff
+-- of the changed fields ({"field": {"from": old, "to": new}}). Entries from
+-- before this migration only have their change_details text.
+ALTER TABLE audit_log
+    ADD COLUMN action VARCHAR(20) NOT NULL DEFAULT 'updated'
+        CHECK (action IN ('created', 'updated', 'state_changed', 'deleted')),
+    ADD COLUMN changes JSONB NOT NULL DEFAULT '{}';
+
+CREATE INDEX idx_audit_log_item_change_date ON audit_log(item_id, change_date DESC);
@@ -0,0 +1,11 @@
+-- Access tokens ended early by logout. The auth middleware rejects any token
+-- whose jti is recorded here. Rows can be purged once expires_at passes, as
+-- the token itself has expired by then.
+CREATE TABLE revoked_access_tokens (
+    jti UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_revoked_access_tokens_expires_at ON revoked_access_tokens(expires_at);
@@ -0,0 +1,5 @@
+-- History retention compacts old update entries into one 'summarized' entry
+-- per item and month, holding the net change over that month.
+ALTER TABLE audit_log DROP CONSTRAINT audit_log_action_check;
+ALTER TABLE audit_log ADD CONSTRAINT audit_log_action_check
+    CHECK (action IN ('created', 'updated', 'state_changed', 'deleted', 'summarized'));
@@ -0,0 +1,10 @@
+-- Each member's web app preferences in an organization (item table columns,
+-- saved searches, list defaults) as one JSONB document. Missing keys take
+-- their defaults, so adding a preference needs no migration.
+CREATE TABLE user_preferences (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    preferences JSONB NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, organization_id)
+);
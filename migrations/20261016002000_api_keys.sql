@@ -0,0 +1,22 @@
+-- Long-lived keys for scripts and other machine clients, sent in the
+-- X-Api-Key header. A key acts for the member who created it in one
+-- organization, with the roles it was given, never more than the member
+-- currently holds there. Only a SHA-256 hash of the key is stored; the key
+-- itself is shown once, when created.
+CREATE TABLE api_keys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    -- Leading characters of the key, to tell keys apart in listings
+    key_prefix VARCHAR(16) NOT NULL,
+    key_hash VARCHAR(64) NOT NULL UNIQUE,
+    roles TEXT[] NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_api_keys_organization_id ON api_keys(organization_id);
+CREATE INDEX idx_api_keys_user_id ON api_keys(user_id);
@@ -0,0 +1,25 @@
+-- Organization documentation pages in Markdown, such as "How we grade vinyl"
+-- or a shelving map, kept next to the data they describe. Every edit is kept
+-- in org_page_versions; org_pages holds the current version.
+CREATE TABLE org_pages (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL DEFAULT '',
+    version INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_org_pages_title ON org_pages(organization_id, LOWER(title));
+
+CREATE TABLE org_page_versions (
+    page_id UUID NOT NULL REFERENCES org_pages(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    -- Kept when the editor's account is deleted
+    edited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (page_id, version)
+);
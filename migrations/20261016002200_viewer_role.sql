@@ -0,0 +1,6 @@
+-- VIEWER members may read the organization's data but not change it
+ALTER TABLE user_organizations DROP CONSTRAINT valid_user_org_roles;
+ALTER TABLE user_organizations
+ADD CONSTRAINT valid_user_org_roles CHECK (
+    roles <@ ARRAY['USER', 'ADMIN', 'LOAN_MANAGER', 'VIEWER']::TEXT[]
+);
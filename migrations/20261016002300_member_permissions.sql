@@ -0,0 +1,36 @@
+-- CURATOR may manage the catalogue (kinds, fields, locations, tags,
+-- collections) and pages
+ALTER TABLE user_organizations DROP CONSTRAINT valid_user_org_roles;
+ALTER TABLE user_organizations
+ADD CONSTRAINT valid_user_org_roles CHECK (
+    roles <@ ARRAY['USER', 'ADMIN', 'LOAN_MANAGER', 'VIEWER', 'CURATOR']::TEXT[]
+);
+
+-- What each member may do in the organization. Roles are presets: memberships
+-- given roles without explicit permissions store the roles' permissions (see
+-- UserRole::permissions).
+ALTER TABLE user_organizations ADD COLUMN permissions TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE user_organizations
+ADD CONSTRAINT valid_user_org_permissions CHECK (
+    permissions <@ ARRAY[
+        'items:read', 'items:write', 'catalog:write', 'pages:write', 'pages:delete',
+        'history:export', 'settings:write', 'loans:override', 'members:manage',
+        'admin:users'
+    ]::TEXT[]
+);
+
+-- Existing memberships get the permissions of the roles they hold
+UPDATE user_organizations SET permissions = ARRAY(
+    SELECT DISTINCT p FROM unnest(roles) AS r(role), unnest(
+        CASE r.role
+            WHEN 'VIEWER' THEN ARRAY['items:read']
+            WHEN 'USER' THEN ARRAY['items:read', 'items:write', 'pages:write']
+            WHEN 'LOAN_MANAGER' THEN ARRAY['items:read', 'items:write', 'loans:override']
+            WHEN 'ADMIN' THEN ARRAY[
+                'items:read', 'items:write', 'catalog:write', 'pages:write', 'pages:delete',
+                'history:export', 'settings:write', 'loans:override', 'members:manage',
+                'admin:users'
+            ]
+        END
+    ) AS p
+);
@@ -0,0 +1,22 @@
+-- Invitations to join an organization. The invite link carries a signed
+-- token naming the invite; the row decides whether it can still be used.
+-- Accepting creates the membership with the stored roles and permissions.
+CREATE TABLE organization_invites (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    roles TEXT[] NOT NULL,
+    permissions TEXT[] NOT NULL,
+    invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    accepted_at TIMESTAMP WITH TIME ZONE,
+    accepted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_organization_invites_organization_id ON organization_invites(organization_id);
+
+-- One pending invite per address; inviting again replaces it
+CREATE UNIQUE INDEX idx_organization_invites_pending_email
+    ON organization_invites(organization_id, LOWER(email))
+    WHERE accepted_at IS NULL;
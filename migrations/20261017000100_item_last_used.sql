@@ -0,0 +1,3 @@
+-- When each item was last played, read or otherwise used; rotation suggests
+-- items that have gone unused the longest
+ALTER TABLE items ADD COLUMN last_used_at TIMESTAMPTZ;
@@ -0,0 +1,18 @@
+-- Each time an item is played, read or otherwise used. items.last_used_at
+-- remains the latest use, so rotation and the item list need no join.
+CREATE TABLE usage_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    item_id UUID NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    -- Kept when the user's account is deleted
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    used_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    note TEXT
+);
+
+CREATE INDEX idx_usage_log_item ON usage_log(item_id, used_at DESC);
+CREATE INDEX idx_usage_log_org ON usage_log(organization_id, used_at);
+
+-- Uses marked before the log existed
+INSERT INTO usage_log (organization_id, item_id, used_at)
+SELECT organization_id, id, last_used_at FROM items WHERE last_used_at IS NOT NULL;
@@ -0,0 +1,4 @@
+-- Consecutive failed password logins, and when the resulting lock ends.
+-- Both reset on a successful login or an admin unlock.
+ALTER TABLE users ADD COLUMN failed_login_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN locked_until TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,16 @@
+-- Photos of items. The image itself is in blob storage under storage_key;
+-- deleting an item removes its rows here and the API removes the blobs.
+CREATE TABLE item_photos (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    item_id UUID NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    storage_key VARCHAR(255) NOT NULL,
+    -- Name of the uploaded file, e.g. "PCS 7088.jpg"
+    filename VARCHAR(255) NOT NULL,
+    content_type VARCHAR(64) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_item_photos_item ON item_photos(item_id, created_at);
@@ -0,0 +1,3 @@
+-- Storage key of the organization's logo, shown in the web app header.
+-- Kept out of the settings JSON, which clients replace as a whole.
+ALTER TABLE organizations ADD COLUMN logo_key VARCHAR(255);
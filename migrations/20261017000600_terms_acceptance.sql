@@ -0,0 +1,11 @@
+-- Terms of service consent, for instances that require it (TERMS_VERSION).
+-- Every version a user accepts is kept as a record of their consent; the
+-- latest is on the user for the login check.
+ALTER TABLE users ADD COLUMN terms_accepted_version VARCHAR(64);
+
+CREATE TABLE terms_acceptances (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    version VARCHAR(64) NOT NULL,
+    accepted_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, version)
+);
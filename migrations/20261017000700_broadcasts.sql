@@ -0,0 +1,15 @@
+-- Notices from system administrators, such as scheduled downtime, shown as a
+-- banner to members of the targeted organizations until they expire.
+CREATE TABLE broadcasts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    message TEXT NOT NULL,
+    -- NULL reaches every organization
+    organization_ids UUID[],
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- Whether members were also emailed
+    email BOOLEAN NOT NULL DEFAULT FALSE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_broadcasts_expires_at ON broadcasts(expires_at);
@@ -0,0 +1,23 @@
+-- Background jobs: work taken off the request path, such as sending email,
+-- run by a worker in the API server and retried with backoff until it
+-- succeeds or runs out of attempts.
+CREATE TABLE jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(100) NOT NULL,
+    -- The organization the work is for; NULL for instance-wide work
+    organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE,
+    payload JSONB NOT NULL,
+    -- pending, running, succeeded or failed
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 5,
+    last_error TEXT,
+    run_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMP WITH TIME ZONE,
+    finished_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT jobs_status_check CHECK (status IN ('pending', 'running', 'succeeded', 'failed'))
+);
+
+CREATE INDEX idx_jobs_pending ON jobs(run_at) WHERE status = 'pending';
+CREATE INDEX idx_jobs_created_at ON jobs(created_at DESC);
@@ -0,0 +1,3 @@
+-- Set by a system administrator to stop all changes to an organization; its
+-- admins cannot lift it (compare the read_only organization setting)
+ALTER TABLE organizations ADD COLUMN read_only BOOLEAN NOT NULL DEFAULT FALSE;
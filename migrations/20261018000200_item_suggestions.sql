@@ -0,0 +1,24 @@
+-- Details found for items by metadata lookups (MusicBrainz, OpenLibrary),
+-- kept for a member to apply or dismiss
+CREATE TABLE item_suggestions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    item_id UUID NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    -- Where the details came from, such as musicbrainz
+    provider VARCHAR(32) NOT NULL,
+    source_url TEXT,
+    -- Suggested values by item field, as in an item update
+    changes JSONB NOT NULL,
+    -- pending, applied or dismissed
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    decided_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    decided_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CONSTRAINT item_suggestions_status_check CHECK (status IN ('pending', 'applied', 'dismissed'))
+);
+
+-- At most one suggestion per item awaits review; a repeated lookup keeps it
+CREATE UNIQUE INDEX idx_item_suggestions_pending ON item_suggestions(item_id)
+    WHERE status = 'pending';
+CREATE INDEX idx_item_suggestions_org ON item_suggestions(organization_id, created_at)
+    WHERE status = 'pending';
@@ -0,0 +1,9 @@
+-- Duplicate detection pairs items whose names are alike by trigram similarity
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_items_name_trgm ON items USING GIN (name gin_trgm_ops);
+
+-- Merging a duplicate into an item is recorded on the item kept
+ALTER TABLE audit_log DROP CONSTRAINT audit_log_action_check;
+ALTER TABLE audit_log ADD CONSTRAINT audit_log_action_check
+    CHECK (action IN ('created', 'updated', 'state_changed', 'merged', 'deleted', 'summarized'));
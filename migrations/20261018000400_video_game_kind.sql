@@ -0,0 +1,148 @@
+-- Shared kind for video games, with platform, region, completeness and
+-- condition. See "Adding a shared kind" in docs/soft-fields-design.md.
+
+-- Organizations may already have fields named like the shared ones added
+-- here or by later shared kind migrations; the API only refuses such names
+-- once the shared field exists. Settle each clash so names stay unique:
+-- an org field of the same type, and for enums with no values the shared
+-- field lacks, is merged into the shared field, keeping the items'
+-- soft_fields keys as they are. Any other is renamed to <name>_org (or
+-- _org2, ...), with the keys in its organization's items and pending
+-- suggestions renamed to match. Each merge or rename is reported as a NOTICE.
+--
+-- An org kind named like a new shared kind needs nothing: it becomes that
+-- organization's override of the shared kind, and can be reverted to it.
+CREATE OR REPLACE FUNCTION claim_shared_field_names() RETURNS void AS $$
+DECLARE
+    clash RECORD;
+    new_name TEXT;
+    suffix INTEGER;
+BEGIN
+    FOR clash IN
+        SELECT o.id AS org_field_id, o.org_id, o.name,
+               o.field_type AS org_type, s.id AS shared_field_id, s.field_type AS shared_type
+        FROM fields o
+        JOIN fields s ON s.name = o.name AND s.org_id IS NULL
+        WHERE o.org_id IS NOT NULL
+    LOOP
+        IF clash.org_type = clash.shared_type AND NOT EXISTS (
+            SELECT 1 FROM enum_values ov
+            WHERE ov.field_id = clash.org_field_id
+              AND NOT EXISTS (
+                  SELECT 1 FROM enum_values sv
+                  WHERE sv.field_id = clash.shared_field_id AND sv.value = ov.value
+              )
+        ) THEN
+            DELETE FROM kind_fields kf
+            WHERE kf.field_id = clash.org_field_id
+              AND EXISTS (
+                  SELECT 1 FROM kind_fields skf
+                  WHERE skf.kind_id = kf.kind_id AND skf.field_id = clash.shared_field_id
+              );
+            UPDATE kind_fields SET field_id = clash.shared_field_id
+            WHERE field_id = clash.org_field_id;
+            DELETE FROM fields WHERE id = clash.org_field_id;
+            RAISE NOTICE 'Merged field "%" of organization % into the shared field',
+                clash.name, clash.org_id;
+        ELSE
+            new_name := clash.name || '_org';
+            suffix := 1;
+            WHILE EXISTS (
+                SELECT 1 FROM fields
+                WHERE name = new_name AND (org_id IS NULL OR org_id = clash.org_id)
+            ) LOOP
+                suffix := suffix + 1;
+                new_name := clash.name || '_org' || suffix;
+            END LOOP;
+
+            UPDATE fields SET name = new_name, updated_at = NOW()
+            WHERE id = clash.org_field_id;
+            UPDATE items
+            SET soft_fields = (soft_fields - clash.name)
+                || jsonb_build_object(new_name, soft_fields -> clash.name)
+            WHERE organization_id = clash.org_id AND soft_fields ? clash.name;
+            UPDATE item_suggestions
+            SET changes = jsonb_set(
+                    changes, '{soft_fields}',
+                    ((changes -> 'soft_fields') - clash.name)
+                        || jsonb_build_object(new_name, changes -> 'soft_fields' -> clash.name))
+            WHERE organization_id = clash.org_id AND status = 'pending'
+              AND (changes -> 'soft_fields') ? clash.name;
+            RAISE NOTICE 'Renamed field "%" of organization % to "%" as it differs from the shared field',
+                clash.name, clash.org_id, new_name;
+        END IF;
+    END LOOP;
+END;
+$$ LANGUAGE plpgsql;
+
+INSERT INTO kinds (id, org_id, name, display_name) VALUES
+    ('00000000-0000-0000-0000-000000000009', NULL, 'video_game', 'Video Game');
+
+INSERT INTO fields (id, org_id, name, display_name, field_type) VALUES
+    ('00000000-0000-0000-0001-000000000008', NULL, 'platform',     'Platform',     'enum'),
+    ('00000000-0000-0000-0001-000000000009', NULL, 'region',       'Region',       'enum'),
+    ('00000000-0000-0000-0001-00000000000a', NULL, 'completeness', 'Completeness', 'enum'),
+    ('00000000-0000-0000-0001-00000000000b', NULL, 'condition',    'Condition',    'enum');
+
+-- platform
+INSERT INTO enum_values (field_id, value, display_value, sort_order) VALUES
+    ('00000000-0000-0000-0001-000000000008', 'nes',          'NES',              1),
+    ('00000000-0000-0000-0001-000000000008', 'snes',         'SNES',             2),
+    ('00000000-0000-0000-0001-000000000008', 'n64',          'Nintendo 64',      3),
+    ('00000000-0000-0000-0001-000000000008', 'gamecube',     'GameCube',         4),
+    ('00000000-0000-0000-0001-000000000008', 'wii',          'Wii',              5),
+    ('00000000-0000-0000-0001-000000000008', 'wii_u',        'Wii U',            6),
+    ('00000000-0000-0000-0001-000000000008', 'switch',       'Switch',           7),
+    ('00000000-0000-0000-0001-000000000008', 'game_boy',     'Game Boy',         8),
+    ('00000000-0000-0000-0001-000000000008', 'gba',          'Game Boy Advance', 9),
+    ('00000000-0000-0000-0001-000000000008', 'ds',           'Nintendo DS',      10),
+    ('00000000-0000-0000-0001-000000000008', '3ds',          'Nintendo 3DS',     11),
+    ('00000000-0000-0000-0001-000000000008', 'master_system','Master System',    12),
+    ('00000000-0000-0000-0001-000000000008', 'mega_drive',   'Mega Drive',       13),
+    ('00000000-0000-0000-0001-000000000008', 'saturn',       'Saturn',           14),
+    ('00000000-0000-0000-0001-000000000008', 'dreamcast',    'Dreamcast',        15),
+    ('00000000-0000-0000-0001-000000000008', 'ps1',          'PlayStation',      16),
+    ('00000000-0000-0000-0001-000000000008', 'ps2',          'PlayStation 2',    17),
+    ('00000000-0000-0000-0001-000000000008', 'ps3',          'PlayStation 3',    18),
+    ('00000000-0000-0000-0001-000000000008', 'ps4',          'PlayStation 4',    19),
+    ('00000000-0000-0000-0001-000000000008', 'ps5',          'PlayStation 5',    20),
+    ('00000000-0000-0000-0001-000000000008', 'psp',          'PSP',              21),
+    ('00000000-0000-0000-0001-000000000008', 'ps_vita',      'PS Vita',          22),
+    ('00000000-0000-0000-0001-000000000008', 'xbox',         'Xbox',             23),
+    ('00000000-0000-0000-0001-000000000008', 'xbox_360',     'Xbox 360',         24),
+    ('00000000-0000-0000-0001-000000000008', 'xbox_one',     'Xbox One',         25),
+    ('00000000-0000-0000-0001-000000000008', 'xbox_series',  'Xbox Series X|S',  26),
+    ('00000000-0000-0000-0001-000000000008', 'pc',           'PC',               27),
+    ('00000000-0000-0000-0001-000000000008', 'other',        'Other',            28);
+
+-- region
+INSERT INTO enum_values (field_id, value, display_value, sort_order) VALUES
+    ('00000000-0000-0000-0001-000000000009', 'pal',         'PAL',         1),
+    ('00000000-0000-0000-0001-000000000009', 'ntsc_u',      'NTSC-U',      2),
+    ('00000000-0000-0000-0001-000000000009', 'ntsc_j',      'NTSC-J',      3),
+    ('00000000-0000-0000-0001-000000000009', 'region_free', 'Region free', 4),
+    ('00000000-0000-0000-0001-000000000009', 'other',       'Other',       5);
+
+-- completeness
+INSERT INTO enum_values (field_id, value, display_value, sort_order) VALUES
+    ('00000000-0000-0000-0001-00000000000a', 'sealed',   'Sealed',                   1),
+    ('00000000-0000-0000-0001-00000000000a', 'complete', 'Complete in box',          2),
+    ('00000000-0000-0000-0001-00000000000a', 'boxed',    'Boxed, no manual',         3),
+    ('00000000-0000-0000-0001-00000000000a', 'loose',    'Loose (game only)',        4);
+
+-- condition (same scale as the record gradings)
+INSERT INTO enum_values (field_id, value, display_value, sort_order) VALUES
+    ('00000000-0000-0000-0001-00000000000b', 'mint',      'Mint',      1),
+    ('00000000-0000-0000-0001-00000000000b', 'near_mint', 'Near Mint', 2),
+    ('00000000-0000-0000-0001-00000000000b', 'excellent', 'Excellent', 3),
+    ('00000000-0000-0000-0001-00000000000b', 'good',      'Good',      4),
+    ('00000000-0000-0000-0001-00000000000b', 'fair',      'Fair',      5),
+    ('00000000-0000-0000-0001-00000000000b', 'poor',      'Poor',      6);
+
+INSERT INTO kind_fields (kind_id, field_id, display_order) VALUES
+    ('00000000-0000-0000-0000-000000000009', '00000000-0000-0000-0001-000000000008', 1),  -- platform
+    ('00000000-0000-0000-0000-000000000009', '00000000-0000-0000-0001-000000000009', 2),  -- region
+    ('00000000-0000-0000-0000-000000000009', '00000000-0000-0000-0001-00000000000a', 3),  -- completeness
+    ('00000000-0000-0000-0000-000000000009', '00000000-0000-0000-0001-00000000000b', 4);  -- condition
+
+SELECT claim_shared_field_names();
@@ -0,0 +1,34 @@
+-- Shared kind for Blu-ray and 4K UHD discs. Reuses the shared disks field;
+-- Blu-ray regions are not the video game ones, so they get their own field.
+
+INSERT INTO kinds (id, org_id, name, display_name) VALUES
+    ('00000000-0000-0000-0000-00000000000a', NULL, 'bluray', 'Blu-ray');
+
+INSERT INTO fields (id, org_id, name, display_name, field_type) VALUES
+    ('00000000-0000-0000-0001-00000000000c', NULL, 'disc_format', 'Format',  'enum'),
+    ('00000000-0000-0000-0001-00000000000d', NULL, 'disc_region', 'Region',  'enum'),
+    ('00000000-0000-0000-0001-00000000000e', NULL, 'edition',     'Edition', 'string');
+
+-- disc_format
+INSERT INTO enum_values (field_id, value, display_value, sort_order) VALUES
+    ('00000000-0000-0000-0001-00000000000c', 'bluray',        'Blu-ray',            1),
+    ('00000000-0000-0000-0001-00000000000c', 'uhd',           '4K UHD',             2),
+    ('00000000-0000-0000-0001-00000000000c', 'uhd_bluray',    '4K UHD + Blu-ray',   3),
+    ('00000000-0000-0000-0001-00000000000c', 'bluray_3d',     'Blu-ray 3D',         4),
+    ('00000000-0000-0000-0001-00000000000c', 'other',         'Other',              5);
+
+-- disc_region
+INSERT INTO enum_values (field_id, value, display_value, sort_order) VALUES
+    ('00000000-0000-0000-0001-00000000000d', 'a',           'A (Americas, East Asia)',         1),
+    ('00000000-0000-0000-0001-00000000000d', 'b',           'B (Europe, Africa, Australia)',   2),
+    ('00000000-0000-0000-0001-00000000000d', 'c',           'C (Rest of Asia)',                3),
+    ('00000000-0000-0000-0001-00000000000d', 'region_free', 'Region free',                     4);
+
+INSERT INTO kind_fields (kind_id, field_id, display_order) VALUES
+    ('00000000-0000-0000-0000-00000000000a', '00000000-0000-0000-0001-00000000000c', 1),  -- disc_format
+    ('00000000-0000-0000-0000-00000000000a', '00000000-0000-0000-0001-000000000006', 2),  -- disks
+    ('00000000-0000-0000-0000-00000000000a', '00000000-0000-0000-0001-00000000000d', 3),  -- disc_region
+    ('00000000-0000-0000-0000-00000000000a', '00000000-0000-0000-0001-00000000000e', 4);  -- edition
+
+-- Settle org fields named like the new shared ones; see the video_game migration
+SELECT claim_shared_field_names();
@@ -0,0 +1,28 @@
+-- Fields for the shared book kind, which had none. Reuses the shared edition
+-- field from the bluray kind.
+
+INSERT INTO fields (id, org_id, name, display_name, field_type) VALUES
+    ('00000000-0000-0000-0001-00000000000f', NULL, 'author',      'Author',    'string'),
+    ('00000000-0000-0000-0001-000000000010', NULL, 'isbn',        'ISBN',      'string'),
+    ('00000000-0000-0000-0001-000000000011', NULL, 'publisher',   'Publisher', 'string'),
+    ('00000000-0000-0000-0001-000000000012', NULL, 'year',        'Year',      'number'),
+    ('00000000-0000-0000-0001-000000000013', NULL, 'book_format', 'Format',    'enum');
+
+-- book_format
+INSERT INTO enum_values (field_id, value, display_value, sort_order) VALUES
+    ('00000000-0000-0000-0001-000000000013', 'hardback',  'Hardback',  1),
+    ('00000000-0000-0000-0001-000000000013', 'paperback', 'Paperback', 2),
+    ('00000000-0000-0000-0001-000000000013', 'ebook',     'E-book',    3),
+    ('00000000-0000-0000-0001-000000000013', 'audiobook', 'Audiobook', 4),
+    ('00000000-0000-0000-0001-000000000013', 'other',     'Other',     5);
+
+INSERT INTO kind_fields (kind_id, field_id, display_order) VALUES
+    ('00000000-0000-0000-0000-000000000004', '00000000-0000-0000-0001-00000000000f', 1),  -- author
+    ('00000000-0000-0000-0000-000000000004', '00000000-0000-0000-0001-000000000010', 2),  -- isbn
+    ('00000000-0000-0000-0000-000000000004', '00000000-0000-0000-0001-000000000011', 3),  -- publisher
+    ('00000000-0000-0000-0000-000000000004', '00000000-0000-0000-0001-000000000012', 4),  -- year
+    ('00000000-0000-0000-0000-000000000004', '00000000-0000-0000-0001-00000000000e', 5),  -- edition
+    ('00000000-0000-0000-0000-000000000004', '00000000-0000-0000-0001-000000000013', 6);  -- book_format
+
+-- Settle org fields named like the new shared ones; see the video_game migration
+SELECT claim_shared_field_names();
@@ -0,0 +1,12 @@
+-- Free-text pressing details for the shared vinyl kind, e.g.
+-- "UK, 1973, Harvest SHVL 804, A2/B3 matrix", so importers such as the
+-- Discogs one keep them out of the notes.
+
+INSERT INTO fields (id, org_id, name, display_name, field_type) VALUES
+    ('00000000-0000-0000-0001-000000000014', NULL, 'pressing', 'Pressing', 'string');
+
+INSERT INTO kind_fields (kind_id, field_id, display_order) VALUES
+    ('00000000-0000-0000-0000-000000000001', '00000000-0000-0000-0001-000000000014', 7);  -- pressing
+
+-- Settle org fields named like the new shared ones; see the video_game migration
+SELECT claim_shared_field_names();
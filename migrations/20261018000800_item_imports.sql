@@ -0,0 +1,30 @@
+-- CSV imports run on the server: the uploaded file is kept in blob storage
+-- and an items.import job creates its items, recording progress and the rows
+-- it could not import here.
+CREATE TABLE item_imports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    filename VARCHAR(255),
+    kind_id UUID NOT NULL REFERENCES kinds(id) ON DELETE CASCADE,
+    -- The column mapping, as import_mapping::ImportMapping
+    mapping JSONB NOT NULL,
+    storage_key VARCHAR(255) NOT NULL,
+    -- pending, running, succeeded or failed
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    total_rows INTEGER NOT NULL,
+    processed_rows INTEGER NOT NULL DEFAULT 0,
+    imported_rows INTEGER NOT NULL DEFAULT 0,
+    skipped_rows INTEGER NOT NULL DEFAULT 0,
+    failed_rows INTEGER NOT NULL DEFAULT 0,
+    -- [{"row", "name", "skipped", "message"}] for the first rows not imported
+    errors JSONB NOT NULL DEFAULT '[]',
+    -- Why the import stopped, when it failed as a whole
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMP WITH TIME ZONE,
+    finished_at TIMESTAMP WITH TIME ZONE,
+    CONSTRAINT item_imports_status_check CHECK (status IN ('pending', 'running', 'succeeded', 'failed'))
+);
+
+CREATE INDEX idx_item_imports_organization ON item_imports(organization_id, created_at DESC);
@@ -0,0 +1,5 @@
+-- A collection's cover, one of its items shown to represent it, and its place
+-- in the organization's list of collections (lowest first, then by name).
+ALTER TABLE collections
+    ADD COLUMN cover_item_id UUID REFERENCES items(id) ON DELETE SET NULL,
+    ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
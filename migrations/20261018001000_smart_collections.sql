@@ -0,0 +1,17 @@
+-- Smart collections: collections whose items are not added one by one but are
+-- whichever items match a stored filter, worked out each time they are listed.
+CREATE TABLE smart_collections (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    -- {"kinds", "states", "tags", "all_tags", "search"}, as
+    -- models::SmartCollectionFilter; kinds are named, so no IDs are held
+    filter JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE(organization_id, name)
+);
+
+CREATE TRIGGER update_smart_collections_updated_at BEFORE UPDATE ON smart_collections
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
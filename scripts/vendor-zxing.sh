@@ -0,0 +1,37 @@
+#!/bin/bash
+# Fetch the ZXing barcode decoder the web UI serves from /vendor.
+#
+# The package tarball is checked against the sha512 pinned below, the npm
+# "integrity" value of the release, before the UMD build is copied out. The
+# hash is kept here rather than fetched so a tampered package is caught too.
+set -euo pipefail
+
+VERSION=0.21.3
+# From `npm view @zxing/library@0.21.3 dist.integrity`
+EXPECTED_SHA512=""
+DEST="$(dirname "$0")/../crates/vostuff-web/vendor/zxing-library-${VERSION}.min.js"
+
+if [ -f "$DEST" ]; then
+    exit 0
+fi
+
+if [ -z "$EXPECTED_SHA512" ]; then
+    echo "No sha512 pinned for @zxing/library ${VERSION}; set EXPECTED_SHA512 in $0" >&2
+    exit 1
+fi
+
+WORK=$(mktemp -d)
+trap 'rm -rf "$WORK"' EXIT
+
+curl -fsSL -o "$WORK/library.tgz" \
+    "https://registry.npmjs.org/@zxing/library/-/library-${VERSION}.tgz"
+
+ACTUAL="sha512-$(openssl dgst -sha512 -binary "$WORK/library.tgz" | base64 -w0)"
+if [ "$ACTUAL" != "$EXPECTED_SHA512" ]; then
+    echo "@zxing/library ${VERSION} does not match the pinned sha512" >&2
+    exit 1
+fi
+
+tar -xzf "$WORK/library.tgz" -C "$WORK" package/umd/index.min.js
+cp "$WORK/package/umd/index.min.js" "$DEST"
+echo "Wrote $DEST"